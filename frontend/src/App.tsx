@@ -13,6 +13,9 @@ import {
   LoginPage,
   RegisterPage,
   RecoveryPage,
+  ResetPasswordPage,
+  AcceptInvitationPage,
+  SharedInventoryPage,
   NotificationsPage,
 } from '@/pages';
 import '@/styles/index.css';
@@ -102,7 +105,10 @@ function AppContent() {
     location.pathname === '/setup' ||
     location.pathname === '/login' ||
     location.pathname === '/register' ||
-    location.pathname === '/recover'
+    location.pathname === '/recover' ||
+    location.pathname === '/reset-password' ||
+    location.pathname === '/invite' ||
+    location.pathname === '/shared'
   ) {
     return (
       <>
@@ -153,6 +159,32 @@ function AppContent() {
               )
             }
           />
+          <Route
+            path="/reset-password"
+            element={
+              needsSetup ? (
+                <Navigate to="/setup" replace />
+              ) : isAuthenticated ? (
+                <Navigate to="/" replace />
+              ) : (
+                <ResetPasswordPage />
+              )
+            }
+          />
+          <Route
+            path="/invite"
+            element={
+              needsSetup ? (
+                <Navigate to="/setup" replace />
+              ) : isAuthenticated ? (
+                <Navigate to="/" replace />
+              ) : (
+                <AcceptInvitationPage />
+              )
+            }
+          />
+          {/* Public share links work whether or not the visitor is signed in */}
+          <Route path="/shared" element={<SharedInventoryPage />} />
         </Routes>
         <Toast />
       </>
@@ -0,0 +1,122 @@
+import { useState, useEffect } from 'react';
+import { useApp } from '@/context/AppContext';
+import { useAuth } from '@/context/AuthContext';
+import type { UserSession } from '@/types';
+import { authApi } from '@/services/api';
+
+export function ActiveSessionsSection() {
+  const { showToast } = useApp();
+  const { logout } = useAuth();
+  const [sessions, setSessions] = useState<UserSession[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [revokingId, setRevokingId] = useState<string | null>(null);
+
+  useEffect(() => {
+    void loadSessions();
+  }, []);
+
+  const loadSessions = async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getSessions();
+      if (result.success && result.data) {
+        setSessions(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading sessions:', error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleRevoke = async (session: UserSession) => {
+    setRevokingId(session.id);
+    try {
+      const result = await authApi.revokeSession(session.id);
+      if (result.success) {
+        if (session.current) {
+          logout();
+          return;
+        }
+        showToast('Session signed out', 'success');
+        void loadSessions();
+      } else {
+        showToast(result.error ?? 'Failed to sign out session', 'error');
+      }
+    } catch {
+      showToast('Failed to sign out session', 'error');
+    } finally {
+      setRevokingId(null);
+    }
+  };
+
+  const handleRevokeAll = async () => {
+    try {
+      const result = await authApi.revokeAllSessions();
+      if (!result.success) {
+        showToast(result.error ?? 'Failed to sign out sessions', 'error');
+        return;
+      }
+    } catch {
+      showToast('Failed to sign out sessions', 'error');
+      return;
+    }
+    logout();
+  };
+
+  if (loading && sessions.length === 0) {
+    return (
+      <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+        Loading...
+      </p>
+    );
+  }
+
+  return (
+    <div className="active-sessions-section">
+      <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+        {sessions.map((session) => (
+          <li
+            key={session.id}
+            style={{
+              display: 'flex',
+              alignItems: 'center',
+              justifyContent: 'space-between',
+              gap: '1rem',
+              padding: '0.75rem 0',
+              borderBottom: '1px solid var(--border-color)',
+            }}
+          >
+            <div>
+              <strong>{session.user_agent ?? 'Unknown device'}</strong>
+              {session.current && (
+                <span style={{ marginLeft: '0.5rem', color: 'var(--success, #28a745)' }}>
+                  (this device)
+                </span>
+              )}
+              <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                {session.ip_address ?? 'Unknown address'} · Last active{' '}
+                {new Date(session.last_seen_at).toLocaleString()}
+              </p>
+            </div>
+            <button
+              className="btn btn-secondary btn-sm"
+              onClick={() => void handleRevoke(session)}
+              disabled={revokingId === session.id}
+            >
+              Sign out
+            </button>
+          </li>
+        ))}
+      </ul>
+
+      <button
+        className="btn btn-danger"
+        style={{ marginTop: '1rem' }}
+        onClick={() => void handleRevokeAll()}
+      >
+        <i className="fas fa-sign-out-alt"></i> Sign out everywhere
+      </button>
+    </div>
+  );
+}
@@ -2,8 +2,24 @@ import { useState, useEffect, useCallback } from 'react';
 import { ConfirmModal } from '@/components';
 import { useApp } from '@/context/AppContext';
 import { useAuth } from '@/context/AuthContext';
-import type { UserAccessGrantWithUsers, CreateUserAccessGrantRequest, User } from '@/types';
+import type {
+  AccessGrantLevel,
+  UserAccessGrantWithUsers,
+  CreateUserAccessGrantRequest,
+  User,
+} from '@/types';
 import { authApi } from '@/services/api';
+import { describeShareSchedule, scheduleInputToIso } from '@/utils/shareSchedule';
+
+const GRANT_LEVELS: { value: AccessGrantLevel; label: string }[] = [
+  { value: 'full', label: 'Full Access' },
+  { value: 'edit_inventory', label: 'Edit Inventories' },
+  { value: 'edit_items', label: 'Edit Items' },
+  { value: 'view', label: 'View Only' },
+];
+
+const grantLevelLabel = (level: AccessGrantLevel) =>
+  GRANT_LEVELS.find((option) => option.value === level)?.label ?? level;
 
 export function AllAccessManagement() {
   const { showToast } = useApp();
@@ -15,7 +31,10 @@ export function AllAccessManagement() {
   const [showAddForm, setShowAddForm] = useState(false);
   const [newGrant, setNewGrant] = useState<CreateUserAccessGrantRequest>({
     grantee_username: '',
+    permission_level: 'full',
   });
+  const [grantStartsAt, setGrantStartsAt] = useState('');
+  const [grantExpiresAt, setGrantExpiresAt] = useState('');
   const [grantToRevoke, setGrantToRevoke] = useState<UserAccessGrantWithUsers | null>(null);
 
   const loadUsers = useCallback(async () => {
@@ -67,20 +86,54 @@ export function AllAccessManagement() {
     }
 
     try {
-      const result = await authApi.createAccessGrant(newGrant);
+      const result = await authApi.createAccessGrant({
+        ...newGrant,
+        starts_at: scheduleInputToIso(grantStartsAt),
+        expires_at: scheduleInputToIso(grantExpiresAt),
+      });
       if (result.success) {
-        showToast('All Access granted successfully', 'success');
-        setNewGrant({ grantee_username: '' });
+        showToast(result.message ?? 'Invitation sent', 'success');
+        setNewGrant({ grantee_username: '', permission_level: 'full' });
+        setGrantStartsAt('');
+        setGrantExpiresAt('');
         setShowAddForm(false);
         void loadGrants();
       } else {
-        showToast(result.error ?? 'Failed to grant access', 'error');
+        showToast(result.message ?? result.error ?? 'Failed to grant access', 'error');
       }
     } catch {
       showToast('Failed to grant access', 'error');
     }
   };
 
+  const handleRespond = async (grant: UserAccessGrantWithUsers, accept: boolean) => {
+    try {
+      const result = await authApi.respondToAccessGrant(grant.id, accept);
+      if (result.success) {
+        showToast(accept ? 'All Access accepted' : 'All Access declined', 'success');
+        void loadGrants();
+      } else {
+        showToast(result.error ?? 'Failed to answer invitation', 'error');
+      }
+    } catch {
+      showToast('Failed to answer invitation', 'error');
+    }
+  };
+
+  const handleChangeLevel = async (grant: UserAccessGrantWithUsers, level: AccessGrantLevel) => {
+    try {
+      const result = await authApi.updateAccessGrant(grant.id, { permission_level: level });
+      if (result.success) {
+        showToast('All Access level updated', 'success');
+        void loadGrants();
+      } else {
+        showToast(result.error ?? 'Failed to update access', 'error');
+      }
+    } catch {
+      showToast('Failed to update access', 'error');
+    }
+  };
+
   const handleRevokeAccess = async () => {
     if (!grantToRevoke) {
       return;
@@ -106,8 +159,9 @@ export function AllAccessManagement() {
         <div className="settings-section">
           <h2 className="section-title">All Access Grants</h2>
           <p className="section-description">
-            Grant users full access to <strong>all your inventories</strong> - they can view, edit,
-            delete, and manage sharing as if they own them.
+            Grant users access to <strong>all your inventories</strong>. With Full Access they can
+            view, edit, delete, and manage sharing as if they own them; lower levels work like a
+            share on every inventory.
           </p>
 
           {/* Grant Access Section */}
@@ -132,7 +186,7 @@ export function AllAccessManagement() {
                     <select
                       className="form-select"
                       value={newGrant.grantee_username}
-                      onChange={(e) => setNewGrant({ grantee_username: e.target.value })}
+                      onChange={(e) => setNewGrant({ ...newGrant, grantee_username: e.target.value })}
                     >
                       <option value="">Select a user...</option>
                       {users
@@ -151,7 +205,7 @@ export function AllAccessManagement() {
                       type="text"
                       className="form-input"
                       value={newGrant.grantee_username}
-                      onChange={(e) => setNewGrant({ grantee_username: e.target.value })}
+                      onChange={(e) => setNewGrant({ ...newGrant, grantee_username: e.target.value })}
                       placeholder="Enter username..."
                     />
                   )}
@@ -162,9 +216,58 @@ export function AllAccessManagement() {
                       marginTop: '0.5rem',
                     }}
                   >
-                    This user will have full access to all your current and future inventories.
+                    This user will have access to all your current and future inventories.
                   </p>
                 </div>
+                <div className="form-group">
+                  <label className="form-label" htmlFor="grant-level">
+                    Access Level
+                  </label>
+                  <select
+                    className="form-select"
+                    id="grant-level"
+                    value={newGrant.permission_level ?? 'full'}
+                    onChange={(e) =>
+                      setNewGrant({
+                        ...newGrant,
+                        permission_level: e.target.value as AccessGrantLevel,
+                      })
+                    }
+                  >
+                    {GRANT_LEVELS.map((option) => (
+                      <option key={option.value} value={option.value}>
+                        {option.label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                <div style={{ display: 'flex', gap: '0.5rem' }}>
+                  <div className="form-group" style={{ flex: 1 }}>
+                    <label className="form-label" htmlFor="grant-starts-at">
+                      Starts (optional)
+                    </label>
+                    <input
+                      type="datetime-local"
+                      className="form-input"
+                      id="grant-starts-at"
+                      value={grantStartsAt}
+                      onChange={(e) => setGrantStartsAt(e.target.value)}
+                    />
+                  </div>
+                  <div className="form-group" style={{ flex: 1 }}>
+                    <label className="form-label" htmlFor="grant-expires-at">
+                      Expires (optional)
+                    </label>
+                    <input
+                      type="datetime-local"
+                      className="form-input"
+                      id="grant-expires-at"
+                      value={grantExpiresAt}
+                      min={grantStartsAt || undefined}
+                      onChange={(e) => setGrantExpiresAt(e.target.value)}
+                    />
+                  </div>
+                </div>
                 <div style={{ display: 'flex', gap: '0.5rem' }}>
                   <button className="btn btn-primary" onClick={handleGrantAccess}>
                     Grant Access
@@ -212,15 +315,34 @@ export function AllAccessManagement() {
                         }}
                       >
                         Granted on {new Date(grant.created_at).toLocaleDateString()}
+                        {describeShareSchedule(grant) && ` · ${describeShareSchedule(grant)}`}
+                        {grant.status === 'pending' && ' · Invitation pending'}
+                        {grant.status === 'declined' && ' · Declined'}
                       </div>
                     </div>
-                    <button
-                      className="btn btn-danger"
-                      onClick={() => setGrantToRevoke(grant)}
-                      style={{ fontSize: '0.875rem' }}
-                    >
-                      Revoke
-                    </button>
+                    <div style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+                      <select
+                        className="form-select"
+                        value={grant.permission_level}
+                        onChange={(e) =>
+                          void handleChangeLevel(grant, e.target.value as AccessGrantLevel)
+                        }
+                        style={{ fontSize: '0.875rem' }}
+                      >
+                        {GRANT_LEVELS.map((option) => (
+                          <option key={option.value} value={option.value}>
+                            {option.label}
+                          </option>
+                        ))}
+                      </select>
+                      <button
+                        className="btn btn-danger"
+                        onClick={() => setGrantToRevoke(grant)}
+                        style={{ fontSize: '0.875rem' }}
+                      >
+                        Revoke
+                      </button>
+                    </div>
                   </div>
                 ))}
               </div>
@@ -268,20 +390,44 @@ export function AllAccessManagement() {
                         }}
                       >
                         Granted on {new Date(grant.created_at).toLocaleDateString()}
+                        {describeShareSchedule(grant) && ` · ${describeShareSchedule(grant)}`}
                       </div>
                     </div>
-                    <div
-                      style={{
-                        padding: '0.5rem 1rem',
-                        background: 'var(--success-bg)',
-                        color: 'var(--success)',
-                        borderRadius: '8px',
-                        fontSize: '0.875rem',
-                        fontWeight: '500',
-                      }}
-                    >
-                      ✓ Full Access
-                    </div>
+                    {grant.status === 'pending' ? (
+                      <div style={{ display: 'flex', gap: '0.5rem' }}>
+                        <button
+                          className="btn btn-primary"
+                          onClick={() => void handleRespond(grant, true)}
+                          style={{ fontSize: '0.875rem' }}
+                        >
+                          Accept
+                        </button>
+                        <button
+                          className="btn btn-secondary"
+                          onClick={() => void handleRespond(grant, false)}
+                          style={{ fontSize: '0.875rem' }}
+                        >
+                          Decline
+                        </button>
+                      </div>
+                    ) : grant.status === 'declined' ? (
+                      <span style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
+                        Declined
+                      </span>
+                    ) : (
+                      <div
+                        style={{
+                          padding: '0.5rem 1rem',
+                          background: 'var(--success-bg)',
+                          color: 'var(--success)',
+                          borderRadius: '8px',
+                          fontSize: '0.875rem',
+                          fontWeight: '500',
+                        }}
+                      >
+                        ✓ {grantLevelLabel(grant.permission_level)}
+                      </div>
+                    )}
                   </div>
                 ))}
               </div>
@@ -298,8 +444,8 @@ export function AllAccessManagement() {
             }}
           >
             <p style={{ fontSize: '0.875rem', color: 'var(--text-primary)', margin: 0 }}>
-              <strong>⚠️ Important:</strong> Users with All Access can do everything you can do with
-              your inventories, including deleting them and managing shares. Only grant this to
+              <strong>⚠️ Important:</strong> Users with Full Access can do everything you can do
+              with your inventories, including deleting them and managing shares. Only grant this to
               people you fully trust.
             </p>
           </div>
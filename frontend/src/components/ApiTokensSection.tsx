@@ -0,0 +1,264 @@
+import { useState, useEffect } from 'react';
+import { useApp } from '@/context/AppContext';
+import type { ApiToken, ApiTokenScope } from '@/types';
+import { authApi } from '@/services/api';
+
+const SCOPE_OPTIONS: { value: ApiTokenScope; label: string }[] = [
+  { value: 'items:read', label: 'Read inventories and items' },
+  { value: 'items:write', label: 'Create, edit and delete items' },
+  { value: 'reports:read', label: 'Read reports' },
+];
+
+const EXPIRY_OPTIONS = [
+  { value: '', label: 'Never' },
+  { value: '30', label: '30 days' },
+  { value: '90', label: '90 days' },
+  { value: '365', label: '1 year' },
+];
+
+export function ApiTokensSection() {
+  const { showToast, inventories } = useApp();
+  const [tokens, setTokens] = useState<ApiToken[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [creating, setCreating] = useState(false);
+  const [newToken, setNewToken] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [scopes, setScopes] = useState<ApiTokenScope[]>(['items:read']);
+  const [inventoryIds, setInventoryIds] = useState<number[]>([]);
+  const [expiresInDays, setExpiresInDays] = useState('');
+
+  useEffect(() => {
+    void loadTokens();
+  }, []);
+
+  const loadTokens = async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getApiTokens();
+      if (result.success && result.data) {
+        setTokens(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading API tokens:', error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const toggleScope = (scope: ApiTokenScope) => {
+    setScopes((current) =>
+      current.includes(scope) ? current.filter((s) => s !== scope) : [...current, scope]
+    );
+  };
+
+  const toggleInventory = (id: number) => {
+    setInventoryIds((current) =>
+      current.includes(id) ? current.filter((i) => i !== id) : [...current, id]
+    );
+  };
+
+  const handleCreate = async () => {
+    if (!name.trim() || scopes.length === 0) {
+      showToast('Enter a name and choose at least one scope', 'error');
+      return;
+    }
+
+    setCreating(true);
+    try {
+      const result = await authApi.createApiToken({
+        name: name.trim(),
+        scopes,
+        inventory_ids: inventoryIds.length > 0 ? inventoryIds : undefined,
+        expires_in_days: expiresInDays ? Number(expiresInDays) : undefined,
+      });
+      if (result.success && result.data) {
+        setNewToken(result.data.token);
+        setName('');
+        setInventoryIds([]);
+        void loadTokens();
+      } else {
+        showToast(result.error ?? 'Failed to create API token', 'error');
+      }
+    } catch {
+      showToast('Failed to create API token', 'error');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleDelete = async (token: ApiToken) => {
+    try {
+      const result = await authApi.deleteApiToken(token.id);
+      if (result.success) {
+        showToast(`Deleted "${token.name}"`, 'success');
+        void loadTokens();
+      } else {
+        showToast(result.error ?? 'Failed to delete API token', 'error');
+      }
+    } catch {
+      showToast('Failed to delete API token', 'error');
+    }
+  };
+
+  const handleCopy = async () => {
+    if (!newToken) {
+      return;
+    }
+    try {
+      await navigator.clipboard.writeText(newToken);
+      showToast('Token copied to clipboard', 'success');
+    } catch {
+      showToast('Failed to copy token', 'error');
+    }
+  };
+
+  return (
+    <div className="api-tokens-section">
+      {newToken && (
+        <div
+          style={{
+            padding: '1rem',
+            marginBottom: '1.5rem',
+            background: 'var(--warning-bg, #fff3cd)',
+            border: '1px solid var(--warning-border, #ffeeba)',
+            borderRadius: '8px',
+          }}
+        >
+          <p style={{ marginTop: 0 }}>
+            <strong>Copy this token now.</strong> It will not be shown again.
+          </p>
+          <code style={{ wordBreak: 'break-all' }}>{newToken}</code>
+          <div style={{ marginTop: '0.75rem', display: 'flex', gap: '0.5rem' }}>
+            <button className="btn btn-secondary btn-sm" onClick={() => void handleCopy()}>
+              <i className="fas fa-copy"></i> Copy
+            </button>
+            <button className="btn btn-secondary btn-sm" onClick={() => setNewToken(null)}>
+              Done
+            </button>
+          </div>
+        </div>
+      )}
+
+      {loading && tokens.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {tokens.map((token) => (
+            <li
+              key={token.id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                gap: '1rem',
+                padding: '0.75rem 0',
+                borderBottom: '1px solid var(--border-color)',
+              }}
+            >
+              <div>
+                <strong>{token.name}</strong> <code>{token.token_prefix}…</code>
+                <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                  {token.scopes.join(', ')}
+                  {token.inventory_ids && ` · ${token.inventory_ids.length} inventories`}
+                  {' · '}
+                  {token.last_used_at
+                    ? `Last used ${new Date(token.last_used_at).toLocaleString()}`
+                    : 'Never used'}
+                  {token.expires_at &&
+                    ` · Expires ${new Date(token.expires_at).toLocaleDateString()}`}
+                </p>
+              </div>
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={() => void handleDelete(token)}
+              >
+                Delete
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div style={{ marginTop: '1.5rem' }}>
+        <div className="form-group">
+          <label className="form-label" htmlFor="api-token-name">
+            Token name
+          </label>
+          <input
+            type="text"
+            className="form-input"
+            id="api-token-name"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder="e.g. Home Assistant"
+            maxLength={100}
+          />
+        </div>
+
+        <div className="form-group">
+          <span className="form-label">Scopes</span>
+          {SCOPE_OPTIONS.map((option) => (
+            <label key={option.value} className="form-checkbox">
+              <input
+                type="checkbox"
+                checked={scopes.includes(option.value)}
+                onChange={() => toggleScope(option.value)}
+              />
+              <span className="form-checkbox-label">
+                <code>{option.value}</code> – {option.label}
+              </span>
+            </label>
+          ))}
+        </div>
+
+        {inventories.length > 0 && (
+          <div className="form-group">
+            <span className="form-label">Limit to inventories</span>
+            {inventories.map(
+              (inventory) =>
+                inventory.id !== undefined && (
+                  <label key={inventory.id} className="form-checkbox">
+                    <input
+                      type="checkbox"
+                      checked={inventoryIds.includes(inventory.id)}
+                      onChange={() => toggleInventory(inventory.id as number)}
+                    />
+                    <span className="form-checkbox-label">{inventory.name}</span>
+                  </label>
+                )
+            )}
+            <p className="form-hint">Leave all unchecked to allow every inventory</p>
+          </div>
+        )}
+
+        <div className="form-group">
+          <label className="form-label" htmlFor="api-token-expiry">
+            Expires
+          </label>
+          <select
+            className="form-select"
+            id="api-token-expiry"
+            value={expiresInDays}
+            onChange={(e) => setExpiresInDays(e.target.value)}
+          >
+            {EXPIRY_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <button
+          className="btn btn-primary"
+          onClick={() => void handleCreate()}
+          disabled={creating}
+        >
+          <i className="fas fa-key"></i> Create Token
+        </button>
+      </div>
+    </div>
+  );
+}
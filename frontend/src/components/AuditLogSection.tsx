@@ -0,0 +1,247 @@
+import { useState, useEffect } from 'react';
+import { useApp } from '@/context/AppContext';
+import type { AuditEvent, AuditEventFilters, User } from '@/types';
+import { authApi } from '@/services/api';
+
+const PAGE_SIZE = 50;
+
+const ACTION_GROUPS = [
+  { value: '', label: 'All actions' },
+  { value: 'auth.', label: 'Sign-ins & sessions' },
+  { value: 'totp.', label: 'Two-factor' },
+  { value: 'recovery_codes.', label: 'Recovery codes' },
+  { value: 'user.', label: 'User accounts' },
+  { value: 'invitation.', label: 'Invitations' },
+  { value: 'share.', label: 'Inventory shares' },
+  { value: 'share_link.', label: 'Public share links' },
+  { value: 'group.', label: 'Groups' },
+  { value: 'access_grant.', label: 'All Access grants' },
+  { value: 'inventory.', label: 'Ownership transfers' },
+  { value: 'backup.', label: 'Backups' },
+];
+
+const OUTCOME_COLORS: Record<AuditEvent['outcome'], string> = {
+  success: 'var(--success, #28a745)',
+  failure: 'var(--danger, #dc3545)',
+  denied: 'var(--warning, #ffc107)',
+};
+
+// <input type="datetime-local"> values are local time without a zone
+function toIsoString(value: string): string | undefined {
+  return value ? new Date(value).toISOString() : undefined;
+}
+
+export function AuditLogSection() {
+  const { showToast } = useApp();
+  const [events, setEvents] = useState<AuditEvent[]>([]);
+  const [users, setUsers] = useState<User[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [downloading, setDownloading] = useState(false);
+  const [userId, setUserId] = useState('');
+  const [action, setAction] = useState('');
+  const [from, setFrom] = useState('');
+  const [to, setTo] = useState('');
+  const [offset, setOffset] = useState(0);
+
+  const filters = (): AuditEventFilters => ({
+    user_id: userId || undefined,
+    action: action || undefined,
+    from: toIsoString(from),
+    to: toIsoString(to),
+  });
+
+  useEffect(() => {
+    void loadUsers();
+  }, []);
+
+  useEffect(() => {
+    void loadEvents();
+  }, [userId, action, from, to, offset]);
+
+  const loadUsers = async () => {
+    try {
+      const result = await authApi.getAllUsers();
+      if (result.success && result.data) {
+        setUsers(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading users:', error);
+    }
+  };
+
+  const loadEvents = async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getAuditEvents({ ...filters(), limit: PAGE_SIZE, offset });
+      if (result.success && result.data) {
+        setEvents(result.data);
+      } else {
+        showToast(result.error ?? 'Failed to load audit log', 'error');
+      }
+    } catch (error) {
+      console.error('Error loading audit log:', error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleExport = async () => {
+    setDownloading(true);
+    try {
+      const blob = await authApi.downloadAuditEventsCSV(filters());
+      const url = window.URL.createObjectURL(blob);
+      const a = document.createElement('a');
+      a.href = url;
+      a.download = `audit_log_${new Date().toISOString().split('T')[0]}.csv`;
+      document.body.appendChild(a);
+      a.click();
+      document.body.removeChild(a);
+      window.URL.revokeObjectURL(url);
+    } catch {
+      showToast('Failed to export audit log', 'error');
+    } finally {
+      setDownloading(false);
+    }
+  };
+
+  // Any filter change starts again from the first page
+  const updateFilter = (setter: (value: string) => void) => (value: string) => {
+    setter(value);
+    setOffset(0);
+  };
+
+  return (
+    <div className="audit-log-section">
+      <div
+        style={{
+          display: 'grid',
+          gridTemplateColumns: 'repeat(auto-fit, minmax(180px, 1fr))',
+          gap: '0.75rem',
+          marginBottom: '1rem',
+        }}
+      >
+        <div className="form-group">
+          <label className="form-label" htmlFor="audit-user">
+            User
+          </label>
+          <select
+            id="audit-user"
+            className="form-select"
+            value={userId}
+            onChange={(e) => updateFilter(setUserId)(e.target.value)}
+          >
+            <option value="">All users</option>
+            {users.map((u) => (
+              <option key={u.id} value={u.id}>
+                {u.username}
+              </option>
+            ))}
+          </select>
+        </div>
+        <div className="form-group">
+          <label className="form-label" htmlFor="audit-action">
+            Action
+          </label>
+          <select
+            id="audit-action"
+            className="form-select"
+            value={action}
+            onChange={(e) => updateFilter(setAction)(e.target.value)}
+          >
+            {ACTION_GROUPS.map((group) => (
+              <option key={group.value} value={group.value}>
+                {group.label}
+              </option>
+            ))}
+          </select>
+        </div>
+        <div className="form-group">
+          <label className="form-label" htmlFor="audit-from">
+            From
+          </label>
+          <input
+            id="audit-from"
+            type="datetime-local"
+            className="form-input"
+            value={from}
+            onChange={(e) => updateFilter(setFrom)(e.target.value)}
+          />
+        </div>
+        <div className="form-group">
+          <label className="form-label" htmlFor="audit-to">
+            To
+          </label>
+          <input
+            id="audit-to"
+            type="datetime-local"
+            className="form-input"
+            value={to}
+            onChange={(e) => updateFilter(setTo)(e.target.value)}
+          />
+        </div>
+      </div>
+
+      {loading && events.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : events.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>No matching events.</p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {events.map((event) => (
+            <li
+              key={event.id}
+              style={{ padding: '0.75rem 0', borderBottom: '1px solid var(--border-color)' }}
+            >
+              <div style={{ display: 'flex', justifyContent: 'space-between', gap: '1rem' }}>
+                <strong>
+                  {event.action}{' '}
+                  <span style={{ color: OUTCOME_COLORS[event.outcome], fontWeight: 'normal' }}>
+                    ({event.outcome})
+                  </span>
+                </strong>
+                <span style={{ fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                  {new Date(event.created_at).toLocaleString()}
+                </span>
+              </div>
+              <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                {event.actor_username ?? 'Anonymous'}
+                {event.target_type && ` → ${event.target_type} ${event.target_id ?? ''}`}
+                {event.details && ` · ${event.details}`}
+              </p>
+              <p style={{ margin: 0, fontSize: '0.8rem', color: 'var(--text-secondary)' }}>
+                {event.ip_address ?? 'Unknown address'} · {event.user_agent ?? 'Unknown device'}
+              </p>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div style={{ display: 'flex', gap: '0.5rem', marginTop: '1rem', flexWrap: 'wrap' }}>
+        <button
+          className="btn btn-secondary btn-sm"
+          onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+          disabled={offset === 0 || loading}
+        >
+          <i className="fas fa-chevron-left"></i> Newer
+        </button>
+        <button
+          className="btn btn-secondary btn-sm"
+          onClick={() => setOffset(offset + PAGE_SIZE)}
+          disabled={events.length < PAGE_SIZE || loading}
+        >
+          Older <i className="fas fa-chevron-right"></i>
+        </button>
+        <button
+          className="btn btn-primary btn-sm"
+          style={{ marginLeft: 'auto' }}
+          onClick={() => void handleExport()}
+          disabled={downloading}
+        >
+          <i className="fas fa-file-csv"></i> {downloading ? 'Exporting...' : 'Export CSV'}
+        </button>
+      </div>
+    </div>
+  );
+}
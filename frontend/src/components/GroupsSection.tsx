@@ -0,0 +1,307 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import { useAuth } from '@/context/AuthContext';
+import { ConfirmModal } from '@/components';
+import type { GroupMember, GroupRole, UserGroup, UserGroupWithMembers } from '@/types';
+import { authApi } from '@/services/api';
+
+export function GroupsSection() {
+  const { showToast } = useApp();
+  const { user } = useAuth();
+  const [groups, setGroups] = useState<UserGroup[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [newGroupName, setNewGroupName] = useState('');
+  const [creating, setCreating] = useState(false);
+  const [openGroup, setOpenGroup] = useState<UserGroupWithMembers | null>(null);
+  const [newMember, setNewMember] = useState('');
+  const [groupToDelete, setGroupToDelete] = useState<UserGroup | null>(null);
+
+  const loadGroups = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getGroups();
+      if (result.success && result.data) {
+        setGroups(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading groups:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadGroups();
+  }, [loadGroups]);
+
+  const loadGroup = async (groupId: string) => {
+    try {
+      const result = await authApi.getGroup(groupId);
+      if (result.success && result.data) {
+        setOpenGroup(result.data);
+      } else {
+        showToast(result.error ?? 'Failed to load group', 'error');
+      }
+    } catch {
+      showToast('Failed to load group', 'error');
+    }
+  };
+
+  const canManage = (group: UserGroup) => group.my_role === 'manager' || !!user?.is_admin;
+
+  const handleCreate = async () => {
+    if (!newGroupName.trim()) {
+      showToast('Please enter a group name', 'error');
+      return;
+    }
+    setCreating(true);
+    try {
+      const result = await authApi.createGroup({ name: newGroupName.trim() });
+      if (result.success && result.data) {
+        showToast('Group created', 'success');
+        setNewGroupName('');
+        void loadGroups();
+        void loadGroup(result.data.id);
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to create group', 'error');
+      }
+    } catch {
+      showToast('Failed to create group', 'error');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleAddMember = async () => {
+    if (!openGroup || !newMember.trim()) {
+      return;
+    }
+    try {
+      const result = await authApi.addGroupMember(openGroup.id, newMember.trim());
+      if (result.success) {
+        showToast(result.message ?? 'Member added', 'success');
+        setNewMember('');
+        void loadGroup(openGroup.id);
+        void loadGroups();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to add member', 'error');
+      }
+    } catch {
+      showToast('Failed to add member', 'error');
+    }
+  };
+
+  const handleRoleChange = async (member: GroupMember, role: GroupRole) => {
+    if (!openGroup) {
+      return;
+    }
+    try {
+      const result = await authApi.updateGroupMember(openGroup.id, member.user.id, role);
+      if (result.success) {
+        void loadGroup(openGroup.id);
+      } else {
+        showToast(result.error ?? 'Failed to change role', 'error');
+      }
+    } catch {
+      showToast('Failed to change role', 'error');
+    }
+  };
+
+  const handleRemoveMember = async (member: GroupMember) => {
+    if (!openGroup) {
+      return;
+    }
+    try {
+      const result = await authApi.removeGroupMember(openGroup.id, member.user.id);
+      if (result.success) {
+        void loadGroups();
+        if (member.user.id === user?.id && !user.is_admin) {
+          showToast('You left the group', 'success');
+          setOpenGroup(null);
+        } else {
+          showToast('Member removed', 'success');
+          void loadGroup(openGroup.id);
+        }
+      } else {
+        showToast(result.error ?? 'Failed to remove member', 'error');
+      }
+    } catch {
+      showToast('Failed to remove member', 'error');
+    }
+  };
+
+  const handleDeleteGroup = async () => {
+    if (!groupToDelete) {
+      return;
+    }
+    try {
+      const result = await authApi.deleteGroup(groupToDelete.id);
+      if (result.success) {
+        showToast('Group deleted', 'success');
+        if (openGroup?.id === groupToDelete.id) {
+          setOpenGroup(null);
+        }
+        setGroupToDelete(null);
+        void loadGroups();
+      } else {
+        showToast(result.error ?? 'Failed to delete group', 'error');
+      }
+    } catch {
+      showToast('Failed to delete group', 'error');
+    }
+  };
+
+  return (
+    <div className="groups-section">
+      {loading && groups.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : groups.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>You are not in any groups yet.</p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {groups.map((group) => (
+            <li
+              key={group.id}
+              style={{ padding: '0.75rem 0', borderBottom: '1px solid var(--border-color)' }}
+            >
+              <div
+                style={{
+                  display: 'flex',
+                  alignItems: 'center',
+                  justifyContent: 'space-between',
+                  gap: '1rem',
+                }}
+              >
+                <div>
+                  <strong>{group.name}</strong>
+                  <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                    {group.member_count} member{group.member_count === 1 ? '' : 's'}
+                    {group.my_role === 'manager' && ' · You manage this group'}
+                  </p>
+                </div>
+                <div style={{ display: 'flex', gap: '0.5rem' }}>
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() =>
+                      openGroup?.id === group.id ? setOpenGroup(null) : void loadGroup(group.id)
+                    }
+                  >
+                    {openGroup?.id === group.id ? 'Close' : 'Members'}
+                  </button>
+                  {canManage(group) && (
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => setGroupToDelete(group)}
+                    >
+                      Delete
+                    </button>
+                  )}
+                </div>
+              </div>
+
+              {openGroup?.id === group.id && (
+                <div style={{ marginTop: '0.75rem', paddingLeft: '1rem' }}>
+                  {openGroup.members.map((member) => (
+                    <div
+                      key={member.user.id}
+                      style={{
+                        display: 'flex',
+                        alignItems: 'center',
+                        justifyContent: 'space-between',
+                        gap: '0.5rem',
+                        padding: '0.35rem 0',
+                      }}
+                    >
+                      <span>
+                        {member.user.full_name}{' '}
+                        <span style={{ color: 'var(--text-secondary)' }}>
+                          @{member.user.username}
+                        </span>
+                      </span>
+                      <div style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+                        {canManage(openGroup) ? (
+                          <select
+                            className="form-select"
+                            aria-label={`Role of ${member.user.username}`}
+                            value={member.role}
+                            onChange={(e) =>
+                              void handleRoleChange(member, e.target.value as GroupRole)
+                            }
+                          >
+                            <option value="member">Member</option>
+                            <option value="manager">Manager</option>
+                          </select>
+                        ) : (
+                          <span style={{ fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                            {member.role === 'manager' ? 'Manager' : 'Member'}
+                          </span>
+                        )}
+                        {(canManage(openGroup) || member.user.id === user?.id) && (
+                          <button
+                            className="btn btn-secondary btn-sm"
+                            onClick={() => void handleRemoveMember(member)}
+                          >
+                            {member.user.id === user?.id ? 'Leave' : 'Remove'}
+                          </button>
+                        )}
+                      </div>
+                    </div>
+                  ))}
+
+                  {canManage(openGroup) && (
+                    <div style={{ display: 'flex', gap: '0.5rem', marginTop: '0.75rem' }}>
+                      <input
+                        type="text"
+                        className="form-input"
+                        aria-label="Username to add"
+                        placeholder="Username"
+                        value={newMember}
+                        onChange={(e) => setNewMember(e.target.value)}
+                      />
+                      <button
+                        className="btn btn-primary btn-sm"
+                        onClick={() => void handleAddMember()}
+                      >
+                        Add
+                      </button>
+                    </div>
+                  )}
+                </div>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div style={{ display: 'flex', gap: '0.5rem', marginTop: '1.5rem' }}>
+        <input
+          type="text"
+          className="form-input"
+          aria-label="New group name"
+          placeholder="e.g. Household"
+          value={newGroupName}
+          onChange={(e) => setNewGroupName(e.target.value)}
+          maxLength={100}
+        />
+        <button
+          className="btn btn-primary"
+          onClick={() => void handleCreate()}
+          disabled={creating}
+        >
+          <i className="fas fa-users"></i> Create Group
+        </button>
+      </div>
+
+      <ConfirmModal
+        isOpen={!!groupToDelete}
+        onClose={() => setGroupToDelete(null)}
+        onConfirm={handleDeleteGroup}
+        title="Delete Group"
+        message={`Delete "${groupToDelete?.name}"? Members lose access to everything shared with the group.`}
+        confirmText="Delete"
+      />
+    </div>
+  );
+}
@@ -0,0 +1,281 @@
+import { useState, useEffect } from 'react';
+import { useApp } from '@/context/AppContext';
+import { useAuth } from '@/context/AuthContext';
+import type { Invitation, PermissionLevel } from '@/types';
+import { authApi } from '@/services/api';
+
+const PERMISSION_OPTIONS: { value: PermissionLevel; label: string }[] = [
+  { value: 'view', label: 'View' },
+  { value: 'edit_items', label: 'Edit Items' },
+  { value: 'edit_inventory', label: 'Edit Inventory' },
+];
+
+const EXPIRY_OPTIONS = [
+  { value: '24', label: '1 day' },
+  { value: '72', label: '3 days' },
+  { value: '168', label: '7 days' },
+  { value: '720', label: '30 days' },
+];
+
+// Summarize what accepting an invitation grants
+function describeInvitation(invitation: Invitation): string {
+  const parts = [invitation.is_admin ? 'Administrator' : 'User'];
+  if (invitation.inventory_name && invitation.permission_level) {
+    parts.push(`${invitation.inventory_name} (${invitation.permission_level.replace('_', ' ')})`);
+  }
+  if (invitation.grant_all_access) {
+    parts.push('All Access');
+  }
+  return parts.join(' · ');
+}
+
+export function InvitationsSection() {
+  const { showToast, inventories } = useApp();
+  const { user } = useAuth();
+  const [invitations, setInvitations] = useState<Invitation[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [creating, setCreating] = useState(false);
+  const [inviteUrl, setInviteUrl] = useState<string | null>(null);
+  const [isAdmin, setIsAdmin] = useState(false);
+  const [inventoryId, setInventoryId] = useState('');
+  const [permissionLevel, setPermissionLevel] = useState<PermissionLevel>('view');
+  const [grantAllAccess, setGrantAllAccess] = useState(false);
+  const [expiresInHours, setExpiresInHours] = useState('72');
+
+  // Admins can share any inventory, everyone else only the ones they own
+  const shareableInventories = inventories.filter(
+    (inventory) => inventory.id !== undefined && (user?.is_admin || inventory.user_id === user?.id)
+  );
+
+  useEffect(() => {
+    void loadInvitations();
+  }, []);
+
+  const loadInvitations = async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getInvitations();
+      if (result.success && result.data) {
+        setInvitations(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading invitations:', error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleCreate = async () => {
+    if (!user?.is_admin && !inventoryId && !grantAllAccess) {
+      showToast('Choose an inventory to share or grant All Access', 'error');
+      return;
+    }
+
+    setCreating(true);
+    try {
+      const result = await authApi.createInvitation({
+        is_admin: isAdmin,
+        inventory_id: inventoryId ? Number(inventoryId) : undefined,
+        permission_level: inventoryId ? permissionLevel : undefined,
+        grant_all_access: grantAllAccess,
+        expires_in_hours: Number(expiresInHours),
+      });
+      if (result.success && result.data) {
+        setInviteUrl(`${window.location.origin}${result.data.invite_path}`);
+        setIsAdmin(false);
+        setInventoryId('');
+        setGrantAllAccess(false);
+        void loadInvitations();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to create invitation', 'error');
+      }
+    } catch {
+      showToast('Failed to create invitation', 'error');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleRevoke = async (invitation: Invitation) => {
+    try {
+      const result = await authApi.revokeInvitation(invitation.id);
+      if (result.success) {
+        showToast('Invitation revoked', 'success');
+        void loadInvitations();
+      } else {
+        showToast(result.error ?? 'Failed to revoke invitation', 'error');
+      }
+    } catch {
+      showToast('Failed to revoke invitation', 'error');
+    }
+  };
+
+  const handleCopy = async () => {
+    if (!inviteUrl) {
+      return;
+    }
+    try {
+      await navigator.clipboard.writeText(inviteUrl);
+      showToast('Invitation link copied to clipboard', 'success');
+    } catch {
+      showToast('Failed to copy to clipboard', 'error');
+    }
+  };
+
+  return (
+    <div className="invitations-section">
+      {inviteUrl && (
+        <div
+          style={{
+            padding: '1rem',
+            marginBottom: '1.5rem',
+            background: 'var(--warning-bg, #fff3cd)',
+            border: '1px solid var(--warning-border, #ffeeba)',
+            borderRadius: '8px',
+          }}
+        >
+          <p style={{ marginTop: 0 }}>
+            <strong>Copy this invitation link now.</strong> It will not be shown again and works
+            once.
+          </p>
+          <code style={{ wordBreak: 'break-all' }}>{inviteUrl}</code>
+          <div style={{ marginTop: '0.75rem', display: 'flex', gap: '0.5rem' }}>
+            <button className="btn btn-secondary btn-sm" onClick={() => void handleCopy()}>
+              <i className="fas fa-copy"></i> Copy
+            </button>
+            <button className="btn btn-secondary btn-sm" onClick={() => setInviteUrl(null)}>
+              Done
+            </button>
+          </div>
+        </div>
+      )}
+
+      {loading && invitations.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : invitations.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>No pending invitations.</p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {invitations.map((invitation) => (
+            <li
+              key={invitation.id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                gap: '1rem',
+                padding: '0.75rem 0',
+                borderBottom: '1px solid var(--border-color)',
+              }}
+            >
+              <div>
+                <strong>{describeInvitation(invitation)}</strong>
+                <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                  From {invitation.invited_by_username} · Expires{' '}
+                  {new Date(invitation.expires_at).toLocaleString()}
+                </p>
+              </div>
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={() => void handleRevoke(invitation)}
+              >
+                Revoke
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div style={{ marginTop: '1.5rem' }}>
+        {user?.is_admin && (
+          <div className="form-group">
+            <label className="form-checkbox">
+              <input
+                type="checkbox"
+                checked={isAdmin}
+                onChange={(e) => setIsAdmin(e.target.checked)}
+              />
+              <span className="form-checkbox-label">Administrator</span>
+            </label>
+          </div>
+        )}
+
+        {shareableInventories.length > 0 && (
+          <div className="form-group">
+            <label className="form-label" htmlFor="invitation-inventory">
+              Share inventory
+            </label>
+            <div style={{ display: 'flex', gap: '0.5rem' }}>
+              <select
+                className="form-select"
+                id="invitation-inventory"
+                value={inventoryId}
+                onChange={(e) => setInventoryId(e.target.value)}
+              >
+                <option value="">None</option>
+                {shareableInventories.map((inventory) => (
+                  <option key={inventory.id} value={inventory.id}>
+                    {inventory.name}
+                  </option>
+                ))}
+              </select>
+              {inventoryId && (
+                <select
+                  className="form-select"
+                  aria-label="Permission level"
+                  value={permissionLevel}
+                  onChange={(e) => setPermissionLevel(e.target.value as PermissionLevel)}
+                >
+                  {PERMISSION_OPTIONS.map((option) => (
+                    <option key={option.value} value={option.value}>
+                      {option.label}
+                    </option>
+                  ))}
+                </select>
+              )}
+            </div>
+          </div>
+        )}
+
+        <div className="form-group">
+          <label className="form-checkbox">
+            <input
+              type="checkbox"
+              checked={grantAllAccess}
+              onChange={(e) => setGrantAllAccess(e.target.checked)}
+            />
+            <span className="form-checkbox-label">Give All Access to my inventories</span>
+          </label>
+        </div>
+
+        <div className="form-group">
+          <label className="form-label" htmlFor="invitation-expiry">
+            Expires
+          </label>
+          <select
+            className="form-select"
+            id="invitation-expiry"
+            value={expiresInHours}
+            onChange={(e) => setExpiresInHours(e.target.value)}
+          >
+            {EXPIRY_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <button
+          className="btn btn-primary"
+          onClick={() => void handleCreate()}
+          disabled={creating}
+        >
+          <i className="fas fa-envelope-open-text"></i> Create Invitation
+        </button>
+      </div>
+    </div>
+  );
+}
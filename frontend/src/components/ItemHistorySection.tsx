@@ -0,0 +1,160 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import { useAuth } from '@/context/AuthContext';
+import { ConfirmModal } from '@/components';
+import type { Item, ItemFieldChange, ItemRevision } from '@/types';
+import { itemApi } from '@/services/api';
+import { formatDate, type DateFormatType } from '@/utils/dateFormat';
+
+const FIELD_LABELS: Record<string, string> = {
+  inventory_id: 'Inventory',
+  name: 'Name',
+  description: 'Description',
+  category: 'Category',
+  location: 'Location',
+  purchase_date: 'Purchase date',
+  purchase_price: 'Purchase price',
+  warranty_expiry: 'Warranty expiry',
+  notes: 'Notes',
+  quantity: 'Quantity',
+};
+
+function describeValue(value: unknown): string {
+  if (value === null || value === undefined || value === '') {
+    return '—';
+  }
+  if (typeof value === 'object') {
+    // Organizer values: show the option name or text
+    const organizer = value as { value?: string | null; text_value?: string | null };
+    return organizer.value ?? organizer.text_value ?? '—';
+  }
+  return String(value);
+}
+
+function describeChange(change: ItemFieldChange): string {
+  const label = change.label ?? FIELD_LABELS[change.field] ?? change.field;
+  return `${label}: ${describeValue(change.old_value)} → ${describeValue(change.new_value)}`;
+}
+
+interface ItemHistorySectionProps {
+  itemId: number;
+  onReverted: (item: Item) => void;
+}
+
+export function ItemHistorySection({ itemId, onReverted }: ItemHistorySectionProps) {
+  const { showToast } = useApp();
+  const { settings } = useAuth();
+  const [history, setHistory] = useState<ItemRevision[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [revertTo, setRevertTo] = useState<ItemRevision | null>(null);
+
+  const loadHistory = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await itemApi.getHistory(itemId);
+      if (result.success && result.data) {
+        setHistory(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading item history:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, [itemId]);
+
+  useEffect(() => {
+    void loadHistory();
+  }, [loadHistory]);
+
+  const handleRevert = async () => {
+    if (!revertTo) {
+      return;
+    }
+    try {
+      const result = await itemApi.revert(itemId, revertTo.revision);
+      if (result.success && result.data) {
+        showToast(result.message ?? 'Item reverted', 'success');
+        setRevertTo(null);
+        onReverted(result.data);
+        void loadHistory();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to revert item', 'error');
+      }
+    } catch {
+      showToast('Failed to revert item', 'error');
+    }
+  };
+
+  if (loading && history.length === 0) {
+    return (
+      <p style={{ fontSize: '0.85rem', color: 'var(--text-muted)' }}>Loading history...</p>
+    );
+  }
+  if (history.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="form-group">
+      <label className="form-label">History</label>
+      <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+        {history.map((revision, index) => (
+          <li
+            key={revision.revision}
+            style={{ padding: '0.5rem 0', borderBottom: '1px solid var(--border-color)' }}
+          >
+            <div
+              style={{
+                display: 'flex',
+                justifyContent: 'space-between',
+                alignItems: 'center',
+                gap: '0.5rem',
+                fontSize: '0.85rem',
+              }}
+            >
+              <span>
+                <strong>#{revision.revision}</strong>{' '}
+                {revision.action === 'created'
+                  ? 'Created'
+                  : revision.action === 'reverted'
+                    ? `Reverted to #${revision.reverted_to_revision}`
+                    : 'Changed'}{' '}
+                by {revision.changed_by_username ?? 'unknown'} on{' '}
+                {formatDate(
+                  revision.changed_at,
+                  (settings?.date_format ?? 'MM/DD/YYYY') as DateFormatType
+                )}
+              </span>
+              {index > 0 && (
+                <button className="btn btn-secondary btn-sm" onClick={() => setRevertTo(revision)}>
+                  Revert to this
+                </button>
+              )}
+            </div>
+            <ul
+              style={{
+                margin: '0.25rem 0 0',
+                paddingLeft: '1.25rem',
+                fontSize: '0.8rem',
+                color: 'var(--text-secondary)',
+              }}
+            >
+              {revision.changes.map((change) => (
+                <li key={change.field}>{describeChange(change)}</li>
+              ))}
+            </ul>
+          </li>
+        ))}
+      </ul>
+
+      <ConfirmModal
+        isOpen={!!revertTo}
+        onClose={() => setRevertTo(null)}
+        onConfirm={handleRevert}
+        title="Revert Item"
+        message={`Put this item back the way it was at revision #${revertTo?.revision}? The revert is added to the history, so it can be undone too.`}
+        confirmText="Revert"
+      />
+    </div>
+  );
+}
@@ -0,0 +1,243 @@
+import { useState, useEffect } from 'react';
+import { useApp } from '@/context/AppContext';
+import type { WebauthnCredential } from '@/types';
+import { authApi } from '@/services/api';
+import { createPasskey, isWebauthnSupported } from '@/utils/webauthn';
+
+export function PasskeysSection() {
+  const { showToast } = useApp();
+  const [enabled, setEnabled] = useState<boolean | null>(null);
+  const [passkeys, setPasskeys] = useState<WebauthnCredential[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [registering, setRegistering] = useState(false);
+  const [nickname, setNickname] = useState('');
+  const [renamingId, setRenamingId] = useState<string | null>(null);
+  const [renameValue, setRenameValue] = useState('');
+
+  useEffect(() => {
+    void loadStatus();
+  }, []);
+
+  const loadStatus = async () => {
+    try {
+      const result = await authApi.getWebauthnStatus();
+      const isEnabled = result.success && result.data?.enabled === true;
+      setEnabled(isEnabled);
+      if (isEnabled) {
+        void loadPasskeys();
+      }
+    } catch {
+      setEnabled(false);
+    }
+  };
+
+  const loadPasskeys = async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getPasskeys();
+      if (result.success && result.data) {
+        setPasskeys(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading passkeys:', error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleRegister = async () => {
+    if (!nickname.trim()) {
+      showToast('Enter a name for this passkey', 'error');
+      return;
+    }
+
+    setRegistering(true);
+    try {
+      const options = await authApi.startPasskeyRegistration();
+      if (!options.success || !options.data) {
+        showToast(options.error ?? 'Failed to start passkey registration', 'error');
+        return;
+      }
+      const credential = await createPasskey(options.data);
+      const result = await authApi.finishPasskeyRegistration(nickname.trim(), credential);
+      if (result.success) {
+        showToast('Passkey added', 'success');
+        setNickname('');
+        void loadPasskeys();
+      } else {
+        showToast(result.error ?? 'Failed to add passkey', 'error');
+      }
+    } catch (error) {
+      // The browser rejects with NotAllowedError when the prompt is dismissed
+      if (error instanceof DOMException && error.name === 'NotAllowedError') {
+        showToast('Passkey registration was cancelled', 'info');
+      } else {
+        showToast('Failed to add passkey', 'error');
+      }
+    } finally {
+      setRegistering(false);
+    }
+  };
+
+  const handleRename = async (passkey: WebauthnCredential) => {
+    if (!renameValue.trim()) {
+      return;
+    }
+    try {
+      const result = await authApi.renamePasskey(passkey.id, renameValue.trim());
+      if (result.success) {
+        setRenamingId(null);
+        void loadPasskeys();
+      } else {
+        showToast(result.error ?? 'Failed to rename passkey', 'error');
+      }
+    } catch {
+      showToast('Failed to rename passkey', 'error');
+    }
+  };
+
+  const handleDelete = async (passkey: WebauthnCredential) => {
+    try {
+      const result = await authApi.deletePasskey(passkey.id);
+      if (result.success) {
+        showToast(`Removed "${passkey.nickname}"`, 'success');
+        void loadPasskeys();
+      } else {
+        showToast(result.error ?? 'Failed to remove passkey', 'error');
+      }
+    } catch {
+      showToast('Failed to remove passkey', 'error');
+    }
+  };
+
+  if (enabled === null) {
+    return null;
+  }
+
+  if (!enabled) {
+    return (
+      <p style={{ color: 'var(--text-secondary)', margin: 0 }}>
+        Passkeys are not configured on this server.
+      </p>
+    );
+  }
+
+  return (
+    <div className="passkeys-section">
+      {loading && passkeys.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {passkeys.map((passkey) => (
+            <li
+              key={passkey.id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                gap: '1rem',
+                padding: '0.75rem 0',
+                borderBottom: '1px solid var(--border-color)',
+              }}
+            >
+              {renamingId === passkey.id ? (
+                <input
+                  type="text"
+                  className="form-input"
+                  value={renameValue}
+                  onChange={(e) => setRenameValue(e.target.value)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') {
+                      void handleRename(passkey);
+                    }
+                  }}
+                  maxLength={100}
+                  aria-label="Passkey name"
+                />
+              ) : (
+                <div>
+                  <strong>{passkey.nickname}</strong>
+                  <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                    Added {new Date(passkey.created_at).toLocaleDateString()}
+                    {' · '}
+                    {passkey.last_used_at
+                      ? `Last used ${new Date(passkey.last_used_at).toLocaleString()}`
+                      : 'Never used'}
+                  </p>
+                </div>
+              )}
+              <div style={{ display: 'flex', gap: '0.5rem' }}>
+                {renamingId === passkey.id ? (
+                  <>
+                    <button
+                      className="btn btn-primary btn-sm"
+                      onClick={() => void handleRename(passkey)}
+                    >
+                      Save
+                    </button>
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => setRenamingId(null)}
+                    >
+                      Cancel
+                    </button>
+                  </>
+                ) : (
+                  <>
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => {
+                        setRenamingId(passkey.id);
+                        setRenameValue(passkey.nickname);
+                      }}
+                    >
+                      Rename
+                    </button>
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => void handleDelete(passkey)}
+                    >
+                      Remove
+                    </button>
+                  </>
+                )}
+              </div>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      {isWebauthnSupported() ? (
+        <div style={{ marginTop: '1.5rem' }}>
+          <div className="form-group">
+            <label className="form-label" htmlFor="passkey-nickname">
+              Passkey name
+            </label>
+            <input
+              type="text"
+              className="form-input"
+              id="passkey-nickname"
+              value={nickname}
+              onChange={(e) => setNickname(e.target.value)}
+              placeholder="e.g. MacBook Touch ID or YubiKey"
+              maxLength={100}
+            />
+          </div>
+          <button
+            className="btn btn-primary"
+            onClick={() => void handleRegister()}
+            disabled={registering}
+          >
+            <i className="fas fa-fingerprint"></i> {registering ? 'Waiting...' : 'Add Passkey'}
+          </button>
+        </div>
+      ) : (
+        <p className="form-hint" style={{ marginTop: '1rem' }}>
+          This browser does not support passkeys.
+        </p>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,129 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import type { PendingInventoryShare, UserAccessGrantWithUsers } from '@/types';
+import { authApi } from '@/services/api';
+
+interface PendingInvitationsBannerProps {
+  onAccepted: () => void;
+}
+
+const PERMISSION_NAMES: Record<PendingInventoryShare['permission_level'], string> = {
+  view: 'view',
+  edit_items: 'edit items in',
+  edit_inventory: 'edit',
+};
+
+export function PendingInvitationsBanner({ onAccepted }: PendingInvitationsBannerProps) {
+  const { showToast } = useApp();
+  const [shares, setShares] = useState<PendingInventoryShare[]>([]);
+  const [grants, setGrants] = useState<UserAccessGrantWithUsers[]>([]);
+
+  const loadPending = useCallback(async () => {
+    try {
+      const result = await authApi.getMyInventories();
+      if (result.success && result.data) {
+        setShares(result.data.pending_shares);
+        setGrants(result.data.pending_access_grants);
+      }
+    } catch (error) {
+      console.error('Error loading pending invitations:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadPending();
+  }, [loadPending]);
+
+  const respondToShare = async (share: PendingInventoryShare, accept: boolean) => {
+    try {
+      const result = await authApi.respondToShare(share.id, accept);
+      if (result.success) {
+        showToast(accept ? `"${share.inventory_name}" added` : 'Invitation declined', 'success');
+        void loadPending();
+        if (accept) {
+          onAccepted();
+        }
+      } else {
+        showToast(result.error ?? 'Failed to answer invitation', 'error');
+      }
+    } catch {
+      showToast('Failed to answer invitation', 'error');
+    }
+  };
+
+  const respondToGrant = async (grant: UserAccessGrantWithUsers, accept: boolean) => {
+    try {
+      const result = await authApi.respondToAccessGrant(grant.id, accept);
+      if (result.success) {
+        showToast(accept ? 'All Access accepted' : 'Invitation declined', 'success');
+        void loadPending();
+        if (accept) {
+          onAccepted();
+        }
+      } else {
+        showToast(result.error ?? 'Failed to answer invitation', 'error');
+      }
+    } catch {
+      showToast('Failed to answer invitation', 'error');
+    }
+  };
+
+  if (shares.length === 0 && grants.length === 0) {
+    return null;
+  }
+
+  const row = (key: string, text: string, onRespond: (accept: boolean) => void) => (
+    <div
+      key={key}
+      style={{
+        display: 'flex',
+        alignItems: 'center',
+        justifyContent: 'space-between',
+        gap: '1rem',
+        padding: '0.5rem 0',
+      }}
+    >
+      <span>{text}</span>
+      <div style={{ display: 'flex', gap: '0.5rem' }}>
+        <button className="btn btn-primary btn-sm" onClick={() => onRespond(true)}>
+          Accept
+        </button>
+        <button className="btn btn-secondary btn-sm" onClick={() => onRespond(false)}>
+          Decline
+        </button>
+      </div>
+    </div>
+  );
+
+  return (
+    <div
+      style={{
+        background: 'var(--bg-secondary)',
+        borderRadius: 'var(--radius-lg)',
+        padding: '1.25rem',
+        marginBottom: '1.5rem',
+        border: '1px solid var(--border-color)',
+      }}
+    >
+      <h3 style={{ margin: '0 0 0.5rem', fontSize: '1.125rem', fontWeight: 600 }}>
+        <i className="fas fa-envelope-open-text"></i> Pending Invitations
+      </h3>
+      {shares.map((share) =>
+        row(
+          share.id,
+          share.role_name
+            ? `${share.shared_by_user.full_name} invited you to "${share.inventory_name}" as ${share.role_name}`
+            : `${share.shared_by_user.full_name} invited you to ${PERMISSION_NAMES[share.permission_level]} "${share.inventory_name}"`,
+          (accept) => void respondToShare(share, accept)
+        )
+      )}
+      {grants.map((grant) =>
+        row(
+          grant.id,
+          `${grant.grantor.full_name} offered you All Access to their inventories`,
+          (accept) => void respondToGrant(grant, accept)
+        )
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,230 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import { ConfirmModal } from '@/components';
+import type { Capability, InventoryRole } from '@/types';
+import { authApi } from '@/services/api';
+
+const CAPABILITY_LABELS: Record<Capability, string> = {
+  view: 'View',
+  add_items: 'Add items',
+  edit_items: 'Edit items',
+  remove_items: 'Remove items',
+  manage_organizers: 'Manage organizers',
+  edit_inventory: 'Edit inventory',
+  manage_shares: 'Manage sharing',
+  export: 'Export reports',
+};
+
+const ALL_CAPABILITIES = Object.keys(CAPABILITY_LABELS) as Capability[];
+
+interface RoleDraft {
+  name: string;
+  description: string;
+  capabilities: Capability[];
+}
+
+const EMPTY_DRAFT: RoleDraft = { name: '', description: '', capabilities: ['view'] };
+
+export function RolesSection() {
+  const { showToast } = useApp();
+  const [roles, setRoles] = useState<InventoryRole[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [draft, setDraft] = useState<RoleDraft>(EMPTY_DRAFT);
+  const [editingRoleId, setEditingRoleId] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [roleToDelete, setRoleToDelete] = useState<InventoryRole | null>(null);
+
+  const loadRoles = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getRoles();
+      if (result.success && result.data) {
+        setRoles(result.data.custom);
+      }
+    } catch (error) {
+      console.error('Error loading roles:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadRoles();
+  }, [loadRoles]);
+
+  const toggleCapability = (capability: Capability) => {
+    setDraft((current) => ({
+      ...current,
+      capabilities: current.capabilities.includes(capability)
+        ? current.capabilities.filter((c) => c !== capability)
+        : [...current.capabilities, capability],
+    }));
+  };
+
+  const startEditing = (role: InventoryRole) => {
+    setEditingRoleId(role.id);
+    setDraft({
+      name: role.name,
+      description: role.description ?? '',
+      capabilities: role.capabilities,
+    });
+  };
+
+  const resetDraft = () => {
+    setEditingRoleId(null);
+    setDraft(EMPTY_DRAFT);
+  };
+
+  const handleSave = async () => {
+    if (!draft.name.trim()) {
+      showToast('Please enter a role name', 'error');
+      return;
+    }
+    setSaving(true);
+    try {
+      const data = {
+        name: draft.name.trim(),
+        description: draft.description.trim() || undefined,
+        capabilities: draft.capabilities,
+      };
+      const result = editingRoleId
+        ? await authApi.updateRole(editingRoleId, data)
+        : await authApi.createRole(data);
+      if (result.success) {
+        showToast(editingRoleId ? 'Role updated' : 'Role created', 'success');
+        resetDraft();
+        void loadRoles();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to save role', 'error');
+      }
+    } catch {
+      showToast('Failed to save role', 'error');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!roleToDelete) {
+      return;
+    }
+    try {
+      const result = await authApi.deleteRole(roleToDelete.id);
+      if (result.success) {
+        showToast('Role deleted', 'success');
+        if (editingRoleId === roleToDelete.id) {
+          resetDraft();
+        }
+        setRoleToDelete(null);
+        void loadRoles();
+      } else {
+        showToast(result.error ?? 'Failed to delete role', 'error');
+      }
+    } catch {
+      showToast('Failed to delete role', 'error');
+    }
+  };
+
+  return (
+    <div className="roles-section">
+      {loading && roles.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : roles.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>
+          You have no custom roles. Shares use the built-in View Only, Edit Items and Edit
+          Inventory roles.
+        </p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {roles.map((role) => (
+            <li
+              key={role.id}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                gap: '1rem',
+                padding: '0.75rem 0',
+                borderBottom: '1px solid var(--border-color)',
+              }}
+            >
+              <div>
+                <strong>{role.name}</strong>
+                {role.description && (
+                  <span style={{ color: 'var(--text-secondary)' }}> · {role.description}</span>
+                )}
+                <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                  {role.capabilities.map((capability) => CAPABILITY_LABELS[capability]).join(', ')}
+                </p>
+              </div>
+              <div style={{ display: 'flex', gap: '0.5rem' }}>
+                <button className="btn btn-secondary btn-sm" onClick={() => startEditing(role)}>
+                  Edit
+                </button>
+                <button className="btn btn-secondary btn-sm" onClick={() => setRoleToDelete(role)}>
+                  Delete
+                </button>
+              </div>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div style={{ marginTop: '1.5rem' }}>
+        <div style={{ display: 'flex', gap: '0.5rem' }}>
+          <input
+            type="text"
+            className="form-input"
+            aria-label="Role name"
+            placeholder="e.g. Contributor"
+            value={draft.name}
+            onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+            maxLength={100}
+          />
+          <input
+            type="text"
+            className="form-input"
+            aria-label="Role description"
+            placeholder="Description (optional)"
+            value={draft.description}
+            onChange={(e) => setDraft({ ...draft, description: e.target.value })}
+          />
+        </div>
+        <div style={{ display: 'flex', flexWrap: 'wrap', gap: '0.75rem', margin: '0.75rem 0' }}>
+          {ALL_CAPABILITIES.map((capability) => (
+            <label key={capability} style={{ display: 'flex', alignItems: 'center', gap: '0.35rem' }}>
+              <input
+                type="checkbox"
+                checked={capability === 'view' || draft.capabilities.includes(capability)}
+                disabled={capability === 'view'}
+                onChange={() => toggleCapability(capability)}
+              />
+              {CAPABILITY_LABELS[capability]}
+            </label>
+          ))}
+        </div>
+        <div style={{ display: 'flex', gap: '0.5rem' }}>
+          <button className="btn btn-primary" onClick={() => void handleSave()} disabled={saving}>
+            <i className="fas fa-user-tag"></i> {editingRoleId ? 'Save Role' : 'Create Role'}
+          </button>
+          {editingRoleId && (
+            <button className="btn btn-secondary" onClick={resetDraft}>
+              Cancel
+            </button>
+          )}
+        </div>
+      </div>
+
+      <ConfirmModal
+        isOpen={!!roleToDelete}
+        onClose={() => setRoleToDelete(null)}
+        onConfirm={handleDelete}
+        title="Delete Role"
+        message={`Delete "${roleToDelete?.name}"? Shares using it keep the closest built-in role.`}
+        confirmText="Delete"
+      />
+    </div>
+  );
+}
@@ -1,15 +1,18 @@
 import { useState, useEffect, useCallback } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { Modal, ConfirmModal } from '@/components';
+import { Modal, ConfirmModal, ShareLinksSection } from '@/components';
 import { useApp } from '@/context/AppContext';
 import type {
   InventoryShare,
-  CreateInventoryShareRequest,
+  InventoryGroupShare,
+  UserGroup,
+  InventoryRole,
   PermissionLevel,
   User,
   TransferOwnershipRequest,
 } from '@/types';
 import { authApi } from '@/services/api';
+import { describeShareSchedule, scheduleInputToIso } from '@/utils/shareSchedule';
 
 interface ShareInventoryModalProps {
   isOpen: boolean;
@@ -33,6 +36,50 @@ const PERMISSION_LABELS: Record<PermissionLevel, { label: string; description: s
   },
 };
 
+// Role selects use the permission level for built-in roles and `role:<id>` for custom ones
+const CUSTOM_ROLE_PREFIX = 'role:';
+
+function roleValue(share: { permission_level: PermissionLevel; role_id?: string }): string {
+  return share.role_id ? `${CUSTOM_ROLE_PREFIX}${share.role_id}` : share.permission_level;
+}
+
+function roleRequest(value: string): { permission_level?: PermissionLevel; role_id?: string } {
+  return value.startsWith(CUSTOM_ROLE_PREFIX)
+    ? { role_id: value.slice(CUSTOM_ROLE_PREFIX.length) }
+    : { permission_level: value as PermissionLevel };
+}
+
+function roleLabel(share: { permission_level: PermissionLevel; role_name?: string }): string {
+  return share.role_name ?? PERMISSION_LABELS[share.permission_level].label;
+}
+
+function RoleOptions({
+  customRoles,
+  withDescriptions,
+}: {
+  customRoles: InventoryRole[];
+  withDescriptions?: boolean;
+}) {
+  return (
+    <>
+      {Object.entries(PERMISSION_LABELS).map(([value, { label, description }]) => (
+        <option key={value} value={value}>
+          {withDescriptions ? `${label} - ${description}` : label}
+        </option>
+      ))}
+      {customRoles.length > 0 && (
+        <optgroup label="Custom roles">
+          {customRoles.map((role) => (
+            <option key={role.id} value={`${CUSTOM_ROLE_PREFIX}${role.id}`}>
+              {withDescriptions && role.description ? `${role.name} - ${role.description}` : role.name}
+            </option>
+          ))}
+        </optgroup>
+      )}
+    </>
+  );
+}
+
 export function ShareInventoryModal({
   isOpen,
   onClose,
@@ -45,13 +92,26 @@ export function ShareInventoryModal({
   const [users, setUsers] = useState<User[]>([]);
   const [loading, setLoading] = useState(false);
   const [showAddForm, setShowAddForm] = useState(false);
-  const [newShare, setNewShare] = useState<CreateInventoryShareRequest>({
+  const [newShare, setNewShare] = useState({
     shared_with_username: '',
-    permission_level: 'view',
+    role: 'view',
   });
+  const [customRoles, setCustomRoles] = useState<InventoryRole[]>([]);
+  const [shareStartsAt, setShareStartsAt] = useState('');
+  const [shareExpiresAt, setShareExpiresAt] = useState('');
   const [shareToDelete, setShareToDelete] = useState<InventoryShare | null>(null);
   const [editingShareId, setEditingShareId] = useState<string | null>(null);
 
+  // Group share state
+  const [groupShares, setGroupShares] = useState<InventoryGroupShare[]>([]);
+  const [groups, setGroups] = useState<UserGroup[]>([]);
+  const [newGroupShare, setNewGroupShare] = useState({
+    group_id: '',
+    role: 'view',
+  });
+  const [groupShareStartsAt, setGroupShareStartsAt] = useState('');
+  const [groupShareExpiresAt, setGroupShareExpiresAt] = useState('');
+
   // Transfer ownership state
   const [showTransferForm, setShowTransferForm] = useState(false);
   const [transferRequest, setTransferRequest] = useState<TransferOwnershipRequest>({
@@ -89,12 +149,96 @@ export function ShareInventoryModal({
     }
   }, [inventoryId, showToast]);
 
+  const loadGroupShares = useCallback(async () => {
+    try {
+      const [sharesResult, groupsResult] = await Promise.all([
+        authApi.getInventoryGroupShares(inventoryId),
+        authApi.getGroups(),
+      ]);
+      if (sharesResult.success && sharesResult.data) {
+        setGroupShares(sharesResult.data);
+      }
+      if (groupsResult.success && groupsResult.data) {
+        setGroups(groupsResult.data);
+      }
+    } catch {
+      showToast('Failed to load group shares', 'error');
+    }
+  }, [inventoryId, showToast]);
+
+  const loadRoles = useCallback(async () => {
+    try {
+      const result = await authApi.getInventoryRoles(inventoryId);
+      if (result.success && result.data) {
+        setCustomRoles(result.data.custom);
+      }
+    } catch {
+      showToast('Failed to load roles', 'error');
+    }
+  }, [inventoryId, showToast]);
+
   useEffect(() => {
     if (isOpen) {
       void loadShares();
       void loadUsers();
+      void loadGroupShares();
+      void loadRoles();
+    }
+  }, [isOpen, loadShares, loadUsers, loadGroupShares, loadRoles]);
+
+  const handleAddGroupShare = async () => {
+    if (!newGroupShare.group_id) {
+      showToast('Please select a group', 'error');
+      return;
+    }
+
+    try {
+      const result = await authApi.shareInventoryWithGroup(inventoryId, {
+        group_id: newGroupShare.group_id,
+        ...roleRequest(newGroupShare.role),
+        starts_at: scheduleInputToIso(groupShareStartsAt),
+        expires_at: scheduleInputToIso(groupShareExpiresAt),
+      });
+      if (result.success) {
+        showToast(result.message ?? 'Inventory shared with group', 'success');
+        setNewGroupShare({ group_id: '', role: 'view' });
+        setGroupShareStartsAt('');
+        setGroupShareExpiresAt('');
+        void loadGroupShares();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to share with group', 'error');
+      }
+    } catch {
+      showToast('Failed to share with group', 'error');
+    }
+  };
+
+  const handleUpdateGroupShare = async (shareId: string, role: string) => {
+    try {
+      const result = await authApi.updateInventoryGroupShare(shareId, roleRequest(role));
+      if (result.success) {
+        void loadGroupShares();
+      } else {
+        showToast(result.error ?? 'Failed to update permission', 'error');
+      }
+    } catch {
+      showToast('Failed to update permission', 'error');
+    }
+  };
+
+  const handleRemoveGroupShare = async (shareId: string) => {
+    try {
+      const result = await authApi.removeInventoryGroupShare(shareId);
+      if (result.success) {
+        showToast('Group share removed', 'success');
+        void loadGroupShares();
+      } else {
+        showToast(result.error ?? 'Failed to remove group share', 'error');
+      }
+    } catch {
+      showToast('Failed to remove group share', 'error');
     }
-  }, [isOpen, loadShares, loadUsers]);
+  };
 
   const handleAddShare = async () => {
     if (!newShare.shared_with_username.trim()) {
@@ -103,25 +247,30 @@ export function ShareInventoryModal({
     }
 
     try {
-      const result = await authApi.shareInventory(inventoryId, newShare);
+      const result = await authApi.shareInventory(inventoryId, {
+        shared_with_username: newShare.shared_with_username,
+        ...roleRequest(newShare.role),
+        starts_at: scheduleInputToIso(shareStartsAt),
+        expires_at: scheduleInputToIso(shareExpiresAt),
+      });
       if (result.success) {
-        showToast('Inventory shared successfully', 'success');
-        setNewShare({ shared_with_username: '', permission_level: 'view' });
+        showToast(result.message ?? 'Invitation sent', 'success');
+        setNewShare({ shared_with_username: '', role: 'view' });
+        setShareStartsAt('');
+        setShareExpiresAt('');
         setShowAddForm(false);
         void loadShares();
       } else {
-        showToast(result.error ?? 'Failed to share inventory', 'error');
+        showToast(result.message ?? result.error ?? 'Failed to share inventory', 'error');
       }
     } catch {
       showToast('Failed to share inventory', 'error');
     }
   };
 
-  const handleUpdatePermission = async (shareId: string, newPermission: PermissionLevel) => {
+  const handleUpdatePermission = async (shareId: string, role: string) => {
     try {
-      const result = await authApi.updateInventoryShare(shareId, {
-        permission_level: newPermission,
-      });
+      const result = await authApi.updateInventoryShare(shareId, roleRequest(role));
       if (result.success) {
         showToast('Permission updated successfully', 'success');
         setEditingShareId(null);
@@ -231,24 +380,42 @@ export function ShareInventoryModal({
                 </select>
               </div>
               <div className="form-group">
-                <label className="form-label">Permission Level</label>
+                <label className="form-label">Role</label>
                 <select
                   className="form-select"
-                  value={newShare.permission_level}
-                  onChange={(e) =>
-                    setNewShare({
-                      ...newShare,
-                      permission_level: e.target.value as PermissionLevel,
-                    })
-                  }
+                  value={newShare.role}
+                  onChange={(e) => setNewShare({ ...newShare, role: e.target.value })}
                 >
-                  {Object.entries(PERMISSION_LABELS).map(([value, { label, description }]) => (
-                    <option key={value} value={value}>
-                      {label} - {description}
-                    </option>
-                  ))}
+                  <RoleOptions customRoles={customRoles} withDescriptions />
                 </select>
               </div>
+              <div style={{ display: 'flex', gap: '0.5rem' }}>
+                <div className="form-group" style={{ flex: 1 }}>
+                  <label className="form-label" htmlFor="share-starts-at">
+                    Starts (optional)
+                  </label>
+                  <input
+                    type="datetime-local"
+                    className="form-input"
+                    id="share-starts-at"
+                    value={shareStartsAt}
+                    onChange={(e) => setShareStartsAt(e.target.value)}
+                  />
+                </div>
+                <div className="form-group" style={{ flex: 1 }}>
+                  <label className="form-label" htmlFor="share-expires-at">
+                    Expires (optional)
+                  </label>
+                  <input
+                    type="datetime-local"
+                    className="form-input"
+                    id="share-expires-at"
+                    value={shareExpiresAt}
+                    min={shareStartsAt || undefined}
+                    onChange={(e) => setShareExpiresAt(e.target.value)}
+                  />
+                </div>
+              </div>
               <div style={{ display: 'flex', gap: '0.5rem' }}>
                 <button className="btn btn-primary" onClick={handleAddShare}>
                   Share
@@ -292,24 +459,17 @@ export function ShareInventoryModal({
                       </div>
                       <div style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
                         @{share.shared_with_user.username}
+                        {describeShareSchedule(share) && ` · ${describeShareSchedule(share)}`}
                       </div>
                       {editingShareId === share.id ? (
                         <div style={{ marginTop: '0.75rem' }}>
                           <select
                             className="form-select"
-                            value={share.permission_level}
-                            onChange={(e) =>
-                              handleUpdatePermission(share.id, e.target.value as PermissionLevel)
-                            }
+                            value={roleValue(share)}
+                            onChange={(e) => handleUpdatePermission(share.id, e.target.value)}
                             style={{ maxWidth: '300px' }}
                           >
-                            {Object.entries(PERMISSION_LABELS).map(
-                              ([value, { label, description }]) => (
-                                <option key={value} value={value}>
-                                  {label} - {description}
-                                </option>
-                              )
-                            )}
+                            <RoleOptions customRoles={customRoles} withDescriptions />
                           </select>
                           <button
                             className="btn btn-secondary"
@@ -330,8 +490,13 @@ export function ShareInventoryModal({
                               color: 'var(--text-primary)',
                             }}
                           >
-                            {PERMISSION_LABELS[share.permission_level].label}
+                            {roleLabel(share)}
                           </span>
+                          {share.status !== 'accepted' && (
+                            <span style={{ marginLeft: '0.5rem', color: 'var(--text-secondary)' }}>
+                              {share.status === 'pending' ? 'Invitation pending' : 'Declined'}
+                            </span>
+                          )}
                         </div>
                       )}
                     </div>
@@ -359,6 +524,120 @@ export function ShareInventoryModal({
             )}
           </div>
 
+          {/* Group Shares */}
+          <div className="group-shares-list" style={{ marginTop: '1.5rem' }}>
+            <h3 style={{ fontSize: '1rem', marginBottom: '1rem', color: 'var(--text-secondary)' }}>
+              Groups ({groupShares.length})
+            </h3>
+            {groupShares.length > 0 && (
+              <div style={{ display: 'flex', flexDirection: 'column', gap: '0.75rem' }}>
+                {groupShares.map((share) => (
+                  <div
+                    key={share.id}
+                    className="card"
+                    style={{
+                      padding: '1rem',
+                      display: 'flex',
+                      justifyContent: 'space-between',
+                      alignItems: 'center',
+                      gap: '0.5rem',
+                    }}
+                  >
+                    <div style={{ flex: 1 }}>
+                      <div style={{ fontWeight: '500' }}>👥 {share.group_name}</div>
+                      {describeShareSchedule(share) && (
+                        <div style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
+                          {describeShareSchedule(share)}
+                        </div>
+                      )}
+                    </div>
+                    <select
+                      className="form-select"
+                      aria-label={`Permission for ${share.group_name}`}
+                      value={roleValue(share)}
+                      onChange={(e) => void handleUpdateGroupShare(share.id, e.target.value)}
+                      style={{ maxWidth: '200px' }}
+                    >
+                      <RoleOptions customRoles={customRoles} />
+                    </select>
+                    <button
+                      className="btn btn-icon"
+                      onClick={() => void handleRemoveGroupShare(share.id)}
+                      title="Remove group share"
+                    >
+                      🗑️
+                    </button>
+                  </div>
+                ))}
+              </div>
+            )}
+            {groups.some((group) => !groupShares.some((share) => share.group_id === group.id)) ? (
+              <>
+                <div style={{ display: 'flex', gap: '0.5rem', marginTop: '0.75rem' }}>
+                  <select
+                    className="form-select"
+                    aria-label="Group"
+                    value={newGroupShare.group_id}
+                    onChange={(e) => setNewGroupShare({ ...newGroupShare, group_id: e.target.value })}
+                  >
+                    <option value="">Select a group...</option>
+                    {groups
+                      .filter((group) => !groupShares.some((share) => share.group_id === group.id))
+                      .map((group) => (
+                        <option key={group.id} value={group.id}>
+                          {group.name}
+                        </option>
+                      ))}
+                  </select>
+                  <select
+                    className="form-select"
+                    aria-label="Group role"
+                    value={newGroupShare.role}
+                    onChange={(e) => setNewGroupShare({ ...newGroupShare, role: e.target.value })}
+                  >
+                    <RoleOptions customRoles={customRoles} />
+                  </select>
+                  <button className="btn btn-primary" onClick={() => void handleAddGroupShare()}>
+                    Share
+                  </button>
+                </div>
+                <div style={{ display: 'flex', gap: '0.5rem', marginTop: '0.5rem' }}>
+                  <div className="form-group" style={{ flex: 1 }}>
+                    <label className="form-label" htmlFor="group-share-starts-at">
+                      Starts (optional)
+                    </label>
+                    <input
+                      type="datetime-local"
+                      className="form-input"
+                      id="group-share-starts-at"
+                      value={groupShareStartsAt}
+                      onChange={(e) => setGroupShareStartsAt(e.target.value)}
+                    />
+                  </div>
+                  <div className="form-group" style={{ flex: 1 }}>
+                    <label className="form-label" htmlFor="group-share-expires-at">
+                      Expires (optional)
+                    </label>
+                    <input
+                      type="datetime-local"
+                      className="form-input"
+                      id="group-share-expires-at"
+                      value={groupShareExpiresAt}
+                      min={groupShareStartsAt || undefined}
+                      onChange={(e) => setGroupShareExpiresAt(e.target.value)}
+                    />
+                  </div>
+                </div>
+              </>
+            ) : (
+              groupShares.length === 0 && (
+                <p style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
+                  Create a group in Settings to share with several people at once.
+                </p>
+              )
+            )}
+          </div>
+
           <div
             style={{
               marginTop: '1.5rem',
@@ -373,6 +652,8 @@ export function ShareInventoryModal({
             </p>
           </div>
 
+          <ShareLinksSection inventoryId={inventoryId} />
+
           {/* Transfer Ownership Section */}
           <div
             style={{
@@ -0,0 +1,256 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import type { ShareLink } from '@/types';
+import { authApi } from '@/services/api';
+
+interface ShareLinksSectionProps {
+  inventoryId: number;
+}
+
+const EXPIRY_OPTIONS = [
+  { value: '', label: 'Never' },
+  { value: '1', label: '1 day' },
+  { value: '7', label: '7 days' },
+  { value: '30', label: '30 days' },
+  { value: '90', label: '90 days' },
+];
+
+export function ShareLinksSection({ inventoryId }: ShareLinksSectionProps) {
+  const { showToast } = useApp();
+  const [links, setLinks] = useState<ShareLink[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [creating, setCreating] = useState(false);
+  const [showForm, setShowForm] = useState(false);
+  const [linkUrl, setLinkUrl] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [password, setPassword] = useState('');
+  const [expiresInDays, setExpiresInDays] = useState('');
+
+  const loadLinks = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await authApi.getShareLinks(inventoryId);
+      if (result.success && result.data) {
+        setLinks(result.data);
+      }
+    } catch (error) {
+      console.error('Error loading share links:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, [inventoryId]);
+
+  useEffect(() => {
+    void loadLinks();
+  }, [loadLinks]);
+
+  const handleCreate = async () => {
+    if (!name.trim()) {
+      showToast('Please give the link a name', 'error');
+      return;
+    }
+
+    setCreating(true);
+    try {
+      const result = await authApi.createShareLink(inventoryId, {
+        name: name.trim(),
+        password: password || undefined,
+        expires_in_days: expiresInDays ? Number(expiresInDays) : undefined,
+      });
+      if (result.success && result.data) {
+        setLinkUrl(`${window.location.origin}${result.data.share_path}`);
+        setName('');
+        setPassword('');
+        setExpiresInDays('');
+        setShowForm(false);
+        void loadLinks();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to create share link', 'error');
+      }
+    } catch {
+      showToast('Failed to create share link', 'error');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleRevoke = async (link: ShareLink) => {
+    try {
+      const result = await authApi.revokeShareLink(link.id);
+      if (result.success) {
+        showToast('Share link revoked', 'success');
+        void loadLinks();
+      } else {
+        showToast(result.error ?? 'Failed to revoke share link', 'error');
+      }
+    } catch {
+      showToast('Failed to revoke share link', 'error');
+    }
+  };
+
+  const handleCopy = async () => {
+    if (!linkUrl) {
+      return;
+    }
+    try {
+      await navigator.clipboard.writeText(linkUrl);
+      showToast('Share link copied to clipboard', 'success');
+    } catch {
+      showToast('Failed to copy to clipboard', 'error');
+    }
+  };
+
+  return (
+    <div
+      className="share-links-section"
+      style={{
+        marginTop: '2rem',
+        borderTop: '1px solid var(--border-color)',
+        paddingTop: '1.5rem',
+      }}
+    >
+      <h3 style={{ fontSize: '1rem', marginBottom: '0.5rem' }}>🔗 Public Links</h3>
+      <p style={{ fontSize: '0.875rem', color: 'var(--text-secondary)', marginBottom: '1rem' }}>
+        Anyone with a link can view this inventory read-only, without an account.
+      </p>
+
+      {linkUrl && (
+        <div
+          style={{
+            padding: '1rem',
+            marginBottom: '1rem',
+            background: 'var(--warning-bg, #fff3cd)',
+            border: '1px solid var(--warning-border, #ffeeba)',
+            borderRadius: '8px',
+          }}
+        >
+          <p style={{ marginTop: 0 }}>
+            <strong>Copy this link now.</strong> It will not be shown again.
+          </p>
+          <code style={{ wordBreak: 'break-all' }}>{linkUrl}</code>
+          <div style={{ marginTop: '0.75rem', display: 'flex', gap: '0.5rem' }}>
+            <button className="btn btn-secondary btn-sm" onClick={() => void handleCopy()}>
+              <i className="fas fa-copy"></i> Copy
+            </button>
+            <button className="btn btn-secondary btn-sm" onClick={() => setLinkUrl(null)}>
+              Done
+            </button>
+          </div>
+        </div>
+      )}
+
+      {loading && links.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>Loading links...</p>
+      ) : links.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>No public links.</p>
+      ) : (
+        <div style={{ display: 'flex', flexDirection: 'column', gap: '0.75rem' }}>
+          {links.map((link) => (
+            <div
+              key={link.id}
+              className="card"
+              style={{
+                padding: '1rem',
+                display: 'flex',
+                justifyContent: 'space-between',
+                alignItems: 'center',
+              }}
+            >
+              <div style={{ flex: 1 }}>
+                <div style={{ fontWeight: '500', marginBottom: '0.25rem' }}>
+                  {link.name} {link.password_protected && <span title="Password protected">🔒</span>}
+                </div>
+                <div style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
+                  <code>{link.token_prefix}…</code> · Opened {link.access_count} time
+                  {link.access_count === 1 ? '' : 's'}
+                  {link.last_accessed_at &&
+                    `, last ${new Date(link.last_accessed_at).toLocaleString()}`}
+                  {' · '}
+                  {link.expires_at
+                    ? `Expires ${new Date(link.expires_at).toLocaleDateString()}`
+                    : 'Never expires'}
+                </div>
+              </div>
+              <button
+                className="btn btn-icon"
+                onClick={() => void handleRevoke(link)}
+                title="Revoke link"
+              >
+                🗑️
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {!showForm ? (
+        <button
+          className="btn btn-secondary"
+          onClick={() => setShowForm(true)}
+          style={{ marginTop: '1rem' }}
+        >
+          <span>🔗</span> Create Public Link
+        </button>
+      ) : (
+        <div className="card" style={{ marginTop: '1rem', padding: '1rem' }}>
+          <div className="form-group">
+            <label className="form-label" htmlFor="share-link-name">
+              Name
+            </label>
+            <input
+              type="text"
+              className="form-input"
+              id="share-link-name"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder="e.g. Insurance adjuster"
+              maxLength={100}
+            />
+          </div>
+          <div className="form-group">
+            <label className="form-label" htmlFor="share-link-password">
+              Password (optional)
+            </label>
+            <input
+              type="password"
+              className="form-input"
+              id="share-link-password"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              autoComplete="new-password"
+            />
+          </div>
+          <div className="form-group">
+            <label className="form-label" htmlFor="share-link-expiry">
+              Expires
+            </label>
+            <select
+              className="form-select"
+              id="share-link-expiry"
+              value={expiresInDays}
+              onChange={(e) => setExpiresInDays(e.target.value)}
+            >
+              {EXPIRY_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div style={{ display: 'flex', gap: '0.5rem' }}>
+            <button
+              className="btn btn-primary"
+              onClick={() => void handleCreate()}
+              disabled={creating}
+            >
+              Create Link
+            </button>
+            <button className="btn btn-secondary" onClick={() => setShowForm(false)}>
+              Cancel
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
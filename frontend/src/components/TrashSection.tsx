@@ -0,0 +1,157 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useApp } from '@/context/AppContext';
+import { useAuth } from '@/context/AuthContext';
+import { ConfirmModal } from '@/components';
+import type { TrashEntry, TrashKind } from '@/types';
+import { trashApi } from '@/services/api';
+import { formatDate, type DateFormatType } from '@/utils/dateFormat';
+
+const KIND_LABELS: Record<TrashKind, string> = {
+  inventory: 'Inventory',
+  item: 'Item',
+  organizer_type: 'Organizer',
+};
+
+const KIND_ICONS: Record<TrashKind, string> = {
+  inventory: 'fa-boxes-stacked',
+  item: 'fa-box',
+  organizer_type: 'fa-tags',
+};
+
+function describeEntry(entry: TrashEntry): string {
+  if (entry.kind === 'inventory') {
+    const count = entry.item_count ?? 0;
+    return `${count} item${count === 1 ? '' : 's'}`;
+  }
+  return `in ${entry.inventory_name ?? 'unknown inventory'}`;
+}
+
+export function TrashSection() {
+  const { showToast } = useApp();
+  const { settings } = useAuth();
+  const [entries, setEntries] = useState<TrashEntry[]>([]);
+  const [retentionDays, setRetentionDays] = useState<number | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [entryToPurge, setEntryToPurge] = useState<TrashEntry | null>(null);
+
+  const loadTrash = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await trashApi.getAll();
+      if (result.success && result.data) {
+        setEntries(result.data.entries);
+        setRetentionDays(result.data.retention_days);
+      }
+    } catch (error) {
+      console.error('Error loading trash:', error);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadTrash();
+  }, [loadTrash]);
+
+  const handleRestore = async (entry: TrashEntry) => {
+    try {
+      const result = await trashApi.restore(entry.kind, entry.id);
+      if (result.success) {
+        showToast(result.message ?? 'Restored', 'success');
+        void loadTrash();
+      } else {
+        showToast(result.message ?? result.error ?? 'Failed to restore', 'error');
+      }
+    } catch {
+      showToast('Failed to restore', 'error');
+    }
+  };
+
+  const handlePurge = async () => {
+    if (!entryToPurge) {
+      return;
+    }
+    try {
+      const result = await trashApi.purge(entryToPurge.kind, entryToPurge.id);
+      if (result.success) {
+        showToast('Permanently deleted', 'success');
+        setEntryToPurge(null);
+        void loadTrash();
+      } else {
+        showToast(result.error ?? 'Failed to delete', 'error');
+      }
+    } catch {
+      showToast('Failed to delete', 'error');
+    }
+  };
+
+  return (
+    <div className="trash-section">
+      {retentionDays !== null && (
+        <p style={{ fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+          Entries are deleted for good after {retentionDays} days in the trash.
+        </p>
+      )}
+      {loading && entries.length === 0 ? (
+        <p style={{ textAlign: 'center', padding: '1rem', color: 'var(--text-secondary)' }}>
+          Loading...
+        </p>
+      ) : entries.length === 0 ? (
+        <p style={{ color: 'var(--text-secondary)' }}>The trash is empty.</p>
+      ) : (
+        <ul style={{ listStyle: 'none', margin: 0, padding: 0 }}>
+          {entries.map((entry) => (
+            <li
+              key={`${entry.kind}-${entry.id}`}
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                justifyContent: 'space-between',
+                gap: '1rem',
+                padding: '0.75rem 0',
+                borderBottom: '1px solid var(--border-color)',
+              }}
+            >
+              <div>
+                <i className={`fas ${KIND_ICONS[entry.kind]}`} title={KIND_LABELS[entry.kind]}></i>{' '}
+                <strong>{entry.name}</strong>
+                <span style={{ color: 'var(--text-secondary)' }}> · {describeEntry(entry)}</span>
+                <p style={{ margin: 0, fontSize: '0.85rem', color: 'var(--text-secondary)' }}>
+                  Deleted by {entry.deleted_by_username ?? 'unknown'} on{' '}
+                  {formatDate(
+                    entry.deleted_at,
+                    (settings?.date_format ?? 'MM/DD/YYYY') as DateFormatType
+                  )}
+                </p>
+              </div>
+              <div style={{ display: 'flex', gap: '0.5rem' }}>
+                <button
+                  className="btn btn-secondary btn-sm"
+                  onClick={() => void handleRestore(entry)}
+                >
+                  Restore
+                </button>
+                <button className="btn btn-secondary btn-sm" onClick={() => setEntryToPurge(entry)}>
+                  Delete Forever
+                </button>
+              </div>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <ConfirmModal
+        isOpen={!!entryToPurge}
+        onClose={() => setEntryToPurge(null)}
+        onConfirm={handlePurge}
+        title="Delete Forever"
+        message={
+          entryToPurge?.kind === 'inventory'
+            ? `Permanently delete "${entryToPurge.name}" and everything in it? This can't be undone.`
+            : `Permanently delete "${entryToPurge?.name}"? This can't be undone.`
+        }
+        confirmText="Delete Forever"
+      />
+    </div>
+  );
+}
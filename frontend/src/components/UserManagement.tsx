@@ -3,7 +3,7 @@ import { useApp } from '@/context/AppContext';
 import { useAuth } from '@/context/AuthContext';
 import { authApi } from '@/services/api';
 import { Modal, ConfirmModal } from '@/components';
-import type { User, CreateUserRequest, UpdateUserRequest } from '@/types';
+import type { User, CreateUserRequest, UpdateUserRequest, PasswordResetLink } from '@/types';
 
 export function UserManagement() {
   const { showToast } = useApp();
@@ -15,6 +15,9 @@ export function UserManagement() {
   const [showDeleteModal, setShowDeleteModal] = useState(false);
   const [editingUser, setEditingUser] = useState<User | null>(null);
   const [deletingUser, setDeletingUser] = useState<User | null>(null);
+  const [resetLink, setResetLink] = useState<{ user: User; link: PasswordResetLink } | null>(
+    null
+  );
   const [formData, setFormData] = useState({
     username: '',
     full_name: '',
@@ -163,6 +166,43 @@ export function UserManagement() {
     }
   };
 
+  const handleUnlockUser = async (user: User) => {
+    try {
+      const result = await authApi.unlockUser(user.id);
+      if (result.success) {
+        showToast(result.message ?? `Login lockout cleared for ${user.username}`, 'success');
+      } else {
+        showToast(result.error ?? 'Failed to clear login lockout', 'error');
+      }
+    } catch {
+      showToast('Failed to clear login lockout', 'error');
+    }
+  };
+
+  const handleCreateResetLink = async (user: User) => {
+    try {
+      const result = await authApi.createPasswordResetLink(user.id);
+      if (result.success && result.data) {
+        setResetLink({ user, link: result.data });
+      } else {
+        showToast(result.error ?? 'Failed to create reset link', 'error');
+      }
+    } catch {
+      showToast('Failed to create reset link', 'error');
+    }
+  };
+
+  const resetLinkUrl = resetLink ? `${window.location.origin}${resetLink.link.reset_path}` : '';
+
+  const copyResetLink = async () => {
+    try {
+      await navigator.clipboard.writeText(resetLinkUrl);
+      showToast('Reset link copied to clipboard', 'success');
+    } catch {
+      showToast('Failed to copy to clipboard', 'error');
+    }
+  };
+
   if (loading) {
     return (
       <div style={{ padding: '2rem', textAlign: 'center' }}>
@@ -227,6 +267,21 @@ export function UserManagement() {
               >
                 <i className="fas fa-edit"></i>
               </button>
+              <button
+                className="btn btn-sm btn-ghost"
+                onClick={() => void handleUnlockUser(user)}
+                title="Clear login lockout"
+              >
+                <i className="fas fa-unlock"></i>
+              </button>
+              <button
+                className="btn btn-sm btn-ghost"
+                onClick={() => void handleCreateResetLink(user)}
+                disabled={!user.is_active}
+                title={user.is_active ? 'Create password reset link' : 'Account deactivated'}
+              >
+                <i className="fas fa-link"></i>
+              </button>
               <button
                 className="btn btn-sm btn-ghost btn-danger"
                 onClick={() => openDeleteModal(user)}
@@ -422,6 +477,45 @@ export function UserManagement() {
         </div>
       </Modal>
 
+      {/* Password Reset Link Modal */}
+      <Modal
+        isOpen={resetLink !== null}
+        onClose={() => setResetLink(null)}
+        title="Password Reset Link"
+        subtitle={`For ${resetLink?.user.username}`}
+        footer={
+          <>
+            <button className="btn btn-secondary" onClick={() => setResetLink(null)}>
+              Done
+            </button>
+            <button className="btn btn-primary" onClick={() => void copyResetLink()}>
+              <i className="fas fa-copy"></i>
+              Copy Link
+            </button>
+          </>
+        }
+      >
+        <div className="form-group">
+          <label className="form-label" htmlFor="reset-link-url">
+            Reset Link
+          </label>
+          <input
+            type="text"
+            className="form-input"
+            id="reset-link-url"
+            value={resetLinkUrl}
+            readOnly
+            onFocus={(e) => e.target.select()}
+            style={{ fontFamily: 'monospace' }}
+          />
+          <p className="form-hint">
+            Share this link with the user privately. It works once and expires on{' '}
+            {resetLink && new Date(resetLink.link.expires_at).toLocaleString()}. Creating a new
+            link replaces this one.
+          </p>
+        </div>
+      </Modal>
+
       {/* Delete Confirmation Modal */}
       <ConfirmModal
         isOpen={showDeleteModal}
@@ -10,6 +10,7 @@ export { ChangePasswordModal } from './ChangePasswordModal';
 export { EditProfileModal } from './EditProfileModal';
 export { WarrantyNotificationBanner } from './WarrantyNotificationBanner';
 export { UserManagement } from './UserManagement';
+export { ShareLinksSection } from './ShareLinksSection';
 export { ShareInventoryModal } from './ShareInventoryModal';
 export { AllAccessManagement } from './AllAccessManagement';
 export { RecoveryCodesSection } from './RecoveryCodesSection';
@@ -18,3 +19,13 @@ export { BackupRestoreSection } from './BackupRestoreSection';
 export { InstructionsModal } from './InstructionsModal';
 export { ImageLightbox } from './ImageLightbox';
 export { ImageOrganizerInput } from './ImageOrganizerInput';
+export { ActiveSessionsSection } from './ActiveSessionsSection';
+export { ApiTokensSection } from './ApiTokensSection';
+export { PasskeysSection } from './PasskeysSection';
+export { AuditLogSection } from './AuditLogSection';
+export { InvitationsSection } from './InvitationsSection';
+export { GroupsSection } from './GroupsSection';
+export { RolesSection } from './RolesSection';
+export { ItemHistorySection } from './ItemHistorySection';
+export { TrashSection } from './TrashSection';
+export { PendingInvitationsBanner } from './PendingInvitationsBanner';
@@ -6,7 +6,8 @@ import type {
   DismissedWarranties,
   LoginTotpRequiredResponse,
 } from '@/types';
-import { authApi } from '@/services/api';
+import { authApi, storeSessionTokens, TOKEN_REFRESHED_EVENT } from '@/services/api';
+import { getPasskeyAssertion } from '@/utils/webauthn';
 
 // Storage keys - similar to Humidor
 const TOKEN_KEY = 'home_registry_token';
@@ -28,6 +29,8 @@ interface AuthContextType {
     partialToken: string,
     code: string
   ) => Promise<{ success: boolean; error?: string }>;
+  completeOidcLogin: (code: string) => Promise<{ success: boolean; error?: string }>;
+  completePasskeyLogin: (partialToken?: string) => Promise<{ success: boolean; error?: string }>;
   clearTotpRequired: () => void;
   logout: () => void;
   checkSetupStatus: () => Promise<SetupStatusResponse | null>;
@@ -49,6 +52,15 @@ export function AuthProvider({ children }: { children: ReactNode }) {
   const [needsSetup, setNeedsSetup] = useState<boolean | null>(null);
   const [totpRequired, setTotpRequired] = useState<LoginTotpRequiredResponse | null>(null);
 
+  // Keep the in-memory token in step with silent refreshes done by the API layer
+  useEffect(() => {
+    const onTokenRefreshed = (event: Event) => {
+      setToken((event as CustomEvent<string>).detail);
+    };
+    window.addEventListener(TOKEN_REFRESHED_EVENT, onTokenRefreshed);
+    return () => window.removeEventListener(TOKEN_REFRESHED_EVENT, onTokenRefreshed);
+  }, []);
+
   // Check for existing auth on mount
   useEffect(() => {
     const initAuth = async () => {
@@ -81,8 +93,23 @@ export function AuthProvider({ children }: { children: ReactNode }) {
           logout();
         }
       } else {
-        // Check if setup is needed
-        await checkSetupStatus();
+        // Behind an authenticating proxy the user is already signed in
+        const proxyResult = await authApi.proxySession().catch(() => null);
+        if (proxyResult?.success && proxyResult.data) {
+          storeSessionTokens(proxyResult.data);
+          localStorage.setItem(USER_KEY, JSON.stringify(proxyResult.data.user));
+          setToken(proxyResult.data.token);
+          setUser(proxyResult.data.user);
+          setNeedsSetup(false);
+
+          const settingsResult = await authApi.getSettings(proxyResult.data.token);
+          if (settingsResult.success && settingsResult.data) {
+            setSettings(settingsResult.data);
+          }
+        } else {
+          // Check if setup is needed
+          await checkSetupStatus();
+        }
       }
       setIsLoading(false);
     };
@@ -125,7 +152,7 @@ export function AuthProvider({ children }: { children: ReactNode }) {
           const { token: newToken, user: newUser } = result.data;
 
           // Store auth data
-          localStorage.setItem(TOKEN_KEY, newToken);
+          storeSessionTokens(result.data);
           localStorage.setItem(USER_KEY, JSON.stringify(newUser));
 
           setToken(newToken);
@@ -160,7 +187,7 @@ export function AuthProvider({ children }: { children: ReactNode }) {
           const { token: newToken, user: newUser } = result.data;
 
           // Store auth data
-          localStorage.setItem(TOKEN_KEY, newToken);
+          storeSessionTokens(result.data);
           localStorage.setItem(USER_KEY, JSON.stringify(newUser));
 
           setToken(newToken);
@@ -186,11 +213,99 @@ export function AuthProvider({ children }: { children: ReactNode }) {
     []
   );
 
+  // Sign in with a passkey, or use one in place of the TOTP code when given a partial token
+  const completePasskeyLogin = useCallback(
+    async (partialToken?: string): Promise<{ success: boolean; error?: string }> => {
+      try {
+        const options = partialToken
+          ? await authApi.startPasskeyVerification(partialToken)
+          : await authApi.startPasskeyLogin();
+        if (!options.success || !options.data) {
+          return { success: false, error: options.error ?? 'Passkey sign-in failed' };
+        }
+
+        const credential = await getPasskeyAssertion(options.data);
+        const result = partialToken
+          ? await authApi.finishPasskeyVerification(partialToken, credential)
+          : await authApi.finishPasskeyLogin(credential);
+
+        if (result.success && result.data) {
+          const { token: newToken, user: newUser } = result.data;
+
+          // Store auth data
+          storeSessionTokens(result.data);
+          localStorage.setItem(USER_KEY, JSON.stringify(newUser));
+
+          setToken(newToken);
+          setUser(newUser);
+          setNeedsSetup(false);
+          setTotpRequired(null);
+
+          // Fetch user settings
+          const settingsResult = await authApi.getSettings(newToken);
+          if (settingsResult.success && settingsResult.data) {
+            setSettings(settingsResult.data);
+          }
+
+          return { success: true };
+        } else {
+          return { success: false, error: result.error ?? 'Passkey sign-in failed' };
+        }
+      } catch (error) {
+        // The browser rejects with NotAllowedError when the prompt is dismissed
+        if (error instanceof DOMException && error.name === 'NotAllowedError') {
+          return { success: false, error: 'Passkey sign-in was cancelled' };
+        }
+        console.error('Passkey sign-in error:', error);
+        return { success: false, error: 'Passkey sign-in failed. Please try again.' };
+      }
+    },
+    []
+  );
+
+  const completeOidcLogin = useCallback(
+    async (code: string): Promise<{ success: boolean; error?: string }> => {
+      try {
+        const result = await authApi.oidcExchange(code);
+
+        if (result.success && result.data) {
+          const { token: newToken, user: newUser } = result.data;
+
+          // Store auth data
+          storeSessionTokens(result.data);
+          localStorage.setItem(USER_KEY, JSON.stringify(newUser));
+
+          setToken(newToken);
+          setUser(newUser);
+          setNeedsSetup(false);
+
+          // Fetch user settings
+          const settingsResult = await authApi.getSettings(newToken);
+          if (settingsResult.success && settingsResult.data) {
+            setSettings(settingsResult.data);
+          }
+
+          return { success: true };
+        } else {
+          return { success: false, error: result.error ?? 'Single sign-on failed' };
+        }
+      } catch (error) {
+        console.error('Single sign-on error:', error);
+        return { success: false, error: 'Network error. Please try again.' };
+      }
+    },
+    []
+  );
+
   const clearTotpRequired = useCallback(() => {
     setTotpRequired(null);
   }, []);
 
   const logout = useCallback(() => {
+    // End the session server-side too; local state is cleared regardless
+    if (localStorage.getItem(TOKEN_KEY)) {
+      void authApi.logout().catch(() => undefined);
+    }
     localStorage.removeItem(TOKEN_KEY);
     localStorage.removeItem(USER_KEY);
     sessionStorage.removeItem('home_registry_instructions_dismissed');
@@ -269,17 +384,22 @@ export function AuthProvider({ children }: { children: ReactNode }) {
           warrantyExpiry,
         };
 
-        const result = await authApi.updateSettings(token, {
-          settings_json: {
-            ...settings?.settings_json,
-            dismissedWarranties: dismissed,
+        const result = await authApi.updateSettings(
+          token,
+          {
+            settings_json: {
+              ...settings?.settings_json,
+              dismissedWarranties: dismissed,
+            },
           },
-        });
+          settings?.updated_at
+        );
 
-        if (result.success && result.data) {
+        // On a conflict the response carries the current settings instead
+        if (result.data) {
           setSettings(result.data);
-          return true;
         }
+        return result.success;
       } catch (error) {
         console.error('Error dismissing notification:', error);
       }
@@ -294,17 +414,21 @@ export function AuthProvider({ children }: { children: ReactNode }) {
     }
 
     try {
-      const result = await authApi.updateSettings(token, {
-        settings_json: {
-          ...settings?.settings_json,
-          dismissedWarranties: {},
+      const result = await authApi.updateSettings(
+        token,
+        {
+          settings_json: {
+            ...settings?.settings_json,
+            dismissedWarranties: {},
+          },
         },
-      });
+        settings?.updated_at
+      );
 
-      if (result.success && result.data) {
+      if (result.data) {
         setSettings(result.data);
-        return true;
       }
+      return result.success;
     } catch (error) {
       console.error('Error clearing dismissals:', error);
     }
@@ -323,6 +447,8 @@ export function AuthProvider({ children }: { children: ReactNode }) {
         totpRequired,
         login,
         completeTotpLogin,
+        completeOidcLogin,
+        completePasskeyLogin,
         clearTotpRequired,
         logout,
         checkSetupStatus,
@@ -0,0 +1,259 @@
+import { useState, useEffect } from 'react';
+import { useNavigate, useSearchParams, Link } from 'react-router-dom';
+import { authApi, storeSessionTokens } from '@/services/api';
+import type { Invitation, PermissionLevel } from '@/types';
+import '@/styles/auth.css';
+
+const PERMISSION_LABELS: Record<PermissionLevel, string> = {
+  view: 'view',
+  edit_items: 'edit items in',
+  edit_inventory: 'manage',
+};
+
+export function AcceptInvitationPage() {
+  const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
+  const token = searchParams.get('token') ?? '';
+  const [invitation, setInvitation] = useState<Invitation | null>(null);
+  const [isChecking, setIsChecking] = useState(true);
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [showPassword, setShowPassword] = useState(false);
+
+  const [formData, setFormData] = useState({
+    username: '',
+    full_name: '',
+    password: '',
+    confirmPassword: '',
+  });
+
+  useEffect(() => {
+    const checkInvitation = async () => {
+      if (!token) {
+        setError('This invitation link is incomplete. Ask whoever invited you for a new link.');
+        setIsChecking(false);
+        return;
+      }
+      try {
+        const result = await authApi.previewInvitation(token);
+        if (result.success && result.data) {
+          setInvitation(result.data);
+        } else {
+          setError(result.message ?? result.error ?? 'This invitation is no longer valid.');
+        }
+      } catch {
+        setError('An error occurred. Please try again.');
+      }
+      setIsChecking(false);
+    };
+    void checkInvitation();
+  }, [token]);
+
+  const handleInputChange = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const { name, value } = e.target;
+    setFormData((prev) => ({ ...prev, [name]: value }));
+    setError(null);
+  };
+
+  const validateForm = () => {
+    if (!formData.username.trim()) {
+      setError('Username is required');
+      return false;
+    }
+    if (formData.username.length < 3) {
+      setError('Username must be at least 3 characters');
+      return false;
+    }
+    if (!formData.full_name.trim()) {
+      setError('Full name is required');
+      return false;
+    }
+    if (formData.password.length < 8) {
+      setError('Password must be at least 8 characters');
+      return false;
+    }
+    if (formData.password !== formData.confirmPassword) {
+      setError('Passwords do not match');
+      return false;
+    }
+    return true;
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      const result = await authApi.acceptInvitation({
+        token,
+        username: formData.username,
+        full_name: formData.full_name,
+        password: formData.password,
+      });
+
+      if (result.success && result.data) {
+        storeSessionTokens(result.data);
+        localStorage.setItem('home_registry_user', JSON.stringify(result.data.user));
+        navigate('/');
+        window.location.reload();
+        return;
+      }
+      setError(result.error ?? 'Failed to accept invitation. Please try again.');
+    } catch {
+      setError('Network error. Please check your connection and try again.');
+    }
+
+    setIsLoading(false);
+  };
+
+  return (
+    <div className="auth-page">
+      <div className="auth-background">
+        <div className="auth-gradient-orb auth-gradient-orb-1"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-2"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-3"></div>
+      </div>
+
+      <div className="auth-container">
+        <div className="auth-card auth-card-register">
+          <div className="auth-header">
+            <div className="auth-logo">
+              <img src="/logo_full.png" alt="Home Registry" className="auth-logo-img" />
+            </div>
+            <h1 className="auth-title">You&apos;re Invited</h1>
+            {invitation ? (
+              <p className="auth-subtitle">
+                {invitation.invited_by_username} invited you to Home Registry
+                {invitation.is_admin && ' as an administrator'}.
+                {invitation.inventory_name &&
+                  invitation.permission_level &&
+                  ` You'll be able to ${PERMISSION_LABELS[invitation.permission_level]} "${invitation.inventory_name}".`}
+                {invitation.grant_all_access &&
+                  ` You'll have All Access to ${invitation.invited_by_username}'s inventories.`}
+              </p>
+            ) : (
+              <p className="auth-subtitle">
+                {isChecking ? 'Checking your invitation...' : 'Create your account'}
+              </p>
+            )}
+          </div>
+
+          {error && (
+            <div className="auth-error" role="alert">
+              <i className="fas fa-exclamation-circle"></i>
+              <span>{error}</span>
+            </div>
+          )}
+
+          {invitation && (
+            <form onSubmit={handleSubmit} className="auth-form">
+              <div className="form-group">
+                <label htmlFor="username">
+                  <i className="fas fa-user"></i>
+                  Username
+                </label>
+                <input
+                  type="text"
+                  id="username"
+                  name="username"
+                  value={formData.username}
+                  onChange={handleInputChange}
+                  placeholder="Choose a username"
+                  autoFocus
+                  autoComplete="username"
+                />
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="full_name">
+                  <i className="fas fa-id-card"></i>
+                  Full Name
+                </label>
+                <input
+                  type="text"
+                  id="full_name"
+                  name="full_name"
+                  value={formData.full_name}
+                  onChange={handleInputChange}
+                  placeholder="Enter your full name"
+                  autoComplete="name"
+                />
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="password">
+                  <i className="fas fa-lock"></i>
+                  Password
+                </label>
+                <div className="input-with-icon">
+                  <input
+                    type={showPassword ? 'text' : 'password'}
+                    id="password"
+                    name="password"
+                    value={formData.password}
+                    onChange={handleInputChange}
+                    placeholder="Choose a password"
+                    autoComplete="new-password"
+                  />
+                  <button
+                    type="button"
+                    className="password-toggle"
+                    onClick={() => setShowPassword(!showPassword)}
+                    tabIndex={-1}
+                  >
+                    <i className={`fas ${showPassword ? 'fa-eye-slash' : 'fa-eye'}`}></i>
+                  </button>
+                </div>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="confirmPassword">
+                  <i className="fas fa-lock"></i>
+                  Confirm Password
+                </label>
+                <input
+                  type={showPassword ? 'text' : 'password'}
+                  id="confirmPassword"
+                  name="confirmPassword"
+                  value={formData.confirmPassword}
+                  onChange={handleInputChange}
+                  placeholder="Confirm your password"
+                  autoComplete="new-password"
+                />
+              </div>
+
+              <button type="submit" className="auth-submit-btn" disabled={isLoading}>
+                {isLoading ? (
+                  <>
+                    <span className="btn-spinner"></span>
+                    Creating Account...
+                  </>
+                ) : (
+                  <>
+                    <i className="fas fa-user-plus"></i>
+                    Accept Invitation
+                  </>
+                )}
+              </button>
+            </form>
+          )}
+
+          <div className="auth-footer">
+            <p>
+              Already have an account?{' '}
+              <Link to="/login" className="auth-link">
+                Sign in
+              </Link>
+            </p>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,13 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { Header, LoadingState, EmptyState, Modal, ConfirmModal } from '@/components';
+import {
+  Header,
+  LoadingState,
+  EmptyState,
+  Modal,
+  ConfirmModal,
+  PendingInvitationsBanner,
+} from '@/components';
 import { inventoryApi } from '@/services/api';
 import { useApp } from '@/context/AppContext';
 import { useAuth } from '@/context/AuthContext';
@@ -145,14 +152,18 @@ export function InventoriesPage() {
     }
 
     try {
-      const result = await inventoryApi.update(editingInventory.id, formData);
+      const result = await inventoryApi.update(
+        editingInventory.id,
+        formData,
+        editingInventory.updated_at
+      );
       if (result.success) {
         showToast('Inventory updated successfully!', 'success');
         setShowEditModal(false);
         resetForm();
         void loadInventories();
       } else {
-        showToast(result.error ?? 'Failed to update inventory', 'error');
+        showToast(result.message ?? result.error ?? 'Failed to update inventory', 'error');
       }
     } catch {
       showToast('Failed to update inventory', 'error');
@@ -167,7 +178,7 @@ export function InventoriesPage() {
     try {
       const result = await inventoryApi.delete(deletingInventory.id);
       if (result.success) {
-        showToast('Inventory deleted successfully!', 'success');
+        showToast('Inventory moved to the trash', 'success');
         void loadInventories();
       } else {
         showToast(result.error ?? 'Failed to delete inventory', 'error');
@@ -327,6 +338,8 @@ export function InventoriesPage() {
             )}
           </div>
 
+          <PendingInvitationsBanner onAccepted={() => void loadInventories()} />
+
           {loading ? (
             <LoadingState message="Loading inventories..." />
           ) : inventories.length === 0 ? (
@@ -759,7 +772,7 @@ export function InventoriesPage() {
         }}
         onConfirm={handleDeleteInventory}
         title="Delete Inventory"
-        message={`Are you sure you want to delete "${deletingInventory?.name}"? This action cannot be undone.`}
+        message={`Move "${deletingInventory?.name}" and everything in it to the trash? You can restore it from Settings.`}
         confirmText="Delete"
         confirmButtonClass="btn-danger"
         icon="fas fa-trash"
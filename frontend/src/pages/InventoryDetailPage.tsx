@@ -9,8 +9,9 @@ import {
   ShareInventoryModal,
   ImageLightbox,
   ImageOrganizerInput,
+  ItemHistorySection,
 } from '@/components';
-import { inventoryApi, itemApi, organizerApi, imageApi } from '@/services/api';
+import { inventoryApi, itemApi, organizerApi, imageApi, authApi } from '@/services/api';
 import { useApp } from '@/context/AppContext';
 import { useAuth } from '@/context/AuthContext';
 import { formatDate, type DateFormatType } from '@/utils/dateFormat';
@@ -19,6 +20,7 @@ import { getNotificationMessage } from '@/utils/notifications';
 import type {
   Inventory,
   Item,
+  ItemVisibility,
   CreateItemRequest,
   UpdateItemRequest,
   OrganizerTypeWithOptions,
@@ -26,6 +28,13 @@ import type {
   ItemOrganizerValueWithDetails,
 } from '@/types';
 
+const VISIBILITY_OPTIONS: { value: ItemVisibility; label: string }[] = [
+  { value: 'everyone', label: 'Everyone with access' },
+  { value: 'edit_items', label: 'People who can edit items' },
+  { value: 'edit_inventory', label: 'People who can edit the inventory' },
+  { value: 'owner', label: 'Owner only' },
+];
+
 export function InventoryDetailPage() {
   const { id } = useParams<{ id: string }>();
   const navigate = useNavigate();
@@ -49,6 +58,9 @@ export function InventoryDetailPage() {
   const [showEditItemModal, setShowEditItemModal] = useState(false);
   const [editingItem, setEditingItem] = useState<Item | null>(null);
   const [editItemData, setEditItemData] = useState<UpdateItemRequest>({});
+  const [editVisibility, setEditVisibility] = useState<ItemVisibility>('everyone');
+  // Only owners and full All Access users can hide items from other people
+  const [canManageSharing, setCanManageSharing] = useState(false);
   const [editOrganizerValues, setEditOrganizerValues] = useState<
     Record<string, { optionId?: number; textValue?: string }>
   >({});
@@ -154,6 +166,16 @@ export function InventoryDetailPage() {
     }
   }, [id, loadInventoryDetail]);
 
+  useEffect(() => {
+    if (!id) {
+      return;
+    }
+    authApi
+      .getInventoryPermissions(parseInt(id, 10))
+      .then((result) => setCanManageSharing(!!result.data?.can_manage_sharing))
+      .catch(() => setCanManageSharing(false));
+  }, [id]);
+
   // Enhancement 1: Auto-open item details modal if navigated from notification
   // RECOMMENDED FIX: Extract primitive value to prevent unnecessary re-runs
   const openItemId = (location.state as { openItemId?: number } | null)?.openItemId;
@@ -264,6 +286,7 @@ export function InventoryDetailPage() {
       notes: item.notes ?? '',
       quantity: item.quantity ?? 1,
     });
+    setEditVisibility(item.visibility ?? 'everyone');
     setEditOrganizerValues({});
     if (item.id) {
       try {
@@ -308,7 +331,7 @@ export function InventoryDetailPage() {
     }
 
     try {
-      const result = await itemApi.update(editingItem.id, editItemData);
+      const result = await itemApi.update(editingItem.id, editItemData, editingItem.updated_at);
       if (result.success) {
         // Save organizer values
         const valuesToSave: SetItemOrganizerValueRequest[] = [];
@@ -325,13 +348,16 @@ export function InventoryDetailPage() {
         if (valuesToSave.length > 0) {
           await itemApi.setOrganizerValues(editingItem.id, { values: valuesToSave });
         }
+        if (canManageSharing && editVisibility !== (editingItem.visibility ?? 'everyone')) {
+          await itemApi.updateVisibility(editingItem.id, editVisibility);
+        }
 
         showToast('Item updated successfully!', 'success');
         setShowEditItemModal(false);
         setEditingItem(null);
         void loadInventoryDetail(parseInt(id ?? '0', 10));
       } else {
-        showToast(result.error ?? 'Failed to update item', 'error');
+        showToast(result.message ?? result.error ?? 'Failed to update item', 'error');
       }
     } catch {
       showToast('Failed to update item', 'error');
@@ -352,7 +378,7 @@ export function InventoryDetailPage() {
     try {
       const result = await itemApi.delete(deletingItem.id);
       if (result.success) {
-        showToast('Item deleted successfully!', 'success');
+        showToast('Item moved to the trash', 'success');
         void loadInventoryDetail(parseInt(id ?? '0', 10));
       } else {
         showToast(result.error ?? 'Failed to delete item', 'error');
@@ -519,7 +545,20 @@ export function InventoryDetailPage() {
                         </div>
                       )}
                       <div className="item-card-header">
-                        <h3 className="item-card-title">{item.name}</h3>
+                        <h3 className="item-card-title">
+                          {item.name}
+                          {item.visibility && item.visibility !== 'everyone' && (
+                            <i
+                              className="fas fa-lock"
+                              title="Hidden from some people this inventory is shared with"
+                              style={{
+                                marginLeft: '0.5rem',
+                                fontSize: '0.8em',
+                                color: 'var(--text-secondary)',
+                              }}
+                            ></i>
+                          )}
+                        </h3>
                         {item.category && (
                           <span className="item-card-category">{item.category}</span>
                         )}
@@ -1040,6 +1079,15 @@ export function InventoryDetailPage() {
                 </div>
               )}
             </div>
+            {viewingItem.id && (
+              <ItemHistorySection
+                itemId={viewingItem.id}
+                onReverted={(item) => {
+                  void handleViewItem(item);
+                  void loadInventoryDetail(parseInt(id ?? '0', 10));
+                }}
+              />
+            )}
           </div>
         )}
       </Modal>
@@ -1247,6 +1295,26 @@ export function InventoryDetailPage() {
             onChange={(e) => setEditItemData({ ...editItemData, notes: e.target.value })}
           />
         </div>
+
+        {canManageSharing && (
+          <div className="form-group">
+            <label className="form-label" htmlFor="edit-item-visibility">
+              Visible To
+            </label>
+            <select
+              className="form-select"
+              id="edit-item-visibility"
+              value={editVisibility}
+              onChange={(e) => setEditVisibility(e.target.value as ItemVisibility)}
+            >
+              {VISIBILITY_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
       </Modal>
 
       {/* Delete Confirmation Modal (Issue 3) */}
@@ -1258,7 +1326,7 @@ export function InventoryDetailPage() {
         }}
         onConfirm={handleDeleteItem}
         title="Delete Item"
-        message={`Are you sure you want to delete "${deletingItem?.name}"? This action cannot be undone.`}
+        message={`Move "${deletingItem?.name}" to the trash? You can restore it from Settings.`}
         confirmText="Delete"
         confirmButtonClass="btn-danger"
         icon="fas fa-trash"
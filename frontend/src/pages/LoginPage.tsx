@@ -1,13 +1,25 @@
-import { useState } from 'react';
-import { useNavigate, Link } from 'react-router-dom';
+import { useState, useEffect } from 'react';
+import { useNavigate, useSearchParams, Link } from 'react-router-dom';
 import { useAuth } from '@/context/AuthContext';
+import { authApi } from '@/services/api';
+import { isWebauthnSupported } from '@/utils/webauthn';
 import '@/styles/auth.css';
 
 export function LoginPage() {
   const navigate = useNavigate();
-  const { login, completeTotpLogin, totpRequired, clearTotpRequired } = useAuth();
+  const [searchParams, setSearchParams] = useSearchParams();
+  const {
+    login,
+    completeTotpLogin,
+    completeOidcLogin,
+    completePasskeyLogin,
+    totpRequired,
+    clearTotpRequired,
+  } = useAuth();
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [ssoProvider, setSsoProvider] = useState<string | null>(null);
+  const [passkeysEnabled, setPasskeysEnabled] = useState(false);
   const [showPassword, setShowPassword] = useState(false);
   const [totpCode, setTotpCode] = useState('');
 
@@ -16,6 +28,47 @@ export function LoginPage() {
     password: '',
   });
 
+  // Offer single sign-on when the server has it configured
+  useEffect(() => {
+    void authApi.getOidcStatus().then((result) => {
+      if (result.success && result.data?.enabled) {
+        setSsoProvider(result.data.provider_name ?? 'Single Sign-On');
+      }
+    });
+    if (isWebauthnSupported()) {
+      void authApi.getWebauthnStatus().then((result) => {
+        setPasskeysEnabled(result.success && result.data?.enabled === true);
+      });
+    }
+  }, []);
+
+  // Finish a single sign-on that redirected back here
+  useEffect(() => {
+    const oidcCode = searchParams.get('oidc_code');
+    const oidcError = searchParams.get('oidc_error');
+    if (!oidcCode && !oidcError) {
+      return;
+    }
+    setSearchParams({}, { replace: true });
+
+    if (oidcError) {
+      setError(oidcError);
+      return;
+    }
+    if (oidcCode) {
+      setIsLoading(true);
+      void completeOidcLogin(oidcCode).then((result) => {
+        setIsLoading(false);
+        if (result.success) {
+          navigate('/');
+        } else {
+          setError(result.error ?? 'Single sign-on failed');
+        }
+      });
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
   const handleInputChange = (e: React.ChangeEvent<HTMLInputElement>) => {
     const { name, value } = e.target;
     setFormData((prev) => ({ ...prev, [name]: value }));
@@ -86,6 +139,21 @@ export function LoginPage() {
     setIsLoading(false);
   };
 
+  const handlePasskeyLogin = async () => {
+    setIsLoading(true);
+    setError(null);
+
+    const result = await completePasskeyLogin(totpRequired?.partial_token);
+
+    if (result.success) {
+      navigate('/');
+    } else {
+      setError(result.error ?? 'Passkey sign-in failed');
+    }
+
+    setIsLoading(false);
+  };
+
   const handleBackToLogin = () => {
     clearTotpRequired();
     setTotpCode('');
@@ -164,6 +232,18 @@ export function LoginPage() {
               </button>
             </form>
 
+            {totpRequired.webauthn_available && isWebauthnSupported() && (
+              <button
+                type="button"
+                className="btn btn-secondary"
+                onClick={() => void handlePasskeyLogin()}
+                disabled={isLoading}
+                style={{ width: '100%', marginTop: '1rem', justifyContent: 'center' }}
+              >
+                <i className="fas fa-fingerprint"></i> Use a passkey instead
+              </button>
+            )}
+
             <div className="auth-footer">
               <p>
                 <button
@@ -271,6 +351,28 @@ export function LoginPage() {
             </button>
           </form>
 
+          {passkeysEnabled && (
+            <button
+              type="button"
+              className="btn btn-secondary"
+              onClick={() => void handlePasskeyLogin()}
+              disabled={isLoading}
+              style={{ width: '100%', marginTop: '1rem', justifyContent: 'center' }}
+            >
+              <i className="fas fa-fingerprint"></i> Sign in with a passkey
+            </button>
+          )}
+
+          {ssoProvider && (
+            <a
+              href="/api/auth/oidc/login"
+              className="btn btn-secondary"
+              style={{ width: '100%', marginTop: '1rem', justifyContent: 'center' }}
+            >
+              <i className="fas fa-key"></i> Sign in with {ssoProvider}
+            </a>
+          )}
+
           <div className="auth-footer">
             <p>
               Don&apos;t have an account?{' '}
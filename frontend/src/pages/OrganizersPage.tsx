@@ -133,16 +133,20 @@ export function OrganizersPage() {
     try {
       if (editingType?.id) {
         // Update existing type
-        const res = await organizerApi.updateType(editingType.id, {
-          name: typeName,
-          input_type: typeInputType,
-          is_required: typeIsRequired,
-        });
+        const res = await organizerApi.updateType(
+          editingType.id,
+          {
+            name: typeName,
+            input_type: typeInputType,
+            is_required: typeIsRequired,
+          },
+          editingType.updated_at
+        );
         if (res.success) {
           showToast('Organizer updated successfully', 'success');
           void loadData();
         } else {
-          showToast(res.error ?? 'Failed to update organizer', 'error');
+          showToast(res.message ?? res.error ?? 'Failed to update organizer', 'error');
         }
       } else {
         // Create new type
@@ -178,12 +182,16 @@ export function OrganizersPage() {
     try {
       if (editingOption?.id) {
         // Update existing option
-        const res = await organizerApi.updateOption(editingOption.id, { name: optionName });
+        const res = await organizerApi.updateOption(
+          editingOption.id,
+          { name: optionName },
+          editingOption.updated_at
+        );
         if (res.success) {
           showToast('Option updated successfully', 'success');
           void loadData();
         } else {
-          showToast(res.error ?? 'Failed to update option', 'error');
+          showToast(res.message ?? res.error ?? 'Failed to update option', 'error');
         }
       } else {
         // Create new option
@@ -216,7 +224,7 @@ export function OrganizersPage() {
     try {
       const res = await organizerApi.deleteType(typeToDelete.id);
       if (res.success) {
-        showToast('Organizer deleted successfully', 'success');
+        showToast('Organizer moved to the trash', 'success');
         void loadData();
       } else {
         showToast(res.error ?? 'Failed to delete organizer', 'error');
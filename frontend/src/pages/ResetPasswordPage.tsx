@@ -0,0 +1,212 @@
+import { useState } from 'react';
+import { useNavigate, useSearchParams, Link } from 'react-router-dom';
+import { authApi } from '@/services/api';
+import '@/styles/auth.css';
+
+export function ResetPasswordPage() {
+  const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
+  const token = searchParams.get('token') ?? '';
+  const [step, setStep] = useState<'input' | 'success'>('input');
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(
+    token ? null : 'This reset link is incomplete. Ask an administrator for a new link.'
+  );
+  const [showPassword, setShowPassword] = useState(false);
+
+  const [formData, setFormData] = useState({
+    newPassword: '',
+    confirmPassword: '',
+  });
+
+  const handleInputChange = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const { name, value } = e.target;
+    setFormData((prev) => ({ ...prev, [name]: value }));
+    setError(null);
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!token) {
+      setError('This reset link is incomplete. Ask an administrator for a new link.');
+      return;
+    }
+
+    if (!formData.newPassword) {
+      setError('Please enter a new password');
+      return;
+    }
+
+    if (formData.newPassword.length < 8) {
+      setError('Password must be at least 8 characters long');
+      return;
+    }
+
+    if (formData.newPassword !== formData.confirmPassword) {
+      setError('Passwords do not match');
+      return;
+    }
+
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      const result = await authApi.redeemPasswordReset(token, formData.newPassword);
+
+      if (result.success) {
+        setStep('success');
+      } else {
+        setError(result.message ?? result.error ?? 'Failed to reset password.');
+      }
+    } catch {
+      setError('An error occurred. Please try again.');
+    }
+
+    setIsLoading(false);
+  };
+
+  if (step === 'success') {
+    return (
+      <div className="auth-page">
+        <div className="auth-background">
+          <div className="auth-gradient-orb auth-gradient-orb-1"></div>
+          <div className="auth-gradient-orb auth-gradient-orb-2"></div>
+          <div className="auth-gradient-orb auth-gradient-orb-3"></div>
+        </div>
+
+        <div className="auth-container">
+          <div className="auth-card">
+            <div className="auth-header">
+              <div className="auth-logo">
+                <img src="/logo_full.png" alt="Home Registry" className="auth-logo-img" />
+              </div>
+              <div
+                style={{
+                  width: '60px',
+                  height: '60px',
+                  borderRadius: '50%',
+                  background: 'var(--success-bg, #d4edda)',
+                  display: 'flex',
+                  alignItems: 'center',
+                  justifyContent: 'center',
+                  margin: '1rem auto',
+                }}
+              >
+                <i
+                  className="fas fa-check"
+                  style={{ fontSize: '24px', color: 'var(--success, #28a745)' }}
+                ></i>
+              </div>
+              <h1 className="auth-title">Password Reset!</h1>
+              <p className="auth-subtitle">
+                Your password has been changed and any existing sessions were signed out.
+              </p>
+            </div>
+
+            <button onClick={() => navigate('/login')} className="auth-submit-btn">
+              <i className="fas fa-sign-in-alt"></i>
+              Go to Login
+            </button>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="auth-page">
+      <div className="auth-background">
+        <div className="auth-gradient-orb auth-gradient-orb-1"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-2"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-3"></div>
+      </div>
+
+      <div className="auth-container">
+        <div className="auth-card">
+          <div className="auth-header">
+            <div className="auth-logo">
+              <img src="/logo_full.png" alt="Home Registry" className="auth-logo-img" />
+            </div>
+            <h1 className="auth-title">Reset Password</h1>
+            <p className="auth-subtitle">Choose a new password for your account</p>
+          </div>
+
+          {error && (
+            <div className="auth-error" role="alert">
+              <i className="fas fa-exclamation-circle"></i>
+              <span>{error}</span>
+            </div>
+          )}
+
+          <form onSubmit={handleSubmit} className="auth-form">
+            <div className="form-group">
+              <label htmlFor="newPassword">
+                <i className="fas fa-lock"></i>
+                New Password
+              </label>
+              <div className="input-with-icon">
+                <input
+                  type={showPassword ? 'text' : 'password'}
+                  id="newPassword"
+                  name="newPassword"
+                  value={formData.newPassword}
+                  onChange={handleInputChange}
+                  placeholder="Enter new password"
+                  autoFocus
+                  autoComplete="new-password"
+                />
+                <button
+                  type="button"
+                  className="password-toggle"
+                  onClick={() => setShowPassword(!showPassword)}
+                  tabIndex={-1}
+                >
+                  <i className={`fas ${showPassword ? 'fa-eye-slash' : 'fa-eye'}`}></i>
+                </button>
+              </div>
+            </div>
+
+            <div className="form-group">
+              <label htmlFor="confirmPassword">
+                <i className="fas fa-lock"></i>
+                Confirm New Password
+              </label>
+              <input
+                type={showPassword ? 'text' : 'password'}
+                id="confirmPassword"
+                name="confirmPassword"
+                value={formData.confirmPassword}
+                onChange={handleInputChange}
+                placeholder="Confirm new password"
+                autoComplete="new-password"
+              />
+            </div>
+
+            <button type="submit" className="auth-submit-btn" disabled={isLoading || !token}>
+              {isLoading ? (
+                <>
+                  <span className="btn-spinner"></span>
+                  Resetting Password...
+                </>
+              ) : (
+                <>
+                  <i className="fas fa-key"></i>
+                  Reset Password
+                </>
+              )}
+            </button>
+          </form>
+
+          <div className="auth-footer">
+            <p>
+              <Link to="/login" className="auth-link">
+                Back to Login
+              </Link>
+            </p>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -6,6 +6,14 @@ import {
   RecoveryCodesSection,
   TotpSettings,
   BackupRestoreSection,
+  ActiveSessionsSection,
+  ApiTokensSection,
+  PasskeysSection,
+  AuditLogSection,
+  InvitationsSection,
+  GroupsSection,
+  RolesSection,
+  TrashSection,
 } from '@/components';
 import { useApp } from '@/context/AppContext';
 import { useAuth } from '@/context/AuthContext';
@@ -291,6 +299,126 @@ export function SettingsPage() {
             <TotpSettings />
           </section>
 
+          {/* Passkeys */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-fingerprint"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Passkeys</h2>
+                <p className="settings-section-description">
+                  Sign in with Touch ID, Windows Hello, your phone or a security key
+                </p>
+              </div>
+            </div>
+
+            <PasskeysSection />
+          </section>
+
+          {/* Active Sessions */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-laptop"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Active Sessions</h2>
+                <p className="settings-section-description">
+                  Devices currently signed in to your account
+                </p>
+              </div>
+            </div>
+
+            <ActiveSessionsSection />
+          </section>
+
+          {/* Personal API Tokens */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-key"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">API Tokens</h2>
+                <p className="settings-section-description">
+                  Tokens for scripts and automations such as Home Assistant
+                </p>
+              </div>
+            </div>
+
+            <ApiTokensSection />
+          </section>
+
+          {/* Household Groups */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-users"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Groups</h2>
+                <p className="settings-section-description">
+                  Group people together so inventories can be shared with all of them at once
+                </p>
+              </div>
+            </div>
+
+            <GroupsSection />
+          </section>
+
+          {/* Custom Roles */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-user-tag"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Roles</h2>
+                <p className="settings-section-description">
+                  Pick exactly what people can do in the inventories you share with them
+                </p>
+              </div>
+            </div>
+
+            <RolesSection />
+          </section>
+
+          {/* Trash */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-trash-can"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Trash</h2>
+                <p className="settings-section-description">
+                  Restore deleted inventories, items and organizers, or delete them for good
+                </p>
+              </div>
+            </div>
+
+            <TrashSection />
+          </section>
+
+          {/* Invitations */}
+          <section className="settings-section">
+            <div className="settings-section-header">
+              <div className="settings-section-icon">
+                <i className="fas fa-envelope-open-text"></i>
+              </div>
+              <div>
+                <h2 className="settings-section-title">Invitations</h2>
+                <p className="settings-section-description">
+                  Invite someone to create their own account, optionally with access to your
+                  inventories
+                </p>
+              </div>
+            </div>
+
+            <InvitationsSection />
+          </section>
+
           {/* Backup & Restore (Admin Only) */}
           {user?.is_admin && (
             <section className="settings-section">
@@ -329,6 +457,26 @@ export function SettingsPage() {
             </section>
           )}
 
+          {/* Audit Log (Admin Only) */}
+          {user?.is_admin && (
+            <section className="settings-section">
+              <div className="settings-section-header">
+                <div className="settings-section-icon">
+                  <i className="fas fa-clipboard-list"></i>
+                </div>
+                <div>
+                  <h2 className="settings-section-title">Audit Log</h2>
+                  <p className="settings-section-description">
+                    Sign-ins, account changes, sharing and backups, with who did them and from
+                    where
+                  </p>
+                </div>
+              </div>
+
+              <AuditLogSection />
+            </section>
+          )}
+
           {/* All Access Management */}
           <section className="settings-section">
             <div className="settings-section-header">
@@ -1,6 +1,6 @@
 import { useState } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { authApi } from '@/services/api';
+import { authApi, storeSessionTokens } from '@/services/api';
 import { useApp } from '@/context/AppContext';
 import '@/styles/auth.css';
 
@@ -90,7 +90,7 @@ export function SetupPage() {
 
       if (result.success && result.data) {
         // Store auth data
-        localStorage.setItem('home_registry_token', result.data.token);
+        storeSessionTokens(result.data);
         localStorage.setItem('home_registry_user', JSON.stringify(result.data.user));
 
         // Generate recovery codes
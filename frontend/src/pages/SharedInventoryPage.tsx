@@ -0,0 +1,207 @@
+import { useState, useEffect, useCallback } from 'react';
+import { useSearchParams } from 'react-router-dom';
+import { authApi } from '@/services/api';
+import type { SharedInventoryView } from '@/types';
+import '@/styles/auth.css';
+
+export function SharedInventoryPage() {
+  const [searchParams] = useSearchParams();
+  const token = searchParams.get('token') ?? '';
+  const [inventory, setInventory] = useState<SharedInventoryView | null>(null);
+  const [isLoading, setIsLoading] = useState(true);
+  const [needsPassword, setNeedsPassword] = useState(false);
+  const [password, setPassword] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const openLink = useCallback(
+    async (linkPassword?: string) => {
+      if (!token) {
+        setError('This link is incomplete. Ask whoever shared it for a new one.');
+        setIsLoading(false);
+        return;
+      }
+      setIsLoading(true);
+      try {
+        const result = await authApi.viewShareLink(token, linkPassword);
+        if (result.success && result.data) {
+          setInventory(result.data);
+          setNeedsPassword(false);
+          setError(null);
+        } else if (result.error === 'Password required') {
+          setNeedsPassword(true);
+        } else if (result.error === 'Incorrect password') {
+          setNeedsPassword(true);
+          setError('Incorrect password');
+        } else {
+          setError(result.message ?? result.error ?? 'This link is no longer valid.');
+        }
+      } catch {
+        setError('An error occurred. Please try again.');
+      }
+      setIsLoading(false);
+    },
+    [token]
+  );
+
+  useEffect(() => {
+    void openLink();
+  }, [openLink]);
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!password) {
+      setError('Please enter the password');
+      return;
+    }
+    void openLink(password);
+  };
+
+  return (
+    <div className="auth-page">
+      <div className="auth-background">
+        <div className="auth-gradient-orb auth-gradient-orb-1"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-2"></div>
+        <div className="auth-gradient-orb auth-gradient-orb-3"></div>
+      </div>
+
+      <div className="auth-container">
+        <div className={inventory ? 'auth-card setup-card wide' : 'auth-card'}>
+          <div className="auth-header">
+            <div className="auth-logo">
+              <img src="/logo_full.png" alt="Home Registry" className="auth-logo-img" />
+            </div>
+            <h1 className="auth-title">{inventory?.name ?? 'Shared Inventory'}</h1>
+            <p className="auth-subtitle">
+              {inventory
+                ? [inventory.location, inventory.description].filter(Boolean).join(' · ') ||
+                  'Read-only view'
+                : isLoading
+                  ? 'Opening shared inventory...'
+                  : needsPassword
+                    ? 'This inventory is password protected'
+                    : 'Read-only view'}
+            </p>
+          </div>
+
+          {error && (
+            <div className="auth-error" role="alert">
+              <i className="fas fa-exclamation-circle"></i>
+              <span>{error}</span>
+            </div>
+          )}
+
+          {needsPassword && !inventory && (
+            <form onSubmit={handleSubmit} className="auth-form">
+              <div className="form-group">
+                <label htmlFor="password">
+                  <i className="fas fa-lock"></i>
+                  Password
+                </label>
+                <input
+                  type="password"
+                  id="password"
+                  name="password"
+                  value={password}
+                  onChange={(e) => {
+                    setPassword(e.target.value);
+                    setError(null);
+                  }}
+                  placeholder="Enter the link password"
+                  autoFocus
+                />
+              </div>
+              <button type="submit" className="auth-submit-btn" disabled={isLoading}>
+                <i className="fas fa-unlock"></i>
+                View Inventory
+              </button>
+            </form>
+          )}
+
+          {inventory && (
+            <div>
+              {inventory.image_url && (
+                <img
+                  src={inventory.image_url}
+                  alt={inventory.name}
+                  style={{
+                    width: '100%',
+                    maxHeight: '240px',
+                    objectFit: 'cover',
+                    borderRadius: '8px',
+                    marginBottom: '1rem',
+                  }}
+                />
+              )}
+              {inventory.items.length === 0 ? (
+                <p style={{ textAlign: 'center', color: 'var(--text-secondary)' }}>
+                  This inventory has no items yet.
+                </p>
+              ) : (
+                <div style={{ display: 'flex', flexDirection: 'column', gap: '0.75rem' }}>
+                  {inventory.items.map((item) => (
+                    <div key={item.id} className="card" style={{ padding: '1rem' }}>
+                      <div style={{ fontWeight: 600 }}>
+                        {item.name}
+                        {item.quantity !== undefined && item.quantity > 1 && ` × ${item.quantity}`}
+                      </div>
+                      <div style={{ fontSize: '0.875rem', color: 'var(--text-secondary)' }}>
+                        {[
+                          item.category,
+                          item.location,
+                          item.purchase_price !== undefined &&
+                            item.purchase_price !== null &&
+                            item.purchase_price.toLocaleString(undefined, {
+                              minimumFractionDigits: 2,
+                              maximumFractionDigits: 2,
+                            }),
+                          item.purchase_date &&
+                            `Purchased ${new Date(item.purchase_date).toLocaleDateString()}`,
+                        ]
+                          .filter(Boolean)
+                          .join(' · ')}
+                      </div>
+                      {item.description && (
+                        <p style={{ margin: '0.5rem 0 0' }}>{item.description}</p>
+                      )}
+                      {item.organizer_values.map((val) => (
+                        <div
+                          key={val.organizer_type_id}
+                          style={{ marginTop: '0.5rem', fontSize: '0.875rem' }}
+                        >
+                          <span style={{ fontWeight: 500 }}>{val.organizer_type_name}: </span>
+                          {val.input_type === 'image' && val.text_value ? (
+                            <img
+                              src={val.text_value}
+                              alt={`${val.organizer_type_name} for ${item.name}`}
+                              className="organizer-image-preview"
+                            />
+                          ) : (
+                            <span style={{ color: 'var(--text-secondary)' }}>
+                              {val.value ?? val.text_value ?? '—'}
+                            </span>
+                          )}
+                        </div>
+                      ))}
+                    </div>
+                  ))}
+                </div>
+              )}
+              {inventory.expires_at && (
+                <p
+                  style={{
+                    marginTop: '1rem',
+                    fontSize: '0.875rem',
+                    color: 'var(--text-secondary)',
+                    textAlign: 'center',
+                  }}
+                >
+                  This link expires {new Date(inventory.expires_at).toLocaleString()}.
+                </p>
+              )}
+            </div>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -7,4 +7,7 @@ export { SetupPage } from './SetupPage';
 export { LoginPage } from './LoginPage';
 export { RegisterPage } from './RegisterPage';
 export { RecoveryPage } from './RecoveryPage';
+export { ResetPasswordPage } from './ResetPasswordPage';
+export { AcceptInvitationPage } from './AcceptInvitationPage';
+export { SharedInventoryPage } from './SharedInventoryPage';
 export { NotificationsPage } from './NotificationsPage';
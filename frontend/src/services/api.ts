@@ -6,6 +6,8 @@ import type {
   UpdateInventoryRequest,
   CreateItemRequest,
   UpdateItemRequest,
+  ItemVisibility,
+  ItemRevision,
   OrganizerTypeWithOptions,
   OrganizerType,
   OrganizerOption,
@@ -23,19 +25,57 @@ import type {
   LoginRequest,
   LoginResponse,
   LoginApiResponse,
+  UserSession,
+  OidcStatusResponse,
+  WebauthnStatusResponse,
+  WebauthnCredential,
+  PublicKeyCredentialCreationOptionsJSON,
+  PublicKeyCredentialRequestOptionsJSON,
+  RegistrationCredentialJSON,
+  AuthenticationCredentialJSON,
+  ApiToken,
+  CreateApiTokenRequest,
+  CreateApiTokenResponse,
   InitialSetupRequest,
   UpdateProfileRequest,
   ChangePasswordRequest,
   UpdateUserSettingsRequest,
   CreateUserRequest,
   UpdateUserRequest,
+  AuditEvent,
+  AuditEventFilters,
+  PasswordResetLink,
+  Invitation,
+  CreateInvitationRequest,
+  CreateInvitationResponse,
+  AcceptInvitationRequest,
+  GroupRole,
+  UserGroup,
+  UserGroupWithMembers,
+  CreateGroupRequest,
+  UpdateGroupRequest,
+  InventoryGroupShare,
+  CreateInventoryGroupShareRequest,
+  InventoryRole,
+  RolesResponse,
+  CreateInventoryRoleRequest,
+  UpdateInventoryRoleRequest,
+  TrashKind,
+  TrashEntry,
+  TrashListing,
+  ShareLink,
+  CreateShareLinkRequest,
+  CreateShareLinkResponse,
+  SharedInventoryView,
   // Sharing types
   InventoryShare,
   CreateInventoryShareRequest,
   UpdateInventoryShareRequest,
+  MyInventoriesResponse,
   UserAccessGrant,
   UserAccessGrantWithUsers,
   CreateUserAccessGrantRequest,
+  UpdateUserAccessGrantRequest,
   EffectivePermissions,
   // Transfer ownership types
   TransferOwnershipRequest,
@@ -56,18 +96,80 @@ import type {
   CategorySummary,
   // Backup types
   BackupInfo,
+  BackupVerificationReport,
+  RemoteBackupStatus,
+  BackupContents,
+  SelectiveRestoreSummary,
   // Image types
   ImageUploadResponse,
 } from '@/types';
 
 const API_BASE = '/api';
 const TOKEN_KEY = 'home_registry_token';
+const REFRESH_TOKEN_KEY = 'home_registry_refresh_token';
+
+// Fired on window after the access token was renewed with the refresh token
+export const TOKEN_REFRESHED_EVENT = 'home_registry_token_refreshed';
 
 // Get auth token from localStorage
 function getToken(): string | null {
   return localStorage.getItem(TOKEN_KEY);
 }
 
+// Store the tokens returned by login, setup, registration or refresh
+export function storeSessionTokens(data: Pick<LoginResponse, 'token' | 'refresh_token'>): void {
+  localStorage.setItem(TOKEN_KEY, data.token);
+  localStorage.setItem(REFRESH_TOKEN_KEY, data.refresh_token);
+}
+
+// Only one refresh may be in flight: the refresh token rotates on every use,
+// so concurrent refreshes with the same token would look like token theft
+let refreshInFlight: Promise<string | null> | null = null;
+
+// Exchange the stored refresh token for a new access token
+function refreshAccessToken(): Promise<string | null> {
+  refreshInFlight ??= (async () => {
+    const refreshToken = localStorage.getItem(REFRESH_TOKEN_KEY);
+    if (!refreshToken) {
+      return null;
+    }
+
+    try {
+      const response = await fetch(`${API_BASE}/auth/refresh`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ refresh_token: refreshToken }),
+      });
+      if (!response.ok) {
+        localStorage.removeItem(REFRESH_TOKEN_KEY);
+        return null;
+      }
+
+      const result = (await response.json()) as ApiResponse<LoginResponse>;
+      if (!result.success || !result.data) {
+        return null;
+      }
+
+      storeSessionTokens(result.data);
+      window.dispatchEvent(new CustomEvent(TOKEN_REFRESHED_EVENT, { detail: result.data.token }));
+      return result.data.token;
+    } catch {
+      return null;
+    } finally {
+      refreshInFlight = null;
+    }
+  })();
+
+  return refreshInFlight;
+}
+
+// Whether the request was authenticated with the stored access token
+function usesStoredToken(options: RequestInit): boolean {
+  const token = getToken();
+  const headers = options.headers as Record<string, string> | undefined;
+  return !!token && headers?.Authorization === `Bearer ${token}`;
+}
+
 // Get headers with optional auth token
 function getHeaders(includeAuth = true): Record<string, string> {
   const headers: Record<string, string> = {
@@ -84,10 +186,17 @@ function getHeaders(includeAuth = true): Record<string, string> {
   return headers;
 }
 
+// If-Match header for updating a record only while it is still at `version`,
+// its `updated_at`. A stale version fails with 412 and the current record.
+function ifMatch(version?: string | null): Record<string, string> {
+  return version ? { 'If-Match': `"${version}"` } : {};
+}
+
 async function handleResponse<T>(response: Response): Promise<ApiResponse<T>> {
   // Handle 401 Unauthorized - redirect to login
   if (response.status === 401) {
     localStorage.removeItem(TOKEN_KEY);
+    localStorage.removeItem(REFRESH_TOKEN_KEY);
     localStorage.removeItem('home_registry_user');
     // Only redirect if not already on login/setup page
     if (
@@ -201,10 +310,30 @@ async function fetchWithRetry(
   maxRetries = 5
 ): Promise<Response> {
   let lastError: Error | null = null;
+  let requestOptions = options;
+  let refreshed = false;
 
   for (let attempt = 0; attempt <= maxRetries; attempt++) {
     try {
-      const response = await fetch(url, options);
+      const response = await fetch(url, requestOptions);
+
+      // Access tokens are short-lived - renew once and replay the request
+      if (response.status === 401 && !refreshed && usesStoredToken(requestOptions)) {
+        refreshed = true;
+        const newToken = await refreshAccessToken();
+        if (newToken) {
+          requestOptions = {
+            ...requestOptions,
+            headers: {
+              ...(requestOptions.headers as Record<string, string>),
+              Authorization: `Bearer ${newToken}`,
+            },
+          };
+          attempt--;
+          continue;
+        }
+        return response;
+      }
 
       // Handle rate limiting (429 Too Many Requests)
       if (response.status === 429) {
@@ -285,10 +414,14 @@ export const inventoryApi = {
     return handleResponse<Inventory>(response);
   },
 
-  async update(id: number, data: UpdateInventoryRequest): Promise<ApiResponse<Inventory>> {
+  async update(
+    id: number,
+    data: UpdateInventoryRequest,
+    version?: string | null
+  ): Promise<ApiResponse<Inventory>> {
     const response = await fetchWithRetry(`${API_BASE}/inventories/${id}`, {
       method: 'PUT',
-      headers: getHeaders(),
+      headers: { ...getHeaders(), ...ifMatch(version) },
       body: JSON.stringify(data),
     });
     return handleResponse<Inventory>(response);
@@ -335,10 +468,14 @@ export const itemApi = {
     return handleResponse<Item>(response);
   },
 
-  async update(id: number, data: UpdateItemRequest): Promise<ApiResponse<Item>> {
+  async update(
+    id: number,
+    data: UpdateItemRequest,
+    version?: string | null
+  ): Promise<ApiResponse<Item>> {
     const response = await fetchWithRetry(`${API_BASE}/items/${id}`, {
       method: 'PUT',
-      headers: getHeaders(),
+      headers: { ...getHeaders(), ...ifMatch(version) },
       body: JSON.stringify(data),
     });
     return handleResponse<Item>(response);
@@ -352,6 +489,32 @@ export const itemApi = {
     return handleResponse<boolean>(response);
   },
 
+  async updateVisibility(id: number, visibility: ItemVisibility): Promise<ApiResponse<Item>> {
+    const response = await fetchWithRetry(`${API_BASE}/items/${id}/visibility`, {
+      method: 'PUT',
+      headers: getHeaders(),
+      body: JSON.stringify({ visibility }),
+    });
+    return handleResponse<Item>(response);
+  },
+
+  // Get an item's revisions, newest first
+  async getHistory(id: number): Promise<ApiResponse<ItemRevision[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/items/${id}/history`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<ItemRevision[]>(response);
+  },
+
+  // Put an item back the way it was at an earlier revision
+  async revert(id: number, revision: number): Promise<ApiResponse<Item>> {
+    const response = await fetchWithRetry(`${API_BASE}/items/${id}/history/${revision}/revert`, {
+      method: 'POST',
+      headers: getHeaders(),
+    });
+    return handleResponse<Item>(response);
+  },
+
   async search(query: string): Promise<ApiResponse<Item[]>> {
     const response = await fetchWithRetry(`${API_BASE}/items/search/${encodeURIComponent(query)}`, {
       headers: getHeaders(),
@@ -410,11 +573,12 @@ export const organizerApi = {
 
   async updateType(
     id: number,
-    data: UpdateOrganizerTypeRequest
+    data: UpdateOrganizerTypeRequest,
+    version?: string | null
   ): Promise<ApiResponse<OrganizerType>> {
     const response = await fetchWithRetry(`${API_BASE}/organizers/${id}`, {
       method: 'PUT',
-      headers: getHeaders(),
+      headers: { ...getHeaders(), ...ifMatch(version) },
       body: JSON.stringify(data),
     });
     return handleResponse<OrganizerType>(response);
@@ -450,11 +614,12 @@ export const organizerApi = {
 
   async updateOption(
     optionId: number,
-    data: UpdateOrganizerOptionRequest
+    data: UpdateOrganizerOptionRequest,
+    version?: string | null
   ): Promise<ApiResponse<OrganizerOption>> {
     const response = await fetchWithRetry(`${API_BASE}/organizer-options/${optionId}`, {
       method: 'PUT',
-      headers: getHeaders(),
+      headers: { ...getHeaders(), ...ifMatch(version) },
       body: JSON.stringify(data),
     });
     return handleResponse<OrganizerOption>(response);
@@ -514,6 +679,17 @@ export async function checkHealth(): Promise<ApiResponse<{ status: string; messa
 
 // ==================== Authentication API ====================
 
+// Build the query string for audit log filters, skipping empty values
+function auditQuery(filters: AuditEventFilters): URLSearchParams {
+  const params = new URLSearchParams();
+  Object.entries(filters).forEach(([key, value]) => {
+    if (value !== undefined && value !== '') {
+      params.append(key, String(value));
+    }
+  });
+  return params;
+}
+
 export const authApi = {
   // Check if initial setup is needed
   async checkSetupStatus(): Promise<ApiResponse<SetupStatusResponse>> {
@@ -535,12 +711,52 @@ export const authApi = {
 
   // Login
   async login(data: LoginRequest): Promise<ApiResponse<LoginApiResponse>> {
-    const response = await fetchWithRetry(`${API_BASE}/auth/login`, {
+    // Never replay a throttled password attempt; tell the user how long to wait instead
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/login`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(data),
+      },
+      0
+    );
+    const result = await handleResponse<LoginApiResponse>(response);
+    if (response.status === 429 && result.error && result.message) {
+      return { ...result, error: `${result.error}. ${result.message}` };
+    }
+    return result;
+  },
+
+  // Start a session from the identity of a trusted authenticating proxy
+  async proxySession(): Promise<ApiResponse<LoginResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/proxy/session`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify(data),
     });
-    return handleResponse<LoginApiResponse>(response);
+    // No proxy identity is the normal case; don't treat it as an expired login
+    if (response.status === 401) {
+      return { success: false, error: 'No proxy identity' };
+    }
+    return handleResponse<LoginResponse>(response);
+  },
+
+  // Whether single sign-on is configured
+  async getOidcStatus(): Promise<ApiResponse<OidcStatusResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/oidc/config`, {
+      headers: { 'Content-Type': 'application/json' },
+    });
+    return handleResponse<OidcStatusResponse>(response);
+  },
+
+  // Exchange the one-time code from a completed single sign-on for a session
+  async oidcExchange(code: string): Promise<ApiResponse<LoginResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/oidc/exchange`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ code }),
+    });
+    return handleResponse<LoginResponse>(response);
   },
 
   // Register new user (after initial setup)
@@ -595,6 +811,77 @@ export const authApi = {
     return handleResponse<{ message: string }>(response);
   },
 
+  // End the current session on the server
+  async logout(): Promise<ApiResponse<Record<string, never>>> {
+    const headers = getHeaders();
+    localStorage.removeItem(REFRESH_TOKEN_KEY);
+    const response = await fetchWithRetry(`${API_BASE}/auth/logout`, {
+      method: 'POST',
+      headers,
+    });
+    return handleResponse<Record<string, never>>(response);
+  },
+
+  // List active sessions for the current user
+  async getSessions(): Promise<ApiResponse<UserSession[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/sessions`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<UserSession[]>(response);
+  },
+
+  // Sign out a single session
+  async revokeSession(sessionId: string): Promise<ApiResponse<Record<string, never>>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/sessions/${encodeURIComponent(sessionId)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<Record<string, never>>(response);
+  },
+
+  // Sign out every session, including this one
+  async revokeAllSessions(): Promise<ApiResponse<Record<string, never>>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/sessions`, {
+      method: 'DELETE',
+      headers: getHeaders(),
+    });
+    localStorage.removeItem(REFRESH_TOKEN_KEY);
+    return handleResponse<Record<string, never>>(response);
+  },
+
+  // List personal API tokens
+  async getApiTokens(): Promise<ApiResponse<ApiToken[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/tokens`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<ApiToken[]>(response);
+  },
+
+  // Create a personal API token (the secret is only returned here)
+  async createApiToken(data: CreateApiTokenRequest): Promise<ApiResponse<CreateApiTokenResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/tokens`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<CreateApiTokenResponse>(response);
+  },
+
+  // Delete a personal API token
+  async deleteApiToken(tokenId: string): Promise<ApiResponse<Record<string, never>>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/tokens/${encodeURIComponent(tokenId)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<Record<string, never>>(response);
+  },
+
   // Get user settings
   async getSettings(token?: string): Promise<ApiResponse<UserSettings>> {
     const headers: Record<string, string> = { 'Content-Type': 'application/json' };
@@ -616,13 +903,15 @@ export const authApi = {
   // Update user settings
   async updateSettings(
     token: string,
-    data: Partial<UpdateUserSettingsRequest>
+    data: Partial<UpdateUserSettingsRequest>,
+    version?: string | null
   ): Promise<ApiResponse<UserSettings>> {
     const response = await fetchWithRetry(`${API_BASE}/auth/settings`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
         Authorization: `Bearer ${token}`,
+        ...ifMatch(version),
       },
       body: JSON.stringify(data),
     });
@@ -674,6 +963,110 @@ export const authApi = {
     return handleResponse<{ message: string }>(response);
   },
 
+  // Admin: Clear a user's login lockout after failed password attempts
+  async unlockUser(userId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(`${API_BASE}/admin/users/${userId}/unlock`, {
+      method: 'POST',
+      headers: getHeaders(),
+    });
+    return handleResponse<undefined>(response);
+  },
+
+  // Admin: Issue a single-use password reset link for a user
+  async createPasswordResetLink(
+    userId: string,
+    expiresInHours?: number
+  ): Promise<ApiResponse<PasswordResetLink>> {
+    const response = await fetchWithRetry(`${API_BASE}/admin/users/${userId}/password-reset`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify({ expires_in_hours: expiresInHours }),
+    });
+    return handleResponse<PasswordResetLink>(response);
+  },
+
+  // Invite a new user (admins, or owners sharing an inventory)
+  async createInvitation(
+    data: CreateInvitationRequest
+  ): Promise<ApiResponse<CreateInvitationResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/invitations`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<CreateInvitationResponse>(response);
+  },
+
+  // List pending invitations (admins see all of them)
+  async getInvitations(): Promise<ApiResponse<Invitation[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/invitations`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<Invitation[]>(response);
+  },
+
+  // Revoke a pending invitation
+  async revokeInvitation(invitationId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/invitations/${encodeURIComponent(invitationId)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Look up what an invitation grants (no auth required)
+  async previewInvitation(token: string): Promise<ApiResponse<Invitation>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/invitations/preview`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ token }),
+    });
+    return handleResponse<Invitation>(response);
+  },
+
+  // Accept an invitation by creating an account (no auth required)
+  async acceptInvitation(data: AcceptInvitationRequest): Promise<ApiResponse<LoginResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/invitations/accept`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(data),
+    });
+    return handleResponse<LoginResponse>(response);
+  },
+
+  // Admin: Search the security audit log
+  async getAuditEvents(filters: AuditEventFilters): Promise<ApiResponse<AuditEvent[]>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/admin/audit-events?${auditQuery(filters).toString()}`,
+      {
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<AuditEvent[]>(response);
+  },
+
+  // Admin: Download every audit event matching the filters as CSV
+  async downloadAuditEventsCSV(filters: AuditEventFilters): Promise<Blob> {
+    const { limit: _limit, offset: _offset, ...exportFilters } = filters;
+    const response = await fetchWithRetry(
+      `${API_BASE}/admin/audit-events/export?${auditQuery(exportFilters).toString()}`,
+      {
+        headers: {
+          Authorization: `Bearer ${getToken()}`,
+        },
+      }
+    );
+
+    if (!response.ok) {
+      throw new Error('Failed to download audit log');
+    }
+
+    return response.blob();
+  },
+
   // ==================== Inventory Sharing ====================
 
   // Get shares for an inventory
@@ -719,6 +1112,260 @@ export const authApi = {
     return handleResponse<Record<string, never>>(response);
   },
 
+  // Get accessible inventories plus shares and All Access grants waiting for an answer
+  async getMyInventories(): Promise<ApiResponse<MyInventoriesResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/inventories`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<MyInventoriesResponse>(response);
+  },
+
+  // Accept or decline a share offered to the current user
+  async respondToShare(
+    shareId: string,
+    accept: boolean
+  ): Promise<ApiResponse<InventoryShare>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/shares/${shareId}/${accept ? 'accept' : 'decline'}`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<InventoryShare>(response);
+  },
+
+  // ==================== Household Groups ====================
+
+  // List the current user's groups (admins get every group)
+  async getGroups(): Promise<ApiResponse<UserGroup[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/groups`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<UserGroup[]>(response);
+  },
+
+  // Create a group with the current user as manager
+  async createGroup(data: CreateGroupRequest): Promise<ApiResponse<UserGroup>> {
+    const response = await fetchWithRetry(`${API_BASE}/groups`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<UserGroup>(response);
+  },
+
+  // Get a group with its members
+  async getGroup(groupId: string): Promise<ApiResponse<UserGroupWithMembers>> {
+    const response = await fetchWithRetry(`${API_BASE}/groups/${encodeURIComponent(groupId)}`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<UserGroupWithMembers>(response);
+  },
+
+  // Rename or describe a group
+  async updateGroup(groupId: string, data: UpdateGroupRequest): Promise<ApiResponse<UserGroup>> {
+    const response = await fetchWithRetry(`${API_BASE}/groups/${encodeURIComponent(groupId)}`, {
+      method: 'PUT',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<UserGroup>(response);
+  },
+
+  // Delete a group
+  async deleteGroup(groupId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(`${API_BASE}/groups/${encodeURIComponent(groupId)}`, {
+      method: 'DELETE',
+      headers: getHeaders(),
+    });
+    return handleResponse<undefined>(response);
+  },
+
+  // Add a user to a group
+  async addGroupMember(
+    groupId: string,
+    username: string,
+    role: GroupRole = 'member'
+  ): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/groups/${encodeURIComponent(groupId)}/members`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+        body: JSON.stringify({ username, role }),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Change a member's role
+  async updateGroupMember(
+    groupId: string,
+    userId: string,
+    role: GroupRole
+  ): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/groups/${encodeURIComponent(groupId)}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'PUT',
+        headers: getHeaders(),
+        body: JSON.stringify({ role }),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Remove a member from a group (or leave it)
+  async removeGroupMember(groupId: string, userId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/groups/${encodeURIComponent(groupId)}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Get the groups an inventory is shared with
+  async getInventoryGroupShares(inventoryId: number): Promise<ApiResponse<InventoryGroupShare[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/group-shares`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<InventoryGroupShare[]>(response);
+  },
+
+  // Share an inventory with a group
+  async shareInventoryWithGroup(
+    inventoryId: number,
+    data: CreateInventoryGroupShareRequest
+  ): Promise<ApiResponse<InventoryGroupShare>> {
+    const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/group-shares`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<InventoryGroupShare>(response);
+  },
+
+  // Update a group share's permission
+  async updateInventoryGroupShare(
+    shareId: string,
+    data: UpdateInventoryShareRequest
+  ): Promise<ApiResponse<InventoryGroupShare>> {
+    const response = await fetchWithRetry(`${API_BASE}/group-shares/${shareId}`, {
+      method: 'PUT',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<InventoryGroupShare>(response);
+  },
+
+  // Stop sharing an inventory with a group
+  async removeInventoryGroupShare(shareId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(`${API_BASE}/group-shares/${shareId}`, {
+      method: 'DELETE',
+      headers: getHeaders(),
+    });
+    return handleResponse<undefined>(response);
+  },
+
+  // ==================== Custom Roles ====================
+
+  // List the built-in roles and the current user's custom roles
+  async getRoles(): Promise<ApiResponse<RolesResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/roles`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<RolesResponse>(response);
+  },
+
+  // List the roles that can be given to shares of an inventory
+  async getInventoryRoles(inventoryId: number): Promise<ApiResponse<RolesResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/roles`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<RolesResponse>(response);
+  },
+
+  // Create a custom role
+  async createRole(data: CreateInventoryRoleRequest): Promise<ApiResponse<InventoryRole>> {
+    const response = await fetchWithRetry(`${API_BASE}/roles`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<InventoryRole>(response);
+  },
+
+  // Update a custom role; shares using it follow along
+  async updateRole(
+    roleId: string,
+    data: UpdateInventoryRoleRequest
+  ): Promise<ApiResponse<InventoryRole>> {
+    const response = await fetchWithRetry(`${API_BASE}/roles/${roleId}`, {
+      method: 'PUT',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<InventoryRole>(response);
+  },
+
+  // Delete a custom role
+  async deleteRole(roleId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(`${API_BASE}/roles/${roleId}`, {
+      method: 'DELETE',
+      headers: getHeaders(),
+    });
+    return handleResponse<undefined>(response);
+  },
+
+  // ==================== Public Share Links ====================
+
+  // List the public share links for an inventory
+  async getShareLinks(inventoryId: number): Promise<ApiResponse<ShareLink[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/share-links`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<ShareLink[]>(response);
+  },
+
+  // Create a read-only public link to an inventory
+  async createShareLink(
+    inventoryId: number,
+    data: CreateShareLinkRequest
+  ): Promise<ApiResponse<CreateShareLinkResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/share-links`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<CreateShareLinkResponse>(response);
+  },
+
+  // Revoke a public share link
+  async revokeShareLink(linkId: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/share-links/${encodeURIComponent(linkId)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Open a public share link (no auth required)
+  async viewShareLink(token: string, password?: string): Promise<ApiResponse<SharedInventoryView>> {
+    const response = await fetchWithRetry(`${API_BASE}/public/share-links/view`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ token, password }),
+    });
+    return handleResponse<SharedInventoryView>(response);
+  },
+
   // Get effective permissions for current user on an inventory
   async getInventoryPermissions(inventoryId: number): Promise<ApiResponse<EffectivePermissions>> {
     const response = await fetchWithRetry(`${API_BASE}/inventories/${inventoryId}/permissions`, {
@@ -775,6 +1422,19 @@ export const authApi = {
     return handleResponse<UserAccessGrant>(response);
   },
 
+  // Change the level of an All Access grant I have made
+  async updateAccessGrant(
+    grantId: string,
+    data: UpdateUserAccessGrantRequest
+  ): Promise<ApiResponse<UserAccessGrant>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/access-grants/${grantId}`, {
+      method: 'PUT',
+      headers: getHeaders(),
+      body: JSON.stringify(data),
+    });
+    return handleResponse<UserAccessGrant>(response);
+  },
+
   // Revoke All Access from a user
   async revokeAccessGrant(grantId: string): Promise<ApiResponse<Record<string, never>>> {
     const response = await fetchWithRetry(`${API_BASE}/auth/access-grants/${grantId}`, {
@@ -784,6 +1444,21 @@ export const authApi = {
     return handleResponse<Record<string, never>>(response);
   },
 
+  // Accept or decline an All Access grant offered to the current user
+  async respondToAccessGrant(
+    grantId: string,
+    accept: boolean
+  ): Promise<ApiResponse<UserAccessGrant>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/access-grants/${grantId}/${accept ? 'accept' : 'decline'}`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<UserAccessGrant>(response);
+  },
+
   // ==================== Recovery Codes ====================
 
   // Generate new recovery codes (replaces any existing codes)
@@ -894,6 +1569,19 @@ export const authApi = {
     return handleResponse<{ message: string }>(response);
   },
 
+  // Set a new password using an admin-issued reset link (no auth required)
+  async redeemPasswordReset(
+    token: string,
+    newPassword: string
+  ): Promise<ApiResponse<{ message: string }>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/password-reset`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ token, new_password: newPassword }),
+    });
+    return handleResponse<{ message: string }>(response);
+  },
+
   // Change TOTP mode
   async changeTotpMode(mode: TotpMode): Promise<ApiResponse<{ mode: string }>> {
     const response = await fetchWithRetry(`${API_BASE}/auth/totp/mode`, {
@@ -913,9 +1601,153 @@ export const authApi = {
     });
     return handleResponse<undefined>(response);
   },
+
+  // ==================== WebAuthn Passkeys ====================
+
+  // Whether passkeys are configured
+  async getWebauthnStatus(): Promise<ApiResponse<WebauthnStatusResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/config`, {
+      headers: { 'Content-Type': 'application/json' },
+    });
+    return handleResponse<WebauthnStatusResponse>(response);
+  },
+
+  // List the current user's passkeys
+  async getPasskeys(): Promise<ApiResponse<WebauthnCredential[]>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/credentials`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<WebauthnCredential[]>(response);
+  },
+
+  // Start registering a passkey
+  async startPasskeyRegistration(): Promise<ApiResponse<PublicKeyCredentialCreationOptionsJSON>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/register/start`, {
+      method: 'POST',
+      headers: getHeaders(),
+    });
+    return handleResponse<PublicKeyCredentialCreationOptionsJSON>(response);
+  },
+
+  // Finish registering a passkey
+  async finishPasskeyRegistration(
+    nickname: string,
+    credential: RegistrationCredentialJSON
+  ): Promise<ApiResponse<WebauthnCredential>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/register/finish`, {
+      method: 'POST',
+      headers: getHeaders(),
+      body: JSON.stringify({ nickname, credential }),
+    });
+    return handleResponse<WebauthnCredential>(response);
+  },
+
+  // Rename a passkey
+  async renamePasskey(id: string, nickname: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/webauthn/credentials/${encodeURIComponent(id)}`,
+      {
+        method: 'PUT',
+        headers: getHeaders(),
+        body: JSON.stringify({ nickname }),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Delete a passkey
+  async deletePasskey(id: string): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/auth/webauthn/credentials/${encodeURIComponent(id)}`,
+      {
+        method: 'DELETE',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<undefined>(response);
+  },
+
+  // Start a passwordless login (no auth required)
+  async startPasskeyLogin(): Promise<ApiResponse<PublicKeyCredentialRequestOptionsJSON>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/login/start`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+    });
+    return handleResponse<PublicKeyCredentialRequestOptionsJSON>(response);
+  },
+
+  // Finish a passwordless login
+  async finishPasskeyLogin(
+    credential: AuthenticationCredentialJSON
+  ): Promise<ApiResponse<LoginResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/login/finish`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ credential }),
+    });
+    return handleResponse<LoginResponse>(response);
+  },
+
+  // Start verifying a login with a passkey instead of a TOTP code (uses partial_token)
+  async startPasskeyVerification(
+    partialToken: string
+  ): Promise<ApiResponse<PublicKeyCredentialRequestOptionsJSON>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/verify/start`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        Authorization: `Bearer ${partialToken}`,
+      },
+    });
+    return handleResponse<PublicKeyCredentialRequestOptionsJSON>(response);
+  },
+
+  // Finish verifying a login with a passkey
+  async finishPasskeyVerification(
+    partialToken: string,
+    credential: AuthenticationCredentialJSON
+  ): Promise<ApiResponse<LoginResponse>> {
+    const response = await fetchWithRetry(`${API_BASE}/auth/webauthn/verify/finish`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        Authorization: `Bearer ${partialToken}`,
+      },
+      body: JSON.stringify({ credential }),
+    });
+    return handleResponse<LoginResponse>(response);
+  },
 };
 
 // ==================== Inventory Reports ====================
+export const trashApi = {
+  // List the current user's trash
+  async getAll(): Promise<ApiResponse<TrashListing>> {
+    const response = await fetchWithRetry(`${API_BASE}/trash`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<TrashListing>(response);
+  },
+
+  // Restore an entry; an inventory brings back what was trashed with it
+  async restore(kind: TrashKind, id: number): Promise<ApiResponse<TrashEntry>> {
+    const response = await fetchWithRetry(`${API_BASE}/trash/${kind}/${id}/restore`, {
+      method: 'POST',
+      headers: getHeaders(),
+    });
+    return handleResponse<TrashEntry>(response);
+  },
+
+  // Permanently delete an entry
+  async purge(kind: TrashKind, id: number): Promise<ApiResponse<void>> {
+    const response = await fetchWithRetry(`${API_BASE}/trash/${kind}/${id}`, {
+      method: 'DELETE',
+      headers: getHeaders(),
+    });
+    return handleResponse<undefined>(response);
+  },
+};
+
 export const reportApi = {
   // Get comprehensive inventory report
   async getInventoryReport(
@@ -1048,6 +1880,26 @@ export const backupApi = {
     return handleResponse<BackupInfo>(response);
   },
 
+  // Get off-site replication settings and the latest replication outcome
+  async remoteStatus(): Promise<ApiResponse<RemoteBackupStatus>> {
+    const response = await fetchWithRetry(`${API_BASE}/backup/remote/status`, {
+      headers: getHeaders(),
+    });
+    return handleResponse<RemoteBackupStatus>(response);
+  },
+
+  // Verify a server-side backup, optionally with a dry-run restore
+  async verify(filename: string, dryRun = false): Promise<ApiResponse<BackupVerificationReport>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/backup/verify/${encodeURIComponent(filename)}?dry_run=${dryRun}`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<BackupVerificationReport>(response);
+  },
+
   // Restore from a server-side backup
   async restore(filename: string): Promise<ApiResponse<null>> {
     const response = await fetchWithRetry(
@@ -1060,6 +1912,47 @@ export const backupApi = {
     return handleResponse<null>(response);
   },
 
+  // Browse the inventories and users in a backup
+  async browse(filename: string): Promise<ApiResponse<BackupContents>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/backup/browse/${encodeURIComponent(filename)}`,
+      {
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<BackupContents>(response);
+  },
+
+  // Merge a single inventory back from a backup
+  async restoreInventory(
+    filename: string,
+    inventoryId: number
+  ): Promise<ApiResponse<SelectiveRestoreSummary>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/backup/restore/${encodeURIComponent(filename)}/inventory/${inventoryId}`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<SelectiveRestoreSummary>(response);
+  },
+
+  // Merge a single user's account and data back from a backup
+  async restoreUser(
+    filename: string,
+    userId: string
+  ): Promise<ApiResponse<SelectiveRestoreSummary>> {
+    const response = await fetchWithRetry(
+      `${API_BASE}/backup/restore/${encodeURIComponent(filename)}/user/${userId}`,
+      {
+        method: 'POST',
+        headers: getHeaders(),
+      }
+    );
+    return handleResponse<SelectiveRestoreSummary>(response);
+  },
+
   // Delete a backup
   async delete(filename: string): Promise<ApiResponse<null>> {
     const response = await fetchWithRetry(`${API_BASE}/backup/${encodeURIComponent(filename)}`, {
@@ -36,10 +36,63 @@ export interface Item {
   warranty_expiry?: string;
   notes?: string;
   quantity?: number;
+  visibility?: ItemVisibility;
   created_at?: string;
   updated_at?: string;
 }
 
+// Who can see an item when its inventory is shared
+// everyone: anyone who can open the inventory, including public share links
+// edit_items / edit_inventory: people shared with at that level or higher
+// owner: only the owner and users with full All Access
+export type ItemVisibility = 'everyone' | 'edit_items' | 'edit_inventory' | 'owner';
+
+// What produced an item revision
+export type ItemHistoryAction = 'created' | 'updated' | 'reverted';
+
+// One changed field of an item. Organizer values use the field "organizer:<type id>",
+// carry the organizer name as label, and have { organizer_option_id, text_value, value }
+// (or null) as values.
+export interface ItemFieldChange {
+  field: string;
+  label?: string;
+  old_value: unknown;
+  new_value: unknown;
+}
+
+// A numbered revision in an item's history
+export interface ItemRevision {
+  revision: number;
+  action: ItemHistoryAction;
+  reverted_to_revision: number | null;
+  changed_by_user_id: string | null;
+  changed_by_username: string | null;
+  changed_at: string;
+  changes: ItemFieldChange[];
+}
+
+export type TrashKind = 'inventory' | 'item' | 'organizer_type';
+
+// An inventory, item or organizer type in the trash
+export interface TrashEntry {
+  kind: TrashKind;
+  id: number;
+  name: string;
+  inventory_id: number | null;
+  inventory_name: string | null;
+  // Items trashed along with an inventory
+  item_count: number | null;
+  deleted_at: string;
+  deleted_by_user_id: string | null;
+  deleted_by_username: string | null;
+}
+
+export interface TrashListing {
+  entries: TrashEntry[];
+  // Days after which entries are purged, null when they are kept
+  retention_days: number | null;
+}
+
 export interface Category {
   id?: number;
   name: string;
@@ -261,9 +314,113 @@ export interface LoginRequest {
 
 export interface LoginResponse {
   token: string;
+  refresh_token: string;
+  expires_in: number;
   user: User;
 }
 
+export interface UserSession {
+  id: string;
+  user_agent?: string;
+  ip_address?: string;
+  created_at: string;
+  last_seen_at: string;
+  expires_at: string;
+  current: boolean;
+}
+
+export interface OidcStatusResponse {
+  enabled: boolean;
+  provider_name?: string;
+}
+
+// ==================== WebAuthn Passkey Types ====================
+
+export interface WebauthnStatusResponse {
+  enabled: boolean;
+}
+
+export interface WebauthnCredential {
+  id: string;
+  nickname: string;
+  transports: string[];
+  created_at: string;
+  last_used_at?: string;
+}
+
+// Ceremony options as sent by the server; binary values are base64url strings
+export interface PublicKeyCredentialDescriptorJSON {
+  type: 'public-key';
+  id: string;
+  transports?: string[];
+}
+
+export interface PublicKeyCredentialCreationOptionsJSON {
+  challenge: string;
+  rp: { id: string; name: string };
+  user: { id: string; name: string; displayName: string };
+  pubKeyCredParams: { type: 'public-key'; alg: number }[];
+  timeout: number;
+  attestation: AttestationConveyancePreference;
+  authenticatorSelection: {
+    residentKey: ResidentKeyRequirement;
+    userVerification: UserVerificationRequirement;
+  };
+  excludeCredentials: PublicKeyCredentialDescriptorJSON[];
+}
+
+export interface PublicKeyCredentialRequestOptionsJSON {
+  challenge: string;
+  rpId: string;
+  timeout: number;
+  userVerification: UserVerificationRequirement;
+  allowCredentials: PublicKeyCredentialDescriptorJSON[];
+}
+
+export interface RegistrationCredentialJSON {
+  id: string;
+  response: {
+    clientDataJSON: string;
+    attestationObject: string;
+    transports: string[];
+  };
+}
+
+export interface AuthenticationCredentialJSON {
+  id: string;
+  response: {
+    clientDataJSON: string;
+    authenticatorData: string;
+    signature: string;
+    userHandle?: string;
+  };
+}
+
+export type ApiTokenScope = 'items:read' | 'items:write' | 'reports:read';
+
+export interface ApiToken {
+  id: string;
+  name: string;
+  token_prefix: string;
+  scopes: ApiTokenScope[];
+  inventory_ids?: number[];
+  created_at: string;
+  expires_at?: string;
+  last_used_at?: string;
+}
+
+export interface CreateApiTokenRequest {
+  name: string;
+  scopes: ApiTokenScope[];
+  inventory_ids?: number[];
+  expires_in_days?: number;
+}
+
+export interface CreateApiTokenResponse {
+  token: string;
+  api_token: ApiToken;
+}
+
 export interface InitialSetupRequest {
   username: string;
   full_name: string;
@@ -313,15 +470,93 @@ export interface UpdateUserRequest {
   is_active?: boolean;
 }
 
+// Audit log types (admin)
+export type AuditOutcome = 'success' | 'failure' | 'denied';
+
+export interface AuditEvent {
+  id: number;
+  created_at: string;
+  actor_id?: string;
+  actor_username?: string;
+  action: string;
+  target_type?: string;
+  target_id?: string;
+  outcome: AuditOutcome;
+  details?: string;
+  ip_address?: string;
+  user_agent?: string;
+}
+
+export interface AuditEventFilters {
+  user_id?: string;
+  // Exact action, or a group prefix ending in '.' such as 'auth.'
+  action?: string;
+  from?: string;
+  to?: string;
+  limit?: number;
+  offset?: number;
+}
+
 // Permission types - 4-tier system
 // view: Can view inventory and items
 // edit_items: Can view and edit item details (not add/remove)
 // edit_inventory: Can view, edit items, add/remove items, edit inventory details
-// all_access: User-to-user grant - full access to ALL grantor's inventories (via UserAccessGrant)
+// all_access: User-to-user grant - access to ALL grantor's inventories (via UserAccessGrant)
 export type PermissionLevel = 'view' | 'edit_items' | 'edit_inventory';
 
+// Level of an All Access grant; anything below full behaves like a share on every inventory
+export type AccessGrantLevel = PermissionLevel | 'full';
+
+// A single thing a share lets its recipient do; roles are sets of capabilities
+export type Capability =
+  | 'view'
+  | 'add_items'
+  | 'edit_items'
+  | 'remove_items'
+  | 'manage_organizers'
+  | 'edit_inventory'
+  | 'manage_shares'
+  | 'export';
+
+// An owner-defined role that can be given to shares of the owner's inventories
+export interface InventoryRole {
+  id: string;
+  owner_user_id: string;
+  name: string;
+  description: string | null;
+  capabilities: Capability[];
+  created_at: string;
+  updated_at: string;
+}
+
+// A built-in role, one per permission level
+export interface BuiltinRole {
+  permission_level: PermissionLevel;
+  capabilities: Capability[];
+}
+
+export interface RolesResponse {
+  builtin: BuiltinRole[];
+  custom: InventoryRole[];
+}
+
+export interface CreateInventoryRoleRequest {
+  name: string;
+  description?: string;
+  capabilities: Capability[];
+}
+
+export interface UpdateInventoryRoleRequest {
+  name?: string;
+  description?: string;
+  capabilities?: Capability[];
+}
+
 // Permission source - where the user's access comes from
-export type PermissionSource = 'owner' | 'all_access' | 'inventory_share' | 'none';
+export type PermissionSource = 'owner' | 'all_access' | 'inventory_share' | 'group_share' | 'none';
+
+// Whether the recipient has accepted a share or All Access grant yet
+export type ShareStatus = 'pending' | 'accepted' | 'declined';
 
 export interface InventoryShare {
   id: string;
@@ -329,17 +564,41 @@ export interface InventoryShare {
   shared_with_user: User;
   shared_by_user: User;
   permission_level: PermissionLevel;
+  role_id?: string; // Custom role; overrides permission_level when set
+  role_name?: string;
+  status: ShareStatus;
+  starts_at?: string;
+  expires_at?: string;
+  is_active: boolean;
+  expires_in_seconds?: number;
   created_at: string;
   updated_at: string;
 }
 
 export interface CreateInventoryShareRequest {
   shared_with_username: string;
+  permission_level?: PermissionLevel;
+  role_id?: string; // One of the owner's custom roles, instead of permission_level
+  starts_at?: string;
+  expires_at?: string;
+}
+
+// A share waiting for the current user to accept or decline it
+export interface PendingInventoryShare {
+  id: string;
+  inventory_id: number;
+  inventory_name: string;
+  shared_by_user: User;
   permission_level: PermissionLevel;
+  role_name?: string;
+  starts_at?: string;
+  expires_at?: string;
+  created_at: string;
 }
 
 export interface UpdateInventoryShareRequest {
-  permission_level: PermissionLevel;
+  permission_level?: PermissionLevel;
+  role_id?: string;
 }
 
 // User Access Grant types (All Access tier)
@@ -347,6 +606,10 @@ export interface UserAccessGrant {
   id: string;
   grantor_user_id: string;
   grantee_user_id: string;
+  permission_level: AccessGrantLevel;
+  status: ShareStatus;
+  starts_at?: string;
+  expires_at?: string;
   created_at: string;
   updated_at: string;
 }
@@ -355,12 +618,32 @@ export interface UserAccessGrantWithUsers {
   id: string;
   grantor: User;
   grantee: User;
+  permission_level: AccessGrantLevel;
+  status: ShareStatus;
+  starts_at?: string;
+  expires_at?: string;
+  is_active: boolean;
+  expires_in_seconds?: number;
   created_at: string;
   updated_at: string;
 }
 
 export interface CreateUserAccessGrantRequest {
   grantee_username: string;
+  permission_level?: AccessGrantLevel;
+  starts_at?: string;
+  expires_at?: string;
+}
+
+export interface UpdateUserAccessGrantRequest {
+  permission_level: AccessGrantLevel;
+}
+
+// Inventories the current user can open, plus invitations waiting for an answer
+export interface MyInventoriesResponse {
+  inventories: Inventory[];
+  pending_shares: PendingInventoryShare[];
+  pending_access_grants: UserAccessGrantWithUsers[];
 }
 
 // Ownership Transfer types
@@ -390,6 +673,7 @@ export interface EffectivePermissions {
   is_owner: boolean;
   has_all_access: boolean;
   permission_source: PermissionSource;
+  capabilities: Capability[];
 }
 
 // Recovery Codes types
@@ -422,6 +706,148 @@ export interface RecoveryCodeUsedResponse {
   remaining_codes: number;
 }
 
+// Password reset link types
+export interface PasswordResetLink {
+  token: string;
+  reset_path: string;
+  expires_at: string;
+}
+
+// Invitation types
+export interface Invitation {
+  id: string;
+  invited_by: string;
+  invited_by_username: string;
+  is_admin: boolean;
+  inventory_id: number | null;
+  inventory_name: string | null;
+  permission_level: PermissionLevel | null;
+  grant_all_access: boolean;
+  created_at: string;
+  expires_at: string;
+  accepted_at: string | null;
+  accepted_by: string | null;
+}
+
+export interface CreateInvitationRequest {
+  is_admin?: boolean;
+  inventory_id?: number;
+  permission_level?: PermissionLevel;
+  grant_all_access?: boolean;
+  expires_in_hours?: number;
+}
+
+export interface CreateInvitationResponse {
+  invitation: Invitation;
+  token: string;
+  invite_path: string;
+}
+
+export interface AcceptInvitationRequest {
+  token: string;
+  username: string;
+  full_name: string;
+  password: string;
+}
+
+// Household group types
+export type GroupRole = 'member' | 'manager';
+
+export interface UserGroup {
+  id: string;
+  name: string;
+  description: string | null;
+  created_by: string | null;
+  member_count: number;
+  my_role: GroupRole | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface GroupMember {
+  user: User;
+  role: GroupRole;
+  added_at: string;
+}
+
+export interface UserGroupWithMembers extends UserGroup {
+  members: GroupMember[];
+}
+
+export interface CreateGroupRequest {
+  name: string;
+  description?: string;
+}
+
+export interface UpdateGroupRequest {
+  name?: string;
+  description?: string;
+}
+
+export interface InventoryGroupShare {
+  id: string;
+  inventory_id: number;
+  group_id: string;
+  group_name: string;
+  shared_by_user_id: string;
+  permission_level: PermissionLevel;
+  role_id?: string;
+  role_name?: string;
+  starts_at?: string;
+  expires_at?: string;
+  is_active: boolean;
+  expires_in_seconds?: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateInventoryGroupShareRequest {
+  group_id: string;
+  permission_level?: PermissionLevel;
+  role_id?: string;
+  starts_at?: string;
+  expires_at?: string;
+}
+
+// Public share link types
+export interface ShareLink {
+  id: string;
+  inventory_id: number;
+  created_by: string;
+  name: string;
+  token_prefix: string;
+  password_protected: boolean;
+  access_count: number;
+  last_accessed_at: string | null;
+  created_at: string;
+  expires_at: string | null;
+}
+
+export interface CreateShareLinkRequest {
+  name: string;
+  password?: string;
+  expires_in_days?: number;
+}
+
+export interface CreateShareLinkResponse {
+  share_link: ShareLink;
+  token: string;
+  share_path: string;
+}
+
+export interface SharedItem extends Item {
+  organizer_values: ItemOrganizerValueWithDetails[];
+}
+
+export interface SharedInventoryView {
+  name: string;
+  description?: string;
+  location?: string;
+  image_url?: string;
+  items: SharedItem[];
+  expires_at: string | null;
+}
+
 // Inventory Report types
 export interface InventoryReportParams {
   inventory_id?: number;
@@ -515,6 +941,8 @@ export interface TotpDisableRequest {
 export interface LoginTotpRequiredResponse {
   requires_totp: boolean;
   partial_token: string;
+  // A registered passkey can be used instead of the code
+  webauthn_available: boolean;
   user: User;
 }
 
@@ -527,6 +955,21 @@ export interface BackupInfo {
   name: string;
   date: string;
   size: string;
+  local: boolean;
+  remote: boolean;
+  replication_error?: string;
+}
+
+export interface RemoteBackupStatus {
+  enabled: boolean;
+  endpoint?: string;
+  bucket?: string;
+  prefix?: string;
+  retention_count?: number;
+  last_success_at?: string;
+  last_uploaded?: string;
+  last_failure_at?: string;
+  last_error?: string;
 }
 
 export interface BackupMetadata {
@@ -535,4 +978,70 @@ export interface BackupMetadata {
   created_at: string;
   database_type: string;
   description?: string;
+  checksum?: string;
+}
+
+export interface BackupTableCount {
+  table: string;
+  rows: number;
+}
+
+export interface BackupVerificationReport {
+  filename: string;
+  valid: boolean;
+  version: string | null;
+  app_version: string | null;
+  checksum_valid: boolean | null;
+  version_compatible: boolean;
+  missing_tables: string[];
+  integrity_errors: string[];
+  warnings: string[];
+  dry_run: BackupTableCount[] | null;
+  dry_run_error: string | null;
+}
+
+export interface BackupInventorySummary {
+  id: number;
+  name: string;
+  owner_user_id: string | null;
+  owner_username: string | null;
+  item_count: number;
+  exists_in_database: boolean;
+}
+
+export interface BackupUserSummary {
+  id: string;
+  username: string;
+  full_name: string;
+  is_admin: boolean;
+  inventory_count: number;
+  exists_in_database: boolean;
+}
+
+export interface BackupContents {
+  filename: string;
+  created_at: string;
+  inventories: BackupInventorySummary[];
+  users: BackupUserSummary[];
+}
+
+export interface RestoredInventory {
+  original_id: number;
+  new_id: number;
+  name: string;
+}
+
+export interface SelectiveRestoreSummary {
+  user_restored: boolean;
+  inventories: RestoredInventory[];
+  items_restored: number;
+  organizer_types_restored: number;
+  organizer_options_restored: number;
+  organizer_values_restored: number;
+  tags_restored: number;
+  custom_values_restored: number;
+  revisions_restored: number;
+  shares_restored: number;
+  access_grants_restored: number;
+  warnings: string[];
 }
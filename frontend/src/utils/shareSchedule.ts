@@ -0,0 +1,57 @@
+/**
+ * Share schedule utility
+ * Formats the start/expiry window of inventory shares and All Access grants
+ */
+
+interface ScheduledAccess {
+  starts_at?: string;
+  expires_at?: string;
+  is_active: boolean;
+  expires_in_seconds?: number;
+}
+
+/**
+ * Format a number of seconds as a rough remaining time, e.g. "3 days" or "5 hours"
+ */
+function formatRemaining(seconds: number): string {
+  const units: [number, string][] = [
+    [86400, 'day'],
+    [3600, 'hour'],
+    [60, 'minute'],
+  ];
+  for (const [size, name] of units) {
+    if (seconds >= size) {
+      const count = Math.floor(seconds / size);
+      return `${count} ${name}${count === 1 ? '' : 's'}`;
+    }
+  }
+  return 'less than a minute';
+}
+
+/**
+ * Describe when a share or grant applies, or null if it has no schedule
+ */
+export function describeShareSchedule(access: ScheduledAccess): string | null {
+  if (!access.starts_at && !access.expires_at) {
+    return null;
+  }
+  if (access.starts_at && new Date(access.starts_at) > new Date()) {
+    const starts = `Starts ${new Date(access.starts_at).toLocaleString()}`;
+    return access.expires_at
+      ? `${starts}, ends ${new Date(access.expires_at).toLocaleString()}`
+      : starts;
+  }
+  if (!access.is_active || access.expires_in_seconds === 0) {
+    return 'Expired';
+  }
+  return access.expires_in_seconds !== undefined
+    ? `Expires in ${formatRemaining(access.expires_in_seconds)}`
+    : null;
+}
+
+/**
+ * Convert a datetime-local input value to an ISO timestamp, or undefined if empty
+ */
+export function scheduleInputToIso(value: string): string | undefined {
+  return value ? new Date(value).toISOString() : undefined;
+}
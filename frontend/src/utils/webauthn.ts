@@ -0,0 +1,106 @@
+/**
+ * WebAuthn browser helpers
+ * Converts between the server's JSON ceremony options (base64url strings)
+ * and the binary structures `navigator.credentials` works with
+ */
+
+import type {
+  AuthenticationCredentialJSON,
+  PublicKeyCredentialCreationOptionsJSON,
+  PublicKeyCredentialDescriptorJSON,
+  PublicKeyCredentialRequestOptionsJSON,
+  RegistrationCredentialJSON,
+} from '@/types';
+
+/**
+ * Whether this browser supports passkeys
+ */
+export function isWebauthnSupported(): boolean {
+  return typeof window !== 'undefined' && typeof window.PublicKeyCredential === 'function';
+}
+
+function base64urlToBuffer(value: string): ArrayBuffer {
+  const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+  const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+  const binary = atob(padded);
+  const bytes = new Uint8Array(binary.length);
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i);
+  }
+  return bytes.buffer;
+}
+
+function bufferToBase64url(buffer: ArrayBuffer): string {
+  const bytes = new Uint8Array(buffer);
+  let binary = '';
+  for (const byte of bytes) {
+    binary += String.fromCharCode(byte);
+  }
+  return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+function toDescriptor(descriptor: PublicKeyCredentialDescriptorJSON): PublicKeyCredentialDescriptor {
+  return {
+    type: descriptor.type,
+    id: base64urlToBuffer(descriptor.id),
+    transports: descriptor.transports as AuthenticatorTransport[] | undefined,
+  };
+}
+
+/**
+ * Create a passkey with the browser from the server's registration options
+ */
+export async function createPasskey(
+  options: PublicKeyCredentialCreationOptionsJSON
+): Promise<RegistrationCredentialJSON> {
+  const credential = (await navigator.credentials.create({
+    publicKey: {
+      ...options,
+      challenge: base64urlToBuffer(options.challenge),
+      user: { ...options.user, id: base64urlToBuffer(options.user.id) },
+      excludeCredentials: options.excludeCredentials.map(toDescriptor),
+    },
+  })) as PublicKeyCredential | null;
+  if (!credential) {
+    throw new Error('Passkey registration was cancelled');
+  }
+
+  const response = credential.response as AuthenticatorAttestationResponse;
+  return {
+    id: credential.id,
+    response: {
+      clientDataJSON: bufferToBase64url(response.clientDataJSON),
+      attestationObject: bufferToBase64url(response.attestationObject),
+      transports: response.getTransports(),
+    },
+  };
+}
+
+/**
+ * Sign a login challenge with a passkey from the server's authentication options
+ */
+export async function getPasskeyAssertion(
+  options: PublicKeyCredentialRequestOptionsJSON
+): Promise<AuthenticationCredentialJSON> {
+  const credential = (await navigator.credentials.get({
+    publicKey: {
+      ...options,
+      challenge: base64urlToBuffer(options.challenge),
+      allowCredentials: options.allowCredentials.map(toDescriptor),
+    },
+  })) as PublicKeyCredential | null;
+  if (!credential) {
+    throw new Error('Passkey sign-in was cancelled');
+  }
+
+  const response = credential.response as AuthenticatorAssertionResponse;
+  return {
+    id: credential.id,
+    response: {
+      clientDataJSON: bufferToBase64url(response.clientDataJSON),
+      authenticatorData: bufferToBase64url(response.authenticatorData),
+      signature: bufferToBase64url(response.signature),
+      userHandle: response.userHandle ? bufferToBase64url(response.userHandle) : undefined,
+    },
+  };
+}
@@ -0,0 +1,29 @@
+-- Create server-side login sessions
+-- Each login creates a session. Access tokens are short-lived JWTs that carry
+-- the session id; refresh tokens rotate on every use and are stored hashed.
+
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- SHA-256 hex digest of the current refresh token
+    refresh_token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Digest of the refresh token it replaced; presenting it again means the
+    -- token was stolen and the session is revoked
+    previous_refresh_token_hash VARCHAR(64),
+
+    -- Device information shown in the session list
+    user_agent TEXT,
+    ip_address VARCHAR(45),
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_user_sessions_user_id ON user_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_previous_hash ON user_sessions(previous_refresh_token_hash);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_expires_at ON user_sessions(expires_at);
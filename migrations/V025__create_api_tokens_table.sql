@@ -0,0 +1,27 @@
+-- Create personal API tokens for scripts and automations
+-- Tokens are shown once at creation and stored as SHA-256 digests. Each token
+-- carries a set of scopes and can optionally be limited to specific inventories.
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+
+    -- SHA-256 hex digest of the full token
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Leading characters of the token so users can tell tokens apart
+    token_prefix VARCHAR(16) NOT NULL,
+
+    -- Granted scopes, e.g. {items:read,reports:read}
+    scopes TEXT[] NOT NULL,
+    -- NULL means every inventory the owner can access
+    inventory_ids INTEGER[],
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
@@ -0,0 +1,25 @@
+-- Create WebAuthn credentials (passkeys and hardware security keys)
+-- A user can register several credentials. Each one can sign in without a
+-- password or stand in for the TOTP code during login.
+
+CREATE TABLE IF NOT EXISTS webauthn_credentials (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    nickname VARCHAR(100) NOT NULL,
+
+    -- Credential ID chosen by the authenticator
+    credential_id BYTEA NOT NULL UNIQUE,
+    -- COSE-encoded public key from the attested credential data
+    public_key BYTEA NOT NULL,
+    -- Signature counter, used to detect cloned authenticators
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    -- Transport hints reported at registration (usb, nfc, ble, internal, hybrid)
+    transports TEXT[] NOT NULL DEFAULT '{}',
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id ON webauthn_credentials(user_id);
@@ -0,0 +1,12 @@
+-- Track failed password logins per username for throttling and lockout
+-- Keyed by the lower-cased username rather than the user id so that unknown
+-- usernames are throttled exactly like real ones and responses don't reveal
+-- which accounts exist.
+
+CREATE TABLE IF NOT EXISTS login_attempts (
+    username VARCHAR(255) PRIMARY KEY,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- No password attempts are accepted for this username until then
+    locked_until TIMESTAMPTZ
+);
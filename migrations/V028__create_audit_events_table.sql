@@ -0,0 +1,51 @@
+-- Create the security audit log
+-- Records authentication and admin actions: who did what to which target, from
+-- where, and whether it succeeded. Actors and targets are plain values rather
+-- than foreign keys so entries outlive the users and objects they mention.
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Who acted; NULL for anonymous requests such as failed logins
+    actor_id UUID,
+    -- Username at the time, or the name tried for a failed login
+    actor_username VARCHAR(255),
+
+    -- Dotted action name, e.g. auth.login or user.deleted
+    action VARCHAR(64) NOT NULL,
+    -- What was acted on, e.g. ('user', '<uuid>') or ('backup', '<filename>')
+    target_type VARCHAR(50),
+    target_id VARCHAR(255),
+
+    -- success, failure or denied
+    outcome VARCHAR(16) NOT NULL CHECK (outcome IN ('success', 'failure', 'denied')),
+    details TEXT,
+
+    -- Client info
+    ip_address VARCHAR(45),
+    user_agent TEXT
+);
+
+-- Indexes for the admin query filters
+CREATE INDEX IF NOT EXISTS idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor_id ON audit_events(actor_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_action ON audit_events(action, created_at);
+
+-- The log is append-only: refuse edits and deletions
+CREATE OR REPLACE FUNCTION prevent_audit_event_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_events_append_only ON audit_events;
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION prevent_audit_event_changes();
+
+DROP TRIGGER IF EXISTS audit_events_no_truncate ON audit_events;
+CREATE TRIGGER audit_events_no_truncate
+    BEFORE TRUNCATE ON audit_events
+    FOR EACH STATEMENT EXECUTE FUNCTION prevent_audit_event_changes();
@@ -0,0 +1,17 @@
+-- Make password reset tokens usable as admin-issued reset links
+-- Tokens are shown once when minted and stored as SHA-256 digests. Each one
+-- expires, can only be redeemed once, and records the admin who issued it.
+
+-- Nothing has issued tokens before now; drop any strays rather than keep
+-- plaintext values with no expiry around
+DELETE FROM password_reset_tokens;
+
+ALTER TABLE password_reset_tokens RENAME COLUMN token TO token_hash;
+
+ALTER TABLE password_reset_tokens
+    ADD COLUMN IF NOT EXISTS created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ NOT NULL,
+    ADD COLUMN IF NOT EXISTS used_at TIMESTAMPTZ;
+
+DROP INDEX IF EXISTS idx_password_reset_tokens_token;
+CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_expires_at ON password_reset_tokens(expires_at);
@@ -0,0 +1,35 @@
+-- Create invitations for onboarding new users without choosing their password
+-- Tokens are shown once when created and stored as SHA-256 digests. An
+-- invitation can carry the new user's role and an inventory share or All
+-- Access grant from the inviter, which is applied when it is accepted.
+
+CREATE TABLE IF NOT EXISTS user_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- SHA-256 hex digest of the invitation token
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Role the new account is created with
+    is_admin BOOLEAN NOT NULL DEFAULT false,
+
+    -- Optional inventory share applied on acceptance
+    inventory_id INT REFERENCES inventories(id) ON DELETE CASCADE,
+    permission_level VARCHAR(20)
+        CHECK (permission_level IN ('view', 'edit_items', 'edit_inventory')),
+    -- Optional All Access grant from the inviter applied on acceptance
+    grant_all_access BOOLEAN NOT NULL DEFAULT false,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    accepted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    CONSTRAINT user_invitations_share_check
+        CHECK ((inventory_id IS NULL) = (permission_level IS NULL))
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_user_invitations_invited_by ON user_invitations(invited_by);
+CREATE INDEX IF NOT EXISTS idx_user_invitations_expires_at ON user_invitations(expires_at);
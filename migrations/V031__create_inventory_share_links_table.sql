@@ -0,0 +1,29 @@
+-- Create public read-only share links for inventories
+-- Links let people without an account (insurance adjusters, movers) view one
+-- inventory. Tokens are shown once at creation and stored as SHA-256 digests;
+-- a link can also require a password and expire.
+
+CREATE TABLE IF NOT EXISTS inventory_share_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inventory_id INT NOT NULL REFERENCES inventories(id) ON DELETE CASCADE,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+
+    -- SHA-256 hex digest of the full token
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    -- Leading characters of the token so links can be told apart
+    token_prefix VARCHAR(16) NOT NULL,
+    -- Argon2 hash of the optional link password
+    password_hash VARCHAR(255),
+
+    -- Access tracking
+    access_count BIGINT NOT NULL DEFAULT 0,
+    last_accessed_at TIMESTAMPTZ,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_inventory_share_links_inventory_id ON inventory_share_links(inventory_id);
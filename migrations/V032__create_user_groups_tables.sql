@@ -0,0 +1,38 @@
+-- Create household groups that inventories can be shared with
+-- A group has members and managers. Sharing an inventory with a group gives
+-- every current member that permission level, so adding someone to the group
+-- gives them access to everything shared with it and removing them takes it away.
+
+CREATE TABLE IF NOT EXISTS user_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS user_group_members (
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Managers can rename the group and add or remove members
+    role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('member', 'manager')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS inventory_group_shares (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inventory_id INT NOT NULL REFERENCES inventories(id) ON DELETE CASCADE,
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    shared_by_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    permission_level VARCHAR(20) NOT NULL
+        CHECK (permission_level IN ('view', 'edit_items', 'edit_inventory')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_inventory_group_share UNIQUE (inventory_id, group_id)
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_user_group_members_user_id ON user_group_members(user_id);
+CREATE INDEX IF NOT EXISTS idx_inventory_group_shares_group_id ON inventory_group_shares(group_id);
@@ -0,0 +1,20 @@
+-- Let inventory shares and All Access grants start later and expire
+-- Both columns are optional: a share without `starts_at` is active right away
+-- and one without `expires_at` lasts until it is removed. Shares outside their
+-- window are ignored when resolving access and expired rows are cleaned up.
+
+ALTER TABLE inventory_shares ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ;
+ALTER TABLE inventory_shares ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE inventory_shares ADD CONSTRAINT inventory_shares_schedule_check
+    CHECK (starts_at IS NULL OR expires_at IS NULL OR expires_at > starts_at);
+
+ALTER TABLE user_access_grants ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ;
+ALTER TABLE user_access_grants ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE user_access_grants ADD CONSTRAINT user_access_grants_schedule_check
+    CHECK (starts_at IS NULL OR expires_at IS NULL OR expires_at > starts_at);
+
+-- Indexes for the expiry cleanup
+CREATE INDEX IF NOT EXISTS idx_inventory_shares_expires_at
+    ON inventory_shares(expires_at) WHERE expires_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_user_access_grants_expires_at
+    ON user_access_grants(expires_at) WHERE expires_at IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Let recipients accept or decline inventory shares and All Access grants
+-- New shares and grants start out 'pending' and give no access until the
+-- recipient accepts them. Existing rows were given without asking, so they
+-- are treated as already accepted.
+
+ALTER TABLE inventory_shares ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'accepted'
+    CHECK (status IN ('pending', 'accepted', 'declined'));
+
+ALTER TABLE user_access_grants ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'accepted'
+    CHECK (status IN ('pending', 'accepted', 'declined'));
+
+-- Indexes for listing a user's pending invitations
+CREATE INDEX IF NOT EXISTS idx_inventory_shares_pending
+    ON inventory_shares(shared_with_user_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_user_access_grants_pending
+    ON user_access_grants(grantee_user_id) WHERE status = 'pending';
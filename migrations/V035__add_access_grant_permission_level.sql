@@ -0,0 +1,8 @@
+-- Give All Access grants a permission level
+-- 'view', 'edit_items' and 'edit_inventory' match the per-inventory share levels
+-- and apply to every inventory of the grantor; 'full' is the original All Access,
+-- which also allows deleting inventories and managing sharing. Existing grants
+-- keep full access.
+
+ALTER TABLE user_access_grants ADD COLUMN IF NOT EXISTS permission_level VARCHAR(20) NOT NULL DEFAULT 'full'
+    CHECK (permission_level IN ('view', 'edit_items', 'edit_inventory', 'full'));
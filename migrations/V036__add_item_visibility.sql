@@ -0,0 +1,9 @@
+-- Let owners hide individual items inside shared inventories
+-- 'everyone' is visible to anyone who can open the inventory (and public share links);
+-- 'edit_items' and 'edit_inventory' need a share or All Access grant of at least that
+-- level; 'owner' is only visible to the owner and users with full All Access.
+
+ALTER TABLE items ADD COLUMN IF NOT EXISTS visibility VARCHAR(20) NOT NULL DEFAULT 'everyone'
+    CHECK (visibility IN ('everyone', 'edit_items', 'edit_inventory', 'owner'));
+
+CREATE INDEX IF NOT EXISTS idx_items_private ON items(inventory_id) WHERE visibility <> 'everyone';
@@ -0,0 +1,26 @@
+-- Let owners define their own roles for sharing, made of individual capabilities
+-- The three fixed permission levels stay available as built-in roles. A share with a
+-- custom role keeps the closest built-in level in permission_level, so item visibility,
+-- backups and deleted roles fall back to that level.
+
+CREATE TABLE IF NOT EXISTS inventory_roles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    capabilities TEXT[] NOT NULL DEFAULT ARRAY['view']::TEXT[]
+        CHECK (capabilities <@ ARRAY['view', 'add_items', 'edit_items', 'remove_items',
+            'manage_organizers', 'edit_inventory', 'manage_shares', 'export']::TEXT[]),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_inventory_role_name UNIQUE (owner_user_id, name)
+);
+
+ALTER TABLE inventory_shares
+    ADD COLUMN IF NOT EXISTS role_id UUID REFERENCES inventory_roles(id) ON DELETE SET NULL;
+ALTER TABLE inventory_group_shares
+    ADD COLUMN IF NOT EXISTS role_id UUID REFERENCES inventory_roles(id) ON DELETE SET NULL;
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_inventory_shares_role_id ON inventory_shares(role_id) WHERE role_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_inventory_group_shares_role_id ON inventory_group_shares(role_id) WHERE role_id IS NOT NULL;
@@ -0,0 +1,28 @@
+-- Keep a history of changes to each item
+-- Every create, edit, organizer value change and revert adds a numbered revision
+-- listing the fields that changed with their old and new values. The actor is kept
+-- as a plain value, like the audit log, so history outlives deleted users.
+
+CREATE TABLE IF NOT EXISTS item_history (
+    id BIGSERIAL PRIMARY KEY,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+
+    -- created, updated or reverted
+    action VARCHAR(16) NOT NULL CHECK (action IN ('created', 'updated', 'reverted')),
+    -- The revision a revert went back to
+    reverted_to_revision INTEGER,
+
+    changed_by_user_id UUID,
+    changed_by_username VARCHAR(255),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- [{"field": "purchase_price", "old_value": 899.0, "new_value": 749.0}, ...]
+    -- Organizer values use the field "organizer:<type id>" and carry a label
+    changes JSONB NOT NULL DEFAULT '[]'::JSONB,
+
+    CONSTRAINT unique_item_revision UNIQUE (item_id, revision)
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_item_history_changed_by ON item_history(changed_by_user_id) WHERE changed_by_user_id IS NOT NULL;
@@ -0,0 +1,29 @@
+-- Move deleted inventories, items and organizer types to a trash bin instead of
+-- removing them. A trashed row has deleted_at set and is left out of every query
+-- until it is restored or purged. Trashing an inventory also trashes its live items
+-- and organizer types with trashed_with_inventory set, so restoring the inventory
+-- brings back exactly those and not things that were trashed on their own before.
+
+ALTER TABLE inventories
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE items
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS trashed_with_inventory BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE organizer_types
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_by_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS trashed_with_inventory BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- A trashed organizer type shouldn't block creating a new one with the same name
+ALTER TABLE organizer_types DROP CONSTRAINT IF EXISTS unique_organizer_name_per_inventory;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_organizer_name_per_inventory
+    ON organizer_types(inventory_id, name) WHERE deleted_at IS NULL;
+
+-- Indexes for the trash listing and the auto-purge
+CREATE INDEX IF NOT EXISTS idx_inventories_deleted_at ON inventories(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_items_deleted_at ON items(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_organizer_types_deleted_at ON organizer_types(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,22 @@
+-- Link local accounts to the external identity sources allowed to sign in to them
+-- An SSO or LDAP login only reaches an account linked to that source, so an
+-- identity that merely shares a username with a local account (such as the
+-- initial admin) can't take it over. Accounts created by an external login are
+-- linked automatically; an admin can link an existing account, and the subject
+-- (the OIDC `sub` or the directory entry's DN) is then filled in by the first
+-- login from that source.
+
+CREATE TABLE IF NOT EXISTS user_identity_links (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 'sso' or 'ldap'
+    source VARCHAR(20) NOT NULL CHECK (source IN ('sso', 'ldap')),
+    -- Stable id of the identity at the source (the OIDC `sub` claim, the LDAP
+    -- entry's DN); NULL until the first login after an admin linked the account
+    subject VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, source)
+);
+
+-- Indexes
+CREATE UNIQUE INDEX IF NOT EXISTS unique_user_identity_subject
+    ON user_identity_links(source, subject) WHERE subject IS NOT NULL;
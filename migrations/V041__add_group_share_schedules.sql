@@ -0,0 +1,12 @@
+-- Let group shares start later and expire, like direct inventory shares
+-- A group share outside its window gives the group's members nothing and
+-- expired rows are cleaned up along with the other shares.
+
+ALTER TABLE inventory_group_shares ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ;
+ALTER TABLE inventory_group_shares ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE inventory_group_shares ADD CONSTRAINT inventory_group_shares_schedule_check
+    CHECK (starts_at IS NULL OR expires_at IS NULL OR expires_at > starts_at);
+
+-- Index for the expiry cleanup
+CREATE INDEX IF NOT EXISTS idx_inventory_group_shares_expires_at
+    ON inventory_group_shares(expires_at) WHERE expires_at IS NOT NULL;
@@ -0,0 +1,167 @@
+//! Security audit log
+//!
+//! Provides the helper other endpoints use to append to the audit log, and the
+//! admin endpoints for searching it and exporting it as CSV.
+
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+
+use crate::db::DatabaseService;
+use crate::models::{ApiResponse, AuditEvent, AuditEventQuery, ErrorResponse, NewAuditEvent};
+
+use super::auth::{require_admin, session_client_info};
+
+/// Entries returned per page when no limit is given
+const DEFAULT_PAGE_SIZE: i64 = 100;
+
+/// Largest page the query endpoint will return
+const MAX_PAGE_SIZE: i64 = 1000;
+
+/// Longest IP address the log stores
+const MAX_IP_ADDRESS_LENGTH: usize = 45;
+
+/// Actor recorded for changes made with the command-line admin tool
+pub const CLI_ACTOR: &str = "cli";
+
+/// Append an event to the audit log, filling in the client's IP address and
+/// user agent from the request. Failures are logged but never fail the request
+/// being audited.
+pub async fn record(db_service: &DatabaseService, req: &HttpRequest, mut event: NewAuditEvent) {
+    let (user_agent, ip_address) = session_client_info(req);
+    event.user_agent = user_agent;
+    event.ip_address = ip_address.map(|ip| ip.chars().take(MAX_IP_ADDRESS_LENGTH).collect());
+
+    insert_event(db_service, &event).await;
+}
+
+/// Append an event made by a command-line admin subcommand, which has no
+/// request or signed-in user; the actor is recorded as [`CLI_ACTOR`].
+pub async fn record_cli(db_service: &DatabaseService, mut event: NewAuditEvent) {
+    event.actor_id = None;
+    event.actor_username = Some(CLI_ACTOR.to_string());
+
+    insert_event(db_service, &event).await;
+}
+
+async fn insert_event(db_service: &DatabaseService, event: &NewAuditEvent) {
+    if let Err(e) = db_service.create_audit_event(event).await {
+        warn!(
+            "Failed to record audit event {}: {}",
+            event.action.as_str(),
+            e
+        );
+    }
+}
+
+/// Format audit events as CSV, one row per event
+fn format_audit_events_as_csv(
+    events: &[AuditEvent],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for event in events {
+        writer.serialize(event)?;
+    }
+
+    writer.flush()?;
+    Ok(writer.into_inner()?)
+}
+
+/// Search the audit log (admin only)
+///
+/// Filters: `user_id` (actor or target), `action` (exact, or a prefix ending in
+/// `.` such as `auth.`), `from` and `to` (RFC 3339), plus `limit` and `offset`.
+#[get("/admin/audit-events")]
+pub async fn admin_get_audit_events(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    query: web::Query<AuditEventQuery>,
+) -> Result<impl Responder> {
+    let _auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let query = query.into_inner();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service.get_audit_events(&query, Some(limit)).await {
+        Ok(events) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} audit events", events.len())),
+            data: Some(events),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting audit events: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve audit events".to_string()),
+            }))
+        },
+    }
+}
+
+/// Export every audit event matching the filters as CSV (admin only)
+#[get("/admin/audit-events/export")]
+pub async fn admin_export_audit_events(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    query: web::Query<AuditEventQuery>,
+) -> Result<impl Responder> {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let events = match db_service.get_audit_events(&query, None).await {
+        Ok(events) => events,
+        Err(e) => {
+            error!("Error getting audit events for export: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve audit events".to_string()),
+            }));
+        },
+    };
+
+    match format_audit_events_as_csv(&events) {
+        Ok(csv_data) => {
+            let filename = format!(
+                "audit-log-{}.csv",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            );
+
+            info!(
+                "Admin {} exported {} audit events",
+                auth_ctx.username,
+                events.len()
+            );
+
+            Ok(HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"{filename}\""),
+                ))
+                .body(csv_data))
+        },
+        Err(e) => {
+            error!("Error formatting audit log CSV: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "CSV serialization error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
@@ -3,26 +3,39 @@
 //! Provides endpoints for login, registration, profile management,
 //! admin user management, and initial setup.
 
+use actix_web::http::{header, StatusCode};
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Result};
 use deadpool_postgres::Pool;
 use log::{error, info, warn};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use uuid::Uuid;
+use validator::Validate;
 
+use crate::auth::ldap::{self, LdapConfig, LdapLogin};
+use crate::auth::lockout;
+use crate::auth::proxy::{self, ProxyAuthConfig};
+use crate::auth::webauthn::WebauthnConfig;
 use crate::auth::{
-    extract_token, generate_partial_token, generate_token, hash_password, validate_password,
-    validate_username, verify_password, verify_token, AuthContext,
+    access_token_lifetime_minutes, extract_token, generate_api_token, generate_partial_token,
+    generate_password_reset_token, generate_random_secret, generate_refresh_token, generate_token,
+    hash_api_token, hash_password, hash_password_reset_token, hash_refresh_token, is_api_token,
+    item_id_from_path, jwt_token_lifetime_hours, validate_password, validate_username,
+    verify_password, verify_token, ApiTokenAccess, AuthContext, API_TOKEN_DISPLAY_LENGTH,
+    PASSWORD_RESET_DEFAULT_HOURS, PASSWORD_RESET_MAX_HOURS,
 };
 use crate::db::DatabaseService;
 use crate::models::{
-    AdminCreateUserRequest, AdminUpdateUserRequest, ApiResponse, ChangePasswordRequest,
-    ConfirmRecoveryCodesRequest, CreateInventoryShareRequest, CreateUserAccessGrantRequest,
-    ErrorResponse, InitialSetupRequest, LoginRequest, LoginResponse, LoginTotpRequiredResponse,
-    PermissionSource, RecoveryCodeUsedResponse, RecoveryCodesResponse, RecoveryCodesStatus,
-    SetupStatusResponse, TransferOwnershipRequest, TransferOwnershipResponse,
-    UpdateInventoryShareRequest, UpdateProfileRequest, UpdateUserSettingsRequest,
-    UseRecoveryCodeRequest, UserResponse,
+    AdminCreateUserRequest, AdminUpdateUserRequest, ApiResponse, AuditAction, AuditOutcome,
+    Capability, ChangePasswordRequest, ConfirmRecoveryCodesRequest, CreateApiTokenRequest,
+    CreateApiTokenResponse, CreateInventoryShareRequest, CreatePasswordResetRequest,
+    CreateUserAccessGrantRequest, ErrorResponse, IdentitySource, InitialSetupRequest, LoginRequest,
+    LoginResponse, LoginTotpRequiredResponse, MyInventoriesResponse, NewAuditEvent,
+    PasswordResetLinkResponse, RecoveryCodeUsedResponse, RecoveryCodesResponse,
+    RecoveryCodesStatus, RedeemPasswordResetRequest, RefreshTokenRequest, SetupStatusResponse,
+    ShareStatus, TransferOwnershipRequest, TransferOwnershipResponse, UpdateInventoryShareRequest,
+    UpdateProfileRequest, UpdateUserAccessGrantRequest, UpdateUserSettingsRequest,
+    UseRecoveryCodeRequest, User, UserResponse,
 };
 
 // ==================== Helper Functions ====================
@@ -33,6 +46,16 @@ pub async fn get_auth_context_from_request(
     pool: &Pool,
 ) -> Result<AuthContext, HttpResponse> {
     let Some(token) = extract_token(req) else {
+        // Without a token, fall back to identity headers from a trusted proxy
+        if let Some(user) = get_proxy_user(req, pool).await? {
+            return Ok(AuthContext {
+                user_id: user.id,
+                username: user.username,
+                is_admin: user.is_admin,
+                session_id: None,
+                api_token: None,
+            });
+        }
         return Err(HttpResponse::Unauthorized().json(ErrorResponse {
             success: false,
             error: "No authentication token provided".to_string(),
@@ -40,6 +63,10 @@ pub async fn get_auth_context_from_request(
         }));
     };
 
+    if is_api_token(&token) {
+        return get_auth_context_from_api_token(req, pool, &token).await;
+    }
+
     let claims = match verify_token(&token) {
         Ok(c) => c,
         Err(e) => {
@@ -68,33 +95,34 @@ pub async fn get_auth_context_from_request(
         }));
     };
 
+    let Some(session_id) = auth_ctx.session_id else {
+        return Err(HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "Token is not bound to a session".to_string(),
+            message: Some("Please log in again".to_string()),
+        }));
+    };
+
     // Verify user still exists and is active
     let db_service = DatabaseService::new(pool.clone());
-    match db_service.get_user_by_id(auth_ctx.user_id).await {
-        Ok(Some(user)) => {
-            if !user.is_active {
-                return Err(HttpResponse::Forbidden().json(ErrorResponse {
-                    success: false,
-                    error: "Account is deactivated".to_string(),
-                    message: Some(
-                        "Your account has been deactivated. Contact an administrator.".to_string(),
-                    ),
-                }));
-            }
-        },
-        Ok(None) => {
+    load_active_user(&db_service, auth_ctx.user_id).await?;
+
+    // Verify the session hasn't been revoked (logout, password change, deactivation)
+    match db_service.touch_session(session_id, auth_ctx.user_id).await {
+        Ok(true) => {},
+        Ok(false) => {
             return Err(HttpResponse::Unauthorized().json(ErrorResponse {
                 success: false,
-                error: "User not found".to_string(),
+                error: "Session expired or revoked".to_string(),
                 message: Some("Please log in again".to_string()),
             }));
         },
         Err(e) => {
-            error!("Database error verifying user: {}", e);
+            error!("Database error verifying session: {}", e);
             return Err(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
                 error: "Database error".to_string(),
-                message: Some("Unable to verify user".to_string()),
+                message: Some("Unable to verify session".to_string()),
             }));
         },
     }
@@ -102,6 +130,158 @@ pub async fn get_auth_context_from_request(
     Ok(auth_ctx)
 }
 
+/// Authenticate a request made with a personal API token
+async fn get_auth_context_from_api_token(
+    req: &HttpRequest,
+    pool: &Pool,
+    token: &str,
+) -> Result<AuthContext, HttpResponse> {
+    let db_service = DatabaseService::new(pool.clone());
+    let (user_id, api_token) = match db_service.use_api_token(&hash_api_token(token)).await {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            return Err(HttpResponse::Unauthorized().json(ErrorResponse {
+                success: false,
+                error: "Invalid or expired API token".to_string(),
+                message: Some("Create a new API token in your account settings".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Database error verifying API token: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: Some("Unable to verify API token".to_string()),
+            }));
+        },
+    };
+
+    let user = load_active_user(&db_service, user_id).await?;
+    let access = ApiTokenAccess {
+        token_id: api_token.id,
+        scopes: api_token.scopes,
+        inventory_ids: api_token.inventory_ids,
+    };
+
+    // Item paths don't name an inventory; a restricted token is checked
+    // against the inventory the item belongs to
+    let item_inventory_id = match item_id_from_path(req.path()) {
+        Some(item_id) if access.inventory_ids.is_some() => {
+            match db_service.get_item_by_id(item_id).await {
+                Ok(item) => item.map(|item| item.inventory_id),
+                Err(e) => {
+                    error!("Database error loading item for API token check: {}", e);
+                    return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                        success: false,
+                        error: "Database error".to_string(),
+                        message: Some("Unable to verify API token".to_string()),
+                    }));
+                },
+            }
+        },
+        _ => None,
+    };
+
+    if let Err(reason) = access.check_request(req, item_inventory_id) {
+        return Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: reason,
+            message: Some("This API token does not permit the request".to_string()),
+        }));
+    }
+
+    Ok(AuthContext {
+        user_id: user.id,
+        username: user.username,
+        is_admin: user.is_admin,
+        session_id: None,
+        api_token: Some(access),
+    })
+}
+
+/// Resolve the user named by a trusted authenticating proxy, if proxy
+/// authentication is enabled and the request carries the identity headers
+async fn get_proxy_user(req: &HttpRequest, pool: &Pool) -> Result<Option<User>, HttpResponse> {
+    let Some(config) = req.app_data::<web::Data<ProxyAuthConfig>>() else {
+        return Ok(None);
+    };
+
+    let identity = match proxy::identity_from_request(config, req) {
+        Ok(Some(identity)) => identity,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            warn!("Refusing proxy authentication: {}", e);
+            return Err(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Proxy authentication not accepted".to_string(),
+                message: Some(
+                    "Identity headers are only accepted from trusted proxies".to_string(),
+                ),
+            }));
+        },
+    };
+
+    let external = ExternalIdentity {
+        username: &identity.username,
+        full_name: identity.full_name.as_deref(),
+        is_admin: config
+            .admin_group
+            .as_ref()
+            .map(|group| identity.groups.contains(group)),
+        auto_provision: config.auto_provision,
+        link: None,
+        source: "proxy",
+    };
+    let db_service = DatabaseService::new(pool.clone());
+    match resolve_external_user(&db_service, &external).await {
+        Ok(user) => Ok(Some(user)),
+        Err(ExternalLoginError::Rejected(message)) => {
+            Err(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Login not allowed".to_string(),
+                message: Some(message),
+            }))
+        },
+        Err(ExternalLoginError::Internal) => {
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: Some("Unable to verify user".to_string()),
+            }))
+        },
+    }
+}
+
+/// Load the user behind a token, rejecting deleted and deactivated accounts
+async fn load_active_user(
+    db_service: &DatabaseService,
+    user_id: Uuid,
+) -> Result<User, HttpResponse> {
+    match db_service.get_user_by_id(user_id).await {
+        Ok(Some(user)) if user.is_active => Ok(user),
+        Ok(Some(_)) => Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Account is deactivated".to_string(),
+            message: Some(
+                "Your account has been deactivated. Contact an administrator.".to_string(),
+            ),
+        })),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "User not found".to_string(),
+            message: Some("Please log in again".to_string()),
+        })),
+        Err(e) => {
+            error!("Database error verifying user: {}", e);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: Some("Unable to verify user".to_string()),
+            }))
+        },
+    }
+}
+
 /// Require admin privileges
 pub async fn require_admin(req: &HttpRequest, pool: &Pool) -> Result<AuthContext, HttpResponse> {
     let auth_ctx = get_auth_context_from_request(req, pool).await?;
@@ -117,6 +297,267 @@ pub async fn require_admin(req: &HttpRequest, pool: &Pool) -> Result<AuthContext
     Ok(auth_ctx)
 }
 
+/// Maximum stored length of a session's user agent string
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Device details recorded for a session: (user agent, IP address)
+pub(crate) fn session_client_info(req: &HttpRequest) -> (Option<String>, Option<String>) {
+    let user_agent = req
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+    let proxy_config = req.app_data::<web::Data<ProxyAuthConfig>>();
+    let ip_address =
+        proxy::client_ip(proxy_config.map(web::Data::get_ref), req).map(|ip| ip.to_string());
+
+    (user_agent, ip_address)
+}
+
+/// Start a login session for a user and issue its access and refresh tokens
+pub async fn start_session(
+    db_service: &DatabaseService,
+    user: User,
+    req: &HttpRequest,
+) -> Result<LoginResponse, Box<dyn std::error::Error>> {
+    let (user_agent, ip_address) = session_client_info(req);
+    let refresh_token = generate_refresh_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(jwt_token_lifetime_hours());
+
+    // Opportunistically clear out old sessions; failure here shouldn't block login
+    if let Err(e) = db_service.delete_stale_sessions().await {
+        warn!("Failed to delete stale sessions: {}", e);
+    }
+
+    let session_id = db_service
+        .create_session(
+            user.id,
+            &hash_refresh_token(&refresh_token),
+            user_agent.as_deref(),
+            ip_address.as_deref(),
+            expires_at,
+        )
+        .await?;
+    let token = generate_token(&user, session_id)?;
+
+    super::audit::record(
+        db_service,
+        req,
+        NewAuditEvent::new(AuditAction::Login)
+            .actor(user.id, &user.username)
+            .target("session", &session_id),
+    )
+    .await;
+
+    Ok(LoginResponse {
+        token,
+        refresh_token,
+        expires_in: access_token_lifetime_minutes() * 60,
+        user: user.into(),
+    })
+}
+
+/// A user authenticated by an external identity source (OIDC or LDAP)
+pub struct ExternalIdentity<'a> {
+    pub username: &'a str,
+    pub full_name: Option<&'a str>,
+    /// Admin group membership, or `None` to leave `is_admin` alone
+    pub is_admin: Option<bool>,
+    /// Create a local account when no username matches
+    pub auto_provision: bool,
+    /// The source the account has to be linked to and the identity's subject there.
+    /// `None` for trusted proxies, which name the local account directly.
+    pub link: Option<(IdentitySource, &'a str)>,
+    /// Name of the source, for log messages
+    pub source: &'static str,
+}
+
+/// Why an external login could not be mapped to a local account
+#[derive(Debug)]
+pub enum ExternalLoginError {
+    /// The login is refused; the message is safe to show the user
+    Rejected(String),
+    Internal,
+}
+
+impl std::fmt::Display for ExternalLoginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExternalLoginError::Rejected(msg) => write!(f, "{msg}"),
+            ExternalLoginError::Internal => write!(f, "An internal error occurred"),
+        }
+    }
+}
+
+/// Find the account linked to an external identity
+///
+/// An account with the same username only matches if an admin linked it to the
+/// source ahead of its first login, which binds the link to this identity.
+async fn find_linked_user(
+    db_service: &DatabaseService,
+    identity: &ExternalIdentity<'_>,
+    link_source: IdentitySource,
+    subject: &str,
+) -> Result<Option<User>, ExternalLoginError> {
+    let internal_error = |e: Box<dyn std::error::Error>| {
+        error!("Database error during {} login: {}", identity.source, e);
+        ExternalLoginError::Internal
+    };
+
+    if let Some(user) = db_service
+        .get_user_by_identity(link_source, subject)
+        .await
+        .map_err(internal_error)?
+    {
+        return Ok(Some(user));
+    }
+    let Some(user) = db_service
+        .get_user_by_username(identity.username)
+        .await
+        .map_err(internal_error)?
+    else {
+        return Ok(None);
+    };
+
+    let awaiting_first_login = db_service
+        .get_user_identity_links(user.id)
+        .await
+        .map_err(internal_error)?
+        .iter()
+        .any(|link| link.source == link_source && link.subject.is_none());
+    if !awaiting_first_login {
+        warn!(
+            "Refused {} login: account {} isn't linked to it",
+            identity.source, user.username
+        );
+        return Err(ExternalLoginError::Rejected(format!(
+            "The account '{}' isn't linked to this {} login. Ask an administrator to link it.",
+            user.username, identity.source
+        )));
+    }
+    db_service
+        .link_user_identity(user.id, link_source, Some(subject))
+        .await
+        .map_err(internal_error)?;
+    info!(
+        "Linked account {} to its {} identity on first login",
+        user.username, identity.source
+    );
+    Ok(Some(user))
+}
+
+/// Find (or create) the local account for an external login and sync its admin flag
+pub async fn resolve_external_user(
+    db_service: &DatabaseService,
+    identity: &ExternalIdentity<'_>,
+) -> Result<User, ExternalLoginError> {
+    let source = identity.source;
+    let username = identity.username;
+    let internal_error = |e: Box<dyn std::error::Error>| {
+        error!("Database error during {} login: {}", source, e);
+        ExternalLoginError::Internal
+    };
+
+    let existing = match identity.link {
+        Some((link_source, subject)) => {
+            find_linked_user(db_service, identity, link_source, subject).await?
+        },
+        None => db_service
+            .get_user_by_username(username)
+            .await
+            .map_err(internal_error)?,
+    };
+
+    let user = match existing {
+        Some(user) => user,
+        None if identity.auto_provision => {
+            validate_username(username).map_err(|msg| {
+                ExternalLoginError::Rejected(format!("Cannot create account '{username}': {msg}"))
+            })?;
+
+            // External accounts get a random password nobody knows; an admin can set one later
+            let password_hash = hash_password(generate_random_secret(48))
+                .await
+                .map_err(|e| internal_error(format!("{e}").into()))?;
+            let user = db_service
+                .create_user(
+                    username,
+                    identity.full_name.unwrap_or(username),
+                    &password_hash,
+                    identity.is_admin.unwrap_or(false),
+                    true,
+                )
+                .await
+                .map_err(internal_error)?;
+            if let Some((link_source, subject)) = identity.link {
+                db_service
+                    .link_user_identity(user.id, link_source, Some(subject))
+                    .await
+                    .map_err(internal_error)?;
+            }
+            if let Err(e) = db_service.create_user_settings(user.id).await {
+                warn!(
+                    "Failed to create user settings for {}: {}",
+                    user.username, e
+                );
+            }
+            info!(
+                "Provisioned account {} from {} login",
+                user.username, source
+            );
+            user
+        },
+        None => {
+            return Err(ExternalLoginError::Rejected(format!(
+                "No account matches '{username}'. Ask an administrator to create one."
+            )));
+        },
+    };
+
+    if !user.is_active {
+        return Err(ExternalLoginError::Rejected(
+            "Your account has been deactivated. Contact an administrator.".to_string(),
+        ));
+    }
+
+    match identity.is_admin {
+        Some(is_admin) if is_admin != user.is_admin => {
+            // Never demote the last admin, or nobody could manage the instance
+            if !is_admin
+                && db_service
+                    .count_admin_users()
+                    .await
+                    .map_err(internal_error)?
+                    <= 1
+            {
+                warn!(
+                    "Not removing admin from {}: they are the only administrator",
+                    user.username
+                );
+                return Ok(user);
+            }
+            let update = AdminUpdateUserRequest {
+                username: None,
+                full_name: None,
+                password: None,
+                is_admin: Some(is_admin),
+                is_active: None,
+            };
+            info!(
+                "Setting admin={} for {} from {} group membership",
+                is_admin, user.username, source
+            );
+            Ok(db_service
+                .admin_update_user(user.id, update)
+                .await
+                .map_err(internal_error)?
+                .unwrap_or(user))
+        },
+        _ => Ok(user),
+    }
+}
+
 // ==================== Public Endpoints ====================
 
 /// Check if initial setup is needed (no users exist)
@@ -149,6 +590,7 @@ pub async fn get_setup_status(pool: web::Data<Pool>) -> Result<impl Responder> {
 #[post("/auth/setup")]
 pub async fn initial_setup(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     req: web::Json<InitialSetupRequest>,
 ) -> Result<impl Responder> {
     let db_service = DatabaseService::new(pool.get_ref().clone());
@@ -229,9 +671,24 @@ pub async fn initial_setup(
         warn!("Failed to create user settings: {}", e);
     }
 
-    // Generate token
-    let token = match generate_token(&user) {
-        Ok(t) => t,
+    info!(
+        "Initial setup completed - created admin user: {}",
+        user.username
+    );
+
+    super::audit::record(
+        &db_service,
+        &http_req,
+        NewAuditEvent::new(AuditAction::UserCreated)
+            .actor(user.id, &user.username)
+            .target("user", &user.id)
+            .details("Initial admin account"),
+    )
+    .await;
+
+    // Start a session for the new admin
+    let session = match start_session(&db_service, user, &http_req).await {
+        Ok(session) => session,
         Err(e) => {
             error!("Error generating token: {}", e);
             return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -242,33 +699,103 @@ pub async fn initial_setup(
         },
     };
 
-    info!(
-        "Initial setup completed - created admin user: {}",
-        user.username
-    );
-
     Ok(HttpResponse::Created().json(ApiResponse {
         success: true,
-        data: Some(LoginResponse {
-            token,
-            user: user.into(),
-        }),
+        data: Some(session),
         message: Some("Setup completed successfully".to_string()),
         error: None,
     }))
 }
 
-/// User login
-#[post("/auth/login")]
-pub async fn login(pool: web::Data<Pool>, req: web::Json<LoginRequest>) -> Result<impl Responder> {
-    let db_service = DatabaseService::new(pool.get_ref().clone());
+/// Refuse password attempts while the username is throttled after repeated failures.
+/// Unknown usernames are tracked the same way, so this doesn't reveal which exist.
+async fn check_login_lockout(db_service: &DatabaseService, key: &str) -> Result<(), HttpResponse> {
+    let locked_until = match db_service.get_login_locked_until(key).await {
+        Ok(Some(locked_until)) => locked_until,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            // Don't lock everyone out if the attempts table is unavailable
+            warn!("Failed to check login lockout: {}", e);
+            return Ok(());
+        },
+    };
 
+    let wait_seconds = (locked_until - chrono::Utc::now()).num_seconds().max(1);
+    let wait = if wait_seconds >= 60 {
+        format!("{} minutes", (wait_seconds + 59) / 60)
+    } else {
+        format!("{wait_seconds} seconds")
+    };
+    Err(HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, wait_seconds.to_string()))
+        .json(ErrorResponse {
+            success: false,
+            error: "Too many failed login attempts".to_string(),
+            message: Some(format!("Please wait {wait} before trying again")),
+        }))
+}
+
+/// Audit a rejected login and count a rejected password against the username,
+/// throttling it once there have been too many, and pass the rejection through
+async fn record_login_failure(
+    db_service: &DatabaseService,
+    http_req: &HttpRequest,
+    username: &str,
+    key: &str,
+    response: HttpResponse,
+) -> HttpResponse {
+    let outcome = match response.status() {
+        StatusCode::UNAUTHORIZED => AuditOutcome::Failure,
+        StatusCode::FORBIDDEN => AuditOutcome::Denied,
+        _ => return response,
+    };
+    super::audit::record(
+        db_service,
+        http_req,
+        NewAuditEvent::new(AuditAction::LoginFailed)
+            .claimed_username(username)
+            .outcome(outcome),
+    )
+    .await;
+
+    if outcome != AuditOutcome::Failure {
+        return response;
+    }
+
+    let now = chrono::Utc::now();
+    let reset_before = now - chrono::Duration::hours(lockout::RESET_AFTER_HOURS);
+    match db_service.record_failed_login(key, reset_before).await {
+        Ok(failed_attempts) => {
+            if let Some(delay) = lockout::lockout_delay(failed_attempts) {
+                if failed_attempts >= lockout::LOCKOUT_THRESHOLD {
+                    warn!(
+                        "Login for '{}' locked for {} minutes after {} failed attempts",
+                        key,
+                        delay.num_minutes(),
+                        failed_attempts
+                    );
+                }
+                if let Err(e) = db_service.set_login_locked_until(key, now + delay).await {
+                    warn!("Failed to record login lockout: {}", e);
+                }
+            }
+        },
+        Err(e) => warn!("Failed to record failed login: {}", e),
+    }
+    response
+}
+
+/// Verify a username and password against the local account
+async fn local_login(
+    db_service: &DatabaseService,
+    req: &LoginRequest,
+) -> Result<User, HttpResponse> {
     // Find user by username
     let user = match db_service.get_user_by_username(&req.username).await {
         Ok(Some(u)) => u,
         Ok(None) => {
             // Don't reveal whether username exists
-            return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+            return Err(HttpResponse::Unauthorized().json(ErrorResponse {
                 success: false,
                 error: "Invalid credentials".to_string(),
                 message: Some("Username or password is incorrect".to_string()),
@@ -276,7 +803,7 @@ pub async fn login(pool: web::Data<Pool>, req: web::Json<LoginRequest>) -> Resul
         },
         Err(e) => {
             error!("Database error during login: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
                 error: "Database error".to_string(),
                 message: None,
@@ -286,7 +813,7 @@ pub async fn login(pool: web::Data<Pool>, req: web::Json<LoginRequest>) -> Resul
 
     // Check if user is active
     if !user.is_active {
-        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+        return Err(HttpResponse::Forbidden().json(ErrorResponse {
             success: false,
             error: "Account deactivated".to_string(),
             message: Some(
@@ -301,7 +828,7 @@ pub async fn login(pool: web::Data<Pool>, req: web::Json<LoginRequest>) -> Resul
             Ok(valid) => valid,
             Err(e) => {
                 error!("Error verifying password: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                return Err(HttpResponse::InternalServerError().json(ErrorResponse {
                     success: false,
                     error: "Password verification failed".to_string(),
                     message: None,
@@ -310,81 +837,278 @@ pub async fn login(pool: web::Data<Pool>, req: web::Json<LoginRequest>) -> Resul
         };
 
     if !password_valid {
-        return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+        return Err(HttpResponse::Unauthorized().json(ErrorResponse {
             success: false,
             error: "Invalid credentials".to_string(),
             message: Some("Username or password is incorrect".to_string()),
         }));
     }
 
-    // Check if user has TOTP enabled with a mode that requires login verification
-    let totp_settings = db_service.get_totp_settings(user.id).await.ok().flatten();
-
-    if let Some(ref settings) = totp_settings {
-        if settings.is_enabled && settings.is_verified {
-            let mode: Result<crate::models::TotpMode, _> = settings.totp_mode.parse();
-            if let Ok(mode) = mode {
-                if mode.requires_login_totp() {
-                    // Generate a partial token (5-min, totp_pending=true)
-                    let partial_token = match generate_partial_token(&user) {
-                        Ok(t) => t,
-                        Err(e) => {
-                            error!("Error generating partial token: {}", e);
-                            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                                success: false,
-                                error: "Failed to generate token".to_string(),
-                                message: None,
-                            }));
-                        },
-                    };
-
-                    info!("User {} requires TOTP verification", user.username);
+    Ok(user)
+}
 
-                    return Ok(HttpResponse::Ok().json(ApiResponse {
-                        success: true,
-                        data: Some(LoginTotpRequiredResponse {
-                            requires_totp: true,
-                            partial_token,
-                            user: user.into(),
-                        }),
-                        message: Some("TOTP verification required".to_string()),
-                        error: None,
-                    }));
-                }
-            }
-        }
-    }
+/// Verify a username and password against the directory, if the user is in it.
+/// `Ok(None)` means the login should be checked against the local password instead.
+async fn ldap_login(
+    db_service: &DatabaseService,
+    config: &LdapConfig,
+    req: &LoginRequest,
+) -> Result<Option<User>, HttpResponse> {
+    let invalid_credentials = || {
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "Invalid credentials".to_string(),
+            message: Some("Username or password is incorrect".to_string()),
+        })
+    };
 
-    // Generate token (normal login - no TOTP or recovery_only mode)
-    let token = match generate_token(&user) {
-        Ok(t) => t,
+    // An existing account that isn't linked to the directory is a local account,
+    // even if the directory has an entry with the same username; it keeps its password
+    let local_only = match db_service.get_user_by_username(&req.username).await {
+        Ok(Some(user)) => db_service
+            .get_user_identity_links(user.id)
+            .await
+            .map(|links| !links.iter().any(|link| link.source == IdentitySource::Ldap)),
+        Ok(None) => Ok(false),
+        Err(e) => Err(e),
+    };
+    match local_only {
+        Ok(true) => return Ok(None),
+        Ok(false) => {},
         Err(e) => {
-            error!("Error generating token: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+            error!("Database error during LDAP login: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
-                error: "Failed to generate token".to_string(),
+                error: "An internal error occurred".to_string(),
                 message: None,
             }));
         },
-    };
+    }
 
-    info!("User logged in: {}", user.username);
+    let directory_user = match ldap::authenticate(config, &req.username, &req.password).await {
+        Ok(LdapLogin::Authenticated(directory_user)) => directory_user,
+        Ok(LdapLogin::InvalidPassword) => return Err(invalid_credentials()),
+        Ok(LdapLogin::NotFound) => return Ok(None),
+        Err(e) => {
+            // Keep local accounts usable while the directory is down
+            warn!(
+                "LDAP login for {} failed, trying local account: {}",
+                req.username, e
+            );
+            return Ok(None);
+        },
+    };
 
-    Ok(HttpResponse::Ok().json(ApiResponse {
+    let identity = ExternalIdentity {
+        username: &req.username,
+        full_name: directory_user.full_name.as_deref(),
+        is_admin: directory_user.is_admin,
+        auto_provision: config.auto_provision,
+        link: Some((IdentitySource::Ldap, &directory_user.dn)),
+        source: "LDAP",
+    };
+    match resolve_external_user(db_service, &identity).await {
+        Ok(user) => {
+            info!(
+                "Authenticated {} via LDAP ({})",
+                user.username, directory_user.dn
+            );
+            Ok(Some(user))
+        },
+        Err(ExternalLoginError::Rejected(message)) => {
+            Err(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Login not allowed".to_string(),
+                message: Some(message),
+            }))
+        },
+        Err(ExternalLoginError::Internal) => {
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// User login
+#[post("/auth/login")]
+pub async fn login(
+    pool: web::Data<Pool>,
+    ldap_config: Option<web::Data<LdapConfig>>,
+    http_req: HttpRequest,
+    req: web::Json<LoginRequest>,
+) -> Result<impl Responder> {
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let attempt_key = lockout::attempt_key(&req.username);
+    if let Err(response) = check_login_lockout(&db_service, &attempt_key).await {
+        super::audit::record(
+            &db_service,
+            &http_req,
+            NewAuditEvent::new(AuditAction::LoginLocked)
+                .claimed_username(&req.username)
+                .outcome(AuditOutcome::Denied),
+        )
+        .await;
+        return Ok(response);
+    }
+
+    // Accounts linked to the directory sign in through LDAP; local accounts (such as
+    // the admin created during initial setup) and anyone not in the directory use the
+    // local password
+    let ldap_user = match ldap_config {
+        Some(config) => match ldap_login(&db_service, &config, &req).await {
+            Ok(user) => user,
+            Err(response) => {
+                return Ok(record_login_failure(
+                    &db_service,
+                    &http_req,
+                    &req.username,
+                    &attempt_key,
+                    response,
+                )
+                .await)
+            },
+        },
+        None => None,
+    };
+
+    let user = match ldap_user {
+        Some(user) => user,
+        None => match local_login(&db_service, &req).await {
+            Ok(user) => user,
+            Err(response) => {
+                return Ok(record_login_failure(
+                    &db_service,
+                    &http_req,
+                    &req.username,
+                    &attempt_key,
+                    response,
+                )
+                .await)
+            },
+        },
+    };
+
+    if let Err(e) = db_service.clear_failed_logins(&attempt_key).await {
+        warn!("Failed to reset failed logins for {}: {}", user.username, e);
+    }
+
+    // Check if user has TOTP enabled with a mode that requires login verification
+    let totp_settings = db_service.get_totp_settings(user.id).await.ok().flatten();
+
+    if let Some(ref settings) = totp_settings {
+        if settings.is_enabled && settings.is_verified {
+            let mode: Result<crate::models::TotpMode, _> = settings.totp_mode.parse();
+            if let Ok(mode) = mode {
+                if mode.requires_login_totp() {
+                    // Generate a partial token (5-min, totp_pending=true)
+                    let partial_token = match generate_partial_token(&user) {
+                        Ok(t) => t,
+                        Err(e) => {
+                            error!("Error generating partial token: {}", e);
+                            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                                success: false,
+                                error: "Failed to generate token".to_string(),
+                                message: None,
+                            }));
+                        },
+                    };
+
+                    info!("User {} requires TOTP verification", user.username);
+
+                    // A registered passkey can stand in for the code
+                    let webauthn_available =
+                        http_req.app_data::<web::Data<WebauthnConfig>>().is_some()
+                            && db_service
+                                .get_user_webauthn_credential_ids(user.id)
+                                .await
+                                .is_ok_and(|ids| !ids.is_empty());
+
+                    return Ok(HttpResponse::Ok().json(ApiResponse {
+                        success: true,
+                        data: Some(LoginTotpRequiredResponse {
+                            requires_totp: true,
+                            partial_token,
+                            webauthn_available,
+                            user: user.into(),
+                        }),
+                        message: Some("TOTP verification required".to_string()),
+                        error: None,
+                    }));
+                }
+            }
+        }
+    }
+
+    info!("User logged in: {}", user.username);
+
+    // Start a session (normal login - no TOTP or recovery_only mode)
+    let session = match start_session(&db_service, user, &http_req).await {
+        Ok(session) => session,
+        Err(e) => {
+            error!("Error generating token: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to generate token".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
-        data: Some(LoginResponse {
-            token,
-            user: user.into(),
-        }),
+        data: Some(session),
         message: Some("Login successful".to_string()),
         error: None,
     }))
 }
 
+/// Start a session for the user vouched for by a trusted authenticating proxy,
+/// so the web app can skip the login form
+#[post("/auth/proxy/session")]
+pub async fn proxy_session(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let user = match get_proxy_user(&req, pool.get_ref()).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+                success: false,
+                error: "No proxy identity".to_string(),
+                message: Some("Please log in".to_string()),
+            }));
+        },
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match start_session(&db_service, user, &req).await {
+        Ok(session) => {
+            info!("User {} signed in via proxy", session.user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(session),
+                message: Some("Login successful".to_string()),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error starting proxy session: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to generate token".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
 /// Register new user (public registration after initial setup)
 #[post("/auth/register")]
 pub async fn register(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     req: web::Json<crate::models::CreateUserRequest>,
 ) -> Result<impl Responder> {
     let db_service = DatabaseService::new(pool.get_ref().clone());
@@ -481,9 +1205,20 @@ pub async fn register(
         );
     }
 
-    // Generate token for immediate login
-    let token = match generate_token(&user) {
-        Ok(t) => t,
+    info!("New user registered: {}", user.username);
+
+    super::audit::record(
+        &db_service,
+        &http_req,
+        NewAuditEvent::new(AuditAction::UserRegistered)
+            .actor(user.id, &user.username)
+            .target("user", &user.id),
+    )
+    .await;
+
+    // Start a session for immediate login
+    let session = match start_session(&db_service, user, &http_req).await {
+        Ok(session) => session,
         Err(e) => {
             error!("Error generating token: {}", e);
             return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -494,15 +1229,92 @@ pub async fn register(
         },
     };
 
-    info!("New user registered: {}", user.username);
-
     Ok(HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(session),
+        message: Some("Registration successful".to_string()),
+        error: None,
+    }))
+}
+
+/// Exchange a refresh token for a new access token and refresh token
+///
+/// Refresh tokens are single-use: each call rotates it, and presenting an
+/// already-used token revokes the whole session.
+#[post("/auth/refresh")]
+pub async fn refresh_session(
+    pool: web::Data<Pool>,
+    http_req: HttpRequest,
+    body: web::Json<RefreshTokenRequest>,
+) -> Result<impl Responder> {
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let invalid = || {
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "Invalid refresh token".to_string(),
+            message: Some("Please log in again".to_string()),
+        })
+    };
+
+    let refresh_token = generate_refresh_token();
+    let (_, ip_address) = session_client_info(&http_req);
+    let (session_id, user_id) = match db_service
+        .rotate_refresh_token(
+            &hash_refresh_token(&body.refresh_token),
+            &hash_refresh_token(&refresh_token),
+            ip_address.as_deref(),
+        )
+        .await
+    {
+        Ok(Some(ids)) => ids,
+        Ok(None) => return Ok(invalid()),
+        Err(e) => {
+            error!("Database error refreshing session: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let user = match db_service.get_user_by_id(user_id).await {
+        Ok(Some(u)) if u.is_active => u,
+        Ok(_) => {
+            let _ = db_service.revoke_session(user_id, session_id).await;
+            return Ok(invalid());
+        },
+        Err(e) => {
+            error!("Database error refreshing session: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let token = match generate_token(&user, session_id) {
+        Ok(t) => t,
+        Err(e) => {
+            error!("Error generating token: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to generate token".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(LoginResponse {
             token,
+            refresh_token,
+            expires_in: access_token_lifetime_minutes() * 60,
             user: user.into(),
         }),
-        message: Some("Registration successful".to_string()),
+        message: None,
         error: None,
     }))
 }
@@ -643,6 +1455,16 @@ pub async fn change_password(
         };
 
     if !password_valid {
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::PasswordChanged)
+                .actor(auth_ctx.user_id, &auth_ctx.username)
+                .target("user", &auth_ctx.user_id)
+                .outcome(AuditOutcome::Failure)
+                .details("Current password is incorrect"),
+        )
+        .await;
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
             success: false,
             error: "Current password is incorrect".to_string(),
@@ -663,9 +1485,9 @@ pub async fn change_password(
         },
     };
 
-    // Update password
+    // Update password, signing out every other session
     if let Err(e) = db_service
-        .update_user_password(auth_ctx.user_id, &password_hash)
+        .update_user_password_keeping_session(auth_ctx.user_id, &password_hash, auth_ctx.session_id)
         .await
     {
         error!("Error updating password: {}", e);
@@ -678,6 +1500,15 @@ pub async fn change_password(
 
     info!("User {} changed their password", auth_ctx.username);
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::PasswordChanged)
+            .actor(auth_ctx.user_id, &auth_ctx.username)
+            .target("user", &auth_ctx.user_id),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: None::<()>,
@@ -686,102 +1517,493 @@ pub async fn change_password(
     }))
 }
 
-// ==================== User Settings Endpoints ====================
+// ==================== Session Endpoints ====================
 
-/// Get current user's settings
-#[get("/auth/settings")]
-pub async fn get_user_settings(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+/// Log out by revoking the current session
+#[post("/auth/logout")]
+pub async fn logout(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
     let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
         Ok(ctx) => ctx,
-        Err(response) => return Ok(response),
+        Err(e) => return Ok(e),
     };
 
     let db_service = DatabaseService::new(pool.get_ref().clone());
+    if let Some(session_id) = auth_ctx.session_id {
+        if let Err(e) = db_service
+            .revoke_session(auth_ctx.user_id, session_id)
+            .await
+        {
+            error!("Error revoking session: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to log out".to_string(),
+                message: None,
+            }));
+        }
+    }
+
+    info!("User {} logged out", auth_ctx.username);
+
+    let mut event =
+        NewAuditEvent::new(AuditAction::Logout).actor(auth_ctx.user_id, &auth_ctx.username);
+    if let Some(session_id) = auth_ctx.session_id {
+        event = event.target("session", &session_id);
+    }
+    super::audit::record(&db_service, &req, event).await;
 
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: None::<()>,
+        message: Some("Logged out".to_string()),
+        error: None,
+    }))
+}
+
+/// List the current user's active sessions
+#[get("/auth/sessions")]
+pub async fn get_my_sessions(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
     match db_service
-        .get_or_create_user_settings(auth_ctx.user_id)
+        .get_user_sessions(auth_ctx.user_id, auth_ctx.session_id)
         .await
     {
-        Ok(settings) => Ok(HttpResponse::Ok().json(ApiResponse {
+        Ok(sessions) => Ok(HttpResponse::Ok().json(ApiResponse {
             success: true,
-            data: Some(settings),
+            data: Some(sessions),
             message: None,
             error: None,
         })),
         Err(e) => {
-            error!("Error getting user settings: {}", e);
+            error!("Error retrieving sessions: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
-                error: "An internal error occurred".to_string(),
+                error: "Failed to retrieve sessions".to_string(),
                 message: None,
             }))
         },
     }
 }
 
-/// Update current user's settings
-#[put("/auth/settings")]
-pub async fn update_user_settings(
+/// Revoke one of the current user's sessions
+#[delete("/auth/sessions/{session_id}")]
+pub async fn revoke_my_session(
     pool: web::Data<Pool>,
     req: HttpRequest,
-    body: web::Json<UpdateUserSettingsRequest>,
+    path: web::Path<Uuid>,
 ) -> Result<impl Responder> {
     let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
         Ok(ctx) => ctx,
-        Err(response) => return Ok(response),
+        Err(e) => return Ok(e),
     };
 
+    let session_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
-
-    // Ensure settings exist
-    let _ = db_service
-        .get_or_create_user_settings(auth_ctx.user_id)
-        .await;
-
     match db_service
-        .update_user_settings(auth_ctx.user_id, body.into_inner())
+        .revoke_session(auth_ctx.user_id, session_id)
         .await
     {
-        Ok(Some(settings)) => Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(settings),
-            message: Some("Settings updated successfully".to_string()),
-            error: None,
-        })),
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+        Ok(true) => {
+            info!("User {} revoked session {}", auth_ctx.username, session_id);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::SessionRevoked)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("session", &session_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Session revoked".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
             success: false,
-            error: "Settings not found".to_string(),
+            error: "Session not found".to_string(),
             message: None,
         })),
         Err(e) => {
-            error!("Error updating user settings: {}", e);
+            error!("Error revoking session: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
-                error: "An internal error occurred".to_string(),
+                error: "Failed to revoke session".to_string(),
                 message: None,
             }))
         },
     }
 }
 
-// ==================== Admin User Management Endpoints ====================
-
-/// Get all users (admin only)
-#[get("/admin/users")]
-pub async fn admin_get_users(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
-    let _auth_ctx = match require_admin(&req, pool.get_ref()).await {
+/// Log out everywhere by revoking all of the current user's sessions
+#[delete("/auth/sessions")]
+pub async fn revoke_all_my_sessions(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
         Ok(ctx) => ctx,
-        Err(response) => return Ok(response),
+        Err(e) => return Ok(e),
     };
 
     let db_service = DatabaseService::new(pool.get_ref().clone());
-
-    match db_service.get_all_users().await {
-        Ok(users) => Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(users.clone()),
-            message: Some(format!("Retrieved {count} users", count = users.len())),
-            error: None,
+    match db_service
+        .revoke_user_sessions(auth_ctx.user_id, None)
+        .await
+    {
+        Ok(count) => {
+            info!(
+                "User {} logged out of all {} sessions",
+                auth_ctx.username, count
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::SessionRevoked)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("user", &auth_ctx.user_id)
+                    .details(format!("Revoked all {count} sessions")),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some(format!("Revoked {count} sessions")),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error revoking sessions: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to revoke sessions".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+// ==================== API Token Endpoints ====================
+
+/// List the current user's personal API tokens
+#[get("/auth/tokens")]
+pub async fn get_my_api_tokens(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service.get_user_api_tokens(auth_ctx.user_id).await {
+        Ok(tokens) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(tokens),
+            message: None,
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error retrieving API tokens: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to retrieve API tokens".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Create a personal API token; the token is only returned in this response
+#[post("/auth/tokens")]
+pub async fn create_api_token(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<CreateApiTokenRequest>,
+) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(validation_errors) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(validation_errors.to_string()),
+        }));
+    }
+
+    let mut request = body.into_inner();
+    request.scopes.sort_by_key(ToString::to_string);
+    request.scopes.dedup();
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    // Tokens can only be limited to inventories the user can already reach
+    if let Some(ids) = request.inventory_ids.as_mut() {
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Validation failed".to_string(),
+                message: Some(
+                    "Omit inventory_ids to allow all inventories, or list at least one".to_string(),
+                ),
+            }));
+        }
+        for id in ids.iter() {
+            match db_service
+                .check_inventory_access(auth_ctx.user_id, *id)
+                .await
+            {
+                Ok(true) => {},
+                Ok(false) => {
+                    return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+                        success: false,
+                        error: format!("Access denied to inventory {id}"),
+                        message: None,
+                    }));
+                },
+                Err(e) => {
+                    error!("Error checking inventory access: {}", e);
+                    return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                        success: false,
+                        error: "Failed to create API token".to_string(),
+                        message: None,
+                    }));
+                },
+            }
+        }
+    }
+
+    let token = generate_api_token();
+    match db_service
+        .create_api_token(
+            auth_ctx.user_id,
+            &hash_api_token(&token),
+            &token[..API_TOKEN_DISPLAY_LENGTH],
+            &request,
+        )
+        .await
+    {
+        Ok(api_token) => {
+            info!(
+                "User {} created API token '{}'",
+                auth_ctx.username, api_token.name
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ApiTokenCreated)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("api_token", &api_token.id)
+                    .details(format!("Token '{}'", api_token.name)),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: Some(CreateApiTokenResponse { token, api_token }),
+                message: Some("Copy this token now - it will not be shown again".to_string()),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error creating API token: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to create API token".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Delete one of the current user's API tokens
+#[delete("/auth/tokens/{token_id}")]
+pub async fn delete_api_token(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(e) => return Ok(e),
+    };
+
+    let token_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .delete_api_token(auth_ctx.user_id, token_id)
+        .await
+    {
+        Ok(true) => {
+            info!("User {} deleted API token {}", auth_ctx.username, token_id);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ApiTokenDeleted)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("api_token", &token_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("API token deleted".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "API token not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error deleting API token: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to delete API token".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+// ==================== User Settings Endpoints ====================
+
+/// Get current user's settings
+#[get("/auth/settings")]
+pub async fn get_user_settings(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service
+        .get_or_create_user_settings(auth_ctx.user_id)
+        .await
+    {
+        Ok(settings) => Ok(
+            super::etag::with_etag(HttpResponse::Ok(), Some(settings.updated_at)).json(
+                ApiResponse {
+                    success: true,
+                    data: Some(settings),
+                    message: None,
+                    error: None,
+                },
+            ),
+        ),
+        Err(e) => {
+            error!("Error getting user settings: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Update current user's settings
+#[put("/auth/settings")]
+pub async fn update_user_settings(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<UpdateUserSettingsRequest>,
+) -> Result<impl Responder> {
+    let auth_ctx = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    // Ensure settings exist
+    let current = match db_service
+        .get_or_create_user_settings(auth_ctx.user_id)
+        .await
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Error getting user settings: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+    let expected = match super::etag::check_if_match(&req, &current, Some(current.updated_at)) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service
+        .update_user_settings(auth_ctx.user_id, body.into_inner(), expected)
+        .await
+    {
+        Ok(Some(settings)) => Ok(super::etag::with_etag(
+            HttpResponse::Ok(),
+            Some(settings.updated_at),
+        )
+        .json(ApiResponse {
+            success: true,
+            data: Some(settings),
+            message: Some("Settings updated successfully".to_string()),
+            error: None,
+        })),
+        Ok(None) => {
+            // Changed elsewhere since the If-Match check
+            if expected.is_some() {
+                if let Ok(Some(settings)) = db_service.get_user_settings(auth_ctx.user_id).await {
+                    return Ok(super::etag::precondition_failed(
+                        &settings,
+                        Some(settings.updated_at),
+                    ));
+                }
+            }
+            Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Settings not found".to_string(),
+                message: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error updating user settings: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+// ==================== Admin User Management Endpoints ====================
+
+/// Get all users (admin only)
+#[get("/admin/users")]
+pub async fn admin_get_users(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let _auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service.get_all_users().await {
+        Ok(users) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(users.clone()),
+            message: Some(format!("Retrieved {count} users", count = users.len())),
+            error: None,
         })),
         Err(e) => {
             error!("Error getting users: {}", e);
@@ -839,7 +2061,7 @@ pub async fn admin_create_user(
     req: HttpRequest,
     body: web::Json<AdminCreateUserRequest>,
 ) -> Result<impl Responder> {
-    let _auth_ctx = match require_admin(&req, pool.get_ref()).await {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
         Ok(ctx) => ctx,
         Err(response) => return Ok(response),
     };
@@ -900,6 +2122,15 @@ pub async fn admin_create_user(
             let _ = db_service.create_user_settings(user.id).await;
 
             info!("Admin created new user: {}", user.username);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::UserCreated)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("user", &user.id)
+                    .details(format!("Created '{}'", user.username)),
+            )
+            .await;
             Ok(HttpResponse::Created().json(ApiResponse {
                 success: true,
                 data: Some(UserResponse::from(user)),
@@ -1016,14 +2247,55 @@ pub async fn admin_update_user(
             "Admin {} reset password for user {}",
             auth_ctx.username, user_id
         );
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::PasswordChanged)
+                .actor(auth_ctx.user_id, &auth_ctx.username)
+                .target("user", &user_id)
+                .details("Reset by an admin"),
+        )
+        .await;
     }
 
+    let changed_fields: Vec<&str> = [
+        ("username", body.username.is_some()),
+        ("full_name", body.full_name.is_some()),
+        ("is_admin", body.is_admin.is_some()),
+        ("is_active", body.is_active.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect();
+
     match db_service
         .admin_update_user(user_id, body.into_inner())
         .await
     {
         Ok(Some(user)) => {
             info!("Admin updated user: {}", user.username);
+            if !changed_fields.is_empty() {
+                super::audit::record(
+                    &db_service,
+                    &req,
+                    NewAuditEvent::new(AuditAction::UserUpdated)
+                        .actor(auth_ctx.user_id, &auth_ctx.username)
+                        .target("user", &user.id)
+                        .details(format!(
+                            "Updated {} (admin: {}, active: {})",
+                            changed_fields.join(", "),
+                            user.is_admin,
+                            user.is_active
+                        )),
+                )
+                .await;
+            }
+            // Deactivation takes effect immediately rather than at token expiry
+            if !user.is_active {
+                if let Err(e) = db_service.revoke_user_sessions(user.id, None).await {
+                    error!("Error revoking sessions of deactivated user: {}", e);
+                }
+            }
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(UserResponse::from(user)),
@@ -1109,6 +2381,15 @@ pub async fn admin_delete_user(
                 "Admin deleted user: {} (ID: {})",
                 target_user.username, user_id
             );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::UserDeleted)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("user", &user_id)
+                    .details(format!("Deleted '{}'", target_user.username)),
+            )
+            .await;
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: None::<()>,
@@ -1132,7 +2413,513 @@ pub async fn admin_delete_user(
     }
 }
 
-// ==================== Inventory Sharing Endpoints ====================
+/// Lift a user's login lockout after repeated failed passwords (admin only)
+#[post("/admin/users/{id}/unlock")]
+pub async fn admin_unlock_user(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let user_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let target_user = match db_service.get_user_by_id(user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "User not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting user: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    match db_service
+        .clear_failed_logins(&lockout::attempt_key(&target_user.username))
+        .await
+    {
+        Ok(cleared) => {
+            if cleared {
+                info!(
+                    "Admin {} cleared failed logins for {}",
+                    auth_ctx.username, target_user.username
+                );
+                super::audit::record(
+                    &db_service,
+                    &req,
+                    NewAuditEvent::new(AuditAction::UserUnlocked)
+                        .actor(auth_ctx.user_id, &auth_ctx.username)
+                        .target("user", &target_user.id),
+                )
+                .await;
+            }
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some(if cleared {
+                    "Login lockout cleared".to_string()
+                } else {
+                    "User was not locked out".to_string()
+                }),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error clearing failed logins: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+// ==================== Identity Link Endpoints ====================
+
+/// Look up a user for an admin endpoint, or the response to send when that fails
+async fn admin_target_user(
+    db_service: &DatabaseService,
+    user_id: Uuid,
+) -> Result<User, HttpResponse> {
+    match db_service.get_user_by_id(user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "User not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error getting user: {}", e);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Parse the `{source}` of an identity link path
+fn parse_identity_source(source: &str) -> Result<IdentitySource, HttpResponse> {
+    source.parse().map_err(|e| {
+        HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: e,
+            message: Some("Source must be sso or ldap".to_string()),
+        })
+    })
+}
+
+/// List the external identity sources a user can sign in with (admin only)
+#[get("/admin/users/{id}/identity-links")]
+pub async fn admin_get_identity_links(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    if let Err(response) = require_admin(&req, pool.get_ref()).await {
+        return Ok(response);
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match admin_target_user(&db_service, path.into_inner()).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.get_user_identity_links(user.id).await {
+        Ok(links) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} identity links", links.len())),
+            data: Some(links),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting identity links: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Let an existing account sign in through SSO or LDAP (admin only)
+///
+/// The link is bound to whichever identity of that username signs in first.
+/// Linking an account that is already linked lets it be bound again.
+#[put("/admin/users/{id}/identity-links/{source}")]
+pub async fn admin_link_identity(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, String)>,
+) -> Result<impl Responder> {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let (user_id, source) = path.into_inner();
+    let source = match parse_identity_source(&source) {
+        Ok(source) => source,
+        Err(response) => return Ok(response),
+    };
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match admin_target_user(&db_service, user_id).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.link_user_identity(user.id, source, None).await {
+        Ok(link) => {
+            info!(
+                "Admin {} linked {} to {} logins",
+                auth_ctx.username, user.username, source
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::UserIdentityLinked)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("user", &user.id)
+                    .details(source.to_string()),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(link),
+                message: Some(format!(
+                    "'{}' can now sign in with {source}; the next {source} login as '{}' claims the account",
+                    user.username, user.username
+                )),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error linking identity: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Stop an account from signing in through SSO or LDAP (admin only)
+#[delete("/admin/users/{id}/identity-links/{source}")]
+pub async fn admin_unlink_identity(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, String)>,
+) -> Result<impl Responder> {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let (user_id, source) = path.into_inner();
+    let source = match parse_identity_source(&source) {
+        Ok(source) => source,
+        Err(response) => return Ok(response),
+    };
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match admin_target_user(&db_service, user_id).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.unlink_user_identity(user.id, source).await {
+        Ok(true) => {
+            info!(
+                "Admin {} unlinked {} from {} logins",
+                auth_ctx.username, user.username, source
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::UserIdentityUnlinked)
+                    .actor(auth_ctx.user_id, &auth_ctx.username)
+                    .target("user", &user.id)
+                    .details(source.to_string()),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some(format!(
+                    "'{}' can no longer sign in with {source}",
+                    user.username
+                )),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: format!("'{}' isn't linked to {source}", user.username),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error unlinking identity: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+// ==================== Password Reset Link Endpoints ====================
+
+/// Issue a single-use password reset link for a user (admin only).
+/// Any earlier link for the user that hasn't been redeemed stops working.
+#[post("/admin/users/{id}/password-reset")]
+pub async fn admin_create_password_reset(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<CreatePasswordResetRequest>>,
+) -> Result<impl Responder> {
+    let auth_ctx = match require_admin(&req, pool.get_ref()).await {
+        Ok(ctx) => ctx,
+        Err(response) => return Ok(response),
+    };
+
+    let hours = body
+        .and_then(|b| b.expires_in_hours)
+        .unwrap_or(PASSWORD_RESET_DEFAULT_HOURS);
+    if !(1..=PASSWORD_RESET_MAX_HOURS).contains(&hours) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(format!(
+                "expires_in_hours must be between 1 and {PASSWORD_RESET_MAX_HOURS}"
+            )),
+        }));
+    }
+
+    let user_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let target_user = match db_service.get_user_by_id(user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "User not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting user: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Database error".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    if !target_user.is_active {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Account deactivated".to_string(),
+            message: Some("Reactivate the account before issuing a reset link".to_string()),
+        }));
+    }
+
+    // Opportunistically clear out old links; failure here shouldn't block issuing one
+    if let Err(e) = db_service.delete_expired_password_reset_tokens().await {
+        warn!("Failed to delete expired password reset tokens: {}", e);
+    }
+
+    let token = generate_password_reset_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(hours);
+    if let Err(e) = db_service
+        .create_password_reset_token(
+            user_id,
+            &hash_password_reset_token(&token),
+            auth_ctx.user_id,
+            expires_at,
+        )
+        .await
+    {
+        error!("Error creating password reset token: {}", e);
+        return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+            success: false,
+            error: "Failed to create reset link".to_string(),
+            message: None,
+        }));
+    }
+
+    info!(
+        "Admin {} issued a password reset link for {} (valid {} hours)",
+        auth_ctx.username, target_user.username, hours
+    );
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::PasswordResetLinkCreated)
+            .actor(auth_ctx.user_id, &auth_ctx.username)
+            .target("user", &user_id)
+            .details(format!("Expires {}", expires_at.to_rfc3339())),
+    )
+    .await;
+
+    Ok(HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(PasswordResetLinkResponse {
+            reset_path: format!("/reset-password?token={token}"),
+            token,
+            expires_at,
+        }),
+        message: Some(format!(
+            "Send this link to {} - it will not be shown again",
+            target_user.username
+        )),
+        error: None,
+    }))
+}
+
+/// Redeem a password reset link and set a new password (no authentication required)
+#[post("/auth/password-reset")]
+pub async fn redeem_password_reset(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<RedeemPasswordResetRequest>,
+) -> Result<impl Responder> {
+    if let Err(msg) = validate_password(&body.new_password) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: msg.to_string(),
+            message: Some("Invalid new password".to_string()),
+        }));
+    }
+
+    let password_hash = match hash_password(body.new_password.clone()).await {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Error hashing password: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to reset password".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user_id = match db_service
+        .redeem_password_reset_token(
+            &hash_password_reset_token(body.token.trim()),
+            &password_hash,
+        )
+        .await
+    {
+        Ok(Some(user_id)) => user_id,
+        Ok(None) => {
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid or expired reset link".to_string(),
+                message: Some("Ask an administrator for a new link".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error redeeming password reset token: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to reset password".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    // A fresh password shouldn't still be waiting out an old lockout
+    if let Ok(Some(user)) = db_service.get_user_by_id(user_id).await {
+        if let Err(e) = db_service
+            .clear_failed_logins(&lockout::attempt_key(&user.username))
+            .await
+        {
+            warn!("Failed to reset failed logins for {}: {}", user.username, e);
+        }
+        info!(
+            "User {} reset their password with a reset link",
+            user.username
+        );
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::PasswordReset)
+                .actor(user.id, &user.username)
+                .target("user", &user.id)
+                .details("Reset link"),
+        )
+        .await;
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: None::<()>,
+        message: Some(
+            "Password reset successfully. You can now log in with your new password.".to_string(),
+        ),
+        error: None,
+    }))
+}
+
+// ==================== Inventory Sharing Endpoints ====================
+
+/// Reject a share or grant schedule that has already ended or ends before it starts
+pub(crate) fn validate_share_schedule(
+    starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), HttpResponse> {
+    let Some(expires_at) = expires_at else {
+        return Ok(());
+    };
+    let message = if expires_at <= chrono::Utc::now() {
+        "The expiry time must be in the future"
+    } else if starts_at.is_some_and(|starts_at| expires_at <= starts_at) {
+        "The expiry time must be after the start time"
+    } else {
+        return Ok(());
+    };
+    Err(HttpResponse::BadRequest().json(ErrorResponse {
+        success: false,
+        error: "Invalid schedule".to_string(),
+        message: Some(message.to_string()),
+    }))
+}
+
+/// Describe a share or grant schedule for the audit log, e.g. " until 2026-05-01 12:00 UTC"
+pub(crate) fn describe_share_schedule(
+    starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> String {
+    let starts = starts_at
+        .map(|t| format!(" from {}", t.format("%Y-%m-%d %H:%M UTC")))
+        .unwrap_or_default();
+    let expires = expires_at
+        .map(|t| format!(" until {}", t.format("%Y-%m-%d %H:%M UTC")))
+        .unwrap_or_default();
+    format!("{starts}{expires}")
+}
 
 /// Get shares for an inventory
 #[get("/inventories/{id}/shares")]
@@ -1149,38 +2936,10 @@ pub async fn get_inventory_shares(
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    // Check if user has permission to view shares (must be owner or have All Access)
-    let effective_perms = match db_service
-        .get_effective_permissions(auth.user_id, inventory_id)
-        .await
+    if let Err(response) =
+        super::require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
     {
-        Ok(p) => p,
-        Err(e) => {
-            error!("Error checking permission: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                success: false,
-                error: "An internal error occurred".to_string(),
-                message: None,
-            }));
-        },
-    };
-
-    if effective_perms.permission_source == PermissionSource::None {
-        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-            success: false,
-            error: "Access denied".to_string(),
-            message: Some("You don't have access to this inventory".to_string()),
-        }));
-    }
-
-    if !effective_perms.can_manage_sharing && !auth.is_admin {
-        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-            success: false,
-            error: "Insufficient permissions".to_string(),
-            message: Some(
-                "Only inventory owners or users with All Access can manage shares".to_string(),
-            ),
-        }));
+        return Ok(response);
     }
 
     match db_service.get_inventory_shares(inventory_id).await {
@@ -1224,40 +2983,22 @@ pub async fn create_inventory_share(
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    // Check if user has permission to share (must be owner or have All Access)
-    let effective_perms = match db_service
-        .get_effective_permissions(auth.user_id, inventory_id)
-        .await
+    if let Err(response) =
+        super::require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
     {
-        Ok(p) => p,
-        Err(e) => {
-            error!("Error checking permission: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                success: false,
-                error: "An internal error occurred".to_string(),
-                message: None,
-            }));
-        },
-    };
-
-    if effective_perms.permission_source == PermissionSource::None {
-        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-            success: false,
-            error: "Access denied".to_string(),
-            message: Some("You don't have access to this inventory".to_string()),
-        }));
-    }
-
-    if !effective_perms.can_manage_sharing && !auth.is_admin {
-        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-            success: false,
-            error: "Insufficient permissions".to_string(),
-            message: Some(
-                "Only inventory owners or users with All Access can share this inventory"
-                    .to_string(),
-            ),
-        }));
+        return Ok(response);
     }
+    let role = match super::roles::resolve_share_role(
+        &db_service,
+        inventory_id,
+        body.permission_level,
+        body.role_id,
+    )
+    .await
+    {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
 
     // Find the user to share with
     let target_user = match db_service
@@ -1294,25 +3035,50 @@ pub async fn create_inventory_share(
         }));
     }
 
+    if let Err(response) = validate_share_schedule(body.starts_at, body.expires_at) {
+        return Ok(response);
+    }
+
+    // An expired share for the same user would otherwise count as a duplicate
+    if let Err(e) = db_service.delete_expired_shares().await {
+        warn!("Failed to clean up expired shares: {}", e);
+    }
+
     match db_service
         .create_inventory_share(
             inventory_id,
             target_user.id,
             auth.user_id,
-            body.permission_level,
+            role.clone(),
+            body.starts_at,
+            body.expires_at,
         )
         .await
     {
         Ok(share) => {
             info!(
-                "User {} shared inventory {} with {} (permission: {:?})",
-                auth.username, inventory_id, target_user.username, body.permission_level
+                "User {} shared inventory {} with {} (permission: {})",
+                auth.username, inventory_id, target_user.username, role
             );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ShareCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("share", &share.id)
+                    .details(format!(
+                        "Shared inventory {inventory_id} with '{}' ({}){}",
+                        target_user.username,
+                        role,
+                        describe_share_schedule(body.starts_at, body.expires_at)
+                    )),
+            )
+            .await;
             Ok(HttpResponse::Created().json(ApiResponse {
                 success: true,
                 data: Some(share),
                 message: Some(format!(
-                    "Inventory shared with {username}",
+                    "Invitation sent to {username}; the inventory is shared once they accept",
                     username = target_user.username
                 )),
                 error: None,
@@ -1340,6 +3106,35 @@ pub async fn create_inventory_share(
     }
 }
 
+/// Look up a share and check the user may manage the shares of its inventory.
+/// Returns the share's inventory ID.
+async fn require_share_access(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    share_id: Uuid,
+) -> std::result::Result<i32, HttpResponse> {
+    let inventory_id = match db_service.get_inventory_share(share_id).await {
+        Ok(Some(share)) => share.inventory_id,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting share: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+    super::require_capability(db_service, auth, inventory_id, Capability::ManageShares).await?;
+    Ok(inventory_id)
+}
+
 /// Update share permission level
 #[put("/shares/{share_id}")]
 pub async fn update_inventory_share(
@@ -1348,7 +3143,7 @@ pub async fn update_inventory_share(
     path: web::Path<Uuid>,
     body: web::Json<UpdateInventoryShareRequest>,
 ) -> Result<impl Responder> {
-    let _auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
         Ok(a) => a,
         Err(e) => return Ok(e),
     };
@@ -1356,16 +3151,38 @@ pub async fn update_inventory_share(
     let share_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    let inventory_id = match require_share_access(&db_service, &auth, share_id).await {
+        Ok(inventory_id) => inventory_id,
+        Err(response) => return Ok(response),
+    };
+    let role = match super::roles::resolve_share_role(
+        &db_service,
+        inventory_id,
+        body.permission_level,
+        body.role_id,
+    )
+    .await
+    {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
+
     // Update the share permission
     match db_service
-        .update_inventory_share(share_id, body.permission_level)
+        .update_inventory_share(share_id, role.clone())
         .await
     {
         Ok(Some(share)) => {
-            info!(
-                "Updated share {} permission to {:?}",
-                share_id, body.permission_level
-            );
+            info!("Updated share {} permission to {}", share_id, role);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ShareUpdated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("share", &share_id)
+                    .details(format!("Permission set to {role}")),
+            )
+            .await;
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(share),
@@ -1404,9 +3221,21 @@ pub async fn delete_inventory_share(
     let share_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) = require_share_access(&db_service, &auth, share_id).await {
+        return Ok(response);
+    }
+
     match db_service.delete_inventory_share(share_id).await {
         Ok(true) => {
             info!("User {} deleted share {}", auth.username, share_id);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ShareDeleted)
+                    .actor(auth.user_id, &auth.username)
+                    .target("share", &share_id),
+            )
+            .await;
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: None::<()>,
@@ -1430,7 +3259,88 @@ pub async fn delete_inventory_share(
     }
 }
 
-/// Get inventories accessible to the current user (owned + shared + all-access)
+/// Accept a share offered to the current user
+#[post("/shares/{share_id}/accept")]
+pub async fn accept_inventory_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    respond_to_inventory_share(pool, req, path.into_inner(), ShareStatus::Accepted).await
+}
+
+/// Decline a share offered to the current user
+#[post("/shares/{share_id}/decline")]
+pub async fn decline_inventory_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    respond_to_inventory_share(pool, req, path.into_inner(), ShareStatus::Declined).await
+}
+
+/// Record the recipient's answer to a pending share
+async fn respond_to_inventory_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    share_id: Uuid,
+    status: ShareStatus,
+) -> Result<HttpResponse> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service
+        .respond_to_inventory_share(share_id, auth.user_id, status)
+        .await
+    {
+        Ok(Some(share)) => {
+            info!(
+                "User {} {} share {} of inventory {}",
+                auth.username, status, share_id, share.inventory_id
+            );
+            let action = if status == ShareStatus::Accepted {
+                AuditAction::ShareAccepted
+            } else {
+                AuditAction::ShareDeclined
+            };
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(action)
+                    .actor(auth.user_id, &auth.username)
+                    .target("share", &share_id)
+                    .details(format!("Inventory {}", share.inventory_id)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(share),
+                message: Some(format!("Share {status}")),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Invitation not found".to_string(),
+            message: Some("There is no pending share with this ID for you".to_string()),
+        })),
+        Err(e) => {
+            error!("Error responding to share: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Get inventories accessible to the current user (owned + shared + all-access),
+/// along with shares and All Access grants waiting for them to accept
 #[get("/auth/inventories")]
 pub async fn get_my_inventories(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
     let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
@@ -1440,16 +3350,31 @@ pub async fn get_my_inventories(pool: web::Data<Pool>, req: HttpRequest) -> Resu
 
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.get_accessible_inventories(auth.user_id).await {
-        Ok(inventories) => {
+    let result = tokio::try_join!(
+        db_service.get_accessible_inventories(auth.user_id),
+        db_service.get_pending_inventory_shares(auth.user_id),
+        db_service.get_user_access_grants_by_grantee(auth.user_id),
+    );
+
+    match result {
+        Ok((inventories, pending_shares, received_grants)) => {
             info!(
-                "User {} retrieved {} accessible inventories",
+                "User {} retrieved {} accessible inventories ({} pending shares)",
                 auth.username,
-                inventories.len()
+                inventories.len(),
+                pending_shares.len()
             );
+            let pending_access_grants = received_grants
+                .into_iter()
+                .filter(|grant| grant.status == ShareStatus::Pending)
+                .collect();
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
-                data: Some(inventories),
+                data: Some(MyInventoriesResponse {
+                    inventories,
+                    pending_shares,
+                    pending_access_grants,
+                }),
                 message: None,
                 error: None,
             }))
@@ -1598,8 +3523,23 @@ pub async fn create_access_grant(
         }));
     }
 
+    if let Err(response) = validate_share_schedule(body.starts_at, body.expires_at) {
+        return Ok(response);
+    }
+
+    // An expired grant for the same user would otherwise count as a duplicate
+    if let Err(e) = db_service.delete_expired_shares().await {
+        warn!("Failed to clean up expired access grants: {}", e);
+    }
+
     match db_service
-        .create_user_access_grant(auth.user_id, target_user.id)
+        .create_user_access_grant(
+            auth.user_id,
+            target_user.id,
+            body.permission_level,
+            body.starts_at,
+            body.expires_at,
+        )
         .await
     {
         Ok(grant) => {
@@ -1607,11 +3547,25 @@ pub async fn create_access_grant(
                 "User {} granted All Access to {} for all their inventories",
                 auth.username, target_user.username
             );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::AccessGrantCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("access_grant", &grant.id)
+                    .details(format!(
+                        "{} All Access for '{}'{}",
+                        body.permission_level,
+                        target_user.username,
+                        describe_share_schedule(body.starts_at, body.expires_at)
+                    )),
+            )
+            .await;
             Ok(HttpResponse::Created().json(ApiResponse {
                 success: true,
                 data: Some(grant),
                 message: Some(format!(
-                    "{username} now has All Access to all your inventories",
+                    "Invitation sent to {username}; they get All Access to your inventories once they accept",
                     username = target_user.username
                 )),
                 error: None,
@@ -1639,6 +3593,94 @@ pub async fn create_access_grant(
     }
 }
 
+/// Change the permission level of an All Access grant I have made
+#[put("/auth/access-grants/{grant_id}")]
+pub async fn update_access_grant(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateUserAccessGrantRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let grant_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let grant = match db_service.get_user_access_grant_by_id(grant_id).await {
+        Ok(Some(g)) => g,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Access grant not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error finding access grant: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    // Only the grantor can change their own grants (or admin)
+    if grant.grantor_user_id != auth.user_id && !auth.is_admin {
+        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Access denied".to_string(),
+            message: Some("You can only change access grants you have made".to_string()),
+        }));
+    }
+
+    match db_service
+        .update_user_access_grant(grant_id, body.permission_level)
+        .await
+    {
+        Ok(Some(updated)) => {
+            info!(
+                "User {} changed access grant {} to {}",
+                auth.username, grant_id, body.permission_level
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::AccessGrantUpdated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("access_grant", &grant_id)
+                    .details(format!(
+                        "Level changed from {} to {}",
+                        grant.permission_level, body.permission_level
+                    )),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(updated),
+                message: Some("All Access grant updated successfully".to_string()),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Access grant not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error updating access grant: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
 /// Revoke All Access grant (remove someone's access to all my inventories)
 #[delete("/auth/access-grants/{grant_id}")]
 pub async fn delete_access_grant(
@@ -1686,6 +3728,14 @@ pub async fn delete_access_grant(
     match db_service.delete_user_access_grant(grant_id).await {
         Ok(true) => {
             info!("User {} revoked access grant {}", auth.username, grant_id);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::AccessGrantDeleted)
+                    .actor(auth.user_id, &auth.username)
+                    .target("access_grant", &grant_id),
+            )
+            .await;
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: None::<()>,
@@ -1709,6 +3759,85 @@ pub async fn delete_access_grant(
     }
 }
 
+/// Accept an All Access grant offered to the current user
+#[post("/auth/access-grants/{grant_id}/accept")]
+pub async fn accept_access_grant(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    respond_to_access_grant(pool, req, path.into_inner(), ShareStatus::Accepted).await
+}
+
+/// Decline an All Access grant offered to the current user
+#[post("/auth/access-grants/{grant_id}/decline")]
+pub async fn decline_access_grant(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    respond_to_access_grant(pool, req, path.into_inner(), ShareStatus::Declined).await
+}
+
+/// Record the grantee's answer to a pending All Access grant
+async fn respond_to_access_grant(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    grant_id: Uuid,
+    status: ShareStatus,
+) -> Result<HttpResponse> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service
+        .respond_to_user_access_grant(grant_id, auth.user_id, status)
+        .await
+    {
+        Ok(Some(grant)) => {
+            info!(
+                "User {} {} access grant {}",
+                auth.username, status, grant_id
+            );
+            let action = if status == ShareStatus::Accepted {
+                AuditAction::AccessGrantAccepted
+            } else {
+                AuditAction::AccessGrantDeclined
+            };
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(action)
+                    .actor(auth.user_id, &auth.username)
+                    .target("access_grant", &grant_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(grant),
+                message: Some(format!("All Access grant {status}")),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Invitation not found".to_string(),
+            message: Some("There is no pending access grant with this ID for you".to_string()),
+        })),
+        Err(e) => {
+            error!("Error responding to access grant: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
 /// Get effective permissions for current user on a specific inventory
 #[get("/inventories/{id}/permissions")]
 pub async fn get_inventory_permissions(
@@ -1871,6 +4000,17 @@ pub async fn transfer_inventory_ownership(
                 "User {} transferred ownership of inventory '{}' (ID: {}) to user {}",
                 auth.user_id, inventory_name, inventory_id, target_username
             );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::OwnershipTransferred)
+                    .actor(auth.user_id, &auth.username)
+                    .target("inventory", &inventory_id)
+                    .details(format!(
+                        "Transferred '{inventory_name}' to '{target_username}'"
+                    )),
+            )
+            .await;
 
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
@@ -1980,6 +4120,15 @@ pub async fn generate_recovery_codes(
 
     info!("Generated 10 recovery codes for user {}", auth.user_id);
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::RecoveryCodesGenerated)
+            .actor(auth.user_id, &auth.username)
+            .target("user", &auth.user_id),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(RecoveryCodesResponse {
@@ -2104,6 +4253,7 @@ pub async fn confirm_recovery_codes(
 #[post("/auth/recovery-codes/use")]
 pub async fn use_recovery_code(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     body: web::Json<UseRecoveryCodeRequest>,
 ) -> Result<impl Responder> {
     let db_service = DatabaseService::new(pool.get_ref().clone());
@@ -2186,6 +4336,15 @@ pub async fn use_recovery_code(
 
     let Some(code_id) = matched_code_id else {
         warn!("Invalid recovery code attempt for user {}", user.username);
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::RecoveryCodeUsed)
+                .claimed_username(&user.username)
+                .target("user", &user.id)
+                .outcome(AuditOutcome::Failure),
+        )
+        .await;
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
             success: false,
             error: "Invalid username or recovery code".to_string(),
@@ -2236,6 +4395,16 @@ pub async fn use_recovery_code(
         user.username, remaining
     );
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::RecoveryCodeUsed)
+            .actor(user.id, &user.username)
+            .target("user", &user.id)
+            .details(format!("Password reset, {remaining} codes remaining")),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(RecoveryCodeUsedResponse {
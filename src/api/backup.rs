@@ -1,22 +1,82 @@
 //! Backup & Restore API endpoints
 //!
 //! Provides endpoints for creating, listing, downloading, uploading,
-//! restoring, and deleting database backups. All endpoints require
-//! admin authentication.
+//! verifying, browsing, restoring (fully or selectively), and deleting
+//! database backups. All endpoints require admin authentication.
 
 use actix_multipart::form::{tempfile::TempFile, MultipartForm};
 use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder, Result};
 use deadpool_postgres::Pool;
-use log::{error, info};
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use uuid::Uuid;
 
 use crate::api::auth;
-use crate::db::DatabaseService;
-use crate::models::{ApiResponse, BackupData, BackupInfo, BackupMetadata, ErrorResponse};
+use crate::api::backup_format::{self, format_version_string, CURRENT_BACKUP_VERSION};
+use crate::api::backup_remote::{self, RemoteBackupStore};
+use crate::db::{backup_i64, backup_rows, backup_str, backup_uuid, DatabaseService};
+use crate::models::{
+    ApiResponse, AuditAction, AuditOutcome, BackupContents, BackupData, BackupDatabaseContent,
+    BackupInfo, BackupInventorySummary, BackupMetadata, BackupUserSummary,
+    BackupVerificationReport, ErrorResponse, NewAuditEvent, VerifyBackupQuery,
+};
 
 /// Directory where backup files are stored
 const BACKUPS_DIR: &str = "backups";
 
+/// Foreign key relationships checked during verification: (table, column, referenced table)
+const BACKUP_REFERENCES: [(&str, &str, &str); 40] = [
+    ("inventories", "user_id", "users"),
+    ("items", "inventory_id", "inventories"),
+    ("items", "category_id", "categories"),
+    ("item_tags", "item_id", "items"),
+    ("item_tags", "tag_id", "tags"),
+    ("custom_fields", "category_id", "categories"),
+    ("item_custom_values", "item_id", "items"),
+    ("item_custom_values", "custom_field_id", "custom_fields"),
+    ("organizer_types", "inventory_id", "inventories"),
+    ("organizer_options", "organizer_type_id", "organizer_types"),
+    ("item_organizer_values", "item_id", "items"),
+    (
+        "item_organizer_values",
+        "organizer_type_id",
+        "organizer_types",
+    ),
+    (
+        "item_organizer_values",
+        "organizer_option_id",
+        "organizer_options",
+    ),
+    ("user_settings", "user_id", "users"),
+    ("user_settings", "default_inventory_id", "inventories"),
+    ("inventory_shares", "inventory_id", "inventories"),
+    ("inventory_shares", "shared_with_user_id", "users"),
+    ("inventory_shares", "shared_by_user_id", "users"),
+    ("inventory_shares", "role_id", "inventory_roles"),
+    ("user_access_grants", "grantor_user_id", "users"),
+    ("user_access_grants", "grantee_user_id", "users"),
+    ("recovery_codes", "user_id", "users"),
+    ("password_reset_tokens", "user_id", "users"),
+    ("user_identity_links", "user_id", "users"),
+    ("api_tokens", "user_id", "users"),
+    ("webauthn_credentials", "user_id", "users"),
+    ("user_invitations", "invited_by", "users"),
+    ("user_invitations", "inventory_id", "inventories"),
+    ("user_invitations", "accepted_by", "users"),
+    ("inventory_share_links", "inventory_id", "inventories"),
+    ("inventory_share_links", "created_by", "users"),
+    ("user_groups", "created_by", "users"),
+    ("user_group_members", "group_id", "user_groups"),
+    ("user_group_members", "user_id", "users"),
+    ("inventory_group_shares", "inventory_id", "inventories"),
+    ("inventory_group_shares", "group_id", "user_groups"),
+    ("inventory_group_shares", "shared_by_user_id", "users"),
+    ("inventory_roles", "owner_user_id", "users"),
+    ("inventory_group_shares", "role_id", "inventory_roles"),
+    ("item_history", "item_id", "items"),
+];
+
 /// Multipart form for backup file upload
 #[derive(MultipartForm)]
 struct BackupUploadForm {
@@ -73,7 +133,7 @@ async fn ensure_backups_dir() -> std::io::Result<()> {
 }
 
 /// Create a backup file and return its info
-async fn create_backup_file(
+pub async fn create_backup_file(
     db_service: &DatabaseService,
     filename_prefix: &str,
 ) -> std::result::Result<BackupInfo, Box<dyn std::error::Error>> {
@@ -84,15 +144,17 @@ async fn create_backup_file(
 
     // Export all database data
     let db_content = db_service.export_all_data().await?;
+    let checksum = db_content.checksum()?;
 
     // Create backup envelope with metadata
     let backup_data = BackupData {
         metadata: BackupMetadata {
-            version: "1.0".to_string(),
+            version: format_version_string(CURRENT_BACKUP_VERSION),
             app_version: env!("CARGO_PKG_VERSION").to_string(),
             created_at: chrono::Utc::now().to_rfc3339(),
             database_type: "postgresql".to_string(),
             description: None,
+            checksum: Some(checksum),
         },
         data: db_content,
     };
@@ -110,13 +172,491 @@ async fn create_backup_file(
         format_file_size(file_size)
     );
 
+    // Replicate off-site when configured; a failure here doesn't fail the backup
+    let mut remote = false;
+    let mut replication_error = None;
+    if let Some(store) = RemoteBackupStore::from_env() {
+        match store.replicate(&filename, json_content.as_bytes()).await {
+            Ok(()) => remote = true,
+            Err(e) => replication_error = Some(e),
+        }
+    }
+
     Ok(BackupInfo {
         name: filename,
         date: backup_data.metadata.created_at,
         size: format_file_size(file_size),
+        local: true,
+        remote,
+        replication_error,
     })
 }
 
+/// Why a stored backup could not be read or used for a restore
+#[derive(Debug)]
+pub enum BackupLoadError {
+    /// The filename failed validation
+    InvalidFilename(String),
+    /// The backup exists neither locally nor off-site
+    NotFound,
+    /// The backup exists but could not be read
+    ReadFailed,
+    /// The backup is not valid JSON
+    InvalidFormat,
+    /// The backup failed verification
+    Rejected {
+        error: &'static str,
+        problems: String,
+    },
+}
+
+impl std::fmt::Display for BackupLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFilename(e) => write!(f, "Invalid backup filename: {e}"),
+            Self::NotFound => write!(f, "Backup file not found"),
+            Self::ReadFailed => write!(f, "Failed to read backup file"),
+            Self::InvalidFormat => write!(f, "The backup file could not be parsed"),
+            Self::Rejected { error, problems } => write!(f, "{error}: {problems}"),
+        }
+    }
+}
+
+impl std::error::Error for BackupLoadError {}
+
+impl BackupLoadError {
+    /// The error response sent to API clients
+    fn to_response(&self, filename: &str) -> HttpResponse {
+        match self {
+            Self::InvalidFilename(e) => HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: e.clone(),
+                message: Some("Invalid backup filename".to_string()),
+            }),
+            Self::NotFound => HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Backup file not found".to_string(),
+                message: Some(format!("Backup '{filename}' does not exist")),
+            }),
+            Self::ReadFailed => HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to read backup file".to_string()),
+            }),
+            Self::InvalidFormat => HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid backup file format".to_string(),
+                message: Some("The backup file could not be parsed".to_string()),
+            }),
+            Self::Rejected { error, problems } => HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: (*error).to_string(),
+                message: Some(problems.clone()),
+            }),
+        }
+    }
+}
+
+/// Read a stored backup file from the local directory, falling back to off-site storage
+pub async fn read_stored_backup(filename: &str) -> std::result::Result<Vec<u8>, BackupLoadError> {
+    // Validate filename to prevent path traversal
+    validate_backup_filename(filename).map_err(BackupLoadError::InvalidFilename)?;
+
+    let filepath = format!("{BACKUPS_DIR}/{filename}");
+    if tokio::fs::try_exists(&filepath).await.unwrap_or(false) {
+        return tokio::fs::read(&filepath).await.map_err(|e| {
+            error!("Failed to read backup file '{}': {}", filename, e);
+            BackupLoadError::ReadFailed
+        });
+    }
+
+    // Not on disk: it may only exist off-site
+    let Some(store) = RemoteBackupStore::from_env() else {
+        return Err(BackupLoadError::NotFound);
+    };
+    match store.download(filename).await {
+        Ok(Some(content)) => {
+            info!("Backup '{}' fetched from off-site storage", filename);
+            Ok(content)
+        },
+        Ok(None) => Err(BackupLoadError::NotFound),
+        Err(e) => {
+            error!(
+                "Failed to download backup '{}' from off-site storage: {}",
+                filename, e
+            );
+            backup_remote::record_failure(&format!("Failed to download {filename}: {e}"));
+            Err(BackupLoadError::ReadFailed)
+        },
+    }
+}
+
+/// Read a backup file, returning a ready-to-send error response on failure
+async fn read_backup_file(filename: &str) -> std::result::Result<Vec<u8>, HttpResponse> {
+    read_stored_backup(filename)
+        .await
+        .map_err(|e| e.to_response(filename))
+}
+
+/// Parse a dotted version string ("1.2.3") into numeric components
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect()
+}
+
+/// Check every row-level reference in the backup against the referenced table's ids
+fn check_referential_integrity(data: &BackupDatabaseContent) -> Vec<String> {
+    let tables: HashMap<&str, &serde_json::Value> = data.tables().into_iter().collect();
+
+    // Ids are compared by their JSON text so integer and UUID keys share one code path
+    let ids_of = |table: &str| -> HashSet<String> {
+        tables
+            .get(table)
+            .and_then(|rows| rows.as_array())
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| row.get("id"))
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut id_cache: HashMap<&str, HashSet<String>> = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (table, column, referenced) in BACKUP_REFERENCES {
+        let Some(rows) = tables.get(table).and_then(|rows| rows.as_array()) else {
+            continue;
+        };
+        let known_ids = id_cache
+            .entry(referenced)
+            .or_insert_with(|| ids_of(referenced));
+
+        let dangling: Vec<String> = rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|value| !value.is_null())
+            .map(ToString::to_string)
+            .filter(|value| !known_ids.contains(value))
+            .collect();
+
+        if !dangling.is_empty() {
+            let sample: Vec<&str> = dangling.iter().take(5).map(String::as_str).collect();
+            errors.push(format!(
+                "{table}.{column}: {count} row(s) reference missing {referenced} (e.g. {sample})",
+                count = dangling.len(),
+                sample = sample.join(", ")
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Verify a parsed backup file without touching the database
+///
+/// Returns the report along with the deserialized backup when it could be parsed,
+/// so callers can go on to a dry-run or real restore.
+fn verify_backup_content(
+    filename: &str,
+    raw: &serde_json::Value,
+) -> (BackupVerificationReport, Option<BackupData>) {
+    let mut report = BackupVerificationReport {
+        filename: filename.to_string(),
+        valid: false,
+        version: None,
+        app_version: None,
+        checksum_valid: None,
+        version_compatible: false,
+        missing_tables: Vec::new(),
+        integrity_errors: Vec::new(),
+        warnings: Vec::new(),
+        dry_run: None,
+        dry_run_error: None,
+    };
+
+    // Metadata and version compatibility
+    let metadata: Option<BackupMetadata> = raw
+        .get("metadata")
+        .and_then(|m| serde_json::from_value(m.clone()).ok());
+    let Some(metadata) = metadata else {
+        report
+            .integrity_errors
+            .push("Backup metadata is missing or malformed".to_string());
+        return (report, None);
+    };
+
+    report.version = Some(metadata.version.clone());
+    report.app_version = Some(metadata.app_version.clone());
+
+    // The checksum covers the data exactly as written, so check it before upgrading
+    match (&metadata.checksum, raw.get("data")) {
+        (Some(expected), Some(data)) => {
+            let matches =
+                expected.eq_ignore_ascii_case(&BackupDatabaseContent::checksum_of_json(data));
+            report.checksum_valid = Some(matches);
+            if !matches {
+                report.integrity_errors.push(
+                    "Checksum mismatch: the backup data has been modified or corrupted".to_string(),
+                );
+            }
+        },
+        (Some(_), None) => report.checksum_valid = Some(false),
+        (None, _) => report
+            .warnings
+            .push("Backup has no checksum; it was created before checksums were added".to_string()),
+    }
+
+    // Bring older backup formats up to date; reject formats from newer builds
+    let mut upgraded = raw.clone();
+    match backup_format::upgrade_backup(&mut upgraded) {
+        Ok(original) => {
+            report.version_compatible = true;
+            if original < CURRENT_BACKUP_VERSION {
+                report.warnings.push(format!(
+                    "Backup format {} will be upgraded to {} when restored",
+                    format_version_string(original),
+                    format_version_string(CURRENT_BACKUP_VERSION)
+                ));
+            }
+        },
+        Err(e) => {
+            report.integrity_errors.push(e.to_string());
+            return (report, None);
+        },
+    }
+
+    match (
+        parse_version(&metadata.app_version),
+        parse_version(env!("CARGO_PKG_VERSION")),
+    ) {
+        (Some(backup_app), Some(running_app)) if backup_app > running_app => {
+            report.warnings.push(format!(
+                "Backup was created by a newer Home Registry ({}) than this server ({})",
+                metadata.app_version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        },
+        (None, _) => report.warnings.push(format!(
+            "Unrecognized app version '{}' in backup metadata",
+            metadata.app_version
+        )),
+        _ => {},
+    }
+
+    // Required tables
+    let data_section = upgraded.get("data");
+    for table in BackupDatabaseContent::TABLES {
+        let present = data_section
+            .and_then(|d| d.get(table))
+            .is_some_and(serde_json::Value::is_array);
+        if !present {
+            report.missing_tables.push(table.to_string());
+        }
+    }
+    if !report.missing_tables.is_empty() {
+        return (report, None);
+    }
+
+    let backup_data: BackupData = match serde_json::from_value(upgraded) {
+        Ok(data) => data,
+        Err(e) => {
+            report
+                .integrity_errors
+                .push(format!("Backup data could not be parsed: {e}"));
+            return (report, None);
+        },
+    };
+
+    report
+        .integrity_errors
+        .extend(check_referential_integrity(&backup_data.data));
+
+    report.valid = report.version_compatible
+        && report.missing_tables.is_empty()
+        && report.integrity_errors.is_empty();
+
+    (report, Some(backup_data))
+}
+
+/// List the inventories and users in a backup (existence flags are filled in by the caller)
+fn summarize_backup_contents(filename: &str, backup: &BackupData) -> BackupContents {
+    let data = &backup.data;
+
+    let users: Vec<BackupUserSummary> = backup_rows(&data.users)
+        .filter_map(|row| {
+            let id = backup_uuid(row, "id")?;
+            let inventory_count = backup_rows(&data.inventories)
+                .filter(|inv| backup_uuid(inv, "user_id") == Some(id))
+                .count();
+            Some(BackupUserSummary {
+                id,
+                username: backup_str(row, "username").to_string(),
+                full_name: backup_str(row, "full_name").to_string(),
+                is_admin: row
+                    .get("is_admin")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false),
+                inventory_count: i64::try_from(inventory_count).unwrap_or(i64::MAX),
+                exists_in_database: false,
+            })
+        })
+        .collect();
+
+    let inventories = backup_rows(&data.inventories)
+        .filter_map(|row| {
+            let id = backup_i64(row, "id").and_then(|id| i32::try_from(id).ok())?;
+            let owner_user_id = backup_uuid(row, "user_id");
+            let item_count = backup_rows(&data.items)
+                .filter(|item| backup_i64(item, "inventory_id") == Some(i64::from(id)))
+                .count();
+            Some(BackupInventorySummary {
+                id,
+                name: backup_str(row, "name").to_string(),
+                owner_user_id,
+                owner_username: owner_user_id.and_then(|owner| {
+                    users
+                        .iter()
+                        .find(|u| u.id == owner)
+                        .map(|u| u.username.clone())
+                }),
+                item_count: i64::try_from(item_count).unwrap_or(i64::MAX),
+                exists_in_database: false,
+            })
+        })
+        .collect();
+
+    BackupContents {
+        filename: filename.to_string(),
+        created_at: backup.metadata.created_at.clone(),
+        inventories,
+        users,
+    }
+}
+
+/// Read, parse and verify a stored (local or off-site) backup so it can be restored
+pub async fn load_stored_backup(
+    filename: &str,
+) -> std::result::Result<BackupData, BackupLoadError> {
+    let content = read_stored_backup(filename).await?;
+
+    let raw: serde_json::Value = serde_json::from_slice(&content).map_err(|e| {
+        error!("Invalid backup file format '{}': {}", filename, e);
+        BackupLoadError::InvalidFormat
+    })?;
+
+    match verify_backup_content(filename, &raw) {
+        (report, Some(data)) if report.valid => Ok(data),
+        (report, _) => {
+            let mut problems = report.integrity_errors;
+            if !report.missing_tables.is_empty() {
+                problems.push(format!(
+                    "Missing tables: {}",
+                    report.missing_tables.join(", ")
+                ));
+            }
+            warn!(
+                "Backup '{}' rejected by verification: {}",
+                filename,
+                problems.join("; ")
+            );
+            let error = if report.version_compatible || report.version.is_none() {
+                "Backup verification failed"
+            } else {
+                "Unsupported backup version"
+            };
+            Err(BackupLoadError::Rejected {
+                error,
+                problems: problems.join("; "),
+            })
+        },
+    }
+}
+
+/// Load a verified backup, returning a ready-to-send error response on failure
+async fn load_verified_backup(filename: &str) -> std::result::Result<BackupData, HttpResponse> {
+    load_stored_backup(filename)
+        .await
+        .map_err(|e| e.to_response(filename))
+}
+
+/// List local and off-site backups, newest first
+///
+/// Off-site storage problems don't fail the listing; the error is returned
+/// alongside the local backups instead.
+pub async fn list_backup_files(
+) -> std::result::Result<(Vec<BackupInfo>, Option<String>), std::io::Error> {
+    ensure_backups_dir().await?;
+
+    // Read directory and collect backup file info
+    let mut backups: Vec<BackupInfo> = Vec::new();
+    let mut entries = tokio::fs::read_dir(BACKUPS_DIR).await?;
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if let Some(ext) = path.extension() {
+            if ext == "json" {
+                if let Ok(metadata) = entry.metadata().await {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let date = metadata
+                        .modified()
+                        .map(|t| {
+                            let datetime: chrono::DateTime<chrono::Utc> = t.into();
+                            datetime.to_rfc3339()
+                        })
+                        .unwrap_or_default();
+                    let size = format_file_size(metadata.len());
+
+                    backups.push(BackupInfo {
+                        name,
+                        date,
+                        size,
+                        local: true,
+                        remote: false,
+                        replication_error: None,
+                    });
+                }
+            }
+        }
+    }
+
+    // Merge in off-site backups, marking ones that exist in both places
+    let mut remote_error = None;
+    if let Some(store) = RemoteBackupStore::from_env() {
+        match store.list().await {
+            Ok(objects) => {
+                for object in objects {
+                    if let Some(existing) = backups.iter_mut().find(|b| b.name == object.name) {
+                        existing.remote = true;
+                    } else {
+                        backups.push(BackupInfo {
+                            name: object.name,
+                            date: object.last_modified,
+                            size: format_file_size(object.size),
+                            local: false,
+                            remote: true,
+                            replication_error: None,
+                        });
+                    }
+                }
+            },
+            Err(e) => {
+                error!("Failed to list off-site backups: {}", e);
+                backup_remote::record_failure(&format!("Failed to list backups: {e}"));
+                remote_error = Some(e.to_string());
+            },
+        }
+    }
+
+    // Sort by date, newest first
+    backups.sort_by(|a, b| b.date.cmp(&a.date));
+
+    Ok((backups, remote_error))
+}
+
 // ==================== API Handlers ====================
 
 /// Create a new backup of all database data
@@ -138,10 +678,23 @@ pub async fn create_backup(pool: web::Data<Pool>, req: HttpRequest) -> Result<im
                 "Backup created by admin user {}: {}",
                 auth.username, backup_info.name
             );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::BackupCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("backup", &backup_info.name),
+            )
+            .await;
+            let message = if backup_info.replication_error.is_some() {
+                "Backup created successfully, but off-site replication failed"
+            } else {
+                "Backup created successfully"
+            };
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(backup_info),
-                message: Some("Backup created successfully".to_string()),
+                message: Some(message.to_string()),
                 error: None,
             }))
         },
@@ -167,20 +720,8 @@ pub async fn list_backups(pool: web::Data<Pool>, req: HttpRequest) -> Result<imp
         Err(e) => return Ok(e),
     };
 
-    // Ensure backups directory exists
-    if let Err(e) = ensure_backups_dir().await {
-        error!("Failed to create backups directory: {}", e);
-        return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-            success: false,
-            error: "An internal error occurred".to_string(),
-            message: Some("Failed to access backups directory".to_string()),
-        }));
-    }
-
-    // Read directory and collect backup file info
-    let mut backups: Vec<BackupInfo> = Vec::new();
-    let mut entries = match tokio::fs::read_dir(BACKUPS_DIR).await {
-        Ok(entries) => entries,
+    let (backups, remote_error) = match list_backup_files().await {
+        Ok(result) => result,
         Err(e) => {
             error!("Failed to read backups directory: {}", e);
             return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -191,42 +732,23 @@ pub async fn list_backups(pool: web::Data<Pool>, req: HttpRequest) -> Result<imp
         },
     };
 
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let path = entry.path();
-        if let Some(ext) = path.extension() {
-            if ext == "json" {
-                if let Ok(metadata) = entry.metadata().await {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let date = metadata
-                        .modified()
-                        .map(|t| {
-                            let datetime: chrono::DateTime<chrono::Utc> = t.into();
-                            datetime.to_rfc3339()
-                        })
-                        .unwrap_or_default();
-                    let size = format_file_size(metadata.len());
-
-                    backups.push(BackupInfo { name, date, size });
-                }
-            }
-        }
-    }
-
-    // Sort by date, newest first
-    backups.sort_by(|a, b| b.date.cmp(&a.date));
-
     let count = backups.len();
     info!("Listed {} backups for admin user {}", count, auth.username);
 
+    let message = match remote_error {
+        Some(e) => format!("Retrieved {count} backups (off-site storage unavailable: {e})"),
+        None => format!("Retrieved {count} backups"),
+    };
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(backups),
-        message: Some(format!("Retrieved {count} backups")),
+        message: Some(message),
         error: None,
     }))
 }
 
-/// Download a backup file
+/// Download a backup file (from off-site storage if it is not stored locally)
 ///
 /// GET /api/backup/download/{filename}
 /// Requires: Admin authentication
@@ -243,50 +765,30 @@ pub async fn download_backup(
 
     let filename = path.into_inner();
 
-    // Validate filename to prevent path traversal
-    if let Err(e) = validate_backup_filename(&filename) {
-        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
-            success: false,
-            error: e,
-            message: Some("Invalid backup filename".to_string()),
-        }));
-    }
-
-    let filepath = format!("{BACKUPS_DIR}/{filename}");
-
-    // Verify file exists
-    if !tokio::fs::try_exists(&filepath).await.unwrap_or(false) {
-        return Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: "Backup file not found".to_string(),
-            message: Some(format!("Backup '{filename}' does not exist")),
-        }));
-    }
+    let content = match read_backup_file(&filename).await {
+        Ok(c) => c,
+        Err(response) => return Ok(response),
+    };
 
-    // Read file content
-    match tokio::fs::read(&filepath).await {
-        Ok(content) => {
-            info!(
-                "Backup '{}' downloaded by admin user {}",
-                filename, auth.username
-            );
-            Ok(HttpResponse::Ok()
-                .content_type("application/json")
-                .insert_header((
-                    "Content-Disposition",
-                    format!("attachment; filename=\"{filename}\""),
-                ))
-                .body(content))
-        },
-        Err(e) => {
-            error!("Failed to read backup file '{}': {}", filename, e);
-            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                success: false,
-                error: "An internal error occurred".to_string(),
-                message: Some("Failed to read backup file".to_string()),
-            }))
-        },
-    }
+    info!(
+        "Backup '{}' downloaded by admin user {}",
+        filename, auth.username
+    );
+    super::audit::record(
+        &DatabaseService::new(pool.get_ref().clone()),
+        &req,
+        NewAuditEvent::new(AuditAction::BackupDownloaded)
+            .actor(auth.user_id, &auth.username)
+            .target("backup", &filename),
+    )
+    .await;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{filename}\""),
+        ))
+        .body(content))
 }
 
 /// Upload a backup file
@@ -340,8 +842,8 @@ pub async fn upload_backup(
     };
 
     // Validate JSON structure
-    let backup_data: BackupData = match serde_json::from_slice(&content) {
-        Ok(data) => data,
+    let mut raw: serde_json::Value = match serde_json::from_slice(&content) {
+        Ok(value) => value,
         Err(e) => {
             error!("Invalid backup file format: {}", e);
             return Ok(HttpResponse::BadRequest().json(ErrorResponse {
@@ -352,18 +854,28 @@ pub async fn upload_backup(
         },
     };
 
-    // Validate backup version
-    if backup_data.metadata.version != "1.0" {
+    // Validate backup version (older formats are upgraded in memory only;
+    // the file is stored as uploaded)
+    if let Err(e) = backup_format::upgrade_backup(&mut raw) {
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
             success: false,
-            error: format!(
-                "Unsupported backup version: {}",
-                backup_data.metadata.version
-            ),
+            error: e.to_string(),
             message: Some("This backup version is not supported".to_string()),
         }));
     }
 
+    let backup_data: BackupData = match serde_json::from_value(raw) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("Invalid backup file format: {}", e);
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid backup file format".to_string(),
+                message: Some("The uploaded file is not a valid Home Registry backup".to_string()),
+            }));
+        },
+    };
+
     // Ensure backups directory exists
     if let Err(e) = ensure_backups_dir().await {
         error!("Failed to create backups directory: {}", e);
@@ -418,6 +930,14 @@ pub async fn upload_backup(
         auth.username,
         format_file_size(file_size)
     );
+    super::audit::record(
+        &DatabaseService::new(pool.get_ref().clone()),
+        &req,
+        NewAuditEvent::new(AuditAction::BackupUploaded)
+            .actor(auth.user_id, &auth.username)
+            .target("backup", &target_filename),
+    )
+    .await;
 
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
@@ -425,17 +945,100 @@ pub async fn upload_backup(
             name: target_filename,
             date: backup_data.metadata.created_at,
             size: format_file_size(file_size),
+            local: true,
+            remote: false,
+            replication_error: None,
         }),
         message: Some("Backup uploaded successfully".to_string()),
         error: None,
     }))
 }
 
+/// Verify a backup file without restoring it
+///
+/// POST /api/backup/verify/{filename}
+/// Requires: Admin authentication
+/// Checks the checksum, format version, required tables and referential
+/// integrity. With `dry_run=true` the data is also loaded into a temporary
+/// schema (rolled back afterwards) and per-table row counts are reported.
+#[post("/backup/verify/{filename}")]
+pub async fn verify_backup(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<VerifyBackupQuery>,
+) -> Result<impl Responder> {
+    let auth = match auth::require_admin(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let filename = path.into_inner();
+
+    let content = match read_backup_file(&filename).await {
+        Ok(c) => c,
+        Err(response) => return Ok(response),
+    };
+
+    let raw: serde_json::Value = match serde_json::from_slice(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("Backup '{}' is not valid JSON: {}", filename, e);
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "Invalid backup file format".to_string(),
+                message: Some("The backup file could not be parsed".to_string()),
+            }));
+        },
+    };
+
+    let (mut report, backup_data) = verify_backup_content(&filename, &raw);
+
+    // Only attempt a dry run when the static checks pass
+    if query.dry_run {
+        match backup_data {
+            Some(ref data) if report.valid => {
+                let db_service = DatabaseService::new(pool.get_ref().clone());
+                match db_service.dry_run_import(&data.data).await {
+                    Ok(counts) => report.dry_run = Some(counts),
+                    Err(e) => {
+                        warn!("Dry-run restore of '{}' failed: {}", filename, e);
+                        report.valid = false;
+                        report.dry_run_error = Some(e.to_string());
+                    },
+                }
+            },
+            _ => {
+                report.dry_run_error =
+                    Some("Dry run skipped because verification failed".to_string());
+            },
+        }
+    }
+
+    info!(
+        "Backup '{}' verified by admin user {} (valid: {})",
+        filename, auth.username, report.valid
+    );
+
+    let message = if report.valid {
+        "Backup verified successfully".to_string()
+    } else {
+        "Backup verification found problems".to_string()
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(report),
+        message: Some(message),
+        error: None,
+    }))
+}
+
 /// Restore database from a backup file
 ///
 /// POST /api/backup/restore/{filename}
 /// Requires: Admin authentication
-/// Creates an automatic backup before restoring (safety net)
+/// Verifies the backup first, then creates an automatic backup before restoring (safety net)
 #[post("/backup/restore/{filename}")]
 pub async fn restore_backup(
     pool: web::Data<Pool>,
@@ -449,25 +1052,11 @@ pub async fn restore_backup(
 
     let filename = path.into_inner();
 
-    // Validate filename
-    if let Err(e) = validate_backup_filename(&filename) {
-        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
-            success: false,
-            error: e,
-            message: Some("Invalid backup filename".to_string()),
-        }));
-    }
-
-    let filepath = format!("{BACKUPS_DIR}/{filename}");
-
-    // Verify backup file exists
-    if !tokio::fs::try_exists(&filepath).await.unwrap_or(false) {
-        return Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: "Backup file not found".to_string(),
-            message: Some(format!("Backup '{filename}' does not exist")),
-        }));
-    }
+    // Read and verify the backup before touching anything
+    let backup_data = match load_verified_backup(&filename).await {
+        Ok(data) => data,
+        Err(response) => return Ok(response),
+    };
 
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
@@ -491,75 +1080,311 @@ pub async fn restore_backup(
         },
     };
 
-    // Read and parse the backup file
-    let content = match tokio::fs::read(&filepath).await {
-        Ok(c) => c,
+    // Perform the restore within a transaction
+    match db_service.import_all_data(&backup_data.data).await {
+        Ok(()) => {
+            info!(
+                "Backup '{}' restored by admin user {}",
+                filename, auth.username
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::BackupRestored)
+                    .actor(auth.user_id, &auth.username)
+                    .target("backup", &filename)
+                    .details(format!(
+                        "Full restore, pre-restore backup {}",
+                        auto_backup_info.name
+                    )),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+                success: true,
+                data: None,
+                message: Some(format!(
+                    "Backup restored successfully. A pre-restore backup was created: {}",
+                    auto_backup_info.name
+                )),
+                error: None,
+            }))
+        },
         Err(e) => {
-            error!("Failed to read backup file '{}': {}", filename, e);
+            error!("Failed to restore backup '{}': {}", filename, e);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::BackupRestored)
+                    .actor(auth.user_id, &auth.username)
+                    .target("backup", &filename)
+                    .outcome(AuditOutcome::Failure)
+                    .details("Full restore"),
+            )
+            .await;
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some(format!(
+                    "Failed to restore backup. Your pre-restore backup is available: {}",
+                    auto_backup_info.name
+                )),
+            }))
+        },
+    }
+}
+
+/// Browse the inventories and users contained in a backup
+///
+/// GET /api/backup/browse/{filename}
+/// Requires: Admin authentication
+#[get("/backup/browse/{filename}")]
+pub async fn browse_backup(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<impl Responder> {
+    let auth = match auth::require_admin(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let filename = path.into_inner();
+    let backup_data = match load_verified_backup(&filename).await {
+        Ok(data) => data,
+        Err(response) => return Ok(response),
+    };
+
+    let mut contents = summarize_backup_contents(&filename, &backup_data);
+
+    // Flag records that still exist so the admin can spot what was deleted
+    let user_ids: Vec<Uuid> = contents.users.iter().map(|u| u.id).collect();
+    let inventory_ids: Vec<i32> = contents.inventories.iter().map(|i| i.id).collect();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .get_existing_record_ids(&user_ids, &inventory_ids)
+        .await
+    {
+        Ok((live_users, live_inventories)) => {
+            for user in &mut contents.users {
+                user.exists_in_database = live_users.contains(&user.id);
+            }
+            for inventory in &mut contents.inventories {
+                inventory.exists_in_database = live_inventories.contains(&inventory.id);
+            }
+        },
+        Err(e) => {
+            error!(
+                "Failed to compare backup '{}' with live data: {}",
+                filename, e
+            );
             return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
                 error: "An internal error occurred".to_string(),
-                message: Some("Failed to read backup file".to_string()),
+                message: Some("Failed to read backup contents".to_string()),
             }));
         },
+    }
+
+    info!(
+        "Backup '{}' browsed by admin user {}",
+        filename, auth.username
+    );
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(contents),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Restore a single inventory from a backup without touching other data
+///
+/// `POST /api/backup/restore/{filename}/inventory/{inventory_id}`
+/// Requires: Admin authentication
+/// The inventory, its items, organizers and shares are merged back as new
+/// records with new ids.
+#[post("/backup/restore/{filename}/inventory/{inventory_id}")]
+pub async fn restore_backup_inventory(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+) -> Result<impl Responder> {
+    let auth = match auth::require_admin(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
     };
 
-    let backup_data: BackupData = match serde_json::from_slice(&content) {
+    let (filename, inventory_id) = path.into_inner();
+    let backup_data = match load_verified_backup(&filename).await {
         Ok(data) => data,
+        Err(response) => return Ok(response),
+    };
+
+    let Some(inventory) = backup_rows(&backup_data.data.inventories)
+        .find(|row| backup_i64(row, "id") == Some(i64::from(inventory_id)))
+    else {
+        return Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Inventory not found in backup".to_string(),
+            message: Some(format!(
+                "Backup '{filename}' does not contain inventory {inventory_id}"
+            )),
+        }));
+    };
+    let inventory_name = backup_str(inventory, "name").to_string();
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .restore_inventory_from_backup(&backup_data.data, inventory_id, auth.user_id)
+        .await
+    {
+        Ok(summary) => {
+            info!(
+                "Inventory {} ('{}') restored from backup '{}' by admin user {}",
+                inventory_id, inventory_name, filename, auth.username
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::BackupRestored)
+                    .actor(auth.user_id, &auth.username)
+                    .target("backup", &filename)
+                    .details(format!("Inventory {inventory_id} ('{inventory_name}')")),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(summary),
+                message: Some(format!("Inventory '{inventory_name}' restored from backup")),
+                error: None,
+            }))
+        },
         Err(e) => {
-            error!("Invalid backup file format '{}': {}", filename, e);
-            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            error!(
+                "Failed to restore inventory {} from backup '{}': {}",
+                inventory_id, filename, e
+            );
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
-                error: "Invalid backup file format".to_string(),
-                message: Some("The backup file could not be parsed".to_string()),
-            }));
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to restore inventory. No changes were made.".to_string()),
+            }))
         },
+    }
+}
+
+/// Restore a single user's account and data from a backup without touching other data
+///
+/// `POST /api/backup/restore/{filename}/user/{user_id}`
+/// Requires: Admin authentication
+#[post("/backup/restore/{filename}/user/{user_id}")]
+pub async fn restore_backup_user(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, Uuid)>,
+) -> Result<impl Responder> {
+    let auth = match auth::require_admin(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
     };
 
-    // Validate backup version
-    if backup_data.metadata.version != "1.0" {
-        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+    let (filename, user_id) = path.into_inner();
+    let backup_data = match load_verified_backup(&filename).await {
+        Ok(data) => data,
+        Err(response) => return Ok(response),
+    };
+
+    let Some(user) =
+        backup_rows(&backup_data.data.users).find(|row| backup_uuid(row, "id") == Some(user_id))
+    else {
+        return Ok(HttpResponse::NotFound().json(ErrorResponse {
             success: false,
-            error: format!(
-                "Unsupported backup version: {}",
-                backup_data.metadata.version
-            ),
-            message: Some("This backup version is not supported".to_string()),
+            error: "User not found in backup".to_string(),
+            message: Some(format!(
+                "Backup '{filename}' does not contain user {user_id}"
+            )),
+        }));
+    };
+    let username = backup_str(user, "username").to_string();
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    // A deleted account can only be recreated if its username is still free
+    if let (Ok(None), Ok(Some(_))) = (
+        db_service.get_user_by_id(user_id).await,
+        db_service.get_user_by_username(&username).await,
+    ) {
+        return Ok(HttpResponse::Conflict().json(ErrorResponse {
+            success: false,
+            error: "Username already exists".to_string(),
+            message: Some(format!(
+                "Another account now uses the username '{username}'"
+            )),
         }));
     }
 
-    // Perform the restore within a transaction
-    match db_service.import_all_data(&backup_data.data).await {
-        Ok(()) => {
+    match db_service
+        .restore_user_from_backup(&backup_data.data, user_id)
+        .await
+    {
+        Ok(summary) => {
             info!(
-                "Backup '{}' restored by admin user {}",
-                filename, auth.username
+                "User {} restored from backup '{}' by admin user {}",
+                username, filename, auth.username
             );
-            Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::BackupRestored)
+                    .actor(auth.user_id, &auth.username)
+                    .target("backup", &filename)
+                    .details(format!("User {user_id} ('{username}')")),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
-                data: None,
-                message: Some(format!(
-                    "Backup restored successfully. A pre-restore backup was created: {}",
-                    auto_backup_info.name
-                )),
+                data: Some(summary),
+                message: Some(format!("Data for user '{username}' restored from backup")),
                 error: None,
             }))
         },
         Err(e) => {
-            error!("Failed to restore backup '{}': {}", filename, e);
+            error!(
+                "Failed to restore user {} from backup '{}': {}",
+                user_id, filename, e
+            );
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
                 error: "An internal error occurred".to_string(),
-                message: Some(format!(
-                    "Failed to restore backup. Your pre-restore backup is available: {}",
-                    auto_backup_info.name
-                )),
+                message: Some("Failed to restore user. No changes were made.".to_string()),
             }))
         },
     }
 }
 
-/// Delete a backup file
+/// Get the off-site replication configuration and latest outcome
+///
+/// GET /api/backup/remote/status
+/// Requires: Admin authentication
+#[get("/backup/remote/status")]
+pub async fn remote_backup_status(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+) -> Result<impl Responder> {
+    if let Err(e) = auth::require_admin(&req, pool.get_ref()).await {
+        return Ok(e);
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(backup_remote::replication_status()),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Delete a backup file (both the local and the off-site copy)
 ///
 /// DELETE /api/backup/{filename}
 /// Requires: Admin authentication
@@ -586,9 +1411,21 @@ pub async fn delete_backup(
     }
 
     let filepath = format!("{BACKUPS_DIR}/{filename}");
+    let local_exists = tokio::fs::try_exists(&filepath).await.unwrap_or(false);
+
+    let store = RemoteBackupStore::from_env();
+    let remote_exists = match &store {
+        Some(store) => match store.exists(&filename).await {
+            Ok(exists) => exists,
+            Err(e) => {
+                warn!("Failed to check off-site copy of '{}': {}", filename, e);
+                false
+            },
+        },
+        None => false,
+    };
 
-    // Verify file exists
-    if !tokio::fs::try_exists(&filepath).await.unwrap_or(false) {
+    if !local_exists && !remote_exists {
         return Ok(HttpResponse::NotFound().json(ErrorResponse {
             success: false,
             error: "Backup file not found".to_string(),
@@ -596,27 +1433,52 @@ pub async fn delete_backup(
         }));
     }
 
-    // Delete the file
-    match tokio::fs::remove_file(&filepath).await {
-        Ok(()) => {
-            info!(
-                "Backup '{}' deleted by admin user {}",
-                filename, auth.username
-            );
-            Ok(HttpResponse::Ok().json(ApiResponse::<()> {
-                success: true,
-                data: None,
-                message: Some(format!("Backup {filename} deleted successfully")),
-                error: None,
-            }))
-        },
-        Err(e) => {
+    if local_exists {
+        if let Err(e) = tokio::fs::remove_file(&filepath).await {
             error!("Failed to delete backup '{}': {}", filename, e);
-            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
                 success: false,
                 error: "An internal error occurred".to_string(),
                 message: Some("Failed to delete backup file".to_string()),
-            }))
-        },
+            }));
+        }
+    }
+
+    // Remove the off-site copy as well so a deleted backup doesn't reappear in the list
+    let mut message = format!("Backup {filename} deleted successfully");
+    if let (Some(store), true) = (&store, remote_exists) {
+        if let Err(e) = store.delete(&filename).await {
+            error!("Failed to delete off-site copy of '{}': {}", filename, e);
+            backup_remote::record_failure(&format!("Failed to delete {filename}: {e}"));
+            if !local_exists {
+                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                    success: false,
+                    error: "An internal error occurred".to_string(),
+                    message: Some("Failed to delete off-site backup".to_string()),
+                }));
+            }
+            message = format!(
+                "Backup {filename} deleted locally, but the off-site copy could not be removed"
+            );
+        }
     }
+
+    info!(
+        "Backup '{}' deleted by admin user {}",
+        filename, auth.username
+    );
+    super::audit::record(
+        &DatabaseService::new(pool.get_ref().clone()),
+        &req,
+        NewAuditEvent::new(AuditAction::BackupDeleted)
+            .actor(auth.user_id, &auth.username)
+            .target("backup", &filename),
+    )
+    .await;
+    Ok(HttpResponse::Ok().json(ApiResponse::<()> {
+        success: true,
+        data: None,
+        message: Some(message),
+        error: None,
+    }))
 }
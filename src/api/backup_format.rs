@@ -0,0 +1,398 @@
+//! Backup file format versioning
+//!
+//! Every backup records the format version it was written with in
+//! `metadata.version`. Older backups are brought up to date by running the raw
+//! JSON through a chain of upgrade steps, each converting version N into N+1,
+//! before they are verified or restored. Backups written by a newer build than
+//! the running one are rejected rather than guessed at.
+//!
+//! To change the backup format, bump [`CURRENT_BACKUP_VERSION`] and append an
+//! `upgrade_vN_to_vN+1` step to [`UPGRADES`].
+
+use serde_json::{Map, Value};
+use std::fmt;
+
+use crate::models::BackupDatabaseContent;
+
+/// Backup format version written by this build
+pub const CURRENT_BACKUP_VERSION: u32 = 4;
+
+/// A single upgrade step, transforming a backup of version N into version N+1
+type UpgradeStep = fn(&mut Value) -> Result<(), String>;
+
+/// Upgrade steps in order; `UPGRADES[n - 1]` upgrades version `n` to `n + 1`
+const UPGRADES: [UpgradeStep; (CURRENT_BACKUP_VERSION - 1) as usize] =
+    [upgrade_v1_to_v2, upgrade_v2_to_v3, upgrade_v3_to_v4];
+
+/// Errors raised while bringing a backup up to the current format
+#[derive(Debug, PartialEq, Eq)]
+pub enum BackupUpgradeError {
+    /// `metadata.version` is missing or not a recognizable version
+    UnrecognizedVersion(String),
+    /// The backup was written by a newer build than this one
+    TooNew { found: u32, supported: u32 },
+    /// An upgrade step could not transform the backup
+    StepFailed { from: u32, message: String },
+}
+
+impl fmt::Display for BackupUpgradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnrecognizedVersion(version) => {
+                write!(f, "Unrecognized backup format version '{version}'")
+            },
+            Self::TooNew { found, supported } => write!(
+                f,
+                "Backup format version {} is newer than this server supports ({}). \
+                 Upgrade Home Registry before restoring this backup.",
+                format_version_string(*found),
+                format_version_string(*supported)
+            ),
+            Self::StepFailed { from, message } => write!(
+                f,
+                "Failed to upgrade backup from format version {} to {}: {message}",
+                format_version_string(*from),
+                format_version_string(from + 1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BackupUpgradeError {}
+
+/// Render a format version the way it is stored in `metadata.version` ("2.0")
+#[must_use]
+pub fn format_version_string(version: u32) -> String {
+    format!("{version}.0")
+}
+
+/// Parse `metadata.version`; accepts "2" as well as "2.0"
+#[must_use]
+pub fn parse_format_version(version: &str) -> Option<u32> {
+    let (major, minor) = version
+        .trim()
+        .split_once('.')
+        .unwrap_or((version.trim(), "0"));
+    if minor != "0" {
+        return None;
+    }
+    major.parse::<u32>().ok().filter(|v| *v >= 1)
+}
+
+/// Read the format version from a raw backup document
+pub fn backup_format_version(raw: &Value) -> Result<u32, BackupUpgradeError> {
+    let version = raw
+        .get("metadata")
+        .and_then(|m| m.get("version"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    parse_format_version(version)
+        .ok_or_else(|| BackupUpgradeError::UnrecognizedVersion(version.to_string()))
+}
+
+/// Upgrade a raw backup document in place to [`CURRENT_BACKUP_VERSION`]
+///
+/// Returns the version the backup was originally written with.
+pub fn upgrade_backup(raw: &mut Value) -> Result<u32, BackupUpgradeError> {
+    let original = backup_format_version(raw)?;
+    if original > CURRENT_BACKUP_VERSION {
+        return Err(BackupUpgradeError::TooNew {
+            found: original,
+            supported: CURRENT_BACKUP_VERSION,
+        });
+    }
+
+    for from in original..CURRENT_BACKUP_VERSION {
+        UPGRADES[(from - 1) as usize](raw)
+            .map_err(|message| BackupUpgradeError::StepFailed { from, message })?;
+    }
+
+    if let Some(metadata) = raw.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.insert(
+            "version".to_string(),
+            Value::String(format_version_string(CURRENT_BACKUP_VERSION)),
+        );
+    }
+
+    Ok(original)
+}
+
+// ==================== Upgrade Steps ====================
+
+/// 1.0 → 2.0: `password_reset_tokens` became a required table
+fn upgrade_v1_to_v2(raw: &mut Value) -> Result<(), String> {
+    let data = raw
+        .get_mut("data")
+        .and_then(Value::as_object_mut)
+        .ok_or("backup has no data section")?;
+    data.entry("password_reset_tokens")
+        .or_insert_with(|| Value::Array(vec![]));
+    Ok(())
+}
+
+/// 2.0 → 3.0: password reset tokens are stored hashed and expire
+///
+/// Legacy rows keep their id and owner but are hashed and marked as already
+/// expired, so they restore cleanly without becoming usable links.
+fn upgrade_v2_to_v3(raw: &mut Value) -> Result<(), String> {
+    let Some(rows) = raw
+        .get_mut("data")
+        .and_then(|d| d.get_mut("password_reset_tokens"))
+        .and_then(Value::as_array_mut)
+    else {
+        return Ok(());
+    };
+
+    for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+        let Some(token) = row.remove("token") else {
+            continue;
+        };
+        let token = token
+            .as_str()
+            .ok_or("password reset token is not a string")?;
+        row.insert(
+            "token_hash".to_string(),
+            Value::String(crate::auth::hash_password_reset_token(token)),
+        );
+        let expires_at = row
+            .get("created_at")
+            .cloned()
+            .unwrap_or_else(|| Value::String("1970-01-01T00:00:00Z".to_string()));
+        row.insert("expires_at".to_string(), expires_at);
+    }
+    Ok(())
+}
+
+/// 3.0 → 4.0: shares and All Access grants have to be accepted, grants have
+/// a permission level, items have a visibility, items and organizer types can
+/// be trashed, and more tables are backed up
+///
+/// A restore fills columns missing from a row with NULL rather than their
+/// default, so NOT NULL columns added since 3.0 are filled in here with the
+/// value their migration gave existing rows.
+fn upgrade_v3_to_v4(raw: &mut Value) -> Result<(), String> {
+    let data = raw
+        .get_mut("data")
+        .and_then(Value::as_object_mut)
+        .ok_or("backup has no data section")?;
+
+    // Tables backed up since 3.0 start out empty
+    for table in BackupDatabaseContent::TABLES {
+        data.entry(table).or_insert_with(|| Value::Array(vec![]));
+    }
+
+    // Shares and grants from before invitations were given without asking
+    fill_missing_column(data, "inventory_shares", "status", "accepted");
+    fill_missing_column(data, "user_access_grants", "status", "accepted");
+    // Grants from before permission levels gave full access
+    fill_missing_column(data, "user_access_grants", "permission_level", "full");
+    // Items from before per-item visibility were visible to everyone
+    fill_missing_column(data, "items", "visibility", "everyone");
+    // Nothing was in the trash yet
+    fill_missing_column(data, "items", "trashed_with_inventory", false);
+    fill_missing_column(data, "organizer_types", "trashed_with_inventory", false);
+    Ok(())
+}
+
+/// Set `column` to `value` on every row of `table` where it is missing or null
+fn fill_missing_column(
+    data: &mut Map<String, Value>,
+    table: &str,
+    column: &str,
+    value: impl Into<Value>,
+) {
+    let Some(rows) = data.get_mut(table).and_then(Value::as_array_mut) else {
+        return;
+    };
+    let value = value.into();
+    for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+        let entry = row.entry(column).or_insert(Value::Null);
+        if entry.is_null() {
+            *entry = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_format_version() {
+        assert_eq!(parse_format_version("1.0"), Some(1));
+        assert_eq!(parse_format_version("2"), Some(2));
+        assert_eq!(parse_format_version("1.1"), None);
+        assert_eq!(parse_format_version("0"), None);
+        assert_eq!(parse_format_version("abc"), None);
+    }
+
+    #[test]
+    fn test_upgrade_v1_adds_password_reset_tokens() {
+        let mut raw = json!({
+            "metadata": { "version": "1.0" },
+            "data": { "users": [] }
+        });
+
+        assert_eq!(upgrade_backup(&mut raw), Ok(1));
+        assert_eq!(raw["data"]["password_reset_tokens"], json!([]));
+        assert_eq!(
+            raw["metadata"]["version"],
+            json!(format_version_string(CURRENT_BACKUP_VERSION))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_keeps_existing_data() {
+        let mut raw = json!({
+            "metadata": { "version": "1.0" },
+            "data": { "password_reset_tokens": [{ "id": 1 }] }
+        });
+
+        upgrade_backup(&mut raw).unwrap();
+        assert_eq!(raw["data"]["password_reset_tokens"], json!([{ "id": 1 }]));
+    }
+
+    #[test]
+    fn test_upgrade_v2_hashes_and_expires_reset_tokens() {
+        let mut raw = json!({
+            "metadata": { "version": "2.0" },
+            "data": { "password_reset_tokens": [{
+                "id": "7f1c4a4e-0000-0000-0000-000000000000",
+                "token": "legacy-token",
+                "created_at": "2025-01-01T00:00:00Z"
+            }] }
+        });
+
+        assert_eq!(upgrade_backup(&mut raw), Ok(2));
+        let row = &raw["data"]["password_reset_tokens"][0];
+        assert!(row.get("token").is_none());
+        assert_eq!(
+            row["token_hash"],
+            json!(crate::auth::hash_password_reset_token("legacy-token"))
+        );
+        assert_eq!(row["expires_at"], json!("2025-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_upgrade_v3_accepts_existing_shares_and_grants() {
+        let mut raw = json!({
+            "metadata": { "version": "3.0" },
+            "data": {
+                "inventory_shares": [{ "id": "a", "permission_level": "view" }],
+                "user_access_grants": [
+                    { "id": "b", "status": null },
+                    { "id": "c", "status": "pending" }
+                ]
+            }
+        });
+
+        assert_eq!(upgrade_backup(&mut raw), Ok(3));
+        assert_eq!(
+            raw["data"]["inventory_shares"][0]["status"],
+            json!("accepted")
+        );
+        assert_eq!(
+            raw["data"]["user_access_grants"][0]["status"],
+            json!("accepted")
+        );
+        assert_eq!(
+            raw["data"]["user_access_grants"][1]["status"],
+            json!("pending")
+        );
+    }
+
+    #[test]
+    fn test_upgrade_v3_gives_existing_grants_full_access() {
+        let mut raw = json!({
+            "metadata": { "version": "3.0" },
+            "data": { "user_access_grants": [
+                { "id": "a" },
+                { "id": "b", "permission_level": "view" }
+            ] }
+        });
+
+        upgrade_backup(&mut raw).unwrap();
+        let grants = &raw["data"]["user_access_grants"];
+        assert_eq!(grants[0]["permission_level"], json!("full"));
+        assert_eq!(grants[1]["permission_level"], json!("view"));
+    }
+
+    #[test]
+    fn test_upgrade_v3_makes_existing_items_visible_to_everyone() {
+        let mut raw = json!({
+            "metadata": { "version": "3.0" },
+            "data": { "items": [{ "id": 1 }, { "id": 2, "visibility": "owner" }] }
+        });
+
+        upgrade_backup(&mut raw).unwrap();
+        assert_eq!(raw["data"]["items"][0]["visibility"], json!("everyone"));
+        assert_eq!(raw["data"]["items"][1]["visibility"], json!("owner"));
+    }
+
+    #[test]
+    fn test_upgrade_v3_adds_tables_backed_up_since() {
+        let mut raw = json!({ "metadata": { "version": "3.0" }, "data": {} });
+
+        upgrade_backup(&mut raw).unwrap();
+        for table in BackupDatabaseContent::TABLES {
+            assert_eq!(raw["data"][table], json!([]), "{table}");
+        }
+    }
+
+    #[test]
+    fn test_upgrade_v3_leaves_nothing_trashed_with_an_inventory() {
+        let mut raw = json!({
+            "metadata": { "version": "3.0" },
+            "data": {
+                "items": [{ "id": 1 }],
+                "organizer_types": [{ "id": 2 }]
+            }
+        });
+
+        upgrade_backup(&mut raw).unwrap();
+        assert_eq!(
+            raw["data"]["items"][0]["trashed_with_inventory"],
+            json!(false)
+        );
+        assert_eq!(
+            raw["data"]["organizer_types"][0]["trashed_with_inventory"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn test_current_version_is_unchanged() {
+        let version = format_version_string(CURRENT_BACKUP_VERSION);
+        let mut raw = json!({ "metadata": { "version": version }, "data": {} });
+        let before = raw.clone();
+
+        assert_eq!(upgrade_backup(&mut raw), Ok(CURRENT_BACKUP_VERSION));
+        assert_eq!(raw, before);
+    }
+
+    #[test]
+    fn test_newer_backup_is_rejected() {
+        let mut raw = json!({ "metadata": { "version": "99.0" }, "data": {} });
+
+        let err = upgrade_backup(&mut raw).unwrap_err();
+        assert_eq!(
+            err,
+            BackupUpgradeError::TooNew {
+                found: 99,
+                supported: CURRENT_BACKUP_VERSION
+            }
+        );
+        assert!(err.to_string().contains("newer than this server supports"));
+    }
+
+    #[test]
+    fn test_missing_version_is_rejected() {
+        let mut raw = json!({ "data": {} });
+
+        assert!(matches!(
+            upgrade_backup(&mut raw),
+            Err(BackupUpgradeError::UnrecognizedVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+//! Off-site backup replication to S3-compatible storage
+//!
+//! Replication is optional and enabled by setting `BACKUP_S3_ENDPOINT` and
+//! `BACKUP_S3_BUCKET`. Every backup created by the server is copied to the
+//! bucket under `BACKUP_S3_PREFIX`; the local `backups` directory remains the
+//! primary copy. Works with AWS S3 as well as self-hosted stores such as `MinIO`.
+//!
+//! | Variable                      | Default         | Purpose                                   |
+//! |-------------------------------|-----------------|-------------------------------------------|
+//! | `BACKUP_S3_ENDPOINT`          | —               | e.g. `http://minio:9000`                  |
+//! | `BACKUP_S3_BUCKET`            | —               | Bucket name (must already exist)          |
+//! | `BACKUP_S3_ACCESS_KEY`        | —               | Access key id                             |
+//! | `BACKUP_S3_SECRET_KEY`        | —               | Secret access key                         |
+//! | `BACKUP_S3_REGION`            | `us-east-1`     | Region name used for request signing      |
+//! | `BACKUP_S3_PREFIX`            | `home-registry/`| Key prefix for backup objects             |
+//! | `BACKUP_S3_PATH_STYLE`        | `true`          | Path-style URLs (required by MinIO)       |
+//! | `BACKUP_S3_RETENTION_COUNT`   | unset           | Keep only the newest N remote backups     |
+
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use s3::creds::Credentials;
+use s3::error::S3Error;
+use s3::{Bucket, Region};
+use std::env;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::models::RemoteBackupStatus;
+
+/// Backup files replicated to (and pruned from) the bucket all start with this
+const BACKUP_FILE_PREFIX: &str = "home_registry_";
+
+/// Outcome of the most recent replication activity, reported by the status endpoint
+static LAST_ACTIVITY: Mutex<ReplicationActivity> = Mutex::new(ReplicationActivity {
+    success_at: None,
+    uploaded: None,
+    failure_at: None,
+    error: None,
+});
+
+struct ReplicationActivity {
+    success_at: Option<DateTime<Utc>>,
+    uploaded: Option<String>,
+    failure_at: Option<DateTime<Utc>>,
+    error: Option<String>,
+}
+
+/// S3 connection settings
+#[derive(Debug, Clone)]
+pub struct RemoteBackupConfig {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Key prefix, normalized to end with `/` unless empty
+    pub prefix: String,
+    pub path_style: bool,
+    /// Number of newest remote backups to keep; `None` disables pruning
+    pub retention_count: Option<usize>,
+}
+
+impl RemoteBackupConfig {
+    /// Read the configuration from the environment; `None` when replication is disabled
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let non_empty = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+        let endpoint = non_empty("BACKUP_S3_ENDPOINT")?;
+        let bucket = non_empty("BACKUP_S3_BUCKET")?;
+
+        Some(Self {
+            endpoint,
+            bucket,
+            region: non_empty("BACKUP_S3_REGION").unwrap_or_else(|| "us-east-1".to_string()),
+            access_key: non_empty("BACKUP_S3_ACCESS_KEY").unwrap_or_default(),
+            secret_key: non_empty("BACKUP_S3_SECRET_KEY").unwrap_or_default(),
+            prefix: normalize_prefix(
+                &non_empty("BACKUP_S3_PREFIX").unwrap_or_else(|| "home-registry/".to_string()),
+            ),
+            path_style: non_empty("BACKUP_S3_PATH_STYLE")
+                .is_none_or(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "no")),
+            retention_count: non_empty("BACKUP_S3_RETENTION_COUNT")
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|n| *n > 0),
+        })
+    }
+}
+
+/// Normalize a key prefix so it is either empty or ends with a single `/`
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{trimmed}/")
+    }
+}
+
+/// A backup object stored in the bucket
+#[derive(Debug, Clone)]
+pub struct RemoteBackupObject {
+    pub name: String,
+    pub size: u64,
+    pub last_modified: String,
+}
+
+/// Client for the configured backup bucket
+pub struct RemoteBackupStore {
+    config: RemoteBackupConfig,
+    bucket: Box<Bucket>,
+}
+
+impl RemoteBackupStore {
+    pub fn new(config: RemoteBackupConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let credentials = Credentials::new(
+            Some(&config.access_key),
+            Some(&config.secret_key),
+            None,
+            None,
+            None,
+        )?;
+        let region = Region::Custom {
+            region: config.region.clone(),
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+        };
+
+        let mut bucket = Bucket::new(&config.bucket, region, credentials)?;
+        if config.path_style {
+            bucket = bucket.with_path_style();
+        }
+
+        Ok(Self { config, bucket })
+    }
+
+    /// Build a store from the environment
+    ///
+    /// Returns `None` when replication is not configured. A configuration that
+    /// cannot be turned into a client is logged and reported as a failure.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let config = RemoteBackupConfig::from_env()?;
+        match Self::new(config) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                error!("Invalid off-site backup configuration: {}", e);
+                record_failure(&format!("Invalid configuration: {e}"));
+                None
+            },
+        }
+    }
+
+    fn object_key(&self, filename: &str) -> String {
+        format!("{}{filename}", self.config.prefix)
+    }
+
+    /// Upload a backup file
+    pub async fn upload(
+        &self,
+        filename: &str,
+        content: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.bucket
+            .put_object_with_content_type(self.object_key(filename), content, "application/json")
+            .await?;
+        Ok(())
+    }
+
+    /// List backup files in the bucket, newest first
+    pub async fn list(&self) -> Result<Vec<RemoteBackupObject>, Box<dyn std::error::Error>> {
+        let pages = self.bucket.list(self.config.prefix.clone(), None).await?;
+
+        let mut objects: Vec<RemoteBackupObject> = pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .filter_map(|object| {
+                let name = object.key.strip_prefix(&self.config.prefix)?.to_string();
+                let is_backup = name.starts_with(BACKUP_FILE_PREFIX)
+                    && Path::new(&name)
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+                    && !name.contains('/');
+                is_backup.then_some(RemoteBackupObject {
+                    name,
+                    size: object.size,
+                    last_modified: object.last_modified,
+                })
+            })
+            .collect();
+
+        objects.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+        Ok(objects)
+    }
+
+    /// Download a backup file; `None` if it does not exist in the bucket
+    pub async fn download(
+        &self,
+        filename: &str,
+    ) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        match self.bucket.get_object(self.object_key(filename)).await {
+            Ok(response) => Ok(Some(response.to_vec())),
+            Err(S3Error::HttpFailWithBody(404, _)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check whether a backup file exists in the bucket
+    pub async fn exists(&self, filename: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        match self.bucket.head_object(self.object_key(filename)).await {
+            Ok((_, status)) => Ok(status != 404),
+            Err(S3Error::HttpFailWithBody(404, _)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete a backup file from the bucket (succeeds if it is already gone)
+    pub async fn delete(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.bucket.delete_object(self.object_key(filename)).await?;
+        Ok(())
+    }
+
+    /// Delete remote backups beyond the configured retention count
+    ///
+    /// Returns the names of the deleted backups.
+    pub async fn prune(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let Some(keep) = self.config.retention_count else {
+            return Ok(Vec::new());
+        };
+
+        let mut pruned = Vec::new();
+        for object in self.list().await?.into_iter().skip(keep) {
+            self.delete(&object.name).await?;
+            pruned.push(object.name);
+        }
+
+        if !pruned.is_empty() {
+            info!(
+                "Pruned {} off-site backups beyond retention of {}",
+                pruned.len(),
+                keep
+            );
+        }
+        Ok(pruned)
+    }
+
+    /// Upload a freshly created backup and apply retention, recording the outcome
+    ///
+    /// Failures are logged and recorded for the status endpoint rather than
+    /// failing backup creation; the error message is returned to the caller.
+    pub async fn replicate(&self, filename: &str, content: &[u8]) -> Result<(), String> {
+        if let Err(e) = self.upload(filename, content).await {
+            let message = format!("Failed to upload {filename}: {e}");
+            error!("Off-site backup replication failed: {}", message);
+            record_failure(&message);
+            return Err(message);
+        }
+        info!(
+            "Backup {} replicated to s3://{}/{}",
+            filename,
+            self.config.bucket,
+            self.object_key(filename)
+        );
+
+        if let Ok(mut activity) = LAST_ACTIVITY.lock() {
+            activity.success_at = Some(Utc::now());
+            activity.uploaded = Some(filename.to_string());
+        }
+
+        // Pruning failures don't undo a successful upload, but are still reported
+        if let Err(e) = self.prune().await {
+            let message = format!("Failed to prune old off-site backups: {e}");
+            warn!("{}", message);
+            record_failure(&message);
+        }
+
+        Ok(())
+    }
+}
+
+/// Record a replication failure for the status endpoint
+pub fn record_failure(message: &str) {
+    if let Ok(mut activity) = LAST_ACTIVITY.lock() {
+        activity.failure_at = Some(Utc::now());
+        activity.error = Some(message.to_string());
+    }
+}
+
+/// Current replication configuration and the outcome of the latest activity
+#[must_use]
+pub fn replication_status() -> RemoteBackupStatus {
+    let config = RemoteBackupConfig::from_env();
+    let mut status = RemoteBackupStatus {
+        enabled: config.is_some(),
+        endpoint: config.as_ref().map(|c| c.endpoint.clone()),
+        bucket: config.as_ref().map(|c| c.bucket.clone()),
+        prefix: config.as_ref().map(|c| c.prefix.clone()),
+        retention_count: config.as_ref().and_then(|c| c.retention_count),
+        last_success_at: None,
+        last_uploaded: None,
+        last_failure_at: None,
+        last_error: None,
+    };
+
+    if let Ok(activity) = LAST_ACTIVITY.lock() {
+        status.last_success_at = activity.success_at;
+        status.last_uploaded.clone_from(&activity.uploaded);
+        status.last_failure_at = activity.failure_at;
+        status.last_error.clone_from(&activity.error);
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_prefix() {
+        assert_eq!(normalize_prefix("home-registry"), "home-registry/");
+        assert_eq!(normalize_prefix("/nested/path/"), "nested/path/");
+        assert_eq!(normalize_prefix(""), "");
+        assert_eq!(normalize_prefix("/"), "");
+    }
+}
@@ -0,0 +1,74 @@
+//! Optimistic concurrency
+//!
+//! Items, inventories, organizer types and options, and user settings are sent
+//! with an `ETag` made from their `updated_at`, in the same format as in the JSON
+//! body. A `PUT` with an `If-Match` header only goes through when it names the
+//! current version. Otherwise it is answered with 412 and the current
+//! representation, so one edit never silently overwrites another.
+
+use actix_web::http::header::{self, EntityTag, Header, IfMatch};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::models::ApiResponse;
+
+/// The entity tag of a representation last changed at `updated_at`
+#[must_use]
+pub fn entity_tag(updated_at: DateTime<Utc>) -> EntityTag {
+    EntityTag::new_strong(updated_at.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// Add the `ETag` of a representation to a response, if it has a version
+pub(super) fn with_etag(
+    mut builder: HttpResponseBuilder,
+    updated_at: Option<DateTime<Utc>>,
+) -> HttpResponseBuilder {
+    if let Some(updated_at) = updated_at {
+        builder.insert_header(header::ETag(entity_tag(updated_at)));
+    }
+    builder
+}
+
+/// Check a request's `If-Match` header against the current representation
+///
+/// Returns the version the update has to apply to, or `None` when the request
+/// has no precondition. Fails with 412 when no tag names the current version.
+pub(super) fn check_if_match<T: Serialize>(
+    req: &HttpRequest,
+    current: &T,
+    updated_at: Option<DateTime<Utc>>,
+) -> Result<Option<DateTime<Utc>>, HttpResponse> {
+    if !req.headers().contains_key(header::IF_MATCH) {
+        return Ok(None);
+    }
+    let tags = match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => return Ok(None),
+        Ok(IfMatch::Items(tags)) => tags,
+        // A header that can't be parsed can't name the current version either
+        Err(_) => Vec::new(),
+    };
+    match updated_at {
+        Some(version) if tags.iter().any(|tag| tag.strong_eq(&entity_tag(version))) => {
+            Ok(Some(version))
+        },
+        _ => Err(precondition_failed(current, updated_at)),
+    }
+}
+
+/// Answer an update of something that changed since the client last read it,
+/// with its current representation
+pub(super) fn precondition_failed<T: Serialize>(
+    current: &T,
+    updated_at: Option<DateTime<Utc>>,
+) -> HttpResponse {
+    with_etag(HttpResponse::PreconditionFailed(), updated_at).json(ApiResponse {
+        success: false,
+        data: Some(current),
+        message: Some(
+            "Someone else changed this in the meantime. Review the current version and try again."
+                .to_string(),
+        ),
+        error: Some("Precondition failed".to_string()),
+    })
+}
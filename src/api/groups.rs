@@ -0,0 +1,797 @@
+//! Household groups
+//!
+//! Groups collect users (a household, a family) so inventories can be shared
+//! with all of them at once. Members get access to whatever is shared with the
+//! group; managers can also rename it and add or remove members. Access through
+//! a group is resolved on every request, so removing a member takes effect
+//! immediately.
+
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::AuthContext;
+use crate::db::DatabaseService;
+use crate::models::{
+    AddGroupMemberRequest, ApiResponse, AuditAction, Capability, CreateGroupRequest,
+    CreateInventoryGroupShareRequest, ErrorResponse, GroupRole, NewAuditEvent,
+    UpdateGroupMemberRequest, UpdateGroupRequest, UpdateInventoryShareRequest, UserGroup,
+    UserGroupWithMembers,
+};
+
+use super::auth::{
+    describe_share_schedule, get_auth_context_from_request, validate_share_schedule,
+};
+use super::require_capability;
+
+fn internal_error() -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        success: false,
+        error: "An internal error occurred".to_string(),
+        message: None,
+    })
+}
+
+fn group_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        success: false,
+        error: "Group not found".to_string(),
+        message: None,
+    })
+}
+
+/// Load a group the user can see: members see their groups, admins see all of them
+///
+/// Groups the user can't see answer 404 so their existence isn't revealed.
+async fn load_visible_group(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    group_id: Uuid,
+) -> Result<UserGroup, HttpResponse> {
+    match db_service.get_group(group_id, auth.user_id).await {
+        Ok(Some(group)) if group.my_role.is_some() || auth.is_admin => Ok(group),
+        Ok(_) => Err(group_not_found()),
+        Err(e) => {
+            error!("Error getting group: {}", e);
+            Err(internal_error())
+        },
+    }
+}
+
+/// Load a group the user manages (group managers and admins)
+async fn load_managed_group(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    group_id: Uuid,
+) -> Result<UserGroup, HttpResponse> {
+    let group = load_visible_group(db_service, auth, group_id).await?;
+    if group.my_role == Some(GroupRole::Manager) || auth.is_admin {
+        Ok(group)
+    } else {
+        Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Insufficient permissions".to_string(),
+            message: Some("Only group managers can change this group".to_string()),
+        }))
+    }
+}
+
+/// Refuse to remove or demote a group's last manager
+async fn ensure_other_manager(
+    db_service: &DatabaseService,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    match db_service.get_group_role(group_id, user_id).await {
+        Ok(Some(GroupRole::Manager)) => {},
+        Ok(_) => return Ok(()),
+        Err(e) => {
+            error!("Error getting group role: {}", e);
+            return Err(internal_error());
+        },
+    }
+
+    match db_service.count_group_managers(group_id).await {
+        Ok(count) if count > 1 => Ok(()),
+        Ok(_) => Err(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "A group needs at least one manager".to_string(),
+            message: Some("Make another member a manager first".to_string()),
+        })),
+        Err(e) => {
+            error!("Error counting group managers: {}", e);
+            Err(internal_error())
+        },
+    }
+}
+
+/// List the user's groups (admins see every group)
+#[get("/groups")]
+pub async fn get_groups(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service.get_groups(auth.user_id, auth.is_admin).await {
+        Ok(groups) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} groups", groups.len())),
+            data: Some(groups),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting groups: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Create a group; the creator becomes its first manager
+#[post("/groups")]
+pub async fn create_group(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<CreateGroupRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service.create_group(auth.user_id, &body).await {
+        Ok(group) => {
+            info!("User {} created group '{}'", auth.username, group.name);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group", &group.id)
+                    .details(format!("Created group '{}'", group.name)),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: Some(group),
+                message: Some("Group created".to_string()),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            error!("Error creating group: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Get a group with its members
+#[get("/groups/{id}")]
+pub async fn get_group(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = match load_visible_group(&db_service, &auth, path.into_inner()).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.get_group_members(group.id).await {
+        Ok(members) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(UserGroupWithMembers { group, members }),
+            message: None,
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting group members: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Rename or describe a group
+#[put("/groups/{id}")]
+pub async fn update_group(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateGroupRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = match load_managed_group(&db_service, &auth, path.into_inner()).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    if let Err(e) = db_service.update_group(group.id, &body).await {
+        error!("Error updating group: {}", e);
+        return Ok(internal_error());
+    }
+
+    info!("User {} updated group {}", auth.username, group.id);
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::GroupUpdated)
+            .actor(auth.user_id, &auth.username)
+            .target("group", &group.id),
+    )
+    .await;
+
+    match db_service.get_group(group.id, auth.user_id).await {
+        Ok(Some(group)) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(group),
+            message: Some("Group updated".to_string()),
+            error: None,
+        })),
+        Ok(None) => Ok(group_not_found()),
+        Err(e) => {
+            error!("Error getting group: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Delete a group, removing access to everything shared with it
+#[delete("/groups/{id}")]
+pub async fn delete_group(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = match load_managed_group(&db_service, &auth, path.into_inner()).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.delete_group(group.id).await {
+        Ok(true) => {
+            info!("User {} deleted group '{}'", auth.username, group.name);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupDeleted)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group", &group.id)
+                    .details(format!("Deleted group '{}'", group.name)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Group deleted".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(group_not_found()),
+        Err(e) => {
+            error!("Error deleting group: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Add a user to a group
+#[post("/groups/{id}/members")]
+pub async fn add_group_member(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<AddGroupMemberRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = match load_managed_group(&db_service, &auth, path.into_inner()).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    let user = match db_service.get_user_by_username(&body.username).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "User not found".to_string(),
+                message: Some(format!(
+                    "No user found with username: {username}",
+                    username = body.username
+                )),
+            }));
+        },
+        Err(e) => {
+            error!("Error finding user: {}", e);
+            return Ok(internal_error());
+        },
+    };
+
+    let role = body.role.unwrap_or(GroupRole::Member);
+    match db_service.add_group_member(group.id, user.id, role).await {
+        Ok(()) => {
+            info!(
+                "User {} added {} to group '{}' as {}",
+                auth.username, user.username, group.name, role
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupMemberAdded)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group", &group.id)
+                    .details(format!("Added '{}' as {role}", user.username)),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some(format!("{} added to the group", user.username)),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            if e.to_string().contains("duplicate") || e.to_string().contains("unique") {
+                return Ok(HttpResponse::Conflict().json(ErrorResponse {
+                    success: false,
+                    error: "Already a member".to_string(),
+                    message: Some(format!("{} is already in this group", user.username)),
+                }));
+            }
+            error!("Error adding group member: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Change a member's role
+#[put("/groups/{id}/members/{user_id}")]
+pub async fn update_group_member(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<UpdateGroupMemberRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let (group_id, user_id) = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = match load_managed_group(&db_service, &auth, group_id).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    if body.role == GroupRole::Member {
+        if let Err(response) = ensure_other_manager(&db_service, group.id, user_id).await {
+            return Ok(response);
+        }
+    }
+
+    match db_service
+        .update_group_member_role(group.id, user_id, body.role)
+        .await
+    {
+        Ok(true) => {
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupMemberUpdated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group", &group.id)
+                    .details(format!("Member {user_id} is now {}", body.role)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Member role updated".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Member not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error updating group member: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Remove a member from a group; members may also remove themselves
+#[delete("/groups/{id}/members/{user_id}")]
+pub async fn remove_group_member(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let (group_id, user_id) = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let group = if user_id == auth.user_id {
+        load_visible_group(&db_service, &auth, group_id).await
+    } else {
+        load_managed_group(&db_service, &auth, group_id).await
+    };
+    let group = match group {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+
+    if let Err(response) = ensure_other_manager(&db_service, group.id, user_id).await {
+        return Ok(response);
+    }
+
+    match db_service.remove_group_member(group.id, user_id).await {
+        Ok(true) => {
+            info!(
+                "User {} removed {} from group '{}'",
+                auth.username, user_id, group.name
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupMemberRemoved)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group", &group.id)
+                    .details(format!("Removed member {user_id}")),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Member removed from the group".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Member not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error removing group member: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// List the groups an inventory is shared with
+#[get("/inventories/{id}/group-shares")]
+pub async fn get_inventory_group_shares(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let inventory_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+
+    match db_service.get_inventory_group_shares(inventory_id).await {
+        Ok(shares) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} group shares", shares.len())),
+            data: Some(shares),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting group shares: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Share an inventory with a group the user belongs to
+#[post("/inventories/{id}/group-shares")]
+pub async fn create_inventory_group_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<CreateInventoryGroupShareRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let inventory_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+    let group = match load_visible_group(&db_service, &auth, body.group_id).await {
+        Ok(group) => group,
+        Err(response) => return Ok(response),
+    };
+    let role = match super::roles::resolve_share_role(
+        &db_service,
+        inventory_id,
+        body.permission_level,
+        body.role_id,
+    )
+    .await
+    {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
+
+    if let Err(response) = validate_share_schedule(body.starts_at, body.expires_at) {
+        return Ok(response);
+    }
+
+    match db_service
+        .create_inventory_group_share(
+            inventory_id,
+            group.id,
+            auth.user_id,
+            role.clone(),
+            body.starts_at,
+            body.expires_at,
+        )
+        .await
+    {
+        Ok(share) => {
+            info!(
+                "User {} shared inventory {} with group '{}' (permission: {})",
+                auth.username, inventory_id, group.name, role
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupShareCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group_share", &share.id)
+                    .details(format!(
+                        "Shared inventory {inventory_id} with group '{}' ({}){}",
+                        group.name,
+                        role,
+                        describe_share_schedule(body.starts_at, body.expires_at)
+                    )),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: Some(share),
+                message: Some(format!("Inventory shared with {}", group.name)),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            if e.to_string().contains("duplicate") || e.to_string().contains("unique") {
+                return Ok(HttpResponse::Conflict().json(ErrorResponse {
+                    success: false,
+                    error: "Already shared".to_string(),
+                    message: Some(format!(
+                        "This inventory is already shared with {}",
+                        group.name
+                    )),
+                }));
+            }
+            error!("Error creating group share: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Change the permission level of a group share
+#[put("/group-shares/{id}")]
+pub async fn update_inventory_group_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateInventoryShareRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let share_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let share = match db_service.get_inventory_group_share(share_id).await {
+        Ok(Some(share)) => share,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting group share: {}", e);
+            return Ok(internal_error());
+        },
+    };
+    if let Err(response) = require_capability(
+        &db_service,
+        &auth,
+        share.inventory_id,
+        Capability::ManageShares,
+    )
+    .await
+    {
+        return Ok(response);
+    }
+    let role = match super::roles::resolve_share_role(
+        &db_service,
+        share.inventory_id,
+        body.permission_level,
+        body.role_id,
+    )
+    .await
+    {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service
+        .update_inventory_group_share(share_id, role.clone())
+        .await
+    {
+        Ok(Some(share)) => {
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupShareUpdated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group_share", &share_id)
+                    .details(format!("Permission set to {role}")),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(share),
+                message: Some("Share permission updated".to_string()),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Share not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error updating group share: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Stop sharing an inventory with a group
+#[delete("/group-shares/{id}")]
+pub async fn delete_inventory_group_share(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let share_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let share = match db_service.get_inventory_group_share(share_id).await {
+        Ok(Some(share)) => share,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting group share: {}", e);
+            return Ok(internal_error());
+        },
+    };
+    if let Err(response) = require_capability(
+        &db_service,
+        &auth,
+        share.inventory_id,
+        Capability::ManageShares,
+    )
+    .await
+    {
+        return Ok(response);
+    }
+
+    match db_service.delete_inventory_group_share(share_id).await {
+        Ok(true) => {
+            info!(
+                "User {} stopped sharing inventory {} with group '{}'",
+                auth.username, share.inventory_id, share.group_name
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::GroupShareDeleted)
+                    .actor(auth.user_id, &auth.username)
+                    .target("group_share", &share_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Share removed successfully".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Share not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error deleting group share: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
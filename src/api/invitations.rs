@@ -0,0 +1,465 @@
+//! User invitations
+//!
+//! Lets admins, and inventory owners sharing their inventories, invite new
+//! users with a single-use link. The invitee picks their own username and
+//! password, and any role, inventory share or All Access grant attached to the
+//! invitation is applied when the account is created.
+
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+use uuid::Uuid;
+
+use crate::auth::{
+    generate_invitation_token, hash_invitation_token, hash_password, validate_password,
+    validate_username, INVITATION_DEFAULT_HOURS, INVITATION_MAX_HOURS,
+};
+use crate::db::DatabaseService;
+use crate::models::{
+    AcceptInvitationRequest, ApiResponse, AuditAction, AuditOutcome, Capability,
+    CreateInvitationRequest, CreateInvitationResponse, ErrorResponse, InvitationTokenRequest,
+    NewAuditEvent,
+};
+
+use super::auth::{get_auth_context_from_request, start_session};
+
+/// Describe what an invitation grants, for logs and the audit trail
+fn describe_invitation(request: &CreateInvitationRequest) -> String {
+    let mut parts = vec![if request.is_admin {
+        "Administrator".to_string()
+    } else {
+        "User".to_string()
+    }];
+    if let (Some(inventory_id), Some(level)) = (request.inventory_id, request.permission_level) {
+        parts.push(format!("inventory {inventory_id} ({level})"));
+    }
+    if request.grant_all_access {
+        parts.push("All Access".to_string());
+    }
+    parts.join(", ")
+}
+
+/// Invite a new user
+///
+/// Admins can invite anyone with any role. Other users can only invite people
+/// to inventories they may share, or give them All Access to their own
+/// inventories, and cannot create administrators.
+#[post("/invitations")]
+pub async fn create_invitation(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<CreateInvitationRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let hours = body.expires_in_hours.unwrap_or(INVITATION_DEFAULT_HOURS);
+    if !(1..=INVITATION_MAX_HOURS).contains(&hours) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(format!(
+                "expires_in_hours must be between 1 and {INVITATION_MAX_HOURS}"
+            )),
+        }));
+    }
+
+    if body.inventory_id.is_some() != body.permission_level.is_some() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some("inventory_id and permission_level must be given together".to_string()),
+        }));
+    }
+
+    if !auth.is_admin {
+        if body.is_admin {
+            return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Admin access required".to_string(),
+                message: Some("Only administrators can invite administrators".to_string()),
+            }));
+        }
+        if body.inventory_id.is_none() && !body.grant_all_access {
+            return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Admin access required".to_string(),
+                message: Some(
+                    "Invitations from non-administrators must share an inventory or grant All Access"
+                        .to_string(),
+                ),
+            }));
+        }
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    if let Some(inventory_id) = body.inventory_id {
+        match db_service.get_inventory_by_id(inventory_id).await {
+            Ok(Some(_)) => {},
+            Ok(None) => {
+                return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                    success: false,
+                    error: "Inventory not found".to_string(),
+                    message: None,
+                }));
+            },
+            Err(e) => {
+                error!("Error getting inventory: {}", e);
+                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                    success: false,
+                    error: "An internal error occurred".to_string(),
+                    message: None,
+                }));
+            },
+        }
+
+        if let Err(response) =
+            super::require_capability(&db_service, &auth, inventory_id, Capability::ManageShares)
+                .await
+        {
+            return Ok(response);
+        }
+    }
+
+    // Opportunistically clear out old invitations; failure here shouldn't block creating one
+    if let Err(e) = db_service.delete_expired_invitations().await {
+        warn!("Failed to delete expired invitations: {}", e);
+    }
+
+    let token = generate_invitation_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(hours);
+    let invitation = match db_service
+        .create_invitation(
+            &hash_invitation_token(&token),
+            auth.user_id,
+            &body,
+            expires_at,
+        )
+        .await
+    {
+        Ok(invitation) => invitation,
+        Err(e) => {
+            error!("Error creating invitation: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to create invitation".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let description = describe_invitation(&body);
+    info!(
+        "User {} created invitation {} ({}, valid {} hours)",
+        auth.username, invitation.id, description, hours
+    );
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::InvitationCreated)
+            .actor(auth.user_id, &auth.username)
+            .target("invitation", &invitation.id)
+            .details(description),
+    )
+    .await;
+
+    Ok(HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(CreateInvitationResponse {
+            invitation,
+            invite_path: format!("/invite?token={token}"),
+            token,
+        }),
+        message: Some("Invitation created - the link will not be shown again".to_string()),
+        error: None,
+    }))
+}
+
+/// List pending invitations (admins see every invitation, others their own)
+#[get("/invitations")]
+pub async fn get_invitations(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let invited_by = if auth.is_admin {
+        None
+    } else {
+        Some(auth.user_id)
+    };
+
+    match db_service.get_pending_invitations(invited_by).await {
+        Ok(invitations) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!(
+                "Retrieved {} pending invitations",
+                invitations.len()
+            )),
+            data: Some(invitations),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting invitations: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve invitations".to_string()),
+            }))
+        },
+    }
+}
+
+/// Revoke an invitation (admins, or the user who sent it)
+#[delete("/invitations/{id}")]
+pub async fn revoke_invitation(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let invitation_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let invitation = match db_service.get_invitation(invitation_id).await {
+        Ok(Some(invitation)) => invitation,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Invitation not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting invitation: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    if invitation.invited_by != auth.user_id && !auth.is_admin {
+        return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Access denied".to_string(),
+            message: Some("You can only revoke invitations you have sent".to_string()),
+        }));
+    }
+
+    if invitation.accepted_at.is_some() {
+        return Ok(HttpResponse::Conflict().json(ErrorResponse {
+            success: false,
+            error: "Invitation already accepted".to_string(),
+            message: None,
+        }));
+    }
+
+    match db_service.delete_invitation(invitation_id).await {
+        Ok(true) => {
+            info!(
+                "User {} revoked invitation {}",
+                auth.username, invitation_id
+            );
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::InvitationRevoked)
+                    .actor(auth.user_id, &auth.username)
+                    .target("invitation", &invitation_id),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Invitation revoked".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Invitation not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error deleting invitation: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Show what a pending invitation grants (no authentication required)
+#[post("/auth/invitations/preview")]
+pub async fn preview_invitation(
+    pool: web::Data<Pool>,
+    body: web::Json<InvitationTokenRequest>,
+) -> Result<impl Responder> {
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service
+        .get_pending_invitation_by_token_hash(&hash_invitation_token(body.token.trim()))
+        .await
+    {
+        Ok(Some(invitation)) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(invitation),
+            message: None,
+            error: None,
+        })),
+        Ok(None) => Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Invalid or expired invitation".to_string(),
+            message: Some("Ask whoever invited you for a new link".to_string()),
+        })),
+        Err(e) => {
+            error!("Error getting invitation: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Accept an invitation by creating an account, then log in (no authentication required)
+#[post("/auth/invitations/accept")]
+pub async fn accept_invitation(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<AcceptInvitationRequest>,
+) -> Result<impl Responder> {
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let token_hash = hash_invitation_token(body.token.trim());
+
+    let invalid_invitation = || {
+        HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Invalid or expired invitation".to_string(),
+            message: Some("Ask whoever invited you for a new link".to_string()),
+        })
+    };
+
+    let invitation = match db_service
+        .get_pending_invitation_by_token_hash(&token_hash)
+        .await
+    {
+        Ok(Some(invitation)) => invitation,
+        Ok(None) => return Ok(invalid_invitation()),
+        Err(e) => {
+            error!("Error getting invitation: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    if let Err(msg) = validate_username(&body.username) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: msg.to_string(),
+            message: Some("Invalid username".to_string()),
+        }));
+    }
+
+    if let Err(msg) = validate_password(&body.password) {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: msg.to_string(),
+            message: Some("Invalid password".to_string()),
+        }));
+    }
+
+    if let Ok(Some(_)) = db_service.get_user_by_username(&body.username).await {
+        return Ok(HttpResponse::Conflict().json(ErrorResponse {
+            success: false,
+            error: "Username already taken".to_string(),
+            message: Some("Please choose a different username".to_string()),
+        }));
+    }
+
+    let password_hash = match hash_password(body.password.clone()).await {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Error hashing password: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Failed to process password".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let user = match db_service
+        .accept_invitation(&token_hash, &body.username, &body.full_name, &password_hash)
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(invalid_invitation()),
+        Err(e) => {
+            error!("Error accepting invitation: {}", e);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::InvitationAccepted)
+                    .claimed_username(&body.username)
+                    .target("invitation", &invitation.id)
+                    .outcome(AuditOutcome::Failure),
+            )
+            .await;
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to create account".to_string()),
+            }));
+        },
+    };
+
+    info!(
+        "User {} joined via an invitation from {}",
+        user.username, invitation.invited_by_username
+    );
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::InvitationAccepted)
+            .actor(user.id, &user.username)
+            .target("invitation", &invitation.id)
+            .details(format!("Invited by '{}'", invitation.invited_by_username)),
+    )
+    .await;
+
+    let session = match start_session(&db_service, user, &req).await {
+        Ok(session) => session,
+        Err(e) => {
+            error!("Error generating token: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "Account created but login failed".to_string(),
+                message: Some("Please log in manually".to_string()),
+            }));
+        },
+    };
+
+    Ok(HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(session),
+        message: Some("Welcome! Your account has been created".to_string()),
+        error: None,
+    }))
+}
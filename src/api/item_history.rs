@@ -0,0 +1,221 @@
+//! Item history
+//!
+//! Every change to an item's fields or organizer values is kept as a numbered
+//! revision listing who changed which fields, and their old and new values.
+//! An item can be reverted to any earlier revision, which adds a new revision
+//! rather than rewriting the history.
+
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+
+use crate::auth::AuthContext;
+use crate::db::DatabaseService;
+use crate::models::{
+    ApiResponse, Capability, ErrorResponse, ItemFieldChange, ItemHistoryAction,
+    ItemOrganizerValueWithDetails, ItemRevision,
+};
+
+use super::auth::get_auth_context_from_request;
+use super::{require_item_capability, require_move_access};
+
+fn internal_error(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        success: false,
+        error: "An internal error occurred".to_string(),
+        message: Some(message.to_string()),
+    })
+}
+
+/// Add a revision to an item's history. Failures are logged but never fail the
+/// change being recorded.
+pub(super) async fn record(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    item_id: i32,
+    action: ItemHistoryAction,
+    reverted_to_revision: Option<i32>,
+    changes: &[ItemFieldChange],
+) {
+    if let Err(e) = db_service
+        .record_item_revision(
+            item_id,
+            action,
+            reverted_to_revision,
+            auth.user_id,
+            &auth.username,
+            changes,
+        )
+        .await
+    {
+        warn!("Failed to record history of item {}: {}", item_id, e);
+    }
+}
+
+/// Organizer values of an item, or none if they can't be read. Only used to work
+/// out history entries, so a failure shouldn't stop the edit itself.
+pub(super) async fn organizer_values_or_empty(
+    db_service: &DatabaseService,
+    item_id: i32,
+) -> Vec<ItemOrganizerValueWithDetails> {
+    db_service
+        .get_item_organizer_values(item_id)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to read organizer values of item {}: {}", item_id, e);
+            Vec::new()
+        })
+}
+
+/// List an item's revisions, newest first
+///
+/// Needs view access to the inventory, at a level that can see the item.
+#[get("/items/{id}/history")]
+pub async fn get_item_history(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let item_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::View).await
+    {
+        return Ok(response);
+    }
+
+    match db_service.get_item_history(item_id).await {
+        Ok(history) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} revisions", history.len())),
+            data: Some(history),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error retrieving history of item {}: {}", item_id, e);
+            Ok(internal_error("Failed to retrieve item history"))
+        },
+    }
+}
+
+/// Put an item's fields and organizer values back the way they were at `revision`
+///
+/// Needs the same access as editing the item, plus moving access when the item
+/// has changed inventory since. Organizer options deleted in the meantime can't
+/// be restored and are left out.
+#[post("/items/{id}/history/{revision}/revert")]
+pub async fn revert_item(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let (item_id, revision) = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let item =
+        match require_item_capability(&db_service, &auth, item_id, Capability::EditItems).await {
+            Ok(item) => item,
+            Err(response) => return Ok(response),
+        };
+
+    let history = match db_service.get_item_history(item_id).await {
+        Ok(history) => history,
+        Err(e) => {
+            error!("Error retrieving history of item {}: {}", item_id, e);
+            return Ok(internal_error("Failed to revert item"));
+        },
+    };
+    if !history.iter().any(|r| r.revision == revision) {
+        return Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: format!("Revision {revision} of item {item_id} not found"),
+            message: Some("Revision not found".to_string()),
+        }));
+    }
+    let later: Vec<ItemRevision> = history
+        .into_iter()
+        .filter(|r| r.revision > revision)
+        .collect();
+
+    let organizers_before = match db_service.get_item_organizer_values(item_id).await {
+        Ok(values) => values,
+        Err(e) => {
+            error!("Error retrieving item organizer values: {}", e);
+            return Ok(internal_error("Failed to revert item"));
+        },
+    };
+    let changes = ItemFieldChange::reverting(&later, &item, &organizers_before);
+    if changes.is_empty() {
+        return Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(item),
+            message: Some(format!("Item already matches revision {revision}")),
+            error: None,
+        }));
+    }
+
+    if let Some(target_id) = changes
+        .iter()
+        .find(|change| change.field == "inventory_id")
+        .and_then(|change| change.new_value.as_i64())
+        .and_then(|id| i32::try_from(id).ok())
+    {
+        if let Err(response) =
+            require_move_access(&db_service, &auth, item.inventory_id, target_id).await
+        {
+            return Ok(response);
+        }
+    }
+
+    match db_service.apply_item_changes(item_id, &changes).await {
+        Ok(Some(reverted)) => {
+            // Record what actually changed, which leaves out organizer values that
+            // couldn't be restored
+            let organizers_after = organizer_values_or_empty(&db_service, item_id).await;
+            let mut applied = ItemFieldChange::between_items(&item, &reverted);
+            applied.extend(ItemFieldChange::between_organizer_values(
+                &organizers_before,
+                &organizers_after,
+            ));
+            record(
+                &db_service,
+                &auth,
+                item_id,
+                ItemHistoryAction::Reverted,
+                Some(revision),
+                &applied,
+            )
+            .await;
+            info!(
+                "User {} reverted item {} to revision {}",
+                auth.username, item_id, revision
+            );
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(reverted),
+                message: Some(format!("Item reverted to revision {revision}")),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: format!("Item with id {item_id} not found"),
+            message: Some("Item not found".to_string()),
+        })),
+        Err(e) => {
+            error!("Error reverting item {}: {}", item_id, e);
+            Ok(internal_error("Failed to revert item"))
+        },
+    }
+}
@@ -1,14 +1,27 @@
+pub mod audit;
 pub mod auth;
 pub mod backup;
+pub mod backup_format;
+pub mod backup_remote;
+pub mod etag;
+pub mod groups;
+pub mod invitations;
+pub mod item_history;
+pub mod oidc;
+pub mod roles;
+pub mod share_links;
 pub mod totp;
+pub mod trash;
+pub mod webauthn;
 
 use crate::db::DatabaseService;
 use crate::models::{
-    ApiResponse, CreateInventoryRequest, CreateItemRequest, CreateOrganizerOptionRequest,
-    CreateOrganizerTypeRequest, ErrorResponse, ImageUploadResponse, InventoryReportData,
-    InventoryReportRequest, Item, ItemExportRow, SetItemOrganizerValuesRequest,
-    UpdateInventoryRequest, UpdateItemRequest, UpdateOrganizerOptionRequest,
-    UpdateOrganizerTypeRequest,
+    ApiResponse, AuditAction, Capability, CreateInventoryRequest, CreateItemRequest,
+    CreateOrganizerOptionRequest, CreateOrganizerTypeRequest, EffectivePermissions, ErrorResponse,
+    ImageUploadResponse, InventoryReportData, InventoryReportRequest, Item, ItemExportRow,
+    ItemFieldChange, ItemHistoryAction, ItemViewer, ItemVisibility, NewAuditEvent, OrganizerType,
+    SetItemOrganizerValuesRequest, UpdateInventoryRequest, UpdateItemRequest,
+    UpdateItemVisibilityRequest, UpdateOrganizerOptionRequest, UpdateOrganizerTypeRequest,
 };
 use actix_multipart::form::{tempfile::TempFile, MultipartForm};
 use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Result, Scope};
@@ -26,6 +39,184 @@ fn validate_image_url(image_url: Option<&str>) -> std::result::Result<(), String
     Ok(())
 }
 
+/// Check the request may act on an inventory as decided by `allowed`.
+/// Admins always pass; API tokens must also be allowed to touch the inventory.
+async fn require_permission(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    inventory_id: i32,
+    allowed: impl Fn(&EffectivePermissions) -> bool,
+    denied_message: String,
+) -> std::result::Result<(), HttpResponse> {
+    if !auth.allows_inventory(inventory_id) {
+        return Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Insufficient permissions".to_string(),
+            message: Some("This API token is not allowed to access this inventory".to_string()),
+        }));
+    }
+    if auth.is_admin {
+        return Ok(());
+    }
+
+    match db_service
+        .get_effective_permissions(auth.user_id, inventory_id)
+        .await
+    {
+        Ok(perms) if allowed(&perms) => Ok(()),
+        Ok(_) => Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Insufficient permissions".to_string(),
+            message: Some(denied_message),
+        })),
+        Err(e) => {
+            error!("Error checking permission: {}", e);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Check the user holds `capability` on an inventory, through ownership, All Access or
+/// the role of a share. Every per-inventory permission check goes through here.
+pub(crate) async fn require_capability(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    inventory_id: i32,
+    capability: Capability,
+) -> std::result::Result<(), HttpResponse> {
+    require_permission(
+        db_service,
+        auth,
+        inventory_id,
+        |perms| perms.has(capability),
+        format!("Your access to this inventory does not include '{capability}'"),
+    )
+    .await
+}
+
+/// Load an item and check the user holds `capability` on its inventory, at a level
+/// that can see the item
+async fn require_item_capability(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    item_id: i32,
+    capability: Capability,
+) -> std::result::Result<Item, HttpResponse> {
+    let item = match db_service.get_item_by_id(item_id).await {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Item with id {item_id} not found"),
+                message: Some("Item not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving item: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve item".to_string()),
+            }));
+        },
+    };
+    require_capability(db_service, auth, item.inventory_id, capability).await?;
+    let visibility = item.visibility;
+    require_permission(
+        db_service,
+        auth,
+        item.inventory_id,
+        |perms| match visibility {
+            ItemVisibility::Everyone => perms.can_view,
+            ItemVisibility::EditItems => perms.can_edit_items,
+            ItemVisibility::EditInventory => perms.can_edit_inventory,
+            ItemVisibility::Owner => perms.can_delete_inventory,
+        },
+        "This item is private".to_string(),
+    )
+    .await?;
+    Ok(item)
+}
+
+/// Check export access to the inventory a report asks for. Across all inventories
+/// a report only covers those the user may export, which can't be narrowed to an
+/// API token's inventories, so restricted tokens have to name one.
+async fn require_report_access(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    inventory_id: Option<i32>,
+) -> std::result::Result<(), HttpResponse> {
+    match inventory_id {
+        Some(inventory_id) => {
+            require_capability(db_service, auth, inventory_id, Capability::Export).await
+        },
+        None if auth
+            .api_token
+            .as_ref()
+            .is_some_and(|token| token.inventory_ids.is_some()) =>
+        {
+            Err(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Insufficient permissions".to_string(),
+                message: Some(
+                    "This API token is restricted to specific inventories; pass inventory_id"
+                        .to_string(),
+                ),
+            }))
+        },
+        None => Ok(()),
+    }
+}
+
+/// Check the user may move an item between inventories: it leaves one and joins the other
+async fn require_move_access(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    from_inventory_id: i32,
+    to_inventory_id: i32,
+) -> std::result::Result<(), HttpResponse> {
+    require_capability(db_service, auth, from_inventory_id, Capability::RemoveItems).await?;
+    require_capability(db_service, auth, to_inventory_id, Capability::AddItems).await
+}
+
+/// Load an organizer type and check the user may manage organizers of its inventory
+async fn require_organizer_access(
+    db_service: &DatabaseService,
+    auth: &crate::auth::AuthContext,
+    organizer_id: i32,
+) -> std::result::Result<OrganizerType, HttpResponse> {
+    let organizer = match db_service.get_organizer_type_by_id(organizer_id).await {
+        Ok(Some(organizer)) => organizer,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Organizer type with id {organizer_id} not found"),
+                message: Some("Organizer type not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving organizer type: {}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve organizer type".to_string()),
+            }));
+        },
+    };
+    require_capability(
+        db_service,
+        auth,
+        organizer.inventory_id,
+        Capability::ManageOrganizers,
+    )
+    .await?;
+    Ok(organizer)
+}
+
 #[get("/")]
 pub async fn index() -> impl Responder {
     // Serve the static HTML file instead of embedded HTML
@@ -55,7 +246,9 @@ pub async fn get_inventories(pool: web::Data<Pool>, req: HttpRequest) -> Result<
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
     match db_service.get_accessible_inventories(auth.user_id).await {
-        Ok(inventories) => {
+        Ok(mut inventories) => {
+            // API tokens may be limited to a subset of the user's inventories
+            inventories.retain(|inv| inv.id.is_some_and(|id| auth.allows_inventory(id)));
             info!(
                 "Successfully retrieved {} inventories for user {}",
                 inventories.len(),
@@ -145,12 +338,14 @@ pub async fn get_inventory(pool: web::Data<Pool>, path: web::Path<i32>) -> Resul
     match db_service.get_inventory_by_id(inventory_id).await {
         Ok(Some(inventory)) => {
             info!("Successfully retrieved inventory with id: {}", inventory_id);
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(inventory),
-                message: Some("Inventory retrieved successfully".to_string()),
-                error: None,
-            }))
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), inventory.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(inventory),
+                    message: Some("Inventory retrieved successfully".to_string()),
+                    error: None,
+                }),
+            )
         },
         Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
             success: false,
@@ -171,12 +366,22 @@ pub async fn get_inventory(pool: web::Data<Pool>, path: web::Path<i32>) -> Resul
 #[get("/inventories/{id}/items")]
 pub async fn get_inventory_items(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
+    // Items can be hidden from some of the people the inventory is shared with
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.get_items_by_inventory(inventory_id).await {
+    match db_service
+        .get_items_by_inventory(inventory_id, ItemViewer::User(auth.user_id))
+        .await
+    {
         Ok(items) => {
             info!(
                 "Successfully retrieved {} items for inventory {}",
@@ -207,9 +412,15 @@ pub async fn get_inventory_items(
 #[put("/inventories/{id}")]
 pub async fn update_inventory(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<UpdateInventoryRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     // Validate input before processing
     if let Err(validation_errors) = req.validate() {
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
@@ -231,24 +442,62 @@ pub async fn update_inventory(
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    let inventory = match db_service.get_inventory_by_id(inventory_id).await {
+        Ok(Some(inventory)) => inventory,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Inventory with id {inventory_id} not found"),
+                message: Some("Inventory not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving inventory: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to update inventory".to_string()),
+            }));
+        },
+    };
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::EditInventory).await
+    {
+        return Ok(response);
+    }
+    let expected = match etag::check_if_match(&http_req, &inventory, inventory.updated_at) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
     match db_service
-        .update_inventory(inventory_id, req.into_inner())
+        .update_inventory(inventory_id, req.into_inner(), expected)
         .await
     {
         Ok(Some(inventory)) => {
             info!("Successfully updated inventory with id: {}", inventory_id);
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(inventory),
-                message: Some("Inventory updated successfully".to_string()),
-                error: None,
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), inventory.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(inventory),
+                    message: Some("Inventory updated successfully".to_string()),
+                    error: None,
+                }),
+            )
+        },
+        Ok(None) => {
+            // Changed by someone else since the If-Match check
+            if expected.is_some() {
+                if let Ok(Some(current)) = db_service.get_inventory_by_id(inventory_id).await {
+                    return Ok(etag::precondition_failed(&current, current.updated_at));
+                }
+            }
+            Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Inventory with id {inventory_id} not found"),
+                message: Some("Inventory not found".to_string()),
             }))
         },
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: format!("Inventory with id {inventory_id} not found"),
-            message: Some("Inventory not found".to_string()),
-        })),
         Err(e) => {
             error!("Error updating inventory: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -263,18 +512,58 @@ pub async fn update_inventory(
 #[delete("/inventories/{id}")]
 pub async fn delete_inventory(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.delete_inventory(inventory_id).await {
+    // Deleting is not a capability a role can give: only the owner and full All Access
+    match db_service.get_inventory_by_id(inventory_id).await {
+        Ok(Some(_)) => {},
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Inventory with id {inventory_id} not found"),
+                message: Some("Inventory not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving inventory: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to delete inventory".to_string()),
+            }));
+        },
+    }
+    if let Err(response) = require_permission(
+        &db_service,
+        &auth,
+        inventory_id,
+        |perms| perms.can_delete_inventory,
+        "Only the inventory owner or users with full All Access can delete it".to_string(),
+    )
+    .await
+    {
+        return Ok(response);
+    }
+
+    match db_service
+        .delete_inventory(inventory_id, auth.user_id)
+        .await
+    {
         Ok(true) => {
             info!("Successfully deleted inventory with id: {}", inventory_id);
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(()),
-                message: Some("Inventory deleted successfully".to_string()),
+                message: Some("Inventory moved to the trash".to_string()),
                 error: None,
             }))
         },
@@ -295,12 +584,24 @@ pub async fn delete_inventory(
 }
 
 // Items API endpoints
+
+/// Every item the user can see, across all inventories they can access
 #[get("/items")]
-pub async fn get_items(pool: web::Data<Pool>) -> Result<impl Responder> {
+pub async fn get_items(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.get_all_items().await {
-        Ok(items) => {
+    match db_service
+        .get_all_items(ItemViewer::User(auth.user_id))
+        .await
+    {
+        Ok(mut items) => {
+            // API tokens may be limited to a subset of the user's inventories
+            items.retain(|item| auth.allows_inventory(item.inventory_id));
             info!("Successfully retrieved {} items from database", items.len());
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
@@ -321,41 +622,46 @@ pub async fn get_items(pool: web::Data<Pool>) -> Result<impl Responder> {
 }
 
 #[get("/items/{id}")]
-pub async fn get_item(pool: web::Data<Pool>, path: web::Path<i32>) -> Result<impl Responder> {
+pub async fn get_item(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let item_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.get_item_by_id(item_id).await {
-        Ok(Some(item)) => {
+    match require_item_capability(&db_service, &auth, item_id, Capability::View).await {
+        Ok(item) => {
             info!("Successfully retrieved item with id: {}", item_id);
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(item),
-                message: Some("Item retrieved successfully".to_string()),
-                error: None,
-            }))
-        },
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: format!("Item with id {item_id} not found"),
-            message: Some("Item not found".to_string()),
-        })),
-        Err(e) => {
-            error!("Error retrieving item: {}", e);
-            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                success: false,
-                error: "An internal error occurred".to_string(),
-                message: Some("Failed to retrieve item".to_string()),
-            }))
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), item.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(item),
+                    message: Some("Item retrieved successfully".to_string()),
+                    error: None,
+                }),
+            )
         },
+        Err(response) => Ok(response),
     }
 }
 
 #[post("/items")]
 pub async fn create_item(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     req: web::Json<CreateItemRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     // Validate input before processing
     if let Err(validation_errors) = req.validate() {
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
@@ -367,9 +673,28 @@ pub async fn create_item(
 
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    // Items without an inventory go to inventory 1, see `DatabaseService::create_item`
+    let inventory_id = req.inventory_id.unwrap_or(1);
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::AddItems).await
+    {
+        return Ok(response);
+    }
+
     match db_service.create_item(req.into_inner()).await {
         Ok(item) => {
             info!("Successfully created item: {}", item.name);
+            if let Some(item_id) = item.id {
+                item_history::record(
+                    &db_service,
+                    &auth,
+                    item_id,
+                    ItemHistoryAction::Created,
+                    None,
+                    &ItemFieldChange::for_new_item(&item),
+                )
+                .await;
+            }
             Ok(HttpResponse::Created().json(ApiResponse {
                 success: true,
                 data: Some(item),
@@ -391,9 +716,15 @@ pub async fn create_item(
 #[put("/items/{id}")]
 pub async fn update_item(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<UpdateItemRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     // Validate input before processing
     if let Err(validation_errors) = req.validate() {
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
@@ -406,21 +737,62 @@ pub async fn update_item(
     let item_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.update_item(item_id, req.into_inner()).await {
-        Ok(Some(item)) => {
+    let item =
+        match require_item_capability(&db_service, &auth, item_id, Capability::EditItems).await {
+            Ok(item) => item,
+            Err(response) => return Ok(response),
+        };
+    if let Some(target_id) = req.inventory_id.filter(|id| *id != item.inventory_id) {
+        if let Err(response) =
+            require_move_access(&db_service, &auth, item.inventory_id, target_id).await
+        {
+            return Ok(response);
+        }
+    }
+
+    let expected = match etag::check_if_match(&http_req, &item, item.updated_at) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service
+        .update_item(item_id, req.into_inner(), expected)
+        .await
+    {
+        Ok(Some(updated)) => {
             info!("Successfully updated item with id: {}", item_id);
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(item),
-                message: Some("Item updated successfully".to_string()),
-                error: None,
+            item_history::record(
+                &db_service,
+                &auth,
+                item_id,
+                ItemHistoryAction::Updated,
+                None,
+                &ItemFieldChange::between_items(&item, &updated),
+            )
+            .await;
+            let item = updated;
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), item.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(item),
+                    message: Some("Item updated successfully".to_string()),
+                    error: None,
+                }),
+            )
+        },
+        Ok(None) => {
+            // Changed by someone else since the If-Match check
+            if expected.is_some() {
+                if let Ok(Some(current)) = db_service.get_item_by_id(item_id).await {
+                    return Ok(etag::precondition_failed(&current, current.updated_at));
+                }
+            }
+            Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Item with id {item_id} not found"),
+                message: Some("Item not found".to_string()),
             }))
         },
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: format!("Item with id {item_id} not found"),
-            message: Some("Item not found".to_string()),
-        })),
         Err(e) => {
             error!("Error updating item: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -433,17 +805,32 @@ pub async fn update_item(
 }
 
 #[delete("/items/{id}")]
-pub async fn delete_item(pool: web::Data<Pool>, path: web::Path<i32>) -> Result<impl Responder> {
+pub async fn delete_item(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let item_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.delete_item(item_id).await {
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::RemoveItems).await
+    {
+        return Ok(response);
+    }
+
+    match db_service.delete_item(item_id, auth.user_id).await {
         Ok(true) => {
             info!("Successfully deleted item with id: {}", item_id);
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(()),
-                message: Some("Item deleted successfully".to_string()),
+                message: Some("Item moved to the trash".to_string()),
                 error: None,
             }))
         },
@@ -466,13 +853,24 @@ pub async fn delete_item(pool: web::Data<Pool>, path: web::Path<i32>) -> Result<
 #[get("/items/search/{query}")]
 pub async fn search_items(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<String>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let query = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.search_items(&query).await {
-        Ok(items) => {
+    match db_service
+        .search_items(&query, ItemViewer::User(auth.user_id))
+        .await
+    {
+        Ok(mut items) => {
+            // API tokens may be limited to a subset of the user's inventories
+            items.retain(|item| auth.allows_inventory(item.inventory_id));
             info!(
                 "Successfully searched items with query '{}', found {} results",
                 query,
@@ -499,6 +897,66 @@ pub async fn search_items(
     }
 }
 
+/// Hide an item from people the inventory is shared with, or show it again
+#[put("/items/{id}/visibility")]
+pub async fn update_item_visibility(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<UpdateItemVisibilityRequest>,
+) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let item_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    // Visibility is a sharing decision, so it needs the same rights as managing shares
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+
+    match db_service
+        .update_item_visibility(item_id, body.visibility)
+        .await
+    {
+        Ok(Some(item)) => {
+            audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ItemVisibilityChanged)
+                    .actor(auth.user_id, &auth.username)
+                    .target("item", &item_id)
+                    .details(format!("'{}' visible to {}", item.name, body.visibility)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(item),
+                message: Some("Item visibility updated".to_string()),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: format!("Item with id {item_id} not found"),
+            message: Some("Item not found".to_string()),
+        })),
+        Err(e) => {
+            error!("Error updating item visibility: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to update item visibility".to_string()),
+            }))
+        },
+    }
+}
+
 // ==================== Organizer Type Endpoints ====================
 
 #[get("/inventories/{id}/organizers")]
@@ -543,12 +1001,29 @@ pub async fn get_inventory_organizers(
 #[post("/inventories/{id}/organizers")]
 pub async fn create_organizer_type(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<CreateOrganizerTypeRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let inventory_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) = require_capability(
+        &db_service,
+        &auth,
+        inventory_id,
+        Capability::ManageOrganizers,
+    )
+    .await
+    {
+        return Ok(response);
+    }
+
     match db_service
         .create_organizer_type(inventory_id, req.into_inner())
         .await
@@ -587,12 +1062,14 @@ pub async fn get_organizer_type(
                 "Successfully retrieved organizer type with id: {}",
                 organizer_id
             );
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(organizer),
-                message: Some("Organizer type retrieved successfully".to_string()),
-                error: None,
-            }))
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), organizer.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(organizer),
+                    message: Some("Organizer type retrieved successfully".to_string()),
+                    error: None,
+                }),
+            )
         },
         Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
             success: false,
@@ -613,14 +1090,29 @@ pub async fn get_organizer_type(
 #[put("/organizers/{id}")]
 pub async fn update_organizer_type(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<UpdateOrganizerTypeRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let organizer_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    let organizer = match require_organizer_access(&db_service, &auth, organizer_id).await {
+        Ok(organizer) => organizer,
+        Err(response) => return Ok(response),
+    };
+    let expected = match etag::check_if_match(&http_req, &organizer, organizer.updated_at) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
     match db_service
-        .update_organizer_type(organizer_id, req.into_inner())
+        .update_organizer_type(organizer_id, req.into_inner(), expected)
         .await
     {
         Ok(Some(organizer)) => {
@@ -628,18 +1120,28 @@ pub async fn update_organizer_type(
                 "Successfully updated organizer type with id: {}",
                 organizer_id
             );
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(organizer),
-                message: Some("Organizer type updated successfully".to_string()),
-                error: None,
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), organizer.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(organizer),
+                    message: Some("Organizer type updated successfully".to_string()),
+                    error: None,
+                }),
+            )
+        },
+        Ok(None) => {
+            // Changed by someone else since the If-Match check
+            if expected.is_some() {
+                if let Ok(Some(current)) = db_service.get_organizer_type_by_id(organizer_id).await {
+                    return Ok(etag::precondition_failed(&current, current.updated_at));
+                }
+            }
+            Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Organizer type with id {organizer_id} not found"),
+                message: Some("Organizer type not found".to_string()),
             }))
         },
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: format!("Organizer type with id {organizer_id} not found"),
-            message: Some("Organizer type not found".to_string()),
-        })),
         Err(e) => {
             error!("Error updating organizer type: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -654,12 +1156,25 @@ pub async fn update_organizer_type(
 #[delete("/organizers/{id}")]
 pub async fn delete_organizer_type(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let organizer_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
-    match db_service.delete_organizer_type(organizer_id).await {
+    if let Err(response) = require_organizer_access(&db_service, &auth, organizer_id).await {
+        return Ok(response);
+    }
+
+    match db_service
+        .delete_organizer_type(organizer_id, auth.user_id)
+        .await
+    {
         Ok(true) => {
             info!(
                 "Successfully deleted organizer type with id: {}",
@@ -668,7 +1183,7 @@ pub async fn delete_organizer_type(
             Ok(HttpResponse::Ok().json(ApiResponse {
                 success: true,
                 data: Some(()),
-                message: Some("Organizer type deleted successfully".to_string()),
+                message: Some("Organizer type moved to the trash".to_string()),
                 error: None,
             }))
         },
@@ -726,12 +1241,22 @@ pub async fn get_organizer_options(
 #[post("/organizers/{id}/options")]
 pub async fn create_organizer_option(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<CreateOrganizerOptionRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let organizer_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) = require_organizer_access(&db_service, &auth, organizer_id).await {
+        return Ok(response);
+    }
+
     match db_service
         .create_organizer_option(organizer_id, req.into_inner())
         .await
@@ -759,14 +1284,48 @@ pub async fn create_organizer_option(
 #[put("/organizer-options/{id}")]
 pub async fn update_organizer_option(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<UpdateOrganizerOptionRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let option_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    let option = match db_service.get_organizer_option_by_id(option_id).await {
+        Ok(Some(option)) => option,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Organizer option with id {option_id} not found"),
+                message: Some("Organizer option not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving organizer option: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve organizer option".to_string()),
+            }));
+        },
+    };
+    if let Err(response) =
+        require_organizer_access(&db_service, &auth, option.organizer_type_id).await
+    {
+        return Ok(response);
+    }
+    let expected = match etag::check_if_match(&http_req, &option, option.updated_at) {
+        Ok(expected) => expected,
+        Err(response) => return Ok(response),
+    };
+
     match db_service
-        .update_organizer_option(option_id, req.into_inner())
+        .update_organizer_option(option_id, req.into_inner(), expected)
         .await
     {
         Ok(Some(option)) => {
@@ -774,18 +1333,28 @@ pub async fn update_organizer_option(
                 "Successfully updated organizer option with id: {}",
                 option_id
             );
-            Ok(HttpResponse::Ok().json(ApiResponse {
-                success: true,
-                data: Some(option),
-                message: Some("Organizer option updated successfully".to_string()),
-                error: None,
+            Ok(
+                etag::with_etag(HttpResponse::Ok(), option.updated_at).json(ApiResponse {
+                    success: true,
+                    data: Some(option),
+                    message: Some("Organizer option updated successfully".to_string()),
+                    error: None,
+                }),
+            )
+        },
+        Ok(None) => {
+            // Changed by someone else since the If-Match check
+            if expected.is_some() {
+                if let Ok(Some(current)) = db_service.get_organizer_option_by_id(option_id).await {
+                    return Ok(etag::precondition_failed(&current, current.updated_at));
+                }
+            }
+            Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Organizer option with id {option_id} not found"),
+                message: Some("Organizer option not found".to_string()),
             }))
         },
-        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
-            success: false,
-            error: format!("Organizer option with id {option_id} not found"),
-            message: Some("Organizer option not found".to_string()),
-        })),
         Err(e) => {
             error!("Error updating organizer option: {}", e);
             Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -800,11 +1369,39 @@ pub async fn update_organizer_option(
 #[delete("/organizer-options/{id}")]
 pub async fn delete_organizer_option(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let option_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    let organizer_id = match db_service.get_organizer_option_by_id(option_id).await {
+        Ok(Some(option)) => option.organizer_type_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: format!("Organizer option with id {option_id} not found"),
+                message: Some("Organizer option not found".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error retrieving organizer option: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve organizer option".to_string()),
+            }));
+        },
+    };
+    if let Err(response) = require_organizer_access(&db_service, &auth, organizer_id).await {
+        return Ok(response);
+    }
+
     match db_service.delete_organizer_option(option_id).await {
         Ok(true) => {
             info!(
@@ -839,11 +1436,23 @@ pub async fn delete_organizer_option(
 #[get("/items/{id}/organizer-values")]
 pub async fn get_item_organizer_values(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let item_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::View).await
+    {
+        return Ok(response);
+    }
+
     match db_service.get_item_organizer_values(item_id).await {
         Ok(values) => {
             info!(
@@ -875,17 +1484,40 @@ pub async fn get_item_organizer_values(
 #[put("/items/{id}/organizer-values")]
 pub async fn set_item_organizer_values(
     pool: web::Data<Pool>,
+    http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<SetItemOrganizerValuesRequest>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&http_req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let item_id = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::EditItems).await
+    {
+        return Ok(response);
+    }
+    let before = item_history::organizer_values_or_empty(&db_service, item_id).await;
+
     match db_service
         .set_item_organizer_values(item_id, req.into_inner().values)
         .await
     {
         Ok(values) => {
+            let after = item_history::organizer_values_or_empty(&db_service, item_id).await;
+            item_history::record(
+                &db_service,
+                &auth,
+                item_id,
+                ItemHistoryAction::Updated,
+                None,
+                &ItemFieldChange::between_organizer_values(&before, &after),
+            )
+            .await;
             info!(
                 "Successfully set {} organizer values for item {}",
                 values.len(),
@@ -912,16 +1544,39 @@ pub async fn set_item_organizer_values(
 #[delete("/items/{item_id}/organizer-values/{organizer_type_id}")]
 pub async fn delete_item_organizer_value(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     path: web::Path<(i32, i32)>,
 ) -> Result<impl Responder> {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
     let (item_id, organizer_type_id) = path.into_inner();
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
+    if let Err(response) =
+        require_item_capability(&db_service, &auth, item_id, Capability::EditItems).await
+    {
+        return Ok(response);
+    }
+    let before = item_history::organizer_values_or_empty(&db_service, item_id).await;
+
     match db_service
         .delete_item_organizer_value(item_id, organizer_type_id)
         .await
     {
         Ok(true) => {
+            let after = item_history::organizer_values_or_empty(&db_service, item_id).await;
+            item_history::record(
+                &db_service,
+                &auth,
+                item_id,
+                ItemHistoryAction::Updated,
+                None,
+                &ItemFieldChange::between_organizer_values(&before, &after),
+            )
+            .await;
             info!(
                 "Successfully deleted organizer value for item {} type {}",
                 item_id, organizer_type_id
@@ -1175,7 +1830,7 @@ pub async fn get_inventory_item_images(
     path: web::Path<i32>,
 ) -> Result<impl Responder> {
     // Auth check — consistent with other inventory endpoints
-    let _auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
+    let auth = match auth::get_auth_context_from_request(&req, pool.get_ref()).await {
         Ok(a) => a,
         Err(e) => return Ok(e),
     };
@@ -1184,7 +1839,7 @@ pub async fn get_inventory_item_images(
     let db_service = DatabaseService::new(pool.get_ref().clone());
 
     match db_service
-        .get_item_image_urls_by_inventory(inventory_id)
+        .get_item_image_urls_by_inventory(inventory_id, ItemViewer::User(auth.user_id))
         .await
     {
         Ok(image_map) => {
@@ -1226,11 +1881,11 @@ pub async fn get_inventory_item_images(
 /// * `inventories` - Map of inventory IDs to names for lookup
 ///
 /// # Returns
-/// * `Ok(Vec<u8>)` - UTF-8 encoded CSV data ready for HTTP response
+/// * `Ok(Vec<u8>)` - UTF-8 encoded CSV data ready for an HTTP response or file
 /// * `Err` - CSV serialization or I/O errors
-fn format_items_as_csv(
+pub fn format_items_as_csv<S: std::hash::BuildHasher>(
     items: Vec<Item>,
-    inventories: &std::collections::HashMap<i32, String>,
+    inventories: &std::collections::HashMap<i32, String, S>,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let mut writer = csv::Writer::from_writer(vec![]);
 
@@ -1362,29 +2017,8 @@ pub async fn get_inventory_report(
         }
     }
 
-    // Check inventory access if specific inventory requested
-    if let Some(inv_id) = request.inventory_id {
-        match db_service
-            .check_inventory_access(auth.user_id, inv_id)
-            .await
-        {
-            Ok(false) => {
-                return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-                    success: false,
-                    error: "Access denied to this inventory".to_string(),
-                    message: None,
-                }))
-            },
-            Err(e) => {
-                error!("Error checking inventory access: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                    success: false,
-                    error: "An internal error occurred".to_string(),
-                    message: None,
-                }));
-            },
-            _ => {},
-        }
+    if let Err(response) = require_report_access(&db_service, &auth, request.inventory_id).await {
+        return Ok(response);
     }
 
     // Fetch report data
@@ -1406,20 +2040,21 @@ pub async fn get_inventory_report(
     // Handle format selection - CSV vs JSON export
     if format == "csv" {
         // Fetch inventory names for CSV export
-        let inventory_names = match db_service.get_accessible_inventories(auth.user_id).await {
-            Ok(inventories) => inventories
-                .into_iter()
-                .filter_map(|inv| inv.id.map(|id| (id, inv.name)))
-                .collect(),
-            Err(e) => {
-                error!("Error fetching inventories for CSV: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                    success: false,
-                    error: "Failed to fetch inventory names".to_string(),
-                    message: Some(e.to_string()),
-                }));
-            },
-        };
+        let inventory_names: std::collections::HashMap<i32, String> =
+            match db_service.get_accessible_inventories(auth.user_id).await {
+                Ok(inventories) => inventories
+                    .into_iter()
+                    .filter_map(|inv| inv.id.map(|id| (id, inv.name)))
+                    .collect(),
+                Err(e) => {
+                    error!("Error fetching inventories for CSV: {}", e);
+                    return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                        success: false,
+                        error: "Failed to fetch inventory names".to_string(),
+                        message: Some(e.to_string()),
+                    }));
+                },
+            };
 
         match format_items_as_csv(items, &inventory_names) {
             Ok(csv_data) => {
@@ -1521,29 +2156,8 @@ pub async fn get_inventory_statistics_endpoint(
     let db_service = DatabaseService::new(pool.get_ref().clone());
     let request = query.into_inner();
 
-    // Check inventory access if specific inventory requested
-    if let Some(inv_id) = request.inventory_id {
-        match db_service
-            .check_inventory_access(auth.user_id, inv_id)
-            .await
-        {
-            Ok(false) => {
-                return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-                    success: false,
-                    error: "Access denied to this inventory".to_string(),
-                    message: None,
-                }))
-            },
-            Err(e) => {
-                error!("Error checking inventory access: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                    success: false,
-                    error: "An internal error occurred".to_string(),
-                    message: None,
-                }));
-            },
-            _ => {},
-        }
+    if let Err(response) = require_report_access(&db_service, &auth, request.inventory_id).await {
+        return Ok(response);
     }
 
     match db_service
@@ -1584,29 +2198,8 @@ pub async fn get_category_breakdown_endpoint(
     let db_service = DatabaseService::new(pool.get_ref().clone());
     let request = query.into_inner();
 
-    // Check inventory access if specific inventory requested
-    if let Some(inv_id) = request.inventory_id {
-        match db_service
-            .check_inventory_access(auth.user_id, inv_id)
-            .await
-        {
-            Ok(false) => {
-                return Ok(HttpResponse::Forbidden().json(ErrorResponse {
-                    success: false,
-                    error: "Access denied to this inventory".to_string(),
-                    message: None,
-                }))
-            },
-            Err(e) => {
-                error!("Error checking inventory access: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
-                    success: false,
-                    error: "An internal error occurred".to_string(),
-                    message: None,
-                }));
-            },
-            _ => {},
-        }
+    if let Err(response) = require_report_access(&db_service, &auth, request.inventory_id).await {
+        return Ok(response);
     }
 
     match db_service
@@ -1657,9 +2250,20 @@ pub fn api_scope() -> Scope {
         .service(auth::initial_setup)
         .service(auth::login)
         .service(auth::register)
+        .service(auth::refresh_session)
+        .service(auth::proxy_session)
         .service(auth::get_current_user)
         .service(auth::update_current_user)
         .service(auth::change_password)
+        // Session endpoints
+        .service(auth::logout)
+        .service(auth::get_my_sessions)
+        .service(auth::revoke_all_my_sessions)
+        .service(auth::revoke_my_session)
+        // Personal API token endpoints
+        .service(auth::get_my_api_tokens)
+        .service(auth::create_api_token)
+        .service(auth::delete_api_token)
         // Recovery codes endpoints
         .service(auth::generate_recovery_codes)
         .service(auth::get_recovery_codes_status)
@@ -1672,17 +2276,65 @@ pub fn api_scope() -> Scope {
         .service(auth::create_inventory_share)
         .service(auth::update_inventory_share)
         .service(auth::delete_inventory_share)
+        .service(auth::accept_inventory_share)
+        .service(auth::decline_inventory_share)
         .service(auth::transfer_inventory_ownership)
         .service(auth::get_inventory_permissions)
         .service(auth::get_my_access_grants)
         .service(auth::get_received_access_grants)
         .service(auth::create_access_grant)
+        .service(auth::update_access_grant)
         .service(auth::delete_access_grant)
+        .service(auth::accept_access_grant)
+        .service(auth::decline_access_grant)
         .service(auth::admin_get_users)
         .service(auth::admin_get_user)
         .service(auth::admin_create_user)
         .service(auth::admin_update_user)
         .service(auth::admin_delete_user)
+        .service(auth::admin_unlock_user)
+        .service(auth::admin_get_identity_links)
+        .service(auth::admin_link_identity)
+        .service(auth::admin_unlink_identity)
+        .service(auth::admin_create_password_reset)
+        .service(auth::redeem_password_reset)
+        // Household group routes
+        .service(groups::get_groups)
+        .service(groups::create_group)
+        .service(groups::get_group)
+        .service(groups::update_group)
+        .service(groups::delete_group)
+        .service(groups::add_group_member)
+        .service(groups::update_group_member)
+        .service(groups::remove_group_member)
+        .service(groups::get_inventory_group_shares)
+        .service(groups::create_inventory_group_share)
+        .service(groups::update_inventory_group_share)
+        .service(groups::delete_inventory_group_share)
+        .service(roles::get_roles)
+        .service(roles::create_role)
+        .service(roles::update_role)
+        .service(roles::delete_role)
+        .service(roles::get_inventory_roles)
+        // Public share link routes
+        .service(share_links::get_share_links)
+        .service(share_links::create_share_link)
+        .service(share_links::delete_share_link)
+        .service(share_links::view_share_link)
+        // Invitation routes
+        .service(invitations::create_invitation)
+        .service(invitations::get_invitations)
+        .service(invitations::revoke_invitation)
+        .service(invitations::preview_invitation)
+        .service(invitations::accept_invitation)
+        // Audit log routes
+        .service(audit::admin_export_audit_events)
+        .service(audit::admin_get_audit_events)
+        // OIDC single sign-on routes
+        .service(oidc::oidc_status)
+        .service(oidc::oidc_login)
+        .service(oidc::oidc_callback)
+        .service(oidc::oidc_exchange)
         // TOTP authenticator routes
         .service(totp::totp_setup)
         .service(totp::totp_verify_setup)
@@ -1691,6 +2343,17 @@ pub fn api_scope() -> Scope {
         .service(totp::totp_update_mode)
         .service(totp::totp_disable)
         .service(totp::totp_status)
+        // WebAuthn passkey routes
+        .service(webauthn::webauthn_status)
+        .service(webauthn::webauthn_register_start)
+        .service(webauthn::webauthn_register_finish)
+        .service(webauthn::get_webauthn_credentials)
+        .service(webauthn::rename_webauthn_credential)
+        .service(webauthn::delete_webauthn_credential)
+        .service(webauthn::webauthn_login_start)
+        .service(webauthn::webauthn_login_finish)
+        .service(webauthn::webauthn_verify_start)
+        .service(webauthn::webauthn_verify_finish)
         // Inventory routes
         .service(get_inventories)
         .service(create_inventory)
@@ -1707,9 +2370,17 @@ pub fn api_scope() -> Scope {
         .service(update_item)
         .service(delete_item)
         .service(search_items)
+        .service(update_item_visibility)
         .service(get_item_organizer_values)
         .service(set_item_organizer_values)
         .service(delete_item_organizer_value)
+        // Item history
+        .service(item_history::get_item_history)
+        .service(item_history::revert_item)
+        // Trash
+        .service(trash::get_trash)
+        .service(trash::restore_from_trash)
+        .service(trash::purge_from_trash)
         // Organizer routes
         .service(get_organizer_type)
         .service(update_organizer_type)
@@ -1729,8 +2400,13 @@ pub fn api_scope() -> Scope {
         // Backup & Restore routes
         .service(backup::create_backup)
         .service(backup::list_backups)
+        .service(backup::remote_backup_status)
         .service(backup::download_backup)
         .service(backup::upload_backup)
+        .service(backup::verify_backup)
+        .service(backup::browse_backup)
+        .service(backup::restore_backup_inventory)
+        .service(backup::restore_backup_user)
         .service(backup::restore_backup)
         .service(backup::delete_backup)
         // Catch-all for non-existent API endpoints
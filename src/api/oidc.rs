@@ -0,0 +1,271 @@
+//! OIDC single sign-on API endpoints
+//!
+//! Provides the login redirect, the provider callback, and the exchange of the
+//! resulting one-time code for a regular session. SSO users are matched to local
+//! accounts by a configurable claim and can be created on first login.
+//!
+//! The login's `state` is also kept in a short-lived cookie, so a callback is
+//! only accepted in the browser that started the login.
+
+use actix_web::cookie::{time, Cookie, SameSite};
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+use serde_json::{Map, Value};
+
+use crate::auth::oidc::{
+    claim_groups, claim_str, issue_login_code, redeem_login_code, OidcClient, OidcConfig, OidcError,
+};
+use crate::db::DatabaseService;
+use crate::models::{
+    ApiResponse, ErrorResponse, IdentitySource, OidcCallbackQuery, OidcExchangeRequest,
+    OidcStatusResponse, User,
+};
+
+use super::auth::{resolve_external_user, start_session, ExternalIdentity};
+
+/// Cookie binding a login to the browser that started it
+const STATE_COOKIE: &str = "oidc_state";
+
+/// How long the state cookie lives; matches how long the provider may take
+const STATE_COOKIE_MAX_AGE: time::Duration = time::Duration::minutes(10);
+
+/// The state cookie, scoped to the callback path; an empty value with no
+/// lifetime removes it
+fn state_cookie(config: &OidcConfig, state: &str) -> Cookie<'static> {
+    let callback_path = reqwest::Url::parse(&config.redirect_url)
+        .map_or_else(|_| "/".to_string(), |url| url.path().to_string());
+    let mut cookie = Cookie::build(STATE_COOKIE, state.to_string())
+        .path(callback_path)
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(config.redirect_url.starts_with("https://"))
+        .finish();
+    if state.is_empty() {
+        cookie.make_removal();
+    } else {
+        cookie.set_max_age(STATE_COOKIE_MAX_AGE);
+    }
+    cookie
+}
+
+/// Redirect the browser back to the login page with a query parameter
+fn redirect_to_login(param: &str, value: &str) -> HttpResponse {
+    let query = url_encode_pair(param, value);
+    HttpResponse::Found()
+        .insert_header(("Location", format!("/login?{query}")))
+        .finish()
+}
+
+fn url_encode_pair(key: &str, value: &str) -> String {
+    let mut serializer = reqwest::Url::parse("http://localhost/").expect("static URL is valid");
+    serializer.query_pairs_mut().append_pair(key, value);
+    serializer.query().unwrap_or_default().to_string()
+}
+
+/// Whether SSO is configured and how to label it
+#[get("/auth/oidc/config")]
+pub async fn oidc_status(oidc: Option<web::Data<OidcConfig>>) -> Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(OidcStatusResponse {
+            enabled: oidc.is_some(),
+            provider_name: oidc.map(|config| config.provider_name.clone()),
+        }),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Start an SSO login by redirecting to the identity provider
+#[get("/auth/oidc/login")]
+pub async fn oidc_login(oidc: Option<web::Data<OidcConfig>>) -> Result<impl Responder> {
+    let Some(config) = oidc else {
+        return Ok(redirect_to_login(
+            "oidc_error",
+            "Single sign-on is not configured",
+        ));
+    };
+
+    let url = match OidcClient::discover(&config).await {
+        Ok(client) => client.authorization_url(),
+        Err(e) => Err(e),
+    };
+    let (url, state) = match url {
+        Ok(login) => login,
+        Err(e) => {
+            error!("Failed to start SSO login: {}", e);
+            return Ok(redirect_to_login(
+                "oidc_error",
+                "The identity provider is unavailable",
+            ));
+        },
+    };
+
+    Ok(HttpResponse::Found()
+        .insert_header(("Location", url))
+        .cookie(state_cookie(&config, &state))
+        .finish())
+}
+
+/// Provider callback: verify the login and hand the frontend a one-time code
+#[get("/auth/oidc/callback")]
+pub async fn oidc_callback(
+    pool: web::Data<Pool>,
+    oidc: Option<web::Data<OidcConfig>>,
+    req: HttpRequest,
+    query: web::Query<OidcCallbackQuery>,
+) -> Result<impl Responder> {
+    let Some(config) = oidc else {
+        return Ok(redirect_to_login(
+            "oidc_error",
+            "Single sign-on is not configured",
+        ));
+    };
+
+    let cookie_state = req.cookie(STATE_COOKIE).map(|c| c.value().to_string());
+    let mut response =
+        complete_callback(&pool, &config, query.into_inner(), cookie_state.as_deref()).await;
+    // The state is single use either way
+    if let Err(e) = response.add_cookie(&state_cookie(&config, "")) {
+        warn!("Failed to clear the SSO state cookie: {}", e);
+    }
+    Ok(response)
+}
+
+async fn complete_callback(
+    pool: &Pool,
+    config: &OidcConfig,
+    query: OidcCallbackQuery,
+    cookie_state: Option<&str>,
+) -> HttpResponse {
+    if let Some(provider_error) = query.error {
+        warn!(
+            "Identity provider returned an error: {} {}",
+            provider_error,
+            query.error_description.as_deref().unwrap_or_default()
+        );
+        return redirect_to_login(
+            "oidc_error",
+            query
+                .error_description
+                .as_deref()
+                .unwrap_or(&provider_error),
+        );
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return redirect_to_login("oidc_error", "Missing authorization code");
+    };
+    // A callback without the browser's state cookie was not started here,
+    // e.g. a login link planted by someone else
+    if cookie_state != Some(state.as_str()) {
+        warn!("SSO callback state does not match the browser's login");
+        return redirect_to_login("oidc_error", &OidcError::InvalidState.to_string());
+    }
+
+    let claims = match OidcClient::discover(config).await {
+        Ok(client) => client.complete_login(&code, &state).await,
+        Err(e) => Err(e),
+    };
+    let claims = match claims {
+        Ok(claims) => claims,
+        Err(e) => {
+            warn!("SSO login failed: {}", e);
+            return redirect_to_login("oidc_error", &e.to_string());
+        },
+    };
+
+    let db_service = DatabaseService::new(pool.clone());
+    let user = match resolve_user(&db_service, config, &claims).await {
+        Ok(user) => user,
+        Err(message) => return redirect_to_login("oidc_error", &message),
+    };
+
+    info!("User {} signed in via SSO", user.username);
+    redirect_to_login("oidc_code", &issue_login_code(user.id))
+}
+
+/// Exchange the one-time code from the callback for a session
+#[post("/auth/oidc/exchange")]
+pub async fn oidc_exchange(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<OidcExchangeRequest>,
+) -> Result<impl Responder> {
+    let Some(user_id) = redeem_login_code(&body.code) else {
+        return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "Invalid or expired login code".to_string(),
+            message: Some("Please sign in again".to_string()),
+        }));
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match db_service.get_user_by_id(user_id).await {
+        Ok(Some(user)) if user.is_active => user,
+        Ok(_) => {
+            return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+                success: false,
+                error: "Account is unavailable".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Database error during SSO exchange: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    match start_session(&db_service, user, &req).await {
+        Ok(session) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(session),
+            message: Some("Login successful".to_string()),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error starting SSO session: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to complete login".to_string()),
+            }))
+        },
+    }
+}
+
+/// Find (or create) the local account for an SSO login
+async fn resolve_user(
+    db_service: &DatabaseService,
+    config: &OidcConfig,
+    claims: &Map<String, Value>,
+) -> Result<User, String> {
+    let Some(username) = claim_str(claims, &config.username_claim) else {
+        return Err(format!(
+            "The identity provider did not send the '{}' claim",
+            config.username_claim
+        ));
+    };
+    let Some(subject) = claim_str(claims, "sub") else {
+        return Err("The identity provider did not send the 'sub' claim".to_string());
+    };
+    let identity = ExternalIdentity {
+        username,
+        full_name: claim_str(claims, "name"),
+        is_admin: config
+            .admin_group
+            .as_ref()
+            .map(|group| claim_groups(claims, &config.groups_claim).contains(group)),
+        auto_provision: config.auto_provision,
+        link: Some((IdentitySource::Sso, subject)),
+        source: "SSO",
+    };
+
+    resolve_external_user(db_service, &identity)
+        .await
+        .map_err(|e| e.to_string())
+}
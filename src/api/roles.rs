@@ -0,0 +1,367 @@
+//! Custom roles
+//!
+//! Owners can define their own roles for sharing, each a set of capabilities
+//! (view, add items, manage shares, ...). The three permission levels remain
+//! available as built-in roles. A role can only be given to shares of its
+//! owner's inventories.
+
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::AuthContext;
+use crate::db::DatabaseService;
+use crate::models::{
+    ApiResponse, AuditAction, BuiltinRole, Capability, CreateInventoryRoleRequest, ErrorResponse,
+    InventoryRole, NewAuditEvent, PermissionLevel, RolesResponse, ShareRole,
+    UpdateInventoryRoleRequest,
+};
+
+use super::auth::get_auth_context_from_request;
+use super::require_capability;
+
+fn internal_error() -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        success: false,
+        error: "An internal error occurred".to_string(),
+        message: None,
+    })
+}
+
+fn role_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        success: false,
+        error: "Role not found".to_string(),
+        message: None,
+    })
+}
+
+fn duplicate_role(name: &str) -> HttpResponse {
+    HttpResponse::Conflict().json(ErrorResponse {
+        success: false,
+        error: "Role already exists".to_string(),
+        message: Some(format!("You already have a role named '{name}'")),
+    })
+}
+
+/// The built-in roles followed by `owner_id`'s custom roles
+async fn roles_for_owner(
+    db_service: &DatabaseService,
+    owner_id: Option<Uuid>,
+) -> std::result::Result<RolesResponse, HttpResponse> {
+    let builtin = [
+        PermissionLevel::View,
+        PermissionLevel::EditItems,
+        PermissionLevel::EditInventory,
+    ]
+    .into_iter()
+    .map(|level| BuiltinRole {
+        permission_level: level,
+        capabilities: level.capabilities().to_vec(),
+    })
+    .collect();
+
+    let Some(owner_id) = owner_id else {
+        return Ok(RolesResponse {
+            builtin,
+            custom: Vec::new(),
+        });
+    };
+    match db_service.get_inventory_roles(owner_id).await {
+        Ok(custom) => Ok(RolesResponse { builtin, custom }),
+        Err(e) => {
+            error!("Error getting roles: {}", e);
+            Err(internal_error())
+        },
+    }
+}
+
+/// Load a role the user may change: its owner, or an admin
+///
+/// Other users' roles answer 404 so their existence isn't revealed.
+async fn load_owned_role(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    role_id: Uuid,
+) -> std::result::Result<InventoryRole, HttpResponse> {
+    match db_service.get_inventory_role(role_id).await {
+        Ok(Some(role)) if role.owner_user_id == auth.user_id || auth.is_admin => Ok(role),
+        Ok(_) => Err(role_not_found()),
+        Err(e) => {
+            error!("Error getting role: {}", e);
+            Err(internal_error())
+        },
+    }
+}
+
+/// Work out what a share request gives: `role_id` if set, otherwise `permission_level`.
+/// A custom role has to belong to the inventory's owner.
+pub(super) async fn resolve_share_role(
+    db_service: &DatabaseService,
+    inventory_id: i32,
+    permission_level: PermissionLevel,
+    role_id: Option<Uuid>,
+) -> std::result::Result<ShareRole, HttpResponse> {
+    let Some(role_id) = role_id else {
+        return Ok(ShareRole::Builtin(permission_level));
+    };
+
+    let role = match db_service.get_inventory_role(role_id).await {
+        Ok(role) => role,
+        Err(e) => {
+            error!("Error getting role: {}", e);
+            return Err(internal_error());
+        },
+    };
+    let owner_id = match db_service.get_inventory_by_id(inventory_id).await {
+        Ok(inventory) => inventory.and_then(|inventory| inventory.user_id),
+        Err(e) => {
+            error!("Error getting inventory: {}", e);
+            return Err(internal_error());
+        },
+    };
+
+    match role {
+        Some(role) if owner_id == Some(role.owner_user_id) => Ok(ShareRole::from(&role)),
+        _ => Err(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Unknown role".to_string(),
+            message: Some("Roles can only be used on their owner's inventories".to_string()),
+        })),
+    }
+}
+
+/// List the built-in roles and the user's own custom roles
+#[get("/roles")]
+pub async fn get_roles(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match roles_for_owner(&db_service, Some(auth.user_id)).await {
+        Ok(roles) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} custom roles", roles.custom.len())),
+            data: Some(roles),
+            error: None,
+        })),
+        Err(response) => Ok(response),
+    }
+}
+
+/// List the roles that can be given to shares of an inventory (its owner's roles)
+#[get("/inventories/{id}/roles")]
+pub async fn get_inventory_roles(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let inventory_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let owner_id = match db_service.get_inventory_by_id(inventory_id).await {
+        Ok(Some(inventory)) => inventory.user_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Inventory not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting inventory: {}", e);
+            return Ok(internal_error());
+        },
+    };
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+
+    match roles_for_owner(&db_service, owner_id).await {
+        Ok(roles) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} custom roles", roles.custom.len())),
+            data: Some(roles),
+            error: None,
+        })),
+        Err(response) => Ok(response),
+    }
+}
+
+/// Create a custom role
+#[post("/roles")]
+pub async fn create_role(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    body: web::Json<CreateInventoryRoleRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let body = body.into_inner();
+    let name = body.name.clone();
+
+    match db_service.create_inventory_role(auth.user_id, body).await {
+        Ok(role) => {
+            info!("User {} created role '{}'", auth.username, role.name);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::RoleCreated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("role", &role.id)
+                    .details(format!("Created role '{}'", role.name)),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: Some(role),
+                message: Some("Role created".to_string()),
+                error: None,
+            }))
+        },
+        Err(e) => {
+            if e.to_string().contains("duplicate") || e.to_string().contains("unique") {
+                return Ok(duplicate_role(&name));
+            }
+            error!("Error creating role: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Rename a custom role or change its capabilities; shares using it follow along
+#[put("/roles/{id}")]
+pub async fn update_role(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateInventoryRoleRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let role_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let role = match load_owned_role(&db_service, &auth, role_id).await {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
+    let body = body.into_inner();
+    let name = body.name.clone().unwrap_or(role.name);
+
+    match db_service.update_inventory_role(role_id, body).await {
+        Ok(Some(role)) => {
+            let capabilities: Vec<String> =
+                role.capabilities.iter().map(ToString::to_string).collect();
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::RoleUpdated)
+                    .actor(auth.user_id, &auth.username)
+                    .target("role", &role_id)
+                    .details(format!(
+                        "Role '{}' can {}",
+                        role.name,
+                        capabilities.join(", ")
+                    )),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(role),
+                message: Some("Role updated".to_string()),
+                error: None,
+            }))
+        },
+        Ok(None) => Ok(role_not_found()),
+        Err(e) => {
+            if e.to_string().contains("duplicate") || e.to_string().contains("unique") {
+                return Ok(duplicate_role(&name));
+            }
+            error!("Error updating role: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
+
+/// Delete a custom role; shares using it fall back to the closest built-in role
+#[delete("/roles/{id}")]
+pub async fn delete_role(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let role_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let role = match load_owned_role(&db_service, &auth, role_id).await {
+        Ok(role) => role,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.delete_inventory_role(role_id).await {
+        Ok(true) => {
+            info!("User {} deleted role '{}'", auth.username, role.name);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::RoleDeleted)
+                    .actor(auth.user_id, &auth.username)
+                    .target("role", &role_id)
+                    .details(format!("Deleted role '{}'", role.name)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Role deleted".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(role_not_found()),
+        Err(e) => {
+            error!("Error deleting role: {}", e);
+            Ok(internal_error())
+        },
+    }
+}
@@ -0,0 +1,405 @@
+//! Public read-only share links
+//!
+//! Users who can manage an inventory's shares can create revocable links that show one
+//! inventory to people without an account. Links can require a password and
+//! expire, and every successful visit is counted.
+
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::{
+    generate_share_link_token, hash_password, hash_share_link_token, validate_password,
+    verify_password, SHARE_LINK_DISPLAY_LENGTH,
+};
+use crate::db::DatabaseService;
+use crate::models::{
+    ApiResponse, AuditAction, Capability, CreateShareLinkRequest, CreateShareLinkResponse,
+    ErrorResponse, ItemViewer, NewAuditEvent, SharedInventoryView, SharedItem,
+    ViewShareLinkRequest,
+};
+
+use super::auth::get_auth_context_from_request;
+use super::require_capability;
+
+/// List an inventory's share links
+#[get("/inventories/{id}/share-links")]
+pub async fn get_share_links(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let inventory_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+
+    match db_service.get_inventory_share_links(inventory_id).await {
+        Ok(links) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} share links", links.len())),
+            data: Some(links),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error getting share links: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to retrieve share links".to_string()),
+            }))
+        },
+    }
+}
+
+/// Create a public share link for an inventory
+///
+/// The token is only returned in this response; afterwards just its prefix is shown.
+#[post("/inventories/{id}/share-links")]
+pub async fn create_share_link(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<CreateShareLinkRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let inventory_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    match db_service.get_inventory_by_id(inventory_id).await {
+        Ok(Some(_)) => {},
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Inventory not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting inventory: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    }
+
+    if let Err(response) =
+        require_capability(&db_service, &auth, inventory_id, Capability::ManageShares).await
+    {
+        return Ok(response);
+    }
+
+    let password = body.password.as_deref().filter(|p| !p.is_empty());
+    let password_hash = match password {
+        Some(password) => {
+            if let Err(msg) = validate_password(password) {
+                return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                    success: false,
+                    error: msg.to_string(),
+                    message: Some("Invalid link password".to_string()),
+                }));
+            }
+            match hash_password(password.to_string()).await {
+                Ok(hash) => Some(hash),
+                Err(e) => {
+                    error!("Error hashing share link password: {}", e);
+                    return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                        success: false,
+                        error: "Failed to process password".to_string(),
+                        message: None,
+                    }));
+                },
+            }
+        },
+        None => None,
+    };
+
+    let token = generate_share_link_token();
+    let token_prefix: String = token.chars().take(SHARE_LINK_DISPLAY_LENGTH).collect();
+
+    let share_link = match db_service
+        .create_share_link(
+            inventory_id,
+            auth.user_id,
+            &hash_share_link_token(&token),
+            &token_prefix,
+            password_hash.as_deref(),
+            &body,
+        )
+        .await
+    {
+        Ok(link) => link,
+        Err(e) => {
+            error!("Error creating share link: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: Some("Failed to create share link".to_string()),
+            }));
+        },
+    };
+
+    info!(
+        "User {} created share link '{}' for inventory {}",
+        auth.username, share_link.name, inventory_id
+    );
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::ShareLinkCreated)
+            .actor(auth.user_id, &auth.username)
+            .target("share_link", &share_link.id)
+            .details(format!(
+                "Inventory {inventory_id}{}",
+                if share_link.password_protected {
+                    ", password protected"
+                } else {
+                    ""
+                }
+            )),
+    )
+    .await;
+
+    Ok(HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(CreateShareLinkResponse {
+            share_link,
+            share_path: format!("/shared?token={token}"),
+            token,
+        }),
+        message: Some("Share link created - copy it now, it will not be shown again".to_string()),
+        error: None,
+    }))
+}
+
+/// Revoke a share link
+#[delete("/share-links/{id}")]
+pub async fn delete_share_link(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let link_id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let link = match db_service.get_share_link(link_id).await {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share link not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting share link: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    // Whoever created the link can always revoke it
+    if link.created_by != auth.user_id {
+        if let Err(response) = require_capability(
+            &db_service,
+            &auth,
+            link.inventory_id,
+            Capability::ManageShares,
+        )
+        .await
+        {
+            return Ok(response);
+        }
+    }
+
+    match db_service.delete_share_link(link_id).await {
+        Ok(true) => {
+            info!("User {} revoked share link {}", auth.username, link_id);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::ShareLinkRevoked)
+                    .actor(auth.user_id, &auth.username)
+                    .target("share_link", &link_id)
+                    .details(format!("Inventory {}", link.inventory_id)),
+            )
+            .await;
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Share link revoked".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Share link not found".to_string(),
+            message: None,
+        })),
+        Err(e) => {
+            error!("Error deleting share link: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }))
+        },
+    }
+}
+
+/// Open a share link and return the shared inventory (no authentication required)
+///
+/// Password-protected links answer 403 until the right password is sent.
+#[post("/public/share-links/view")]
+pub async fn view_share_link(
+    pool: web::Data<Pool>,
+    body: web::Json<ViewShareLinkRequest>,
+) -> Result<impl Responder> {
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let (link, password_hash) = match db_service
+        .get_active_share_link_by_token_hash(&hash_share_link_token(body.token.trim()))
+        .await
+    {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share link not found".to_string(),
+                message: Some("This link has expired or been revoked".to_string()),
+            }));
+        },
+        Err(e) => {
+            error!("Error getting share link: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    if let Some(password_hash) = password_hash {
+        let Some(password) = body.password.clone().filter(|p| !p.is_empty()) else {
+            return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+                success: false,
+                error: "Password required".to_string(),
+                message: Some("This link is password protected".to_string()),
+            }));
+        };
+        match verify_password(password, password_hash).await {
+            Ok(true) => {},
+            Ok(false) => {
+                return Ok(HttpResponse::Forbidden().json(ErrorResponse {
+                    success: false,
+                    error: "Incorrect password".to_string(),
+                    message: None,
+                }));
+            },
+            Err(e) => {
+                error!("Error verifying share link password: {}", e);
+                return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                    success: false,
+                    error: "An internal error occurred".to_string(),
+                    message: None,
+                }));
+            },
+        }
+    }
+
+    let inventory = match db_service.get_inventory_by_id(link.inventory_id).await {
+        Ok(Some(inventory)) => inventory,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ErrorResponse {
+                success: false,
+                error: "Share link not found".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => {
+            error!("Error getting shared inventory: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    let (items, mut organizer_values) = match tokio::try_join!(
+        db_service.get_items_by_inventory(link.inventory_id, ItemViewer::Public),
+        db_service.get_item_organizer_values_by_inventory(link.inventory_id),
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Error getting shared inventory items: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
+                success: false,
+                error: "An internal error occurred".to_string(),
+                message: None,
+            }));
+        },
+    };
+
+    if let Err(e) = db_service.record_share_link_access(link.id).await {
+        warn!("Failed to record access to share link {}: {}", link.id, e);
+    }
+
+    let items = items
+        .into_iter()
+        .map(|item| SharedItem {
+            organizer_values: item
+                .id
+                .and_then(|id| organizer_values.remove(&id))
+                .unwrap_or_default(),
+            item,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(SharedInventoryView {
+            name: inventory.name,
+            description: inventory.description,
+            location: inventory.location,
+            image_url: inventory.image_url,
+            items,
+            expires_at: link.expires_at,
+        }),
+        message: None,
+        error: None,
+    }))
+}
@@ -9,17 +9,15 @@ use log::{error, info, warn};
 use uuid::Uuid;
 
 use crate::auth::totp::{generate_totp_setup, verify_totp_code};
-use crate::auth::{
-    extract_token, generate_token, hash_password, validate_password, verify_password, verify_token,
-};
+use crate::auth::{extract_token, hash_password, validate_password, verify_password, verify_token};
 use crate::db::DatabaseService;
 use crate::models::{
-    ApiResponse, ErrorResponse, LoginResponse, TotpDisableRequest, TotpModeRequest,
-    TotpRecoveryRequest, TotpSetupResponse, TotpStatusResponse, TotpVerifyRequest,
+    ApiResponse, AuditAction, AuditOutcome, ErrorResponse, NewAuditEvent, TotpDisableRequest,
+    TotpModeRequest, TotpRecoveryRequest, TotpSetupResponse, TotpStatusResponse, TotpVerifyRequest,
     TotpVerifySetupRequest, TotpVerifySetupResponse,
 };
 
-use super::auth::get_auth_context_from_request;
+use super::auth::{get_auth_context_from_request, start_session};
 
 /// Maximum failed TOTP attempts before rate-limiting
 const MAX_FAILED_ATTEMPTS: i32 = 5;
@@ -215,6 +213,16 @@ pub async fn totp_verify_setup(
         auth.username, mode_str
     );
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::TotpEnabled)
+            .actor(auth.user_id, &auth.username)
+            .target("user", &auth.user_id)
+            .details(format!("Mode {mode_str}")),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(TotpVerifySetupResponse {
@@ -312,6 +320,15 @@ pub async fn totp_verify(
             "TOTP login verification rate limited for user {}",
             user.username
         );
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::TotpVerify)
+                .actor(user.id, &user.username)
+                .outcome(AuditOutcome::Denied)
+                .details("Too many failed attempts"),
+        )
+        .await;
         return Ok(HttpResponse::TooManyRequests().json(ErrorResponse {
             success: false,
             error: "Too many failed attempts".to_string(),
@@ -336,6 +353,14 @@ pub async fn totp_verify(
 
     if !is_valid {
         let _ = db_service.increment_totp_failed_attempts(user_id).await;
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::TotpVerify)
+                .actor(user.id, &user.username)
+                .outcome(AuditOutcome::Failure),
+        )
+        .await;
         return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
             success: false,
             error: "Invalid TOTP code".to_string(),
@@ -347,9 +372,11 @@ pub async fn totp_verify(
     let _ = db_service.reset_totp_failed_attempts(user_id).await;
     let _ = db_service.update_totp_last_used(user_id).await;
 
-    // Generate full JWT token
-    let full_token = match generate_token(&user) {
-        Ok(t) => t,
+    info!("User {} completed TOTP verification", user.username);
+
+    // Start the full session
+    let session = match start_session(&db_service, user, &req).await {
+        Ok(session) => session,
         Err(e) => {
             error!("Error generating token: {}", e);
             return Ok(HttpResponse::InternalServerError().json(ErrorResponse {
@@ -360,14 +387,9 @@ pub async fn totp_verify(
         },
     };
 
-    info!("User {} completed TOTP verification", user.username);
-
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
-        data: Some(LoginResponse {
-            token: full_token,
-            user: user.into(),
-        }),
+        data: Some(session),
         message: Some("Login successful".to_string()),
         error: None,
     }))
@@ -379,6 +401,7 @@ pub async fn totp_verify(
 #[post("/auth/totp/recover")]
 pub async fn totp_recover(
     pool: web::Data<Pool>,
+    req: HttpRequest,
     body: web::Json<TotpRecoveryRequest>,
 ) -> Result<impl Responder> {
     let db_service = DatabaseService::new(pool.get_ref().clone());
@@ -463,6 +486,15 @@ pub async fn totp_recover(
 
     if !is_valid {
         let _ = db_service.increment_totp_failed_attempts(user.id).await;
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::TotpRecovery)
+                .claimed_username(&user.username)
+                .target("user", &user.id)
+                .outcome(AuditOutcome::Failure),
+        )
+        .await;
         return Ok(HttpResponse::BadRequest().json(ErrorResponse {
             success: false,
             error: "Invalid credentials".to_string(),
@@ -505,6 +537,16 @@ pub async fn totp_recover(
         user.username
     );
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::TotpRecovery)
+            .actor(user.id, &user.username)
+            .target("user", &user.id)
+            .details("Password reset"),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(serde_json::json!({
@@ -568,6 +610,16 @@ pub async fn totp_update_mode(
         mode_str, auth.username
     );
 
+    super::audit::record(
+        &db_service,
+        &req,
+        NewAuditEvent::new(AuditAction::TotpModeChanged)
+            .actor(auth.user_id, &auth.username)
+            .target("user", &auth.user_id)
+            .details(format!("Mode {mode_str}")),
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(serde_json::json!({ "mode": mode_str })),
@@ -616,6 +668,16 @@ pub async fn totp_disable(
         };
 
     if !password_valid {
+        super::audit::record(
+            &db_service,
+            &req,
+            NewAuditEvent::new(AuditAction::TotpDisabled)
+                .actor(auth.user_id, &auth.username)
+                .target("user", &auth.user_id)
+                .outcome(AuditOutcome::Failure)
+                .details("Password is incorrect"),
+        )
+        .await;
         return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
             success: false,
             error: "Invalid password".to_string(),
@@ -627,6 +689,14 @@ pub async fn totp_disable(
     match db_service.delete_totp_settings(auth.user_id).await {
         Ok(true) => {
             info!("TOTP disabled for user {}", auth.username);
+            super::audit::record(
+                &db_service,
+                &req,
+                NewAuditEvent::new(AuditAction::TotpDisabled)
+                    .actor(auth.user_id, &auth.username)
+                    .target("user", &auth.user_id),
+            )
+            .await;
             Ok(HttpResponse::Ok().json(ApiResponse::<()> {
                 success: true,
                 data: None,
@@ -0,0 +1,225 @@
+//! Trash bin
+//!
+//! Deleting an inventory, item or organizer type moves it to the trash, where
+//! it can be restored or purged for good. Trashing an inventory takes its items
+//! and organizer types along, and restoring it brings them back. Entries are
+//! purged automatically once they've been in the trash for
+//! `TRASH_RETENTION_DAYS` days.
+
+use std::env;
+
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info};
+
+use crate::auth::AuthContext;
+use crate::db::DatabaseService;
+use crate::models::{ApiResponse, Capability, ErrorResponse, TrashEntry, TrashKind, TrashListing};
+
+use super::auth::get_auth_context_from_request;
+use super::require_capability;
+
+/// Days trashed entries are kept when `TRASH_RETENTION_DAYS` isn't set
+const DEFAULT_RETENTION_DAYS: i32 = 30;
+
+/// Days after which trashed entries are purged, from `TRASH_RETENTION_DAYS`.
+/// `None` when it is set to 0, which keeps them until purged by hand.
+#[must_use]
+pub fn retention_days() -> Option<i32> {
+    let days = env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .filter(|days: &i32| *days >= 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    (days > 0).then_some(days)
+}
+
+fn internal_error(message: &str) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        success: false,
+        error: "An internal error occurred".to_string(),
+        message: Some(message.to_string()),
+    })
+}
+
+fn not_in_trash(kind: TrashKind, id: i32) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse {
+        success: false,
+        error: format!("No {kind} with id {id} in your trash"),
+        message: Some("Not found in the trash".to_string()),
+    })
+}
+
+/// Parse the `{kind}/{id}` of a trash entry path
+fn parse_entry_path(path: (String, i32)) -> std::result::Result<(TrashKind, i32), HttpResponse> {
+    let (kind, id) = path;
+    match kind.parse() {
+        Ok(kind) => Ok((kind, id)),
+        Err(e) => Err(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: e,
+            message: Some("Kind must be inventory, item or organizer_type".to_string()),
+        })),
+    }
+}
+
+/// Find an entry in the user's trash and check they may restore or purge it
+///
+/// Trashed inventories only show up in their owner's trash. Items and organizer
+/// types also need the capability to add (restore) or remove (purge) them in
+/// their inventory.
+async fn require_trash_entry(
+    db_service: &DatabaseService,
+    auth: &AuthContext,
+    kind: TrashKind,
+    id: i32,
+    restoring: bool,
+) -> std::result::Result<TrashEntry, HttpResponse> {
+    let entry = match db_service.get_trash(auth.user_id).await {
+        Ok(entries) => entries
+            .into_iter()
+            .find(|entry| entry.kind == kind && entry.id == id),
+        Err(e) => {
+            error!("Error retrieving trash: {}", e);
+            return Err(internal_error("Failed to retrieve trash"));
+        },
+    };
+    let Some(entry) = entry else {
+        return Err(not_in_trash(kind, id));
+    };
+
+    let capability = match (kind, restoring) {
+        (TrashKind::Inventory, _) => return Ok(entry),
+        (TrashKind::Item, true) => Capability::AddItems,
+        (TrashKind::Item, false) => Capability::RemoveItems,
+        (TrashKind::OrganizerType, _) => Capability::ManageOrganizers,
+    };
+    if let Some(inventory_id) = entry.inventory_id {
+        require_capability(db_service, auth, inventory_id, capability).await?;
+    }
+    Ok(entry)
+}
+
+/// List the user's trash, newest first
+#[get("/trash")]
+pub async fn get_trash(pool: web::Data<Pool>, req: HttpRequest) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service.get_trash(auth.user_id).await {
+        Ok(entries) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: Some(format!("Retrieved {} trash entries", entries.len())),
+            data: Some(TrashListing {
+                entries,
+                retention_days: retention_days(),
+            }),
+            error: None,
+        })),
+        Err(e) => {
+            error!("Error retrieving trash of user {}: {}", auth.username, e);
+            Ok(internal_error("Failed to retrieve trash"))
+        },
+    }
+}
+
+/// Restore an entry from the trash
+#[post("/trash/{kind}/{id}/restore")]
+pub async fn restore_from_trash(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let (kind, id) = match parse_entry_path(path.into_inner()) {
+        Ok(entry) => entry,
+        Err(response) => return Ok(response),
+    };
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let entry = match require_trash_entry(&db_service, &auth, kind, id, true).await {
+        Ok(entry) => entry,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.restore_from_trash(kind, id).await {
+        Ok(true) => {
+            info!(
+                "User {} restored {} {} from the trash",
+                auth.username, kind, id
+            );
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                message: Some(format!("Restored '{}'", entry.name)),
+                data: Some(entry),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(not_in_trash(kind, id)),
+        Err(e) => {
+            if e.to_string().contains("duplicate") || e.to_string().contains("unique") {
+                return Ok(HttpResponse::Conflict().json(ErrorResponse {
+                    success: false,
+                    error: "Organizer already exists".to_string(),
+                    message: Some(format!(
+                        "Rename or delete the organizer named '{}' before restoring this one",
+                        entry.name
+                    )),
+                }));
+            }
+            error!("Error restoring {} {}: {}", kind, id, e);
+            Ok(internal_error("Failed to restore from the trash"))
+        },
+    }
+}
+
+/// Permanently delete an entry from the trash
+#[delete("/trash/{kind}/{id}")]
+pub async fn purge_from_trash(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let (kind, id) = match parse_entry_path(path.into_inner()) {
+        Ok(entry) => entry,
+        Err(response) => return Ok(response),
+    };
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+
+    let entry = match require_trash_entry(&db_service, &auth, kind, id, false).await {
+        Ok(entry) => entry,
+        Err(response) => return Ok(response),
+    };
+
+    match db_service.purge_from_trash(kind, id).await {
+        Ok(true) => {
+            info!(
+                "User {} purged {} {} from the trash",
+                auth.username, kind, id
+            );
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(()),
+                message: Some(format!("Permanently deleted '{}'", entry.name)),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(not_in_trash(kind, id)),
+        Err(e) => {
+            error!("Error purging {} {}: {}", kind, id, e);
+            Ok(internal_error("Failed to delete from the trash"))
+        },
+    }
+}
@@ -0,0 +1,589 @@
+//! `WebAuthn` passkey API endpoints
+//!
+//! Provides passkey registration and management for signed-in users, and the
+//! authentication ceremonies for passwordless login and for completing the
+//! TOTP step of a login with a passkey instead of a code.
+
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Result};
+use deadpool_postgres::Pool;
+use log::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::webauthn::{
+    encode_base64url, finish_registration, start_assertion_check, start_authentication,
+    start_registration, user_handle, PendingAssertion, WebauthnConfig, WebauthnError,
+    CEREMONY_TIMEOUT, SUPPORTED_ALGORITHMS,
+};
+use crate::auth::{extract_token, verify_token};
+use crate::db::DatabaseService;
+use crate::models::{
+    ApiResponse, AuthenticatorSelectionCriteria, ErrorResponse, PublicKeyCredentialCreationOptions,
+    PublicKeyCredentialDescriptor, PublicKeyCredentialParameters,
+    PublicKeyCredentialRequestOptions, PublicKeyCredentialRpEntity, PublicKeyCredentialUserEntity,
+    RenameWebauthnCredentialRequest, User, WebauthnAuthenticateFinishRequest,
+    WebauthnRegisterFinishRequest, WebauthnStatusResponse,
+};
+
+use super::auth::{get_auth_context_from_request, start_session};
+
+// ==================== Helpers ====================
+
+fn ceremony_timeout_ms() -> u64 {
+    u64::try_from(CEREMONY_TIMEOUT.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Reject requests when passkeys aren't configured
+fn require_config(
+    config: Option<web::Data<WebauthnConfig>>,
+) -> std::result::Result<web::Data<WebauthnConfig>, HttpResponse> {
+    config.ok_or_else(|| {
+        HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Passkeys are not configured".to_string(),
+            message: Some("Ask an administrator to set WEBAUTHN_ORIGIN".to_string()),
+        })
+    })
+}
+
+fn webauthn_error_response(e: &WebauthnError) -> HttpResponse {
+    let body = ErrorResponse {
+        success: false,
+        error: e.to_string(),
+        message: Some("Please try again".to_string()),
+    };
+    match e {
+        WebauthnError::InvalidResponse(_) | WebauthnError::InvalidChallenge => {
+            HttpResponse::BadRequest().json(body)
+        },
+        WebauthnError::VerificationFailed(_) | WebauthnError::CounterRegression => {
+            HttpResponse::Unauthorized().json(body)
+        },
+    }
+}
+
+fn internal_error(context: &str, e: &dyn std::fmt::Display) -> HttpResponse {
+    error!("{}: {}", context, e);
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        success: false,
+        error: "An internal error occurred".to_string(),
+        message: None,
+    })
+}
+
+fn credential_descriptors(ids: Vec<(Vec<u8>, Vec<String>)>) -> Vec<PublicKeyCredentialDescriptor> {
+    ids.into_iter()
+        .map(|(id, transports)| PublicKeyCredentialDescriptor {
+            kind: "public-key".to_string(),
+            id: encode_base64url(&id),
+            transports,
+        })
+        .collect()
+}
+
+/// Resolve the user behind a partial (TOTP pending) token
+fn partial_token_user_id(req: &HttpRequest) -> std::result::Result<Uuid, HttpResponse> {
+    let unauthorized = |error: &str| {
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: error.to_string(),
+            message: Some("Please log in again".to_string()),
+        })
+    };
+
+    let token =
+        extract_token(req).ok_or_else(|| unauthorized("No authentication token provided"))?;
+    let claims = verify_token(&token).map_err(|_| unauthorized("Invalid or expired token"))?;
+    if !claims.totp_pending {
+        return Err(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Invalid token type".to_string(),
+            message: Some("This endpoint requires a TOTP verification token".to_string()),
+        }));
+    }
+    Uuid::parse_str(&claims.sub).map_err(|_| unauthorized("Invalid token"))
+}
+
+/// Verify an assertion against the stored credential and return its active owner
+///
+/// `expected_user` is set for second-factor verification, where the ceremony was
+/// started for a specific user; passwordless logins require user verification.
+async fn complete_assertion(
+    db_service: &DatabaseService,
+    config: &WebauthnConfig,
+    assertion: &PendingAssertion,
+    expected_user: Option<Uuid>,
+) -> std::result::Result<User, HttpResponse> {
+    let rejected = || {
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            success: false,
+            error: "Passkey not recognized".to_string(),
+            message: Some("Use a passkey registered to your account".to_string()),
+        })
+    };
+
+    if assertion.ceremony_user_id != expected_user {
+        return Err(webauthn_error_response(&WebauthnError::InvalidChallenge));
+    }
+
+    let key = match db_service
+        .get_webauthn_credential_key(&assertion.credential_id)
+        .await
+    {
+        Ok(Some(key)) => key,
+        Ok(None) => return Err(rejected()),
+        Err(e) => return Err(internal_error("Error loading passkey", &e)),
+    };
+    if expected_user.is_some_and(|user_id| user_id != key.user_id)
+        || assertion
+            .user_handle
+            .as_ref()
+            .is_some_and(|handle| handle.as_slice() != key.user_id.as_bytes())
+    {
+        return Err(rejected());
+    }
+
+    let stored_count = u32::try_from(key.sign_count).unwrap_or(u32::MAX);
+    let sign_count = match assertion.verify(
+        config,
+        &key.public_key,
+        stored_count,
+        expected_user.is_none(),
+    ) {
+        Ok(count) => count,
+        Err(e) => {
+            warn!("Passkey assertion for user {} failed: {}", key.user_id, e);
+            return Err(webauthn_error_response(&e));
+        },
+    };
+    if let Err(e) = db_service
+        .update_webauthn_credential_usage(key.id, i64::from(sign_count))
+        .await
+    {
+        warn!("Failed to record passkey usage: {}", e);
+    }
+
+    match db_service.get_user_by_id(key.user_id).await {
+        Ok(Some(user)) if user.is_active => Ok(user),
+        Ok(Some(_)) => Err(HttpResponse::Forbidden().json(ErrorResponse {
+            success: false,
+            error: "Account deactivated".to_string(),
+            message: Some(
+                "Your account has been deactivated. Contact an administrator.".to_string(),
+            ),
+        })),
+        Ok(None) => Err(rejected()),
+        Err(e) => Err(internal_error("Database error during passkey login", &e)),
+    }
+}
+
+/// Start a full session after a successful passkey ceremony
+async fn session_response(
+    db_service: &DatabaseService,
+    user: User,
+    req: &HttpRequest,
+) -> HttpResponse {
+    match start_session(db_service, user, req).await {
+        Ok(session) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(session),
+            message: Some("Login successful".to_string()),
+            error: None,
+        }),
+        Err(e) => internal_error("Error starting passkey session", &e),
+    }
+}
+
+// ==================== Status ====================
+
+/// Whether passkeys are configured
+#[get("/auth/webauthn/config")]
+pub async fn webauthn_status(config: Option<web::Data<WebauthnConfig>>) -> Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(WebauthnStatusResponse {
+            enabled: config.is_some(),
+        }),
+        message: None,
+        error: None,
+    }))
+}
+
+// ==================== Registration ====================
+
+/// Start registering a passkey for the current user
+#[post("/auth/webauthn/register/start")]
+pub async fn webauthn_register_start(
+    pool: web::Data<Pool>,
+    config: Option<web::Data<WebauthnConfig>>,
+    req: HttpRequest,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match db_service.get_user_by_id(auth.user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(HttpResponse::NotFound().finish()),
+        Err(e) => return Ok(internal_error("Error loading user", &e)),
+    };
+    let existing = match db_service
+        .get_user_webauthn_credential_ids(auth.user_id)
+        .await
+    {
+        Ok(ids) => ids,
+        Err(e) => return Ok(internal_error("Error loading passkeys", &e)),
+    };
+
+    let options = PublicKeyCredentialCreationOptions {
+        challenge: start_registration(user.id),
+        rp: PublicKeyCredentialRpEntity {
+            id: config.rp_id.clone(),
+            name: config.rp_name.clone(),
+        },
+        user: PublicKeyCredentialUserEntity {
+            id: user_handle(user.id),
+            name: user.username,
+            display_name: user.full_name,
+        },
+        pub_key_cred_params: SUPPORTED_ALGORITHMS
+            .iter()
+            .map(|alg| PublicKeyCredentialParameters {
+                kind: "public-key".to_string(),
+                alg: *alg,
+            })
+            .collect(),
+        timeout: ceremony_timeout_ms(),
+        attestation: "none".to_string(),
+        authenticator_selection: AuthenticatorSelectionCriteria {
+            resident_key: "preferred".to_string(),
+            user_verification: "preferred".to_string(),
+        },
+        // Stop the same authenticator being registered twice
+        exclude_credentials: credential_descriptors(existing),
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(options),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Finish registering a passkey
+#[post("/auth/webauthn/register/finish")]
+pub async fn webauthn_register_finish(
+    pool: web::Data<Pool>,
+    config: Option<web::Data<WebauthnConfig>>,
+    req: HttpRequest,
+    body: web::Json<WebauthnRegisterFinishRequest>,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let registration = match finish_registration(&config, &body.credential) {
+        Ok(registration) if registration.user_id == auth.user_id => registration,
+        Ok(_) => return Ok(webauthn_error_response(&WebauthnError::InvalidChallenge)),
+        Err(e) => {
+            warn!("Passkey registration for {} failed: {}", auth.username, e);
+            return Ok(webauthn_error_response(&e));
+        },
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .get_webauthn_credential_key(&registration.credential_id)
+        .await
+    {
+        Ok(None) => {},
+        Ok(Some(_)) => {
+            return Ok(HttpResponse::Conflict().json(ErrorResponse {
+                success: false,
+                error: "This passkey is already registered".to_string(),
+                message: None,
+            }));
+        },
+        Err(e) => return Ok(internal_error("Error checking passkey", &e)),
+    }
+
+    match db_service
+        .create_webauthn_credential(
+            auth.user_id,
+            &body.nickname,
+            &registration.credential_id,
+            &registration.public_key,
+            i64::from(registration.sign_count),
+            &registration.transports,
+        )
+        .await
+    {
+        Ok(credential) => {
+            info!(
+                "User {} registered passkey '{}'",
+                auth.username, credential.nickname
+            );
+            Ok(HttpResponse::Created().json(ApiResponse {
+                success: true,
+                data: Some(credential),
+                message: Some("Passkey registered".to_string()),
+                error: None,
+            }))
+        },
+        Err(e) => Ok(internal_error("Error storing passkey", &e)),
+    }
+}
+
+// ==================== Credential Management ====================
+
+/// List the current user's passkeys
+#[get("/auth/webauthn/credentials")]
+pub async fn get_webauthn_credentials(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service.get_user_webauthn_credentials(auth.user_id).await {
+        Ok(credentials) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(credentials),
+            message: None,
+            error: None,
+        })),
+        Err(e) => Ok(internal_error("Error listing passkeys", &e)),
+    }
+}
+
+/// Rename one of the current user's passkeys
+#[put("/auth/webauthn/credentials/{id}")]
+pub async fn rename_webauthn_credential(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<RenameWebauthnCredentialRequest>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+    if let Err(e) = body.validate() {
+        return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+            success: false,
+            error: "Validation failed".to_string(),
+            message: Some(e.to_string()),
+        }));
+    }
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .rename_webauthn_credential(auth.user_id, path.into_inner(), &body.nickname)
+        .await
+    {
+        Ok(true) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: None::<()>,
+            message: Some("Passkey renamed".to_string()),
+            error: None,
+        })),
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Passkey not found".to_string(),
+            message: None,
+        })),
+        Err(e) => Ok(internal_error("Error renaming passkey", &e)),
+    }
+}
+
+/// Delete one of the current user's passkeys
+#[delete("/auth/webauthn/credentials/{id}")]
+pub async fn delete_webauthn_credential(
+    pool: web::Data<Pool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder> {
+    let auth = match get_auth_context_from_request(&req, pool.get_ref()).await {
+        Ok(a) => a,
+        Err(e) => return Ok(e),
+    };
+
+    let id = path.into_inner();
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    match db_service
+        .delete_webauthn_credential(auth.user_id, id)
+        .await
+    {
+        Ok(true) => {
+            info!("User {} deleted passkey {}", auth.username, id);
+            Ok(HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: None::<()>,
+                message: Some("Passkey deleted".to_string()),
+                error: None,
+            }))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            success: false,
+            error: "Passkey not found".to_string(),
+            message: None,
+        })),
+        Err(e) => Ok(internal_error("Error deleting passkey", &e)),
+    }
+}
+
+// ==================== Passwordless Login ====================
+
+/// Start a passwordless login; any discoverable passkey for this site may answer
+#[post("/auth/webauthn/login/start")]
+pub async fn webauthn_login_start(
+    config: Option<web::Data<WebauthnConfig>>,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(PublicKeyCredentialRequestOptions {
+            challenge: start_authentication(None),
+            rp_id: config.rp_id.clone(),
+            timeout: ceremony_timeout_ms(),
+            user_verification: "required".to_string(),
+            allow_credentials: Vec::new(),
+        }),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Finish a passwordless login and start a session
+#[post("/auth/webauthn/login/finish")]
+pub async fn webauthn_login_finish(
+    pool: web::Data<Pool>,
+    config: Option<web::Data<WebauthnConfig>>,
+    req: HttpRequest,
+    body: web::Json<WebauthnAuthenticateFinishRequest>,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+    let assertion = match start_assertion_check(&config, &body.credential) {
+        Ok(assertion) => assertion,
+        Err(e) => return Ok(webauthn_error_response(&e)),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match complete_assertion(&db_service, &config, &assertion, None).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    // A verified passkey is already multi-factor, so TOTP isn't asked for
+    info!("User {} logged in with a passkey", user.username);
+    Ok(session_response(&db_service, user, &req).await)
+}
+
+// ==================== Second Factor ====================
+
+/// Start verifying a login with a passkey instead of a TOTP code
+/// Requires a `partial_token` (JWT with `totp_pending=true`)
+#[post("/auth/webauthn/verify/start")]
+pub async fn webauthn_verify_start(
+    pool: web::Data<Pool>,
+    config: Option<web::Data<WebauthnConfig>>,
+    req: HttpRequest,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+    let user_id = match partial_token_user_id(&req) {
+        Ok(user_id) => user_id,
+        Err(response) => return Ok(response),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let credentials = match db_service.get_user_webauthn_credential_ids(user_id).await {
+        Ok(ids) if !ids.is_empty() => ids,
+        Ok(_) => {
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse {
+                success: false,
+                error: "No passkeys registered".to_string(),
+                message: Some("Enter the code from your authenticator app".to_string()),
+            }));
+        },
+        Err(e) => return Ok(internal_error("Error loading passkeys", &e)),
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(PublicKeyCredentialRequestOptions {
+            challenge: start_authentication(Some(user_id)),
+            rp_id: config.rp_id.clone(),
+            timeout: ceremony_timeout_ms(),
+            user_verification: "discouraged".to_string(),
+            allow_credentials: credential_descriptors(credentials),
+        }),
+        message: None,
+        error: None,
+    }))
+}
+
+/// Finish verifying a login with a passkey and start the full session
+#[post("/auth/webauthn/verify/finish")]
+pub async fn webauthn_verify_finish(
+    pool: web::Data<Pool>,
+    config: Option<web::Data<WebauthnConfig>>,
+    req: HttpRequest,
+    body: web::Json<WebauthnAuthenticateFinishRequest>,
+) -> Result<impl Responder> {
+    let config = match require_config(config) {
+        Ok(config) => config,
+        Err(response) => return Ok(response),
+    };
+    let user_id = match partial_token_user_id(&req) {
+        Ok(user_id) => user_id,
+        Err(response) => return Ok(response),
+    };
+    let assertion = match start_assertion_check(&config, &body.credential) {
+        Ok(assertion) => assertion,
+        Err(e) => return Ok(webauthn_error_response(&e)),
+    };
+
+    let db_service = DatabaseService::new(pool.get_ref().clone());
+    let user = match complete_assertion(&db_service, &config, &assertion, Some(user_id)).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    info!(
+        "User {} completed login verification with a passkey",
+        user.username
+    );
+    Ok(session_response(&db_service, user, &req).await)
+}
@@ -0,0 +1,269 @@
+//! LDAP authentication module
+//!
+//! Authenticates logins with a search-then-bind against an LDAP directory
+//! (`OpenLDAP`, Active Directory, ...): find the user's entry with a service
+//! account, check admin group membership, then bind as the user to verify the
+//! password.
+
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use std::env;
+use std::time::Duration;
+
+/// Timeout for connecting to the directory
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// LDAP result code for a failed bind
+const INVALID_CREDENTIALS: u32 = 49;
+
+/// LDAP-related errors
+#[derive(Debug)]
+pub enum LdapError {
+    /// The directory could not be reached or rejected a request
+    Directory(String),
+    /// The user filter matched more than one entry
+    AmbiguousUser(String),
+}
+
+impl std::fmt::Display for LdapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LdapError::Directory(msg) => write!(f, "LDAP error: {msg}"),
+            LdapError::AmbiguousUser(username) => {
+                write!(
+                    f,
+                    "LDAP user filter matched several entries for '{username}'"
+                )
+            },
+        }
+    }
+}
+
+impl std::error::Error for LdapError {}
+
+impl From<ldap3::LdapError> for LdapError {
+    fn from(e: ldap3::LdapError) -> Self {
+        LdapError::Directory(e.to_string())
+    }
+}
+
+// ==================== Configuration ====================
+
+/// LDAP settings, read from `LDAP_*` environment variables
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    /// `ldap://` or `ldaps://` URL of the directory server
+    pub url: String,
+    /// Service account used to search for users; anonymous search when unset
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    /// Base DN searched for user entries
+    pub search_base: String,
+    /// Search filter; `{username}` is replaced with the escaped login name
+    pub user_filter: String,
+    /// Attribute holding the user's display name
+    pub full_name_attribute: String,
+    /// Members of this group become admins, everyone else loses admin; unset leaves `is_admin` alone
+    pub admin_group_dn: Option<String>,
+    /// Upgrade `ldap://` connections with `StartTLS`
+    pub start_tls: bool,
+    /// Skip certificate verification (self-signed test directories only)
+    pub tls_skip_verify: bool,
+    /// Create a local account on first login when no username matches
+    pub auto_provision: bool,
+}
+
+impl LdapConfig {
+    /// Build the configuration from the environment. Returns `None` unless
+    /// both `LDAP_URL` and `LDAP_SEARCH_BASE` are set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let flag = |key: &str, default: bool| {
+            var(key).map_or(default, |v| v.eq_ignore_ascii_case("true") || v == "1")
+        };
+
+        Some(Self {
+            url: var("LDAP_URL")?,
+            bind_dn: var("LDAP_BIND_DN"),
+            bind_password: var("LDAP_BIND_PASSWORD"),
+            search_base: var("LDAP_SEARCH_BASE")?,
+            user_filter: var("LDAP_USER_FILTER").unwrap_or_else(|| "(uid={username})".to_string()),
+            full_name_attribute: var("LDAP_FULL_NAME_ATTRIBUTE")
+                .unwrap_or_else(|| "cn".to_string()),
+            admin_group_dn: var("LDAP_ADMIN_GROUP_DN"),
+            start_tls: flag("LDAP_START_TLS", false),
+            tls_skip_verify: flag("LDAP_TLS_SKIP_VERIFY", false),
+            auto_provision: flag("LDAP_AUTO_PROVISION", true),
+        })
+    }
+}
+
+// ==================== Authentication ====================
+
+/// A directory user whose password was verified
+#[derive(Debug, Clone)]
+pub struct LdapUser {
+    pub dn: String,
+    pub full_name: Option<String>,
+    /// Admin group membership, or `None` when no admin group is configured
+    pub is_admin: Option<bool>,
+}
+
+/// Outcome of an LDAP login attempt
+#[derive(Debug)]
+pub enum LdapLogin {
+    Authenticated(LdapUser),
+    InvalidPassword,
+    /// The directory has no entry for this username
+    NotFound,
+}
+
+/// Build the user search filter for a login name
+#[must_use]
+pub fn user_filter(config: &LdapConfig, username: &str) -> String {
+    config
+        .user_filter
+        .replace("{username}", &ldap_escape(username))
+}
+
+/// Filter matching a user in a `groupOfNames`, `groupOfUniqueNames` or `posixGroup`
+#[must_use]
+pub fn group_member_filter(user_dn: &str, username: &str) -> String {
+    format!(
+        "(|(member={dn})(uniqueMember={dn})(memberUid={uid}))",
+        dn = ldap_escape(user_dn),
+        uid = ldap_escape(username)
+    )
+}
+
+/// Verify a username and password against the directory
+pub async fn authenticate(
+    config: &LdapConfig,
+    username: &str,
+    password: &str,
+) -> Result<LdapLogin, LdapError> {
+    // An empty password would be an unauthenticated bind, which servers accept
+    if password.is_empty() {
+        return Ok(LdapLogin::InvalidPassword);
+    }
+
+    let mut ldap = connect(config).await?;
+    let result = search_and_bind(&mut ldap, config, username, password).await;
+    let _ = ldap.unbind().await;
+    result
+}
+
+async fn connect(config: &LdapConfig) -> Result<Ldap, LdapError> {
+    let settings = LdapConnSettings::new()
+        .set_conn_timeout(CONNECT_TIMEOUT)
+        .set_starttls(config.start_tls)
+        .set_no_tls_verify(config.tls_skip_verify);
+    let (conn, ldap) = LdapConnAsync::with_settings(settings, &config.url).await?;
+    ldap3::drive!(conn);
+    Ok(ldap)
+}
+
+async fn search_and_bind(
+    ldap: &mut Ldap,
+    config: &LdapConfig,
+    username: &str,
+    password: &str,
+) -> Result<LdapLogin, LdapError> {
+    if let (Some(bind_dn), Some(bind_password)) = (&config.bind_dn, &config.bind_password) {
+        ldap.simple_bind(bind_dn, bind_password).await?.success()?;
+    }
+
+    let (entries, _) = ldap
+        .search(
+            &config.search_base,
+            Scope::Subtree,
+            &user_filter(config, username),
+            vec![config.full_name_attribute.as_str()],
+        )
+        .await?
+        .success()?;
+    let entry = match entries.len() {
+        0 => return Ok(LdapLogin::NotFound),
+        1 => SearchEntry::construct(entries.into_iter().next().expect("one entry")),
+        _ => return Err(LdapError::AmbiguousUser(username.to_string())),
+    };
+
+    // Check the group while still bound as the service account, which can
+    // usually read groups the user cannot
+    let is_admin = match &config.admin_group_dn {
+        Some(group_dn) => {
+            let (groups, _) = ldap
+                .search(
+                    group_dn,
+                    Scope::Base,
+                    &group_member_filter(&entry.dn, username),
+                    vec!["1.1"],
+                )
+                .await?
+                .non_error()?;
+            Some(!groups.is_empty())
+        },
+        None => None,
+    };
+
+    let bind = ldap.simple_bind(&entry.dn, password).await?;
+    if bind.rc == INVALID_CREDENTIALS {
+        return Ok(LdapLogin::InvalidPassword);
+    }
+    bind.success()?;
+
+    let full_name = entry
+        .attrs
+        .get(&config.full_name_attribute)
+        .and_then(|values| values.first())
+        .cloned();
+
+    Ok(LdapLogin::Authenticated(LdapUser {
+        dn: entry.dn,
+        full_name,
+        is_admin,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(user_filter: &str) -> LdapConfig {
+        LdapConfig {
+            url: "ldap://localhost:389".to_string(),
+            bind_dn: None,
+            bind_password: None,
+            search_base: "ou=people,dc=example,dc=org".to_string(),
+            user_filter: user_filter.to_string(),
+            full_name_attribute: "cn".to_string(),
+            admin_group_dn: None,
+            start_tls: false,
+            tls_skip_verify: false,
+            auto_provision: true,
+        }
+    }
+
+    #[test]
+    fn test_user_filter_escapes_username() {
+        let config = config("(&(objectClass=person)(uid={username}))");
+        assert_eq!(
+            user_filter(&config, "alice"),
+            "(&(objectClass=person)(uid=alice))"
+        );
+        // Filter metacharacters can't widen the search
+        assert_eq!(
+            user_filter(&config, "*)(uid=*"),
+            "(&(objectClass=person)(uid=\\2a\\29\\28uid=\\2a))"
+        );
+    }
+
+    #[test]
+    fn test_group_member_filter() {
+        assert_eq!(
+            group_member_filter("uid=bob,ou=people,dc=example,dc=org", "bob"),
+            "(|(member=uid=bob,ou=people,dc=example,dc=org)\
+             (uniqueMember=uid=bob,ou=people,dc=example,dc=org)(memberUid=bob))"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Per-account login throttling
+//!
+//! Failed password logins are counted per username, whether or not an account
+//! with that name exists, so throttled responses don't reveal which usernames
+//! are real. After a few free attempts every further failure doubles the wait
+//! before the next attempt is accepted, and a long run of failures locks the
+//! username out for a while. An admin can clear the lock early.
+
+use chrono::Duration;
+
+/// Failures allowed before attempts start being delayed
+pub const FREE_ATTEMPTS: i32 = 3;
+
+/// Failures after which the username is locked out
+pub const LOCKOUT_THRESHOLD: i32 = 10;
+
+/// How long a locked-out username stays locked
+pub const LOCKOUT_MINUTES: i64 = 15;
+
+/// A failure this long after the previous one starts the count again
+pub const RESET_AFTER_HOURS: i64 = 24;
+
+/// Longest column value stored for a username
+const MAX_KEY_LENGTH: usize = 255;
+
+/// The key failed attempts are tracked under for a login name
+#[must_use]
+pub fn attempt_key(username: &str) -> String {
+    username
+        .trim()
+        .to_lowercase()
+        .chars()
+        .take(MAX_KEY_LENGTH)
+        .collect()
+}
+
+/// How long to refuse further attempts after this many consecutive failures
+#[must_use]
+pub fn lockout_delay(failed_attempts: i32) -> Option<Duration> {
+    if failed_attempts >= LOCKOUT_THRESHOLD {
+        Some(Duration::minutes(LOCKOUT_MINUTES))
+    } else if failed_attempts > FREE_ATTEMPTS {
+        // 1s, 2s, 4s, ... doubling with each failure
+        Some(Duration::seconds(
+            1 << (failed_attempts - FREE_ATTEMPTS - 1),
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_delay_backs_off_then_locks() {
+        for attempts in 0..=FREE_ATTEMPTS {
+            assert_eq!(lockout_delay(attempts), None);
+        }
+        assert_eq!(lockout_delay(FREE_ATTEMPTS + 1), Some(Duration::seconds(1)));
+        assert_eq!(lockout_delay(FREE_ATTEMPTS + 2), Some(Duration::seconds(2)));
+        assert_eq!(lockout_delay(FREE_ATTEMPTS + 3), Some(Duration::seconds(4)));
+        assert!(lockout_delay(LOCKOUT_THRESHOLD - 1).unwrap() < Duration::minutes(1));
+        assert_eq!(
+            lockout_delay(LOCKOUT_THRESHOLD),
+            Some(Duration::minutes(LOCKOUT_MINUTES))
+        );
+        assert_eq!(
+            lockout_delay(LOCKOUT_THRESHOLD + 20),
+            Some(Duration::minutes(LOCKOUT_MINUTES))
+        );
+    }
+
+    #[test]
+    fn test_attempt_key_normalizes_username() {
+        assert_eq!(attempt_key("  Alice "), "alice");
+        assert_eq!(attempt_key(&"x".repeat(1000)).len(), MAX_KEY_LENGTH);
+    }
+}
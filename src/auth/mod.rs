@@ -3,9 +3,14 @@
 //! Provides JWT token handling, password hashing with Argon2, auth middleware for Actix-Web,
 //! and TOTP-based two-factor authentication.
 
+pub mod ldap;
+pub mod lockout;
+pub mod oidc;
+pub mod proxy;
 pub mod totp;
+pub mod webauthn;
 
-use actix_web::HttpRequest;
+use actix_web::{http::Method, HttpRequest};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
@@ -16,7 +21,7 @@ use std::env;
 use std::sync::OnceLock;
 use uuid::Uuid;
 
-use crate::models::{Claims, User};
+use crate::models::{ApiTokenScope, Claims, User};
 
 // ==================== JWT Secret Management ====================
 
@@ -114,7 +119,7 @@ fn read_jwt_secret() -> Option<String> {
 }
 
 /// Generate a cryptographically secure random string
-fn generate_random_secret(length: usize) -> String {
+pub(crate) fn generate_random_secret(length: usize) -> String {
     use rand::Rng;
     rand::thread_rng()
         .sample_iter(&rand::distributions::Alphanumeric)
@@ -131,7 +136,10 @@ pub fn jwt_secret() -> String {
     get_or_init_jwt_secret().to_string()
 }
 
-/// Get JWT token lifetime in hours from environment
+/// Get session lifetime in hours from environment
+///
+/// Sessions (and their refresh tokens) expire this long after login; the
+/// access tokens issued within a session are much shorter-lived.
 #[must_use]
 pub fn jwt_token_lifetime_hours() -> i64 {
     env::var("JWT_TOKEN_LIFETIME_HOURS")
@@ -140,16 +148,35 @@ pub fn jwt_token_lifetime_hours() -> i64 {
         .unwrap_or(24) // Default to 24 hours
 }
 
-/// Generate a JWT token for a user
-pub fn generate_token(user: &User) -> Result<String, jsonwebtoken::errors::Error> {
+/// Get access token lifetime in minutes from environment
+#[must_use]
+pub fn access_token_lifetime_minutes() -> i64 {
+    env::var("ACCESS_TOKEN_LIFETIME_MINUTES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|minutes| *minutes > 0)
+        .unwrap_or(15) // Default to 15 minutes
+}
+
+/// Generate a short-lived access token for a user's session
+pub fn generate_token(
+    user: &User,
+    session_id: Uuid,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode_access_token(user, Some(session_id))
+}
+
+fn encode_access_token(
+    user: &User,
+    session_id: Option<Uuid>,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let now = Utc::now();
-    let token_lifetime_hours = jwt_token_lifetime_hours();
     // Safe cast: Unix timestamps are always positive, max(0) ensures non-negative
     #[allow(
         clippy::cast_sign_loss,
         reason = "Unix timestamps are always positive; max(0) ensures safety"
     )]
-    let expiration = (now + chrono::Duration::hours(token_lifetime_hours))
+    let expiration = (now + chrono::Duration::minutes(access_token_lifetime_minutes()))
         .timestamp()
         .max(0) as u64;
 
@@ -164,6 +191,7 @@ pub fn generate_token(user: &User) -> Result<String, jsonwebtoken::errors::Error
         )]
         iat: now.timestamp().max(0) as u64,
         totp_pending: false,
+        sid: session_id.map(|id| id.to_string()),
     };
 
     let header = Header::new(Algorithm::HS256);
@@ -174,6 +202,23 @@ pub fn generate_token(user: &User) -> Result<String, jsonwebtoken::errors::Error
     )
 }
 
+/// Generate a random refresh token (returned to the client once, stored hashed)
+#[must_use]
+pub fn generate_refresh_token() -> String {
+    generate_random_secret(64)
+}
+
+/// Hash a refresh token for storage and lookup
+#[must_use]
+pub fn hash_refresh_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+fn sha256_hex(value: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(value.as_bytes()))
+}
+
 /// Generate a short-lived partial JWT token for TOTP verification.
 /// This token has `totp_pending: true` and expires in 5 minutes.
 /// It can ONLY be used with the TOTP verify endpoint.
@@ -197,6 +242,7 @@ pub fn generate_partial_token(user: &User) -> Result<String, jsonwebtoken::error
         )]
         iat: now.timestamp().max(0) as u64,
         totp_pending: true,
+        sid: None,
     };
 
     let header = Header::new(Algorithm::HS256);
@@ -216,7 +262,8 @@ pub fn verify_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error>
     decode::<Claims>(token, &key, &validation).map(|data| data.claims)
 }
 
-/// Extract JWT token from Authorization header or `auth_token` cookie
+/// Extract the bearer token from the Authorization header or `auth_token` cookie.
+/// This is either a JWT or a personal API token (see [`is_api_token`]).
 #[must_use]
 pub fn extract_token(req: &HttpRequest) -> Option<String> {
     // Try Authorization header first (Bearer token)
@@ -236,6 +283,199 @@ pub fn extract_token(req: &HttpRequest) -> Option<String> {
     None
 }
 
+// ==================== Password Reset Links ====================
+
+/// How long an admin-issued password reset link stays valid by default
+pub const PASSWORD_RESET_DEFAULT_HOURS: i64 = 24;
+
+/// Longest lifetime an admin can give a password reset link
+pub const PASSWORD_RESET_MAX_HOURS: i64 = 168;
+
+/// Generate a random password reset token (returned to the admin once, stored hashed)
+#[must_use]
+pub fn generate_password_reset_token() -> String {
+    generate_random_secret(48)
+}
+
+/// Hash a password reset token for storage and lookup
+#[must_use]
+pub fn hash_password_reset_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+// ==================== Invitations ====================
+
+/// How long an invitation stays valid by default
+pub const INVITATION_DEFAULT_HOURS: i64 = 72;
+
+/// Longest lifetime an invitation can be given
+pub const INVITATION_MAX_HOURS: i64 = 720;
+
+/// Generate a random invitation token (returned to the inviter once, stored hashed)
+#[must_use]
+pub fn generate_invitation_token() -> String {
+    generate_random_secret(48)
+}
+
+/// Hash an invitation token for storage and lookup
+#[must_use]
+pub fn hash_invitation_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+// ==================== Public Share Links ====================
+
+/// Number of leading characters of a share link token kept for display
+pub const SHARE_LINK_DISPLAY_LENGTH: usize = 8;
+
+/// Generate a random share link token (returned to the creator once, stored hashed)
+#[must_use]
+pub fn generate_share_link_token() -> String {
+    generate_random_secret(32)
+}
+
+/// Hash a share link token for storage and lookup
+#[must_use]
+pub fn hash_share_link_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+// ==================== Personal API Tokens ====================
+
+/// Prefix that marks a bearer token as a personal API token rather than a JWT
+pub const API_TOKEN_PREFIX: &str = "hrp_";
+
+/// Number of leading characters of an API token kept for display
+pub const API_TOKEN_DISPLAY_LENGTH: usize = 12;
+
+/// Generate a random personal API token (returned to the client once, stored hashed)
+#[must_use]
+pub fn generate_api_token() -> String {
+    format!("{API_TOKEN_PREFIX}{}", generate_random_secret(48))
+}
+
+/// Whether a bearer token is a personal API token
+#[must_use]
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// Hash a personal API token for storage and lookup
+#[must_use]
+pub fn hash_api_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+/// Scope an API token needs to call an endpoint, or `None` if API tokens
+/// cannot use it at all (account, sharing, admin and backup endpoints)
+#[must_use]
+pub fn required_api_token_scope(method: &Method, path: &str) -> Option<ApiTokenScope> {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let read = method == Method::GET || method == Method::HEAD;
+
+    match segments.as_slice() {
+        ["reports", ..] if read => Some(ApiTokenScope::ReportsRead),
+        // Sharing, ownership and creating, renaming or deleting whole inventories
+        // stay with interactive sessions
+        ["inventories", _, "shares" | "transfer-ownership", ..] => None,
+        ["inventories"] | ["inventories", _] if !read => None,
+        ["inventories" | "items" | "organizers" | "organizer-options" | "images", ..] => {
+            Some(if read {
+                ApiTokenScope::ItemsRead
+            } else {
+                ApiTokenScope::ItemsWrite
+            })
+        },
+        _ => None,
+    }
+}
+
+/// Inventory named by a `/api/inventories/{id}/...` path
+fn inventory_id_from_path(path: &str) -> Option<i32> {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    path.strip_prefix("/inventories/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Item named by a `/api/items/{id}/...` path
+pub(crate) fn item_id_from_path(path: &str) -> Option<i32> {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    path.strip_prefix("/items/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Endpoints that name no inventory in their path but apply a token's inventory
+/// restriction themselves: lists and reports filter or check what they cover,
+/// and creating an item checks the inventory in the request body
+fn restriction_checked_by_handler(method: &Method, path: &str) -> bool {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let read = method == Method::GET || method == Method::HEAD;
+
+    match segments.as_slice() {
+        ["items"] if method == Method::POST => true,
+        ["inventories" | "items"] | ["items", "search", _] | ["reports", ..] => read,
+        _ => false,
+    }
+}
+
+/// Restrictions carried by a request authenticated with a personal API token
+#[derive(Debug, Clone)]
+pub struct ApiTokenAccess {
+    pub token_id: Uuid,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Inventories the token is limited to; `None` means all accessible inventories
+    pub inventory_ids: Option<Vec<i32>>,
+}
+
+impl ApiTokenAccess {
+    #[must_use = "permission check result should be used to enforce access control"]
+    pub fn allows_inventory(&self, inventory_id: i32) -> bool {
+        self.inventory_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&inventory_id))
+    }
+
+    /// Check that the token's scopes and inventory restriction cover a request
+    ///
+    /// `item_inventory_id` is the inventory of the item a `/api/items/{id}`
+    /// request targets, since those paths don't name an inventory themselves.
+    pub fn check_request(
+        &self,
+        req: &HttpRequest,
+        item_inventory_id: Option<i32>,
+    ) -> Result<(), String> {
+        let Some(scope) = required_api_token_scope(req.method(), req.path()) else {
+            return Err("API tokens cannot access this endpoint".to_string());
+        };
+        if !self.scopes.contains(&scope) {
+            return Err(format!("API token is missing the {scope} scope"));
+        }
+
+        if self.inventory_ids.is_none() {
+            return Ok(());
+        }
+
+        // The request must name an inventory the token may use, in its
+        // path or through the item it targets. Query parameters don't count, since
+        // most handlers never look at them.
+        let inventory_id = inventory_id_from_path(req.path()).or(item_inventory_id);
+        match inventory_id {
+            Some(id) if self.allows_inventory(id) => Ok(()),
+            Some(_) => Err("API token is not allowed to access this inventory".to_string()),
+            None if restriction_checked_by_handler(req.method(), req.path()) => Ok(()),
+            None => Err("API token is restricted to specific inventories".to_string()),
+        }
+    }
+}
+
 // ==================== Password Hashing ====================
 
 /// Hash a password using Argon2id
@@ -275,6 +515,10 @@ pub struct AuthContext {
     pub user_id: Uuid,
     pub username: String,
     pub is_admin: bool,
+    /// Login session the access token belongs to
+    pub session_id: Option<Uuid>,
+    /// Set when the request was authenticated with a personal API token
+    pub api_token: Option<ApiTokenAccess>,
 }
 
 impl AuthContext {
@@ -283,8 +527,19 @@ impl AuthContext {
             user_id: Uuid::parse_str(&claims.sub)?,
             username: claims.username.clone(),
             is_admin: claims.is_admin,
+            session_id: claims.sid.as_deref().map(Uuid::parse_str).transpose()?,
+            api_token: None,
         })
     }
+
+    /// Whether the request may touch an inventory; only API tokens can be narrower
+    /// than the user's own access
+    #[must_use = "permission check result should be used to enforce access control"]
+    pub fn allows_inventory(&self, inventory_id: i32) -> bool {
+        self.api_token
+            .as_ref()
+            .is_none_or(|token| token.allows_inventory(inventory_id))
+    }
 }
 
 // ==================== Helper Functions ====================
@@ -339,7 +594,10 @@ pub fn verify_password_sync(
         .is_ok())
 }
 
-/// Alias for `generate_token` to match common naming convention
+/// Create a standalone access token for tests
+///
+/// The token is not bound to a session, so the API rejects it; it is only
+/// useful for exercising token encoding and verification.
 pub fn create_token(user_id: &Uuid, username: &str) -> Result<String, jsonwebtoken::errors::Error> {
     let user = User {
         id: *user_id,
@@ -353,7 +611,7 @@ pub fn create_token(user_id: &Uuid, username: &str) -> Result<String, jsonwebtok
         recovery_codes_generated_at: None, // Not generated initially
         recovery_codes_confirmed: false,   // Not confirmed initially
     };
-    generate_token(&user)
+    encode_access_token(&user, None)
 }
 
 #[cfg(test)]
@@ -366,6 +624,71 @@ mod tests {
         assert!(validate_password("validpassword123").is_ok());
     }
 
+    #[test]
+    fn test_api_token_scope_for_endpoints() {
+        assert_eq!(
+            required_api_token_scope(&Method::GET, "/api/inventories/3/items"),
+            Some(ApiTokenScope::ItemsRead)
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::PUT, "/api/items/7"),
+            Some(ApiTokenScope::ItemsWrite)
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::GET, "/api/reports/inventory/statistics"),
+            Some(ApiTokenScope::ReportsRead)
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::POST, "/api/inventories/3/shares"),
+            None
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::DELETE, "/api/inventories/3"),
+            None
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::POST, "/api/inventories"),
+            None
+        );
+        assert_eq!(
+            required_api_token_scope(&Method::POST, "/api/inventories/3/organizers"),
+            Some(ApiTokenScope::ItemsWrite)
+        );
+        assert_eq!(required_api_token_scope(&Method::GET, "/api/auth/me"), None);
+        assert_eq!(
+            required_api_token_scope(&Method::GET, "/api/backup/list"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_restricted_token_needs_inventory_in_path() {
+        let token = ApiTokenAccess {
+            token_id: Uuid::new_v4(),
+            scopes: vec![ApiTokenScope::ItemsRead, ApiTokenScope::ItemsWrite],
+            inventory_ids: Some(vec![3]),
+        };
+        let check = |method: Method, uri: &str, item_inventory_id: Option<i32>| {
+            let req = actix_web::test::TestRequest::default()
+                .method(method)
+                .uri(uri)
+                .to_http_request();
+            token.check_request(&req, item_inventory_id)
+        };
+
+        assert!(check(Method::GET, "/api/inventories/3/items", None).is_ok());
+        assert!(check(Method::GET, "/api/inventories/4/items", None).is_err());
+        assert!(check(Method::PUT, "/api/items/7", Some(3)).is_ok());
+        assert!(check(Method::PUT, "/api/items/7", Some(4)).is_err());
+        // A query parameter doesn't stand in for the inventory being accessed
+        assert!(check(Method::DELETE, "/api/images/a.jpg?inventory_id=3", None).is_err());
+        assert!(check(Method::PUT, "/api/organizers/5?inventory_id=3", None).is_err());
+        // Lists are filtered by their handlers
+        assert!(check(Method::GET, "/api/items", None).is_ok());
+        assert!(check(Method::GET, "/api/items/search/lamp", None).is_ok());
+        assert!(check(Method::POST, "/api/items", None).is_ok());
+    }
+
     #[test]
     fn test_username_validation() {
         assert!(validate_username("ab").is_err());
@@ -0,0 +1,404 @@
+//! OIDC single sign-on module
+//!
+//! Implements the authorization code flow with PKCE against any OIDC provider
+//! (Authentik, Keycloak, ...): provider discovery, the authorization redirect,
+//! the code exchange and ID token verification.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::generate_random_secret;
+
+/// How long a login may take between the redirect to the provider and the callback
+const PENDING_LOGIN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How long the one-time code handed to the frontend after the callback stays valid
+const LOGIN_CODE_TTL: Duration = Duration::from_secs(60);
+
+/// Timeout for requests to the provider
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// OIDC-related errors
+#[derive(Debug)]
+pub enum OidcError {
+    /// The provider could not be reached or returned an unexpected response
+    Provider(String),
+    /// The ID token failed verification
+    InvalidToken(String),
+    /// The login state is unknown or expired
+    InvalidState,
+}
+
+impl std::fmt::Display for OidcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OidcError::Provider(msg) => write!(f, "Identity provider error: {msg}"),
+            OidcError::InvalidToken(msg) => write!(f, "Invalid ID token: {msg}"),
+            OidcError::InvalidState => write!(f, "Login request expired or was already used"),
+        }
+    }
+}
+
+impl std::error::Error for OidcError {}
+
+// ==================== Configuration ====================
+
+/// OIDC settings, read from `OIDC_*` environment variables
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; discovery is fetched from `{issuer}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Confidential clients only; public clients rely on PKCE alone
+    pub client_secret: Option<String>,
+    /// Must point at `/api/auth/oidc/callback` on this server
+    pub redirect_url: String,
+    pub scopes: String,
+    /// Claim matched against local usernames
+    pub username_claim: String,
+    /// Claim holding the user's groups
+    pub groups_claim: String,
+    /// Members of this group become admins, everyone else loses admin; unset leaves `is_admin` alone
+    pub admin_group: Option<String>,
+    /// Create a local account on first login when no username matches
+    pub auto_provision: bool,
+    /// Label for the login button
+    pub provider_name: String,
+}
+
+impl OidcConfig {
+    /// Build the configuration from the environment. Returns `None` unless
+    /// `OIDC_ISSUER_URL`, `OIDC_CLIENT_ID` and `OIDC_REDIRECT_URL` are all set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+        Some(Self {
+            issuer_url: var("OIDC_ISSUER_URL")?.trim_end_matches('/').to_string(),
+            client_id: var("OIDC_CLIENT_ID")?,
+            client_secret: var("OIDC_CLIENT_SECRET"),
+            redirect_url: var("OIDC_REDIRECT_URL")?,
+            scopes: var("OIDC_SCOPES").unwrap_or_else(|| "openid profile email".to_string()),
+            username_claim: var("OIDC_USERNAME_CLAIM")
+                .unwrap_or_else(|| "preferred_username".to_string()),
+            groups_claim: var("OIDC_GROUPS_CLAIM").unwrap_or_else(|| "groups".to_string()),
+            admin_group: var("OIDC_ADMIN_GROUP"),
+            auto_provision: var("OIDC_AUTO_PROVISION")
+                .is_some_and(|v| v.eq_ignore_ascii_case("true") || v == "1"),
+            provider_name: var("OIDC_PROVIDER_NAME")
+                .unwrap_or_else(|| "Single Sign-On".to_string()),
+        })
+    }
+}
+
+// ==================== PKCE & Login State ====================
+
+/// A login started by redirecting to the provider
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+    created: Instant,
+}
+
+/// Logins waiting for the provider callback, keyed by `state`
+static PENDING_LOGINS: Mutex<Option<HashMap<String, PendingLogin>>> = Mutex::new(None);
+
+/// Completed logins waiting for the frontend to redeem them, keyed by one-time code
+static LOGIN_CODES: Mutex<Option<HashMap<String, (Uuid, Instant)>>> = Mutex::new(None);
+
+/// PKCE S256 code challenge for a verifier
+#[must_use]
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Remember a new login attempt, returning its `(state, nonce, code_challenge)`
+fn begin_login() -> (String, String, String) {
+    let state = generate_random_secret(32);
+    let nonce = generate_random_secret(32);
+    let code_verifier = generate_random_secret(64);
+    let challenge = pkce_challenge(&code_verifier);
+
+    let mut pending = PENDING_LOGINS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let pending = pending.get_or_insert_with(HashMap::new);
+    pending.retain(|_, login| login.created.elapsed() < PENDING_LOGIN_TTL);
+    pending.insert(
+        state.clone(),
+        PendingLogin {
+            code_verifier,
+            nonce: nonce.clone(),
+            created: Instant::now(),
+        },
+    );
+
+    (state, nonce, challenge)
+}
+
+/// Take a pending login by state, returning its `(code_verifier, nonce)`
+fn take_pending_login(state: &str) -> Option<(String, String)> {
+    let mut pending = PENDING_LOGINS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let login = pending.as_mut()?.remove(state)?;
+    (login.created.elapsed() < PENDING_LOGIN_TTL).then_some((login.code_verifier, login.nonce))
+}
+
+/// Issue a one-time code the frontend exchanges for a session
+#[must_use]
+pub fn issue_login_code(user_id: Uuid) -> String {
+    let code = generate_random_secret(48);
+    let mut codes = LOGIN_CODES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let codes = codes.get_or_insert_with(HashMap::new);
+    codes.retain(|_, (_, created)| created.elapsed() < LOGIN_CODE_TTL);
+    codes.insert(code.clone(), (user_id, Instant::now()));
+    code
+}
+
+/// Redeem a one-time login code, returning the user it was issued for
+#[must_use]
+pub fn redeem_login_code(code: &str) -> Option<Uuid> {
+    let mut codes = LOGIN_CODES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let (user_id, created) = codes.as_mut()?.remove(code)?;
+    (created.elapsed() < LOGIN_CODE_TTL).then_some(user_id)
+}
+
+// ==================== Provider Client ====================
+
+/// The parts of the provider's discovery document we use
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Client for one OIDC provider
+pub struct OidcClient<'a> {
+    config: &'a OidcConfig,
+    http: reqwest::Client,
+    metadata: ProviderMetadata,
+}
+
+impl<'a> OidcClient<'a> {
+    /// Fetch the provider's discovery document
+    pub async fn discover(config: &'a OidcConfig) -> Result<Self, OidcError> {
+        let http = reqwest::Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .build()
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+
+        let url = format!("{}/.well-known/openid-configuration", config.issuer_url);
+        let metadata: ProviderMetadata = http
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| OidcError::Provider(format!("discovery failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| OidcError::Provider(format!("invalid discovery document: {e}")))?;
+
+        if metadata.issuer.trim_end_matches('/') != config.issuer_url {
+            return Err(OidcError::Provider(format!(
+                "issuer mismatch: expected {}, provider reports {}",
+                config.issuer_url, metadata.issuer
+            )));
+        }
+
+        Ok(Self {
+            config,
+            http,
+            metadata,
+        })
+    }
+
+    /// Start a login and build the provider URL to redirect the browser to,
+    /// returned with the login's `state`
+    pub fn authorization_url(&self) -> Result<(String, String), OidcError> {
+        let (state, nonce, challenge) = begin_login();
+        let mut url = reqwest::Url::parse(&self.metadata.authorization_endpoint)
+            .map_err(|e| OidcError::Provider(format!("invalid authorization endpoint: {e}")))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("scope", &self.config.scopes)
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+        Ok((url.into(), state))
+    }
+
+    /// Exchange the callback's authorization code and return the verified ID token claims
+    pub async fn complete_login(
+        &self,
+        code: &str,
+        state: &str,
+    ) -> Result<Map<String, Value>, OidcError> {
+        let (code_verifier, nonce) = take_pending_login(state).ok_or(OidcError::InvalidState)?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier.as_str()),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let tokens: TokenResponse = self
+            .http
+            .post(&self.metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| OidcError::Provider(format!("token exchange failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| OidcError::Provider(format!("invalid token response: {e}")))?;
+
+        self.verify_id_token(&tokens.id_token, &nonce).await
+    }
+
+    /// Verify an ID token's signature, issuer, audience, expiry and nonce
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<Map<String, Value>, OidcError> {
+        let header = decode_header(id_token).map_err(|e| OidcError::InvalidToken(e.to_string()))?;
+
+        let key = match header.alg {
+            // Symmetric signatures are keyed with the client secret (OIDC Core 10.1)
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = self.config.client_secret.as_ref().ok_or_else(|| {
+                    OidcError::InvalidToken("HMAC-signed token without a client secret".into())
+                })?;
+                DecodingKey::from_secret(secret.as_bytes())
+            },
+            _ => self.find_signing_key(header.kid.as_deref()).await?,
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|e| OidcError::InvalidToken(e.to_string()))?
+            .claims;
+
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            return Err(OidcError::InvalidToken("nonce mismatch".into()));
+        }
+
+        Ok(claims)
+    }
+
+    /// Look up the provider key that signed a token
+    async fn find_signing_key(&self, kid: Option<&str>) -> Result<DecodingKey, OidcError> {
+        let jwks: JwkSet = self
+            .http
+            .get(&self.metadata.jwks_uri)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| OidcError::Provider(format!("failed to fetch signing keys: {e}")))?
+            .json()
+            .await
+            .map_err(|e| OidcError::Provider(format!("invalid signing keys: {e}")))?;
+
+        let jwk = match kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .ok_or_else(|| OidcError::InvalidToken("no matching signing key".into()))?;
+
+        DecodingKey::from_jwk(jwk).map_err(|e| OidcError::InvalidToken(e.to_string()))
+    }
+}
+
+// ==================== Claim Helpers ====================
+
+/// A string claim, if present
+#[must_use]
+pub fn claim_str<'c>(claims: &'c Map<String, Value>, name: &str) -> Option<&'c str> {
+    claims
+        .get(name)
+        .and_then(Value::as_str)
+        .filter(|v| !v.is_empty())
+}
+
+/// A group-list claim; accepts either an array of strings or a single string
+#[must_use]
+pub fn claim_groups(claims: &Map<String, Value>, name: &str) -> Vec<String> {
+    match claims.get(name) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(group)) => vec![group.clone()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge_matches_rfc7636_example() {
+        // RFC 7636 Appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_claim_groups_accepts_array_or_string() {
+        let claims: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "groups": ["family", "admins"],
+            "role": "admins",
+        }))
+        .unwrap();
+        assert_eq!(claim_groups(&claims, "groups"), vec!["family", "admins"]);
+        assert_eq!(claim_groups(&claims, "role"), vec!["admins"]);
+        assert!(claim_groups(&claims, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_login_code_is_single_use() {
+        let user_id = Uuid::new_v4();
+        let code = issue_login_code(user_id);
+        assert_eq!(redeem_login_code(&code), Some(user_id));
+        assert_eq!(redeem_login_code(&code), None);
+    }
+}
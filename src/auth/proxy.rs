@@ -0,0 +1,332 @@
+//! Trusted reverse-proxy authentication
+//!
+//! Lets an authenticating proxy (Authelia, Authentik outpost, oauth2-proxy, ...)
+//! vouch for users with headers such as `Remote-User` and `Remote-Groups`.
+//! The headers are only honoured on connections coming directly from one of the
+//! configured trusted proxy addresses.
+
+use actix_web::HttpRequest;
+use std::env;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IP network in CIDR notation, e.g. `172.18.0.0/16` or `fd00::/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Whether the address falls inside this network
+    #[must_use]
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        // Treat IPv4-mapped IPv6 peers (::ffff:a.b.c.d) as IPv4
+        let addr = match addr {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+            IpAddr::V4(_) => addr,
+        };
+        match (self.network, addr) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(addr) & mask
+            },
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(addr) & mask
+            },
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid IP address in '{s}'"))?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("Invalid prefix length in '{s}'"))?,
+            None => max_len,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// Proxy authentication settings, read from `PROXY_AUTH_*` environment variables
+#[derive(Debug, Clone)]
+pub struct ProxyAuthConfig {
+    /// Addresses allowed to set the identity headers
+    pub trusted_proxies: Vec<IpCidr>,
+    /// Header carrying the username
+    pub user_header: String,
+    /// Header carrying a comma-separated group list
+    pub groups_header: String,
+    /// Header carrying the display name
+    pub name_header: String,
+    /// Members of this group become admins, everyone else loses admin; unset leaves `is_admin` alone
+    pub admin_group: Option<String>,
+    /// Create a local account on first request when no username matches
+    pub auto_provision: bool,
+}
+
+impl ProxyAuthConfig {
+    /// Build the configuration from the environment. Returns `Ok(None)` unless
+    /// `PROXY_AUTH_TRUSTED_PROXIES` is set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let var = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+        let Some(trusted) = var("PROXY_AUTH_TRUSTED_PROXIES") else {
+            return Ok(None);
+        };
+        let trusted_proxies = trusted
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<IpCidr>, String>>()?;
+
+        Ok(Some(Self {
+            trusted_proxies,
+            user_header: var("PROXY_AUTH_USER_HEADER").unwrap_or_else(|| "Remote-User".to_string()),
+            groups_header: var("PROXY_AUTH_GROUPS_HEADER")
+                .unwrap_or_else(|| "Remote-Groups".to_string()),
+            name_header: var("PROXY_AUTH_NAME_HEADER").unwrap_or_else(|| "Remote-Name".to_string()),
+            admin_group: var("PROXY_AUTH_ADMIN_GROUP"),
+            auto_provision: var("PROXY_AUTH_AUTO_PROVISION")
+                .is_none_or(|v| v.eq_ignore_ascii_case("true") || v == "1"),
+        }))
+    }
+
+    /// Whether the address is one of the trusted proxies
+    #[must_use]
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|cidr| cidr.contains(addr))
+    }
+}
+
+/// Identity asserted by the proxy for one request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyIdentity {
+    pub username: String,
+    pub full_name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+/// Why proxy identity headers were not accepted
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProxyAuthError {
+    /// The identity header came from a peer that is not a trusted proxy
+    UntrustedPeer(Option<IpAddr>),
+}
+
+impl std::fmt::Display for ProxyAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyAuthError::UntrustedPeer(Some(addr)) => {
+                write!(
+                    f,
+                    "Proxy authentication header sent by untrusted peer {addr}"
+                )
+            },
+            ProxyAuthError::UntrustedPeer(None) => {
+                write!(f, "Proxy authentication header sent by unknown peer")
+            },
+        }
+    }
+}
+
+impl std::error::Error for ProxyAuthError {}
+
+/// Read the proxy identity headers. Returns `Ok(None)` when the request has no
+/// user header and an error when a peer that isn't a trusted proxy sent one.
+pub fn identity_from_request(
+    config: &ProxyAuthConfig,
+    req: &HttpRequest,
+) -> Result<Option<ProxyIdentity>, ProxyAuthError> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let Some(username) = header(&config.user_header) else {
+        return Ok(None);
+    };
+
+    // Use the TCP peer, never X-Forwarded-For, which the client controls
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    if !peer.is_some_and(|addr| config.is_trusted(addr)) {
+        return Err(ProxyAuthError::UntrustedPeer(peer));
+    }
+
+    Ok(Some(ProxyIdentity {
+        username: username.to_string(),
+        full_name: header(&config.name_header).map(str::to_string),
+        groups: header(&config.groups_header)
+            .map(|groups| {
+                groups
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|g| !g.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }))
+}
+
+/// Address of the client behind a request, for sessions and the audit log
+///
+/// This is the TCP peer, unless the peer is a trusted proxy: then the
+/// `X-Forwarded-For` chain is walked from the right, skipping trusted proxies,
+/// so entries a client prepends itself are never used.
+#[must_use]
+pub fn client_ip(config: Option<&ProxyAuthConfig>, req: &HttpRequest) -> Option<IpAddr> {
+    let peer = req.peer_addr().map(|addr| addr.ip())?;
+    let Some(config) = config.filter(|config| config.is_trusted(peer)) else {
+        return Some(peer);
+    };
+
+    let forwarded: Vec<IpAddr> = req
+        .headers()
+        .get_all("X-Forwarded-For")
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|entry| entry.trim().parse().ok())
+        .collect();
+    Some(
+        forwarded
+            .iter()
+            .rev()
+            .find(|addr| !config.is_trusted(**addr))
+            .or_else(|| forwarded.first())
+            .copied()
+            .unwrap_or(peer),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn config() -> ProxyAuthConfig {
+        ProxyAuthConfig {
+            trusted_proxies: vec!["172.18.0.0/16".parse().unwrap(), "::1".parse().unwrap()],
+            user_header: "Remote-User".to_string(),
+            groups_header: "Remote-Groups".to_string(),
+            name_header: "Remote-Name".to_string(),
+            admin_group: Some("admins".to_string()),
+            auto_provision: true,
+        }
+    }
+
+    #[test]
+    fn test_cidr_matching() {
+        let v4: IpCidr = "10.1.0.0/16".parse().unwrap();
+        assert!(v4.contains("10.1.255.3".parse().unwrap()));
+        assert!(!v4.contains("10.2.0.1".parse().unwrap()));
+        assert!(v4.contains("::ffff:10.1.0.9".parse().unwrap()));
+
+        let host: IpCidr = "192.168.1.10".parse().unwrap();
+        assert!(host.contains("192.168.1.10".parse().unwrap()));
+        assert!(!host.contains("192.168.1.11".parse().unwrap()));
+
+        let any: IpCidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("8.8.8.8".parse().unwrap()));
+
+        let v6: IpCidr = "fd00::/8".parse().unwrap();
+        assert!(v6.contains("fd12:3456::1".parse().unwrap()));
+        assert!(!v6.contains("fe80::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("not-an-ip/8".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn test_identity_only_from_trusted_peer() {
+        let config = config();
+        let request = |peer: &str| {
+            TestRequest::default()
+                .peer_addr(peer.parse().unwrap())
+                .insert_header(("Remote-User", "alice"))
+                .insert_header(("Remote-Name", "Alice Smith"))
+                .insert_header(("Remote-Groups", "family, admins"))
+                .to_http_request()
+        };
+
+        let identity = identity_from_request(&config, &request("172.18.0.5:40000"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.username, "alice");
+        assert_eq!(identity.full_name.as_deref(), Some("Alice Smith"));
+        assert_eq!(identity.groups, vec!["family", "admins"]);
+
+        assert!(identity_from_request(&config, &request("[::1]:40000")).is_ok());
+        assert_eq!(
+            identity_from_request(&config, &request("203.0.113.7:40000")),
+            Err(ProxyAuthError::UntrustedPeer(Some(
+                "203.0.113.7".parse().unwrap()
+            )))
+        );
+
+        // Requests without the header are left to the other login methods
+        let plain = TestRequest::default()
+            .peer_addr("203.0.113.7:40000".parse().unwrap())
+            .to_http_request();
+        assert_eq!(identity_from_request(&config, &plain), Ok(None));
+    }
+
+    #[test]
+    fn test_client_ip_only_trusts_forwarded_header_from_proxies() {
+        let config = config();
+        let request = |peer: &str, forwarded: &str| {
+            TestRequest::default()
+                .peer_addr(peer.parse().unwrap())
+                .insert_header(("X-Forwarded-For", forwarded))
+                .to_http_request()
+        };
+        let ip = |addr: &str| Some(addr.parse::<IpAddr>().unwrap());
+
+        // Anyone else gets their own address recorded, whatever they claim
+        let direct = request("203.0.113.7:40000", "198.51.100.1");
+        assert_eq!(client_ip(Some(&config), &direct), ip("203.0.113.7"));
+        assert_eq!(client_ip(None, &direct), ip("203.0.113.7"));
+
+        // Behind a trusted proxy, the address the proxy saw is used
+        let proxied = request("172.18.0.5:40000", "198.51.100.1");
+        assert_eq!(client_ip(Some(&config), &proxied), ip("198.51.100.1"));
+        assert_eq!(client_ip(None, &proxied), ip("172.18.0.5"));
+
+        // Entries the client prepended itself are skipped
+        let spoofed = request("172.18.0.5:40000", "10.9.9.9, 198.51.100.1, 172.18.0.9");
+        assert_eq!(client_ip(Some(&config), &spoofed), ip("198.51.100.1"));
+
+        // Without a forwarded header the proxy's own address is all there is
+        let bare = TestRequest::default()
+            .peer_addr("172.18.0.5:40000".parse().unwrap())
+            .to_http_request();
+        assert_eq!(client_ip(Some(&config), &bare), ip("172.18.0.5"));
+    }
+}
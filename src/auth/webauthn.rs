@@ -0,0 +1,556 @@
+//! `WebAuthn` (passkey) module
+//!
+//! Implements the relying-party side of the `WebAuthn` registration and
+//! authentication ceremonies: challenge bookkeeping, client data and
+//! authenticator data checks, COSE public key parsing, and assertion signature
+//! verification (ES256, `EdDSA` and RS256). Attestation statements are not
+//! verified; registrations request `"none"` attestation.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ciborium::value::Value;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::io::Cursor;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::models::{AuthenticationCredential, RegistrationCredential};
+
+/// How long the browser has to complete a ceremony
+pub const CEREMONY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// COSE algorithm identifiers we can verify, in order of preference
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [COSE_ALG_ES256, COSE_ALG_EDDSA, COSE_ALG_RS256];
+
+const COSE_ALG_ES256: i64 = -7;
+const COSE_ALG_EDDSA: i64 = -8;
+const COSE_ALG_RS256: i64 = -257;
+
+/// Authenticator data flags
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// WebAuthn-related errors
+#[derive(Debug, PartialEq, Eq)]
+pub enum WebauthnError {
+    /// The browser's response is malformed
+    InvalidResponse(String),
+    /// The challenge is unknown, expired or belongs to another ceremony
+    InvalidChallenge,
+    /// The response doesn't check out (origin, RP ID, flags or signature)
+    VerificationFailed(String),
+    /// The signature counter went backwards, suggesting a cloned authenticator
+    CounterRegression,
+}
+
+impl std::fmt::Display for WebauthnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebauthnError::InvalidResponse(msg) => write!(f, "Invalid passkey response: {msg}"),
+            WebauthnError::InvalidChallenge => {
+                write!(f, "Passkey request expired or was already used")
+            },
+            WebauthnError::VerificationFailed(msg) => {
+                write!(f, "Passkey verification failed: {msg}")
+            },
+            WebauthnError::CounterRegression => {
+                write!(f, "Passkey signature counter went backwards")
+            },
+        }
+    }
+}
+
+impl std::error::Error for WebauthnError {}
+
+// ==================== Configuration ====================
+
+/// Relying party settings, read from `WEBAUTHN_*` environment variables
+#[derive(Debug, Clone)]
+pub struct WebauthnConfig {
+    /// Domain the credentials are scoped to, e.g. `inventory.example.com`
+    pub rp_id: String,
+    /// Name shown by the browser during registration
+    pub rp_name: String,
+    /// Exact origin the web app is served from, e.g. `https://inventory.example.com`
+    pub origin: String,
+}
+
+impl WebauthnConfig {
+    /// Build the configuration from the environment. Returns `None` unless
+    /// `WEBAUTHN_ORIGIN` is set; the RP ID defaults to the origin's host.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+        let origin = var("WEBAUTHN_ORIGIN")?.trim_end_matches('/').to_string();
+        let rp_id = var("WEBAUTHN_RP_ID").or_else(|| origin_host(&origin))?;
+        Some(Self {
+            rp_id,
+            rp_name: var("WEBAUTHN_RP_NAME").unwrap_or_else(|| "Home Registry".to_string()),
+            origin,
+        })
+    }
+}
+
+/// Host part of an origin such as `https://example.com:8443`
+fn origin_host(origin: &str) -> Option<String> {
+    let (_, rest) = origin.split_once("://")?;
+    let host = rest.split(['/', ':']).next()?;
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+// ==================== Ceremony State ====================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CeremonyKind {
+    Registration,
+    Authentication,
+}
+
+/// A ceremony waiting for the browser's response
+struct PendingCeremony {
+    kind: CeremonyKind,
+    user_id: Option<Uuid>,
+    created: Instant,
+}
+
+/// Ceremonies in progress, keyed by their base64url challenge
+static CEREMONIES: Mutex<Option<HashMap<String, PendingCeremony>>> = Mutex::new(None);
+
+fn start_ceremony(kind: CeremonyKind, user_id: Option<Uuid>) -> String {
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+    let challenge = URL_SAFE_NO_PAD.encode(bytes);
+
+    let mut ceremonies = CEREMONIES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let ceremonies = ceremonies.get_or_insert_with(HashMap::new);
+    ceremonies.retain(|_, pending| pending.created.elapsed() < CEREMONY_TIMEOUT);
+    ceremonies.insert(
+        challenge.clone(),
+        PendingCeremony {
+            kind,
+            user_id,
+            created: Instant::now(),
+        },
+    );
+    challenge
+}
+
+/// Consume a challenge, returning the user the ceremony was started for
+fn take_ceremony(challenge: &str, kind: CeremonyKind) -> Result<Option<Uuid>, WebauthnError> {
+    let mut ceremonies = CEREMONIES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let pending = ceremonies
+        .as_mut()
+        .and_then(|ceremonies| ceremonies.remove(challenge))
+        .ok_or(WebauthnError::InvalidChallenge)?;
+    if pending.kind != kind || pending.created.elapsed() >= CEREMONY_TIMEOUT {
+        return Err(WebauthnError::InvalidChallenge);
+    }
+    Ok(pending.user_id)
+}
+
+/// Start registering a credential for a user; returns the challenge
+#[must_use]
+pub fn start_registration(user_id: Uuid) -> String {
+    start_ceremony(CeremonyKind::Registration, Some(user_id))
+}
+
+/// Start an authentication ceremony, optionally bound to a user (second factor);
+/// returns the challenge
+#[must_use]
+pub fn start_authentication(user_id: Option<Uuid>) -> String {
+    start_ceremony(CeremonyKind::Authentication, user_id)
+}
+
+// ==================== Encoding Helpers ====================
+
+/// Decode base64url, tolerating padding
+pub fn decode_base64url(value: &str) -> Result<Vec<u8>, WebauthnError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| WebauthnError::InvalidResponse("bad base64url value".to_string()))
+}
+
+#[must_use]
+pub fn encode_base64url(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// The `WebAuthn` user handle for an account (its UUID bytes)
+#[must_use]
+pub fn user_handle(user_id: Uuid) -> String {
+    encode_base64url(user_id.as_bytes())
+}
+
+// ==================== Client & Authenticator Data ====================
+
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Check `clientDataJSON` and consume its challenge
+fn verify_client_data(
+    config: &WebauthnConfig,
+    client_data_json: &[u8],
+    kind: CeremonyKind,
+) -> Result<Option<Uuid>, WebauthnError> {
+    let client_data: CollectedClientData = serde_json::from_slice(client_data_json)
+        .map_err(|e| WebauthnError::InvalidResponse(format!("client data: {e}")))?;
+
+    let expected_type = match kind {
+        CeremonyKind::Registration => "webauthn.create",
+        CeremonyKind::Authentication => "webauthn.get",
+    };
+    if client_data.kind != expected_type {
+        return Err(WebauthnError::VerificationFailed(format!(
+            "unexpected ceremony type '{}'",
+            client_data.kind
+        )));
+    }
+
+    // Consume the challenge before any further checks so it can't be retried
+    let user_id = take_ceremony(&client_data.challenge, kind)?;
+
+    if client_data.origin != config.origin {
+        return Err(WebauthnError::VerificationFailed(format!(
+            "unexpected origin '{}'",
+            client_data.origin
+        )));
+    }
+    Ok(user_id)
+}
+
+/// Parsed authenticator data
+struct AuthenticatorData {
+    rp_id_hash: [u8; 32],
+    flags: u8,
+    sign_count: u32,
+    /// Credential ID and COSE public key, present during registration
+    attested_credential: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+fn parse_authenticator_data(bytes: &[u8]) -> Result<AuthenticatorData, WebauthnError> {
+    let invalid = || WebauthnError::InvalidResponse("authenticator data too short".to_string());
+    if bytes.len() < 37 {
+        return Err(invalid());
+    }
+
+    let mut rp_id_hash = [0u8; 32];
+    rp_id_hash.copy_from_slice(&bytes[..32]);
+    let flags = bytes[32];
+    let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+    let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL_DATA == 0 {
+        None
+    } else {
+        // AAGUID (16 bytes), credential ID length (2 bytes), credential ID, COSE key
+        let rest = bytes.get(37 + 16..).ok_or_else(invalid)?;
+        let id_len = usize::from(u16::from_be_bytes([
+            *rest.first().ok_or_else(invalid)?,
+            *rest.get(1).ok_or_else(invalid)?,
+        ]));
+        let credential_id = rest.get(2..2 + id_len).ok_or_else(invalid)?.to_vec();
+        let key_bytes = &rest[2 + id_len..];
+
+        // The COSE key is followed by optional extension data; keep only the key
+        let mut cursor = Cursor::new(key_bytes);
+        let _: Value = ciborium::de::from_reader(&mut cursor)
+            .map_err(|e| WebauthnError::InvalidResponse(format!("credential public key: {e}")))?;
+        let key_len = usize::try_from(cursor.position()).map_err(|_| invalid())?;
+        Some((credential_id, key_bytes[..key_len].to_vec()))
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags,
+        sign_count,
+        attested_credential,
+    })
+}
+
+fn check_rp_and_flags(
+    config: &WebauthnConfig,
+    auth_data: &AuthenticatorData,
+    require_user_verification: bool,
+) -> Result<(), WebauthnError> {
+    if auth_data.rp_id_hash[..] != Sha256::digest(config.rp_id.as_bytes())[..] {
+        return Err(WebauthnError::VerificationFailed(
+            "credential belongs to another site".to_string(),
+        ));
+    }
+    if auth_data.flags & FLAG_USER_PRESENT == 0 {
+        return Err(WebauthnError::VerificationFailed(
+            "user presence was not confirmed".to_string(),
+        ));
+    }
+    if require_user_verification && auth_data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err(WebauthnError::VerificationFailed(
+            "user verification (PIN or biometrics) is required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ==================== COSE Keys & Signatures ====================
+
+/// A credential public key
+enum CoseKey {
+    Es256 { x: Vec<u8>, y: Vec<u8> },
+    Ed25519 { x: Vec<u8> },
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+fn parse_cose_key(bytes: &[u8]) -> Result<CoseKey, WebauthnError> {
+    let invalid = |msg: &str| WebauthnError::InvalidResponse(format!("public key: {msg}"));
+    let value: Value = ciborium::de::from_reader(bytes).map_err(|e| invalid(&e.to_string()))?;
+    let Value::Map(entries) = value else {
+        return Err(invalid("not a map"));
+    };
+
+    let field = |label: i64| {
+        entries.iter().find_map(|(key, value)| match key {
+            Value::Integer(key) if i128::from(*key) == i128::from(label) => Some(value),
+            _ => None,
+        })
+    };
+    let int_field = |label: i64| -> Option<i64> {
+        match field(label)? {
+            Value::Integer(value) => i64::try_from(i128::from(*value)).ok(),
+            _ => None,
+        }
+    };
+    let bytes_field = |label: i64| -> Result<Vec<u8>, WebauthnError> {
+        match field(label) {
+            Some(Value::Bytes(bytes)) => Ok(bytes.clone()),
+            _ => Err(invalid(&format!("missing parameter {label}"))),
+        }
+    };
+
+    // 1 = kty, 3 = alg, -1 = crv (EC2/OKP) or n (RSA), -2 = x or e, -3 = y
+    match (int_field(1), int_field(3)) {
+        (Some(2), Some(COSE_ALG_ES256)) if int_field(-1) == Some(1) => Ok(CoseKey::Es256 {
+            x: bytes_field(-2)?,
+            y: bytes_field(-3)?,
+        }),
+        (Some(1), Some(COSE_ALG_EDDSA)) if int_field(-1) == Some(6) => Ok(CoseKey::Ed25519 {
+            x: bytes_field(-2)?,
+        }),
+        (Some(3), Some(COSE_ALG_RS256)) => Ok(CoseKey::Rs256 {
+            n: bytes_field(-1)?,
+            e: bytes_field(-2)?,
+        }),
+        (kty, alg) => Err(invalid(&format!(
+            "unsupported key type {kty:?} / algorithm {alg:?}"
+        ))),
+    }
+}
+
+fn verify_signature(public_key: &[u8], message: &[u8], sig: &[u8]) -> Result<(), WebauthnError> {
+    let result = match parse_cose_key(public_key)? {
+        CoseKey::Es256 { x, y } => {
+            let mut point = Vec::with_capacity(65);
+            point.push(0x04);
+            point.extend_from_slice(&x);
+            point.extend_from_slice(&y);
+            UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point).verify(message, sig)
+        },
+        CoseKey::Ed25519 { x } => {
+            UnparsedPublicKey::new(&signature::ED25519, x).verify(message, sig)
+        },
+        CoseKey::Rs256 { n, e } => RsaPublicKeyComponents { n, e }.verify(
+            &signature::RSA_PKCS1_2048_8192_SHA256,
+            message,
+            sig,
+        ),
+    };
+    result.map_err(|_| WebauthnError::VerificationFailed("invalid signature".to_string()))
+}
+
+// ==================== Registration ====================
+
+/// A newly registered credential, ready to be stored
+#[derive(Debug)]
+pub struct VerifiedRegistration {
+    pub user_id: Uuid,
+    pub credential_id: Vec<u8>,
+    /// COSE-encoded public key
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+    pub transports: Vec<String>,
+}
+
+/// Verify the browser's response to a registration ceremony
+pub fn finish_registration(
+    config: &WebauthnConfig,
+    credential: &RegistrationCredential,
+) -> Result<VerifiedRegistration, WebauthnError> {
+    let client_data_json = decode_base64url(&credential.response.client_data_json)?;
+    let user_id = verify_client_data(config, &client_data_json, CeremonyKind::Registration)?
+        .ok_or(WebauthnError::InvalidChallenge)?;
+
+    let attestation_object = decode_base64url(&credential.response.attestation_object)?;
+    let attestation: Value = ciborium::de::from_reader(attestation_object.as_slice())
+        .map_err(|e| WebauthnError::InvalidResponse(format!("attestation object: {e}")))?;
+    let auth_data_bytes = match &attestation {
+        Value::Map(entries) => entries.iter().find_map(|(key, value)| match (key, value) {
+            (Value::Text(key), Value::Bytes(bytes)) if key == "authData" => Some(bytes),
+            _ => None,
+        }),
+        _ => None,
+    }
+    .ok_or_else(|| WebauthnError::InvalidResponse("attestation has no authData".to_string()))?;
+
+    let auth_data = parse_authenticator_data(auth_data_bytes)?;
+    check_rp_and_flags(config, &auth_data, false)?;
+    let Some((credential_id, public_key)) = auth_data.attested_credential else {
+        return Err(WebauthnError::InvalidResponse(
+            "no attested credential data".to_string(),
+        ));
+    };
+    if decode_base64url(&credential.id)? != credential_id {
+        return Err(WebauthnError::InvalidResponse(
+            "credential ID mismatch".to_string(),
+        ));
+    }
+
+    // Refuse keys we couldn't verify assertions for later
+    parse_cose_key(&public_key)?;
+
+    Ok(VerifiedRegistration {
+        user_id,
+        credential_id,
+        public_key,
+        sign_count: auth_data.sign_count,
+        transports: credential.response.transports.clone(),
+    })
+}
+
+// ==================== Authentication ====================
+
+/// An authentication response whose client data checked out, waiting for the
+/// stored credential to verify its signature against
+#[derive(Debug)]
+pub struct PendingAssertion {
+    /// User the ceremony was started for (second-factor verification)
+    pub ceremony_user_id: Option<Uuid>,
+    pub credential_id: Vec<u8>,
+    /// User handle reported by the authenticator (passwordless login)
+    pub user_handle: Option<Vec<u8>>,
+    client_data_json: Vec<u8>,
+    authenticator_data: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+/// Decode an authentication response, check its client data and consume the challenge
+pub fn start_assertion_check(
+    config: &WebauthnConfig,
+    credential: &AuthenticationCredential,
+) -> Result<PendingAssertion, WebauthnError> {
+    let response = &credential.response;
+    let client_data_json = decode_base64url(&response.client_data_json)?;
+    let ceremony_user_id =
+        verify_client_data(config, &client_data_json, CeremonyKind::Authentication)?;
+
+    Ok(PendingAssertion {
+        ceremony_user_id,
+        credential_id: decode_base64url(&credential.id)?,
+        user_handle: response
+            .user_handle
+            .as_deref()
+            .filter(|handle| !handle.is_empty())
+            .map(decode_base64url)
+            .transpose()?,
+        client_data_json,
+        authenticator_data: decode_base64url(&response.authenticator_data)?,
+        signature: decode_base64url(&response.signature)?,
+    })
+}
+
+impl PendingAssertion {
+    /// Verify the signature with the stored public key and return the new
+    /// signature counter
+    pub fn verify(
+        &self,
+        config: &WebauthnConfig,
+        public_key: &[u8],
+        stored_sign_count: u32,
+        require_user_verification: bool,
+    ) -> Result<u32, WebauthnError> {
+        let auth_data = parse_authenticator_data(&self.authenticator_data)?;
+        check_rp_and_flags(config, &auth_data, require_user_verification)?;
+
+        let mut signed = self.authenticator_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&self.client_data_json));
+        verify_signature(public_key, &signed, &self.signature)?;
+
+        // Authenticators without a counter always report 0
+        if (auth_data.sign_count != 0 || stored_sign_count != 0)
+            && auth_data.sign_count <= stored_sign_count
+        {
+            return Err(WebauthnError::CounterRegression);
+        }
+        Ok(auth_data.sign_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_host() {
+        assert_eq!(
+            origin_host("https://inventory.example.com").as_deref(),
+            Some("inventory.example.com")
+        );
+        assert_eq!(
+            origin_host("http://localhost:8210").as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(origin_host("localhost"), None);
+    }
+
+    #[test]
+    fn test_challenges_are_single_use_and_typed() {
+        let user_id = Uuid::new_v4();
+        let challenge = start_registration(user_id);
+        assert_eq!(
+            take_ceremony(&challenge, CeremonyKind::Authentication),
+            Err(WebauthnError::InvalidChallenge)
+        );
+
+        let challenge = start_registration(user_id);
+        assert_eq!(
+            take_ceremony(&challenge, CeremonyKind::Registration),
+            Ok(Some(user_id))
+        );
+        assert_eq!(
+            take_ceremony(&challenge, CeremonyKind::Registration),
+            Err(WebauthnError::InvalidChallenge)
+        );
+    }
+
+    #[test]
+    fn test_parse_authenticator_data_rejects_truncated_input() {
+        assert!(parse_authenticator_data(&[0u8; 36]).is_err());
+
+        // Attested credential flag set but no credential data
+        let mut bytes = vec![0u8; 37];
+        bytes[32] = FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL_DATA;
+        assert!(parse_authenticator_data(&bytes).is_err());
+    }
+}
@@ -0,0 +1,519 @@
+//! Command-line admin subcommands
+//!
+//! The server binary doubles as an admin tool for operations that must work
+//! without the HTTP stack, e.g. recovering a locked-out admin account:
+//!
+//! ```text
+//! home-registry migrate
+//! home-registry create-admin <username> [<full name>]
+//! home-registry reset-password <username>
+//! home-registry disable-totp <username>
+//! home-registry unlock-user <username>
+//! home-registry backup create|list|restore <file>
+//! home-registry export-csv [--inventory <id>] [<output file>]
+//! ```
+//!
+//! Passwords are prompted for without echo on a terminal, or read from the
+//! first line of standard input when it is piped. Account changes and restores
+//! are written to the audit log with `cli` as the actor.
+
+use deadpool_postgres::Pool;
+use std::fmt;
+use std::io::{BufRead, IsTerminal, Write};
+use uuid::Uuid;
+
+use crate::api::{audit, backup, format_items_as_csv};
+use crate::auth::{hash_password, lockout, validate_password, validate_username};
+use crate::db::DatabaseService;
+use crate::models::{AuditAction, AuditOutcome, ItemViewer, NewAuditEvent};
+
+/// Usage text printed for `help` and invalid invocations
+pub const USAGE: &str = "\
+Usage: home-registry [COMMAND]
+
+Without a command the HTTP server is started.
+
+Commands:
+  serve                               Start the HTTP server (default)
+  migrate                             Apply pending database migrations and exit
+  create-admin <username> [<name>]    Create an admin account (prompts for a password)
+  reset-password <username>           Set a new password (prompts for the password)
+  disable-totp <username>             Turn off two-factor authentication for a user
+  unlock-user <username>              Clear a login lockout after failed password attempts
+  backup create                       Create a backup in the backups directory
+  backup list                         List local and off-site backups
+  backup restore <file>               Restore a backup (a safety backup is created first)
+  export-csv [--inventory <id>] [<file>]
+                                      Export items as CSV to a file or standard output
+  help                                Show this message";
+
+/// A parsed command-line invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    Help,
+    Migrate,
+    CreateAdmin {
+        username: String,
+        full_name: Option<String>,
+    },
+    ResetPassword {
+        username: String,
+    },
+    DisableTotp {
+        username: String,
+    },
+    UnlockUser {
+        username: String,
+    },
+    BackupCreate,
+    BackupList,
+    BackupRestore {
+        filename: String,
+    },
+    ExportCsv {
+        inventory_id: Option<i32>,
+        output: Option<String>,
+    },
+}
+
+/// An invalid command line
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+impl Command {
+    /// Parse the arguments following the program name
+    pub fn parse<I>(args: I) -> Result<Self, UsageError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let args: Vec<String> = args.into_iter().collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        let command = match args.as_slice() {
+            [] | ["serve"] => Self::Serve,
+            ["help" | "--help" | "-h"] => Self::Help,
+            ["migrate"] => Self::Migrate,
+            ["create-admin", username, full_name @ ..] => Self::CreateAdmin {
+                username: (*username).to_string(),
+                full_name: (!full_name.is_empty()).then(|| full_name.join(" ")),
+            },
+            ["reset-password", username] => Self::ResetPassword {
+                username: (*username).to_string(),
+            },
+            ["disable-totp", username] => Self::DisableTotp {
+                username: (*username).to_string(),
+            },
+            ["unlock-user", username] => Self::UnlockUser {
+                username: (*username).to_string(),
+            },
+            ["backup", "create"] => Self::BackupCreate,
+            ["backup", "list"] => Self::BackupList,
+            ["backup", "restore", filename] => Self::BackupRestore {
+                filename: (*filename).to_string(),
+            },
+            ["export-csv", rest @ ..] => parse_export_csv(rest)?,
+            _ => {
+                return Err(UsageError(format!(
+                    "Unknown or incomplete command '{}'",
+                    args.join(" ")
+                )))
+            },
+        };
+        Ok(command)
+    }
+}
+
+fn parse_export_csv(args: &[&str]) -> Result<Command, UsageError> {
+    let mut inventory_id = None;
+    let mut output = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if *arg == "--inventory" {
+            let value = args
+                .next()
+                .ok_or_else(|| UsageError("--inventory requires an inventory id".to_string()))?;
+            inventory_id = Some(
+                value
+                    .parse::<i32>()
+                    .map_err(|_| UsageError(format!("Invalid inventory id '{value}'")))?,
+            );
+        } else if output.is_none() && !arg.starts_with('-') {
+            output = Some((*arg).to_string());
+        } else {
+            return Err(UsageError(format!("Unexpected argument '{arg}'")));
+        }
+    }
+
+    Ok(Command::ExportCsv {
+        inventory_id,
+        output,
+    })
+}
+
+/// Run an admin subcommand against the database
+///
+/// `Serve`, `Help` and `Migrate` are handled by the binary itself.
+pub async fn run(command: Command, pool: &Pool) -> Result<(), Box<dyn std::error::Error>> {
+    let db_service = DatabaseService::new(pool.clone());
+
+    match command {
+        Command::Serve | Command::Help | Command::Migrate => {
+            Err("This command is handled by the server binary".into())
+        },
+        Command::CreateAdmin {
+            username,
+            full_name,
+        } => {
+            let result = create_admin(&db_service, &username, full_name.as_deref()).await;
+            let event =
+                NewAuditEvent::new(AuditAction::UserCreated).details(format!("Admin '{username}'"));
+            audited(&db_service, event, result).await
+        },
+        Command::ResetPassword { username } => {
+            let result = reset_password(&db_service, &username).await;
+            let event =
+                NewAuditEvent::new(AuditAction::PasswordReset).details(format!("'{username}'"));
+            audited(&db_service, event, result).await
+        },
+        Command::DisableTotp { username } => {
+            let result = disable_totp(&db_service, &username).await;
+            let event =
+                NewAuditEvent::new(AuditAction::TotpDisabled).details(format!("'{username}'"));
+            audited(&db_service, event, result).await
+        },
+        Command::UnlockUser { username } => {
+            let result = unlock_user(&db_service, &username).await;
+            let event =
+                NewAuditEvent::new(AuditAction::UserUnlocked).details(format!("'{username}'"));
+            audited(&db_service, event, result).await
+        },
+        Command::BackupCreate => {
+            let info = backup::create_backup_file(&db_service, "home_registry_backup").await?;
+            println!("Created backup {} ({})", info.name, info.size);
+            if let Some(e) = info.replication_error {
+                eprintln!("Warning: off-site replication failed: {e}");
+            }
+            Ok(())
+        },
+        Command::BackupList => backup_list().await,
+        Command::BackupRestore { filename } => {
+            let result = backup_restore(&db_service, &filename).await;
+            let event = NewAuditEvent::new(AuditAction::BackupRestored)
+                .target("backup", &filename)
+                .details("Full restore");
+            audited(&db_service, event, result.map(|()| None)).await
+        },
+        Command::ExportCsv {
+            inventory_id,
+            output,
+        } => export_csv(&db_service, inventory_id, output.as_deref()).await,
+    }
+}
+
+/// Record a subcommand in the audit log and pass its result through
+///
+/// A successful run is recorded against the user it acted on, when there is
+/// one; a failed run is recorded with the error as its details.
+async fn audited(
+    db_service: &DatabaseService,
+    event: NewAuditEvent,
+    result: Result<Option<Uuid>, Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = match &result {
+        Ok(Some(user_id)) => event.target("user", user_id),
+        Ok(None) => event,
+        Err(e) => {
+            let details = match &event.details {
+                Some(details) => format!("{details}: {e}"),
+                None => e.to_string(),
+            };
+            event.outcome(AuditOutcome::Failure).details(details)
+        },
+    };
+    audit::record_cli(db_service, event).await;
+    result.map(|_| ())
+}
+
+/// Prompt for a password, without echo when attached to a terminal
+fn read_password(prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if std::io::stdin().is_terminal() {
+        return Ok(rpassword::prompt_password(prompt)?);
+    }
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Prompt for a new password (twice on a terminal) and validate it
+fn read_new_password() -> Result<String, Box<dyn std::error::Error>> {
+    let password = read_password("New password: ")?;
+    validate_password(&password)?;
+
+    if std::io::stdin().is_terminal() && read_password("Confirm password: ")? != password {
+        return Err("Passwords do not match".into());
+    }
+    Ok(password)
+}
+
+async fn create_admin(
+    db_service: &DatabaseService,
+    username: &str,
+    full_name: Option<&str>,
+) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+    validate_username(username)?;
+    if db_service.get_user_by_username(username).await?.is_some() {
+        return Err(format!("User '{username}' already exists").into());
+    }
+
+    let password = read_new_password()?;
+    let password_hash = hash_password(password)
+        .await
+        .map_err(|e| format!("Failed to hash password: {e}"))?;
+
+    let user = db_service
+        .create_user(
+            username,
+            full_name.unwrap_or(username),
+            &password_hash,
+            true, // is_admin
+            true, // is_active
+        )
+        .await?;
+
+    if let Err(e) = db_service.create_user_settings(user.id).await {
+        log::warn!("Failed to create user settings: {}", e);
+    }
+
+    println!("Created admin user '{}'", user.username);
+    Ok(Some(user.id))
+}
+
+async fn reset_password(
+    db_service: &DatabaseService,
+    username: &str,
+) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+    let user = db_service
+        .get_user_by_username(username)
+        .await?
+        .ok_or_else(|| format!("User '{username}' not found"))?;
+
+    let password = read_new_password()?;
+    let password_hash = hash_password(password)
+        .await
+        .map_err(|e| format!("Failed to hash password: {e}"))?;
+    db_service
+        .update_user_password(user.id, &password_hash)
+        .await?;
+
+    println!(
+        "Password reset for user '{}'; existing sessions were signed out",
+        user.username
+    );
+    if !user.is_active {
+        println!("Note: the account is deactivated and still cannot log in");
+    }
+    Ok(Some(user.id))
+}
+
+async fn disable_totp(
+    db_service: &DatabaseService,
+    username: &str,
+) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+    let user = db_service
+        .get_user_by_username(username)
+        .await?
+        .ok_or_else(|| format!("User '{username}' not found"))?;
+
+    if db_service.delete_totp_settings(user.id).await? {
+        println!(
+            "Two-factor authentication disabled for user '{}'",
+            user.username
+        );
+    } else {
+        println!(
+            "Two-factor authentication was not set up for user '{}'",
+            user.username
+        );
+    }
+    Ok(Some(user.id))
+}
+
+async fn unlock_user(
+    db_service: &DatabaseService,
+    username: &str,
+) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+    // Failed attempts are tracked by name, so this works for any username
+    if db_service
+        .clear_failed_logins(&lockout::attempt_key(username))
+        .await?
+    {
+        println!("Login lockout cleared for '{username}'");
+    } else {
+        println!("'{username}' has no failed login attempts");
+    }
+
+    let user = db_service.get_user_by_username(username).await?;
+    Ok(user.map(|user| user.id))
+}
+
+async fn backup_list() -> Result<(), Box<dyn std::error::Error>> {
+    let (backups, remote_error) = backup::list_backup_files().await?;
+
+    if let Some(e) = remote_error {
+        eprintln!("Warning: off-site storage unavailable: {e}");
+    }
+    if backups.is_empty() {
+        println!("No backups found");
+        return Ok(());
+    }
+
+    for info in backups {
+        let location = match (info.local, info.remote) {
+            (true, true) => "local+remote",
+            (false, true) => "remote",
+            _ => "local",
+        };
+        println!("{}\t{}\t{}\t{}", info.name, info.date, info.size, location);
+    }
+    Ok(())
+}
+
+async fn backup_restore(
+    db_service: &DatabaseService,
+    filename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Verify before touching anything, then take a safety backup like the API does
+    let backup_data = backup::load_stored_backup(filename).await?;
+
+    let auto_backup = backup::create_backup_file(db_service, "home_registry_auto_pre_restore")
+        .await
+        .map_err(|e| format!("Failed to create safety backup, restore aborted: {e}"))?;
+    println!("Created pre-restore backup {}", auto_backup.name);
+
+    db_service
+        .import_all_data(&backup_data.data)
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to restore backup: {e}. Your pre-restore backup is available: {}",
+                auto_backup.name
+            )
+        })?;
+
+    println!("Restored backup {filename}");
+    Ok(())
+}
+
+async fn export_csv(
+    db_service: &DatabaseService,
+    inventory_id: Option<i32>,
+    output: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let items = match inventory_id {
+        Some(id) => {
+            if db_service.get_inventory_by_id(id).await?.is_none() {
+                return Err(format!("Inventory {id} not found").into());
+            }
+            db_service
+                .get_items_by_inventory(id, ItemViewer::Everything)
+                .await?
+        },
+        None => db_service.get_all_items(ItemViewer::Everything).await?,
+    };
+    let count = items.len();
+    let inventory_names = db_service.get_all_inventory_names().await?;
+    let csv_data = format_items_as_csv(items, &inventory_names)?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &csv_data)?;
+            println!("Exported {count} items to {path}");
+        },
+        None => std::io::stdout().write_all(&csv_data)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, UsageError> {
+        Command::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn test_parse_defaults_to_serve() {
+        assert_eq!(parse(&[]), Ok(Command::Serve));
+        assert_eq!(parse(&["serve"]), Ok(Command::Serve));
+    }
+
+    #[test]
+    fn test_parse_account_commands() {
+        assert_eq!(
+            parse(&["create-admin", "alice", "Alice", "Smith"]),
+            Ok(Command::CreateAdmin {
+                username: "alice".to_string(),
+                full_name: Some("Alice Smith".to_string()),
+            })
+        );
+        assert_eq!(
+            parse(&["reset-password", "alice"]),
+            Ok(Command::ResetPassword {
+                username: "alice".to_string()
+            })
+        );
+        assert!(parse(&["reset-password"]).is_err());
+        assert!(parse(&["disable-totp", "alice", "bob"]).is_err());
+        assert_eq!(
+            parse(&["unlock-user", "alice"]),
+            Ok(Command::UnlockUser {
+                username: "alice".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_backup_commands() {
+        assert_eq!(parse(&["backup", "list"]), Ok(Command::BackupList));
+        assert_eq!(
+            parse(&["backup", "restore", "home_registry_backup_x.json"]),
+            Ok(Command::BackupRestore {
+                filename: "home_registry_backup_x.json".to_string()
+            })
+        );
+        assert!(parse(&["backup"]).is_err());
+        assert!(parse(&["backup", "restore"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_csv() {
+        assert_eq!(
+            parse(&["export-csv"]),
+            Ok(Command::ExportCsv {
+                inventory_id: None,
+                output: None
+            })
+        );
+        assert_eq!(
+            parse(&["export-csv", "--inventory", "3", "items.csv"]),
+            Ok(Command::ExportCsv {
+                inventory_id: Some(3),
+                output: Some("items.csv".to_string())
+            })
+        );
+        assert!(parse(&["export-csv", "--inventory", "abc"]).is_err());
+        assert!(parse(&["export-csv", "a.csv", "b.csv"]).is_err());
+    }
+}
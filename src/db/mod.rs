@@ -1,26 +1,61 @@
 use crate::models::{
+    AccessGrantLevel,
     AdminUpdateUserRequest,
+    // API token models
+    ApiToken,
+    // Audit log models
+    AuditEvent,
+    AuditEventQuery,
     // Backup & Restore models
     BackupDatabaseContent,
+    BackupTableCount,
+    Capability,
+    CreateApiTokenRequest,
+    CreateGroupRequest,
     CreateInventoryRequest,
+    CreateInventoryRoleRequest,
+    CreateInvitationRequest,
     CreateItemRequest,
     CreateOrganizerOptionRequest,
     CreateOrganizerTypeRequest,
+    CreateShareLinkRequest,
     EffectivePermissions,
+    GroupMember,
+    GroupRole,
+    IdentitySource,
     Inventory,
+    InventoryGroupShare,
+    InventoryRole,
     InventoryShare,
     InventoryShareWithUser,
+    Invitation,
     Item,
+    ItemFieldChange,
+    ItemHistoryAction,
     ItemOrganizerValue,
     ItemOrganizerValueWithDetails,
+    ItemRevision,
+    ItemViewer,
+    ItemVisibility,
+    NewAuditEvent,
     OrganizerOption,
     OrganizerType,
     OrganizerTypeWithOptions,
+    PendingInventoryShare,
     PermissionLevel,
     PermissionSource,
+    RestoredInventory,
+    SelectiveRestoreSummary,
     SetItemOrganizerValueRequest,
+    ShareLink,
+    ShareRole,
+    ShareStatus,
     // TOTP models
     TotpSettings,
+    TrashEntry,
+    TrashKind,
+    UpdateGroupRequest,
+    UpdateInventoryRoleRequest,
     UpdateItemRequest,
     UpdateOrganizerOptionRequest,
     UpdateOrganizerTypeRequest,
@@ -30,12 +65,20 @@ use crate::models::{
     // User Access Grant models (All Access tier)
     UserAccessGrant,
     UserAccessGrantWithUsers,
+    UserGroup,
+    UserIdentityLink,
     UserResponse,
+    // Session models
+    UserSession,
     UserSettings,
+    // WebAuthn models
+    WebauthnCredential,
+    WebauthnCredentialKey,
 };
 use chrono::{DateTime, Utc};
 use deadpool_postgres::{Config, ManagerConfig, Pool, RecyclingMethod};
-use log::{error, info};
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
 use std::env;
 use tokio_postgres::NoTls;
 use uuid::Uuid;
@@ -48,6 +91,53 @@ fn escape_like_pattern(input: &str) -> String {
         .replace('_', "\\_")
 }
 
+/// SQL condition on an `inventory_shares` row: the share's role includes exporting.
+/// Built-in roles all do; custom roles only when given the `export` capability.
+const EXPORTABLE_SHARE_SQL: &str =
+    "(role_id IS NULL OR role_id IN (SELECT id FROM inventory_roles WHERE 'export' = ANY(capabilities)))";
+
+/// Same as [`EXPORTABLE_SHARE_SQL`] for an `inventory_group_shares` row aliased `gs`
+const EXPORTABLE_GROUP_SHARE_SQL: &str =
+    "(gs.role_id IS NULL OR gs.role_id IN (SELECT id FROM inventory_roles WHERE 'export' = ANY(capabilities)))";
+
+/// `AND updated_at = $param` when an update may only apply to the version the
+/// client last saw (from `If-Match`), otherwise nothing
+fn expected_version_sql(expected: Option<&DateTime<Utc>>, param: i32) -> String {
+    expected.map_or_else(String::new, |_| format!(" AND updated_at = ${param}"))
+}
+
+/// SQL condition limiting the item row aliased `item` to what `viewer` may see.
+/// Trashed items are left out for everyone.
+/// For `ItemViewer::User` the user id has to be bound to parameter `user_param`.
+fn item_visibility_sql(viewer: ItemViewer, item: &str, user_param: &str) -> String {
+    match viewer {
+        ItemViewer::Everything => format!("{item}.deleted_at IS NULL"),
+        ItemViewer::Public => {
+            format!("{item}.deleted_at IS NULL AND {item}.visibility = 'everyone'")
+        },
+        // Owners and full All Access see every item; anyone else needs an active share,
+        // group share or grant that ranks at least as high as the item's visibility
+        ItemViewer::User(_) => format!(
+            "{item}.deleted_at IS NULL AND EXISTS (
+                SELECT 1 FROM inventories vi
+                WHERE vi.id = {item}.inventory_id AND (
+                    vi.user_id = {user_param}
+                    OR vi.user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = {user_param} AND permission_level = 'full' AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                    OR (CASE {item}.visibility WHEN 'edit_items' THEN 1 WHEN 'edit_inventory' THEN 2 WHEN 'owner' THEN 3 ELSE 0 END) <= (
+                        SELECT MAX(CASE levels.level WHEN 'edit_items' THEN 1 WHEN 'edit_inventory' THEN 2 ELSE 0 END) FROM (
+                            SELECT permission_level FROM inventory_shares WHERE inventory_id = vi.id AND shared_with_user_id = {user_param} AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted'
+                            UNION ALL
+                            SELECT gs.permission_level FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gs.inventory_id = vi.id AND gm.user_id = {user_param} AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW())
+                            UNION ALL
+                            SELECT permission_level FROM user_access_grants WHERE grantor_user_id = vi.user_id AND grantee_user_id = {user_param} AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted'
+                        ) levels(level)
+                    )
+                )
+            )"
+        ),
+    }
+}
+
 pub fn get_pool() -> Result<Pool, Box<dyn std::error::Error + Send + Sync>> {
     let db_url =
         env::var("DATABASE_URL").map_err(|_| "DATABASE_URL environment variable must be set")?;
@@ -102,16 +192,22 @@ impl DatabaseService {
         Self { pool }
     }
 
-    pub async fn get_all_items(&self) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
+    pub async fn get_all_items(
+        &self,
+        viewer: ItemViewer,
+    ) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let rows = client
-            .query(
-                "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at 
-             FROM items ORDER BY created_at DESC",
-                &[],
-            )
-            .await?;
+        let sql = format!(
+            "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility 
+             FROM items WHERE {visible} ORDER BY created_at DESC",
+            visible = item_visibility_sql(viewer, "items", "$1")
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
+        if let ItemViewer::User(ref user_id) = viewer {
+            params.push(user_id);
+        }
+        let rows = client.query(&sql, &params).await?;
 
         let mut items = Vec::new();
         for row in rows {
@@ -129,6 +225,10 @@ impl DatabaseService {
                 quantity: row.get(10),
                 created_at: row.get::<_, Option<DateTime<Utc>>>(11),
                 updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
             };
             items.push(item);
         }
@@ -145,8 +245,8 @@ impl DatabaseService {
 
         let rows = client
             .query(
-                "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at 
-             FROM items WHERE id = $1",
+                "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility 
+             FROM items WHERE id = $1 AND deleted_at IS NULL",
                 &[&id],
             )
             .await?;
@@ -166,6 +266,10 @@ impl DatabaseService {
                 quantity: row.get(10),
                 created_at: row.get::<_, Option<DateTime<Utc>>>(11),
                 updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
             };
             Ok(Some(item))
         } else {
@@ -199,7 +303,7 @@ impl DatabaseService {
             .query_one(
                 "INSERT INTO items (inventory_id, name, description, category, location, purchase_date, purchase_price, warranty_expiry, notes, quantity) 
              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
-             RETURNING id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at",
+             RETURNING id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility",
                 &[
                     &request.inventory_id.unwrap_or(1),
                     &request.name,
@@ -229,6 +333,10 @@ impl DatabaseService {
             quantity: row.get(10),
             created_at: row.get::<_, Option<DateTime<Utc>>>(11),
             updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+            visibility: row
+                .get::<_, String>(13)
+                .parse()
+                .unwrap_or(ItemVisibility::Owner),
         };
 
         info!("Created new item: {} (ID: {:?})", item.name, item.id);
@@ -239,6 +347,7 @@ impl DatabaseService {
         &self,
         id: i32,
         request: UpdateItemRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Option<Item>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
@@ -321,9 +430,13 @@ impl DatabaseService {
 
         fields.push("updated_at = NOW()".to_string());
         values.push(&id);
+        let expected_version = expected_version_sql(expected_updated_at.as_ref(), param_count + 1);
+        if let Some(ref expected) = expected_updated_at {
+            values.push(expected);
+        }
 
         let query = format!(
-            "UPDATE items SET {} WHERE id = ${} RETURNING id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at",
+            "UPDATE items SET {} WHERE id = ${}{expected_version} AND deleted_at IS NULL RETURNING id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility",
             fields.join(", "),
             param_count
         );
@@ -345,6 +458,10 @@ impl DatabaseService {
                 quantity: row.get(10),
                 created_at: row.get::<_, Option<DateTime<Utc>>>(11),
                 updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
             };
             info!("Updated item ID: {}", id);
             Ok(Some(item))
@@ -353,38 +470,77 @@ impl DatabaseService {
         }
     }
 
-    pub async fn delete_item(&self, id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+    /// Change who can see an item in a shared inventory
+    pub async fn update_item_visibility(
+        &self,
+        id: i32,
+        visibility: ItemVisibility,
+    ) -> Result<Option<Item>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                "UPDATE items SET visibility = $1, updated_at = NOW() WHERE id = $2 AND deleted_at IS NULL",
+                &[&visibility.to_string(), &id],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Ok(None);
+        }
+        info!("Set visibility of item ID {} to {}", id, visibility);
+        self.get_item_by_id(id).await
+    }
+
+    /// Move an item to the trash
+    pub async fn delete_item(
+        &self,
+        id: i32,
+        deleted_by: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows_affected = client
-            .execute("DELETE FROM items WHERE id = $1", &[&id])
+            .execute(
+                "UPDATE items SET deleted_at = NOW(), deleted_by_user_id = $2, trashed_with_inventory = FALSE
+                 WHERE id = $1 AND deleted_at IS NULL",
+                &[&id, &deleted_by],
+            )
             .await?;
 
         let deleted = rows_affected > 0;
         if deleted {
-            info!("Deleted item ID: {}", id);
+            info!("Moved item ID {} to the trash", id);
         }
         Ok(deleted)
     }
 
-    pub async fn search_items(&self, query: &str) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
+    pub async fn search_items(
+        &self,
+        query: &str,
+        viewer: ItemViewer,
+    ) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         // Escape SQL LIKE wildcards to prevent pattern injection
         let escaped_query = escape_like_pattern(&query.to_lowercase());
         let search_pattern = format!("%{escaped_query}%");
-        let rows = client
-            .query(
-                "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at 
+        let sql = format!(
+            "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility 
              FROM items 
-             WHERE LOWER(name) LIKE $1 ESCAPE '\\' 
+             WHERE (LOWER(name) LIKE $1 ESCAPE '\\' 
                 OR LOWER(description) LIKE $1 ESCAPE '\\' 
                 OR LOWER(category) LIKE $1 ESCAPE '\\' 
-                OR LOWER(location) LIKE $1 ESCAPE '\\'
+                OR LOWER(location) LIKE $1 ESCAPE '\\')
+               AND {visible}
              ORDER BY created_at DESC",
-                &[&search_pattern],
-            )
-            .await?;
+            visible = item_visibility_sql(viewer, "items", "$2")
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![&search_pattern];
+        if let ItemViewer::User(ref user_id) = viewer {
+            params.push(user_id);
+        }
+        let rows = client.query(&sql, &params).await?;
 
         let mut items = Vec::new();
         for row in rows {
@@ -402,6 +558,10 @@ impl DatabaseService {
                 quantity: row.get(10),
                 created_at: row.get::<_, Option<DateTime<Utc>>>(11),
                 updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
             };
             items.push(item);
         }
@@ -424,7 +584,7 @@ impl DatabaseService {
         let rows = client
             .query(
                 "SELECT id, name, description, location, image_url, user_id, created_at, updated_at 
-                 FROM inventories WHERE id = $1",
+                 FROM inventories WHERE id = $1 AND deleted_at IS NULL",
                 &[&id],
             )
             .await?;
@@ -446,6 +606,22 @@ impl DatabaseService {
         }
     }
 
+    /// Names of all inventories keyed by id (used for exports)
+    pub async fn get_all_inventory_names(
+        &self,
+    ) -> Result<HashMap<i32, String>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, name FROM inventories WHERE deleted_at IS NULL",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
     pub async fn create_inventory(
         &self,
         request: CreateInventoryRequest,
@@ -484,6 +660,7 @@ impl DatabaseService {
         &self,
         id: i32,
         request: crate::models::UpdateInventoryRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Option<Inventory>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
@@ -519,9 +696,13 @@ impl DatabaseService {
 
         fields.push("updated_at = NOW()".to_string());
         values.push(&id);
+        let expected_version = expected_version_sql(expected_updated_at.as_ref(), param_count + 1);
+        if let Some(ref expected) = expected_updated_at {
+            values.push(expected);
+        }
 
         let query = format!(
-            "UPDATE inventories SET {} WHERE id = ${} RETURNING id, name, description, location, image_url, user_id, created_at, updated_at",
+            "UPDATE inventories SET {} WHERE id = ${}{expected_version} AND deleted_at IS NULL RETURNING id, name, description, location, image_url, user_id, created_at, updated_at",
             fields.join(", "),
             param_count
         );
@@ -546,36 +727,65 @@ impl DatabaseService {
         }
     }
 
-    pub async fn delete_inventory(&self, id: i32) -> Result<bool, Box<dyn std::error::Error>> {
-        let client = self.pool.get().await?;
+    /// Move an inventory to the trash, together with its live items and organizer
+    /// types. Those are marked as trashed with the inventory so restoring it brings
+    /// back exactly them.
+    pub async fn delete_inventory(
+        &self,
+        id: i32,
+        deleted_by: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
 
-        let rows_affected = client
-            .execute("DELETE FROM inventories WHERE id = $1", &[&id])
+        let rows_affected = transaction
+            .execute(
+                "UPDATE inventories SET deleted_at = NOW(), deleted_by_user_id = $2
+                 WHERE id = $1 AND deleted_at IS NULL",
+                &[&id, &deleted_by],
+            )
             .await?;
+        if rows_affected == 0 {
+            return Ok(false);
+        }
 
-        let deleted = rows_affected > 0;
-        if deleted {
-            info!(
-                "Deleted inventory ID: {} (CASCADE: organizers and items)",
-                id
-            );
+        for table in ["items", "organizer_types"] {
+            transaction
+                .execute(
+                    &format!(
+                        "UPDATE {table} SET deleted_at = NOW(), deleted_by_user_id = $2, trashed_with_inventory = TRUE
+                         WHERE inventory_id = $1 AND deleted_at IS NULL"
+                    ),
+                    &[&id, &deleted_by],
+                )
+                .await?;
         }
-        Ok(deleted)
+        transaction.commit().await?;
+
+        info!(
+            "Moved inventory ID {} to the trash (with its organizers and items)",
+            id
+        );
+        Ok(true)
     }
 
     pub async fn get_items_by_inventory(
         &self,
         inventory_id: i32,
+        viewer: ItemViewer,
     ) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let rows = client
-            .query(
-                "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at 
-                 FROM items WHERE inventory_id = $1 ORDER BY created_at DESC",
-                &[&inventory_id],
-            )
-            .await?;
+        let sql = format!(
+            "SELECT id, inventory_id, name, description, category, location, purchase_date::text, purchase_price::float8, warranty_expiry::text, notes, quantity, created_at, updated_at, visibility 
+             FROM items WHERE inventory_id = $1 AND {visible} ORDER BY created_at DESC",
+            visible = item_visibility_sql(viewer, "items", "$2")
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![&inventory_id];
+        if let ItemViewer::User(ref user_id) = viewer {
+            params.push(user_id);
+        }
+        let rows = client.query(&sql, &params).await?;
 
         let mut items = Vec::new();
         for row in rows {
@@ -593,6 +803,10 @@ impl DatabaseService {
                 quantity: row.get(10),
                 created_at: row.get::<_, Option<DateTime<Utc>>>(11),
                 updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
             };
             items.push(item);
         }
@@ -616,7 +830,7 @@ impl DatabaseService {
         let rows = client
             .query(
                 "SELECT id, inventory_id, name, input_type, is_required, display_order, created_at, updated_at 
-                 FROM organizer_types WHERE inventory_id = $1 ORDER BY display_order ASC, name ASC",
+                 FROM organizer_types WHERE inventory_id = $1 AND deleted_at IS NULL ORDER BY display_order ASC, name ASC",
                 &[&inventory_id],
             )
             .await?;
@@ -681,7 +895,7 @@ impl DatabaseService {
         let rows = client
             .query(
                 "SELECT id, inventory_id, name, input_type, is_required, display_order, created_at, updated_at 
-                 FROM organizer_types WHERE id = $1",
+                 FROM organizer_types WHERE id = $1 AND deleted_at IS NULL",
                 &[&id],
             )
             .await?;
@@ -744,6 +958,7 @@ impl DatabaseService {
         &self,
         id: i32,
         request: UpdateOrganizerTypeRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Option<OrganizerType>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
@@ -778,9 +993,13 @@ impl DatabaseService {
 
         fields.push("updated_at = NOW()".to_string());
         values.push(&id);
+        let expected_version = expected_version_sql(expected_updated_at.as_ref(), param_count + 1);
+        if let Some(ref expected) = expected_updated_at {
+            values.push(expected);
+        }
 
         let query = format!(
-            "UPDATE organizer_types SET {} WHERE id = ${} 
+            "UPDATE organizer_types SET {} WHERE id = ${}{expected_version} AND deleted_at IS NULL
              RETURNING id, inventory_id, name, input_type, is_required, display_order, created_at, updated_at",
             fields.join(", "),
             param_count
@@ -806,16 +1025,26 @@ impl DatabaseService {
         }
     }
 
-    pub async fn delete_organizer_type(&self, id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+    /// Move an organizer type to the trash. Its options and item values are kept so
+    /// they come back on restore, but are hidden while the type is trashed.
+    pub async fn delete_organizer_type(
+        &self,
+        id: i32,
+        deleted_by: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows_affected = client
-            .execute("DELETE FROM organizer_types WHERE id = $1", &[&id])
+            .execute(
+                "UPDATE organizer_types SET deleted_at = NOW(), deleted_by_user_id = $2, trashed_with_inventory = FALSE
+                 WHERE id = $1 AND deleted_at IS NULL",
+                &[&id, &deleted_by],
+            )
             .await?;
 
         let deleted = rows_affected > 0;
         if deleted {
-            info!("Deleted organizer type ID: {}", id);
+            info!("Moved organizer type ID {} to the trash", id);
         }
         Ok(deleted)
     }
@@ -923,6 +1152,7 @@ impl DatabaseService {
         &self,
         id: i32,
         request: UpdateOrganizerOptionRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Option<OrganizerOption>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
@@ -947,9 +1177,13 @@ impl DatabaseService {
 
         fields.push("updated_at = NOW()".to_string());
         values.push(&id);
+        let expected_version = expected_version_sql(expected_updated_at.as_ref(), param_count + 1);
+        if let Some(ref expected) = expected_updated_at {
+            values.push(expected);
+        }
 
         let query = format!(
-            "UPDATE organizer_options SET {} WHERE id = ${} 
+            "UPDATE organizer_options SET {} WHERE id = ${}{expected_version}
              RETURNING id, organizer_type_id, name, display_order, created_at, updated_at",
             fields.join(", "),
             param_count
@@ -1011,7 +1245,7 @@ impl DatabaseService {
                  FROM item_organizer_values iov
                  JOIN organizer_types ot ON iov.organizer_type_id = ot.id
                  LEFT JOIN organizer_options oo ON iov.organizer_option_id = oo.id
-                 WHERE iov.item_id = $1
+                 WHERE iov.item_id = $1 AND ot.deleted_at IS NULL
                  ORDER BY ot.display_order ASC, ot.name ASC",
                 &[&item_id],
             )
@@ -1039,6 +1273,56 @@ impl DatabaseService {
         Ok(values)
     }
 
+    /// Get organizer values for every item in an inventory, keyed by item ID
+    pub async fn get_item_organizer_values_by_inventory(
+        &self,
+        inventory_id: i32,
+    ) -> Result<
+        std::collections::HashMap<i32, Vec<ItemOrganizerValueWithDetails>>,
+        Box<dyn std::error::Error>,
+    > {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT
+                    iov.item_id,
+                    iov.organizer_type_id,
+                    ot.name as organizer_type_name,
+                    ot.input_type,
+                    ot.is_required,
+                    COALESCE(oo.name, iov.text_value) as display_value,
+                    iov.organizer_option_id,
+                    iov.text_value
+                 FROM item_organizer_values iov
+                 JOIN organizer_types ot ON iov.organizer_type_id = ot.id
+                 LEFT JOIN organizer_options oo ON iov.organizer_option_id = oo.id
+                 WHERE ot.inventory_id = $1 AND ot.deleted_at IS NULL
+                 ORDER BY ot.display_order ASC, ot.name ASC",
+                &[&inventory_id],
+            )
+            .await?;
+
+        let mut values: std::collections::HashMap<i32, Vec<ItemOrganizerValueWithDetails>> =
+            std::collections::HashMap::new();
+        for row in rows {
+            values
+                .entry(row.get(0))
+                .or_default()
+                .push(ItemOrganizerValueWithDetails {
+                    organizer_type_id: row.get(1),
+                    organizer_type_name: row.get(2),
+                    input_type: row.get(3),
+                    is_required: row.get(4),
+                    value: row.get(5),
+                    organizer_option_id: row.get(6),
+                    text_value: row.get(7),
+                });
+        }
+
+        Ok(values)
+    }
+
     pub async fn set_item_organizer_value(
         &self,
         item_id: i32,
@@ -1133,108 +1417,463 @@ impl DatabaseService {
         Ok(rows_affected)
     }
 
-    // ==================== Item Image Operations ====================
+    // ==================== Item History Operations ====================
 
-    /// Bulk-fetch item images for an inventory.
-    /// Returns a map of `item_id` → `image_url` for all items that have an image organizer value.
-    /// This avoids N+1 queries when rendering item card thumbnails.
-    pub async fn get_item_image_urls_by_inventory(
+    /// Add the next revision to an item's history. Nothing is recorded when there
+    /// are no changes; otherwise the new revision number is returned.
+    pub async fn record_item_revision(
         &self,
-        inventory_id: i32,
-    ) -> Result<std::collections::HashMap<i32, String>, Box<dyn std::error::Error>> {
+        item_id: i32,
+        action: ItemHistoryAction,
+        reverted_to_revision: Option<i32>,
+        actor_id: Uuid,
+        actor_username: &str,
+        changes: &[ItemFieldChange],
+    ) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        // Lock the item so concurrent edits get consecutive revision numbers
+        let locked = transaction
+            .query_opt("SELECT id FROM items WHERE id = $1 FOR UPDATE", &[&item_id])
+            .await?;
+        if locked.is_none() {
+            return Ok(None);
+        }
+
+        let row = transaction
+            .query_one(
+                "INSERT INTO item_history (item_id, revision, action, reverted_to_revision,
+                                           changed_by_user_id, changed_by_username, changes)
+                 SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4, $5, $6
+                 FROM item_history WHERE item_id = $1
+                 RETURNING revision",
+                &[
+                    &item_id,
+                    &action.to_string(),
+                    &reverted_to_revision,
+                    &actor_id,
+                    &actor_username,
+                    &serde_json::to_value(changes)?,
+                ],
+            )
+            .await?;
+
+        transaction.commit().await?;
+        let revision: i32 = row.get(0);
+        info!(
+            "Recorded revision {} of item {} ({} fields)",
+            revision,
+            item_id,
+            changes.len()
+        );
+        Ok(Some(revision))
+    }
+
+    /// An item's revisions, newest first
+    pub async fn get_item_history(
+        &self,
+        item_id: i32,
+    ) -> Result<Vec<ItemRevision>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows = client
             .query(
-                "SELECT iov.item_id, iov.text_value
-                 FROM item_organizer_values iov
-                 JOIN organizer_types ot ON iov.organizer_type_id = ot.id
-                 JOIN items i ON iov.item_id = i.id
-                 WHERE ot.inventory_id = $1
-                   AND ot.input_type = 'image'
-                   AND iov.text_value IS NOT NULL
-                   AND iov.text_value != ''
-                 ORDER BY ot.display_order ASC",
-                &[&inventory_id],
+                "SELECT revision, action, reverted_to_revision, changed_by_user_id,
+                        changed_by_username, changed_at, changes
+                 FROM item_history WHERE item_id = $1
+                 ORDER BY revision DESC",
+                &[&item_id],
             )
             .await?;
 
-        let mut image_map = std::collections::HashMap::new();
+        let mut revisions = Vec::with_capacity(rows.len());
         for row in rows {
-            let item_id: i32 = row.get(0);
-            let image_url: String = row.get(1);
-            // First image organizer wins (by display_order)
-            image_map.entry(item_id).or_insert(image_url);
+            revisions.push(ItemRevision {
+                revision: row.get(0),
+                action: row
+                    .get::<_, String>(1)
+                    .parse()
+                    .unwrap_or(ItemHistoryAction::Updated),
+                reverted_to_revision: row.get(2),
+                changed_by_user_id: row.get(3),
+                changed_by_username: row.get(4),
+                changed_at: row.get(5),
+                changes: serde_json::from_value(row.get(6))?,
+            });
         }
+        Ok(revisions)
+    }
 
-        info!(
-            "Retrieved {} item image URLs for inventory {}",
-            image_map.len(),
-            inventory_id
+    /// Write history field values back to an item, e.g. to revert it. Unlike
+    /// `update_item` this can clear fields. Organizer values whose type or option
+    /// has been deleted since are skipped.
+    pub async fn apply_item_changes(
+        &self,
+        item_id: i32,
+        changes: &[ItemFieldChange],
+    ) -> Result<Option<Item>, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let mut fields = Vec::new();
+        let mut values: Vec<Box<dyn tokio_postgres::types::ToSql + Sync + Send>> = Vec::new();
+        for change in changes {
+            let value = &change.new_value;
+            let as_i32 = || value.as_i64().and_then(|v| i32::try_from(v).ok());
+            let as_date = || {
+                value
+                    .as_str()
+                    .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            };
+            // Column names come from this match, never from the request
+            let (column, param): (&str, Box<dyn tokio_postgres::types::ToSql + Sync + Send>) =
+                match change.field.as_str() {
+                    "inventory_id" => match as_i32() {
+                        Some(inventory_id) => ("inventory_id", Box::new(inventory_id)),
+                        None => continue,
+                    },
+                    "name" => match value.as_str() {
+                        Some(name) => ("name", Box::new(name.to_string())),
+                        None => continue,
+                    },
+                    "description" => ("description", Box::new(value.as_str().map(str::to_string))),
+                    "category" => ("category", Box::new(value.as_str().map(str::to_string))),
+                    "location" => ("location", Box::new(value.as_str().map(str::to_string))),
+                    "notes" => ("notes", Box::new(value.as_str().map(str::to_string))),
+                    "purchase_date" => ("purchase_date", Box::new(as_date())),
+                    "warranty_expiry" => ("warranty_expiry", Box::new(as_date())),
+                    "purchase_price" => ("purchase_price", Box::new(value.as_f64())),
+                    "quantity" => ("quantity", Box::new(as_i32())),
+                    _ => continue,
+                };
+            values.push(param);
+            fields.push(format!("{column} = ${}", values.len()));
+        }
+
+        fields.push("updated_at = NOW()".to_string());
+        values.push(Box::new(item_id));
+        let query = format!(
+            "UPDATE items SET {} WHERE id = ${} AND deleted_at IS NULL",
+            fields.join(", "),
+            values.len()
         );
-        Ok(image_map)
-    }
+        let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = values
+            .iter()
+            .map(|v| v.as_ref() as &(dyn tokio_postgres::types::ToSql + Sync))
+            .collect();
+        if transaction.execute(&query, &params).await? == 0 {
+            return Ok(None);
+        }
 
-    // ==================== User Operations ====================
+        for change in changes {
+            let Some(type_id) = change.organizer_type_id() else {
+                continue;
+            };
+            if change.new_value.is_null() {
+                transaction
+                    .execute(
+                        "DELETE FROM item_organizer_values WHERE item_id = $1 AND organizer_type_id = $2",
+                        &[&item_id, &type_id],
+                    )
+                    .await?;
+                continue;
+            }
+            let option_id = change.new_value["organizer_option_id"]
+                .as_i64()
+                .and_then(|v| i32::try_from(v).ok());
+            let text_value = change.new_value["text_value"].as_str();
+            transaction
+                .execute(
+                    "INSERT INTO item_organizer_values (item_id, organizer_type_id, organizer_option_id, text_value)
+                     SELECT $1, $2, $3, $4
+                     WHERE EXISTS (SELECT 1 FROM organizer_types WHERE id = $2 AND deleted_at IS NULL)
+                       AND ($3::INTEGER IS NULL OR EXISTS (SELECT 1 FROM organizer_options WHERE id = $3))
+                     ON CONFLICT (item_id, organizer_type_id)
+                     DO UPDATE SET organizer_option_id = $3, text_value = $4, updated_at = NOW()",
+                    &[&item_id, &type_id, &option_id, &text_value],
+                )
+                .await?;
+        }
 
-    /// Get user count for setup status check
-    pub async fn get_user_count(&self) -> Result<i64, Box<dyn std::error::Error>> {
-        let client = self.pool.get().await?;
-        let row = client.query_one("SELECT COUNT(*) FROM users", &[]).await?;
-        Ok(row.get(0))
+        transaction.commit().await?;
+        info!(
+            "Applied {} history changes to item {}",
+            changes.len(),
+            item_id
+        );
+        self.get_item_by_id(item_id).await
     }
 
-    /// Get a user by ID
-    pub async fn get_user_by_id(
+    // ==================== Trash Operations ====================
+
+    /// The trash of a user, newest first: trashed inventories they own, and items
+    /// and organizer types trashed on their own from live inventories they own or
+    /// that they deleted themselves
+    pub async fn get_trash(
         &self,
-        id: Uuid,
-    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        user_id: Uuid,
+    ) -> Result<Vec<TrashEntry>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
+
         let rows = client
             .query(
-                "SELECT id, username, full_name, password_hash, is_admin, is_active, created_at, updated_at,
-                        recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false)
-                 FROM users WHERE id = $1",
-                &[&id],
+                "SELECT 'inventory', i.id, i.name, NULL::int4, NULL::text,
+                        (SELECT COUNT(*) FROM items WHERE inventory_id = i.id AND trashed_with_inventory AND deleted_at IS NOT NULL),
+                        i.deleted_at, i.deleted_by_user_id, u.username
+                 FROM inventories i
+                 LEFT JOIN users u ON u.id = i.deleted_by_user_id
+                 WHERE i.deleted_at IS NOT NULL AND i.user_id = $1
+                 UNION ALL
+                 SELECT 'item', it.id, it.name, inv.id, inv.name, NULL::int8,
+                        it.deleted_at, it.deleted_by_user_id, u.username
+                 FROM items it
+                 JOIN inventories inv ON inv.id = it.inventory_id
+                 LEFT JOIN users u ON u.id = it.deleted_by_user_id
+                 WHERE it.deleted_at IS NOT NULL AND NOT it.trashed_with_inventory
+                   AND inv.deleted_at IS NULL
+                   AND (inv.user_id = $1 OR it.deleted_by_user_id = $1)
+                 UNION ALL
+                 SELECT 'organizer_type', ot.id, ot.name, inv.id, inv.name, NULL::int8,
+                        ot.deleted_at, ot.deleted_by_user_id, u.username
+                 FROM organizer_types ot
+                 JOIN inventories inv ON inv.id = ot.inventory_id
+                 LEFT JOIN users u ON u.id = ot.deleted_by_user_id
+                 WHERE ot.deleted_at IS NOT NULL AND NOT ot.trashed_with_inventory
+                   AND inv.deleted_at IS NULL
+                   AND (inv.user_id = $1 OR ot.deleted_by_user_id = $1)
+                 ORDER BY 7 DESC",
+                &[&user_id],
             )
             .await?;
 
-        if let Some(row) = rows.first() {
-            Ok(Some(User {
-                id: row.get(0),
-                username: row.get(1),
-                full_name: row.get(2),
-                password_hash: row.get(3),
-                is_admin: row.get(4),
-                is_active: row.get(5),
-                created_at: row.get(6),
-                updated_at: row.get(7),
-                recovery_codes_generated_at: row.get(8),
-                recovery_codes_confirmed: row.get(9),
-            }))
-        } else {
-            Ok(None)
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            entries.push(TrashEntry {
+                kind: row.get::<_, String>(0).parse()?,
+                id: row.get(1),
+                name: row.get(2),
+                inventory_id: row.get(3),
+                inventory_name: row.get(4),
+                item_count: row.get(5),
+                deleted_at: row.get(6),
+                deleted_by_user_id: row.get(7),
+                deleted_by_username: row.get(8),
+            });
         }
+        Ok(entries)
     }
 
-    /// Get a user by username
-    pub async fn get_user_by_username(
+    /// Bring an entry back from the trash. Restoring an inventory also restores the
+    /// items and organizer types trashed along with it.
+    pub async fn restore_from_trash(
         &self,
-        username: &str,
-    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
-        let client = self.pool.get().await?;
-        let rows = client
-            .query(
-                "SELECT id, username, full_name, password_hash, is_admin, is_active, created_at, updated_at,
-                        recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false)
-                 FROM users WHERE LOWER(username) = LOWER($1)",
-                &[&username],
+        kind: TrashKind,
+        id: i32,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let rows_affected = transaction
+            .execute(
+                &format!(
+                    "UPDATE {} SET deleted_at = NULL, deleted_by_user_id = NULL
+                     WHERE id = $1 AND deleted_at IS NOT NULL",
+                    kind.table()
+                ),
+                &[&id],
             )
             .await?;
+        if rows_affected == 0 {
+            return Ok(false);
+        }
 
-        if let Some(row) = rows.first() {
-            Ok(Some(User {
-                id: row.get(0),
+        if kind == TrashKind::Inventory {
+            for table in ["items", "organizer_types"] {
+                transaction
+                    .execute(
+                        &format!(
+                            "UPDATE {table} SET deleted_at = NULL, deleted_by_user_id = NULL, trashed_with_inventory = FALSE
+                             WHERE inventory_id = $1 AND trashed_with_inventory"
+                        ),
+                        &[&id],
+                    )
+                    .await?;
+            }
+        }
+        transaction.commit().await?;
+
+        info!("Restored {} ID {} from the trash", kind, id);
+        Ok(true)
+    }
+
+    /// Permanently delete an entry that is in the trash. Purging an inventory also
+    /// deletes everything in it.
+    pub async fn purge_from_trash(
+        &self,
+        kind: TrashKind,
+        id: i32,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                &format!(
+                    "DELETE FROM {} WHERE id = $1 AND deleted_at IS NOT NULL",
+                    kind.table()
+                ),
+                &[&id],
+            )
+            .await?;
+
+        let purged = rows_affected > 0;
+        if purged {
+            info!("Purged {} ID {} from the trash", kind, id);
+        }
+        Ok(purged)
+    }
+
+    /// Permanently delete everything that has been in the trash for more than `days`
+    /// days. Returns the number of inventories, items and organizer types purged.
+    pub async fn purge_trash_older_than(
+        &self,
+        days: i32,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let mut purged = 0;
+        // Inventories first, which takes whatever was trashed with them along
+        for kind in [
+            TrashKind::Inventory,
+            TrashKind::Item,
+            TrashKind::OrganizerType,
+        ] {
+            purged += client
+                .execute(
+                    &format!(
+                        "DELETE FROM {} WHERE deleted_at < NOW() - make_interval(days => $1)",
+                        kind.table()
+                    ),
+                    &[&days],
+                )
+                .await?;
+        }
+
+        Ok(purged)
+    }
+
+    // ==================== Item Image Operations ====================
+
+    /// Bulk-fetch item images for an inventory.
+    /// Returns a map of `item_id` → `image_url` for all items that have an image organizer value.
+    /// This avoids N+1 queries when rendering item card thumbnails.
+    pub async fn get_item_image_urls_by_inventory(
+        &self,
+        inventory_id: i32,
+        viewer: ItemViewer,
+    ) -> Result<std::collections::HashMap<i32, String>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let sql = format!(
+            "SELECT iov.item_id, iov.text_value
+             FROM item_organizer_values iov
+             JOIN organizer_types ot ON iov.organizer_type_id = ot.id
+             JOIN items i ON iov.item_id = i.id
+             WHERE ot.inventory_id = $1
+               AND ot.deleted_at IS NULL
+               AND ot.input_type = 'image'
+               AND iov.text_value IS NOT NULL
+               AND iov.text_value != ''
+               AND {visible}
+             ORDER BY ot.display_order ASC",
+            visible = item_visibility_sql(viewer, "i", "$2")
+        );
+        let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![&inventory_id];
+        if let ItemViewer::User(ref user_id) = viewer {
+            params.push(user_id);
+        }
+        let rows = client.query(&sql, &params).await?;
+
+        let mut image_map = std::collections::HashMap::new();
+        for row in rows {
+            let item_id: i32 = row.get(0);
+            let image_url: String = row.get(1);
+            // First image organizer wins (by display_order)
+            image_map.entry(item_id).or_insert(image_url);
+        }
+
+        info!(
+            "Retrieved {} item image URLs for inventory {}",
+            image_map.len(),
+            inventory_id
+        );
+        Ok(image_map)
+    }
+
+    // ==================== User Operations ====================
+
+    /// Get user count for setup status check
+    pub async fn get_user_count(&self) -> Result<i64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let row = client.query_one("SELECT COUNT(*) FROM users", &[]).await?;
+        Ok(row.get(0))
+    }
+
+    /// Get a user by ID
+    pub async fn get_user_by_id(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT id, username, full_name, password_hash, is_admin, is_active, created_at, updated_at,
+                        recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false)
+                 FROM users WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        if let Some(row) = rows.first() {
+            Ok(Some(User {
+                id: row.get(0),
+                username: row.get(1),
+                full_name: row.get(2),
+                password_hash: row.get(3),
+                is_admin: row.get(4),
+                is_active: row.get(5),
+                created_at: row.get(6),
+                updated_at: row.get(7),
+                recovery_codes_generated_at: row.get(8),
+                recovery_codes_confirmed: row.get(9),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get a user by username
+    pub async fn get_user_by_username(
+        &self,
+        username: &str,
+    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT id, username, full_name, password_hash, is_admin, is_active, created_at, updated_at,
+                        recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false)
+                 FROM users WHERE LOWER(username) = LOWER($1)",
+                &[&username],
+            )
+            .await?;
+
+        if let Some(row) = rows.first() {
+            Ok(Some(User {
+                id: row.get(0),
                 username: row.get(1),
                 full_name: row.get(2),
                 password_hash: row.get(3),
@@ -1250,6 +1889,107 @@ impl DatabaseService {
         }
     }
 
+    /// Get the user linked to an identity at an external source
+    pub async fn get_user_by_identity(
+        &self,
+        source: IdentitySource,
+        subject: &str,
+    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT u.id, u.username, u.full_name, u.password_hash, u.is_admin, u.is_active,
+                        u.created_at, u.updated_at, u.recovery_codes_generated_at,
+                        COALESCE(u.recovery_codes_confirmed, false)
+                 FROM users u
+                 JOIN user_identity_links l ON l.user_id = u.id
+                 WHERE l.source = $1 AND l.subject = $2",
+                &[&source.to_string(), &subject],
+            )
+            .await?;
+
+        Ok(rows.first().map(|row| User {
+            id: row.get(0),
+            username: row.get(1),
+            full_name: row.get(2),
+            password_hash: row.get(3),
+            is_admin: row.get(4),
+            is_active: row.get(5),
+            created_at: row.get(6),
+            updated_at: row.get(7),
+            recovery_codes_generated_at: row.get(8),
+            recovery_codes_confirmed: row.get(9),
+        }))
+    }
+
+    /// Get the external identity sources a user is linked to
+    pub async fn get_user_identity_links(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserIdentityLink>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT source, subject, created_at FROM user_identity_links
+                 WHERE user_id = $1 ORDER BY source",
+                &[&user_id],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(UserIdentityLink {
+                    source: row.get::<_, String>(0).parse()?,
+                    subject: row.get(1),
+                    created_at: row.get(2),
+                })
+            })
+            .collect()
+    }
+
+    /// Link a user to an external identity source, replacing any earlier link to it.
+    /// Without a subject the link is bound by the next login from that source.
+    pub async fn link_user_identity(
+        &self,
+        user_id: Uuid,
+        source: IdentitySource,
+        subject: Option<&str>,
+    ) -> Result<UserIdentityLink, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let row = client
+            .query_one(
+                "INSERT INTO user_identity_links (user_id, source, subject)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (user_id, source) DO UPDATE SET subject = EXCLUDED.subject
+                 RETURNING created_at",
+                &[&user_id, &source.to_string(), &subject],
+            )
+            .await?;
+
+        Ok(UserIdentityLink {
+            source,
+            subject: subject.map(str::to_string),
+            created_at: row.get(0),
+        })
+    }
+
+    /// Remove a user's link to an external identity source
+    pub async fn unlink_user_identity(
+        &self,
+        user_id: Uuid,
+        source: IdentitySource,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let rows_affected = client
+            .execute(
+                "DELETE FROM user_identity_links WHERE user_id = $1 AND source = $2",
+                &[&user_id, &source.to_string()],
+            )
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
     /// Get all users (admin only)
     pub async fn get_all_users(&self) -> Result<Vec<UserResponse>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
@@ -1435,21 +2175,51 @@ impl DatabaseService {
         }
     }
 
-    /// Update user password
+    /// Update user password and revoke all of the user's sessions
     pub async fn update_user_password(
         &self,
         id: Uuid,
         password_hash: &str,
     ) -> Result<bool, Box<dyn std::error::Error>> {
-        let client = self.pool.get().await?;
+        self.update_user_password_keeping_session(id, password_hash, None)
+            .await
+    }
 
-        let rows_affected = client
+    /// Update user password and revoke the user's sessions except `keep_session_id`
+    pub async fn update_user_password_keeping_session(
+        &self,
+        id: Uuid,
+        password_hash: &str,
+        keep_session_id: Option<Uuid>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let rows_affected = transaction
             .execute(
                 "UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2",
                 &[&password_hash, &id],
             )
             .await?;
 
+        // A password change ends every other login
+        let revoked = transaction
+            .execute(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE user_id = $1 AND revoked_at IS NULL
+                   AND ($2::uuid IS NULL OR id <> $2)",
+                &[&id, &keep_session_id],
+            )
+            .await?;
+
+        transaction.commit().await?;
+
+        if revoked > 0 {
+            info!(
+                "Revoked {} sessions for user {} after password change",
+                revoked, id
+            );
+        }
         Ok(rows_affected > 0)
     }
 
@@ -1545,11 +2315,12 @@ impl DatabaseService {
         })
     }
 
-    /// Update user settings
+    /// Update user settings, only if they are still at `expected_updated_at` when given
     pub async fn update_user_settings(
         &self,
         user_id: Uuid,
         request: UpdateUserSettingsRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Option<UserSettings>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
@@ -1599,9 +2370,13 @@ impl DatabaseService {
 
         fields.push("updated_at = NOW()".to_string());
         values.push(&user_id);
+        let expected_version = expected_version_sql(expected_updated_at.as_ref(), param_count + 1);
+        if let Some(ref expected) = expected_updated_at {
+            values.push(expected);
+        }
 
         let query = format!(
-            "UPDATE user_settings SET {} WHERE user_id = ${} 
+            "UPDATE user_settings SET {} WHERE user_id = ${}{expected_version}
              RETURNING id, user_id, theme, default_inventory_id, items_per_page, date_format, 
                        currency, notifications_enabled, settings_json, created_at, updated_at",
             fields.join(", "),
@@ -1643,36 +2418,72 @@ impl DatabaseService {
 
     // ==================== Inventory Sharing Operations ====================
 
-    /// Share an inventory with a user
+    /// Invite a user to an inventory, optionally only between `starts_at` and `expires_at`;
+    /// the share stays pending until the user accepts it
     pub async fn create_inventory_share(
         &self,
         inventory_id: i32,
         shared_with_user_id: Uuid,
         shared_by_user_id: Uuid,
-        permission_level: PermissionLevel,
+        role: impl Into<ShareRole>,
+        starts_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<InventoryShare, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let permission_str = permission_level.to_string();
+        let role = role.into();
+        let permission_str = role.level().to_string();
         let row = client
             .query_one(
-                "INSERT INTO inventory_shares (inventory_id, shared_with_user_id, shared_by_user_id, permission_level) 
-                 VALUES ($1, $2, $3, $4) 
-                 RETURNING id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, created_at, updated_at",
-                &[&inventory_id, &shared_with_user_id, &shared_by_user_id, &permission_str],
+                "INSERT INTO inventory_shares (inventory_id, shared_with_user_id, shared_by_user_id, permission_level, starts_at, expires_at, status, role_id) 
+                 VALUES ($1, $2, $3, $4, $5, $6, 'pending', $7) 
+                 RETURNING id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, starts_at, expires_at, created_at, updated_at, status, role_id",
+                &[&inventory_id, &shared_with_user_id, &shared_by_user_id, &permission_str, &starts_at, &expires_at, &role.role_id()],
             )
             .await?;
 
+        Ok(Self::row_to_inventory_share(&row))
+    }
+
+    /// Map an `inventory_shares` row (id, `inventory_id`, `shared_with_user_id`,
+    /// `shared_by_user_id`, `permission_level`, `starts_at`, `expires_at`, `created_at`,
+    /// `updated_at`, status, `role_id`) to an [`InventoryShare`]
+    fn row_to_inventory_share(row: &tokio_postgres::Row) -> InventoryShare {
         let perm_str: String = row.get(4);
-        Ok(InventoryShare {
+        InventoryShare {
             id: row.get(0),
             inventory_id: row.get(1),
             shared_with_user_id: row.get(2),
             shared_by_user_id: row.get(3),
             permission_level: perm_str.parse().unwrap_or(PermissionLevel::View),
-            created_at: row.get(5),
-            updated_at: row.get(6),
-        })
+            role_id: row.get(10),
+            status: row
+                .get::<_, String>(9)
+                .parse()
+                .unwrap_or(ShareStatus::Pending),
+            starts_at: row.get(5),
+            expires_at: row.get(6),
+            created_at: row.get(7),
+            updated_at: row.get(8),
+        }
+    }
+
+    /// Get a share by ID
+    pub async fn get_inventory_share(
+        &self,
+        share_id: Uuid,
+    ) -> Result<Option<InventoryShare>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, starts_at, expires_at, created_at, updated_at, status, role_id
+                 FROM inventory_shares WHERE id = $1",
+                &[&share_id],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_inventory_share))
     }
 
     /// Get shares for an inventory
@@ -1687,10 +2498,15 @@ impl DatabaseService {
                 "SELECT 
                     s.id, s.inventory_id, s.permission_level, s.created_at, s.updated_at,
                     sw.id, sw.username, sw.full_name, sw.is_admin, sw.is_active, sw.created_at, sw.updated_at,
-                    sb.id, sb.username, sb.full_name, sb.is_admin, sb.is_active, sb.created_at, sb.updated_at
+                    sb.id, sb.username, sb.full_name, sb.is_admin, sb.is_active, sb.created_at, sb.updated_at,
+                    s.starts_at, s.expires_at,
+                    (s.starts_at IS NULL OR s.starts_at <= NOW()) AND (s.expires_at IS NULL OR s.expires_at > NOW()) AND s.status = 'accepted',
+                    GREATEST(EXTRACT(EPOCH FROM (s.expires_at - NOW())), 0)::BIGINT,
+                    s.status, s.role_id, r.name
                  FROM inventory_shares s
                  JOIN users sw ON s.shared_with_user_id = sw.id
                  JOIN users sb ON s.shared_by_user_id = sb.id
+                 LEFT JOIN inventory_roles r ON r.id = s.role_id
                  WHERE s.inventory_id = $1
                  ORDER BY s.created_at DESC",
                 &[&inventory_id],
@@ -1705,6 +2521,16 @@ impl DatabaseService {
                     id: row.get(0),
                     inventory_id: row.get(1),
                     permission_level: perm_str.parse().unwrap_or(PermissionLevel::View),
+                    role_id: row.get(24),
+                    role_name: row.get(25),
+                    status: row
+                        .get::<_, String>(23)
+                        .parse()
+                        .unwrap_or(ShareStatus::Pending),
+                    starts_at: row.get(19),
+                    expires_at: row.get(20),
+                    is_active: row.get(21),
+                    expires_in_seconds: row.get(22),
                     created_at: row.get(3),
                     updated_at: row.get(4),
                     shared_with_user: UserResponse {
@@ -1740,136 +2566,161 @@ impl DatabaseService {
     ) -> Result<EffectivePermissions, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        // Check if user is the owner
+        // Nobody holds anything on a trashed inventory, whatever it was shared through;
+        // the trash endpoints check ownership themselves to restore it
         let owner_rows = client
             .query(
-                "SELECT user_id FROM inventories WHERE id = $1",
+                "SELECT user_id FROM inventories WHERE id = $1 AND deleted_at IS NULL",
                 &[&inventory_id],
             )
             .await?;
+        let Some(row) = owner_rows.first() else {
+            return Ok(EffectivePermissions::from_capabilities(
+                &[],
+                PermissionSource::None,
+                false,
+            ));
+        };
 
-        if let Some(row) = owner_rows.first() {
-            let owner_id: Option<Uuid> = row.get(0);
-            if owner_id == Some(user_id) {
-                return Ok(EffectivePermissions {
-                    can_view: true,
-                    can_edit_items: true,
-                    can_add_items: true,
-                    can_remove_items: true,
-                    can_edit_inventory: true,
-                    can_delete_inventory: true,
-                    can_manage_sharing: true,
-                    can_manage_organizers: true,
-                    is_owner: true,
-                    has_all_access: false,
-                    permission_source: PermissionSource::Owner,
-                });
-            }
+        // Check if user is the owner
+        let owner_id: Option<Uuid> = row.get(0);
+        if owner_id == Some(user_id) {
+            return Ok(EffectivePermissions::from_capabilities(
+                &Capability::ALL,
+                PermissionSource::Owner,
+                true,
+            ));
+        }
 
-            // Check for All Access grant from the owner
-            if let Some(owner_uuid) = owner_id {
-                let all_access_rows = client
-                    .query(
-                        "SELECT id FROM user_access_grants 
-                         WHERE grantor_user_id = $1 AND grantee_user_id = $2",
-                        &[&owner_uuid, &user_id],
-                    )
-                    .await?;
+        // Level of a limited All Access grant from the owner, combined with shares below
+        let mut grant_level = None;
+
+        // Check for All Access grant from the owner
+        if let Some(owner_uuid) = owner_id {
+            let all_access_rows = client
+                .query(
+                    "SELECT permission_level FROM user_access_grants 
+                     WHERE grantor_user_id = $1 AND grantee_user_id = $2
+                       AND (starts_at IS NULL OR starts_at <= NOW())
+                       AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted'",
+                    &[&owner_uuid, &user_id],
+                )
+                .await?;
 
-                if !all_access_rows.is_empty() {
-                    return Ok(EffectivePermissions {
-                        can_view: true,
-                        can_edit_items: true,
-                        can_add_items: true,
-                        can_remove_items: true,
-                        can_edit_inventory: true,
-                        can_delete_inventory: true,
-                        can_manage_sharing: true,
-                        can_manage_organizers: true,
-                        is_owner: false,
-                        has_all_access: true,
-                        permission_source: PermissionSource::AllAccess,
-                    });
-                }
+            grant_level = all_access_rows.first().map(|row| {
+                row.get::<_, String>(0)
+                    .parse()
+                    .unwrap_or(AccessGrantLevel::View)
+            });
+
+            if grant_level == Some(AccessGrantLevel::Full) {
+                return Ok(EffectivePermissions::from_capabilities(
+                    &Capability::ALL,
+                    PermissionSource::AllAccess,
+                    true,
+                ));
             }
         }
 
-        // Check for per-inventory share
+        // Collect the roles of per-inventory shares, direct or through any of the user's
+        // groups, and of a limited All Access grant. The user gets every capability any of
+        // them gives; the source granting the most is reported, and on a tie the most
+        // specific one (direct share, then group share, then the grant).
+        // Shares only count while inside their schedule, direct ones once accepted.
         let share_rows = client
             .query(
-                "SELECT permission_level FROM inventory_shares 
-                 WHERE inventory_id = $1 AND shared_with_user_id = $2",
+                "SELECT s.permission_level, r.capabilities, false AS via_group FROM inventory_shares s
+                 LEFT JOIN inventory_roles r ON r.id = s.role_id
+                 WHERE s.inventory_id = $1 AND s.shared_with_user_id = $2
+                   AND (s.starts_at IS NULL OR s.starts_at <= NOW())
+                   AND (s.expires_at IS NULL OR s.expires_at > NOW()) AND s.status = 'accepted'
+                 UNION ALL
+                 SELECT gs.permission_level, r.capabilities, true FROM inventory_group_shares gs
+                 JOIN user_group_members gm ON gm.group_id = gs.group_id
+                 LEFT JOIN inventory_roles r ON r.id = gs.role_id
+                 WHERE gs.inventory_id = $1 AND gm.user_id = $2
+                   AND (gs.starts_at IS NULL OR gs.starts_at <= NOW())
+                   AND (gs.expires_at IS NULL OR gs.expires_at > NOW())",
                 &[&inventory_id, &user_id],
             )
             .await?;
 
-        if let Some(row) = share_rows.first() {
-            let perm_str: String = row.get(0);
-            let permission = perm_str.parse().unwrap_or(PermissionLevel::View);
+        let roles: Vec<(Vec<Capability>, PermissionSource)> = share_rows
+            .iter()
+            .map(|row| {
+                let custom: Option<Vec<String>> = row.get(1);
+                let capabilities = match custom {
+                    Some(custom) => custom.iter().filter_map(|c| c.parse().ok()).collect(),
+                    None => row
+                        .get::<_, String>(0)
+                        .parse()
+                        .unwrap_or(PermissionLevel::View)
+                        .capabilities()
+                        .to_vec(),
+                };
+                let source = if row.get::<_, bool>(2) {
+                    PermissionSource::GroupShare
+                } else {
+                    PermissionSource::InventoryShare
+                };
+                (capabilities, source)
+            })
+            .chain(
+                grant_level
+                    .and_then(AccessGrantLevel::share_level)
+                    .map(|level| (level.capabilities().to_vec(), PermissionSource::AllAccess)),
+            )
+            .collect();
 
-            return Ok(EffectivePermissions {
-                can_view: permission.can_view(),
-                can_edit_items: permission.can_edit_items(),
-                can_add_items: permission.can_add_items(),
-                can_remove_items: permission.can_remove_items(),
-                can_edit_inventory: permission.can_edit_inventory(),
-                can_delete_inventory: false, // Only owner or AllAccess can delete
-                can_manage_sharing: false,   // Only owner or AllAccess can manage sharing
-                can_manage_organizers: permission.can_manage_organizers(),
-                is_owner: false,
-                has_all_access: false,
-                permission_source: PermissionSource::InventoryShare,
-            });
-        }
+        let strongest = roles.iter().max_by_key(|(capabilities, source)| {
+            let specificity = match source {
+                PermissionSource::InventoryShare => 2,
+                PermissionSource::GroupShare => 1,
+                _ => 0,
+            };
+            (capabilities.len(), specificity)
+        });
 
-        // No access
-        Ok(EffectivePermissions {
-            can_view: false,
-            can_edit_items: false,
-            can_add_items: false,
-            can_remove_items: false,
-            can_edit_inventory: false,
-            can_delete_inventory: false,
-            can_manage_sharing: false,
-            can_manage_organizers: false,
-            is_owner: false,
-            has_all_access: false,
-            permission_source: PermissionSource::None,
-        })
+        let Some((_, source)) = strongest else {
+            // No access
+            return Ok(EffectivePermissions::from_capabilities(
+                &[],
+                PermissionSource::None,
+                false,
+            ));
+        };
+
+        let capabilities: Vec<Capability> = roles
+            .iter()
+            .flat_map(|(capabilities, _)| capabilities.iter().copied())
+            .collect();
+        Ok(EffectivePermissions::from_capabilities(
+            &capabilities,
+            source.clone(),
+            false,
+        ))
     }
 
-    /// Update inventory share permission
+    /// Change the role of an inventory share
     pub async fn update_inventory_share(
         &self,
         share_id: Uuid,
-        permission_level: PermissionLevel,
+        role: impl Into<ShareRole>,
     ) -> Result<Option<InventoryShare>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let permission_str = permission_level.to_string();
+        let role = role.into();
+        let permission_str = role.level().to_string();
         let rows = client
             .query(
-                "UPDATE inventory_shares SET permission_level = $1, updated_at = NOW() 
+                "UPDATE inventory_shares SET permission_level = $1, role_id = $3, updated_at = NOW() 
                  WHERE id = $2 
-                 RETURNING id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, created_at, updated_at",
-                &[&permission_str, &share_id],
+                 RETURNING id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, starts_at, expires_at, created_at, updated_at, status, role_id",
+                &[&permission_str, &share_id, &role.role_id()],
             )
             .await?;
 
-        if let Some(row) = rows.first() {
-            let perm_str: String = row.get(4);
-            Ok(Some(InventoryShare {
-                id: row.get(0),
-                inventory_id: row.get(1),
-                shared_with_user_id: row.get(2),
-                shared_by_user_id: row.get(3),
-                permission_level: perm_str.parse().unwrap_or(PermissionLevel::View),
-                created_at: row.get(5),
-                updated_at: row.get(6),
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(rows.first().map(Self::row_to_inventory_share))
     }
 
     /// Delete inventory share
@@ -1886,8 +2737,110 @@ impl DatabaseService {
         Ok(rows_affected > 0)
     }
 
-    /// Get inventories accessible to a user (owned, shared via `inventory_shares`, or via All Access grants)
-    pub async fn get_accessible_inventories(
+    /// Accept or decline a pending share on behalf of its recipient
+    /// Returns `None` if the user has no pending share with this ID
+    pub async fn respond_to_inventory_share(
+        &self,
+        share_id: Uuid,
+        user_id: Uuid,
+        status: ShareStatus,
+    ) -> Result<Option<InventoryShare>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "UPDATE inventory_shares SET status = $1, updated_at = NOW() 
+                 WHERE id = $2 AND shared_with_user_id = $3 AND status = 'pending' 
+                 RETURNING id, inventory_id, shared_with_user_id, shared_by_user_id, permission_level, starts_at, expires_at, created_at, updated_at, status, role_id",
+                &[&status.to_string(), &share_id, &user_id],
+            )
+            .await?;
+
+        Ok(rows.first().map(Self::row_to_inventory_share))
+    }
+
+    /// Get shares waiting for a user to accept or decline them
+    pub async fn get_pending_inventory_shares(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<PendingInventoryShare>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT 
+                    s.id, s.inventory_id, i.name, s.permission_level, s.starts_at, s.expires_at, s.created_at,
+                    sb.id, sb.username, sb.full_name, sb.is_admin, sb.is_active, sb.created_at, sb.updated_at,
+                    r.name
+                 FROM inventory_shares s
+                 JOIN inventories i ON s.inventory_id = i.id
+                 JOIN users sb ON s.shared_by_user_id = sb.id
+                 LEFT JOIN inventory_roles r ON r.id = s.role_id
+                 WHERE s.shared_with_user_id = $1 AND s.status = 'pending'
+                   AND i.deleted_at IS NULL
+                   AND (s.expires_at IS NULL OR s.expires_at > NOW())
+                 ORDER BY s.created_at DESC",
+                &[&user_id],
+            )
+            .await?;
+
+        let shares = rows
+            .iter()
+            .map(|row| {
+                let perm_str: String = row.get(3);
+                PendingInventoryShare {
+                    id: row.get(0),
+                    inventory_id: row.get(1),
+                    inventory_name: row.get(2),
+                    permission_level: perm_str.parse().unwrap_or(PermissionLevel::View),
+                    role_name: row.get(14),
+                    starts_at: row.get(4),
+                    expires_at: row.get(5),
+                    created_at: row.get(6),
+                    shared_by_user: UserResponse {
+                        id: row.get(7),
+                        username: row.get(8),
+                        full_name: row.get(9),
+                        is_admin: row.get(10),
+                        is_active: row.get(11),
+                        created_at: row.get(12),
+                        updated_at: row.get(13),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(shares)
+    }
+
+    /// Delete inventory shares, group shares and All Access grants whose `expires_at` has passed
+    pub async fn delete_expired_shares(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let shares = client
+            .execute(
+                "DELETE FROM inventory_shares WHERE expires_at IS NOT NULL AND expires_at <= NOW()",
+                &[],
+            )
+            .await?;
+        let group_shares = client
+            .execute(
+                "DELETE FROM inventory_group_shares WHERE expires_at IS NOT NULL AND expires_at <= NOW()",
+                &[],
+            )
+            .await?;
+        let grants = client
+            .execute(
+                "DELETE FROM user_access_grants WHERE expires_at IS NOT NULL AND expires_at <= NOW()",
+                &[],
+            )
+            .await?;
+
+        Ok(shares + group_shares + grants)
+    }
+
+    /// Get inventories accessible to a user (owned, shared directly or with one of their groups, or via All Access grants)
+    pub async fn get_accessible_inventories(
         &self,
         user_id: Uuid,
     ) -> Result<Vec<Inventory>, Box<dyn std::error::Error>> {
@@ -1897,15 +2850,23 @@ impl DatabaseService {
         // 1. Inventories owned by the user (i.user_id = $1)
         // 2. Inventories shared directly with the user (inventory_shares)
         // 3. Inventories owned by users who granted All Access to this user (user_access_grants)
+        //    Shares and grants outside their starts_at/expires_at window are skipped
+        // 4. Inventories shared with a group the user belongs to (inventory_group_shares),
+        //    again only inside the share's window
         let rows = client
             .query(
                 "SELECT DISTINCT i.id, i.name, i.description, i.location, i.image_url, i.user_id, i.created_at, i.updated_at 
                  FROM inventories i
                  LEFT JOIN inventory_shares s ON i.id = s.inventory_id AND s.shared_with_user_id = $1
+                    AND (s.starts_at IS NULL OR s.starts_at <= NOW()) AND (s.expires_at IS NULL OR s.expires_at > NOW()) AND s.status = 'accepted'
                  LEFT JOIN user_access_grants g ON i.user_id = g.grantor_user_id AND g.grantee_user_id = $1
-                 WHERE i.user_id = $1 
+                    AND (g.starts_at IS NULL OR g.starts_at <= NOW()) AND (g.expires_at IS NULL OR g.expires_at > NOW()) AND g.status = 'accepted'
+                 WHERE i.deleted_at IS NULL AND (
+                    i.user_id = $1 
                     OR s.shared_with_user_id = $1
                     OR g.grantee_user_id = $1
+                    OR i.id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $1 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()))
+                 )
                  ORDER BY i.name ASC",
                 &[&user_id],
             )
@@ -1928,1131 +2889,3820 @@ impl DatabaseService {
         Ok(inventories)
     }
 
-    // ==================== User Access Grant Operations (All Access Tier) ====================
+    // ==================== Household Group Operations ====================
 
-    /// Create a user access grant (All Access tier)
-    pub async fn create_user_access_grant(
+    /// Columns selected for a [`UserGroup`], see [`Self::row_to_group`]
+    ///
+    /// `$1` is the user whose role is reported in `my_role`.
+    const GROUP_COLUMNS: &'static str =
+        "g.id, g.name, g.description, g.created_by, g.created_at, g.updated_at,
+         (SELECT COUNT(*) FROM user_group_members c WHERE c.group_id = g.id), m.role
+         FROM user_groups g
+         LEFT JOIN user_group_members m ON m.group_id = g.id AND m.user_id = $1";
+
+    /// Map a row selected with [`Self::GROUP_COLUMNS`] to a [`UserGroup`]
+    fn row_to_group(row: &tokio_postgres::Row) -> UserGroup {
+        let role: Option<String> = row.get(7);
+        UserGroup {
+            id: row.get(0),
+            name: row.get(1),
+            description: row.get(2),
+            created_by: row.get(3),
+            created_at: row.get(4),
+            updated_at: row.get(5),
+            member_count: row.get(6),
+            my_role: role.and_then(|r| r.parse().ok()),
+        }
+    }
+
+    /// Create a group with its creator as the first manager
+    pub async fn create_group(
         &self,
-        grantor_user_id: Uuid,
-        grantee_user_id: Uuid,
-    ) -> Result<UserAccessGrant, Box<dyn std::error::Error>> {
-        let client = self.pool.get().await?;
+        created_by: Uuid,
+        request: &CreateGroupRequest,
+    ) -> Result<UserGroup, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
 
-        let row = client
+        let row = transaction
             .query_one(
-                "INSERT INTO user_access_grants (grantor_user_id, grantee_user_id) 
-                 VALUES ($1, $2) 
-                 RETURNING id, grantor_user_id, grantee_user_id, created_at, updated_at",
-                &[&grantor_user_id, &grantee_user_id],
+                "INSERT INTO user_groups (name, description, created_by)
+                 VALUES ($1, $2, $3)
+                 RETURNING id",
+                &[&request.name.trim(), &request.description, &created_by],
             )
             .await?;
+        let group_id: Uuid = row.get(0);
 
-        Ok(UserAccessGrant {
-            id: row.get(0),
-            grantor_user_id: row.get(1),
-            grantee_user_id: row.get(2),
-            created_at: row.get(3),
-            updated_at: row.get(4),
-        })
+        transaction
+            .execute(
+                "INSERT INTO user_group_members (group_id, user_id, role) VALUES ($1, $2, $3)",
+                &[&group_id, &created_by, &GroupRole::Manager.to_string()],
+            )
+            .await?;
+        transaction.commit().await?;
+
+        self.get_group(group_id, created_by)
+            .await?
+            .ok_or_else(|| "Group disappeared after insert".into())
     }
 
-    /// Get all access grants where the user is the grantor (people who can access my inventories)
-    pub async fn get_user_access_grants_by_grantor(
+    /// List groups by name; all of them, or only those `user_id` belongs to
+    pub async fn get_groups(
         &self,
-        grantor_user_id: Uuid,
-    ) -> Result<Vec<UserAccessGrantWithUsers>, Box<dyn std::error::Error>> {
+        user_id: Uuid,
+        include_all: bool,
+    ) -> Result<Vec<UserGroup>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows = client
             .query(
-                "SELECT 
-                    g.id, g.created_at, g.updated_at,
-                    gr.id, gr.username, gr.full_name, gr.is_admin, gr.is_active, gr.created_at, gr.updated_at,
-                    ge.id, ge.username, ge.full_name, ge.is_admin, ge.is_active, ge.created_at, ge.updated_at
-                 FROM user_access_grants g
-                 JOIN users gr ON g.grantor_user_id = gr.id
-                 JOIN users ge ON g.grantee_user_id = ge.id
-                 WHERE g.grantor_user_id = $1
-                 ORDER BY g.created_at DESC",
-                &[&grantor_user_id],
+                &format!(
+                    "SELECT {} WHERE $2 OR m.user_id IS NOT NULL ORDER BY g.name ASC",
+                    Self::GROUP_COLUMNS
+                ),
+                &[&user_id, &include_all],
             )
             .await?;
 
-        let grants = rows
-            .iter()
-            .map(|row| UserAccessGrantWithUsers {
-                id: row.get(0),
-                created_at: row.get(1),
-                updated_at: row.get(2),
-                grantor: UserResponse {
-                    id: row.get(3),
-                    username: row.get(4),
-                    full_name: row.get(5),
-                    is_admin: row.get(6),
-                    is_active: row.get(7),
-                    created_at: row.get(8),
-                    updated_at: row.get(9),
-                },
-                grantee: UserResponse {
-                    id: row.get(10),
-                    username: row.get(11),
-                    full_name: row.get(12),
-                    is_admin: row.get(13),
-                    is_active: row.get(14),
-                    created_at: row.get(15),
-                    updated_at: row.get(16),
-                },
-            })
-            .collect();
+        Ok(rows.iter().map(Self::row_to_group).collect())
+    }
 
-        Ok(grants)
+    /// Get a group by ID, with `viewer_id`'s role in it
+    pub async fn get_group(
+        &self,
+        group_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<Option<UserGroup>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                &format!("SELECT {} WHERE g.id = $2", Self::GROUP_COLUMNS),
+                &[&viewer_id, &group_id],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_group))
     }
 
-    /// Get all access grants where the user is the grantee (users who gave me access)
-    pub async fn get_user_access_grants_by_grantee(
+    /// Get a group's members, managers first
+    pub async fn get_group_members(
         &self,
-        grantee_user_id: Uuid,
-    ) -> Result<Vec<UserAccessGrantWithUsers>, Box<dyn std::error::Error>> {
+        group_id: Uuid,
+    ) -> Result<Vec<GroupMember>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows = client
             .query(
-                "SELECT 
-                    g.id, g.created_at, g.updated_at,
-                    gr.id, gr.username, gr.full_name, gr.is_admin, gr.is_active, gr.created_at, gr.updated_at,
-                    ge.id, ge.username, ge.full_name, ge.is_admin, ge.is_active, ge.created_at, ge.updated_at
-                 FROM user_access_grants g
-                 JOIN users gr ON g.grantor_user_id = gr.id
-                 JOIN users ge ON g.grantee_user_id = ge.id
-                 WHERE g.grantee_user_id = $1
-                 ORDER BY g.created_at DESC",
-                &[&grantee_user_id],
+                "SELECT u.id, u.username, u.full_name, u.is_admin, u.is_active, u.created_at, u.updated_at,
+                        m.role, m.created_at
+                 FROM user_group_members m
+                 JOIN users u ON u.id = m.user_id
+                 WHERE m.group_id = $1
+                 ORDER BY m.role = 'manager' DESC, u.username ASC",
+                &[&group_id],
             )
             .await?;
 
-        let grants = rows
+        Ok(rows
             .iter()
-            .map(|row| UserAccessGrantWithUsers {
-                id: row.get(0),
-                created_at: row.get(1),
-                updated_at: row.get(2),
-                grantor: UserResponse {
-                    id: row.get(3),
-                    username: row.get(4),
-                    full_name: row.get(5),
-                    is_admin: row.get(6),
-                    is_active: row.get(7),
-                    created_at: row.get(8),
-                    updated_at: row.get(9),
-                },
-                grantee: UserResponse {
-                    id: row.get(10),
-                    username: row.get(11),
-                    full_name: row.get(12),
-                    is_admin: row.get(13),
-                    is_active: row.get(14),
-                    created_at: row.get(15),
-                    updated_at: row.get(16),
-                },
+            .map(|row| {
+                let role: String = row.get(7);
+                GroupMember {
+                    user: UserResponse {
+                        id: row.get(0),
+                        username: row.get(1),
+                        full_name: row.get(2),
+                        is_admin: row.get(3),
+                        is_active: row.get(4),
+                        created_at: row.get(5),
+                        updated_at: row.get(6),
+                    },
+                    role: role.parse().unwrap_or(GroupRole::Member),
+                    added_at: row.get(8),
+                }
             })
-            .collect();
-
-        Ok(grants)
+            .collect())
     }
 
-    /// Delete a user access grant
-    pub async fn delete_user_access_grant(
+    /// Rename or describe a group
+    pub async fn update_group(
         &self,
-        grant_id: Uuid,
+        group_id: Uuid,
+        request: &UpdateGroupRequest,
     ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows_affected = client
-            .execute("DELETE FROM user_access_grants WHERE id = $1", &[&grant_id])
+            .execute(
+                "UPDATE user_groups
+                 SET name = COALESCE($2, name), description = COALESCE($3, description), updated_at = NOW()
+                 WHERE id = $1",
+                &[
+                    &group_id,
+                    &request.name.as_deref().map(str::trim),
+                    &request.description,
+                ],
+            )
             .await?;
 
         Ok(rows_affected > 0)
     }
 
-    /// Get a user access grant by ID
-    pub async fn get_user_access_grant_by_id(
+    /// Delete a group, its memberships and the inventory shares made to it
+    pub async fn delete_group(&self, group_id: Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute("DELETE FROM user_groups WHERE id = $1", &[&group_id])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Add a user to a group
+    pub async fn add_group_member(
         &self,
-        grant_id: Uuid,
-    ) -> Result<Option<UserAccessGrant>, Box<dyn std::error::Error>> {
+        group_id: Uuid,
+        user_id: Uuid,
+        role: GroupRole,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let rows = client
-            .query(
-                "SELECT id, grantor_user_id, grantee_user_id, created_at, updated_at 
-                 FROM user_access_grants WHERE id = $1",
-                &[&grant_id],
+        client
+            .execute(
+                "INSERT INTO user_group_members (group_id, user_id, role) VALUES ($1, $2, $3)",
+                &[&group_id, &user_id, &role.to_string()],
             )
             .await?;
 
-        if let Some(row) = rows.first() {
-            Ok(Some(UserAccessGrant {
-                id: row.get(0),
-                grantor_user_id: row.get(1),
-                grantee_user_id: row.get(2),
-                created_at: row.get(3),
-                updated_at: row.get(4),
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(())
     }
 
-    // ==================== Ownership Transfer Operations ====================
-
-    /// Transfer ownership of an inventory from one user to another
-    /// This operation:
-    /// 1. Updates the inventory's `user_id` to the new owner
-    /// 2. Removes all existing shares for the inventory (new owner controls sharing)
-    /// 3. The previous owner loses all access
-    pub async fn transfer_inventory_ownership(
+    /// Get a user's role in a group, or `None` when they are not a member
+    pub async fn get_group_role(
         &self,
-        inventory_id: i32,
-        from_user_id: Uuid,
-        to_user_id: Uuid,
-    ) -> Result<(i64, i64), Box<dyn std::error::Error>> {
-        let mut client = self.pool.get().await?;
-
-        // Start a transaction for atomic operation
-        let transaction = client.transaction().await?;
+        group_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<GroupRole>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        // Verify the inventory exists and is owned by from_user_id
-        let verify_result = transaction
+        let row = client
             .query_opt(
-                "SELECT id FROM inventories WHERE id = $1 AND user_id = $2",
-                &[&inventory_id, &from_user_id],
+                "SELECT role FROM user_group_members WHERE group_id = $1 AND user_id = $2",
+                &[&group_id, &user_id],
             )
             .await?;
 
-        if verify_result.is_none() {
-            return Err("Inventory not found or you are not the owner".into());
-        }
+        Ok(row.and_then(|row| row.get::<_, String>(0).parse().ok()))
+    }
 
-        // Verify the target user exists
-        let target_user = transaction
-            .query_opt(
-                "SELECT id FROM users WHERE id = $1 AND is_active = true",
-                &[&to_user_id],
+    /// Count the managers of a group
+    pub async fn count_group_managers(
+        &self,
+        group_id: Uuid,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "SELECT COUNT(*) FROM user_group_members WHERE group_id = $1 AND role = 'manager'",
+                &[&group_id],
             )
             .await?;
 
-        if target_user.is_none() {
-            return Err("Target user not found or is inactive".into());
-        }
+        Ok(row.get(0))
+    }
 
-        // Count items that will be transferred (for reporting)
-        let items_count: i64 = transaction
-            .query_one(
-                "SELECT COUNT(*) FROM items WHERE inventory_id = $1",
-                &[&inventory_id],
-            )
-            .await?
-            .get(0);
+    /// Change a member's role
+    pub async fn update_group_member_role(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        role: GroupRole,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        // Transfer ownership by updating user_id
-        transaction
+        let rows_affected = client
             .execute(
-                "UPDATE inventories SET user_id = $1, updated_at = NOW() WHERE id = $2",
-                &[&to_user_id, &inventory_id],
+                "UPDATE user_group_members SET role = $3 WHERE group_id = $1 AND user_id = $2",
+                &[&group_id, &user_id, &role.to_string()],
             )
             .await?;
 
-        // Remove all existing shares for this inventory (new owner will manage sharing)
-        let shares_removed = transaction
+        Ok(rows_affected > 0)
+    }
+
+    /// Remove a user from a group, ending any access they had through it
+    pub async fn remove_group_member(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
             .execute(
-                "DELETE FROM inventory_shares WHERE inventory_id = $1",
-                &[&inventory_id],
+                "DELETE FROM user_group_members WHERE group_id = $1 AND user_id = $2",
+                &[&group_id, &user_id],
             )
             .await?;
 
-        // Commit the transaction
-        transaction.commit().await?;
-
-        info!(
-            "Transferred ownership of inventory {} from {:?} to {:?}. Items: {}, Shares removed: {}",
-            inventory_id, from_user_id, to_user_id, items_count, shares_removed
-        );
-
-        // Safe cast: shares_removed is clamped to i64::MAX, preventing wrap
-        #[allow(
-            clippy::cast_possible_wrap,
-            reason = "Value is clamped to i64::MAX preventing wrap"
-        )]
-        let shares_removed_i64 = shares_removed.min(i64::MAX as u64) as i64;
-        Ok((items_count, shares_removed_i64))
+        Ok(rows_affected > 0)
     }
 
-    // ==================== Recovery Codes Methods ====================
+    /// Columns selected for an [`InventoryGroupShare`], see [`Self::row_to_group_share`]
+    const GROUP_SHARE_COLUMNS: &'static str =
+        "s.id, s.inventory_id, s.group_id, g.name, s.shared_by_user_id, s.permission_level,
+         s.created_at, s.updated_at, s.role_id, r.name, s.starts_at, s.expires_at,
+         (s.starts_at IS NULL OR s.starts_at <= NOW()) AND (s.expires_at IS NULL OR s.expires_at > NOW()),
+         GREATEST(EXTRACT(EPOCH FROM (s.expires_at - NOW())), 0)::BIGINT
+         FROM inventory_group_shares s
+         JOIN user_groups g ON g.id = s.group_id
+         LEFT JOIN inventory_roles r ON r.id = s.role_id";
+
+    /// Map a row selected with [`Self::GROUP_SHARE_COLUMNS`] to an [`InventoryGroupShare`]
+    fn row_to_group_share(row: &tokio_postgres::Row) -> InventoryGroupShare {
+        let perm_str: String = row.get(5);
+        InventoryGroupShare {
+            id: row.get(0),
+            inventory_id: row.get(1),
+            group_id: row.get(2),
+            group_name: row.get(3),
+            shared_by_user_id: row.get(4),
+            permission_level: perm_str.parse().unwrap_or(PermissionLevel::View),
+            role_id: row.get(8),
+            role_name: row.get(9),
+            starts_at: row.get(10),
+            expires_at: row.get(11),
+            is_active: row.get(12),
+            expires_in_seconds: row.get(13),
+            created_at: row.get(6),
+            updated_at: row.get(7),
+        }
+    }
 
-    /// Store recovery codes for a user (deletes any existing codes first)
-    pub async fn store_recovery_codes(
+    /// Share an inventory with every member of a group
+    pub async fn create_inventory_group_share(
         &self,
-        user_id: Uuid,
-        code_hashes: Vec<String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        inventory_id: i32,
+        group_id: Uuid,
+        shared_by_user_id: Uuid,
+        role: impl Into<ShareRole>,
+        starts_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<InventoryGroupShare, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        // Delete any existing recovery codes for this user
-        client
-            .execute("DELETE FROM recovery_codes WHERE user_id = $1", &[&user_id])
+        let role = role.into();
+        let row = client
+            .query_one(
+                "INSERT INTO inventory_group_shares (inventory_id, group_id, shared_by_user_id, permission_level, role_id, starts_at, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 RETURNING id",
+                &[
+                    &inventory_id,
+                    &group_id,
+                    &shared_by_user_id,
+                    &role.level().to_string(),
+                    &role.role_id(),
+                    &starts_at,
+                    &expires_at,
+                ],
+            )
             .await?;
 
-        // Insert new codes
-        for code_hash in code_hashes {
-            client
-                .execute(
-                    "INSERT INTO recovery_codes (user_id, code_hash) VALUES ($1, $2)",
-                    &[&user_id, &code_hash],
-                )
-                .await?;
-        }
+        self.get_inventory_group_share(row.get(0))
+            .await?
+            .ok_or_else(|| "Group share disappeared after insert".into())
+    }
 
-        // Update user's recovery codes timestamp and reset confirmation
-        client
-            .execute(
-                "UPDATE users SET recovery_codes_generated_at = NOW(), recovery_codes_confirmed = false WHERE id = $1",
-                &[&user_id],
+    /// Get the group shares of an inventory
+    pub async fn get_inventory_group_shares(
+        &self,
+        inventory_id: i32,
+    ) -> Result<Vec<InventoryGroupShare>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} WHERE s.inventory_id = $1 ORDER BY g.name ASC",
+                    Self::GROUP_SHARE_COLUMNS
+                ),
+                &[&inventory_id],
             )
             .await?;
 
-        info!("Stored {} recovery codes for user {}", 10, user_id);
-        Ok(())
+        Ok(rows.iter().map(Self::row_to_group_share).collect())
     }
 
-    /// Confirm that user has saved their recovery codes
-    pub async fn confirm_recovery_codes(
+    /// Get a group share by ID
+    pub async fn get_inventory_group_share(
         &self,
-        user_id: Uuid,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        share_id: Uuid,
+    ) -> Result<Option<InventoryGroupShare>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        client
-            .execute(
-                "UPDATE users SET recovery_codes_confirmed = true WHERE id = $1",
-                &[&user_id],
+        let row = client
+            .query_opt(
+                &format!("SELECT {} WHERE s.id = $1", Self::GROUP_SHARE_COLUMNS),
+                &[&share_id],
             )
             .await?;
 
-        info!("User {} confirmed saving recovery codes", user_id);
-        Ok(())
+        Ok(row.as_ref().map(Self::row_to_group_share))
     }
 
-    /// Get all unused recovery code hashes for a user (for verification)
-    pub async fn get_unused_recovery_codes(
+    /// Change the role of a group share
+    pub async fn update_inventory_group_share(
         &self,
-        user_id: Uuid,
-    ) -> Result<Vec<(Uuid, String)>, Box<dyn std::error::Error>> {
+        share_id: Uuid,
+        role: impl Into<ShareRole>,
+    ) -> Result<Option<InventoryGroupShare>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let rows = client
-            .query(
-                "SELECT id, code_hash FROM recovery_codes WHERE user_id = $1 AND is_used = false",
-                &[&user_id],
+        let role = role.into();
+        let rows_affected = client
+            .execute(
+                "UPDATE inventory_group_shares SET permission_level = $1, role_id = $3, updated_at = NOW() WHERE id = $2",
+                &[&role.level().to_string(), &share_id, &role.role_id()],
             )
             .await?;
 
-        let codes: Vec<(Uuid, String)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
-
-        Ok(codes)
+        if rows_affected == 0 {
+            return Ok(None);
+        }
+        self.get_inventory_group_share(share_id).await
     }
 
-    /// Mark a recovery code as used
-    pub async fn mark_recovery_code_used(
+    /// Stop sharing an inventory with a group
+    pub async fn delete_inventory_group_share(
         &self,
-        code_id: Uuid,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        share_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        client
+        let rows_affected = client
             .execute(
-                "UPDATE recovery_codes SET is_used = true, used_at = NOW() WHERE id = $1",
-                &[&code_id],
+                "DELETE FROM inventory_group_shares WHERE id = $1",
+                &[&share_id],
             )
             .await?;
 
-        info!("Marked recovery code {} as used", code_id);
-        Ok(())
+        Ok(rows_affected > 0)
     }
 
-    /// Get count of unused recovery codes for a user
-    pub async fn get_unused_recovery_codes_count(
+    // ==================== Custom Role Operations ====================
+
+    /// Map an `inventory_roles` row (id, `owner_user_id`, name, description,
+    /// capabilities, `created_at`, `updated_at`) to an [`InventoryRole`]
+    fn row_to_inventory_role(row: &tokio_postgres::Row) -> InventoryRole {
+        let capabilities: Vec<String> = row.get(4);
+        InventoryRole {
+            id: row.get(0),
+            owner_user_id: row.get(1),
+            name: row.get(2),
+            description: row.get(3),
+            capabilities: capabilities.iter().filter_map(|c| c.parse().ok()).collect(),
+            created_at: row.get(5),
+            updated_at: row.get(6),
+        }
+    }
+
+    /// Create a custom role for an owner's inventories
+    pub async fn create_inventory_role(
         &self,
-        user_id: Uuid,
-    ) -> Result<i32, Box<dyn std::error::Error>> {
+        owner_user_id: Uuid,
+        request: CreateInventoryRoleRequest,
+    ) -> Result<InventoryRole, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
+        let capabilities: Vec<String> = Capability::normalize(&request.capabilities)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
         let row = client
             .query_one(
-                "SELECT COUNT(*)::int4 FROM recovery_codes WHERE user_id = $1 AND is_used = false",
-                &[&user_id],
+                "INSERT INTO inventory_roles (owner_user_id, name, description, capabilities)
+                 VALUES ($1, $2, $3, $4)
+                 RETURNING id, owner_user_id, name, description, capabilities, created_at, updated_at",
+                &[
+                    &owner_user_id,
+                    &request.name,
+                    &request.description,
+                    &capabilities,
+                ],
             )
             .await?;
 
-        Ok(row.get(0))
+        Ok(Self::row_to_inventory_role(&row))
     }
 
-    /// Get recovery codes status for a user
-    pub async fn get_recovery_codes_status(
+    /// Get the custom roles an owner has defined
+    pub async fn get_inventory_roles(
         &self,
-        user_id: Uuid,
-    ) -> Result<(bool, bool, i32, Option<DateTime<Utc>>), Box<dyn std::error::Error>> {
+        owner_user_id: Uuid,
+    ) -> Result<Vec<InventoryRole>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        // Get user info
-        let user_row = client
-            .query_one(
-                "SELECT recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false) FROM users WHERE id = $1",
-                &[&user_id],
+        let rows = client
+            .query(
+                "SELECT id, owner_user_id, name, description, capabilities, created_at, updated_at
+                 FROM inventory_roles WHERE owner_user_id = $1 ORDER BY name ASC",
+                &[&owner_user_id],
             )
             .await?;
 
-        let generated_at: Option<DateTime<Utc>> = user_row.get(0);
-        let confirmed: bool = user_row.get(1);
+        Ok(rows.iter().map(Self::row_to_inventory_role).collect())
+    }
 
-        // Get unused count
-        let count_row = client
-            .query_one(
-                "SELECT COUNT(*)::int4 FROM recovery_codes WHERE user_id = $1 AND is_used = false",
-                &[&user_id],
+    /// Get a custom role by ID
+    pub async fn get_inventory_role(
+        &self,
+        role_id: Uuid,
+    ) -> Result<Option<InventoryRole>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT id, owner_user_id, name, description, capabilities, created_at, updated_at
+                 FROM inventory_roles WHERE id = $1",
+                &[&role_id],
             )
             .await?;
 
-        let unused_count: i32 = count_row.get(0);
-        let has_codes = unused_count > 0;
+        Ok(row.as_ref().map(Self::row_to_inventory_role))
+    }
 
-        Ok((has_codes, confirmed, unused_count, generated_at))
+    /// Rename a custom role or change its capabilities. Shares using the role keep
+    /// their stored permission level in step with the new capabilities.
+    pub async fn update_inventory_role(
+        &self,
+        role_id: Uuid,
+        request: UpdateInventoryRoleRequest,
+    ) -> Result<Option<InventoryRole>, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let capabilities: Option<Vec<String>> = request.capabilities.as_ref().map(|caps| {
+            Capability::normalize(caps)
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        });
+        let Some(row) = transaction
+            .query_opt(
+                "UPDATE inventory_roles SET
+                    name = COALESCE($2, name),
+                    description = COALESCE($3, description),
+                    capabilities = COALESCE($4, capabilities),
+                    updated_at = NOW()
+                 WHERE id = $1
+                 RETURNING id, owner_user_id, name, description, capabilities, created_at, updated_at",
+                &[&role_id, &request.name, &request.description, &capabilities],
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let role = Self::row_to_inventory_role(&row);
+
+        let level = PermissionLevel::closest(&role.capabilities).to_string();
+        for table in ["inventory_shares", "inventory_group_shares"] {
+            transaction
+                .execute(
+                    &format!("UPDATE {table} SET permission_level = $1 WHERE role_id = $2"),
+                    &[&level, &role_id],
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(Some(role))
     }
 
-    // ==================== Inventory Reporting Operations ====================
+    /// Delete a custom role; shares using it fall back to their stored permission level
+    pub async fn delete_inventory_role(
+        &self,
+        role_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-    /// Check if user has access to a specific inventory
-    pub async fn check_inventory_access(
+        let rows_affected = client
+            .execute("DELETE FROM inventory_roles WHERE id = $1", &[&role_id])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    // ==================== Share Link Operations ====================
+
+    /// Map an `inventory_share_links` row (id, `inventory_id`, `created_by`, name,
+    /// `token_prefix`, `password_protected`, `access_count`, `last_accessed_at`,
+    /// `created_at`, `expires_at`) to a [`ShareLink`]
+    fn row_to_share_link(row: &tokio_postgres::Row) -> ShareLink {
+        ShareLink {
+            id: row.get(0),
+            inventory_id: row.get(1),
+            created_by: row.get(2),
+            name: row.get(3),
+            token_prefix: row.get(4),
+            password_protected: row.get(5),
+            access_count: row.get(6),
+            last_accessed_at: row.get(7),
+            created_at: row.get(8),
+            expires_at: row.get(9),
+        }
+    }
+
+    /// Store a new public share link for an inventory
+    ///
+    /// The link password, if any, must already be hashed.
+    pub async fn create_share_link(
         &self,
-        user_id: Uuid,
         inventory_id: i32,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
+        created_by: Uuid,
+        token_hash: &str,
+        token_prefix: &str,
+        password_hash: Option<&str>,
+        request: &CreateShareLinkRequest,
+    ) -> Result<ShareLink, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
+        let expires_at = request
+            .expires_in_days
+            .map(|days| Utc::now() + chrono::Duration::days(days));
 
         let row = client
             .query_one(
-                "SELECT COUNT(*)::int8 as count FROM inventories 
-                 WHERE id = $1 AND (
-                     user_id = $2
-                     OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $2)
-                     OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $2)
-                 )",
-                &[&inventory_id, &user_id],
+                "INSERT INTO inventory_share_links
+                    (inventory_id, created_by, name, token_hash, token_prefix, password_hash, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 RETURNING id, inventory_id, created_by, name, token_prefix, password_hash IS NOT NULL,
+                           access_count, last_accessed_at, created_at, expires_at",
+                &[
+                    &inventory_id,
+                    &created_by,
+                    &request.name.trim(),
+                    &token_hash,
+                    &token_prefix,
+                    &password_hash,
+                    &expires_at,
+                ],
             )
             .await?;
 
-        let count: i64 = row.get(0);
-        Ok(count > 0)
+        Ok(Self::row_to_share_link(&row))
     }
 
-    /// Retrieves filtered inventory items for report generation.
-    ///
-    /// This method enforces row-level security by only returning items from inventories
-    /// that the user owns or has been granted access to via shares or access grants.
-    ///
-    /// # Arguments
-    /// * `request` - Filter parameters (`inventory_id`, `category`, dates, prices, etc.)
-    /// * `user_id` - UUID of the authenticated user making the request
-    ///
-    /// # Returns
-    /// * `Ok(Vec<Item>)` - Filtered and sorted items accessible to the user
-    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
-    pub async fn get_inventory_report_data(
+    /// List an inventory's share links, newest first
+    pub async fn get_inventory_share_links(
         &self,
-        request: crate::models::InventoryReportRequest,
-        user_id: Uuid,
-    ) -> Result<Vec<crate::models::Item>, Box<dyn std::error::Error>> {
+        inventory_id: i32,
+    ) -> Result<Vec<ShareLink>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        // Build dynamic WHERE clause based on filters
-        let mut conditions = vec![
-            "i.inventory_id IN (
-                SELECT id FROM inventories 
-                WHERE user_id = $1
-                   OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1)
-                   OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1)
-            )".to_string()
-        ];
-        let mut param_index = 2;
+        let rows = client
+            .query(
+                "SELECT id, inventory_id, created_by, name, token_prefix, password_hash IS NOT NULL,
+                        access_count, last_accessed_at, created_at, expires_at
+                 FROM inventory_share_links WHERE inventory_id = $1 ORDER BY created_at DESC",
+                &[&inventory_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(Self::row_to_share_link).collect())
+    }
+
+    /// Get a share link by ID
+    pub async fn get_share_link(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<ShareLink>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT id, inventory_id, created_by, name, token_prefix, password_hash IS NOT NULL,
+                        access_count, last_accessed_at, created_at, expires_at
+                 FROM inventory_share_links WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_share_link))
+    }
+
+    /// Get the unexpired share link with this token hash, along with its password hash
+    pub async fn get_active_share_link_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(ShareLink, Option<String>)>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT id, inventory_id, created_by, name, token_prefix, password_hash IS NOT NULL,
+                        access_count, last_accessed_at, created_at, expires_at, password_hash
+                 FROM inventory_share_links
+                 WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())",
+                &[&token_hash],
+            )
+            .await?;
+
+        Ok(row.map(|row| (Self::row_to_share_link(&row), row.get(10))))
+    }
+
+    /// Count a successful visit to a share link
+    pub async fn record_share_link_access(
+        &self,
+        id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE inventory_share_links
+                 SET access_count = access_count + 1, last_accessed_at = NOW()
+                 WHERE id = $1",
+                &[&id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Revoke a share link. Returns false if it does not exist.
+    pub async fn delete_share_link(&self, id: Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute("DELETE FROM inventory_share_links WHERE id = $1", &[&id])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    // ==================== User Access Grant Operations (All Access Tier) ====================
+
+    /// Offer a user access grant (All Access tier), optionally only between `starts_at` and `expires_at`;
+    /// the grant stays pending until the grantee accepts it
+    pub async fn create_user_access_grant(
+        &self,
+        grantor_user_id: Uuid,
+        grantee_user_id: Uuid,
+        permission_level: AccessGrantLevel,
+        starts_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<UserAccessGrant, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "INSERT INTO user_access_grants (grantor_user_id, grantee_user_id, permission_level, starts_at, expires_at, status) 
+                 VALUES ($1, $2, $3, $4, $5, 'pending') 
+                 RETURNING id, grantor_user_id, grantee_user_id, starts_at, expires_at, created_at, updated_at, status, permission_level",
+                &[&grantor_user_id, &grantee_user_id, &permission_level.to_string(), &starts_at, &expires_at],
+            )
+            .await?;
+
+        Ok(UserAccessGrant {
+            id: row.get(0),
+            grantor_user_id: row.get(1),
+            grantee_user_id: row.get(2),
+            permission_level: row
+                .get::<_, String>(8)
+                .parse()
+                .unwrap_or(AccessGrantLevel::View),
+            status: row
+                .get::<_, String>(7)
+                .parse()
+                .unwrap_or(ShareStatus::Pending),
+            starts_at: row.get(3),
+            expires_at: row.get(4),
+            created_at: row.get(5),
+            updated_at: row.get(6),
+        })
+    }
+
+    /// Get all access grants where the user is the grantor (people who can access my inventories)
+    pub async fn get_user_access_grants_by_grantor(
+        &self,
+        grantor_user_id: Uuid,
+    ) -> Result<Vec<UserAccessGrantWithUsers>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT 
+                    g.id, g.created_at, g.updated_at,
+                    gr.id, gr.username, gr.full_name, gr.is_admin, gr.is_active, gr.created_at, gr.updated_at,
+                    ge.id, ge.username, ge.full_name, ge.is_admin, ge.is_active, ge.created_at, ge.updated_at,
+                    g.starts_at, g.expires_at,
+                    (g.starts_at IS NULL OR g.starts_at <= NOW()) AND (g.expires_at IS NULL OR g.expires_at > NOW()) AND g.status = 'accepted',
+                    GREATEST(EXTRACT(EPOCH FROM (g.expires_at - NOW())), 0)::BIGINT,
+                    g.status, g.permission_level
+                 FROM user_access_grants g
+                 JOIN users gr ON g.grantor_user_id = gr.id
+                 JOIN users ge ON g.grantee_user_id = ge.id
+                 WHERE g.grantor_user_id = $1
+                 ORDER BY g.created_at DESC",
+                &[&grantor_user_id],
+            )
+            .await?;
+
+        let grants = rows
+            .iter()
+            .map(|row| UserAccessGrantWithUsers {
+                id: row.get(0),
+                permission_level: row
+                    .get::<_, String>(22)
+                    .parse()
+                    .unwrap_or(AccessGrantLevel::View),
+                status: row
+                    .get::<_, String>(21)
+                    .parse()
+                    .unwrap_or(ShareStatus::Pending),
+                starts_at: row.get(17),
+                expires_at: row.get(18),
+                is_active: row.get(19),
+                expires_in_seconds: row.get(20),
+                created_at: row.get(1),
+                updated_at: row.get(2),
+                grantor: UserResponse {
+                    id: row.get(3),
+                    username: row.get(4),
+                    full_name: row.get(5),
+                    is_admin: row.get(6),
+                    is_active: row.get(7),
+                    created_at: row.get(8),
+                    updated_at: row.get(9),
+                },
+                grantee: UserResponse {
+                    id: row.get(10),
+                    username: row.get(11),
+                    full_name: row.get(12),
+                    is_admin: row.get(13),
+                    is_active: row.get(14),
+                    created_at: row.get(15),
+                    updated_at: row.get(16),
+                },
+            })
+            .collect();
+
+        Ok(grants)
+    }
+
+    /// Get all access grants where the user is the grantee (users who gave me access)
+    pub async fn get_user_access_grants_by_grantee(
+        &self,
+        grantee_user_id: Uuid,
+    ) -> Result<Vec<UserAccessGrantWithUsers>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT 
+                    g.id, g.created_at, g.updated_at,
+                    gr.id, gr.username, gr.full_name, gr.is_admin, gr.is_active, gr.created_at, gr.updated_at,
+                    ge.id, ge.username, ge.full_name, ge.is_admin, ge.is_active, ge.created_at, ge.updated_at,
+                    g.starts_at, g.expires_at,
+                    (g.starts_at IS NULL OR g.starts_at <= NOW()) AND (g.expires_at IS NULL OR g.expires_at > NOW()) AND g.status = 'accepted',
+                    GREATEST(EXTRACT(EPOCH FROM (g.expires_at - NOW())), 0)::BIGINT,
+                    g.status, g.permission_level
+                 FROM user_access_grants g
+                 JOIN users gr ON g.grantor_user_id = gr.id
+                 JOIN users ge ON g.grantee_user_id = ge.id
+                 WHERE g.grantee_user_id = $1
+                 ORDER BY g.created_at DESC",
+                &[&grantee_user_id],
+            )
+            .await?;
+
+        let grants = rows
+            .iter()
+            .map(|row| UserAccessGrantWithUsers {
+                id: row.get(0),
+                permission_level: row
+                    .get::<_, String>(22)
+                    .parse()
+                    .unwrap_or(AccessGrantLevel::View),
+                status: row
+                    .get::<_, String>(21)
+                    .parse()
+                    .unwrap_or(ShareStatus::Pending),
+                starts_at: row.get(17),
+                expires_at: row.get(18),
+                is_active: row.get(19),
+                expires_in_seconds: row.get(20),
+                created_at: row.get(1),
+                updated_at: row.get(2),
+                grantor: UserResponse {
+                    id: row.get(3),
+                    username: row.get(4),
+                    full_name: row.get(5),
+                    is_admin: row.get(6),
+                    is_active: row.get(7),
+                    created_at: row.get(8),
+                    updated_at: row.get(9),
+                },
+                grantee: UserResponse {
+                    id: row.get(10),
+                    username: row.get(11),
+                    full_name: row.get(12),
+                    is_admin: row.get(13),
+                    is_active: row.get(14),
+                    created_at: row.get(15),
+                    updated_at: row.get(16),
+                },
+            })
+            .collect();
+
+        Ok(grants)
+    }
+
+    /// Change the level of a user access grant
+    pub async fn update_user_access_grant(
+        &self,
+        grant_id: Uuid,
+        permission_level: AccessGrantLevel,
+    ) -> Result<Option<UserAccessGrant>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "UPDATE user_access_grants SET permission_level = $1, updated_at = NOW() 
+                 WHERE id = $2 
+                 RETURNING id, grantor_user_id, grantee_user_id, starts_at, expires_at, created_at, updated_at, status, permission_level",
+                &[&permission_level.to_string(), &grant_id],
+            )
+            .await?;
+
+        Ok(rows.first().map(|row| UserAccessGrant {
+            id: row.get(0),
+            grantor_user_id: row.get(1),
+            grantee_user_id: row.get(2),
+            permission_level: row
+                .get::<_, String>(8)
+                .parse()
+                .unwrap_or(AccessGrantLevel::View),
+            status: row
+                .get::<_, String>(7)
+                .parse()
+                .unwrap_or(ShareStatus::Pending),
+            starts_at: row.get(3),
+            expires_at: row.get(4),
+            created_at: row.get(5),
+            updated_at: row.get(6),
+        }))
+    }
+
+    /// Delete a user access grant
+    pub async fn delete_user_access_grant(
+        &self,
+        grant_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute("DELETE FROM user_access_grants WHERE id = $1", &[&grant_id])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Accept or decline a pending All Access grant on behalf of its grantee
+    /// Returns `None` if the user has no pending grant with this ID
+    pub async fn respond_to_user_access_grant(
+        &self,
+        grant_id: Uuid,
+        user_id: Uuid,
+        status: ShareStatus,
+    ) -> Result<Option<UserAccessGrant>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "UPDATE user_access_grants SET status = $1, updated_at = NOW() 
+                 WHERE id = $2 AND grantee_user_id = $3 AND status = 'pending' 
+                 RETURNING id, grantor_user_id, grantee_user_id, starts_at, expires_at, created_at, updated_at, status, permission_level",
+                &[&status.to_string(), &grant_id, &user_id],
+            )
+            .await?;
+
+        Ok(rows.first().map(|row| UserAccessGrant {
+            id: row.get(0),
+            grantor_user_id: row.get(1),
+            grantee_user_id: row.get(2),
+            permission_level: row
+                .get::<_, String>(8)
+                .parse()
+                .unwrap_or(AccessGrantLevel::View),
+            status: row
+                .get::<_, String>(7)
+                .parse()
+                .unwrap_or(ShareStatus::Pending),
+            starts_at: row.get(3),
+            expires_at: row.get(4),
+            created_at: row.get(5),
+            updated_at: row.get(6),
+        }))
+    }
+
+    /// Get a user access grant by ID
+    pub async fn get_user_access_grant_by_id(
+        &self,
+        grant_id: Uuid,
+    ) -> Result<Option<UserAccessGrant>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, grantor_user_id, grantee_user_id, starts_at, expires_at, created_at, updated_at, status, permission_level 
+                 FROM user_access_grants WHERE id = $1",
+                &[&grant_id],
+            )
+            .await?;
+
+        if let Some(row) = rows.first() {
+            Ok(Some(UserAccessGrant {
+                id: row.get(0),
+                grantor_user_id: row.get(1),
+                grantee_user_id: row.get(2),
+                permission_level: row
+                    .get::<_, String>(8)
+                    .parse()
+                    .unwrap_or(AccessGrantLevel::View),
+                status: row
+                    .get::<_, String>(7)
+                    .parse()
+                    .unwrap_or(ShareStatus::Pending),
+                starts_at: row.get(3),
+                expires_at: row.get(4),
+                created_at: row.get(5),
+                updated_at: row.get(6),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // ==================== Ownership Transfer Operations ====================
+
+    /// Transfer ownership of an inventory from one user to another
+    /// This operation:
+    /// 1. Updates the inventory's `user_id` to the new owner
+    /// 2. Removes all existing shares for the inventory (new owner controls sharing)
+    /// 3. The previous owner loses all access
+    pub async fn transfer_inventory_ownership(
+        &self,
+        inventory_id: i32,
+        from_user_id: Uuid,
+        to_user_id: Uuid,
+    ) -> Result<(i64, i64), Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+
+        // Start a transaction for atomic operation
+        let transaction = client.transaction().await?;
+
+        // Verify the inventory exists and is owned by from_user_id
+        let verify_result = transaction
+            .query_opt(
+                "SELECT id FROM inventories WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
+                &[&inventory_id, &from_user_id],
+            )
+            .await?;
+
+        if verify_result.is_none() {
+            return Err("Inventory not found or you are not the owner".into());
+        }
+
+        // Verify the target user exists
+        let target_user = transaction
+            .query_opt(
+                "SELECT id FROM users WHERE id = $1 AND is_active = true",
+                &[&to_user_id],
+            )
+            .await?;
+
+        if target_user.is_none() {
+            return Err("Target user not found or is inactive".into());
+        }
+
+        // Count items that will be transferred (for reporting)
+        let items_count: i64 = transaction
+            .query_one(
+                "SELECT COUNT(*) FROM items WHERE inventory_id = $1 AND deleted_at IS NULL",
+                &[&inventory_id],
+            )
+            .await?
+            .get(0);
+
+        // Transfer ownership by updating user_id
+        transaction
+            .execute(
+                "UPDATE inventories SET user_id = $1, updated_at = NOW() WHERE id = $2",
+                &[&to_user_id, &inventory_id],
+            )
+            .await?;
+
+        // Remove all existing shares for this inventory (new owner will manage sharing)
+        let shares_removed = transaction
+            .execute(
+                "DELETE FROM inventory_shares WHERE inventory_id = $1",
+                &[&inventory_id],
+            )
+            .await?
+            + transaction
+                .execute(
+                    "DELETE FROM inventory_group_shares WHERE inventory_id = $1",
+                    &[&inventory_id],
+                )
+                .await?;
+
+        // Commit the transaction
+        transaction.commit().await?;
+
+        info!(
+            "Transferred ownership of inventory {} from {:?} to {:?}. Items: {}, Shares removed: {}",
+            inventory_id, from_user_id, to_user_id, items_count, shares_removed
+        );
+
+        // Safe cast: shares_removed is clamped to i64::MAX, preventing wrap
+        #[allow(
+            clippy::cast_possible_wrap,
+            reason = "Value is clamped to i64::MAX preventing wrap"
+        )]
+        let shares_removed_i64 = shares_removed.min(i64::MAX as u64) as i64;
+        Ok((items_count, shares_removed_i64))
+    }
+
+    // ==================== Recovery Codes Methods ====================
+
+    /// Store recovery codes for a user (deletes any existing codes first)
+    pub async fn store_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        // Delete any existing recovery codes for this user
+        client
+            .execute("DELETE FROM recovery_codes WHERE user_id = $1", &[&user_id])
+            .await?;
+
+        // Insert new codes
+        for code_hash in code_hashes {
+            client
+                .execute(
+                    "INSERT INTO recovery_codes (user_id, code_hash) VALUES ($1, $2)",
+                    &[&user_id, &code_hash],
+                )
+                .await?;
+        }
+
+        // Update user's recovery codes timestamp and reset confirmation
+        client
+            .execute(
+                "UPDATE users SET recovery_codes_generated_at = NOW(), recovery_codes_confirmed = false WHERE id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        info!("Stored {} recovery codes for user {}", 10, user_id);
+        Ok(())
+    }
+
+    /// Confirm that user has saved their recovery codes
+    pub async fn confirm_recovery_codes(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE users SET recovery_codes_confirmed = true WHERE id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        info!("User {} confirmed saving recovery codes", user_id);
+        Ok(())
+    }
+
+    /// Get all unused recovery code hashes for a user (for verification)
+    pub async fn get_unused_recovery_codes(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<(Uuid, String)>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, code_hash FROM recovery_codes WHERE user_id = $1 AND is_used = false",
+                &[&user_id],
+            )
+            .await?;
+
+        let codes: Vec<(Uuid, String)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+
+        Ok(codes)
+    }
+
+    /// Mark a recovery code as used
+    pub async fn mark_recovery_code_used(
+        &self,
+        code_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE recovery_codes SET is_used = true, used_at = NOW() WHERE id = $1",
+                &[&code_id],
+            )
+            .await?;
+
+        info!("Marked recovery code {} as used", code_id);
+        Ok(())
+    }
+
+    /// Get count of unused recovery codes for a user
+    pub async fn get_unused_recovery_codes_count(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "SELECT COUNT(*)::int4 FROM recovery_codes WHERE user_id = $1 AND is_used = false",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    /// Get recovery codes status for a user
+    pub async fn get_recovery_codes_status(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(bool, bool, i32, Option<DateTime<Utc>>), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        // Get user info
+        let user_row = client
+            .query_one(
+                "SELECT recovery_codes_generated_at, COALESCE(recovery_codes_confirmed, false) FROM users WHERE id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        let generated_at: Option<DateTime<Utc>> = user_row.get(0);
+        let confirmed: bool = user_row.get(1);
+
+        // Get unused count
+        let count_row = client
+            .query_one(
+                "SELECT COUNT(*)::int4 FROM recovery_codes WHERE user_id = $1 AND is_used = false",
+                &[&user_id],
+            )
+            .await?;
+
+        let unused_count: i32 = count_row.get(0);
+        let has_codes = unused_count > 0;
+
+        Ok((has_codes, confirmed, unused_count, generated_at))
+    }
+
+    // ==================== Password Reset Token Operations ====================
+
+    /// Store a new reset token for a user, replacing any link not yet redeemed
+    pub async fn create_password_reset_token(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        created_by: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        transaction
+            .execute(
+                "DELETE FROM password_reset_tokens WHERE user_id = $1 AND used_at IS NULL",
+                &[&user_id],
+            )
+            .await?;
+        transaction
+            .execute(
+                "INSERT INTO password_reset_tokens (user_id, token_hash, created_by, expires_at)
+                 VALUES ($1, $2, $3, $4)",
+                &[&user_id, &token_hash, &created_by, &expires_at],
+            )
+            .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Redeem a reset token: set the new password and sign the user out everywhere.
+    /// Returns the user's id, or `None` if the token is unknown, used, expired or
+    /// belongs to a deactivated account.
+    pub async fn redeem_password_reset_token(
+        &self,
+        token_hash: &str,
+        password_hash: &str,
+    ) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        // Marking the token used in the same statement that checks it keeps
+        // concurrent redemptions from both succeeding
+        let Some(row) = transaction
+            .query_opt(
+                "UPDATE password_reset_tokens t SET used_at = NOW()
+                 FROM users u
+                 WHERE u.id = t.user_id AND u.is_active
+                   AND t.token_hash = $1 AND t.used_at IS NULL AND t.expires_at > NOW()
+                 RETURNING t.user_id",
+                &[&token_hash],
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let user_id: Uuid = row.get(0);
+
+        transaction
+            .execute(
+                "UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2",
+                &[&password_hash, &user_id],
+            )
+            .await?;
+        transaction
+            .execute(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE user_id = $1 AND revoked_at IS NULL",
+                &[&user_id],
+            )
+            .await?;
+
+        transaction.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    /// Remove reset tokens past their expiry, redeemed or not
+    pub async fn delete_expired_password_reset_tokens(
+        &self,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                "DELETE FROM password_reset_tokens WHERE expires_at < NOW()",
+                &[],
+            )
+            .await?;
+
+        Ok(rows_affected)
+    }
+
+    // ==================== Invitation Operations ====================
+
+    /// Columns selected for an [`Invitation`], see [`Self::row_to_invitation`]
+    const INVITATION_COLUMNS: &'static str =
+        "i.id, i.invited_by, u.username, i.is_admin, i.inventory_id, inv.name, i.permission_level,
+         i.grant_all_access, i.created_at, i.expires_at, i.accepted_at, i.accepted_by
+         FROM user_invitations i
+         JOIN users u ON u.id = i.invited_by
+         LEFT JOIN inventories inv ON inv.id = i.inventory_id";
+
+    /// Map a row selected with [`Self::INVITATION_COLUMNS`] to an [`Invitation`]
+    fn row_to_invitation(row: &tokio_postgres::Row) -> Invitation {
+        let permission_level: Option<String> = row.get(6);
+        Invitation {
+            id: row.get(0),
+            invited_by: row.get(1),
+            invited_by_username: row.get(2),
+            is_admin: row.get(3),
+            inventory_id: row.get(4),
+            inventory_name: row.get(5),
+            permission_level: permission_level.and_then(|p| p.parse().ok()),
+            grant_all_access: row.get(7),
+            created_at: row.get(8),
+            expires_at: row.get(9),
+            accepted_at: row.get(10),
+            accepted_by: row.get(11),
+        }
+    }
+
+    /// Store a new invitation
+    ///
+    /// The request is stored as given, so callers should check the inviter may
+    /// grant the role, share and All Access it carries.
+    pub async fn create_invitation(
+        &self,
+        token_hash: &str,
+        invited_by: Uuid,
+        request: &CreateInvitationRequest,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Invitation, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+        let permission_level = request.permission_level.map(|p| p.to_string());
+
+        let row = client
+            .query_one(
+                "INSERT INTO user_invitations
+                    (token_hash, invited_by, is_admin, inventory_id, permission_level, grant_all_access, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 RETURNING id",
+                &[
+                    &token_hash,
+                    &invited_by,
+                    &request.is_admin,
+                    &request.inventory_id,
+                    &permission_level,
+                    &request.grant_all_access,
+                    &expires_at,
+                ],
+            )
+            .await?;
+
+        self.get_invitation(row.get(0))
+            .await?
+            .ok_or_else(|| "Invitation disappeared after insert".into())
+    }
+
+    /// Get an invitation by ID
+    pub async fn get_invitation(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<Invitation>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                &format!("SELECT {} WHERE i.id = $1", Self::INVITATION_COLUMNS),
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_invitation))
+    }
+
+    /// Get the pending (unaccepted, unexpired) invitation with this token hash
+    pub async fn get_pending_invitation_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<Invitation>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                &format!(
+                    "SELECT {} WHERE i.token_hash = $1 AND i.accepted_at IS NULL AND i.expires_at > NOW()",
+                    Self::INVITATION_COLUMNS
+                ),
+                &[&token_hash],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_invitation))
+    }
+
+    /// List pending invitations, newest first, optionally only those one user sent
+    pub async fn get_pending_invitations(
+        &self,
+        invited_by: Option<Uuid>,
+    ) -> Result<Vec<Invitation>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} WHERE i.accepted_at IS NULL AND i.expires_at > NOW()
+                       AND ($1::uuid IS NULL OR i.invited_by = $1)
+                     ORDER BY i.created_at DESC",
+                    Self::INVITATION_COLUMNS
+                ),
+                &[&invited_by],
+            )
+            .await?;
+
+        Ok(rows.iter().map(Self::row_to_invitation).collect())
+    }
+
+    /// Revoke an invitation. Returns false if it does not exist.
+    pub async fn delete_invitation(&self, id: Uuid) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute("DELETE FROM user_invitations WHERE id = $1", &[&id])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Accept an invitation: create the account with the invitation's role and
+    /// apply its inventory share and All Access grant. Returns `None` if the
+    /// token is unknown, already accepted or expired.
+    pub async fn accept_invitation(
+        &self,
+        token_hash: &str,
+        username: &str,
+        full_name: &str,
+        password_hash: &str,
+    ) -> Result<Option<User>, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        // Marking the invitation accepted in the same statement that checks it
+        // keeps concurrent acceptances from both succeeding
+        let Some(invitation) = transaction
+            .query_opt(
+                "UPDATE user_invitations SET accepted_at = NOW()
+                 WHERE token_hash = $1 AND accepted_at IS NULL AND expires_at > NOW()
+                 RETURNING id, invited_by, is_admin, inventory_id, permission_level, grant_all_access",
+                &[&token_hash],
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let invitation_id: Uuid = invitation.get(0);
+        let invited_by: Uuid = invitation.get(1);
+        let is_admin: bool = invitation.get(2);
+        let inventory_id: Option<i32> = invitation.get(3);
+        let permission_level: Option<String> = invitation.get(4);
+        let grant_all_access: bool = invitation.get(5);
+
+        let row = transaction
+            .query_one(
+                "INSERT INTO users (username, full_name, password_hash, is_admin, is_active)
+                 VALUES ($1, $2, $3, $4, true)
+                 RETURNING id, username, full_name, password_hash, is_admin, is_active, created_at, updated_at",
+                &[&username, &full_name, &password_hash, &is_admin],
+            )
+            .await?;
+        let user = User {
+            id: row.get(0),
+            username: row.get(1),
+            full_name: row.get(2),
+            password_hash: row.get(3),
+            is_admin: row.get(4),
+            is_active: row.get(5),
+            created_at: row.get(6),
+            updated_at: row.get(7),
+            recovery_codes_generated_at: None,
+            recovery_codes_confirmed: false,
+        };
+
+        transaction
+            .execute(
+                "UPDATE user_invitations SET accepted_by = $2 WHERE id = $1",
+                &[&invitation_id, &user.id],
+            )
+            .await?;
+        transaction
+            .execute(
+                "INSERT INTO user_settings (user_id) VALUES ($1)",
+                &[&user.id],
+            )
+            .await?;
+
+        if let (Some(inventory_id), Some(permission_level)) = (inventory_id, permission_level) {
+            transaction
+                .execute(
+                    "INSERT INTO inventory_shares (inventory_id, shared_with_user_id, shared_by_user_id, permission_level)
+                     VALUES ($1, $2, $3, $4)",
+                    &[&inventory_id, &user.id, &invited_by, &permission_level],
+                )
+                .await?;
+        }
+
+        if grant_all_access {
+            transaction
+                .execute(
+                    "INSERT INTO user_access_grants (grantor_user_id, grantee_user_id) VALUES ($1, $2)",
+                    &[&invited_by, &user.id],
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+        info!(
+            "Created user {} (ID: {}) from invitation {}",
+            user.username, user.id, invitation_id
+        );
+        Ok(Some(user))
+    }
+
+    /// Remove invitations that expired without being accepted
+    pub async fn delete_expired_invitations(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                "DELETE FROM user_invitations WHERE accepted_at IS NULL AND expires_at < NOW()",
+                &[],
+            )
+            .await?;
+
+        Ok(rows_affected)
+    }
+
+    // ==================== Inventory Reporting Operations ====================
+
+    /// Check if user has access to a specific inventory
+    pub async fn check_inventory_access(
+        &self,
+        user_id: Uuid,
+        inventory_id: i32,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "SELECT COUNT(*)::int8 as count FROM inventories 
+                 WHERE id = $1 AND deleted_at IS NULL AND (
+                     user_id = $2
+                     OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $2 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                     OR id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $2 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()))
+                     OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $2 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                 )",
+                &[&inventory_id, &user_id],
+            )
+            .await?;
+
+        let count: i64 = row.get(0);
+        Ok(count > 0)
+    }
+
+    /// Retrieves filtered inventory items for report generation.
+    ///
+    /// This method enforces row-level security by only returning items from inventories
+    /// that the user owns or has been granted access to via shares or access grants.
+    ///
+    /// # Arguments
+    /// * `request` - Filter parameters (`inventory_id`, `category`, dates, prices, etc.)
+    /// * `user_id` - UUID of the authenticated user making the request
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Item>)` - Filtered and sorted items accessible to the user
+    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
+    pub async fn get_inventory_report_data(
+        &self,
+        request: crate::models::InventoryReportRequest,
+        user_id: Uuid,
+    ) -> Result<Vec<crate::models::Item>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        // Build dynamic WHERE clause based on filters
+        let mut conditions = vec![
+            format!("i.inventory_id IN (
+                SELECT id FROM inventories 
+                WHERE user_id = $1
+                   OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted' AND {EXPORTABLE_SHARE_SQL})
+                   OR id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $1 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()) AND {EXPORTABLE_GROUP_SHARE_SQL})
+                   OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+            )"),
+            item_visibility_sql(ItemViewer::User(user_id), "i", "$1"),
+        ];
+        let mut param_index = 2;
+
+        // Build parameters vector
+        let mut params: Vec<Box<dyn tokio_postgres::types::ToSql + Sync>> = vec![Box::new(user_id)];
+
+        // Add optional filters
+        if let Some(inv_id) = request.inventory_id {
+            conditions.push(format!("i.inventory_id = ${param_index}"));
+            params.push(Box::new(inv_id));
+            param_index += 1;
+        }
+
+        if let Some(ref category) = request.category {
+            conditions.push(format!("i.category = ${param_index}"));
+            params.push(Box::new(category.clone()));
+            param_index += 1;
+        }
+
+        if let Some(ref location) = request.location {
+            let pattern = format!("%{}%", escape_like_pattern(location));
+            conditions.push(format!("i.location ILIKE ${param_index}"));
+            params.push(Box::new(pattern));
+            param_index += 1;
+        }
+
+        if let Some(ref from_date) = request.from_date {
+            conditions.push(format!("i.purchase_date >= ${param_index}::date"));
+            params.push(Box::new(from_date.clone()));
+            param_index += 1;
+        }
+
+        if let Some(ref to_date) = request.to_date {
+            conditions.push(format!("i.purchase_date <= ${param_index}::date"));
+            params.push(Box::new(to_date.clone()));
+            param_index += 1;
+        }
+
+        if let Some(min_price) = request.min_price {
+            conditions.push(format!("i.purchase_price >= ${param_index}::float8"));
+            params.push(Box::new(min_price));
+            param_index += 1;
+        }
+
+        if let Some(max_price) = request.max_price {
+            conditions.push(format!("i.purchase_price <= ${param_index}::float8"));
+            params.push(Box::new(max_price));
+            #[allow(unused_assignments)]
+            {
+                param_index += 1;
+            }
+        }
+
+        // Build ORDER BY clause
+        let order_by = build_order_by(&request);
+
+        let query = format!(
+            "SELECT i.id, i.inventory_id, i.name, i.description, i.category, i.location,
+                    i.purchase_date::text, i.purchase_price::float8, i.warranty_expiry::text,
+                    i.notes, i.quantity, i.created_at, i.updated_at, i.visibility
+             FROM items i
+             WHERE {}
+             ORDER BY {}",
+            conditions.join(" AND "),
+            order_by
+        );
+
+        // Convert params to references for query
+        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let rows = client.query(&query, &params_refs).await?;
+
+        let items: Vec<crate::models::Item> = rows
+            .iter()
+            .map(|row| crate::models::Item {
+                id: Some(row.get(0)),
+                inventory_id: row.get(1),
+                name: row.get(2),
+                description: row.get(3),
+                category: row.get(4),
+                location: row.get(5),
+                purchase_date: row.get::<_, Option<String>>(6),
+                purchase_price: row.get(7),
+                warranty_expiry: row.get::<_, Option<String>>(8),
+                notes: row.get(9),
+                quantity: row.get(10),
+                created_at: row.get::<_, Option<DateTime<Utc>>>(11),
+                updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+                visibility: row
+                    .get::<_, String>(13)
+                    .parse()
+                    .unwrap_or(ItemVisibility::Owner),
+            })
+            .collect();
+
+        info!(
+            "Generated report with {} items for user {}",
+            items.len(),
+            user_id
+        );
+        Ok(items)
+    }
+
+    /// Calculates aggregated statistics across inventory items.
+    ///
+    /// Computes total item count, total value (price × quantity), average values,
+    /// and date ranges for items. When `inventory_id` is None, aggregates across
+    /// all inventories the user has access to.
+    ///
+    /// # Arguments
+    /// * `inventory_id` - Optional inventory ID to limit statistics to one inventory
+    /// * `user_id` - UUID of the authenticated user making the request
+    ///
+    /// # Returns
+    /// * `Ok(InventoryStatistics)` - Aggregated statistics
+    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
+    pub async fn get_inventory_statistics(
+        &self,
+        inventory_id: Option<i32>,
+        user_id: Uuid,
+    ) -> Result<crate::models::InventoryStatistics, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let (query, params): (String, Vec<Box<dyn tokio_postgres::types::ToSql + Sync>>) =
+            if let Some(inv_id) = inventory_id {
+                (
+                    format!(
+                        "SELECT 
+                    COUNT(*)::int8 as total_items,
+                    COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as total_value,
+                    COALESCE(SUM(quantity), 0)::int8 as total_quantity,
+                    COUNT(DISTINCT category)::int8 as category_count,
+                    1::int8 as inventories_count,
+                    MIN(purchase_date)::text as oldest_item_date,
+                    MAX(purchase_date)::text as newest_item_date,
+                    COALESCE(AVG(purchase_price::float8), 0.0)::float8 as average_item_value
+                 FROM items
+                 WHERE inventory_id = $1 AND {visible}",
+                        visible = item_visibility_sql(ItemViewer::User(user_id), "items", "$2")
+                    ),
+                    vec![Box::new(inv_id), Box::new(user_id)],
+                )
+            } else {
+                (
+                format!("SELECT 
+                    COUNT(*)::int8 as total_items,
+                    COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as total_value,
+                    COALESCE(SUM(quantity), 0)::int8 as total_quantity,
+                    COUNT(DISTINCT category)::int8 as category_count,
+                    COUNT(DISTINCT inventory_id)::int8 as inventories_count,
+                    MIN(purchase_date)::text as oldest_item_date,
+                    MAX(purchase_date)::text as newest_item_date,
+                    COALESCE(AVG(purchase_price::float8), 0.0)::float8 as average_item_value
+                 FROM items
+                 WHERE inventory_id IN (
+                     SELECT id FROM inventories 
+                     WHERE user_id = $1
+                        OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted' AND {EXPORTABLE_SHARE_SQL})
+                        OR id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $1 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()) AND {EXPORTABLE_GROUP_SHARE_SQL})
+                        OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                 ) AND {visible}",
+                visible = item_visibility_sql(ItemViewer::User(user_id), "items", "$1")
+            ),
+                vec![Box::new(user_id)],
+            )
+            };
+
+        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let row = client.query_one(&query, &params_refs).await?;
+
+        let statistics = crate::models::InventoryStatistics {
+            total_items: row.get(0),
+            total_value: row.get(1),
+            total_quantity: row.get(2),
+            category_count: row.get(3),
+            inventories_count: row.get(4),
+            oldest_item_date: row.get(5),
+            newest_item_date: row.get(6),
+            average_item_value: row.get(7),
+        };
+
+        info!("Generated statistics for user {}", user_id);
+        Ok(statistics)
+    }
+
+    /// Generates category breakdown with item counts and value percentages.
+    ///
+    /// Groups items by category and calculates total values, quantities, and
+    /// percentage of total inventory value for each category. Uncategorized
+    /// items are grouped under "Uncategorized".
+    ///
+    /// # Arguments
+    /// * `inventory_id` - Optional inventory ID to limit breakdown to one inventory
+    /// * `user_id` - UUID of the authenticated user making the request
+    ///
+    /// # Returns
+    /// * `Ok(Vec<CategoryBreakdown>)` - Breakdown sorted by total value descending
+    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
+    pub async fn get_category_breakdown(
+        &self,
+        inventory_id: Option<i32>,
+        user_id: Uuid,
+    ) -> Result<Vec<crate::models::CategoryBreakdown>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let (query, params): (String, Vec<Box<dyn tokio_postgres::types::ToSql + Sync>>) =
+            if let Some(inv_id) = inventory_id {
+                (
+                format!("WITH totals AS (
+                     SELECT COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as grand_total
+                     FROM items
+                     WHERE inventory_id = $1 AND {totals_visible}
+                 )
+                 SELECT 
+                     COALESCE(i.category, 'Uncategorized') as category,
+                     COUNT(*)::int8 as item_count,
+                     COALESCE(SUM(i.quantity), 0)::int8 as total_quantity,
+                     COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0)::float8 as total_value,
+                     CASE 
+                         WHEN t.grand_total > 0 THEN 
+                             (COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0) / t.grand_total * 100.0)::float8
+                         ELSE 0.0::float8
+                     END as percentage
+                 FROM items i
+                 CROSS JOIN totals t
+                 WHERE i.inventory_id = $1 AND {visible}
+                 GROUP BY i.category, t.grand_total
+                 ORDER BY total_value DESC",
+                totals_visible = item_visibility_sql(ItemViewer::User(user_id), "items", "$2"),
+                visible = item_visibility_sql(ItemViewer::User(user_id), "i", "$2")
+            ),
+                vec![Box::new(inv_id), Box::new(user_id)],
+            )
+            } else {
+                (
+                format!("WITH totals AS (
+                     SELECT COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as grand_total
+                     FROM items
+                     WHERE inventory_id IN (
+                         SELECT id FROM inventories 
+                         WHERE user_id = $1
+                            OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted' AND {EXPORTABLE_SHARE_SQL})
+                            OR id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $1 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()) AND {EXPORTABLE_GROUP_SHARE_SQL})
+                            OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                     ) AND {totals_visible}
+                 )
+                 SELECT 
+                     COALESCE(i.category, 'Uncategorized') as category,
+                     COUNT(*)::int8 as item_count,
+                     COALESCE(SUM(i.quantity), 0)::int8 as total_quantity,
+                     COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0)::float8 as total_value,
+                     CASE 
+                         WHEN t.grand_total > 0 THEN 
+                             (COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0) / t.grand_total * 100.0)::float8
+                         ELSE 0.0::float8
+                     END as percentage
+                 FROM items i
+                 CROSS JOIN totals t
+                 WHERE i.inventory_id IN (
+                     SELECT id FROM inventories 
+                     WHERE user_id = $1
+                        OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted' AND {EXPORTABLE_SHARE_SQL})
+                        OR id IN (SELECT gs.inventory_id FROM inventory_group_shares gs JOIN user_group_members gm ON gm.group_id = gs.group_id WHERE gm.user_id = $1 AND (gs.starts_at IS NULL OR gs.starts_at <= NOW()) AND (gs.expires_at IS NULL OR gs.expires_at > NOW()) AND {EXPORTABLE_GROUP_SHARE_SQL})
+                        OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1 AND (starts_at IS NULL OR starts_at <= NOW()) AND (expires_at IS NULL OR expires_at > NOW()) AND status = 'accepted')
+                 ) AND {visible}
+                 GROUP BY i.category, t.grand_total
+                 ORDER BY total_value DESC",
+                totals_visible = item_visibility_sql(ItemViewer::User(user_id), "items", "$1"),
+                visible = item_visibility_sql(ItemViewer::User(user_id), "i", "$1")
+            ),
+                vec![Box::new(user_id)],
+            )
+            };
+
+        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let rows = client.query(&query, &params_refs).await?;
+
+        let breakdown = rows
+            .iter()
+            .map(|row| crate::models::CategoryBreakdown {
+                category: row.get(0),
+                item_count: row.get(1),
+                total_quantity: row.get(2),
+                total_value: row.get(3),
+                percentage_of_total: row.get(4),
+            })
+            .collect();
+
+        info!("Generated category breakdown for user {}", user_id);
+        Ok(breakdown)
+    }
+
+    // ==================== Backup & Restore Methods ====================
+
+    /// Export all database tables as JSON values for backup
+    pub async fn export_all_data(
+        &self,
+    ) -> Result<BackupDatabaseContent, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        // Helper closure to build the JSON export query for a table
+        let build_export_query = |table: &str| {
+            format!("SELECT COALESCE(jsonb_agg(to_jsonb(t)), '[]'::jsonb) FROM {table} t")
+        };
+
+        let users: serde_json::Value = client
+            .query_one(&build_export_query("users"), &[])
+            .await?
+            .get(0);
+        let inventories: serde_json::Value = client
+            .query_one(&build_export_query("inventories"), &[])
+            .await?
+            .get(0);
+        let items: serde_json::Value = client
+            .query_one(&build_export_query("items"), &[])
+            .await?
+            .get(0);
+        let categories: serde_json::Value = client
+            .query_one(&build_export_query("categories"), &[])
+            .await?
+            .get(0);
+        let tags: serde_json::Value = client
+            .query_one(&build_export_query("tags"), &[])
+            .await?
+            .get(0);
+        let item_tags: serde_json::Value = client
+            .query_one(&build_export_query("item_tags"), &[])
+            .await?
+            .get(0);
+        let custom_fields: serde_json::Value = client
+            .query_one(&build_export_query("custom_fields"), &[])
+            .await?
+            .get(0);
+        let item_custom_values: serde_json::Value = client
+            .query_one(&build_export_query("item_custom_values"), &[])
+            .await?
+            .get(0);
+        let organizer_types: serde_json::Value = client
+            .query_one(&build_export_query("organizer_types"), &[])
+            .await?
+            .get(0);
+        let organizer_options: serde_json::Value = client
+            .query_one(&build_export_query("organizer_options"), &[])
+            .await?
+            .get(0);
+        let item_organizer_values: serde_json::Value = client
+            .query_one(&build_export_query("item_organizer_values"), &[])
+            .await?
+            .get(0);
+        let item_history: serde_json::Value = client
+            .query_one(&build_export_query("item_history"), &[])
+            .await?
+            .get(0);
+        let user_settings: serde_json::Value = client
+            .query_one(&build_export_query("user_settings"), &[])
+            .await?
+            .get(0);
+        let inventory_roles: serde_json::Value = client
+            .query_one(&build_export_query("inventory_roles"), &[])
+            .await?
+            .get(0);
+        let inventory_shares: serde_json::Value = client
+            .query_one(&build_export_query("inventory_shares"), &[])
+            .await?
+            .get(0);
+        let user_access_grants: serde_json::Value = client
+            .query_one(&build_export_query("user_access_grants"), &[])
+            .await?
+            .get(0);
+        let recovery_codes: serde_json::Value = client
+            .query_one(&build_export_query("recovery_codes"), &[])
+            .await?
+            .get(0);
+        let password_reset_tokens: serde_json::Value = client
+            .query_one(&build_export_query("password_reset_tokens"), &[])
+            .await?
+            .get(0);
+        let user_identity_links: serde_json::Value = client
+            .query_one(&build_export_query("user_identity_links"), &[])
+            .await?
+            .get(0);
+        let api_tokens: serde_json::Value = client
+            .query_one(&build_export_query("api_tokens"), &[])
+            .await?
+            .get(0);
+        let webauthn_credentials: serde_json::Value = client
+            .query_one(&build_export_query("webauthn_credentials"), &[])
+            .await?
+            .get(0);
+        let user_invitations: serde_json::Value = client
+            .query_one(&build_export_query("user_invitations"), &[])
+            .await?
+            .get(0);
+        let inventory_share_links: serde_json::Value = client
+            .query_one(&build_export_query("inventory_share_links"), &[])
+            .await?
+            .get(0);
+        let user_groups: serde_json::Value = client
+            .query_one(&build_export_query("user_groups"), &[])
+            .await?
+            .get(0);
+        let user_group_members: serde_json::Value = client
+            .query_one(&build_export_query("user_group_members"), &[])
+            .await?
+            .get(0);
+        let inventory_group_shares: serde_json::Value = client
+            .query_one(&build_export_query("inventory_group_shares"), &[])
+            .await?
+            .get(0);
+
+        info!("Successfully exported all database tables for backup");
+
+        Ok(BackupDatabaseContent {
+            users,
+            inventories,
+            items,
+            categories,
+            tags,
+            item_tags,
+            custom_fields,
+            item_custom_values,
+            organizer_types,
+            organizer_options,
+            item_organizer_values,
+            item_history,
+            user_settings,
+            inventory_roles,
+            inventory_shares,
+            user_access_grants,
+            recovery_codes,
+            password_reset_tokens,
+            user_identity_links,
+            api_tokens,
+            webauthn_credentials,
+            user_invitations,
+            inventory_share_links,
+            user_groups,
+            user_group_members,
+            inventory_group_shares,
+        })
+    }
+
+    /// Import all database tables from backup data (within a transaction)
+    pub async fn import_all_data(
+        &self,
+        data: &BackupDatabaseContent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        // Defer foreign key constraint checks until commit
+        transaction
+            .execute("SET CONSTRAINTS ALL DEFERRED", &[])
+            .await?;
+
+        // Truncate all tables in reverse dependency order
+        let truncate_order = [
+            "inventory_group_shares",
+            "user_group_members",
+            "user_groups",
+            "inventory_share_links",
+            "user_invitations",
+            "webauthn_credentials",
+            "api_tokens",
+            "user_identity_links",
+            "password_reset_tokens",
+            "recovery_codes",
+            "user_access_grants",
+            "inventory_shares",
+            "inventory_roles",
+            "user_settings",
+            "item_history",
+            "item_organizer_values",
+            "organizer_options",
+            "organizer_types",
+            "item_custom_values",
+            "custom_fields",
+            "item_tags",
+            "tags",
+            "categories",
+            "items",
+            "inventories",
+            "users",
+        ];
+
+        for table in &truncate_order {
+            let query = format!("TRUNCATE TABLE {table} RESTART IDENTITY CASCADE");
+            transaction.execute(query.as_str(), &[]).await?;
+        }
+
+        // Import tables in dependency order
+        for (table, rows_json) in data.tables() {
+            if let Some(rows) = rows_json.as_array() {
+                for row in rows {
+                    let query = format!(
+                        "INSERT INTO {table} SELECT * FROM jsonb_populate_record(NULL::{table}, $1)"
+                    );
+                    transaction.execute(query.as_str(), &[row]).await?;
+                }
+            }
+        }
+
+        // Reset sequences for tables with serial/identity columns
+        let sequence_tables = [
+            "items",
+            "inventories",
+            "categories",
+            "tags",
+            "custom_fields",
+            "item_custom_values",
+            "item_tags",
+            "organizer_types",
+            "organizer_options",
+            "item_organizer_values",
+            "item_history",
+        ];
+
+        for table in &sequence_tables {
+            let query = format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), \
+                 COALESCE(MAX(id), 0) + 1, false) FROM {table}"
+            );
+            // Ignore errors for tables that may not have sequences
+            if let Err(e) = transaction.execute(query.as_str(), &[]).await {
+                info!(
+                    "Note: Could not reset sequence for table {}: {} (this may be expected)",
+                    table, e
+                );
+            }
+        }
+
+        transaction.commit().await?;
+        info!("Successfully imported all database tables from backup");
+        Ok(())
+    }
+
+    /// Load backup data into a throwaway schema and report per-table row counts
+    ///
+    /// Each table is cloned from the live schema (`LIKE ... INCLUDING ALL`), so
+    /// column types, NOT NULL, CHECK and UNIQUE constraints are enforced while
+    /// loading. The transaction is always rolled back, leaving live data untouched.
+    pub async fn dry_run_import(
+        &self,
+        data: &BackupDatabaseContent,
+    ) -> Result<Vec<BackupTableCount>, Box<dyn std::error::Error>> {
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+
+        let schema = format!("backup_dry_run_{}", Uuid::new_v4().simple());
+        transaction
+            .execute(format!("CREATE SCHEMA {schema}").as_str(), &[])
+            .await?;
+
+        let mut counts = Vec::with_capacity(BackupDatabaseContent::TABLES.len());
+        for (table, rows_json) in data.tables() {
+            let create =
+                format!("CREATE TABLE {schema}.{table} (LIKE public.{table} INCLUDING ALL)");
+            transaction.execute(create.as_str(), &[]).await?;
+
+            if let Some(rows) = rows_json.as_array() {
+                let insert = format!(
+                    "INSERT INTO {schema}.{table} SELECT * FROM jsonb_populate_record(NULL::{schema}.{table}, $1)"
+                );
+                for row in rows {
+                    transaction.execute(insert.as_str(), &[row]).await?;
+                }
+            }
+
+            let rows: i64 = transaction
+                .query_one(
+                    format!("SELECT COUNT(*) FROM {schema}.{table}").as_str(),
+                    &[],
+                )
+                .await?
+                .get(0);
+            counts.push(BackupTableCount {
+                table: table.to_string(),
+                rows,
+            });
+        }
+
+        transaction.rollback().await?;
+        info!("Dry-run restore completed in temporary schema {}", schema);
+        Ok(counts)
+    }
+
+    // ==================== Selective Restore Methods ====================
+
+    /// Look up which of the given backup user and inventory ids still exist
+    pub async fn get_existing_record_ids(
+        &self,
+        user_ids: &[Uuid],
+        inventory_ids: &[i32],
+    ) -> Result<(HashSet<Uuid>, HashSet<i32>), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let users = client
+            .query("SELECT id FROM users WHERE id = ANY($1)", &[&user_ids])
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        let inventories = client
+            .query(
+                "SELECT id FROM inventories WHERE id = ANY($1)",
+                &[&inventory_ids],
+            )
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+
+        Ok((users, inventories))
+    }
+
+    /// Merge a single inventory (items, organizers, tags, custom field values,
+    /// item history and shares) from a backup
+    ///
+    /// The inventory keeps its original id if that id is free; its items,
+    /// organizers and shares always get new ids. Tags and custom fields that
+    /// still exist are reused, deleted ones are recreated. Nothing in the live
+    /// database is modified or removed. If the original owner no longer exists, ownership
+    /// goes to `fallback_owner_id`.
+    pub async fn restore_inventory_from_backup(
+        &self,
+        data: &BackupDatabaseContent,
+        inventory_id: i32,
+        fallback_owner_id: Uuid,
+    ) -> Result<SelectiveRestoreSummary, Box<dyn std::error::Error>> {
+        let inventory = backup_rows(&data.inventories)
+            .find(|row| backup_i64(row, "id") == Some(i64::from(inventory_id)))
+            .ok_or("Inventory not found in backup")?;
+
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+        let mut ctx = BackupMergeContext::load(&transaction).await?;
+
+        let owner_id = match backup_uuid(inventory, "user_id") {
+            Some(id) if ctx.live_users.contains(&id) => id,
+            _ => {
+                ctx.summary.warnings.push(format!(
+                    "Original owner of inventory '{}' no longer exists; it was assigned to you",
+                    backup_str(inventory, "name")
+                ));
+                fallback_owner_id
+            },
+        };
+
+        ctx.merge_inventory(&transaction, data, inventory, owner_id)
+            .await?;
+
+        transaction.commit().await?;
+        info!(
+            "Selectively restored inventory {} from backup ({} items)",
+            inventory_id, ctx.summary.items_restored
+        );
+        Ok(ctx.summary)
+    }
+
+    /// Merge one user's data from a backup
+    ///
+    /// Recreates the account (with its original id and settings) if it was deleted,
+    /// merges every inventory the user owned that no longer exists, and restores
+    /// All Access grants and shares involving the user where the other party still
+    /// exists. Existing records are never overwritten.
+    pub async fn restore_user_from_backup(
+        &self,
+        data: &BackupDatabaseContent,
+        user_id: Uuid,
+    ) -> Result<SelectiveRestoreSummary, Box<dyn std::error::Error>> {
+        let user = backup_rows(&data.users)
+            .find(|row| backup_uuid(row, "id") == Some(user_id))
+            .ok_or("User not found in backup")?;
+
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+        let mut ctx = BackupMergeContext::load(&transaction).await?;
+
+        // Recreate the account if it was deleted
+        if !ctx.live_users.contains(&user_id) {
+            let username = backup_str(user, "username");
+            let taken = transaction
+                .query_opt("SELECT id FROM users WHERE username = $1", &[&username])
+                .await?;
+            if taken.is_some() {
+                return Err(format!("Username '{username}' is already taken").into());
+            }
+
+            ctx.insert_row(&transaction, "users", user.clone(), true)
+                .await?
+                .ok_or("Failed to recreate user")?;
+            ctx.live_users.insert(user_id);
+            ctx.summary.user_restored = true;
+        }
+
+        // Inventories the user owned: merge the ones that no longer exist
+        let mut inventory_ids: HashMap<i64, i32> = HashMap::new();
+        for inventory in backup_rows(&data.inventories)
+            .filter(|row| backup_uuid(row, "user_id") == Some(user_id))
+        {
+            let Some(original_id) = backup_i64(inventory, "id") else {
+                continue;
+            };
+            let still_exists = match i32::try_from(original_id) {
+                Ok(id) => transaction
+                    .query_opt(
+                        "SELECT id FROM inventories WHERE id = $1 AND user_id = $2",
+                        &[&id, &user_id],
+                    )
+                    .await?
+                    .map(|row| row.get::<_, i32>(0)),
+                Err(_) => None,
+            };
+
+            if let Some(id) = still_exists {
+                ctx.summary.warnings.push(format!(
+                    "Inventory '{}' still exists and was not restored",
+                    backup_str(inventory, "name")
+                ));
+                inventory_ids.insert(original_id, id);
+            } else {
+                let new_id = ctx
+                    .merge_inventory(&transaction, data, inventory, user_id)
+                    .await?;
+                inventory_ids.insert(original_id, new_id);
+            }
+        }
+
+        // Settings only come back along with a recreated account
+        if ctx.summary.user_restored {
+            for settings in backup_rows(&data.user_settings)
+                .filter(|row| backup_uuid(row, "user_id") == Some(user_id))
+            {
+                let mut settings = settings.clone();
+                let default_inventory = backup_i64(&settings, "default_inventory_id")
+                    .and_then(|id| inventory_ids.get(&id).copied());
+                settings.insert(
+                    "default_inventory_id".to_string(),
+                    default_inventory.map_or(serde_json::Value::Null, serde_json::Value::from),
+                );
+                ctx.insert_row(&transaction, "user_settings", settings, false)
+                    .await?;
+            }
+        }
+
+        // All Access grants in either direction
+        for grant in backup_rows(&data.user_access_grants) {
+            let grantor = backup_uuid(grant, "grantor_user_id");
+            let grantee = backup_uuid(grant, "grantee_user_id");
+            let other = match (grantor, grantee) {
+                (Some(g), Some(other)) | (Some(other), Some(g)) if g == user_id => other,
+                _ => continue,
+            };
+            if !ctx.live_users.contains(&other) {
+                ctx.summary.warnings.push(format!(
+                    "All Access grant with user {other} skipped: user no longer exists"
+                ));
+                continue;
+            }
+            if ctx
+                .insert_row(&transaction, "user_access_grants", grant.clone(), false)
+                .await?
+                .is_some()
+            {
+                ctx.summary.access_grants_restored += 1;
+            }
+        }
+
+        // Shares of other users' inventories that were shared with this user
+        for share in backup_rows(&data.inventory_shares)
+            .filter(|row| backup_uuid(row, "shared_with_user_id") == Some(user_id))
+        {
+            let Some(inventory_id) =
+                backup_i64(share, "inventory_id").and_then(|id| i32::try_from(id).ok())
+            else {
+                continue;
+            };
+            if inventory_ids.contains_key(&i64::from(inventory_id)) {
+                continue;
+            }
+            let shared_by_live = backup_uuid(share, "shared_by_user_id")
+                .is_some_and(|id| ctx.live_users.contains(&id));
+            let inventory_live = transaction
+                .query_opt("SELECT id FROM inventories WHERE id = $1", &[&inventory_id])
+                .await?
+                .is_some();
+            if !shared_by_live || !inventory_live {
+                ctx.summary.warnings.push(format!(
+                    "Share of inventory {inventory_id} skipped: inventory or sharing user no longer exists"
+                ));
+                continue;
+            }
+            let mut share = share.clone();
+            let role_id = match backup_uuid(&share, "role_id") {
+                Some(id) => ctx.resolve_role(&transaction, data, id, None).await?,
+                None => None,
+            };
+            share.insert(
+                "role_id".to_string(),
+                role_id.map_or(serde_json::Value::Null, |id| serde_json::json!(id)),
+            );
+            if ctx
+                .insert_row(&transaction, "inventory_shares", share, false)
+                .await?
+                .is_some()
+            {
+                ctx.summary.shares_restored += 1;
+            }
+        }
 
-        // Build parameters vector
-        let mut params: Vec<Box<dyn tokio_postgres::types::ToSql + Sync>> = vec![Box::new(user_id)];
+        transaction.commit().await?;
+        info!(
+            "Selectively restored user {} from backup ({} inventories)",
+            user_id,
+            ctx.summary.inventories.len()
+        );
+        Ok(ctx.summary)
+    }
 
-        // Add optional filters
-        if let Some(inv_id) = request.inventory_id {
-            conditions.push(format!("i.inventory_id = ${param_index}"));
-            params.push(Box::new(inv_id));
-            param_index += 1;
+    // ==================== TOTP Settings Operations ====================
+
+    /// Create TOTP settings for a user (during setup, before verification)
+    pub async fn create_totp_settings(
+        &self,
+        user_id: Uuid,
+        encrypted_secret: &str,
+    ) -> Result<TotpSettings, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "INSERT INTO user_totp_settings (user_id, totp_secret_encrypted)
+                 VALUES ($1, $2)
+                 ON CONFLICT (user_id) DO UPDATE SET
+                     totp_secret_encrypted = $2,
+                     is_enabled = false,
+                     is_verified = false,
+                     failed_attempts = 0,
+                     last_failed_at = NULL,
+                     updated_at = NOW()
+                 RETURNING id, user_id, totp_secret_encrypted, totp_mode, is_enabled,
+                           is_verified, created_at, updated_at, last_used_at,
+                           failed_attempts, last_failed_at",
+                &[&user_id, &encrypted_secret],
+            )
+            .await?;
+
+        Ok(TotpSettings {
+            id: row.get(0),
+            user_id: row.get(1),
+            totp_secret_encrypted: row.get(2),
+            totp_mode: row.get(3),
+            is_enabled: row.get(4),
+            is_verified: row.get(5),
+            created_at: row.get(6),
+            updated_at: row.get(7),
+            last_used_at: row.get(8),
+            failed_attempts: row.get(9),
+            last_failed_at: row.get(10),
+        })
+    }
+
+    /// Get TOTP settings for a user
+    pub async fn get_totp_settings(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<TotpSettings>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows = client
+            .query(
+                "SELECT id, user_id, totp_secret_encrypted, totp_mode, is_enabled,
+                        is_verified, created_at, updated_at, last_used_at,
+                        failed_attempts, last_failed_at
+                 FROM user_totp_settings WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        if let Some(row) = rows.first() {
+            Ok(Some(TotpSettings {
+                id: row.get(0),
+                user_id: row.get(1),
+                totp_secret_encrypted: row.get(2),
+                totp_mode: row.get(3),
+                is_enabled: row.get(4),
+                is_verified: row.get(5),
+                created_at: row.get(6),
+                updated_at: row.get(7),
+                last_used_at: row.get(8),
+                failed_attempts: row.get(9),
+                last_failed_at: row.get(10),
+            }))
+        } else {
+            Ok(None)
         }
+    }
+
+    /// Enable TOTP after successful verification (set enabled, verified, and mode)
+    pub async fn enable_totp(
+        &self,
+        user_id: Uuid,
+        mode: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE user_totp_settings
+                 SET is_enabled = true, is_verified = true, totp_mode = $2,
+                     failed_attempts = 0, last_failed_at = NULL, updated_at = NOW()
+                 WHERE user_id = $1",
+                &[&user_id, &mode],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Update TOTP mode
+    pub async fn update_totp_mode(
+        &self,
+        user_id: Uuid,
+        mode: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE user_totp_settings SET totp_mode = $2, updated_at = NOW()
+                 WHERE user_id = $1 AND is_enabled = true",
+                &[&user_id, &mode],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Update TOTP last used timestamp
+    pub async fn update_totp_last_used(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE user_totp_settings SET last_used_at = NOW(), updated_at = NOW()
+                 WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Delete TOTP settings (disable TOTP)
+    pub async fn delete_totp_settings(
+        &self,
+        user_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                "DELETE FROM user_totp_settings WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Increment failed TOTP attempts and record timestamp
+    pub async fn increment_totp_failed_attempts(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "UPDATE user_totp_settings
+                 SET failed_attempts = failed_attempts + 1, last_failed_at = NOW()
+                 WHERE user_id = $1
+                 RETURNING failed_attempts",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    /// Reset failed TOTP attempts after successful verification
+    pub async fn reset_totp_failed_attempts(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE user_totp_settings
+                 SET failed_attempts = 0, last_failed_at = NULL
+                 WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    // ==================== Login Attempt Operations ====================
+
+    /// When the username's password lockout ends, if it is currently locked
+    pub async fn get_login_locked_until(
+        &self,
+        key: &str,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_opt(
+                "SELECT locked_until FROM login_attempts
+                 WHERE username = $1 AND locked_until > NOW()",
+                &[&key],
+            )
+            .await?;
+
+        Ok(row.and_then(|r| r.get(0)))
+    }
+
+    /// Count a failed password login and return the consecutive failures so far.
+    /// The count starts again when the previous failure was before `reset_before`.
+    pub async fn record_failed_login(
+        &self,
+        key: &str,
+        reset_before: DateTime<Utc>,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "INSERT INTO login_attempts (username, failed_attempts, last_failed_at)
+                 VALUES ($1, 1, NOW())
+                 ON CONFLICT (username) DO UPDATE SET
+                     failed_attempts = CASE
+                         WHEN login_attempts.last_failed_at < $2 THEN 1
+                         ELSE login_attempts.failed_attempts + 1
+                     END,
+                     last_failed_at = NOW()
+                 RETURNING failed_attempts",
+                &[&key, &reset_before],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    /// Refuse password logins for the username until the given time
+    pub async fn set_login_locked_until(
+        &self,
+        key: &str,
+        locked_until: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        client
+            .execute(
+                "UPDATE login_attempts SET locked_until = $2 WHERE username = $1",
+                &[&key, &locked_until],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget failed logins for the username, lifting any lockout.
+    /// Returns whether there was anything to clear.
+    pub async fn clear_failed_logins(&self, key: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute("DELETE FROM login_attempts WHERE username = $1", &[&key])
+            .await?;
+
+        Ok(rows_affected > 0)
+    }
+
+    // ==================== Session Operations ====================
+
+    /// Start a login session and return its id
+    pub async fn create_session(
+        &self,
+        user_id: Uuid,
+        refresh_token_hash: &str,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Uuid, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let row = client
+            .query_one(
+                "INSERT INTO user_sessions (user_id, refresh_token_hash, user_agent, ip_address, expires_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 RETURNING id",
+                &[&user_id, &refresh_token_hash, &user_agent, &ip_address, &expires_at],
+            )
+            .await?;
 
-        if let Some(ref category) = request.category {
-            conditions.push(format!("i.category = ${param_index}"));
-            params.push(Box::new(category.clone()));
-            param_index += 1;
-        }
+        Ok(row.get(0))
+    }
 
-        if let Some(ref location) = request.location {
-            let pattern = format!("%{}%", escape_like_pattern(location));
-            conditions.push(format!("i.location ILIKE ${param_index}"));
-            params.push(Box::new(pattern));
-            param_index += 1;
-        }
+    /// Check that a session is still active and record activity on it
+    ///
+    /// Returns false if the session was revoked, has expired, or belongs to another user.
+    pub async fn touch_session(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        if let Some(ref from_date) = request.from_date {
-            conditions.push(format!("i.purchase_date >= ${param_index}::date"));
-            params.push(Box::new(from_date.clone()));
-            param_index += 1;
-        }
+        let rows_affected = client
+            .execute(
+                "UPDATE user_sessions SET last_seen_at = NOW()
+                 WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > NOW()",
+                &[&session_id, &user_id],
+            )
+            .await?;
 
-        if let Some(ref to_date) = request.to_date {
-            conditions.push(format!("i.purchase_date <= ${param_index}::date"));
-            params.push(Box::new(to_date.clone()));
-            param_index += 1;
-        }
+        Ok(rows_affected > 0)
+    }
 
-        if let Some(min_price) = request.min_price {
-            conditions.push(format!("i.purchase_price >= ${param_index}::float8"));
-            params.push(Box::new(min_price));
-            param_index += 1;
-        }
+    /// Replace a session's refresh token, returning the session and user ids
+    ///
+    /// Returns `None` for unknown, expired or revoked tokens. A token that was
+    /// already rotated out is treated as stolen and revokes its session.
+    pub async fn rotate_refresh_token(
+        &self,
+        refresh_token_hash: &str,
+        new_refresh_token_hash: &str,
+        ip_address: Option<&str>,
+    ) -> Result<Option<(Uuid, Uuid)>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        if let Some(max_price) = request.max_price {
-            conditions.push(format!("i.purchase_price <= ${param_index}::float8"));
-            params.push(Box::new(max_price));
-            #[allow(unused_assignments)]
-            {
-                param_index += 1;
-            }
+        let rows = client
+            .query(
+                "UPDATE user_sessions
+                 SET previous_refresh_token_hash = refresh_token_hash,
+                     refresh_token_hash = $2,
+                     ip_address = COALESCE($3, ip_address),
+                     last_seen_at = NOW()
+                 WHERE refresh_token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+                 RETURNING id, user_id",
+                &[&refresh_token_hash, &new_refresh_token_hash, &ip_address],
+            )
+            .await?;
+
+        if let Some(row) = rows.first() {
+            return Ok(Some((row.get(0), row.get(1))));
         }
 
-        // Build ORDER BY clause
-        let order_by = build_order_by(&request);
+        let revoked = client
+            .query(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE previous_refresh_token_hash = $1 AND revoked_at IS NULL
+                 RETURNING id, user_id",
+                &[&refresh_token_hash],
+            )
+            .await?;
+        for row in &revoked {
+            let session_id: Uuid = row.get(0);
+            let user_id: Uuid = row.get(1);
+            warn!(
+                "Refresh token reuse detected for user {}; revoked session {}",
+                user_id, session_id
+            );
+        }
 
-        let query = format!(
-            "SELECT i.id, i.inventory_id, i.name, i.description, i.category, i.location,
-                    i.purchase_date::text, i.purchase_price::float8, i.warranty_expiry::text,
-                    i.notes, i.quantity, i.created_at, i.updated_at
-             FROM items i
-             WHERE {}
-             ORDER BY {}",
-            conditions.join(" AND "),
-            order_by
-        );
+        Ok(None)
+    }
 
-        // Convert params to references for query
-        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
-            params.iter().map(std::convert::AsRef::as_ref).collect();
+    /// Get a user's active sessions, most recently used first
+    pub async fn get_user_sessions(
+        &self,
+        user_id: Uuid,
+        current_session_id: Option<Uuid>,
+    ) -> Result<Vec<UserSession>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        let rows = client.query(&query, &params_refs).await?;
+        let rows = client
+            .query(
+                "SELECT id, user_agent, ip_address, created_at, last_seen_at, expires_at
+                 FROM user_sessions
+                 WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+                 ORDER BY last_seen_at DESC",
+                &[&user_id],
+            )
+            .await?;
 
-        let items: Vec<crate::models::Item> = rows
+        Ok(rows
             .iter()
-            .map(|row| crate::models::Item {
-                id: Some(row.get(0)),
-                inventory_id: row.get(1),
-                name: row.get(2),
-                description: row.get(3),
-                category: row.get(4),
-                location: row.get(5),
-                purchase_date: row.get::<_, Option<String>>(6),
-                purchase_price: row.get(7),
-                warranty_expiry: row.get::<_, Option<String>>(8),
-                notes: row.get(9),
-                quantity: row.get(10),
-                created_at: row.get::<_, Option<DateTime<Utc>>>(11),
-                updated_at: row.get::<_, Option<DateTime<Utc>>>(12),
+            .map(|row| {
+                let id: Uuid = row.get(0);
+                UserSession {
+                    id,
+                    user_agent: row.get(1),
+                    ip_address: row.get(2),
+                    created_at: row.get(3),
+                    last_seen_at: row.get(4),
+                    expires_at: row.get(5),
+                    current: current_session_id == Some(id),
+                }
             })
-            .collect();
-
-        info!(
-            "Generated report with {} items for user {}",
-            items.len(),
-            user_id
-        );
-        Ok(items)
+            .collect())
     }
 
-    /// Calculates aggregated statistics across inventory items.
-    ///
-    /// Computes total item count, total value (price × quantity), average values,
-    /// and date ranges for items. When `inventory_id` is None, aggregates across
-    /// all inventories the user has access to.
-    ///
-    /// # Arguments
-    /// * `inventory_id` - Optional inventory ID to limit statistics to one inventory
-    /// * `user_id` - UUID of the authenticated user making the request
-    ///
-    /// # Returns
-    /// * `Ok(InventoryStatistics)` - Aggregated statistics
-    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
-    pub async fn get_inventory_statistics(
+    /// Revoke one of a user's sessions
+    pub async fn revoke_session(
         &self,
-        inventory_id: Option<i32>,
         user_id: Uuid,
-    ) -> Result<crate::models::InventoryStatistics, Box<dyn std::error::Error>> {
+        session_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let (query, params): (&str, Vec<Box<dyn tokio_postgres::types::ToSql + Sync>>) =
-            if let Some(inv_id) = inventory_id {
-                (
-                    "SELECT 
-                    COUNT(*)::int8 as total_items,
-                    COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as total_value,
-                    COALESCE(SUM(quantity), 0)::int8 as total_quantity,
-                    COUNT(DISTINCT category)::int8 as category_count,
-                    1::int8 as inventories_count,
-                    MIN(purchase_date)::text as oldest_item_date,
-                    MAX(purchase_date)::text as newest_item_date,
-                    COALESCE(AVG(purchase_price::float8), 0.0)::float8 as average_item_value
-                 FROM items
-                 WHERE inventory_id = $1",
-                    vec![Box::new(inv_id)],
-                )
-            } else {
-                (
-                "SELECT 
-                    COUNT(*)::int8 as total_items,
-                    COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as total_value,
-                    COALESCE(SUM(quantity), 0)::int8 as total_quantity,
-                    COUNT(DISTINCT category)::int8 as category_count,
-                    COUNT(DISTINCT inventory_id)::int8 as inventories_count,
-                    MIN(purchase_date)::text as oldest_item_date,
-                    MAX(purchase_date)::text as newest_item_date,
-                    COALESCE(AVG(purchase_price::float8), 0.0)::float8 as average_item_value
-                 FROM items
-                 WHERE inventory_id IN (
-                     SELECT id FROM inventories 
-                     WHERE user_id = $1
-                        OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1)
-                        OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1)
-                 )",
-                vec![Box::new(user_id)],
+        let rows_affected = client
+            .execute(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+                &[&session_id, &user_id],
             )
-            };
-
-        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
-            params.iter().map(std::convert::AsRef::as_ref).collect();
-
-        let row = client.query_one(query, &params_refs).await?;
-
-        let statistics = crate::models::InventoryStatistics {
-            total_items: row.get(0),
-            total_value: row.get(1),
-            total_quantity: row.get(2),
-            category_count: row.get(3),
-            inventories_count: row.get(4),
-            oldest_item_date: row.get(5),
-            newest_item_date: row.get(6),
-            average_item_value: row.get(7),
-        };
+            .await?;
 
-        info!("Generated statistics for user {}", user_id);
-        Ok(statistics)
+        Ok(rows_affected > 0)
     }
 
-    /// Generates category breakdown with item counts and value percentages.
-    ///
-    /// Groups items by category and calculates total values, quantities, and
-    /// percentage of total inventory value for each category. Uncategorized
-    /// items are grouped under "Uncategorized".
-    ///
-    /// # Arguments
-    /// * `inventory_id` - Optional inventory ID to limit breakdown to one inventory
-    /// * `user_id` - UUID of the authenticated user making the request
-    ///
-    /// # Returns
-    /// * `Ok(Vec<CategoryBreakdown>)` - Breakdown sorted by total value descending
-    /// * `Err(Box<dyn Error>)` - Database connection or query execution errors
-    pub async fn get_category_breakdown(
+    /// Revoke all of a user's sessions, optionally keeping one (e.g. the current session)
+    pub async fn revoke_user_sessions(
         &self,
-        inventory_id: Option<i32>,
         user_id: Uuid,
-    ) -> Result<Vec<crate::models::CategoryBreakdown>, Box<dyn std::error::Error>> {
+        except_session_id: Option<Uuid>,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let (query, params): (&str, Vec<Box<dyn tokio_postgres::types::ToSql + Sync>>) =
-            if let Some(inv_id) = inventory_id {
-                (
-                "WITH totals AS (
-                     SELECT COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as grand_total
-                     FROM items
-                     WHERE inventory_id = $1
-                 )
-                 SELECT 
-                     COALESCE(i.category, 'Uncategorized') as category,
-                     COUNT(*)::int8 as item_count,
-                     COALESCE(SUM(i.quantity), 0)::int8 as total_quantity,
-                     COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0)::float8 as total_value,
-                     CASE 
-                         WHEN t.grand_total > 0 THEN 
-                             (COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0) / t.grand_total * 100.0)::float8
-                         ELSE 0.0::float8
-                     END as percentage
-                 FROM items i
-                 CROSS JOIN totals t
-                 WHERE i.inventory_id = $1
-                 GROUP BY i.category, t.grand_total
-                 ORDER BY total_value DESC",
-                vec![Box::new(inv_id)],
-            )
-            } else {
-                (
-                "WITH totals AS (
-                     SELECT COALESCE(SUM(purchase_price::float8 * quantity), 0.0)::float8 as grand_total
-                     FROM items
-                     WHERE inventory_id IN (
-                         SELECT id FROM inventories 
-                         WHERE user_id = $1
-                            OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1)
-                            OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1)
-                     )
-                 )
-                 SELECT 
-                     COALESCE(i.category, 'Uncategorized') as category,
-                     COUNT(*)::int8 as item_count,
-                     COALESCE(SUM(i.quantity), 0)::int8 as total_quantity,
-                     COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0)::float8 as total_value,
-                     CASE 
-                         WHEN t.grand_total > 0 THEN 
-                             (COALESCE(SUM(i.purchase_price::float8 * i.quantity), 0.0) / t.grand_total * 100.0)::float8
-                         ELSE 0.0::float8
-                     END as percentage
-                 FROM items i
-                 CROSS JOIN totals t
-                 WHERE i.inventory_id IN (
-                     SELECT id FROM inventories 
-                     WHERE user_id = $1
-                        OR id IN (SELECT inventory_id FROM inventory_shares WHERE shared_with_user_id = $1)
-                        OR user_id IN (SELECT grantor_user_id FROM user_access_grants WHERE grantee_user_id = $1)
-                 )
-                 GROUP BY i.category, t.grand_total
-                 ORDER BY total_value DESC",
-                vec![Box::new(user_id)],
+        let rows_affected = client
+            .execute(
+                "UPDATE user_sessions SET revoked_at = NOW()
+                 WHERE user_id = $1 AND revoked_at IS NULL
+                   AND ($2::uuid IS NULL OR id <> $2)",
+                &[&user_id, &except_session_id],
             )
-            };
+            .await?;
 
-        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
-            params.iter().map(std::convert::AsRef::as_ref).collect();
+        if rows_affected > 0 {
+            info!("Revoked {} sessions for user {}", rows_affected, user_id);
+        }
+        Ok(rows_affected)
+    }
+
+    /// Delete sessions that expired or were revoked more than a week ago
+    pub async fn delete_stale_sessions(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let rows_affected = client
+            .execute(
+                "DELETE FROM user_sessions
+                 WHERE expires_at < NOW() - INTERVAL '7 days'
+                    OR revoked_at < NOW() - INTERVAL '7 days'",
+                &[],
+            )
+            .await?;
 
-        let rows = client.query(query, &params_refs).await?;
+        Ok(rows_affected)
+    }
 
-        let breakdown = rows
-            .iter()
-            .map(|row| crate::models::CategoryBreakdown {
-                category: row.get(0),
-                item_count: row.get(1),
-                total_quantity: row.get(2),
-                total_value: row.get(3),
-                percentage_of_total: row.get(4),
-            })
-            .collect();
+    // ==================== Personal API Token Operations ====================
 
-        info!("Generated category breakdown for user {}", user_id);
-        Ok(breakdown)
+    /// Map an `api_tokens` row (id, name, `token_prefix`, scopes, `inventory_ids`,
+    /// `created_at`, `expires_at`, `last_used_at`) to an [`ApiToken`]
+    fn row_to_api_token(row: &tokio_postgres::Row) -> ApiToken {
+        let scopes: Vec<String> = row.get(3);
+        ApiToken {
+            id: row.get(0),
+            name: row.get(1),
+            token_prefix: row.get(2),
+            scopes: scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+            inventory_ids: row.get(4),
+            created_at: row.get(5),
+            expires_at: row.get(6),
+            last_used_at: row.get(7),
+        }
     }
 
-    // ==================== Backup & Restore Methods ====================
-
-    /// Export all database tables as JSON values for backup
-    pub async fn export_all_data(
+    /// Store a new personal API token
+    ///
+    /// The request's scopes and inventory list are stored as given, so callers
+    /// should validate and normalize them first.
+    pub async fn create_api_token(
         &self,
-    ) -> Result<BackupDatabaseContent, Box<dyn std::error::Error>> {
+        user_id: Uuid,
+        token_hash: &str,
+        token_prefix: &str,
+        request: &CreateApiTokenRequest,
+    ) -> Result<ApiToken, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
+        let scopes: Vec<String> = request.scopes.iter().map(ToString::to_string).collect();
+        let expires_at = request
+            .expires_in_days
+            .map(|days| Utc::now() + chrono::Duration::days(days));
 
-        // Helper closure to build the JSON export query for a table
-        let build_export_query = |table: &str| {
-            format!("SELECT COALESCE(jsonb_agg(to_jsonb(t)), '[]'::jsonb) FROM {table} t")
-        };
+        let row = client
+            .query_one(
+                "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, inventory_ids, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 RETURNING id, name, token_prefix, scopes, inventory_ids, created_at, expires_at, last_used_at",
+                &[
+                    &user_id,
+                    &request.name.trim(),
+                    &token_hash,
+                    &token_prefix,
+                    &scopes,
+                    &request.inventory_ids,
+                    &expires_at,
+                ],
+            )
+            .await?;
 
-        let users: serde_json::Value = client
-            .query_one(&build_export_query("users"), &[])
-            .await?
-            .get(0);
-        let inventories: serde_json::Value = client
-            .query_one(&build_export_query("inventories"), &[])
-            .await?
-            .get(0);
-        let items: serde_json::Value = client
-            .query_one(&build_export_query("items"), &[])
-            .await?
-            .get(0);
-        let categories: serde_json::Value = client
-            .query_one(&build_export_query("categories"), &[])
-            .await?
-            .get(0);
-        let tags: serde_json::Value = client
-            .query_one(&build_export_query("tags"), &[])
-            .await?
-            .get(0);
-        let item_tags: serde_json::Value = client
-            .query_one(&build_export_query("item_tags"), &[])
-            .await?
-            .get(0);
-        let custom_fields: serde_json::Value = client
-            .query_one(&build_export_query("custom_fields"), &[])
-            .await?
-            .get(0);
-        let item_custom_values: serde_json::Value = client
-            .query_one(&build_export_query("item_custom_values"), &[])
-            .await?
-            .get(0);
-        let organizer_types: serde_json::Value = client
-            .query_one(&build_export_query("organizer_types"), &[])
-            .await?
-            .get(0);
-        let organizer_options: serde_json::Value = client
-            .query_one(&build_export_query("organizer_options"), &[])
-            .await?
-            .get(0);
-        let item_organizer_values: serde_json::Value = client
-            .query_one(&build_export_query("item_organizer_values"), &[])
-            .await?
-            .get(0);
-        let user_settings: serde_json::Value = client
-            .query_one(&build_export_query("user_settings"), &[])
-            .await?
-            .get(0);
-        let inventory_shares: serde_json::Value = client
-            .query_one(&build_export_query("inventory_shares"), &[])
-            .await?
-            .get(0);
-        let user_access_grants: serde_json::Value = client
-            .query_one(&build_export_query("user_access_grants"), &[])
-            .await?
-            .get(0);
-        let recovery_codes: serde_json::Value = client
-            .query_one(&build_export_query("recovery_codes"), &[])
-            .await?
-            .get(0);
-        let password_reset_tokens: serde_json::Value = client
-            .query_one(&build_export_query("password_reset_tokens"), &[])
-            .await?
-            .get(0);
+        Ok(Self::row_to_api_token(&row))
+    }
 
-        info!("Successfully exported all database tables for backup");
+    /// List a user's API tokens, newest first
+    pub async fn get_user_api_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<ApiToken>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        Ok(BackupDatabaseContent {
-            users,
-            inventories,
-            items,
-            categories,
-            tags,
-            item_tags,
-            custom_fields,
-            item_custom_values,
-            organizer_types,
-            organizer_options,
-            item_organizer_values,
-            user_settings,
-            inventory_shares,
-            user_access_grants,
-            recovery_codes,
-            password_reset_tokens,
-        })
+        let rows = client
+            .query(
+                "SELECT id, name, token_prefix, scopes, inventory_ids, created_at, expires_at, last_used_at
+                 FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+                &[&user_id],
+            )
+            .await?;
+
+        Ok(rows.iter().map(Self::row_to_api_token).collect())
     }
 
-    /// Import all database tables from backup data (within a transaction)
-    pub async fn import_all_data(
+    /// Delete one of a user's API tokens
+    pub async fn delete_api_token(
         &self,
-        data: &BackupDatabaseContent,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut client = self.pool.get().await?;
-        let transaction = client.transaction().await?;
+        user_id: Uuid,
+        token_id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        // Defer foreign key constraint checks until commit
-        transaction
-            .execute("SET CONSTRAINTS ALL DEFERRED", &[])
+        let rows_affected = client
+            .execute(
+                "DELETE FROM api_tokens WHERE id = $1 AND user_id = $2",
+                &[&token_id, &user_id],
+            )
             .await?;
 
-        // Truncate all tables in reverse dependency order
-        let truncate_order = [
-            "password_reset_tokens",
-            "recovery_codes",
-            "user_access_grants",
-            "inventory_shares",
-            "user_settings",
-            "item_organizer_values",
-            "organizer_options",
-            "organizer_types",
-            "item_custom_values",
-            "custom_fields",
-            "item_tags",
-            "tags",
-            "categories",
-            "items",
-            "inventories",
-            "users",
-        ];
+        Ok(rows_affected > 0)
+    }
 
-        for table in &truncate_order {
-            let query = format!("TRUNCATE TABLE {table} RESTART IDENTITY CASCADE");
-            transaction.execute(query.as_str(), &[]).await?;
-        }
+    /// Look up an unexpired API token by hash and record its use
+    ///
+    /// Returns the owning user's id alongside the token.
+    pub async fn use_api_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<(Uuid, ApiToken)>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
 
-        // Import tables in dependency order
-        let import_order: Vec<(&str, &serde_json::Value)> = vec![
-            ("users", &data.users),
-            ("inventories", &data.inventories),
-            ("items", &data.items),
-            ("categories", &data.categories),
-            ("tags", &data.tags),
-            ("item_tags", &data.item_tags),
-            ("custom_fields", &data.custom_fields),
-            ("item_custom_values", &data.item_custom_values),
-            ("organizer_types", &data.organizer_types),
-            ("organizer_options", &data.organizer_options),
-            ("item_organizer_values", &data.item_organizer_values),
-            ("user_settings", &data.user_settings),
-            ("inventory_shares", &data.inventory_shares),
-            ("user_access_grants", &data.user_access_grants),
-            ("recovery_codes", &data.recovery_codes),
-            ("password_reset_tokens", &data.password_reset_tokens),
-        ];
+        let row = client
+            .query_opt(
+                "UPDATE api_tokens SET last_used_at = NOW()
+                 WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+                 RETURNING id, name, token_prefix, scopes, inventory_ids, created_at, expires_at, last_used_at, user_id",
+                &[&token_hash],
+            )
+            .await?;
 
-        for (table, rows_json) in &import_order {
-            if let Some(rows) = rows_json.as_array() {
-                for row in rows {
-                    let query = format!(
-                        "INSERT INTO {table} SELECT * FROM jsonb_populate_record(NULL::{table}, $1)"
-                    );
-                    transaction.execute(query.as_str(), &[row]).await?;
-                }
-            }
-        }
+        Ok(row.map(|row| (row.get(8), Self::row_to_api_token(&row))))
+    }
 
-        // Reset sequences for tables with serial/identity columns
-        let sequence_tables = [
-            "items",
-            "inventories",
-            "categories",
-            "tags",
-            "custom_fields",
-            "item_custom_values",
-            "item_tags",
-            "organizer_types",
-            "organizer_options",
-            "item_organizer_values",
-        ];
+    // ==================== WebAuthn Credential Operations ====================
 
-        for table in &sequence_tables {
-            let query = format!(
-                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), \
-                 COALESCE(MAX(id), 0) + 1, false) FROM {table}"
-            );
-            // Ignore errors for tables that may not have sequences
-            if let Err(e) = transaction.execute(query.as_str(), &[]).await {
-                info!(
-                    "Note: Could not reset sequence for table {}: {} (this may be expected)",
-                    table, e
-                );
-            }
+    /// Map a `webauthn_credentials` row (id, nickname, transports, `created_at`,
+    /// `last_used_at`) to a [`WebauthnCredential`]
+    fn row_to_webauthn_credential(row: &tokio_postgres::Row) -> WebauthnCredential {
+        WebauthnCredential {
+            id: row.get(0),
+            nickname: row.get(1),
+            transports: row.get(2),
+            created_at: row.get(3),
+            last_used_at: row.get(4),
         }
-
-        transaction.commit().await?;
-        info!("Successfully imported all database tables from backup");
-        Ok(())
     }
 
-    // ==================== TOTP Settings Operations ====================
-
-    /// Create TOTP settings for a user (during setup, before verification)
-    pub async fn create_totp_settings(
+    /// Store a newly registered passkey
+    pub async fn create_webauthn_credential(
         &self,
         user_id: Uuid,
-        encrypted_secret: &str,
-    ) -> Result<TotpSettings, Box<dyn std::error::Error>> {
+        nickname: &str,
+        credential_id: &[u8],
+        public_key: &[u8],
+        sign_count: i64,
+        transports: &[String],
+    ) -> Result<WebauthnCredential, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let row = client
             .query_one(
-                "INSERT INTO user_totp_settings (user_id, totp_secret_encrypted)
-                 VALUES ($1, $2)
-                 ON CONFLICT (user_id) DO UPDATE SET
-                     totp_secret_encrypted = $2,
-                     is_enabled = false,
-                     is_verified = false,
-                     failed_attempts = 0,
-                     last_failed_at = NULL,
-                     updated_at = NOW()
-                 RETURNING id, user_id, totp_secret_encrypted, totp_mode, is_enabled,
-                           is_verified, created_at, updated_at, last_used_at,
-                           failed_attempts, last_failed_at",
-                &[&user_id, &encrypted_secret],
+                "INSERT INTO webauthn_credentials (user_id, nickname, credential_id, public_key, sign_count, transports)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 RETURNING id, nickname, transports, created_at, last_used_at",
+                &[
+                    &user_id,
+                    &nickname.trim(),
+                    &credential_id,
+                    &public_key,
+                    &sign_count,
+                    &transports,
+                ],
             )
             .await?;
-
-        Ok(TotpSettings {
-            id: row.get(0),
-            user_id: row.get(1),
-            totp_secret_encrypted: row.get(2),
-            totp_mode: row.get(3),
-            is_enabled: row.get(4),
-            is_verified: row.get(5),
-            created_at: row.get(6),
-            updated_at: row.get(7),
-            last_used_at: row.get(8),
-            failed_attempts: row.get(9),
-            last_failed_at: row.get(10),
-        })
+
+        Ok(Self::row_to_webauthn_credential(&row))
     }
 
-    /// Get TOTP settings for a user
-    pub async fn get_totp_settings(
+    /// List a user's passkeys, newest first
+    pub async fn get_user_webauthn_credentials(
         &self,
         user_id: Uuid,
-    ) -> Result<Option<TotpSettings>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<WebauthnCredential>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         let rows = client
             .query(
-                "SELECT id, user_id, totp_secret_encrypted, totp_mode, is_enabled,
-                        is_verified, created_at, updated_at, last_used_at,
-                        failed_attempts, last_failed_at
-                 FROM user_totp_settings WHERE user_id = $1",
+                "SELECT id, nickname, transports, created_at, last_used_at
+                 FROM webauthn_credentials WHERE user_id = $1
+                 ORDER BY created_at DESC",
                 &[&user_id],
             )
             .await?;
 
-        if let Some(row) = rows.first() {
-            Ok(Some(TotpSettings {
-                id: row.get(0),
-                user_id: row.get(1),
-                totp_secret_encrypted: row.get(2),
-                totp_mode: row.get(3),
-                is_enabled: row.get(4),
-                is_verified: row.get(5),
-                created_at: row.get(6),
-                updated_at: row.get(7),
-                last_used_at: row.get(8),
-                failed_attempts: row.get(9),
-                last_failed_at: row.get(10),
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(rows.iter().map(Self::row_to_webauthn_credential).collect())
     }
 
-    /// Enable TOTP after successful verification (set enabled, verified, and mode)
-    pub async fn enable_totp(
+    /// Credential IDs and transports of a user's passkeys, for ceremony options
+    pub async fn get_user_webauthn_credential_ids(
         &self,
         user_id: Uuid,
-        mode: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<Vec<(Vec<u8>, Vec<String>)>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        client
-            .execute(
-                "UPDATE user_totp_settings
-                 SET is_enabled = true, is_verified = true, totp_mode = $2,
-                     failed_attempts = 0, last_failed_at = NULL, updated_at = NOW()
-                 WHERE user_id = $1",
-                &[&user_id, &mode],
+        let rows = client
+            .query(
+                "SELECT credential_id, transports FROM webauthn_credentials WHERE user_id = $1",
+                &[&user_id],
             )
             .await?;
 
-        Ok(())
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
     }
 
-    /// Update TOTP mode
-    pub async fn update_totp_mode(
+    /// Look up the verification material for a credential ID
+    pub async fn get_webauthn_credential_key(
         &self,
-        user_id: Uuid,
-        mode: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        credential_id: &[u8],
+    ) -> Result<Option<WebauthnCredentialKey>, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        client
-            .execute(
-                "UPDATE user_totp_settings SET totp_mode = $2, updated_at = NOW()
-                 WHERE user_id = $1 AND is_enabled = true",
-                &[&user_id, &mode],
+        let row = client
+            .query_opt(
+                "SELECT id, user_id, public_key, sign_count
+                 FROM webauthn_credentials WHERE credential_id = $1",
+                &[&credential_id],
             )
             .await?;
 
-        Ok(())
+        Ok(row.map(|row| WebauthnCredentialKey {
+            id: row.get(0),
+            user_id: row.get(1),
+            public_key: row.get(2),
+            sign_count: row.get(3),
+        }))
     }
 
-    /// Update TOTP last used timestamp
-    pub async fn update_totp_last_used(
+    /// Record a successful sign-in with a passkey
+    pub async fn update_webauthn_credential_usage(
         &self,
-        user_id: Uuid,
+        id: Uuid,
+        sign_count: i64,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         client
             .execute(
-                "UPDATE user_totp_settings SET last_used_at = NOW(), updated_at = NOW()
-                 WHERE user_id = $1",
-                &[&user_id],
+                "UPDATE webauthn_credentials SET sign_count = $2, last_used_at = NOW() WHERE id = $1",
+                &[&id, &sign_count],
             )
             .await?;
 
         Ok(())
     }
 
-    /// Delete TOTP settings (disable TOTP)
-    pub async fn delete_totp_settings(
+    /// Rename one of a user's passkeys; returns false if it doesn't exist
+    pub async fn rename_webauthn_credential(
         &self,
         user_id: Uuid,
+        id: Uuid,
+        nickname: &str,
     ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let rows_affected = client
+        let updated = client
             .execute(
-                "DELETE FROM user_totp_settings WHERE user_id = $1",
-                &[&user_id],
+                "UPDATE webauthn_credentials SET nickname = $3 WHERE id = $1 AND user_id = $2",
+                &[&id, &user_id, &nickname.trim()],
             )
             .await?;
 
-        Ok(rows_affected > 0)
+        Ok(updated > 0)
     }
 
-    /// Increment failed TOTP attempts and record timestamp
-    pub async fn increment_totp_failed_attempts(
+    /// Delete one of a user's passkeys; returns false if it doesn't exist
+    pub async fn delete_webauthn_credential(
         &self,
         user_id: Uuid,
-    ) -> Result<i32, Box<dyn std::error::Error>> {
+        id: Uuid,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
-        let row = client
-            .query_one(
-                "UPDATE user_totp_settings
-                 SET failed_attempts = failed_attempts + 1, last_failed_at = NOW()
-                 WHERE user_id = $1
-                 RETURNING failed_attempts",
-                &[&user_id],
+        let deleted = client
+            .execute(
+                "DELETE FROM webauthn_credentials WHERE id = $1 AND user_id = $2",
+                &[&id, &user_id],
             )
             .await?;
 
-        Ok(row.get(0))
+        Ok(deleted > 0)
     }
 
-    /// Reset failed TOTP attempts after successful verification
-    pub async fn reset_totp_failed_attempts(
+    // ==================== Audit Log Operations ====================
+
+    /// Append an entry to the audit log
+    pub async fn create_audit_event(
         &self,
-        user_id: Uuid,
+        event: &NewAuditEvent,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let client = self.pool.get().await?;
 
         client
             .execute(
-                "UPDATE user_totp_settings
-                 SET failed_attempts = 0, last_failed_at = NULL
-                 WHERE user_id = $1",
-                &[&user_id],
+                "INSERT INTO audit_events (actor_id, actor_username, action, target_type, target_id,
+                                           outcome, details, ip_address, user_agent)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                &[
+                    &event.actor_id,
+                    &event.actor_username,
+                    &event.action.as_str(),
+                    &event.target_type,
+                    &event.target_id,
+                    &event.outcome.as_str(),
+                    &event.details,
+                    &event.ip_address,
+                    &event.user_agent,
+                ],
             )
             .await?;
 
         Ok(())
     }
+
+    /// Search the audit log, newest first. `limit` of `None` returns every match.
+    pub async fn get_audit_events(
+        &self,
+        query: &AuditEventQuery,
+        limit: Option<i64>,
+    ) -> Result<Vec<AuditEvent>, Box<dyn std::error::Error>> {
+        let client = self.pool.get().await?;
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut params: Vec<Box<dyn tokio_postgres::types::ToSql + Sync>> = Vec::new();
+
+        if let Some(user_id) = query.user_id {
+            params.push(Box::new(user_id));
+            params.push(Box::new(user_id.to_string()));
+            conditions.push(format!(
+                "(actor_id = ${} OR (target_type = 'user' AND target_id = ${}))",
+                params.len() - 1,
+                params.len()
+            ));
+        }
+
+        if let Some(action) = query.action.as_deref().filter(|a| !a.is_empty()) {
+            // A trailing dot matches a whole group of actions, e.g. "auth."
+            if action.ends_with('.') {
+                params.push(Box::new(format!("{}%", escape_like_pattern(action))));
+                conditions.push(format!("action LIKE ${}", params.len()));
+            } else {
+                params.push(Box::new(action.to_string()));
+                conditions.push(format!("action = ${}", params.len()));
+            }
+        }
+
+        if let Some(from) = query.from {
+            params.push(Box::new(from));
+            conditions.push(format!("created_at >= ${}", params.len()));
+        }
+
+        if let Some(to) = query.to {
+            params.push(Box::new(to));
+            conditions.push(format!("created_at <= ${}", params.len()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let limit_clause = match limit {
+            Some(limit) => {
+                params.push(Box::new(limit));
+                params.push(Box::new(query.offset.unwrap_or(0).max(0)));
+                format!("LIMIT ${} OFFSET ${}", params.len() - 1, params.len())
+            },
+            None => String::new(),
+        };
+
+        let sql = format!(
+            "SELECT id, created_at, actor_id, actor_username, action, target_type, target_id,
+                    outcome, details, ip_address, user_agent
+             FROM audit_events
+             {where_clause}
+             ORDER BY created_at DESC, id DESC
+             {limit_clause}"
+        );
+
+        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let rows = client.query(&sql, &params_refs).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AuditEvent {
+                id: row.get(0),
+                created_at: row.get(1),
+                actor_id: row.get(2),
+                actor_username: row.get(3),
+                action: row.get(4),
+                target_type: row.get(5),
+                target_id: row.get(6),
+                outcome: row.get(7),
+                details: row.get(8),
+                ip_address: row.get(9),
+                user_agent: row.get(10),
+            })
+            .collect())
+    }
+}
+
+/// State shared while merging backup rows into the live database
+struct BackupMergeContext {
+    /// Live column names per table, so rows from older backups map onto the current schema
+    columns: HashMap<String, Vec<String>>,
+    live_users: HashSet<Uuid>,
+    live_categories: HashSet<i64>,
+    /// Backup tag id → live tag id (`None` when the tag couldn't be restored)
+    tag_ids: HashMap<i64, Option<i32>>,
+    /// Backup custom field id → live custom field id (`None` when it couldn't be restored)
+    custom_field_ids: HashMap<i64, Option<i32>>,
+    /// Backup custom role id → live role id (`None` when it couldn't be restored)
+    role_ids: HashMap<Uuid, Option<Uuid>>,
+    summary: SelectiveRestoreSummary,
+}
+
+impl BackupMergeContext {
+    async fn load(
+        transaction: &tokio_postgres::Transaction<'_>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let tables: Vec<&str> = BackupDatabaseContent::TABLES.to_vec();
+        let mut columns: HashMap<String, Vec<String>> = HashMap::new();
+        for row in transaction
+            .query(
+                "SELECT table_name::text, column_name::text FROM information_schema.columns \
+                 WHERE table_schema = 'public' AND table_name = ANY($1) \
+                 ORDER BY table_name, ordinal_position",
+                &[&tables],
+            )
+            .await?
+        {
+            columns.entry(row.get(0)).or_default().push(row.get(1));
+        }
+
+        let live_users = transaction
+            .query("SELECT id FROM users", &[])
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        let live_categories = transaction
+            .query("SELECT id FROM categories", &[])
+            .await?
+            .iter()
+            .map(|row| i64::from(row.get::<_, i32>(0)))
+            .collect();
+
+        Ok(Self {
+            columns,
+            live_users,
+            live_categories,
+            tag_ids: HashMap::new(),
+            custom_field_ids: HashMap::new(),
+            role_ids: HashMap::new(),
+            summary: SelectiveRestoreSummary::default(),
+        })
+    }
+
+    /// Insert a backup row, letting the table assign a new id unless `keep_id` is set
+    ///
+    /// Only columns present in both the row and the live table are written.
+    /// Returns `None` when the row conflicted with an existing record.
+    async fn insert_row(
+        &self,
+        transaction: &tokio_postgres::Transaction<'_>,
+        table: &str,
+        row: serde_json::Map<String, serde_json::Value>,
+        keep_id: bool,
+    ) -> Result<Option<tokio_postgres::Row>, Box<dyn std::error::Error>> {
+        let column_list = self
+            .columns
+            .get(table)
+            .ok_or_else(|| format!("Table '{table}' does not exist"))?
+            .iter()
+            .filter(|column| (keep_id || column.as_str() != "id") && row.contains_key(*column))
+            .map(|column| format!("\"{column}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let query = format!(
+            "INSERT INTO {table} ({column_list}) \
+             SELECT {column_list} FROM jsonb_populate_record(NULL::{table}, $1) \
+             ON CONFLICT DO NOTHING RETURNING id"
+        );
+        let row = serde_json::Value::Object(row);
+        Ok(transaction.query_opt(query.as_str(), &[&row]).await?)
+    }
+
+    /// Insert an inventory and its items, organizers, tags, custom field values,
+    /// item history and shares, remapping ids
+    async fn merge_inventory(
+        &mut self,
+        transaction: &tokio_postgres::Transaction<'_>,
+        data: &BackupDatabaseContent,
+        inventory: &serde_json::Map<String, serde_json::Value>,
+        owner_id: Uuid,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let original_id = backup_i64(inventory, "id").ok_or("Backup inventory has no id")?;
+        let name = backup_str(inventory, "name").to_string();
+
+        // Keep the original id when it is free so existing links keep working
+        let id_taken = match i32::try_from(original_id) {
+            Ok(id) => transaction
+                .query_opt("SELECT id FROM inventories WHERE id = $1", &[&id])
+                .await?
+                .is_some(),
+            Err(_) => true,
+        };
+
+        let mut row = inventory.clone();
+        row.insert("user_id".to_string(), serde_json::json!(owner_id));
+        let new_id: i32 = self
+            .insert_row(transaction, "inventories", row, !id_taken)
+            .await?
+            .ok_or("Failed to insert inventory")?
+            .get(0);
+        if !id_taken {
+            // Make sure the sequence never hands out the id we just reused
+            transaction
+                .execute(
+                    "SELECT setval(pg_get_serial_sequence('inventories', 'id'), \
+                     GREATEST(nextval(pg_get_serial_sequence('inventories', 'id')), $1::bigint))",
+                    &[&i64::from(new_id)],
+                )
+                .await?;
+        }
+
+        // Items
+        let mut item_ids: HashMap<i64, i32> = HashMap::new();
+        for item in backup_rows(&data.items)
+            .filter(|row| backup_i64(row, "inventory_id") == Some(original_id))
+        {
+            let mut row = item.clone();
+            row.insert("inventory_id".to_string(), serde_json::json!(new_id));
+            if backup_i64(item, "category_id").is_some_and(|id| !self.live_categories.contains(&id))
+            {
+                row.insert("category_id".to_string(), serde_json::Value::Null);
+            }
+            if let (Some(old), Some(inserted)) = (
+                backup_i64(item, "id"),
+                self.insert_row(transaction, "items", row, false).await?,
+            ) {
+                item_ids.insert(old, inserted.get(0));
+                self.summary.items_restored += 1;
+            }
+        }
+
+        // Organizer types and their options
+        let mut type_ids: HashMap<i64, i32> = HashMap::new();
+        for organizer_type in backup_rows(&data.organizer_types)
+            .filter(|row| backup_i64(row, "inventory_id") == Some(original_id))
+        {
+            let mut row = organizer_type.clone();
+            row.insert("inventory_id".to_string(), serde_json::json!(new_id));
+            if let (Some(old), Some(inserted)) = (
+                backup_i64(organizer_type, "id"),
+                self.insert_row(transaction, "organizer_types", row, false)
+                    .await?,
+            ) {
+                type_ids.insert(old, inserted.get(0));
+                self.summary.organizer_types_restored += 1;
+            }
+        }
+
+        let mut option_ids: HashMap<i64, i32> = HashMap::new();
+        for option in backup_rows(&data.organizer_options) {
+            let Some(type_id) =
+                backup_i64(option, "organizer_type_id").and_then(|id| type_ids.get(&id).copied())
+            else {
+                continue;
+            };
+            let mut row = option.clone();
+            row.insert("organizer_type_id".to_string(), serde_json::json!(type_id));
+            if let (Some(old), Some(inserted)) = (
+                backup_i64(option, "id"),
+                self.insert_row(transaction, "organizer_options", row, false)
+                    .await?,
+            ) {
+                option_ids.insert(old, inserted.get(0));
+                self.summary.organizer_options_restored += 1;
+            }
+        }
+
+        // Item organizer values
+        for value in backup_rows(&data.item_organizer_values) {
+            let item_id = backup_i64(value, "item_id").and_then(|id| item_ids.get(&id).copied());
+            let type_id =
+                backup_i64(value, "organizer_type_id").and_then(|id| type_ids.get(&id).copied());
+            let (Some(item_id), Some(type_id)) = (item_id, type_id) else {
+                continue;
+            };
+            let option_id = backup_i64(value, "organizer_option_id")
+                .and_then(|id| option_ids.get(&id).copied());
+
+            let mut row = value.clone();
+            row.insert("item_id".to_string(), serde_json::json!(item_id));
+            row.insert("organizer_type_id".to_string(), serde_json::json!(type_id));
+            row.insert(
+                "organizer_option_id".to_string(),
+                option_id.map_or(serde_json::Value::Null, serde_json::Value::from),
+            );
+            if self
+                .insert_row(transaction, "item_organizer_values", row, false)
+                .await?
+                .is_some()
+            {
+                self.summary.organizer_values_restored += 1;
+            }
+        }
+
+        // Item tags and custom field values
+        for item_tag in backup_rows(&data.item_tags) {
+            let Some(item_id) =
+                backup_i64(item_tag, "item_id").and_then(|id| item_ids.get(&id).copied())
+            else {
+                continue;
+            };
+            let Some(tag_id) = (match backup_i64(item_tag, "tag_id") {
+                Some(id) => self.resolve_tag(transaction, data, id).await?,
+                None => None,
+            }) else {
+                continue;
+            };
+
+            let mut row = item_tag.clone();
+            row.insert("item_id".to_string(), serde_json::json!(item_id));
+            row.insert("tag_id".to_string(), serde_json::json!(tag_id));
+            if self
+                .insert_row(transaction, "item_tags", row, false)
+                .await?
+                .is_some()
+            {
+                self.summary.tags_restored += 1;
+            }
+        }
+
+        for value in backup_rows(&data.item_custom_values) {
+            let Some(item_id) =
+                backup_i64(value, "item_id").and_then(|id| item_ids.get(&id).copied())
+            else {
+                continue;
+            };
+            let Some(field_id) = (match backup_i64(value, "custom_field_id") {
+                Some(id) => self.resolve_custom_field(transaction, data, id).await?,
+                None => None,
+            }) else {
+                continue;
+            };
+
+            let mut row = value.clone();
+            row.insert("item_id".to_string(), serde_json::json!(item_id));
+            row.insert("custom_field_id".to_string(), serde_json::json!(field_id));
+            if self
+                .insert_row(transaction, "item_custom_values", row, false)
+                .await?
+                .is_some()
+            {
+                self.summary.custom_values_restored += 1;
+            }
+        }
+
+        // Item history
+        for revision in backup_rows(&data.item_history) {
+            let Some(item_id) =
+                backup_i64(revision, "item_id").and_then(|id| item_ids.get(&id).copied())
+            else {
+                continue;
+            };
+            let mut row = revision.clone();
+            row.insert("item_id".to_string(), serde_json::json!(item_id));
+            if self
+                .insert_row(transaction, "item_history", row, false)
+                .await?
+                .is_some()
+            {
+                self.summary.revisions_restored += 1;
+            }
+        }
+
+        // Shares, as long as the user they were shared with still exists
+        for share in backup_rows(&data.inventory_shares)
+            .filter(|row| backup_i64(row, "inventory_id") == Some(original_id))
+        {
+            let shared_with = backup_uuid(share, "shared_with_user_id");
+            let Some(shared_with) =
+                shared_with.filter(|id| *id != owner_id && self.live_users.contains(id))
+            else {
+                self.summary.warnings.push(format!(
+                    "Share of '{name}' skipped: the user it was shared with no longer exists"
+                ));
+                continue;
+            };
+            let shared_by = backup_uuid(share, "shared_by_user_id")
+                .filter(|id| self.live_users.contains(id))
+                .unwrap_or(owner_id);
+
+            let mut row = share.clone();
+            row.insert("inventory_id".to_string(), serde_json::json!(new_id));
+            row.insert(
+                "shared_with_user_id".to_string(),
+                serde_json::json!(shared_with),
+            );
+            row.insert(
+                "shared_by_user_id".to_string(),
+                serde_json::json!(shared_by),
+            );
+            let role_id = match backup_uuid(share, "role_id") {
+                Some(id) => {
+                    self.resolve_role(transaction, data, id, Some(owner_id))
+                        .await?
+                },
+                None => None,
+            };
+            row.insert(
+                "role_id".to_string(),
+                role_id.map_or(serde_json::Value::Null, |id| serde_json::json!(id)),
+            );
+            if self
+                .insert_row(transaction, "inventory_shares", row, false)
+                .await?
+                .is_some()
+            {
+                self.summary.shares_restored += 1;
+            }
+        }
+
+        self.summary.inventories.push(RestoredInventory {
+            original_id: i32::try_from(original_id)?,
+            new_id,
+            name,
+        });
+        Ok(new_id)
+    }
+
+    /// Map a backup tag onto a live tag, recreating it if it was deleted
+    ///
+    /// Tags are shared by every inventory, so a tag that still exists (by id,
+    /// then by name) is reused rather than duplicated.
+    async fn resolve_tag(
+        &mut self,
+        transaction: &tokio_postgres::Transaction<'_>,
+        data: &BackupDatabaseContent,
+        original_id: i64,
+    ) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+        if let Some(id) = self.tag_ids.get(&original_id) {
+            return Ok(*id);
+        }
+        let Some(tag) =
+            backup_rows(&data.tags).find(|row| backup_i64(row, "id") == Some(original_id))
+        else {
+            self.tag_ids.insert(original_id, None);
+            return Ok(None);
+        };
+
+        let existing = transaction
+            .query_opt(
+                "SELECT id FROM tags WHERE id = $1 OR name = $2 ORDER BY (id = $1) DESC LIMIT 1",
+                &[
+                    &i32::try_from(original_id).unwrap_or(0),
+                    &backup_str(tag, "name"),
+                ],
+            )
+            .await?;
+        let id = match existing {
+            Some(row) => Some(row.get(0)),
+            None => self
+                .insert_row(transaction, "tags", tag.clone(), false)
+                .await?
+                .map(|row| row.get(0)),
+        };
+        self.tag_ids.insert(original_id, id);
+        Ok(id)
+    }
+
+    /// Map a backup custom role onto a live role, recreating it if it was deleted
+    ///
+    /// A recreated role keeps its id and goes to its original owner, or to
+    /// `fallback_owner` when that user no longer exists. Shares whose role can't
+    /// be restored keep their closest built-in level.
+    async fn resolve_role(
+        &mut self,
+        transaction: &tokio_postgres::Transaction<'_>,
+        data: &BackupDatabaseContent,
+        original_id: Uuid,
+        fallback_owner: Option<Uuid>,
+    ) -> Result<Option<Uuid>, Box<dyn std::error::Error>> {
+        if let Some(id) = self.role_ids.get(&original_id) {
+            return Ok(*id);
+        }
+        let Some(role) = backup_rows(&data.inventory_roles)
+            .find(|row| backup_uuid(row, "id") == Some(original_id))
+        else {
+            self.role_ids.insert(original_id, None);
+            return Ok(None);
+        };
+
+        let owner = backup_uuid(role, "owner_user_id")
+            .filter(|id| self.live_users.contains(id))
+            .or(fallback_owner);
+        let existing = transaction
+            .query_opt(
+                "SELECT id FROM inventory_roles WHERE id = $1 OR (owner_user_id = $2 AND name = $3) \
+                 ORDER BY (id = $1) DESC LIMIT 1",
+                &[&original_id, &owner, &backup_str(role, "name")],
+            )
+            .await?;
+        let id = match (existing, owner) {
+            (Some(row), _) => Some(row.get(0)),
+            (None, Some(owner)) => {
+                let mut row = role.clone();
+                row.insert("owner_user_id".to_string(), serde_json::json!(owner));
+                self.insert_row(transaction, "inventory_roles", row, true)
+                    .await?
+                    .map(|row| row.get(0))
+            },
+            (None, None) => None,
+        };
+        if id.is_none() {
+            self.summary.warnings.push(format!(
+                "Role '{}' could not be restored; shares with it keep their built-in permission level",
+                backup_str(role, "name")
+            ));
+        }
+        self.role_ids.insert(original_id, id);
+        Ok(id)
+    }
+
+    /// Map a backup custom field onto a live one, recreating it if it was deleted
+    ///
+    /// Custom fields belong to a category rather than an inventory, so a field
+    /// that still exists (by id, then by category and name) is reused. Fields
+    /// whose category no longer exists are skipped.
+    async fn resolve_custom_field(
+        &mut self,
+        transaction: &tokio_postgres::Transaction<'_>,
+        data: &BackupDatabaseContent,
+        original_id: i64,
+    ) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+        if let Some(id) = self.custom_field_ids.get(&original_id) {
+            return Ok(*id);
+        }
+        let Some(field) =
+            backup_rows(&data.custom_fields).find(|row| backup_i64(row, "id") == Some(original_id))
+        else {
+            self.custom_field_ids.insert(original_id, None);
+            return Ok(None);
+        };
+
+        let category_id = backup_i64(field, "category_id")
+            .filter(|id| self.live_categories.contains(id))
+            .and_then(|id| i32::try_from(id).ok());
+        let existing = transaction
+            .query_opt(
+                "SELECT id FROM custom_fields WHERE id = $1 OR (category_id = $2 AND name = $3) \
+                 ORDER BY (id = $1) DESC LIMIT 1",
+                &[
+                    &i32::try_from(original_id).unwrap_or(0),
+                    &category_id,
+                    &backup_str(field, "name"),
+                ],
+            )
+            .await?;
+        let id = match (existing, category_id) {
+            (Some(row), _) => Some(row.get(0)),
+            (None, Some(_)) => self
+                .insert_row(transaction, "custom_fields", field.clone(), false)
+                .await?
+                .map(|row| row.get(0)),
+            (None, None) => {
+                self.summary.warnings.push(format!(
+                    "Values for custom field '{}' skipped: its category no longer exists",
+                    backup_str(field, "name")
+                ));
+                None
+            },
+        };
+        self.custom_field_ids.insert(original_id, id);
+        Ok(id)
+    }
+}
+
+/// Iterate the rows of an exported table
+pub(crate) fn backup_rows(
+    table: &serde_json::Value,
+) -> impl Iterator<Item = &serde_json::Map<String, serde_json::Value>> {
+    table
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_object)
+}
+
+/// Read an integer column from an exported row
+pub(crate) fn backup_i64(
+    row: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Option<i64> {
+    row.get(key).and_then(serde_json::Value::as_i64)
+}
+
+/// Read a UUID column from an exported row
+pub(crate) fn backup_uuid(
+    row: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Option<Uuid> {
+    row.get(key)
+        .and_then(serde_json::Value::as_str)
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+/// Read a text column from an exported row (empty when missing)
+pub(crate) fn backup_str<'a>(
+    row: &'a serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> &'a str {
+    row.get(key)
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
 }
 
 /// Helper function to build ORDER BY clause
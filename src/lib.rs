@@ -12,5 +12,6 @@
 // Public modules
 pub mod api;
 pub mod auth;
+pub mod cli;
 pub mod db;
 pub mod models;
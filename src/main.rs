@@ -1,6 +1,8 @@
 //! Home Registry - A home inventory management system
 //!
-//! This is the main entry point for the Home Registry server.
+//! This is the main entry point for the Home Registry server. Run without
+//! arguments (or with `serve`) to start the HTTP server; see [`cli::USAGE`] for
+//! the admin subcommands.
 
 #![deny(unsafe_code)]
 #![warn(clippy::all, clippy::pedantic)]
@@ -21,6 +23,7 @@ use refinery::embed_migrations;
 use std::{env, time::Duration};
 
 // Use the library crate
+use home_registry::cli::{self, Command};
 use home_registry::{api, auth, db};
 
 // Embed migrations from the migrations directory at compile time
@@ -41,38 +44,9 @@ async fn spa_fallback() -> actix_web::Result<fs::NamedFile> {
     Ok(fs::NamedFile::open("static/index.html")?)
 }
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    dotenv().ok();
-    env_logger::init();
-
-    let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let port = env::var("PORT").unwrap_or_else(|_| "8210".to_string());
-
-    log::info!("Starting Home Inventory server at http://{}:{}", host, port);
-    log::info!(
-        "Environment: {}",
-        env::var("RUST_ENV").unwrap_or_else(|_| "development".to_string())
-    );
-
-    // Ensure uploads directory exists for image storage
-    match std::fs::create_dir_all("uploads/img") {
-        Ok(()) => log::info!("Uploads directory ready: uploads/img/"),
-        Err(e) => log::warn!(
-            "Could not create uploads/img directory: {}. Image uploads will be unavailable.",
-            e
-        ),
-    }
-
-    // Initialize JWT secret at startup (will auto-generate if not found)
-    let _ = auth::get_or_init_jwt_secret();
-    log::info!(
-        "JWT token lifetime: {} hours",
-        auth::jwt_token_lifetime_hours()
-    );
-
-    // Initialize database pool with proper error handling (no panics)
-    let pool = match db::get_pool() {
+/// Initialize the database pool, exiting if the configuration is invalid
+fn init_pool() -> deadpool_postgres::Pool {
+    match db::get_pool() {
         Ok(p) => {
             log::info!("Database pool initialized successfully");
             p
@@ -81,9 +55,11 @@ async fn main() -> std::io::Result<()> {
             log::error!("Failed to initialize database pool: {}", e);
             std::process::exit(1);
         },
-    };
+    }
+}
 
-    // Run database migrations automatically at startup
+/// Apply pending database migrations, exiting if they cannot be applied
+async fn run_migrations(pool: &deadpool_postgres::Pool) {
     // Migrations are embedded in the binary and applied idempotently
     log::info!("Running database migrations...");
     let mut client = match pool.get().await {
@@ -119,6 +95,122 @@ async fn main() -> std::io::Result<()> {
     // Drop the migration client back to the pool
     drop(client);
     log::info!("Migration client returned to pool");
+}
+
+/// How often expired inventory shares and access grants are removed
+const EXPIRED_SHARE_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically delete shares and All Access grants whose expiry has passed
+fn spawn_expired_share_cleanup(pool: deadpool_postgres::Pool) {
+    actix_web::rt::spawn(async move {
+        let db_service = db::DatabaseService::new(pool);
+        let mut interval = actix_web::rt::time::interval(EXPIRED_SHARE_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match db_service.delete_expired_shares().await {
+                Ok(0) => {},
+                Ok(count) => log::info!("Removed {} expired share(s) and access grant(s)", count),
+                Err(e) => log::warn!("Failed to clean up expired shares: {}", e),
+            }
+        }
+    });
+}
+
+/// How often entries that have been in the trash too long are purged
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically purge trashed entries older than `TRASH_RETENTION_DAYS`
+fn spawn_trash_purge(pool: deadpool_postgres::Pool) {
+    let Some(days) = api::trash::retention_days() else {
+        log::info!("Trash auto-purge disabled (TRASH_RETENTION_DAYS=0)");
+        return;
+    };
+    actix_web::rt::spawn(async move {
+        let db_service = db::DatabaseService::new(pool);
+        let mut interval = actix_web::rt::time::interval(TRASH_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match db_service.purge_trash_older_than(days).await {
+                Ok(0) => {},
+                Ok(count) => {
+                    log::info!("Purged {} trashed entries older than {} days", count, days);
+                },
+                Err(e) => log::warn!("Failed to purge the trash: {}", e),
+            }
+        }
+    });
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    dotenv().ok();
+    env_logger::init();
+
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        },
+    };
+
+    match command {
+        Command::Serve => serve().await,
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        },
+        Command::Migrate => {
+            run_migrations(&init_pool()).await;
+            println!("Database migrations are up to date");
+            Ok(())
+        },
+        command => {
+            if let Err(e) = cli::run(command, &init_pool()).await {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+            Ok(())
+        },
+    }
+}
+
+/// Start the HTTP server
+#[allow(clippy::too_many_lines)]
+async fn serve() -> std::io::Result<()> {
+    let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
+    let port = env::var("PORT").unwrap_or_else(|_| "8210".to_string());
+
+    log::info!("Starting Home Inventory server at http://{}:{}", host, port);
+    log::info!(
+        "Environment: {}",
+        env::var("RUST_ENV").unwrap_or_else(|_| "development".to_string())
+    );
+
+    // Ensure uploads directory exists for image storage
+    match std::fs::create_dir_all("uploads/img") {
+        Ok(()) => log::info!("Uploads directory ready: uploads/img/"),
+        Err(e) => log::warn!(
+            "Could not create uploads/img directory: {}. Image uploads will be unavailable.",
+            e
+        ),
+    }
+
+    // Initialize JWT secret at startup (will auto-generate if not found)
+    let _ = auth::get_or_init_jwt_secret();
+    log::info!(
+        "JWT token lifetime: {} hours",
+        auth::jwt_token_lifetime_hours()
+    );
+
+    let pool = init_pool();
+
+    // Run database migrations automatically at startup
+    run_migrations(&pool).await;
+
+    // Expired shares are already ignored when checking access; this keeps the tables tidy
+    spawn_expired_share_cleanup(pool.clone());
+    spawn_trash_purge(pool.clone());
 
     // Rate limiting configuration from environment variables
     // Migrated from actix-governor (GPL-3.0) to actix-extensible-rate-limit (MIT/Apache-2.0)
@@ -143,6 +235,44 @@ async fn main() -> std::io::Result<()> {
         burst_size
     );
 
+    // Single sign-on is enabled only when the OIDC_* settings are present
+    let oidc_config = auth::oidc::OidcConfig::from_env().map(web::Data::new);
+    if let Some(config) = &oidc_config {
+        log::info!("OIDC single sign-on enabled via {}", config.issuer_url);
+    }
+
+    // LDAP login is enabled only when the LDAP_* settings are present
+    let ldap_config = auth::ldap::LdapConfig::from_env().map(web::Data::new);
+    if let Some(config) = &ldap_config {
+        log::info!("LDAP authentication enabled via {}", config.url);
+    }
+
+    // Proxy header authentication is enabled only when trusted proxies are configured
+    let proxy_auth_config = match auth::proxy::ProxyAuthConfig::from_env() {
+        Ok(config) => config.map(web::Data::new),
+        Err(e) => {
+            log::error!("Invalid PROXY_AUTH_TRUSTED_PROXIES: {}", e);
+            std::process::exit(1);
+        },
+    };
+    if let Some(config) = &proxy_auth_config {
+        log::info!(
+            "Proxy authentication enabled: trusting {} from {} address range(s)",
+            config.user_header,
+            config.trusted_proxies.len()
+        );
+    }
+
+    // Passkeys are enabled only when WEBAUTHN_ORIGIN is set
+    let webauthn_config = auth::webauthn::WebauthnConfig::from_env().map(web::Data::new);
+    if let Some(config) = &webauthn_config {
+        log::info!(
+            "WebAuthn passkeys enabled for {} (relying party {})",
+            config.origin,
+            config.rp_id
+        );
+    }
+
     HttpServer::new(move || {
         // Create in-memory rate limiter backend
         // Must be created inside HttpServer closure since it's not Send
@@ -183,12 +313,28 @@ async fn main() -> std::io::Result<()> {
                 actix_web::http::header::AUTHORIZATION,
                 actix_web::http::header::CONTENT_TYPE,
                 actix_web::http::header::ACCEPT,
+                actix_web::http::header::IF_MATCH,
             ])
+            .expose_headers(vec![actix_web::http::header::ETAG])
             .supports_credentials()
             .max_age(3600);
 
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .configure(|cfg| {
+                if let Some(config) = &oidc_config {
+                    cfg.app_data(config.clone());
+                }
+                if let Some(config) = &ldap_config {
+                    cfg.app_data(config.clone());
+                }
+                if let Some(config) = &proxy_auth_config {
+                    cfg.app_data(config.clone());
+                }
+                if let Some(config) = &webauthn_config {
+                    cfg.app_data(config.clone());
+                }
+            })
             // Allow up to 15 MiB JSON bodies (default is 32KB, too small for image uploads)
             .app_data(
                 web::JsonConfig::default()
@@ -27,10 +27,58 @@ pub struct Item {
     pub warranty_expiry: Option<String>,
     pub notes: Option<String>,
     pub quantity: Option<i32>,
+    pub visibility: ItemVisibility,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Who can see an item inside an inventory that is shared with other people
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemVisibility {
+    #[default]
+    Everyone, // Anyone who can open the inventory, including public share links
+    EditItems,     // Shares and grants of edit_items or higher
+    EditInventory, // Shares and grants of edit_inventory
+    Owner,         // Only the owner and users with full All Access
+}
+
+impl std::fmt::Display for ItemVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemVisibility::Everyone => write!(f, "everyone"),
+            ItemVisibility::EditItems => write!(f, "edit_items"),
+            ItemVisibility::EditInventory => write!(f, "edit_inventory"),
+            ItemVisibility::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+impl std::str::FromStr for ItemVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "everyone" => Ok(ItemVisibility::Everyone),
+            "edit_items" => Ok(ItemVisibility::EditItems),
+            "edit_inventory" => Ok(ItemVisibility::EditInventory),
+            "owner" => Ok(ItemVisibility::Owner),
+            _ => Err(format!("Invalid item visibility: {s}")),
+        }
+    }
+}
+
+/// Who is reading items, which decides whether private items are included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemViewer {
+    /// Every item regardless of visibility (CLI exports and other server-side tools)
+    Everything,
+    /// A signed-in user; only items in inventories they can open, at their access level
+    User(uuid::Uuid),
+    /// A visitor through a public share link; only items visible to everyone
+    Public,
+}
+
 #[derive(Deserialize, Debug, Validate)]
 pub struct CreateInventoryRequest {
     #[validate(length(min = 1, max = 255, message = "Name must be 1-255 characters"))]
@@ -113,6 +161,289 @@ pub struct UpdateItemRequest {
     pub inventory_id: Option<i32>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct UpdateItemVisibilityRequest {
+    pub visibility: ItemVisibility,
+}
+
+// ==================== Item History Models ====================
+
+/// Item fields kept in the history. Visibility has its own endpoint and isn't tracked.
+pub const ITEM_HISTORY_FIELDS: [&str; 10] = [
+    "inventory_id",
+    "name",
+    "description",
+    "category",
+    "location",
+    "purchase_date",
+    "purchase_price",
+    "warranty_expiry",
+    "notes",
+    "quantity",
+];
+
+/// History fields for organizer values are this prefix followed by the organizer type ID
+pub const ORGANIZER_FIELD_PREFIX: &str = "organizer:";
+
+/// What produced an item revision
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemHistoryAction {
+    Created,
+    Updated,
+    Reverted,
+}
+
+impl std::fmt::Display for ItemHistoryAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemHistoryAction::Created => write!(f, "created"),
+            ItemHistoryAction::Updated => write!(f, "updated"),
+            ItemHistoryAction::Reverted => write!(f, "reverted"),
+        }
+    }
+}
+
+impl std::str::FromStr for ItemHistoryAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created" => Ok(ItemHistoryAction::Created),
+            "updated" => Ok(ItemHistoryAction::Updated),
+            "reverted" => Ok(ItemHistoryAction::Reverted),
+            _ => Err(format!("Invalid item history action: {s}")),
+        }
+    }
+}
+
+/// One changed field of an item, with its value before and after
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItemFieldChange {
+    /// An entry of `ITEM_HISTORY_FIELDS`, or `organizer:<type id>`
+    pub field: String,
+    /// Organizer type name, for organizer values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+impl ItemFieldChange {
+    /// The tracked fields that differ between two versions of an item
+    #[must_use]
+    pub fn between_items(before: &Item, after: &Item) -> Vec<Self> {
+        let before = serde_json::to_value(before).unwrap_or_default();
+        let after = serde_json::to_value(after).unwrap_or_default();
+        ITEM_HISTORY_FIELDS
+            .iter()
+            .filter(|field| before[**field] != after[**field])
+            .map(|field| Self {
+                field: (*field).to_string(),
+                label: None,
+                old_value: before[*field].clone(),
+                new_value: after[*field].clone(),
+            })
+            .collect()
+    }
+
+    /// The fields a new item was created with
+    #[must_use]
+    pub fn for_new_item(item: &Item) -> Vec<Self> {
+        let item = serde_json::to_value(item).unwrap_or_default();
+        ITEM_HISTORY_FIELDS
+            .iter()
+            .filter(|field| !item[**field].is_null())
+            .map(|field| Self {
+                field: (*field).to_string(),
+                label: None,
+                old_value: serde_json::Value::Null,
+                new_value: item[*field].clone(),
+            })
+            .collect()
+    }
+
+    /// Organizer values that were set, changed or removed
+    #[must_use]
+    pub fn between_organizer_values(
+        before: &[ItemOrganizerValueWithDetails],
+        after: &[ItemOrganizerValueWithDetails],
+    ) -> Vec<Self> {
+        let mut type_ids: Vec<i32> = before
+            .iter()
+            .chain(after)
+            .map(|value| value.organizer_type_id)
+            .collect();
+        type_ids.sort_unstable();
+        type_ids.dedup();
+
+        let find = |values: &[ItemOrganizerValueWithDetails], type_id: i32| {
+            values
+                .iter()
+                .find(|v| v.organizer_type_id == type_id)
+                .cloned()
+        };
+        type_ids
+            .into_iter()
+            .filter_map(|type_id| {
+                let old = find(before, type_id);
+                let new = find(after, type_id);
+                let old_value = old.as_ref().map_or(serde_json::Value::Null, organizer_json);
+                let new_value = new.as_ref().map_or(serde_json::Value::Null, organizer_json);
+                (old_value != new_value).then(|| Self {
+                    field: format!("{ORGANIZER_FIELD_PREFIX}{type_id}"),
+                    label: new.or(old).map(|v| v.organizer_type_name),
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect()
+    }
+
+    /// The changes that take an item back to how it was before `later` revisions,
+    /// going from its current state
+    #[must_use]
+    pub fn reverting(
+        later: &[ItemRevision],
+        current: &Item,
+        current_organizer_values: &[ItemOrganizerValueWithDetails],
+    ) -> Vec<Self> {
+        // Undo newest first, so each field ends up with its oldest "before" value
+        let mut revisions: Vec<&ItemRevision> = later.iter().collect();
+        revisions.sort_by_key(|revision| std::cmp::Reverse(revision.revision));
+        let mut targets: Vec<(String, Option<String>, serde_json::Value)> = Vec::new();
+        for change in revisions.iter().flat_map(|revision| &revision.changes) {
+            targets.retain(|(field, _, _)| *field != change.field);
+            targets.push((
+                change.field.clone(),
+                change.label.clone(),
+                change.old_value.clone(),
+            ));
+        }
+
+        let item = serde_json::to_value(current).unwrap_or_default();
+        let mut changes: Vec<Self> = targets
+            .into_iter()
+            .filter_map(|(field, label, target)| {
+                let current_value = match field.strip_prefix(ORGANIZER_FIELD_PREFIX) {
+                    Some(type_id) => current_organizer_values
+                        .iter()
+                        .find(|v| v.organizer_type_id.to_string() == type_id)
+                        .map_or(serde_json::Value::Null, organizer_json),
+                    None => item[field.as_str()].clone(),
+                };
+                (current_value != target).then_some(Self {
+                    field,
+                    label,
+                    old_value: current_value,
+                    new_value: target,
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        changes
+    }
+
+    /// The organizer type this change is for, if it's an organizer value
+    #[must_use]
+    pub fn organizer_type_id(&self) -> Option<i32> {
+        self.field
+            .strip_prefix(ORGANIZER_FIELD_PREFIX)
+            .and_then(|id| id.parse().ok())
+    }
+}
+
+/// How an organizer value is stored in the history
+fn organizer_json(value: &ItemOrganizerValueWithDetails) -> serde_json::Value {
+    serde_json::json!({
+        "organizer_option_id": value.organizer_option_id,
+        "text_value": value.text_value,
+        "value": value.value,
+    })
+}
+
+/// A numbered revision of an item: who changed what, and when
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemRevision {
+    pub revision: i32,
+    pub action: ItemHistoryAction,
+    pub reverted_to_revision: Option<i32>,
+    pub changed_by_user_id: Option<uuid::Uuid>,
+    pub changed_by_username: Option<String>,
+    pub changed_at: DateTime<Utc>,
+    pub changes: Vec<ItemFieldChange>,
+}
+
+/// What kind of row a trash entry is
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Inventory,
+    Item,
+    OrganizerType,
+}
+
+impl TrashKind {
+    /// The table rows of this kind live in
+    #[must_use]
+    pub fn table(self) -> &'static str {
+        match self {
+            TrashKind::Inventory => "inventories",
+            TrashKind::Item => "items",
+            TrashKind::OrganizerType => "organizer_types",
+        }
+    }
+}
+
+impl std::fmt::Display for TrashKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrashKind::Inventory => write!(f, "inventory"),
+            TrashKind::Item => write!(f, "item"),
+            TrashKind::OrganizerType => write!(f, "organizer_type"),
+        }
+    }
+}
+
+impl std::str::FromStr for TrashKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "inventory" => Ok(TrashKind::Inventory),
+            "item" => Ok(TrashKind::Item),
+            "organizer_type" => Ok(TrashKind::OrganizerType),
+            _ => Err(format!("Invalid trash kind: {s}")),
+        }
+    }
+}
+
+/// An inventory, item or organizer type in the trash. Items and organizer types
+/// trashed along with their inventory aren't listed on their own; restoring the
+/// inventory brings them back.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashEntry {
+    pub kind: TrashKind,
+    pub id: i32,
+    pub name: String,
+    /// The inventory an item or organizer type belongs to
+    pub inventory_id: Option<i32>,
+    pub inventory_name: Option<String>,
+    /// Items trashed along with an inventory
+    pub item_count: Option<i64>,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by_user_id: Option<uuid::Uuid>,
+    pub deleted_by_username: Option<String>,
+}
+
+/// A user's trash, and how long entries are kept before being purged
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashListing {
+    pub entries: Vec<TrashEntry>,
+    /// Days after which trashed entries are purged, `None` when they are kept
+    pub retention_days: Option<i32>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
@@ -487,6 +818,46 @@ pub struct AdminUpdateUserRequest {
     pub is_active: Option<bool>,
 }
 
+/// An external identity source that can sign in to linked local accounts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentitySource {
+    /// OIDC single sign-on
+    Sso,
+    Ldap,
+}
+
+impl std::fmt::Display for IdentitySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentitySource::Sso => write!(f, "sso"),
+            IdentitySource::Ldap => write!(f, "ldap"),
+        }
+    }
+}
+
+impl std::str::FromStr for IdentitySource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sso" => Ok(IdentitySource::Sso),
+            "ldap" => Ok(IdentitySource::Ldap),
+            _ => Err(format!("Invalid identity source: {s}")),
+        }
+    }
+}
+
+/// A local account's link to an external identity source
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserIdentityLink {
+    pub source: IdentitySource,
+    /// Id of the identity at the source; `None` until the first login after an
+    /// admin linked the account
+    pub subject: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Login request
 #[derive(Deserialize, Debug)]
 pub struct LoginRequest {
@@ -498,9 +869,286 @@ pub struct LoginRequest {
 #[derive(Serialize, Debug)]
 pub struct LoginResponse {
     pub token: String,
+    /// Single-use token for obtaining a new access token via `/auth/refresh`
+    pub refresh_token: String,
+    /// Access token lifetime in seconds
+    pub expires_in: i64,
     pub user: UserResponse,
 }
 
+/// Request to exchange a refresh token for new tokens
+#[derive(Deserialize, Debug)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+/// A login session as shown in the user's session list
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// True for the session making the request
+    pub current: bool,
+}
+
+// ==================== OIDC Single Sign-On Models ====================
+
+/// Whether single sign-on is available, for the login page
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OidcStatusResponse {
+    pub enabled: bool,
+    pub provider_name: Option<String>,
+}
+
+/// Query parameters the identity provider sends to the callback
+#[derive(Deserialize, Debug)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// Request to exchange the one-time code from a completed SSO login for a session
+#[derive(Deserialize, Debug)]
+pub struct OidcExchangeRequest {
+    pub code: String,
+}
+
+// ==================== WebAuthn Passkey Models ====================
+
+/// Whether passkeys are available, for the login page
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WebauthnStatusResponse {
+    pub enabled: bool,
+}
+
+/// A registered passkey or security key, as shown to its owner
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebauthnCredential {
+    pub id: uuid::Uuid,
+    pub nickname: String,
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// The verification material stored for a credential
+#[derive(Debug, Clone)]
+pub struct WebauthnCredentialKey {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    /// COSE-encoded public key
+    pub public_key: Vec<u8>,
+    pub sign_count: i64,
+}
+
+/// Relying party in creation options
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicKeyCredentialRpEntity {
+    pub id: String,
+    pub name: String,
+}
+
+/// User account in creation options; `id` is the base64url user handle
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialUserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+/// An accepted signature algorithm (COSE identifier)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicKeyCredentialParameters {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i64,
+}
+
+/// A credential to exclude from registration or allow for authentication
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicKeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Base64url credential ID
+    pub id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub transports: Vec<String>,
+}
+
+/// Authenticator requirements for registration
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelectionCriteria {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+/// Options for `navigator.credentials.create()`; binary values are base64url
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialCreationOptions {
+    pub challenge: String,
+    pub rp: PublicKeyCredentialRpEntity,
+    pub user: PublicKeyCredentialUserEntity,
+    pub pub_key_cred_params: Vec<PublicKeyCredentialParameters>,
+    pub timeout: u64,
+    pub attestation: String,
+    pub authenticator_selection: AuthenticatorSelectionCriteria,
+    pub exclude_credentials: Vec<PublicKeyCredentialDescriptor>,
+}
+
+/// Options for `navigator.credentials.get()`; binary values are base64url
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialRequestOptions {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: u64,
+    pub user_verification: String,
+    pub allow_credentials: Vec<PublicKeyCredentialDescriptor>,
+}
+
+/// Authenticator response to a registration ceremony; binary values are base64url
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorAttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// Credential returned by `navigator.credentials.create()`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistrationCredential {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: AuthenticatorAttestationResponse,
+}
+
+/// Authenticator response to an authentication ceremony; binary values are base64url
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorAssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+/// Credential returned by `navigator.credentials.get()`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthenticationCredential {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: AuthenticatorAssertionResponse,
+}
+
+/// Request to finish registering a passkey
+#[derive(Deserialize, Debug, Validate)]
+pub struct WebauthnRegisterFinishRequest {
+    #[validate(length(min = 1, max = 100, message = "Nickname must be 1-100 characters"))]
+    pub nickname: String,
+    pub credential: RegistrationCredential,
+}
+
+/// Request to finish signing in (or verifying a login) with a passkey
+#[derive(Deserialize, Debug)]
+pub struct WebauthnAuthenticateFinishRequest {
+    pub credential: AuthenticationCredential,
+}
+
+/// Request to rename a passkey
+#[derive(Deserialize, Debug, Validate)]
+pub struct RenameWebauthnCredentialRequest {
+    #[validate(length(min = 1, max = 100, message = "Nickname must be 1-100 characters"))]
+    pub nickname: String,
+}
+
+// ==================== Personal API Token Models ====================
+
+/// What a personal API token is allowed to do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApiTokenScope {
+    /// Read inventories, items, organizers and images
+    #[serde(rename = "items:read")]
+    ItemsRead,
+    /// Create, update and delete items, organizers and images
+    #[serde(rename = "items:write")]
+    ItemsWrite,
+    /// Read inventory reports and statistics
+    #[serde(rename = "reports:read")]
+    ReportsRead,
+}
+
+impl std::fmt::Display for ApiTokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiTokenScope::ItemsRead => write!(f, "items:read"),
+            ApiTokenScope::ItemsWrite => write!(f, "items:write"),
+            ApiTokenScope::ReportsRead => write!(f, "reports:read"),
+        }
+    }
+}
+
+impl std::str::FromStr for ApiTokenScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "items:read" => Ok(ApiTokenScope::ItemsRead),
+            "items:write" => Ok(ApiTokenScope::ItemsWrite),
+            "reports:read" => Ok(ApiTokenScope::ReportsRead),
+            _ => Err(format!("Invalid API token scope: {s}")),
+        }
+    }
+}
+
+/// Personal API token metadata (the token itself is only shown at creation)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    /// Leading characters of the token, for telling tokens apart
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// Inventories the token is limited to; `None` means all accessible inventories
+    pub inventory_ids: Option<Vec<i32>>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Request to create a personal API token
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreateApiTokenRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiTokenScope>,
+    pub inventory_ids: Option<Vec<i32>>,
+    /// Days until the token expires; omit for a token that never expires
+    #[validate(range(min = 1, max = 3650, message = "Expiry must be 1-3650 days"))]
+    pub expires_in_days: Option<i64>,
+}
+
+/// Response for a newly created API token
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateApiTokenResponse {
+    /// The secret token - shown once, never retrievable again
+    pub token: String,
+    pub api_token: ApiToken,
+}
+
 /// Request to update current user's profile
 #[derive(Deserialize, Debug)]
 pub struct UpdateProfileRequest {
@@ -525,62 +1173,344 @@ pub struct Claims {
     pub iat: u64, // Issued at (Unix timestamp)
     #[serde(default)]
     pub totp_pending: bool, // True if user needs to complete TOTP verification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>, // Login session the token belongs to
+}
+
+// ==================== Audit Log Models ====================
+
+/// Security-relevant actions recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Login,
+    LoginFailed,
+    LoginLocked,
+    Logout,
+    PasswordChanged,
+    SessionRevoked,
+    ApiTokenCreated,
+    ApiTokenDeleted,
+    PasswordResetLinkCreated,
+    PasswordReset,
+    InvitationCreated,
+    InvitationRevoked,
+    InvitationAccepted,
+    RecoveryCodesGenerated,
+    RecoveryCodeUsed,
+    TotpEnabled,
+    TotpVerify,
+    TotpModeChanged,
+    TotpDisabled,
+    TotpRecovery,
+    UserRegistered,
+    UserCreated,
+    UserUpdated,
+    UserDeleted,
+    UserUnlocked,
+    UserIdentityLinked,
+    UserIdentityUnlinked,
+    ShareCreated,
+    ShareUpdated,
+    ShareDeleted,
+    ShareAccepted,
+    ShareDeclined,
+    ShareLinkCreated,
+    ShareLinkRevoked,
+    ItemVisibilityChanged,
+    GroupShareCreated,
+    GroupShareUpdated,
+    GroupShareDeleted,
+    GroupCreated,
+    GroupUpdated,
+    GroupDeleted,
+    GroupMemberAdded,
+    GroupMemberUpdated,
+    GroupMemberRemoved,
+    RoleCreated,
+    RoleUpdated,
+    RoleDeleted,
+    AccessGrantCreated,
+    AccessGrantUpdated,
+    AccessGrantDeleted,
+    AccessGrantAccepted,
+    AccessGrantDeclined,
+    OwnershipTransferred,
+    BackupCreated,
+    BackupUploaded,
+    BackupDownloaded,
+    BackupRestored,
+    BackupDeleted,
+}
+
+impl AuditAction {
+    /// Name stored in the log, e.g. `auth.login`
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Login => "auth.login",
+            AuditAction::LoginFailed => "auth.login_failed",
+            AuditAction::LoginLocked => "auth.login_locked",
+            AuditAction::Logout => "auth.logout",
+            AuditAction::PasswordChanged => "auth.password_changed",
+            AuditAction::SessionRevoked => "auth.session_revoked",
+            AuditAction::ApiTokenCreated => "auth.api_token_created",
+            AuditAction::ApiTokenDeleted => "auth.api_token_deleted",
+            AuditAction::PasswordResetLinkCreated => "auth.password_reset_link_created",
+            AuditAction::PasswordReset => "auth.password_reset",
+            AuditAction::InvitationCreated => "invitation.created",
+            AuditAction::InvitationRevoked => "invitation.revoked",
+            AuditAction::InvitationAccepted => "invitation.accepted",
+            AuditAction::RecoveryCodesGenerated => "recovery_codes.generated",
+            AuditAction::RecoveryCodeUsed => "recovery_codes.used",
+            AuditAction::TotpEnabled => "totp.enabled",
+            AuditAction::TotpVerify => "totp.verify",
+            AuditAction::TotpModeChanged => "totp.mode_changed",
+            AuditAction::TotpDisabled => "totp.disabled",
+            AuditAction::TotpRecovery => "totp.recovery",
+            AuditAction::UserRegistered => "user.registered",
+            AuditAction::UserCreated => "user.created",
+            AuditAction::UserUpdated => "user.updated",
+            AuditAction::UserDeleted => "user.deleted",
+            AuditAction::UserUnlocked => "user.unlocked",
+            AuditAction::UserIdentityLinked => "user.identity_linked",
+            AuditAction::UserIdentityUnlinked => "user.identity_unlinked",
+            AuditAction::ShareCreated => "share.created",
+            AuditAction::ShareUpdated => "share.updated",
+            AuditAction::ShareDeleted => "share.deleted",
+            AuditAction::ShareAccepted => "share.accepted",
+            AuditAction::ShareDeclined => "share.declined",
+            AuditAction::ShareLinkCreated => "share_link.created",
+            AuditAction::ShareLinkRevoked => "share_link.revoked",
+            AuditAction::ItemVisibilityChanged => "item.visibility_changed",
+            AuditAction::GroupShareCreated => "share.group_created",
+            AuditAction::GroupShareUpdated => "share.group_updated",
+            AuditAction::GroupShareDeleted => "share.group_deleted",
+            AuditAction::GroupCreated => "group.created",
+            AuditAction::GroupUpdated => "group.updated",
+            AuditAction::GroupDeleted => "group.deleted",
+            AuditAction::GroupMemberAdded => "group.member_added",
+            AuditAction::GroupMemberUpdated => "group.member_updated",
+            AuditAction::GroupMemberRemoved => "group.member_removed",
+            AuditAction::RoleCreated => "role.created",
+            AuditAction::RoleUpdated => "role.updated",
+            AuditAction::RoleDeleted => "role.deleted",
+            AuditAction::AccessGrantCreated => "access_grant.created",
+            AuditAction::AccessGrantUpdated => "access_grant.updated",
+            AuditAction::AccessGrantDeleted => "access_grant.deleted",
+            AuditAction::AccessGrantAccepted => "access_grant.accepted",
+            AuditAction::AccessGrantDeclined => "access_grant.declined",
+            AuditAction::OwnershipTransferred => "inventory.ownership_transferred",
+            AuditAction::BackupCreated => "backup.created",
+            AuditAction::BackupUploaded => "backup.uploaded",
+            AuditAction::BackupDownloaded => "backup.downloaded",
+            AuditAction::BackupRestored => "backup.restored",
+            AuditAction::BackupDeleted => "backup.deleted",
+        }
+    }
 }
 
-// ==================== Permission Models ====================
+/// Whether an audited action went through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Success,
+    /// The attempt failed, e.g. a wrong password or code
+    Failure,
+    /// The attempt was refused, e.g. a locked-out or deactivated account
+    Denied,
+}
 
-/// Permission levels for shared inventories (per-inventory)
-/// The 4-tier system:
-/// 1. View - View shared inventory and its items
-/// 2. `EditItems` - View + Edit item details only (not add/remove)
-/// 3. `EditInventory` - `EditItems` + Edit inventory details, add/remove items
-/// 4. `AllAccess` - User-to-user grant via `UserAccessGrant` table (full access to ALL grantor's inventories)
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum PermissionLevel {
-    View,          // Can only view inventory and items
-    EditItems,     // Can view and edit item details (not add/remove items)
-    EditInventory, // Can view, edit items, add/remove items, edit inventory details
+impl AuditOutcome {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+            AuditOutcome::Denied => "denied",
+        }
+    }
 }
 
-impl PermissionLevel {
-    /// Can view inventory and items
-    #[must_use = "permission check result should be used to enforce access control"]
-    pub fn can_view(&self) -> bool {
-        true // All levels can view
+/// An audit log entry to record; client details are filled in from the request
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: AuditAction,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub outcome: AuditOutcome,
+    pub details: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl NewAuditEvent {
+    /// A successful action with no actor or target yet
+    #[must_use]
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            actor_id: None,
+            actor_username: None,
+            action,
+            target_type: None,
+            target_id: None,
+            outcome: AuditOutcome::Success,
+            details: None,
+            ip_address: None,
+            user_agent: None,
+        }
     }
 
-    /// Can edit existing item details (name, description, etc.)
-    #[must_use = "permission check result should be used to enforce access control"]
-    pub fn can_edit_items(&self) -> bool {
-        matches!(
-            self,
-            PermissionLevel::EditItems | PermissionLevel::EditInventory
-        )
+    #[must_use]
+    pub fn actor(mut self, user_id: Uuid, username: &str) -> Self {
+        self.actor_id = Some(user_id);
+        self.actor_username = Some(username.to_string());
+        self
     }
 
-    /// Can add new items to inventory
-    #[must_use = "permission check result should be used to enforce access control"]
+    /// Record the username given by an anonymous request, e.g. a failed login
+    #[must_use]
+    pub fn claimed_username(mut self, username: &str) -> Self {
+        self.actor_username = Some(username.chars().take(255).collect());
+        self
+    }
+
+    #[must_use]
+    pub fn target(mut self, target_type: &str, target_id: &impl ToString) -> Self {
+        self.target_type = Some(target_type.to_string());
+        self.target_id = Some(target_id.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn outcome(mut self, outcome: AuditOutcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    #[must_use]
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+/// A recorded audit log entry
+#[derive(Serialize, Debug, Clone)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub outcome: String,
+    pub details: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Filters for the admin audit log query and export
+#[derive(Deserialize, Debug, Default)]
+pub struct AuditEventQuery {
+    /// Events where this user is the actor or the target
+    pub user_id: Option<Uuid>,
+    /// Exact action name, or a prefix ending in `.` such as `auth.`
+    pub action: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ==================== Permission Models ====================
+
+/// Permission levels for shared inventories (per-inventory)
+/// The 4-tier system:
+/// 1. View - View shared inventory and its items
+/// 2. `EditItems` - View + Edit item details only (not add/remove)
+/// 3. `EditInventory` - `EditItems` + Edit inventory details, add/remove items
+/// 4. `AllAccess` - User-to-user grant via `UserAccessGrant` table (full access to ALL grantor's inventories)
+///
+/// Levels are ordered from least to most access.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionLevel {
+    #[default]
+    View, // Can only view inventory and items
+    EditItems,     // Can view and edit item details (not add/remove items)
+    EditInventory, // Can view, edit items, add/remove items, edit inventory details
+}
+
+impl PermissionLevel {
+    /// Capabilities of the built-in role for this level
+    #[must_use]
+    pub fn capabilities(self) -> &'static [Capability] {
+        match self {
+            PermissionLevel::View => &[Capability::View, Capability::Export],
+            PermissionLevel::EditItems => {
+                &[Capability::View, Capability::EditItems, Capability::Export]
+            },
+            PermissionLevel::EditInventory => &[
+                Capability::View,
+                Capability::AddItems,
+                Capability::EditItems,
+                Capability::RemoveItems,
+                Capability::ManageOrganizers,
+                Capability::EditInventory,
+                Capability::Export,
+            ],
+        }
+    }
+
+    /// The built-in level closest to a set of capabilities, used wherever only the
+    /// three levels are understood (item visibility, backups, deleted roles)
+    #[must_use]
+    pub fn closest(capabilities: &[Capability]) -> Self {
+        if capabilities.contains(&Capability::EditInventory) {
+            PermissionLevel::EditInventory
+        } else if capabilities.contains(&Capability::EditItems) {
+            PermissionLevel::EditItems
+        } else {
+            PermissionLevel::View
+        }
+    }
+
+    /// Can view inventory and items
+    #[must_use = "permission check result should be used to enforce access control"]
+    pub fn can_view(&self) -> bool {
+        self.capabilities().contains(&Capability::View)
+    }
+
+    /// Can edit existing item details (name, description, etc.)
+    #[must_use = "permission check result should be used to enforce access control"]
+    pub fn can_edit_items(&self) -> bool {
+        self.capabilities().contains(&Capability::EditItems)
+    }
+
+    /// Can add new items to inventory
+    #[must_use = "permission check result should be used to enforce access control"]
     pub fn can_add_items(&self) -> bool {
-        matches!(self, PermissionLevel::EditInventory)
+        self.capabilities().contains(&Capability::AddItems)
     }
 
     /// Can remove items from inventory
     #[must_use = "permission check result should be used to enforce access control"]
     pub fn can_remove_items(&self) -> bool {
-        matches!(self, PermissionLevel::EditInventory)
+        self.capabilities().contains(&Capability::RemoveItems)
     }
 
     /// Can edit inventory details (name, description, etc.)
     #[must_use = "permission check result should be used to enforce access control"]
     pub fn can_edit_inventory(&self) -> bool {
-        matches!(self, PermissionLevel::EditInventory)
+        self.capabilities().contains(&Capability::EditInventory)
     }
 
     /// Can manage organizers for inventory
     #[must_use = "permission check result should be used to enforce access control"]
     pub fn can_manage_organizers(&self) -> bool {
-        matches!(self, PermissionLevel::EditInventory)
+        self.capabilities().contains(&Capability::ManageOrganizers)
     }
 
     // Legacy method - maps to can_edit_items for backward compatibility
@@ -631,6 +1561,203 @@ impl std::str::FromStr for PermissionLevel {
     }
 }
 
+/// A single thing a share lets its recipient do with an inventory.
+/// Built-in roles (see [`PermissionLevel::capabilities`]) and custom roles are both
+/// sets of capabilities.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    View,             // Open the inventory and its items
+    AddItems,         // Create items
+    EditItems,        // Change existing items
+    RemoveItems,      // Delete items
+    ManageOrganizers, // Create, change and delete organizers
+    EditInventory,    // Change the inventory's own details
+    ManageShares,     // Share the inventory and change who it is shared with
+    Export,           // Run reports and export the inventory's items
+}
+
+impl Capability {
+    /// Every capability, in display order
+    pub const ALL: [Capability; 8] = [
+        Capability::View,
+        Capability::AddItems,
+        Capability::EditItems,
+        Capability::RemoveItems,
+        Capability::ManageOrganizers,
+        Capability::EditInventory,
+        Capability::ManageShares,
+        Capability::Export,
+    ];
+
+    /// Sort and deduplicate a capability list; every role can at least view
+    #[must_use]
+    pub fn normalize(capabilities: &[Capability]) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|c| *c == Capability::View || capabilities.contains(c))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Capability::View => write!(f, "view"),
+            Capability::AddItems => write!(f, "add_items"),
+            Capability::EditItems => write!(f, "edit_items"),
+            Capability::RemoveItems => write!(f, "remove_items"),
+            Capability::ManageOrganizers => write!(f, "manage_organizers"),
+            Capability::EditInventory => write!(f, "edit_inventory"),
+            Capability::ManageShares => write!(f, "manage_shares"),
+            Capability::Export => write!(f, "export"),
+        }
+    }
+}
+
+impl std::str::FromStr for Capability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Capability::ALL
+            .into_iter()
+            .find(|c| c.to_string() == s)
+            .ok_or_else(|| format!("Invalid capability: {s}"))
+    }
+}
+
+/// An owner-defined role that can be given to shares of the owner's inventories
+#[derive(Serialize, Debug, Clone)]
+pub struct InventoryRole {
+    pub id: Uuid,
+    pub owner_user_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub capabilities: Vec<Capability>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A built-in role, one per [`PermissionLevel`]
+#[derive(Serialize, Debug, Clone)]
+pub struct BuiltinRole {
+    pub permission_level: PermissionLevel,
+    pub capabilities: Vec<Capability>,
+}
+
+/// The roles a user can pick from when sharing their inventories
+#[derive(Serialize, Debug, Clone)]
+pub struct RolesResponse {
+    pub builtin: Vec<BuiltinRole>,
+    pub custom: Vec<InventoryRole>,
+}
+
+/// Request to create a custom role
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreateInventoryRoleRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    pub description: Option<String>,
+    pub capabilities: Vec<Capability>,
+}
+
+/// Request to rename a custom role or change its capabilities
+#[derive(Deserialize, Debug, Validate)]
+pub struct UpdateInventoryRoleRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub capabilities: Option<Vec<Capability>>,
+}
+
+/// What a share gives its recipient: a built-in level or one of the owner's roles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareRole {
+    Builtin(PermissionLevel),
+    Custom {
+        id: Uuid,
+        name: String,
+        /// Closest built-in level, see [`PermissionLevel::closest`]
+        level: PermissionLevel,
+    },
+}
+
+impl ShareRole {
+    /// The permission level stored alongside the share
+    #[must_use]
+    pub fn level(&self) -> PermissionLevel {
+        match self {
+            ShareRole::Builtin(level) | ShareRole::Custom { level, .. } => *level,
+        }
+    }
+
+    /// The custom role's ID, if any
+    #[must_use]
+    pub fn role_id(&self) -> Option<Uuid> {
+        match self {
+            ShareRole::Builtin(_) => None,
+            ShareRole::Custom { id, .. } => Some(*id),
+        }
+    }
+}
+
+impl From<PermissionLevel> for ShareRole {
+    fn from(level: PermissionLevel) -> Self {
+        ShareRole::Builtin(level)
+    }
+}
+
+impl From<&InventoryRole> for ShareRole {
+    fn from(role: &InventoryRole) -> Self {
+        ShareRole::Custom {
+            id: role.id,
+            name: role.name.clone(),
+            level: PermissionLevel::closest(&role.capabilities),
+        }
+    }
+}
+
+impl std::fmt::Display for ShareRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareRole::Builtin(level) => write!(f, "{level}"),
+            ShareRole::Custom { name, .. } => write!(f, "role '{name}'"),
+        }
+    }
+}
+
+/// Whether the recipient has answered a share or All Access invitation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareStatus {
+    Pending,  // Waiting for the recipient; gives no access yet
+    Accepted, // Gives access
+    Declined, // Turned down by the recipient; kept so the owner can see it
+}
+
+impl std::fmt::Display for ShareStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareStatus::Pending => write!(f, "pending"),
+            ShareStatus::Accepted => write!(f, "accepted"),
+            ShareStatus::Declined => write!(f, "declined"),
+        }
+    }
+}
+
+impl std::str::FromStr for ShareStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(ShareStatus::Pending),
+            "accepted" => Ok(ShareStatus::Accepted),
+            "declined" => Ok(ShareStatus::Declined),
+            _ => Err(format!("Invalid share status: {s}")),
+        }
+    }
+}
+
 /// Inventory share record
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InventoryShare {
@@ -639,6 +1766,10 @@ pub struct InventoryShare {
     pub shared_with_user_id: Uuid,
     pub shared_by_user_id: Uuid,
     pub permission_level: PermissionLevel,
+    pub role_id: Option<Uuid>, // Custom role; overrides permission_level when set
+    pub status: ShareStatus,
+    pub starts_at: Option<DateTime<Utc>>, // Share is ignored before this time
+    pub expires_at: Option<DateTime<Utc>>, // Share is ignored from this time on
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -651,6 +1782,13 @@ pub struct InventoryShareWithUser {
     pub shared_with_user: UserResponse,
     pub shared_by_user: UserResponse,
     pub permission_level: PermissionLevel,
+    pub role_id: Option<Uuid>,
+    pub role_name: Option<String>,
+    pub status: ShareStatus,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool, // Whether the share currently grants access
+    pub expires_in_seconds: Option<i64>, // Time left before the share expires
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -659,24 +1797,293 @@ pub struct InventoryShareWithUser {
 #[derive(Deserialize, Debug)]
 pub struct CreateInventoryShareRequest {
     pub shared_with_username: String, // Username of user to share with
+    #[serde(default)]
     pub permission_level: PermissionLevel,
+    #[serde(default)]
+    pub role_id: Option<Uuid>, // One of the owner's custom roles, instead of permission_level
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A share waiting for the current user to accept or decline it
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingInventoryShare {
+    pub id: Uuid,
+    pub inventory_id: i32,
+    pub inventory_name: String,
+    pub shared_by_user: UserResponse,
+    pub permission_level: PermissionLevel,
+    pub role_name: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
 
 /// Request to update share permissions
 #[derive(Deserialize, Debug)]
 pub struct UpdateInventoryShareRequest {
+    #[serde(default)]
+    pub permission_level: PermissionLevel,
+    #[serde(default)]
+    pub role_id: Option<Uuid>,
+}
+
+// ==================== Household Group Models ====================
+
+/// A member's role within a group
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupRole {
+    Member,  // Gets access to inventories shared with the group
+    Manager, // Can also rename the group and add or remove members
+}
+
+impl std::fmt::Display for GroupRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupRole::Member => write!(f, "member"),
+            GroupRole::Manager => write!(f, "manager"),
+        }
+    }
+}
+
+impl std::str::FromStr for GroupRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "member" => Ok(GroupRole::Member),
+            "manager" => Ok(GroupRole::Manager),
+            _ => Err(format!("Invalid group role: {s}")),
+        }
+    }
+}
+
+/// A household group inventories can be shared with
+#[derive(Serialize, Debug, Clone)]
+pub struct UserGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub member_count: i64,
+    /// The requesting user's role, or `None` when they are not a member (admins)
+    pub my_role: Option<GroupRole>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A group member with user details
+#[derive(Serialize, Debug, Clone)]
+pub struct GroupMember {
+    pub user: UserResponse,
+    pub role: GroupRole,
+    pub added_at: DateTime<Utc>,
+}
+
+/// A group with its members for API responses
+#[derive(Serialize, Debug)]
+pub struct UserGroupWithMembers {
+    #[serde(flatten)]
+    pub group: UserGroup,
+    pub members: Vec<GroupMember>,
+}
+
+/// Request to create a group; the creator becomes its first manager
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreateGroupRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Request to rename or describe a group
+#[derive(Deserialize, Debug, Validate)]
+pub struct UpdateGroupRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Request to add a user to a group
+#[derive(Deserialize, Debug)]
+pub struct AddGroupMemberRequest {
+    pub username: String,
+    /// Defaults to `member`
+    pub role: Option<GroupRole>,
+}
+
+/// Request to change a member's role
+#[derive(Deserialize, Debug)]
+pub struct UpdateGroupMemberRequest {
+    pub role: GroupRole,
+}
+
+/// An inventory shared with a group
+#[derive(Serialize, Debug, Clone)]
+pub struct InventoryGroupShare {
+    pub id: Uuid,
+    pub inventory_id: i32,
+    pub group_id: Uuid,
+    pub group_name: String,
+    pub shared_by_user_id: Uuid,
+    pub permission_level: PermissionLevel,
+    pub role_id: Option<Uuid>,
+    pub role_name: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool, // Whether the share currently grants access
+    pub expires_in_seconds: Option<i64>, // Time left before the share expires
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to share an inventory with a group.
+/// Unlike a direct share there is no invitation: it applies to every member
+/// as soon as its schedule starts, since the group's managers pick the members.
+#[derive(Deserialize, Debug)]
+pub struct CreateInventoryGroupShareRequest {
+    pub group_id: Uuid,
+    #[serde(default)]
     pub permission_level: PermissionLevel,
+    #[serde(default)]
+    pub role_id: Option<Uuid>,
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// ==================== Public Share Link Models ====================
+
+/// Revocable public link giving read-only access to one inventory
+#[derive(Serialize, Debug, Clone)]
+pub struct ShareLink {
+    pub id: Uuid,
+    pub inventory_id: i32,
+    pub created_by: Uuid,
+    pub name: String,
+    /// Leading characters of the token, for telling links apart
+    pub token_prefix: String,
+    pub password_protected: bool,
+    pub access_count: i64,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Request to create a public share link
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreateShareLinkRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    /// Password visitors must enter; omit for a link that opens directly
+    pub password: Option<String>,
+    /// Days until the link expires; omit for a link that never expires
+    #[validate(range(min = 1, max = 365, message = "Expiry must be 1-365 days"))]
+    pub expires_in_days: Option<i64>,
+}
+
+/// Response for a newly created share link (the token is only returned once)
+#[derive(Serialize, Debug)]
+pub struct CreateShareLinkResponse {
+    pub share_link: ShareLink,
+    pub token: String,
+    /// Path of the web app page that shows the shared inventory
+    pub share_path: String,
+}
+
+/// Open a share link (no authentication required)
+#[derive(Deserialize, Debug)]
+pub struct ViewShareLinkRequest {
+    pub token: String,
+    pub password: Option<String>,
+}
+
+/// Item as shown through a share link, with its organizer values
+#[derive(Serialize, Debug)]
+pub struct SharedItem {
+    #[serde(flatten)]
+    pub item: Item,
+    pub organizer_values: Vec<ItemOrganizerValueWithDetails>,
+}
+
+/// Read-only view of an inventory shown through a share link
+#[derive(Serialize, Debug)]
+pub struct SharedInventoryView {
+    pub name: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub image_url: Option<String>,
+    pub items: Vec<SharedItem>,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 // ==================== User Access Grant Models (All Access Tier) ====================
 
-/// User access grant - grants a user full access to ALL inventories of another user
+/// What an All Access grant allows in each of the grantor's inventories
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessGrantLevel {
+    View,          // Same as a view share on every inventory
+    EditItems,     // Same as an edit_items share on every inventory
+    EditInventory, // Same as an edit_inventory share on every inventory
+    #[default]
+    Full, // Everything the owner can do, including deleting inventories and managing sharing
+}
+
+impl AccessGrantLevel {
+    /// The per-inventory share level this grant is equivalent to, or `None` for `Full`,
+    /// which goes beyond what any share can give
+    #[must_use]
+    pub fn share_level(self) -> Option<PermissionLevel> {
+        match self {
+            AccessGrantLevel::View => Some(PermissionLevel::View),
+            AccessGrantLevel::EditItems => Some(PermissionLevel::EditItems),
+            AccessGrantLevel::EditInventory => Some(PermissionLevel::EditInventory),
+            AccessGrantLevel::Full => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AccessGrantLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessGrantLevel::View => write!(f, "view"),
+            AccessGrantLevel::EditItems => write!(f, "edit_items"),
+            AccessGrantLevel::EditInventory => write!(f, "edit_inventory"),
+            AccessGrantLevel::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccessGrantLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "view" => Ok(AccessGrantLevel::View),
+            "edit_items" => Ok(AccessGrantLevel::EditItems),
+            "edit_inventory" => Ok(AccessGrantLevel::EditInventory),
+            "full" => Ok(AccessGrantLevel::Full),
+            _ => Err(format!("Invalid access grant level: {s}")),
+        }
+    }
+}
+
+/// User access grant - grants a user access to ALL inventories of another user
 /// This is the "All Access" tier of the 4-tier permission system
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserAccessGrant {
     pub id: Uuid,
     pub grantor_user_id: Uuid, // User granting access
     pub grantee_user_id: Uuid, // User receiving access
+    pub permission_level: AccessGrantLevel,
+    pub status: ShareStatus,
+    pub starts_at: Option<DateTime<Utc>>, // Grant is ignored before this time
+    pub expires_at: Option<DateTime<Utc>>, // Grant is ignored from this time on
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -687,6 +2094,12 @@ pub struct UserAccessGrantWithUsers {
     pub id: Uuid,
     pub grantor: UserResponse,
     pub grantee: UserResponse,
+    pub permission_level: AccessGrantLevel,
+    pub status: ShareStatus,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool, // Whether the grant currently gives access
+    pub expires_in_seconds: Option<i64>, // Time left before the grant expires
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -695,6 +2108,26 @@ pub struct UserAccessGrantWithUsers {
 #[derive(Deserialize, Debug)]
 pub struct CreateUserAccessGrantRequest {
     pub grantee_username: String, // Username of user to grant access to
+    #[serde(default)]
+    pub permission_level: AccessGrantLevel, // Defaults to full access
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Request to change what an All Access grant allows
+#[derive(Deserialize, Debug)]
+pub struct UpdateUserAccessGrantRequest {
+    pub permission_level: AccessGrantLevel,
+}
+
+/// Inventories the current user can open, plus invitations waiting for an answer
+#[derive(Serialize, Debug)]
+pub struct MyInventoriesResponse {
+    pub inventories: Vec<Inventory>,
+    pub pending_shares: Vec<PendingInventoryShare>,
+    pub pending_access_grants: Vec<UserAccessGrantWithUsers>,
 }
 
 // ==================== Ownership Transfer Models ====================
@@ -733,8 +2166,50 @@ pub struct EffectivePermissions {
     pub can_manage_sharing: bool,
     pub can_manage_organizers: bool,
     pub is_owner: bool,
-    pub has_all_access: bool, // Via UserAccessGrant
+    pub has_all_access: bool, // Access comes from a UserAccessGrant, at any level
     pub permission_source: PermissionSource,
+    /// Every capability the user holds; the `can_*` flags are derived from these
+    pub capabilities: Vec<Capability>,
+}
+
+impl EffectivePermissions {
+    /// Permissions made of `capabilities` from `source`. `full_control` marks the owner
+    /// and full All Access, the only ones who may delete the inventory.
+    #[must_use]
+    pub fn from_capabilities(
+        capabilities: &[Capability],
+        source: PermissionSource,
+        full_control: bool,
+    ) -> Self {
+        let capabilities = if full_control {
+            Capability::ALL.to_vec()
+        } else if source == PermissionSource::None {
+            Vec::new()
+        } else {
+            Capability::normalize(capabilities)
+        };
+        let has = |capability| capabilities.contains(&capability);
+        EffectivePermissions {
+            can_view: has(Capability::View),
+            can_edit_items: has(Capability::EditItems),
+            can_add_items: has(Capability::AddItems),
+            can_remove_items: has(Capability::RemoveItems),
+            can_edit_inventory: has(Capability::EditInventory),
+            can_delete_inventory: full_control,
+            can_manage_sharing: has(Capability::ManageShares),
+            can_manage_organizers: has(Capability::ManageOrganizers),
+            is_owner: source == PermissionSource::Owner,
+            has_all_access: source == PermissionSource::AllAccess,
+            permission_source: source,
+            capabilities,
+        }
+    }
+
+    /// Whether the user holds `capability`
+    #[must_use]
+    pub fn has(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
 }
 
 /// Where the user's permissions come from
@@ -744,6 +2219,7 @@ pub enum PermissionSource {
     Owner,          // User owns the inventory
     AllAccess,      // Via UserAccessGrant from owner
     InventoryShare, // Via InventoryShare record
+    GroupShare,     // Via an InventoryGroupShare to a group the user belongs to
     None,           // No access
 }
 
@@ -794,6 +2270,93 @@ pub struct SetupStatusResponse {
     pub user_count: i64,
 }
 
+// ==================== Password Reset Link Models ====================
+
+/// Admin request to issue a password reset link for a user
+#[derive(Deserialize, Debug, Default)]
+pub struct CreatePasswordResetRequest {
+    /// Hours until the link expires; defaults to 24, at most 168
+    pub expires_in_hours: Option<i64>,
+}
+
+/// A freshly issued reset link (the token is only returned once)
+#[derive(Serialize, Debug)]
+pub struct PasswordResetLinkResponse {
+    pub token: String,
+    /// Path of the web app page that redeems the token
+    pub reset_path: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Redeem a reset link and set a new password (no authentication required)
+#[derive(Deserialize, Debug)]
+pub struct RedeemPasswordResetRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+// ==================== Invitation Models ====================
+
+/// Pending or accepted user invitation
+#[derive(Serialize, Debug, Clone)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub invited_by: Uuid,
+    pub invited_by_username: String,
+    /// Role the new account is created with
+    pub is_admin: bool,
+    /// Inventory shared with the new user on acceptance, if any
+    pub inventory_id: Option<i32>,
+    pub inventory_name: Option<String>,
+    pub permission_level: Option<PermissionLevel>,
+    /// Whether the new user receives All Access to the inviter's inventories
+    pub grant_all_access: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub accepted_by: Option<Uuid>,
+}
+
+/// Request to invite a new user
+#[derive(Deserialize, Debug, Default)]
+pub struct CreateInvitationRequest {
+    /// Create the account as an administrator (admins only)
+    #[serde(default)]
+    pub is_admin: bool,
+    /// Share this inventory with the new user; requires `permission_level`
+    pub inventory_id: Option<i32>,
+    pub permission_level: Option<PermissionLevel>,
+    /// Give the new user All Access to the inviter's inventories
+    #[serde(default)]
+    pub grant_all_access: bool,
+    /// Hours until the invitation expires; defaults to 72, at most 720
+    pub expires_in_hours: Option<i64>,
+}
+
+/// A freshly created invitation (the token is only returned once)
+#[derive(Serialize, Debug)]
+pub struct CreateInvitationResponse {
+    pub invitation: Invitation,
+    pub token: String,
+    /// Path of the web app page that accepts the invitation
+    pub invite_path: String,
+}
+
+/// Look up an invitation by its token (no authentication required)
+#[derive(Deserialize, Debug)]
+pub struct InvitationTokenRequest {
+    pub token: String,
+}
+
+/// Accept an invitation by creating an account (no authentication required)
+#[derive(Deserialize, Debug)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+    pub username: String,
+    pub full_name: String,
+    pub password: String,
+}
+
 // ==================== Recovery Codes Models ====================
 
 /// Recovery code stored in database (hashed)
@@ -992,6 +2555,8 @@ pub struct TotpStatusResponse {
 pub struct LoginTotpRequiredResponse {
     pub requires_totp: bool,
     pub partial_token: String,
+    /// Whether a registered passkey can be used instead of the TOTP code
+    pub webauthn_available: bool,
     pub user: UserResponse,
 }
 
@@ -1003,6 +2568,29 @@ pub struct BackupInfo {
     pub name: String,
     pub date: String,
     pub size: String,
+    /// Stored in the local backups directory
+    #[serde(default)]
+    pub local: bool,
+    /// Replicated to off-site (S3-compatible) storage
+    #[serde(default)]
+    pub remote: bool,
+    /// Why off-site replication of this backup failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_error: Option<String>,
+}
+
+/// Off-site backup replication configuration and latest outcome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteBackupStatus {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub bucket: Option<String>,
+    pub prefix: Option<String>,
+    pub retention_count: Option<usize>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_uploaded: Option<String>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
 }
 
 /// Metadata embedded in the backup file itself
@@ -1013,6 +2601,10 @@ pub struct BackupMetadata {
     pub created_at: String,
     pub database_type: String,
     pub description: Option<String>,
+    /// SHA-256 (hex) of the `data` section as written, see
+    /// [`BackupDatabaseContent::checksum_of_json`]. Absent in older backups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 /// The complete backup data envelope
@@ -1036,15 +2628,196 @@ pub struct BackupDatabaseContent {
     pub organizer_types: serde_json::Value,
     pub organizer_options: serde_json::Value,
     pub item_organizer_values: serde_json::Value,
+    pub item_history: serde_json::Value,
     pub user_settings: serde_json::Value,
+    pub inventory_roles: serde_json::Value,
     pub inventory_shares: serde_json::Value,
     pub user_access_grants: serde_json::Value,
     pub recovery_codes: serde_json::Value,
-    #[serde(default = "default_empty_json_array")]
     pub password_reset_tokens: serde_json::Value,
+    pub user_identity_links: serde_json::Value,
+    pub api_tokens: serde_json::Value,
+    pub webauthn_credentials: serde_json::Value,
+    pub user_invitations: serde_json::Value,
+    pub inventory_share_links: serde_json::Value,
+    pub user_groups: serde_json::Value,
+    pub user_group_members: serde_json::Value,
+    pub inventory_group_shares: serde_json::Value,
+}
+
+impl BackupDatabaseContent {
+    /// Table names in dependency order (parents before children)
+    pub const TABLES: [&'static str; 26] = [
+        "users",
+        "inventories",
+        "items",
+        "categories",
+        "tags",
+        "item_tags",
+        "custom_fields",
+        "item_custom_values",
+        "organizer_types",
+        "organizer_options",
+        "item_organizer_values",
+        "item_history",
+        "user_settings",
+        "inventory_roles",
+        "inventory_shares",
+        "user_access_grants",
+        "recovery_codes",
+        "password_reset_tokens",
+        "user_identity_links",
+        "api_tokens",
+        "webauthn_credentials",
+        "user_invitations",
+        "inventory_share_links",
+        "user_groups",
+        "user_group_members",
+        "inventory_group_shares",
+    ];
+
+    /// Exported rows for each table, in the same order as [`Self::TABLES`]
+    #[must_use]
+    pub fn tables(&self) -> [(&'static str, &serde_json::Value); 26] {
+        [
+            ("users", &self.users),
+            ("inventories", &self.inventories),
+            ("items", &self.items),
+            ("categories", &self.categories),
+            ("tags", &self.tags),
+            ("item_tags", &self.item_tags),
+            ("custom_fields", &self.custom_fields),
+            ("item_custom_values", &self.item_custom_values),
+            ("organizer_types", &self.organizer_types),
+            ("organizer_options", &self.organizer_options),
+            ("item_organizer_values", &self.item_organizer_values),
+            ("item_history", &self.item_history),
+            ("user_settings", &self.user_settings),
+            ("inventory_roles", &self.inventory_roles),
+            ("inventory_shares", &self.inventory_shares),
+            ("user_access_grants", &self.user_access_grants),
+            ("recovery_codes", &self.recovery_codes),
+            ("password_reset_tokens", &self.password_reset_tokens),
+            ("user_identity_links", &self.user_identity_links),
+            ("api_tokens", &self.api_tokens),
+            ("webauthn_credentials", &self.webauthn_credentials),
+            ("user_invitations", &self.user_invitations),
+            ("inventory_share_links", &self.inventory_share_links),
+            ("user_groups", &self.user_groups),
+            ("user_group_members", &self.user_group_members),
+            ("inventory_group_shares", &self.inventory_group_shares),
+        ]
+    }
+
+    /// SHA-256 checksum (lowercase hex) of this content, see [`Self::checksum_of_json`]
+    pub fn checksum(&self) -> Result<String, serde_json::Error> {
+        Ok(Self::checksum_of_json(&serde_json::to_value(self)?))
+    }
+
+    /// SHA-256 checksum (lowercase hex) of a raw `data` section
+    ///
+    /// The JSON is serialized compactly with object keys sorted, so the
+    /// checksum is stable across a write/read round trip of the backup file
+    /// and can be checked before the backup is upgraded or deserialized.
+    #[must_use]
+    pub fn checksum_of_json(data: &serde_json::Value) -> String {
+        use sha2::{Digest, Sha256};
+
+        // `Value` keeps object keys sorted and its Display output is compact
+        format!("{:x}", Sha256::digest(data.to_string().as_bytes()))
+    }
+}
+
+/// Number of rows a dry-run restore loaded into a table
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupTableCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// Result of verifying a stored backup file without restoring it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupVerificationReport {
+    pub filename: String,
+    /// True when the backup can be restored safely
+    pub valid: bool,
+    pub version: Option<String>,
+    pub app_version: Option<String>,
+    /// `None` when the backup predates checksums
+    pub checksum_valid: Option<bool>,
+    pub version_compatible: bool,
+    pub missing_tables: Vec<String>,
+    pub integrity_errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Row counts from the dry-run restore, when requested
+    pub dry_run: Option<Vec<BackupTableCount>>,
+    pub dry_run_error: Option<String>,
+}
+
+/// Query parameters for backup verification
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct VerifyBackupQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// An inventory contained in a backup file (for selective restore)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupInventorySummary {
+    pub id: i32,
+    pub name: String,
+    pub owner_user_id: Option<uuid::Uuid>,
+    pub owner_username: Option<String>,
+    pub item_count: i64,
+    /// Whether an inventory with this id still exists in the live database
+    pub exists_in_database: bool,
+}
+
+/// A user contained in a backup file (for selective restore)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupUserSummary {
+    pub id: uuid::Uuid,
+    pub username: String,
+    pub full_name: String,
+    pub is_admin: bool,
+    pub inventory_count: i64,
+    /// Whether a user with this id still exists in the live database
+    pub exists_in_database: bool,
+}
+
+/// Browsable contents of a backup file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupContents {
+    pub filename: String,
+    pub created_at: String,
+    pub inventories: Vec<BackupInventorySummary>,
+    pub users: Vec<BackupUserSummary>,
+}
+
+/// An inventory merged back from a backup, with its newly assigned id
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RestoredInventory {
+    pub original_id: i32,
+    pub new_id: i32,
+    pub name: String,
 }
 
-/// Default empty JSON array for optional backup fields (backward compatibility)
-fn default_empty_json_array() -> serde_json::Value {
-    serde_json::Value::Array(vec![])
+/// Outcome of a selective (non-destructive) restore
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SelectiveRestoreSummary {
+    /// True when a deleted user account was recreated
+    pub user_restored: bool,
+    pub inventories: Vec<RestoredInventory>,
+    pub items_restored: i64,
+    pub organizer_types_restored: i64,
+    pub organizer_options_restored: i64,
+    pub organizer_values_restored: i64,
+    pub tags_restored: i64,
+    pub custom_values_restored: i64,
+    pub revisions_restored: i64,
+    pub shares_restored: i64,
+    pub access_grants_restored: i64,
+    /// Records that were skipped or adjusted while merging
+    /// (e.g. shares with users that no longer exist)
+    pub warnings: Vec<String>,
 }
@@ -100,7 +100,9 @@ pub async fn create_test_user(pool: &Pool, username: &str) -> (String, String) {
 /// Get a JWT token for a test user
 #[allow(dead_code)]
 pub async fn get_test_token(pool: &Pool, username: &str) -> String {
-    use home_registry::auth::{generate_token, get_or_init_jwt_secret};
+    use home_registry::auth::{
+        generate_refresh_token, generate_token, get_or_init_jwt_secret, hash_refresh_token,
+    };
     use home_registry::db::DatabaseService;
 
     // Initialize JWT secret (critical for tests)
@@ -113,7 +115,19 @@ pub async fn get_test_token(pool: &Pool, username: &str) -> String {
         .expect("Failed to get user")
         .expect("User not found");
 
-    generate_token(&user).expect("Failed to create token")
+    // Access tokens are only accepted for an active login session
+    let session_id = db
+        .create_session(
+            user.id,
+            &hash_refresh_token(&generate_refresh_token()),
+            Some("integration-test"),
+            None,
+            chrono::Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .expect("Failed to create session");
+
+    generate_token(&user, session_id).expect("Failed to create token")
 }
 
 /// Create an admin test user and return their credentials
@@ -179,13 +193,12 @@ pub async fn create_test_inventory(
     Ok(inventory.id.expect("Inventory should have ID"))
 }
 
-/// Delete a test inventory by ID
+/// Permanently delete a test inventory by ID, bypassing the trash
 #[allow(dead_code)]
 pub async fn delete_test_inventory(pool: &Pool, id: i32) -> Result<(), Box<dyn std::error::Error>> {
-    use home_registry::db::DatabaseService;
-
-    let db = DatabaseService::new(pool.clone());
-    db.delete_inventory(id).await?;
+    let conn = pool.get().await?;
+    conn.execute("DELETE FROM inventories WHERE id = $1", &[&id])
+        .await?;
     Ok(())
 }
 
@@ -218,13 +231,12 @@ pub async fn create_test_item(
     Ok(item.id.expect("Item should have ID"))
 }
 
-/// Delete a test item by ID
+/// Permanently delete a test item by ID, bypassing the trash
 #[allow(dead_code)]
 pub async fn delete_test_item(pool: &Pool, id: i32) -> Result<(), Box<dyn std::error::Error>> {
-    use home_registry::db::DatabaseService;
-
-    let db = DatabaseService::new(pool.clone());
-    db.delete_item(id).await?;
+    let conn = pool.get().await?;
+    conn.execute("DELETE FROM items WHERE id = $1", &[&id])
+        .await?;
     Ok(())
 }
 
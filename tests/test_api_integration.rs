@@ -552,7 +552,7 @@ async fn test_delete_inventory_cascade_deletes_items() {
         .unwrap();
 
     // Delete inventory
-    db.delete_inventory(inventory_id).await.unwrap();
+    db.delete_inventory(inventory_id, user.id).await.unwrap();
 
     // Verify item is also deleted (cascade)
     let item_check = db.get_item_by_id(item_id).await.unwrap();
@@ -726,7 +726,7 @@ async fn test_item_quantity_update() {
         inventory_id: None,
     };
 
-    db.update_item(item_id, update).await.unwrap();
+    db.update_item(item_id, update, None).await.unwrap();
 
     let updated_item = db.get_item_by_id(item_id).await.unwrap().unwrap();
     assert_eq!(updated_item.quantity, Some(10));
@@ -765,7 +765,10 @@ async fn test_get_items_by_inventory_filter() {
         .unwrap();
 
     // Get items for inv1 only
-    let inv1_items = db.get_items_by_inventory(inv1_id).await.unwrap();
+    let inv1_items = db
+        .get_items_by_inventory(inv1_id, home_registry::models::ItemViewer::User(user.id))
+        .await
+        .unwrap();
     assert!(inv1_items.iter().any(|item| item.id == Some(item1_id)));
     assert!(!inv1_items.iter().any(|item| item.id == Some(item2_id)));
 
@@ -4,7 +4,7 @@ mod common;
 
 use actix_web::{http::StatusCode, test, web, App};
 use home_registry::auth::{create_token, verify_token};
-use home_registry::models::{Claims, PermissionLevel};
+use home_registry::models::{Claims, PermissionLevel, ShareStatus};
 use serde_json::json;
 
 // ==================== Unit Tests (existing) ====================
@@ -363,8 +363,9 @@ async fn test_user_login_nonexistent_user() {
     )
     .await;
 
+    // A fresh name each run so repeated runs don't trip the login lockout
     let login_payload = json!({
-        "username": "nonexistent_user_12345",
+        "username": common::test_username("nonexistent_user"),
         "password": "Password123!"
     });
 
@@ -700,6 +701,7 @@ async fn test_token_with_expired_claims() {
         exp: (chrono::Utc::now() - chrono::Duration::hours(1)).timestamp() as u64,
         iat: chrono::Utc::now().timestamp() as u64,
         totp_pending: false,
+        sid: None,
     };
 
     let token = encode(
@@ -890,16 +892,22 @@ async fn test_shared_inventory_access_permission() {
         .await
         .unwrap();
 
-    // Share inventory with second user (view permission)
-    let share_result = db
+    // Share inventory with second user (view permission), who accepts it
+    let share = db
         .create_inventory_share(
             inventory_id,
             shared_user.id,
             owner.id,
             PermissionLevel::View,
+            None,
+            None,
         )
-        .await;
-    assert!(share_result.is_ok());
+        .await
+        .unwrap();
+    db.respond_to_inventory_share(share.id, shared_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
 
     // Verify shared user can view
     let accessible_inventories = db.get_accessible_inventories(shared_user.id).await.unwrap();
@@ -914,3 +922,2532 @@ async fn test_shared_inventory_access_permission() {
     common::delete_test_user(&pool, &owner_username).await.ok();
     common::delete_test_user(&pool, &shared_username).await.ok();
 }
+
+// ==================== Server-Side Session Tests ====================
+
+#[actix_web::test]
+async fn test_refresh_token_rotation_and_reuse_detection() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::login)
+                .service(home_registry::api::auth::refresh_session)
+                .service(home_registry::api::auth::get_current_user),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("refresh_rotate");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({"username": username, "password": password}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let first_refresh = body["data"]["refresh_token"].as_str().unwrap().to_string();
+
+    // Refreshing issues a new access token and a new refresh token
+    let req = test::TestRequest::post()
+        .uri("/api/auth/refresh")
+        .set_json(json!({"refresh_token": first_refresh}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let access_token = body["data"]["token"].as_str().unwrap().to_string();
+    let second_refresh = body["data"]["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(first_refresh, second_refresh);
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {access_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Replaying the rotated-out token is rejected and revokes the session
+    let req = test::TestRequest::post()
+        .uri("/api/auth/refresh")
+        .set_json(json!({"refresh_token": first_refresh}))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/refresh")
+        .set_json(json!({"refresh_token": second_refresh}))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {access_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_list_and_revoke_sessions() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::login)
+                .service(home_registry::api::auth::logout)
+                .service(home_registry::api::auth::get_current_user)
+                .service(home_registry::api::auth::get_my_sessions)
+                .service(home_registry::api::auth::revoke_all_my_sessions)
+                .service(home_registry::api::auth::revoke_my_session),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("session_list");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+
+    let mut tokens = Vec::new();
+    for _ in 0..3 {
+        let req = test::TestRequest::post()
+            .uri("/api/auth/login")
+            .insert_header(("User-Agent", "session-test-browser"))
+            .set_json(json!({"username": username, "password": password}))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        tokens.push(body["data"]["token"].as_str().unwrap().to_string());
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", tokens[0])))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let sessions = body["data"].as_array().unwrap();
+    assert_eq!(sessions.len(), 3);
+    assert_eq!(sessions.iter().filter(|s| s["current"] == true).count(), 1);
+    assert_eq!(sessions[0]["user_agent"], "session-test-browser");
+
+    // Revoke the second session from the first
+    let other = sessions.iter().find(|s| s["current"] == false).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/auth/sessions/{other}"))
+        .insert_header(("Authorization", format!("Bearer {}", tokens[0])))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Logging out ends only the current session
+    let req = test::TestRequest::post()
+        .uri("/api/auth/logout")
+        .insert_header(("Authorization", format!("Bearer {}", tokens[0])))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let mut still_valid = Vec::new();
+    for token in &tokens {
+        let req = test::TestRequest::get()
+            .uri("/api/auth/me")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request();
+        if test::call_service(&app, req).await.status() == StatusCode::OK {
+            still_valid.push(token.clone());
+        }
+    }
+    assert_eq!(still_valid.len(), 1);
+
+    // "Log out everywhere" ends the remaining session
+    let req = test::TestRequest::delete()
+        .uri("/api/auth/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", still_valid[0])))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {}", still_valid[0])))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_password_change_revokes_other_sessions() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::get_current_user)
+                .service(home_registry::api::auth::change_password),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("pw_revoke");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+    let current = common::get_test_token(&pool, &username).await;
+    let other = common::get_test_token(&pool, &username).await;
+
+    let req = test::TestRequest::put()
+        .uri("/api/auth/password")
+        .insert_header(("Authorization", format!("Bearer {current}")))
+        .set_json(json!({"current_password": password, "new_password": "AnotherPass456!"}))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {current}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {other}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+// ==================== Personal API Token Tests ====================
+
+#[actix_web::test]
+async fn test_api_token_scopes_and_inventory_restriction() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::get_current_user)
+                .service(home_registry::api::auth::create_api_token)
+                .service(home_registry::api::get_inventories)
+                .service(home_registry::api::get_inventory_statistics_endpoint),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("api_token");
+    let (username, _password) = common::create_test_user(&pool, &username).await;
+    let session_token = common::get_test_token(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let allowed = common::create_test_inventory(&pool, user.id, "Token Allowed")
+        .await
+        .unwrap();
+    let hidden = common::create_test_inventory(&pool, user.id, "Token Hidden")
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .set_json(json!({
+            "name": "Home Assistant",
+            "scopes": ["items:read"],
+            "inventory_ids": [allowed],
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let api_token = body["data"]["token"].as_str().unwrap().to_string();
+    assert!(api_token.starts_with("hrp_"));
+
+    // The listing only includes the inventory the token is limited to
+    let req = test::TestRequest::get()
+        .uri("/api/inventories")
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let ids: Vec<i64> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|inv| inv["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![i64::from(allowed)]);
+
+    // Reports need the reports:read scope
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/reports/inventory/statistics?inventory_id={allowed}"
+        ))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // Account endpoints are off limits to API tokens
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // A reports token cannot read an inventory outside its restriction
+    let req = test::TestRequest::post()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .set_json(json!({
+            "name": "Reports",
+            "scopes": ["reports:read"],
+            "inventory_ids": [allowed],
+        }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let reports_token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/reports/inventory/statistics?inventory_id={allowed}"
+        ))
+        .insert_header(("Authorization", format!("Bearer {reports_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/reports/inventory/statistics?inventory_id={hidden}"
+        ))
+        .insert_header(("Authorization", format!("Bearer {reports_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // Nor a report across all inventories
+    let req = test::TestRequest::get()
+        .uri("/api/reports/inventory/statistics")
+        .insert_header(("Authorization", format!("Bearer {reports_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    common::delete_test_inventory(&pool, allowed).await.ok();
+    common::delete_test_inventory(&pool, hidden).await.ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_restricted_api_token_on_item_endpoints() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::create_api_token)
+                .service(home_registry::api::delete_inventory)
+                .service(home_registry::api::get_items)
+                .service(home_registry::api::search_items)
+                .service(home_registry::api::get_item)
+                .service(home_registry::api::update_item)
+                .service(home_registry::api::delete_item),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("api_token_item");
+    let (username, _password) = common::create_test_user(&pool, &username).await;
+    let session_token = common::get_test_token(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let allowed = common::create_test_inventory(&pool, user.id, "Token Allowed")
+        .await
+        .unwrap();
+    let hidden = common::create_test_inventory(&pool, user.id, "Token Hidden")
+        .await
+        .unwrap();
+    let allowed_item = common::create_test_item(&pool, allowed, "Allowed Item")
+        .await
+        .unwrap();
+    let hidden_item = common::create_test_item(&pool, hidden, "Hidden Item")
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .set_json(json!({
+            "name": "Automation",
+            "scopes": ["items:read", "items:write"],
+            "inventory_ids": [allowed],
+        }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let api_token = body["data"]["token"].as_str().unwrap().to_string();
+
+    // Items in the allowed inventory can be read, updated and deleted
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{allowed_item}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/items/{allowed_item}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .set_json(json!({ "quantity": 3 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Lists and search only return the allowed inventory's items, whatever
+    // inventory the query string names
+    for uri in [
+        "/api/items".to_string(),
+        format!("/api/items/search/Item?inventory_id={allowed}"),
+    ] {
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {api_token}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let ids: Vec<i64> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![i64::from(allowed_item)], "{uri}");
+    }
+
+    // Writing items doesn't extend to deleting the inventory they're in
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/inventories/{allowed}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    assert!(db.get_inventory_by_id(allowed).await.unwrap().is_some());
+
+    // Items in other inventories stay off limits
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/items/{hidden_item}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{hidden_item}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/items/{allowed_item}"))
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    common::delete_test_inventory(&pool, allowed).await.ok();
+    common::delete_test_inventory(&pool, hidden).await.ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_api_token_expiry_and_deletion() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::get_my_api_tokens)
+                .service(home_registry::api::auth::create_api_token)
+                .service(home_registry::api::auth::delete_api_token)
+                .service(home_registry::api::get_inventories),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("api_token_del");
+    let (username, _password) = common::create_test_user(&pool, &username).await;
+    let session_token = common::get_test_token(&pool, &username).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .set_json(json!({"name": "Backup script", "scopes": ["items:read"], "expires_in_days": 30}))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let api_token = body["data"]["token"].as_str().unwrap().to_string();
+    let token_id = body["data"]["api_token"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(body["data"]["api_token"]["expires_at"].is_string());
+
+    let req = test::TestRequest::get()
+        .uri("/api/inventories")
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Listing shows when the token was last used but never the token itself
+    let req = test::TestRequest::get()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let listed = &body["data"][0];
+    assert_eq!(listed["scopes"], json!(["items:read"]));
+    assert!(listed["last_used_at"].is_string());
+    assert!(listed.get("token").is_none());
+    assert!(api_token.starts_with(listed["token_prefix"].as_str().unwrap()));
+
+    // Expired tokens are rejected
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE api_tokens SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1",
+            &[&uuid::Uuid::parse_str(&token_id).unwrap()],
+        )
+        .await
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri("/api/inventories")
+        .insert_header(("Authorization", format!("Bearer {api_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/auth/tokens/{token_id}"))
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/tokens")
+        .insert_header(("Authorization", format!("Bearer {session_token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["data"].as_array().unwrap().is_empty());
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+// ==================== Proxy Authentication Tests ====================
+
+#[actix_web::test]
+async fn test_proxy_headers_from_trusted_proxy() {
+    use home_registry::auth::proxy::ProxyAuthConfig;
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let config = ProxyAuthConfig {
+        trusted_proxies: vec!["172.18.0.0/16".parse().unwrap()],
+        user_header: "Remote-User".to_string(),
+        groups_header: "Remote-Groups".to_string(),
+        name_header: "Remote-Name".to_string(),
+        admin_group: Some("registry-admins".to_string()),
+        auto_provision: true,
+    };
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .service(
+                web::scope("/api")
+                    .service(home_registry::api::auth::get_current_user)
+                    .service(home_registry::api::auth::proxy_session),
+            ),
+    )
+    .await;
+
+    // Another admin, so the proxy user can lose admin without leaving none
+    let other_admin = common::test_username("proxy_admin");
+    let (other_admin, _) = common::create_admin_user(&pool, &other_admin).await;
+
+    let username = common::test_username("proxy_user");
+    let proxied = |uri: &str, peer: &str, groups: &str| {
+        test::TestRequest::get()
+            .uri(uri)
+            .peer_addr(peer.parse().unwrap())
+            .insert_header(("Remote-User", username.as_str()))
+            .insert_header(("Remote-Name", "Proxy User"))
+            .insert_header(("Remote-Groups", groups))
+    };
+
+    // The first request from the proxy creates the account with admin from the group
+    let req = proxied("/api/auth/me", "172.18.0.2:50000", "family,registry-admins").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["username"], username.as_str());
+    assert_eq!(body["data"]["full_name"], "Proxy User");
+    assert_eq!(body["data"]["is_admin"], true);
+
+    // Leaving the group removes admin on the next request
+    let req = proxied("/api/auth/me", "172.18.0.2:50000", "family").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["is_admin"], false);
+
+    // The same headers from anywhere else are refused
+    let req = proxied("/api/auth/me", "203.0.113.9:50000", "registry-admins").to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let req = proxied("/api/auth/proxy/session", "203.0.113.9:50000", "")
+        .method(actix_web::http::Method::POST)
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+
+    // The web app can turn the proxy identity into a regular session
+    let req = proxied("/api/auth/proxy/session", "172.18.0.2:50000", "family")
+        .method(actix_web::http::Method::POST)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let token = body["data"]["token"].as_str().unwrap();
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Without headers there is nothing to trust
+    let req = test::TestRequest::post()
+        .uri("/api/auth/proxy/session")
+        .peer_addr("172.18.0.2:50000".parse().unwrap())
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &other_admin).await.ok();
+}
+
+// ==================== Login Lockout Tests ====================
+
+#[actix_web::test]
+async fn test_failed_logins_throttle_username_without_revealing_it() {
+    use home_registry::auth::lockout::FREE_ATTEMPTS;
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::login)
+                .service(home_registry::api::auth::admin_unlock_user),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("lockout_user");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+    let admin = common::test_username("lockout_admin");
+    common::create_admin_user(&pool, &admin).await;
+    let admin_token = common::get_test_token(&pool, &admin).await;
+    let missing = common::test_username("lockout_missing");
+
+    let login = |username: &str, password: &str| {
+        test::TestRequest::post()
+            .uri("/api/auth/login")
+            .set_json(json!({"username": username, "password": password}))
+            .to_request()
+    };
+
+    // A few mistakes are free, then the username is throttled whether or not it exists
+    let mut throttled = Vec::new();
+    for name in [&username, &missing] {
+        for _ in 0..=FREE_ATTEMPTS {
+            let resp = test::call_service(&app, login(name, "WrongPassword123!")).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+        let resp = test::call_service(&app, login(name, "WrongPassword123!")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers().contains_key("retry-after"));
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        throttled.push(body);
+    }
+    assert_eq!(throttled[0], throttled[1]);
+
+    // Even the right password waits out the lock, and other case spellings share it
+    let resp = test::call_service(&app, login(&username, &password)).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let resp = test::call_service(&app, login(&username.to_uppercase(), &password)).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Only admins can lift the lock
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let user_token = common::get_test_token(&pool, &username).await;
+    let unlock = |token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/api/admin/users/{}/unlock", user.id))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, unlock(&user_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, unlock(&admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, login(&username, &password)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // A successful login resets the count
+    let resp = test::call_service(&app, login(&username, "WrongPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, login(&username, &password)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &admin).await.ok();
+}
+
+// ==================== Audit Log Tests ====================
+
+#[actix_web::test]
+async fn test_audit_log_records_logins_for_admin_query_and_export() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::login)
+                .service(home_registry::api::audit::admin_export_audit_events)
+                .service(home_registry::api::audit::admin_get_audit_events),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("audit_user");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+    let admin = common::test_username("audit_admin");
+    common::create_admin_user(&pool, &admin).await;
+    let admin_token = common::get_test_token(&pool, &admin).await;
+    let user_token = common::get_test_token(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let started = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    for password in ["WrongPassword123!", password.as_str()] {
+        let req = test::TestRequest::post()
+            .uri("/api/auth/login")
+            .insert_header(("User-Agent", "audit-test"))
+            .set_json(json!({"username": username, "password": password}))
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    let get = |uri: String, token: &str| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let from = encode_query_value(&started.to_rfc3339());
+
+    // Only admins can read the log
+    let resp = test::call_service(
+        &app,
+        get(
+            format!("/api/admin/audit-events?user_id={}", user.id),
+            &user_token,
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // The successful login is attributed to the user, with client details
+    let resp = test::call_service(
+        &app,
+        get(
+            format!(
+                "/api/admin/audit-events?user_id={}&action=auth.login&from={from}",
+                user.id
+            ),
+            &admin_token,
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let events = body["data"].as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["actor_username"], username.as_str());
+    assert_eq!(events[0]["outcome"], "success");
+    assert_eq!(events[0]["user_agent"], "audit-test");
+
+    // The failed attempt has no actor, only the name that was tried
+    let resp = test::call_service(
+        &app,
+        get(
+            format!("/api/admin/audit-events?action=auth.&from={from}&limit=1000"),
+            &admin_token,
+        ),
+    )
+    .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let failed: Vec<_> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["actor_username"] == username.as_str() && e["action"] == "auth.login_failed")
+        .collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0]["outcome"], "failure");
+    assert!(failed[0]["actor_id"].is_null());
+
+    // Nothing matches before the test started
+    let resp = test::call_service(
+        &app,
+        get(
+            format!("/api/admin/audit-events?user_id={}&to={from}", user.id),
+            &admin_token,
+        ),
+    )
+    .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 0);
+
+    // CSV export applies the same filters
+    let resp = test::call_service(
+        &app,
+        get(
+            format!("/api/admin/audit-events/export?user_id={}", user.id),
+            &admin_token,
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+    let csv = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("id,created_at,actor_id"));
+    assert_eq!(lines.count(), 1);
+
+    // Entries can't be edited or removed
+    let client = pool.get().await.unwrap();
+    let update = client
+        .execute(
+            "UPDATE audit_events SET outcome = 'failure' WHERE actor_id = $1",
+            &[&user.id],
+        )
+        .await;
+    assert!(update.is_err());
+    let delete = client
+        .execute("DELETE FROM audit_events WHERE actor_id = $1", &[&user.id])
+        .await;
+    assert!(delete.is_err());
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &admin).await.ok();
+}
+
+/// Percent-encode a query string value
+fn encode_query_value(value: &str) -> String {
+    value.replace('+', "%2B").replace(':', "%3A")
+}
+
+#[actix_web::test]
+async fn test_cli_admin_commands_are_audited() {
+    use home_registry::cli::{self, Command};
+    use home_registry::models::AuditEventQuery;
+
+    let pool = common::create_test_pool();
+    let username = common::test_username("cli_audit");
+    let (username, _) = common::create_test_user(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let started = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    cli::run(
+        Command::UnlockUser {
+            username: username.clone(),
+        },
+        &pool,
+    )
+    .await
+    .expect("unlock should succeed");
+    let missing = common::test_username("cli_missing");
+    assert!(cli::run(
+        Command::DisableTotp {
+            username: missing.clone(),
+        },
+        &pool,
+    )
+    .await
+    .is_err());
+
+    let events = |action: &str| {
+        let query = AuditEventQuery {
+            action: Some(action.to_string()),
+            from: Some(started),
+            ..AuditEventQuery::default()
+        };
+        let db = &db;
+        async move { db.get_audit_events(&query, None).await.unwrap() }
+    };
+
+    // Success is recorded against the user, with the cli actor
+    let unlocked = events("user.unlocked").await;
+    let event = unlocked
+        .iter()
+        .find(|e| e.target_id == Some(user.id.to_string()))
+        .expect("unlock should be audited");
+    assert_eq!(event.actor_id, None);
+    assert_eq!(
+        event.actor_username.as_deref(),
+        Some(home_registry::api::audit::CLI_ACTOR)
+    );
+    assert_eq!(event.outcome, "success");
+
+    // Failure is recorded too, naming the user that was asked for
+    let disabled = events("totp.disabled").await;
+    let event = disabled
+        .iter()
+        .find(|e| e.details.as_deref().is_some_and(|d| d.contains(&missing)))
+        .expect("failed disable should be audited");
+    assert_eq!(
+        event.actor_username.as_deref(),
+        Some(home_registry::api::audit::CLI_ACTOR)
+    );
+    assert_eq!(event.outcome, "failure");
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+// ==================== Password Reset Link Tests ====================
+
+#[actix_web::test]
+async fn test_admin_reset_link_is_single_use_and_expires() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::login)
+                .service(home_registry::api::auth::admin_create_password_reset)
+                .service(home_registry::api::auth::redeem_password_reset),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("reset_user");
+    let (username, old_password) = common::create_test_user(&pool, &username).await;
+    let admin = common::test_username("reset_admin");
+    common::create_admin_user(&pool, &admin).await;
+    let admin_token = common::get_test_token(&pool, &admin).await;
+    let user_token = common::get_test_token(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    let issue = |token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/api/admin/users/{}/password-reset", user.id))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let redeem = |token: &str, password: &str| {
+        test::TestRequest::post()
+            .uri("/api/auth/password-reset")
+            .set_json(json!({"token": token, "new_password": password}))
+            .to_request()
+    };
+    let login = |password: &str| {
+        test::TestRequest::post()
+            .uri("/api/auth/login")
+            .set_json(json!({"username": username, "password": password}))
+            .to_request()
+    };
+
+    // Only admins can issue links
+    let resp = test::call_service(&app, issue(&user_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = test::call_service(&app, issue(&admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let first = body["data"]["token"].as_str().unwrap().to_string();
+    assert!(body["data"]["reset_path"]
+        .as_str()
+        .unwrap()
+        .ends_with(&first));
+
+    // Only the hash is stored
+    let client = pool.get().await.unwrap();
+    let stored: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM password_reset_tokens WHERE token_hash = $1",
+            &[&first],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 0);
+
+    // Issuing a new link replaces the old one
+    let resp = test::call_service(&app, issue(&admin_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let second = body["data"]["token"].as_str().unwrap().to_string();
+    let resp = test::call_service(&app, redeem(&first, "NewPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Weak passwords are refused without using up the link
+    let resp = test::call_service(&app, redeem(&second, "short")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = test::call_service(&app, redeem(&second, "NewPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, login(&old_password)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, login("NewPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Links work once
+    let resp = test::call_service(&app, redeem(&second, "OtherPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Expired links are refused
+    let resp = test::call_service(&app, issue(&admin_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let third = body["data"]["token"].as_str().unwrap().to_string();
+    client
+        .execute(
+            "UPDATE password_reset_tokens SET expires_at = NOW() - INTERVAL '1 minute'
+             WHERE user_id = $1",
+            &[&user.id],
+        )
+        .await
+        .unwrap();
+    let resp = test::call_service(&app, redeem(&third, "OtherPassword123!")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &admin).await.ok();
+}
+
+#[actix_web::test]
+async fn test_admin_links_and_unlinks_identities() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::admin_get_identity_links)
+                .service(home_registry::api::auth::admin_link_identity)
+                .service(home_registry::api::auth::admin_unlink_identity),
+        ),
+    )
+    .await;
+
+    let username = common::test_username("link_user");
+    let (username, _password) = common::create_test_user(&pool, &username).await;
+    let admin = common::test_username("link_admin");
+    common::create_admin_user(&pool, &admin).await;
+    let admin_token = common::get_test_token(&pool, &admin).await;
+    let user_token = common::get_test_token(&pool, &username).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let uri = |source: &str| format!("/api/admin/users/{}/identity-links/{source}", user.id);
+
+    // Only admins can link accounts, and only to known sources
+    let req = test::TestRequest::put()
+        .uri(&uri("sso"))
+        .insert_header(("Authorization", format!("Bearer {user_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let req = test::TestRequest::put()
+        .uri(&uri("saml"))
+        .insert_header(("Authorization", format!("Bearer {admin_token}")))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let req = test::TestRequest::put()
+        .uri(&uri("sso"))
+        .insert_header(("Authorization", format!("Bearer {admin_token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/admin/users/{}/identity-links", user.id))
+        .insert_header(("Authorization", format!("Bearer {admin_token}")))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"][0]["source"], "sso");
+    assert!(body["data"][0]["subject"].is_null());
+
+    let unlink = || {
+        test::TestRequest::delete()
+            .uri(&uri("sso"))
+            .insert_header(("Authorization", format!("Bearer {admin_token}")))
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(&app, unlink()).await.status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        test::call_service(&app, unlink()).await.status(),
+        StatusCode::NOT_FOUND
+    );
+    assert!(db
+        .get_user_identity_links(user.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &admin).await.ok();
+}
+
+// ==================== Invitation Tests ====================
+
+#[actix_web::test]
+async fn test_invitation_creates_account_with_share_once() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::invitations::create_invitation)
+                .service(home_registry::api::invitations::get_invitations)
+                .service(home_registry::api::invitations::revoke_invitation)
+                .service(home_registry::api::invitations::preview_invitation)
+                .service(home_registry::api::invitations::accept_invitation),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("invite_owner");
+    common::create_test_user(&pool, &owner).await;
+    let admin = common::test_username("invite_admin");
+    common::create_admin_user(&pool, &admin).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let admin_token = common::get_test_token(&pool, &admin).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Invite Inventory")
+        .await
+        .unwrap();
+
+    let create = |token: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/api/invitations")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(body)
+            .to_request()
+    };
+    let accept = |token: &str, username: &str| {
+        test::TestRequest::post()
+            .uri("/api/auth/invitations/accept")
+            .set_json(json!({
+                "token": token,
+                "username": username,
+                "full_name": "Invited User",
+                "password": "InvitedPass123!"
+            }))
+            .to_request()
+    };
+
+    // Owners can't invite administrators or send bare invitations
+    let resp = test::call_service(&app, create(&owner_token, json!({"is_admin": true}))).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, create(&owner_token, json!({}))).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // An owner invites someone to their inventory
+    let resp = test::call_service(
+        &app,
+        create(
+            &owner_token,
+            json!({"inventory_id": inventory_id, "permission_level": "edit_items"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+    let invitation_id = body["data"]["invitation"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(body["data"]["invite_path"]
+        .as_str()
+        .unwrap()
+        .ends_with(&token));
+
+    // The owner sees their invitation and the admin sees everyone's
+    let list = |token: &str| {
+        test::TestRequest::get()
+            .uri("/api/invitations")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    for bearer in [&owner_token, &admin_token] {
+        let resp = test::call_service(&app, list(bearer)).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|i| i["id"] == invitation_id.as_str()));
+    }
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/auth/invitations/preview")
+            .set_json(json!({"token": token}))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["inventory_name"], "Invite Inventory");
+    assert_eq!(body["data"]["permission_level"], "edit_items");
+
+    // Accepting creates the account, logs in and applies the share
+    let invitee = common::test_username("invitee");
+    let resp = test::call_service(&app, accept(&token, &invitee)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["token"].is_string());
+    let invitee_user = db.get_user_by_username(&invitee).await.unwrap().unwrap();
+    assert!(!invitee_user.is_admin);
+    let perms = db
+        .get_effective_permissions(invitee_user.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert!(!perms.can_add_items);
+
+    // Invitations work once
+    let second = common::test_username("invitee2");
+    let resp = test::call_service(&app, accept(&token, &second)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(db.get_user_by_username(&second).await.unwrap().is_none());
+
+    // Admins can invite administrators and revoke pending invitations
+    let resp = test::call_service(&app, create(&admin_token, json!({"is_admin": true}))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let admin_invite = body["data"]["token"].as_str().unwrap().to_string();
+    let admin_invite_id = body["data"]["invitation"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let revoke = |bearer: &str| {
+        test::TestRequest::delete()
+            .uri(&format!("/api/invitations/{admin_invite_id}"))
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, revoke(&owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, revoke(&admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, accept(&admin_invite, &second)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    common::delete_test_user(&pool, &invitee).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &admin).await.ok();
+}
+
+// ==================== Public Share Link Tests ====================
+
+#[actix_web::test]
+async fn test_public_share_link_lifecycle() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::share_links::get_share_links)
+                .service(home_registry::api::share_links::create_share_link)
+                .service(home_registry::api::share_links::delete_share_link)
+                .service(home_registry::api::share_links::view_share_link),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("link_owner");
+    common::create_test_user(&pool, &owner).await;
+    let stranger = common::test_username("link_stranger");
+    common::create_test_user(&pool, &stranger).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let stranger_token = common::get_test_token(&pool, &stranger).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Shared Inventory")
+        .await
+        .unwrap();
+
+    let create = |bearer: &str, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/api/inventories/{inventory_id}/share-links"))
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(body)
+            .to_request()
+    };
+    let view = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/api/public/share-links/view")
+            .set_json(body)
+            .to_request()
+    };
+
+    // Only people who can manage sharing may create links
+    let resp = test::call_service(&app, create(&stranger_token, json!({"name": "Nope"}))).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = test::call_service(&app, create(&owner_token, json!({"name": "Insurer"}))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+    let link_id = body["data"]["share_link"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(body["data"]["share_path"]
+        .as_str()
+        .unwrap()
+        .ends_with(&token));
+
+    // Anyone with the token sees the inventory, and the visit is counted
+    let resp = test::call_service(&app, view(json!({"token": token}))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["name"], "Shared Inventory");
+    assert!(body["data"]["items"].is_array());
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/api/inventories/{inventory_id}/share-links"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request(),
+    )
+    .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let listed = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|l| l["id"] == link_id.as_str())
+        .unwrap()
+        .clone();
+    assert_eq!(listed["access_count"], 1);
+    assert!(listed["last_accessed_at"].is_string());
+
+    // Password-protected links need the right password
+    let resp = test::call_service(
+        &app,
+        create(
+            &owner_token,
+            json!({"name": "Family", "password": "LinkPassword123!"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let protected = body["data"]["token"].as_str().unwrap().to_string();
+    let resp = test::call_service(&app, view(json!({"token": protected}))).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(
+        &app,
+        view(json!({"token": protected, "password": "WrongPassword123!"})),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(
+        &app,
+        view(json!({"token": protected, "password": "LinkPassword123!"})),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Expired links stop working
+    let client = pool.get().await.unwrap();
+    client
+        .execute(
+            "UPDATE inventory_share_links SET expires_at = NOW() - INTERVAL '1 minute'
+             WHERE token_prefix = $1",
+            &[&protected.chars().take(8).collect::<String>()],
+        )
+        .await
+        .unwrap();
+    let resp = test::call_service(
+        &app,
+        view(json!({"token": protected, "password": "LinkPassword123!"})),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // Revoked links stop working
+    let revoke = |bearer: &str| {
+        test::TestRequest::delete()
+            .uri(&format!("/api/share-links/{link_id}"))
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, revoke(&stranger_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, revoke(&owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, view(json!({"token": token}))).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &stranger).await.ok();
+}
+
+// ==================== Household Group Tests ====================
+
+#[actix_web::test]
+async fn test_group_membership_grants_and_revokes_access() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::groups::create_group)
+                .service(home_registry::api::groups::get_group)
+                .service(home_registry::api::groups::add_group_member)
+                .service(home_registry::api::groups::remove_group_member)
+                .service(home_registry::api::groups::create_inventory_group_share)
+                .service(home_registry::api::auth::get_inventory_permissions),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("group_owner");
+    common::create_test_user(&pool, &owner).await;
+    let member = common::test_username("group_member");
+    common::create_test_user(&pool, &member).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let member_token = common::get_test_token(&pool, &member).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let member_user = db.get_user_by_username(&member).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Group Inventory")
+        .await
+        .unwrap();
+
+    let post = |bearer: &str, uri: String, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(body)
+            .to_request()
+    };
+    let permissions = || {
+        test::TestRequest::get()
+            .uri(&format!("/api/inventories/{inventory_id}/permissions"))
+            .insert_header(("Authorization", format!("Bearer {member_token}")))
+            .to_request()
+    };
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            "/api/groups".to_string(),
+            json!({"name": "Family"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let group_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    // Only members can see or share with the group
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/api/groups/{group_id}"))
+            .insert_header(("Authorization", format!("Bearer {member_token}")))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            format!("/api/groups/{group_id}/members"),
+            json!({"username": member}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // Plain members can't manage the group
+    let resp = test::call_service(
+        &app,
+        post(
+            &member_token,
+            format!("/api/groups/{group_id}/members"),
+            json!({"username": owner}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            format!("/api/inventories/{inventory_id}/group-shares"),
+            json!({"group_id": group_id, "permission_level": "view"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let resp = test::call_service(&app, permissions()).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["can_view"], true);
+    assert_eq!(body["data"]["permission_source"], "group_share");
+
+    // The last manager can't leave, but removing a member revokes their access
+    let remove = |user_id: uuid::Uuid| {
+        test::TestRequest::delete()
+            .uri(&format!("/api/groups/{group_id}/members/{user_id}"))
+            .insert_header(("Authorization", format!("Bearer {owner_token}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, remove(owner_user.id)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, remove(member_user.id)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, permissions()).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["can_view"], false);
+
+    db.delete_group(group_id.parse().unwrap()).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &member).await.ok();
+}
+
+// ==================== Share Invitation Tests ====================
+
+#[actix_web::test]
+async fn test_shares_and_grants_wait_for_the_recipient() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::auth::create_inventory_share)
+                .service(home_registry::api::auth::accept_inventory_share)
+                .service(home_registry::api::auth::get_my_inventories)
+                .service(home_registry::api::auth::get_my_access_grants)
+                .service(home_registry::api::auth::create_access_grant)
+                .service(home_registry::api::auth::decline_access_grant)
+                .service(home_registry::api::auth::get_inventory_permissions),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("invite_share_owner");
+    common::create_test_user(&pool, &owner).await;
+    let recipient = common::test_username("invite_share_user");
+    common::create_test_user(&pool, &recipient).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let recipient_token = common::get_test_token(&pool, &recipient).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Garage")
+        .await
+        .unwrap();
+
+    let post = |bearer: &str, uri: String, body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(body)
+            .to_request()
+    };
+    let get = |bearer: &str, uri: &str| {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .to_request()
+    };
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            format!("/api/inventories/{inventory_id}/shares"),
+            json!({"shared_with_username": recipient, "permission_level": "edit_items"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["status"], "pending");
+    let share_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    // The recipient sees the invitation but not the inventory yet
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, get(&recipient_token, "/api/auth/inventories")).await;
+    assert_eq!(body["data"]["pending_shares"][0]["id"], share_id.as_str());
+    assert_eq!(
+        body["data"]["pending_shares"][0]["inventory_name"],
+        "Garage"
+    );
+    assert_eq!(body["data"]["inventories"].as_array().unwrap().len(), 0);
+
+    // Only the recipient can answer
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            format!("/api/shares/{share_id}/accept"),
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &recipient_token,
+            format!("/api/shares/{share_id}/accept"),
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, get(&recipient_token, "/api/auth/inventories")).await;
+    assert_eq!(body["data"]["pending_shares"].as_array().unwrap().len(), 0);
+    assert_eq!(body["data"]["inventories"][0]["id"], inventory_id);
+
+    // A declined All Access grant gives nothing, and the owner sees it was declined
+    let resp = test::call_service(
+        &app,
+        post(
+            &owner_token,
+            "/api/auth/access-grants".to_string(),
+            json!({"grantee_username": recipient}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let grant_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let resp = test::call_service(
+        &app,
+        post(
+            &recipient_token,
+            format!("/api/auth/access-grants/{grant_id}/decline"),
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        get(
+            &recipient_token,
+            &format!("/api/inventories/{inventory_id}/permissions"),
+        ),
+    )
+    .await;
+    assert_eq!(body["data"]["permission_source"], "inventory_share");
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, get(&owner_token, "/api/auth/access-grants")).await;
+    assert_eq!(body["data"][0]["status"], "declined");
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &recipient).await.ok();
+}
+
+// ==================== Item Visibility Tests ====================
+
+#[actix_web::test]
+async fn test_private_items_cannot_be_opened_by_id() {
+    use home_registry::models::ItemVisibility;
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::get_items)
+                .service(home_registry::api::get_item)
+                .service(home_registry::api::update_item)
+                .service(home_registry::api::delete_item)
+                .service(home_registry::api::get_item_organizer_values)
+                .service(home_registry::api::item_history::get_item_history),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("private_owner");
+    common::create_test_user(&pool, &owner).await;
+    let editor = common::test_username("private_editor");
+    common::create_test_user(&pool, &editor).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let editor_token = common::get_test_token(&pool, &editor).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let editor_user = db.get_user_by_username(&editor).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Study")
+        .await
+        .unwrap();
+    let private_id = common::create_test_item(&pool, inventory_id, "Diary")
+        .await
+        .unwrap();
+    let shared_id = common::create_test_item(&pool, inventory_id, "Desk")
+        .await
+        .unwrap();
+    db.update_item_visibility(private_id, ItemVisibility::Owner)
+        .await
+        .unwrap();
+
+    let share = db
+        .create_inventory_share(
+            inventory_id,
+            editor_user.id,
+            owner_user.id,
+            PermissionLevel::EditItems,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    db.respond_to_inventory_share(share.id, editor_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let send = |method: test::TestRequest, bearer: &str, uri: String| {
+        method
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(json!({"name": "Renamed"}))
+            .to_request()
+    };
+
+    // Items can't be read without signing in
+    for uri in [
+        "/api/items".to_string(),
+        format!("/api/items/{private_id}"),
+        format!("/api/items/{private_id}/organizer-values"),
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{uri}");
+    }
+
+    // An editor can't read, change or remove an item hidden from them
+    for (method, uri) in [
+        (test::TestRequest::get(), format!("/api/items/{private_id}")),
+        (
+            test::TestRequest::get(),
+            format!("/api/items/{private_id}/organizer-values"),
+        ),
+        (
+            test::TestRequest::get(),
+            format!("/api/items/{private_id}/history"),
+        ),
+        (test::TestRequest::put(), format!("/api/items/{private_id}")),
+        (
+            test::TestRequest::delete(),
+            format!("/api/items/{private_id}"),
+        ),
+    ] {
+        let resp = test::call_service(&app, send(method, &editor_token, uri.clone())).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body.get("data").is_none(), "{uri} leaked the item");
+    }
+    let item = db.get_item_by_id(private_id).await.unwrap().unwrap();
+    assert_eq!(item.name, "Diary");
+
+    // The item list leaves out what they can't see, and other people's inventories
+    let list_ids = |bearer: String| {
+        let app = &app;
+        async move {
+            let req = test::TestRequest::get()
+                .uri("/api/items")
+                .insert_header(("Authorization", format!("Bearer {bearer}")))
+                .to_request();
+            let body: serde_json::Value = test::call_and_read_body_json(app, req).await;
+            body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["id"].as_i64().unwrap())
+                .collect::<Vec<_>>()
+        }
+    };
+    let editor_ids = list_ids(editor_token.clone()).await;
+    assert!(editor_ids.contains(&i64::from(shared_id)));
+    assert!(!editor_ids.contains(&i64::from(private_id)));
+    let owner_ids = list_ids(owner_token.clone()).await;
+    assert!(owner_ids.contains(&i64::from(private_id)));
+    assert!(owner_ids.iter().all(|id| [private_id, shared_id]
+        .iter()
+        .any(|own| i64::from(*own) == *id)));
+
+    // Items visible to them and the owner's own items still work
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::get(),
+            &editor_token,
+            format!("/api/items/{shared_id}"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::get(),
+            &owner_token,
+            format!("/api/items/{private_id}"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &editor).await.ok();
+}
+
+// ==================== Custom Role Tests ====================
+
+#[actix_web::test]
+async fn test_custom_role_gates_item_endpoints() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::roles::create_role)
+                .service(home_registry::api::roles::update_role)
+                .service(home_registry::api::auth::create_inventory_share)
+                .service(home_registry::api::create_item)
+                .service(home_registry::api::update_item)
+                .service(home_registry::api::delete_item),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("role_owner");
+    common::create_test_user(&pool, &owner).await;
+    let member = common::test_username("role_member");
+    common::create_test_user(&pool, &member).await;
+    let stranger = common::test_username("role_stranger");
+    common::create_test_user(&pool, &stranger).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let member_token = common::get_test_token(&pool, &member).await;
+    let stranger_token = common::get_test_token(&pool, &stranger).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let member_user = db.get_user_by_username(&member).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Garage")
+        .await
+        .unwrap();
+
+    let send = |method: test::TestRequest, bearer: &str, uri: String, body: serde_json::Value| {
+        method
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(body)
+            .to_request()
+    };
+
+    // The owner defines a role that can only add items
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            "/api/roles".to_string(),
+            json!({"name": "Adder", "capabilities": ["add_items"]}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let role_id = body["data"]["id"].as_str().unwrap().to_string();
+    assert_eq!(body["data"]["capabilities"], json!(["view", "add_items"]));
+
+    // Nobody else can change it
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &member_token,
+            format!("/api/roles/{role_id}"),
+            json!({"capabilities": ["edit_items"]}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            format!("/api/inventories/{inventory_id}/shares"),
+            json!({"shared_with_username": member, "role_id": role_id}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let share_id = uuid::Uuid::parse_str(body["data"]["id"].as_str().unwrap()).unwrap();
+    db.respond_to_inventory_share(share_id, member_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The member can add items but not change or remove them
+    let create = |bearer: &str| {
+        send(
+            test::TestRequest::post(),
+            bearer,
+            "/api/items".to_string(),
+            json!({"inventory_id": inventory_id, "name": "Ladder"}),
+        )
+    };
+    let resp = test::call_service(&app, create(&stranger_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, create(&member_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let item_id = body["data"]["id"].as_i64().unwrap();
+
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &member_token,
+            format!("/api/items/{item_id}"),
+            json!({"name": "Step ladder"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::delete(),
+            &member_token,
+            format!("/api/items/{item_id}"),
+            json!({}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // Giving the role edit_items lets the member edit right away
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &owner_token,
+            format!("/api/roles/{role_id}"),
+            json!({"capabilities": ["add_items", "edit_items"]}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &member_token,
+            format!("/api/items/{item_id}"),
+            json!({"name": "Step ladder"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &member).await.ok();
+    common::delete_test_user(&pool, &stranger).await.ok();
+}
+
+// ==================== Item History Tests ====================
+
+#[actix_web::test]
+async fn test_item_history_records_and_reverts() {
+    use home_registry::models::{CreateOrganizerOptionRequest, CreateOrganizerTypeRequest};
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::create_item)
+                .service(home_registry::api::update_item)
+                .service(home_registry::api::set_item_organizer_values)
+                .service(home_registry::api::item_history::get_item_history)
+                .service(home_registry::api::item_history::revert_item),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("history_owner");
+    common::create_test_user(&pool, &owner).await;
+    let stranger = common::test_username("history_stranger");
+    common::create_test_user(&pool, &stranger).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let stranger_token = common::get_test_token(&pool, &stranger).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Living Room")
+        .await
+        .unwrap();
+    let room = db
+        .create_organizer_type(
+            inventory_id,
+            CreateOrganizerTypeRequest {
+                name: "Room".to_string(),
+                input_type: None,
+                is_required: None,
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let lounge = db
+        .create_organizer_option(
+            room,
+            CreateOrganizerOptionRequest {
+                name: "Lounge".to_string(),
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+
+    let send = |method: test::TestRequest, bearer: &str, uri: String, body: serde_json::Value| {
+        method
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .set_json(body)
+            .to_request()
+    };
+
+    // Revision 1: created
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            "/api/items".to_string(),
+            json!({"inventory_id": inventory_id, "name": "TV", "purchase_price": 899.0}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let item_id = body["data"]["id"].as_i64().unwrap();
+
+    // Revision 2: price change; revision 3: organizer value
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &owner_token,
+            format!("/api/items/{item_id}"),
+            json!({"purchase_price": 749.0, "notes": "On sale"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::put(),
+            &owner_token,
+            format!("/api/items/{item_id}/organizer-values"),
+            json!({"values": [{"organizer_type_id": room, "organizer_option_id": lounge}]}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let history = |bearer: &str| {
+        send(
+            test::TestRequest::get(),
+            bearer,
+            format!("/api/items/{item_id}/history"),
+            json!({}),
+        )
+    };
+    let resp = test::call_service(&app, history(&stranger_token)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, history(&owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let revisions = body["data"].as_array().unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[0]["revision"], 3);
+    assert_eq!(
+        revisions[0]["changes"][0]["field"],
+        format!("organizer:{room}")
+    );
+    assert_eq!(revisions[0]["changes"][0]["label"], "Room");
+    assert_eq!(revisions[0]["changes"][0]["new_value"]["value"], "Lounge");
+    assert_eq!(revisions[1]["action"], "updated");
+    assert_eq!(revisions[1]["changed_by_username"], owner.as_str());
+    assert_eq!(
+        revisions[1]["changes"],
+        json!([
+            {"field": "purchase_price", "old_value": 899.0, "new_value": 749.0},
+            {"field": "notes", "old_value": null, "new_value": "On sale"},
+        ])
+    );
+    assert_eq!(revisions[2]["action"], "created");
+
+    // Strangers can't revert, and unknown revisions are 404
+    let revert = |bearer: &str, revision: i32| {
+        send(
+            test::TestRequest::post(),
+            bearer,
+            format!("/api/items/{item_id}/history/{revision}/revert"),
+            json!({}),
+        )
+    };
+    let resp = test::call_service(&app, revert(&stranger_token, 1)).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = test::call_service(&app, revert(&owner_token, 9)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // Reverting to the created revision clears the later price, notes and room
+    let resp = test::call_service(&app, revert(&owner_token, 1)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["purchase_price"], 899.0);
+    assert!(body["data"]["notes"].is_null());
+    assert!(db
+        .get_item_organizer_values(i32::try_from(item_id).unwrap())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let resp = test::call_service(&app, history(&owner_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"][0]["revision"], 4);
+    assert_eq!(body["data"][0]["action"], "reverted");
+    assert_eq!(body["data"][0]["reverted_to_revision"], 1);
+    assert_eq!(body["data"][0]["changes"].as_array().unwrap().len(), 3);
+
+    // Reverting again to the same revision changes nothing
+    let resp = test::call_service(&app, revert(&owner_token, 1)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, history(&owner_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 4);
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &stranger).await.ok();
+}
+
+#[actix_web::test]
+async fn test_trash_restore_and_purge_via_api() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::delete_inventory)
+                .service(home_registry::api::delete_item)
+                .service(home_registry::api::trash::get_trash)
+                .service(home_registry::api::trash::restore_from_trash)
+                .service(home_registry::api::trash::purge_from_trash),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("trash_owner");
+    common::create_test_user(&pool, &owner).await;
+    let stranger = common::test_username("trash_stranger");
+    common::create_test_user(&pool, &stranger).await;
+    let owner_token = common::get_test_token(&pool, &owner).await;
+    let stranger_token = common::get_test_token(&pool, &stranger).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Garage")
+        .await
+        .unwrap();
+    let item_id = common::create_test_item(&pool, inventory_id, "Drill")
+        .await
+        .unwrap();
+
+    let send = |method: test::TestRequest, bearer: &str, uri: String| {
+        method
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {bearer}")))
+            .to_request()
+    };
+    let trash = |bearer: &str| send(test::TestRequest::get(), bearer, "/api/trash".to_string());
+
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::delete(),
+            &owner_token,
+            format!("/api/items/{item_id}"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, trash(&owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["entries"][0]["kind"], "item");
+    assert_eq!(body["data"]["entries"][0]["name"], "Drill");
+    assert_eq!(body["data"]["entries"][0]["inventory_name"], "Garage");
+
+    // Strangers don't see the entry, and unknown kinds are rejected
+    let resp = test::call_service(&app, trash(&stranger_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["entries"].as_array().unwrap().is_empty());
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &stranger_token,
+            format!("/api/trash/item/{item_id}/restore"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            format!("/api/trash/shelf/{item_id}/restore"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            format!("/api/trash/item/{item_id}/restore"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(db.get_item_by_id(item_id).await.unwrap().is_some());
+
+    // Trashing the inventory lists it alone; restoring it brings the item back
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::delete(),
+            &owner_token,
+            format!("/api/inventories/{inventory_id}"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, trash(&owner_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let entries = body["data"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["kind"], "inventory");
+    assert_eq!(entries[0]["item_count"], 1);
+    let resp = test::call_service(
+        &app,
+        send(
+            test::TestRequest::post(),
+            &owner_token,
+            format!("/api/trash/inventory/{inventory_id}/restore"),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(db.get_item_by_id(item_id).await.unwrap().is_some());
+
+    // Purging is permanent
+    let purge = || {
+        send(
+            test::TestRequest::delete(),
+            &owner_token,
+            format!("/api/trash/item/{item_id}"),
+        )
+    };
+    let resp = test::call_service(&app, purge()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    db.delete_item(item_id, owner_user.id).await.unwrap();
+    let resp = test::call_service(&app, purge()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, purge()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+    common::delete_test_user(&pool, &stranger).await.ok();
+}
+
+#[actix_web::test]
+async fn test_if_match_rejects_stale_updates() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(pool.clone())).service(
+            web::scope("/api")
+                .service(home_registry::api::get_item)
+                .service(home_registry::api::update_item)
+                .service(home_registry::api::auth::get_user_settings)
+                .service(home_registry::api::auth::update_user_settings),
+        ),
+    )
+    .await;
+
+    let owner = common::test_username("etag_owner");
+    common::create_test_user(&pool, &owner).await;
+    let token = common::get_test_token(&pool, &owner).await;
+    let db = home_registry::db::DatabaseService::new(pool.clone());
+    let owner_user = db.get_user_by_username(&owner).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner_user.id, "Kitchen")
+        .await
+        .unwrap();
+    let item_id = common::create_test_item(&pool, inventory_id, "Kettle")
+        .await
+        .unwrap();
+
+    let put = |uri: String, if_match: Option<&str>, body: serde_json::Value| {
+        let mut request = test::TestRequest::put()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {token}")));
+        if let Some(tag) = if_match {
+            request = request.insert_header(("If-Match", tag.to_string()));
+        }
+        request.set_json(body).to_request()
+    };
+    let etag = |resp: &actix_web::dev::ServiceResponse| {
+        resp.headers()
+            .get("ETag")
+            .expect("ETag header")
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+
+    // The ETag is the quoted updated_at of the body
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/api/items/{item_id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let original = etag(&resp);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        original,
+        format!("\"{}\"", body["data"]["updated_at"].as_str().unwrap())
+    );
+
+    // Alice saves first
+    let uri = format!("/api/items/{item_id}");
+    let resp = test::call_service(
+        &app,
+        put(
+            uri.clone(),
+            Some(&original),
+            json!({"name": "Alice's kettle"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let after_alice = etag(&resp);
+    assert_ne!(after_alice, original);
+
+    // Bob's edit of the version he loaded is refused with the current item
+    let resp = test::call_service(
+        &app,
+        put(
+            uri.clone(),
+            Some(&original),
+            json!({"name": "Bob's kettle"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(etag(&resp), after_alice);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["name"], "Alice's kettle");
+
+    // Malformed tags never match; * and no precondition still work
+    let resp = test::call_service(
+        &app,
+        put(uri.clone(), Some("not-quoted"), json!({"notes": "x"})),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+    let resp = test::call_service(&app, put(uri.clone(), Some("*"), json!({"quantity": 2}))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, put(uri, None, json!({"quantity": 3}))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // User settings work the same way
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/auth/settings")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request(),
+    )
+    .await;
+    let settings_etag = etag(&resp);
+    let resp = test::call_service(
+        &app,
+        put(
+            "/api/auth/settings".to_string(),
+            Some(&settings_etag),
+            json!({"theme": "dark"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(
+        &app,
+        put(
+            "/api/auth/settings".to_string(),
+            Some(&settings_etag),
+            json!({"theme": "light"}),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["theme"], "dark");
+
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner).await.ok();
+}
@@ -2,7 +2,9 @@
 
 mod common;
 
+use home_registry::api::backup_format::upgrade_backup;
 use home_registry::db::DatabaseService;
+use home_registry::models::{BackupData, ItemViewer, ShareStatus, TrashKind};
 use uuid::Uuid;
 
 // ==================== User Database Tests ====================
@@ -245,7 +247,7 @@ async fn test_update_inventory() {
         image_url: None,
     };
 
-    db.update_inventory(inventory_id, update_request)
+    db.update_inventory(inventory_id, update_request, None)
         .await
         .expect("Failed to update inventory");
 
@@ -284,7 +286,7 @@ async fn test_delete_inventory() {
         .expect("Failed to create inventory");
 
     let deleted = db
-        .delete_inventory(inventory_id)
+        .delete_inventory(inventory_id, user.id)
         .await
         .expect("Failed to delete inventory");
 
@@ -425,7 +427,7 @@ async fn test_update_item() {
         inventory_id: None,
     };
 
-    db.update_item(item_id, update_request)
+    db.update_item(item_id, update_request, None)
         .await
         .expect("Failed to update item");
 
@@ -446,6 +448,55 @@ async fn test_update_item() {
     common::delete_test_user(&pool, &username).await.ok();
 }
 
+#[tokio::test]
+async fn test_update_item_only_applies_to_expected_version() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_item_version");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Test Inventory")
+        .await
+        .unwrap();
+    let item_id = common::create_test_item(&pool, inventory_id, "Versioned Item")
+        .await
+        .unwrap();
+    let original = db.get_item_by_id(item_id).await.unwrap().unwrap();
+
+    let rename = |name: &str| home_registry::models::UpdateItemRequest {
+        name: Some(name.to_string()),
+        description: None,
+        category: None,
+        location: None,
+        purchase_date: None,
+        purchase_price: None,
+        warranty_expiry: None,
+        notes: None,
+        quantity: None,
+        inventory_id: None,
+    };
+
+    let first = db
+        .update_item(item_id, rename("First"), original.updated_at)
+        .await
+        .unwrap()
+        .expect("Update of the current version should apply");
+    assert_ne!(first.updated_at, original.updated_at);
+
+    // A second edit based on the original version is refused
+    let stale = db
+        .update_item(item_id, rename("Second"), original.updated_at)
+        .await
+        .unwrap();
+    assert!(stale.is_none());
+    let current = db.get_item_by_id(item_id).await.unwrap().unwrap();
+    assert_eq!(current.name, "First");
+
+    // Cleanup
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
 #[tokio::test]
 async fn test_delete_item() {
     let pool = common::create_test_pool();
@@ -469,7 +520,7 @@ async fn test_delete_item() {
         .expect("Failed to create item");
 
     let deleted = db
-        .delete_item(item_id)
+        .delete_item(item_id, user.id)
         .await
         .expect("Failed to delete item");
     assert!(deleted);
@@ -516,7 +567,7 @@ async fn test_get_items_by_inventory() {
         .expect("Failed to create item 2");
 
     let items = db
-        .get_items_by_inventory(inventory_id)
+        .get_items_by_inventory(inventory_id, ItemViewer::Everything)
         .await
         .expect("Failed to get items");
 
@@ -555,7 +606,7 @@ async fn test_search_items() {
         .expect("Failed to create item");
 
     let results = db
-        .search_items(&unique_name)
+        .search_items(&unique_name, ItemViewer::Everything)
         .await
         .expect("Failed to search items");
 
@@ -613,7 +664,7 @@ async fn test_update_nonexistent_inventory() {
         image_url: None,
     };
 
-    let result = db.update_inventory(99999, update).await;
+    let result = db.update_inventory(99999, update, None).await;
     assert!(result.is_ok());
     assert!(result.unwrap().is_none());
 }
@@ -636,7 +687,7 @@ async fn test_update_nonexistent_item() {
         inventory_id: None,
     };
 
-    let result = db.update_item(99999, update).await;
+    let result = db.update_item(99999, update, None).await;
     assert!(result.is_ok());
     assert!(result.unwrap().is_none());
 }
@@ -646,7 +697,7 @@ async fn test_delete_nonexistent_inventory() {
     let pool = common::create_test_pool();
     let db = DatabaseService::new(pool.clone());
 
-    let result = db.delete_inventory(99999).await;
+    let result = db.delete_inventory(99999, Uuid::new_v4()).await;
     assert!(result.is_ok());
     assert!(!result.unwrap());
 }
@@ -656,7 +707,7 @@ async fn test_delete_nonexistent_item() {
     let pool = common::create_test_pool();
     let db = DatabaseService::new(pool.clone());
 
-    let result = db.delete_item(99999).await;
+    let result = db.delete_item(99999, Uuid::new_v4()).await;
     assert!(result.is_ok());
     assert!(!result.unwrap());
 }
@@ -720,7 +771,9 @@ async fn test_user_settings_crud() {
         settings_json: None,
     };
 
-    db.update_user_settings(user.id, update_req).await.unwrap();
+    db.update_user_settings(user.id, update_req, None)
+        .await
+        .unwrap();
     let updated = db.get_user_settings(user.id).await.unwrap().unwrap();
     assert_eq!(updated.theme, "dark");
 
@@ -757,15 +810,32 @@ async fn test_share_inventory() {
         .unwrap();
 
     // Share with view permission
-    let result = db
+    let share = db
         .create_inventory_share(
             inventory_id,
             shared_user.id,
             owner.id,
             home_registry::models::PermissionLevel::View,
+            None,
+            None,
         )
-        .await;
-    assert!(result.is_ok());
+        .await
+        .unwrap();
+    assert_eq!(share.status, ShareStatus::Pending);
+
+    // Nothing is shared until the user accepts
+    let accessible = db.get_accessible_inventories(shared_user.id).await.unwrap();
+    assert!(!accessible.iter().any(|inv| inv.id == Some(inventory_id)));
+    let pending = db
+        .get_pending_inventory_shares(shared_user.id)
+        .await
+        .unwrap();
+    assert!(pending.iter().any(|p| p.id == share.id));
+
+    db.respond_to_inventory_share(share.id, shared_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
 
     // Get shared inventories
     let accessible = db.get_accessible_inventories(shared_user.id).await.unwrap();
@@ -812,9 +882,15 @@ async fn test_unshare_inventory() {
             shared_user.id,
             owner.id,
             home_registry::models::PermissionLevel::View,
+            None,
+            None,
         )
         .await
         .unwrap();
+    db.respond_to_inventory_share(share.id, shared_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
 
     // Unshare
     let result = db.delete_inventory_share(share.id).await;
@@ -832,179 +908,1948 @@ async fn test_unshare_inventory() {
     common::delete_test_user(&pool, &shared_username).await.ok();
 }
 
-// ==================== Data Validation Tests ====================
-
 #[tokio::test]
-async fn test_create_inventory_with_empty_name() {
+async fn test_group_share_follows_membership() {
+    use home_registry::models::{CreateGroupRequest, GroupRole, PermissionLevel, PermissionSource};
+
     let pool = common::create_test_pool();
     let db = DatabaseService::new(pool.clone());
 
-    let username = common::test_username("db_empty_name");
-    common::create_test_user(&pool, &username).await;
-    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
-
-    let request = home_registry::models::CreateInventoryRequest {
-        name: String::new(), // Empty name
-        description: None,
-        location: None,
-        image_url: None,
-    };
-
-    // Should succeed at DB level (validation should be in API layer)
-    let result = db.create_inventory(request, user.id).await;
-    // This tests that DB doesn't crash, validation is API responsibility
-    if let Ok(inventory) = result {
-        common::delete_test_inventory(&pool, inventory.id.unwrap())
-            .await
-            .ok();
-    }
+    let owner_username = common::test_username("db_group_owner");
+    let member_username = common::test_username("db_group_member");
 
-    // Cleanup
-    common::delete_test_user(&pool, &username).await.ok();
-}
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &member_username).await;
 
-#[tokio::test]
-async fn test_create_item_with_null_optional_fields() {
-    let pool = common::create_test_pool();
-    let db = DatabaseService::new(pool.clone());
+    let owner = db
+        .get_user_by_username(&owner_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let member = db
+        .get_user_by_username(&member_username)
+        .await
+        .unwrap()
+        .unwrap();
 
-    let username = common::test_username("db_null_fields");
-    common::create_test_user(&pool, &username).await;
-    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Household Inventory")
+        .await
+        .unwrap();
 
-    let inventory_id = common::create_test_inventory(&pool, user.id, "Test Inventory")
+    let group = db
+        .create_group(
+            owner.id,
+            &CreateGroupRequest {
+                name: "Household".to_string(),
+                description: None,
+            },
+        )
         .await
         .unwrap();
+    assert_eq!(group.my_role, Some(GroupRole::Manager));
+    db.add_group_member(group.id, member.id, GroupRole::Member)
+        .await
+        .unwrap();
+    db.create_inventory_group_share(
+        inventory_id,
+        group.id,
+        owner.id,
+        PermissionLevel::EditItems,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
 
-    let request = home_registry::models::CreateItemRequest {
-        inventory_id: Some(inventory_id),
-        name: "Minimal Item".to_string(),
-        description: None,
-        category: None,
-        location: None,
-        purchase_date: None,
-        purchase_price: None,
-        warranty_expiry: None,
-        notes: None,
-        quantity: None,
-    };
+    // Members get the group's permission level
+    assert!(db
+        .check_inventory_access(member.id, inventory_id)
+        .await
+        .unwrap());
+    let accessible = db.get_accessible_inventories(member.id).await.unwrap();
+    assert!(accessible.iter().any(|inv| inv.id == Some(inventory_id)));
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert!(!perms.can_add_items);
+    assert_eq!(perms.permission_source, PermissionSource::GroupShare);
 
-    let result = db.create_item(request).await;
-    assert!(result.is_ok());
+    // A weaker direct share doesn't take away what the group gives
+    let share = db
+        .create_inventory_share(
+            inventory_id,
+            member.id,
+            owner.id,
+            PermissionLevel::View,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    db.respond_to_inventory_share(share.id, member.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert_eq!(perms.permission_source, PermissionSource::GroupShare);
 
-    let item = result.unwrap();
-    assert_eq!(item.name, "Minimal Item");
-    assert!(item.description.is_none());
-    assert!(item.purchase_price.is_none());
+    // Leaving the group drops back to the direct share
+    db.remove_group_member(group.id, member.id).await.unwrap();
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_view);
+    assert!(!perms.can_edit_items);
+    assert_eq!(perms.permission_source, PermissionSource::InventoryShare);
 
     // Cleanup
-    common::delete_test_item(&pool, item.id.unwrap()).await.ok();
+    db.delete_group(group.id).await.ok();
     common::delete_test_inventory(&pool, inventory_id)
         .await
         .ok();
-    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &member_username).await.ok();
 }
 
 #[tokio::test]
-async fn test_inventory_list_ordering() {
+async fn test_scheduled_shares_only_apply_inside_their_window() {
+    use chrono::{Duration, Utc};
+    use home_registry::models::{AccessGrantLevel, PermissionLevel, PermissionSource};
+
     let pool = common::create_test_pool();
     let db = DatabaseService::new(pool.clone());
 
-    let username = common::test_username("db_order");
-    common::create_test_user(&pool, &username).await;
-    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let owner_username = common::test_username("db_sched_owner");
+    let past_username = common::test_username("db_sched_past");
+    let future_username = common::test_username("db_sched_future");
 
-    // Create inventories with specific names
-    let id1 = common::create_test_inventory(&pool, user.id, "Alpha Inventory")
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &past_username).await;
+    common::create_test_user(&pool, &future_username).await;
+
+    let owner = db
+        .get_user_by_username(&owner_username)
         .await
+        .unwrap()
         .unwrap();
-    let id2 = common::create_test_inventory(&pool, user.id, "Beta Inventory")
+    let past_user = db
+        .get_user_by_username(&past_username)
         .await
+        .unwrap()
         .unwrap();
-    let id3 = common::create_test_inventory(&pool, user.id, "Gamma Inventory")
+    let future_user = db
+        .get_user_by_username(&future_username)
         .await
+        .unwrap()
         .unwrap();
 
-    // Get all inventories (should be ordered by created_at DESC by default)
-    let inventories = db.get_accessible_inventories(user.id).await.unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Garage")
+        .await
+        .unwrap();
 
-    // Verify we got at least our 3 inventories
-    assert!(inventories.len() >= 3);
+    let now = Utc::now();
+    // A share and an All Access grant that both ended yesterday
+    let past_share = db
+        .create_inventory_share(
+            inventory_id,
+            past_user.id,
+            owner.id,
+            PermissionLevel::EditItems,
+            Some(now - Duration::days(14)),
+            Some(now - Duration::days(1)),
+        )
+        .await
+        .unwrap();
+    let past_grant = db
+        .create_user_access_grant(
+            owner.id,
+            past_user.id,
+            AccessGrantLevel::Full,
+            None,
+            Some(now - Duration::days(1)),
+        )
+        .await
+        .unwrap();
+    // A two-week share that starts tomorrow
+    let future_share = db
+        .create_inventory_share(
+            inventory_id,
+            future_user.id,
+            owner.id,
+            PermissionLevel::EditItems,
+            Some(now + Duration::days(1)),
+            Some(now + Duration::days(15)),
+        )
+        .await
+        .unwrap();
+    for (share_id, user_id) in [
+        (past_share.id, past_user.id),
+        (future_share.id, future_user.id),
+    ] {
+        db.respond_to_inventory_share(share_id, user_id, ShareStatus::Accepted)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    db.respond_to_user_access_grant(past_grant.id, past_user.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
 
-    // Cleanup
-    common::delete_test_inventory(&pool, id1).await.ok();
-    common::delete_test_inventory(&pool, id2).await.ok();
-    common::delete_test_inventory(&pool, id3).await.ok();
-    common::delete_test_user(&pool, &username).await.ok();
-}
+    for user_id in [past_user.id, future_user.id] {
+        let perms = db
+            .get_effective_permissions(user_id, inventory_id)
+            .await
+            .unwrap();
+        assert_eq!(perms.permission_source, PermissionSource::None);
+        assert!(!db
+            .check_inventory_access(user_id, inventory_id)
+            .await
+            .unwrap());
+        let accessible = db.get_accessible_inventories(user_id).await.unwrap();
+        assert!(!accessible.iter().any(|inv| inv.id == Some(inventory_id)));
+    }
 
-#[tokio::test]
-async fn test_get_all_items() {
-    let pool = common::create_test_pool();
-    let db = DatabaseService::new(pool.clone());
+    // Owners see the schedule and the time left
+    let shares = db.get_inventory_shares(inventory_id).await.unwrap();
+    assert_eq!(shares.len(), 2);
+    let expired = shares
+        .iter()
+        .find(|s| s.shared_with_user.id == past_user.id)
+        .unwrap();
+    assert!(!expired.is_active);
+    assert_eq!(expired.expires_in_seconds, Some(0));
+    let upcoming = shares
+        .iter()
+        .find(|s| s.shared_with_user.id == future_user.id)
+        .unwrap();
+    assert!(!upcoming.is_active);
+    assert!(upcoming.expires_in_seconds.unwrap() > 14 * 24 * 60 * 60);
 
-    // This test verifies get_all_items works
-    let result = db.get_all_items().await;
-    assert!(result.is_ok());
+    // Cleanup removes expired rows and leaves the upcoming share alone
+    assert!(db.delete_expired_shares().await.unwrap() >= 2);
+    let shares = db.get_inventory_shares(inventory_id).await.unwrap();
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[0].shared_with_user.id, future_user.id);
+    assert!(db
+        .get_user_access_grants_by_grantor(owner.id)
+        .await
+        .unwrap()
+        .is_empty());
 
-    // Should return a vec (may be empty or contain items)
-    let items = result.unwrap();
-    assert!(items.is_empty() || !items.is_empty()); // Just verify it's a valid vec
+    // Cleanup
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &past_username).await.ok();
+    common::delete_test_user(&pool, &future_username).await.ok();
 }
 
 #[tokio::test]
-async fn test_user_password_update_and_verification() {
+async fn test_scheduled_group_shares_only_apply_inside_their_window() {
+    use chrono::{Duration, Utc};
+    use home_registry::models::{CreateGroupRequest, GroupRole, PermissionLevel, PermissionSource};
+
     let pool = common::create_test_pool();
     let db = DatabaseService::new(pool.clone());
 
-    let username = common::test_username("db_pw_verify");
-    let old_password = "OldPassword123!";
-    let new_password = "NewPassword456!";
+    let owner_username = common::test_username("db_gsched_owner");
+    let member_username = common::test_username("db_gsched_member");
 
-    let old_hash = home_registry::auth::hash_password(old_password.to_string())
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &member_username).await;
+
+    let owner = db
+        .get_user_by_username(&owner_username)
         .await
+        .unwrap()
         .unwrap();
-
-    db.create_user(&username, "Test User", &old_hash, false, false)
+    let member = db
+        .get_user_by_username(&member_username)
         .await
+        .unwrap()
         .unwrap();
 
-    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let group = db
+        .create_group(
+            owner.id,
+            &CreateGroupRequest {
+                name: "Neighbours".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.add_group_member(group.id, member.id, GroupRole::Member)
+        .await
+        .unwrap();
 
-    // Verify old password works
-    let old_verification =
-        home_registry::auth::verify_password(old_password.to_string(), user.password_hash.clone())
+    let now = Utc::now();
+    // One group share that ended yesterday, one that starts tomorrow and one running now
+    let mut inventory_ids = Vec::new();
+    for (name, starts_at, expires_at) in [
+        ("Garage", now - Duration::days(14), now - Duration::days(1)),
+        ("Shed", now + Duration::days(1), now + Duration::days(15)),
+        ("Attic", now - Duration::days(1), now + Duration::days(1)),
+    ] {
+        let inventory_id = common::create_test_inventory(&pool, owner.id, name)
             .await
             .unwrap();
-    assert!(old_verification);
-
-    // Update password
-    let new_hash = home_registry::auth::hash_password(new_password.to_string())
+        db.create_inventory_group_share(
+            inventory_id,
+            group.id,
+            owner.id,
+            PermissionLevel::EditItems,
+            Some(starts_at),
+            Some(expires_at),
+        )
         .await
         .unwrap();
-    db.update_user_password(user.id, &new_hash).await.unwrap();
-
-    // Get updated user
-    let updated_user = db.get_user_by_username(&username).await.unwrap().unwrap();
+        inventory_ids.push(inventory_id);
+    }
+    let (expired_id, upcoming_id, active_id) =
+        (inventory_ids[0], inventory_ids[1], inventory_ids[2]);
 
-    // Verify new password works
-    let new_verification = home_registry::auth::verify_password(
-        new_password.to_string(),
-        updated_user.password_hash.clone(),
+    let accessible = db.get_accessible_inventories(member.id).await.unwrap();
+    for inventory_id in [expired_id, upcoming_id] {
+        let perms = db
+            .get_effective_permissions(member.id, inventory_id)
+            .await
+            .unwrap();
+        assert_eq!(perms.permission_source, PermissionSource::None);
+        assert!(!db
+            .check_inventory_access(member.id, inventory_id)
+            .await
+            .unwrap());
+        assert!(!accessible.iter().any(|inv| inv.id == Some(inventory_id)));
+    }
+    let perms = db
+        .get_effective_permissions(member.id, active_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert_eq!(perms.permission_source, PermissionSource::GroupShare);
+    assert!(accessible.iter().any(|inv| inv.id == Some(active_id)));
+
+    // Owners see the schedule of each group share
+    let expired = db.get_inventory_group_shares(expired_id).await.unwrap();
+    assert!(!expired[0].is_active);
+    assert_eq!(expired[0].expires_in_seconds, Some(0));
+    let upcoming = db.get_inventory_group_shares(upcoming_id).await.unwrap();
+    assert!(!upcoming[0].is_active);
+    let active = db.get_inventory_group_shares(active_id).await.unwrap();
+    assert!(active[0].is_active);
+
+    // Cleanup removes the expired group share only
+    db.delete_expired_shares().await.unwrap();
+    assert!(db
+        .get_inventory_group_shares(expired_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        db.get_inventory_group_shares(upcoming_id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Cleanup
+    db.delete_group(group.id).await.ok();
+    for inventory_id in inventory_ids {
+        common::delete_test_inventory(&pool, inventory_id)
+            .await
+            .ok();
+    }
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &member_username).await.ok();
+}
+
+#[tokio::test]
+async fn test_access_grant_level_combines_with_shares() {
+    use home_registry::models::{AccessGrantLevel, PermissionLevel, PermissionSource};
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let owner_username = common::test_username("db_grant_owner");
+    let grantee_username = common::test_username("db_grant_grantee");
+
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &grantee_username).await;
+
+    let owner = db
+        .get_user_by_username(&owner_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let grantee = db
+        .get_user_by_username(&grantee_username)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let shared_inventory = common::create_test_inventory(&pool, owner.id, "Workshop")
+        .await
+        .unwrap();
+    let other_inventory = common::create_test_inventory(&pool, owner.id, "Attic")
+        .await
+        .unwrap();
+
+    let grant = db
+        .create_user_access_grant(owner.id, grantee.id, AccessGrantLevel::View, None, None)
+        .await
+        .unwrap();
+    assert_eq!(grant.permission_level, AccessGrantLevel::View);
+    db.respond_to_user_access_grant(grant.id, grantee.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // A read-only grant gives view access to every inventory, and nothing more
+    let perms = db
+        .get_effective_permissions(grantee.id, other_inventory)
+        .await
+        .unwrap();
+    assert!(perms.can_view);
+    assert!(!perms.can_edit_items);
+    assert!(!perms.can_delete_inventory);
+    assert!(!perms.can_manage_sharing);
+    assert!(perms.has_all_access);
+    assert_eq!(perms.permission_source, PermissionSource::AllAccess);
+
+    // A stronger share on one inventory wins there
+    let share = db
+        .create_inventory_share(
+            shared_inventory,
+            grantee.id,
+            owner.id,
+            PermissionLevel::EditInventory,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    db.respond_to_inventory_share(share.id, grantee.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+    let perms = db
+        .get_effective_permissions(grantee.id, shared_inventory)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_inventory);
+    assert!(!perms.can_delete_inventory);
+    assert!(!perms.has_all_access);
+    assert_eq!(perms.permission_source, PermissionSource::InventoryShare);
+    let perms = db
+        .get_effective_permissions(grantee.id, other_inventory)
+        .await
+        .unwrap();
+    assert!(!perms.can_edit_items);
+    assert_eq!(perms.permission_source, PermissionSource::AllAccess);
+
+    // A stronger grant wins over a weaker share
+    db.update_inventory_share(share.id, PermissionLevel::View)
+        .await
+        .unwrap();
+    db.update_user_access_grant(grant.id, AccessGrantLevel::EditItems)
+        .await
+        .unwrap()
+        .unwrap();
+    let perms = db
+        .get_effective_permissions(grantee.id, shared_inventory)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert!(!perms.can_add_items);
+    assert_eq!(perms.permission_source, PermissionSource::AllAccess);
+
+    // On a tie the share is reported as the source
+    db.update_inventory_share(share.id, PermissionLevel::EditItems)
+        .await
+        .unwrap();
+    let perms = db
+        .get_effective_permissions(grantee.id, shared_inventory)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert_eq!(perms.permission_source, PermissionSource::InventoryShare);
+
+    // A full grant still gives owner-level access, including deletion
+    db.update_user_access_grant(grant.id, AccessGrantLevel::Full)
+        .await
+        .unwrap()
+        .unwrap();
+    let perms = db
+        .get_effective_permissions(grantee.id, shared_inventory)
+        .await
+        .unwrap();
+    assert!(perms.can_delete_inventory);
+    assert!(perms.can_manage_sharing);
+    assert_eq!(perms.permission_source, PermissionSource::AllAccess);
+
+    // Cleanup
+    common::delete_test_inventory(&pool, shared_inventory)
+        .await
+        .ok();
+    common::delete_test_inventory(&pool, other_inventory)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &grantee_username)
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn test_item_visibility_follows_share_level() {
+    use home_registry::models::{InventoryReportRequest, ItemVisibility, PermissionLevel};
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let owner_username = common::test_username("db_vis_owner");
+    let viewer_username = common::test_username("db_vis_viewer");
+    let editor_username = common::test_username("db_vis_editor");
+    let outsider_username = common::test_username("db_vis_outsider");
+
+    for username in [
+        &owner_username,
+        &viewer_username,
+        &editor_username,
+        &outsider_username,
+    ] {
+        common::create_test_user(&pool, username).await;
+    }
+    let owner = db
+        .get_user_by_username(&owner_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let viewer = db
+        .get_user_by_username(&viewer_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let editor = db
+        .get_user_by_username(&editor_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let outsider = db
+        .get_user_by_username(&outsider_username)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Office")
+        .await
+        .unwrap();
+    let marker = Uuid::new_v4().simple().to_string();
+    let public_item = common::create_test_item(&pool, inventory_id, &format!("Stapler {marker}"))
+        .await
+        .unwrap();
+    let editors_item = common::create_test_item(&pool, inventory_id, &format!("Laptop {marker}"))
+        .await
+        .unwrap();
+    let private_item = common::create_test_item(&pool, inventory_id, &format!("Diary {marker}"))
+        .await
+        .unwrap();
+    db.update_item_visibility(editors_item, ItemVisibility::EditItems)
+        .await
+        .unwrap()
+        .unwrap();
+    let updated = db
+        .update_item_visibility(private_item, ItemVisibility::Owner)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.visibility, ItemVisibility::Owner);
+
+    for (user_id, level) in [
+        (viewer.id, PermissionLevel::View),
+        (editor.id, PermissionLevel::EditInventory),
+    ] {
+        let share = db
+            .create_inventory_share(inventory_id, user_id, owner.id, level, None, None)
+            .await
+            .unwrap();
+        db.respond_to_inventory_share(share.id, user_id, ShareStatus::Accepted)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    let visible_ids = |items: Vec<home_registry::models::Item>| {
+        let mut ids: Vec<i32> = items.into_iter().filter_map(|item| item.id).collect();
+        ids.sort_unstable();
+        ids
+    };
+    let mut all = vec![public_item, editors_item, private_item];
+    all.sort_unstable();
+
+    for (viewer, expected) in [
+        (ItemViewer::User(owner.id), all.clone()),
+        (ItemViewer::User(viewer.id), vec![public_item]),
+        (ItemViewer::User(editor.id), {
+            let mut ids = vec![public_item, editors_item];
+            ids.sort_unstable();
+            ids
+        }),
+        (ItemViewer::User(outsider.id), vec![]),
+        (ItemViewer::Public, vec![public_item]),
+        (ItemViewer::Everything, all.clone()),
+    ] {
+        let items = db
+            .get_items_by_inventory(inventory_id, viewer)
+            .await
+            .unwrap();
+        assert_eq!(visible_ids(items), expected, "{viewer:?}");
+        let found = db.search_items(&marker, viewer).await.unwrap();
+        assert_eq!(visible_ids(found), expected, "search as {viewer:?}");
+    }
+
+    // Reports and statistics leave out what the user can't see
+    let request = InventoryReportRequest {
+        inventory_id: Some(inventory_id),
+        category: None,
+        location: None,
+        from_date: None,
+        to_date: None,
+        min_price: None,
+        max_price: None,
+        sort_by: None,
+        sort_order: None,
+        format: None,
+    };
+    let report = db
+        .get_inventory_report_data(request.clone(), viewer.id)
+        .await
+        .unwrap();
+    assert_eq!(visible_ids(report), vec![public_item]);
+    let report = db
+        .get_inventory_report_data(request, owner.id)
+        .await
+        .unwrap();
+    assert_eq!(report.len(), 3);
+
+    let stats = db
+        .get_inventory_statistics(Some(inventory_id), viewer.id)
+        .await
+        .unwrap();
+    assert_eq!(stats.total_items, 1);
+    let stats = db
+        .get_inventory_statistics(Some(inventory_id), editor.id)
+        .await
+        .unwrap();
+    assert_eq!(stats.total_items, 2);
+    let breakdown = db
+        .get_category_breakdown(Some(inventory_id), viewer.id)
+        .await
+        .unwrap();
+    assert_eq!(breakdown.iter().map(|c| c.item_count).sum::<i64>(), 1);
+
+    // Cleanup
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    for username in [
+        &owner_username,
+        &viewer_username,
+        &editor_username,
+        &outsider_username,
+    ] {
+        common::delete_test_user(&pool, username).await.ok();
+    }
+}
+
+#[tokio::test]
+async fn test_custom_role_capabilities() {
+    use home_registry::models::{
+        Capability, CreateInventoryRoleRequest, InventoryReportRequest, PermissionLevel,
+        PermissionSource, ShareRole, UpdateInventoryRoleRequest,
+    };
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let owner_username = common::test_username("db_role_owner");
+    let member_username = common::test_username("db_role_member");
+
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &member_username).await;
+
+    let owner = db
+        .get_user_by_username(&owner_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let member = db
+        .get_user_by_username(&member_username)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Pantry")
+        .await
+        .unwrap();
+    common::create_test_item(&pool, inventory_id, "Flour")
+        .await
+        .unwrap();
+
+    // A role that can add items and share, but not edit or export; view is always added
+    let role = db
+        .create_inventory_role(
+            owner.id,
+            CreateInventoryRoleRequest {
+                name: "Restocker".to_string(),
+                description: None,
+                capabilities: vec![Capability::ManageShares, Capability::AddItems],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        role.capabilities,
+        vec![
+            Capability::View,
+            Capability::AddItems,
+            Capability::ManageShares
+        ]
+    );
+
+    let share = db
+        .create_inventory_share(
+            inventory_id,
+            member.id,
+            owner.id,
+            ShareRole::from(&role),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(share.role_id, Some(role.id));
+    assert_eq!(share.permission_level, PermissionLevel::View);
+    db.respond_to_inventory_share(share.id, member.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.has(Capability::AddItems));
+    assert!(perms.can_manage_sharing);
+    assert!(!perms.can_edit_items);
+    assert!(!perms.has(Capability::Export));
+    assert!(!perms.can_delete_inventory);
+    assert_eq!(perms.permission_source, PermissionSource::InventoryShare);
+
+    // Without the export capability the inventory is left out of reports
+    let request = InventoryReportRequest {
+        inventory_id: None,
+        category: None,
+        location: None,
+        from_date: None,
+        to_date: None,
+        min_price: None,
+        max_price: None,
+        sort_by: None,
+        sort_order: None,
+        format: None,
+    };
+    let report = db
+        .get_inventory_report_data(request.clone(), member.id)
+        .await
+        .unwrap();
+    assert!(report.iter().all(|item| item.inventory_id != inventory_id));
+
+    // Capabilities from every share add up
+    let group = db
+        .create_group(
+            owner.id,
+            &home_registry::models::CreateGroupRequest {
+                name: common::test_username("db_role_group"),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.add_group_member(
+        group.id,
+        member.id,
+        home_registry::models::GroupRole::Member,
+    )
+    .await
+    .unwrap();
+    db.create_inventory_group_share(
+        inventory_id,
+        group.id,
+        owner.id,
+        PermissionLevel::View,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.has(Capability::AddItems));
+    assert!(perms.has(Capability::Export));
+    let report = db
+        .get_inventory_report_data(request, member.id)
+        .await
+        .unwrap();
+    assert!(report.iter().any(|item| item.inventory_id == inventory_id));
+
+    // Changing the role keeps the share's stored level in step
+    db.update_inventory_role(
+        role.id,
+        UpdateInventoryRoleRequest {
+            name: None,
+            description: None,
+            capabilities: Some(vec![Capability::EditItems]),
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let stored = db.get_inventory_share(share.id).await.unwrap().unwrap();
+    assert_eq!(stored.permission_level, PermissionLevel::EditItems);
+    let perms = db
+        .get_effective_permissions(member.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_items);
+    assert!(!perms.can_manage_sharing);
+
+    // Deleting the role falls back to the closest built-in role
+    assert!(db.delete_inventory_role(role.id).await.unwrap());
+    let stored = db.get_inventory_share(share.id).await.unwrap().unwrap();
+    assert_eq!(stored.role_id, None);
+    assert_eq!(stored.permission_level, PermissionLevel::EditItems);
+
+    // Cleanup
+    db.delete_group(group.id).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &member_username).await.ok();
+}
+
+// ==================== Data Validation Tests ====================
+
+#[tokio::test]
+async fn test_create_inventory_with_empty_name() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_empty_name");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    let request = home_registry::models::CreateInventoryRequest {
+        name: String::new(), // Empty name
+        description: None,
+        location: None,
+        image_url: None,
+    };
+
+    // Should succeed at DB level (validation should be in API layer)
+    let result = db.create_inventory(request, user.id).await;
+    // This tests that DB doesn't crash, validation is API responsibility
+    if let Ok(inventory) = result {
+        common::delete_test_inventory(&pool, inventory.id.unwrap())
+            .await
+            .ok();
+    }
+
+    // Cleanup
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[tokio::test]
+async fn test_create_item_with_null_optional_fields() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_null_fields");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Test Inventory")
+        .await
+        .unwrap();
+
+    let request = home_registry::models::CreateItemRequest {
+        inventory_id: Some(inventory_id),
+        name: "Minimal Item".to_string(),
+        description: None,
+        category: None,
+        location: None,
+        purchase_date: None,
+        purchase_price: None,
+        warranty_expiry: None,
+        notes: None,
+        quantity: None,
+    };
+
+    let result = db.create_item(request).await;
+    assert!(result.is_ok());
+
+    let item = result.unwrap();
+    assert_eq!(item.name, "Minimal Item");
+    assert!(item.description.is_none());
+    assert!(item.purchase_price.is_none());
+
+    // Cleanup
+    common::delete_test_item(&pool, item.id.unwrap()).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[tokio::test]
+async fn test_inventory_list_ordering() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_order");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    // Create inventories with specific names
+    let id1 = common::create_test_inventory(&pool, user.id, "Alpha Inventory")
+        .await
+        .unwrap();
+    let id2 = common::create_test_inventory(&pool, user.id, "Beta Inventory")
+        .await
+        .unwrap();
+    let id3 = common::create_test_inventory(&pool, user.id, "Gamma Inventory")
+        .await
+        .unwrap();
+
+    // Get all inventories (should be ordered by created_at DESC by default)
+    let inventories = db.get_accessible_inventories(user.id).await.unwrap();
+
+    // Verify we got at least our 3 inventories
+    assert!(inventories.len() >= 3);
+
+    // Cleanup
+    common::delete_test_inventory(&pool, id1).await.ok();
+    common::delete_test_inventory(&pool, id2).await.ok();
+    common::delete_test_inventory(&pool, id3).await.ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[tokio::test]
+async fn test_get_all_items() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    // This test verifies get_all_items works
+    let result = db.get_all_items(ItemViewer::Everything).await;
+    assert!(result.is_ok());
+
+    // Should return a vec (may be empty or contain items)
+    let items = result.unwrap();
+    assert!(items.is_empty() || !items.is_empty()); // Just verify it's a valid vec
+}
+
+#[tokio::test]
+async fn test_user_password_update_and_verification() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_pw_verify");
+    let old_password = "OldPassword123!";
+    let new_password = "NewPassword456!";
+
+    let old_hash = home_registry::auth::hash_password(old_password.to_string())
+        .await
+        .unwrap();
+
+    db.create_user(&username, "Test User", &old_hash, false, false)
+        .await
+        .unwrap();
+
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    // Verify old password works
+    let old_verification =
+        home_registry::auth::verify_password(old_password.to_string(), user.password_hash.clone())
+            .await
+            .unwrap();
+    assert!(old_verification);
+
+    // Update password
+    let new_hash = home_registry::auth::hash_password(new_password.to_string())
+        .await
+        .unwrap();
+    db.update_user_password(user.id, &new_hash).await.unwrap();
+
+    // Get updated user
+    let updated_user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    // Verify new password works
+    let new_verification = home_registry::auth::verify_password(
+        new_password.to_string(),
+        updated_user.password_hash.clone(),
+    )
+    .await
+    .unwrap();
+    assert!(new_verification);
+
+    // Verify old password no longer works
+    let old_verification_after = home_registry::auth::verify_password(
+        old_password.to_string(),
+        updated_user.password_hash.clone(),
+    )
+    .await
+    .unwrap();
+    assert!(!old_verification_after);
+
+    // Cleanup
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+// ==================== Backup Tests ====================
+
+#[tokio::test]
+async fn test_backup_from_before_share_status_restores() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    // Written by the last build before shares needed accepting (format 3.0)
+    let mut raw: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/backups/home_registry_v3.json"))
+            .expect("Fixture is not valid JSON");
+    assert_eq!(upgrade_backup(&mut raw), Ok(3));
+    let backup: BackupData = serde_json::from_value(raw).expect("Failed to parse backup");
+
+    let counts = db
+        .dry_run_import(&backup.data)
+        .await
+        .expect("Restoring the old backup failed");
+    let rows = |table: &str| {
+        counts
+            .iter()
+            .find(|count| count.table == table)
+            .map_or(0, |count| count.rows)
+    };
+    assert_eq!(rows("users"), 2);
+    assert_eq!(rows("items"), 2);
+    assert_eq!(rows("organizer_types"), 1);
+    assert_eq!(rows("inventory_shares"), 1);
+    assert_eq!(rows("user_access_grants"), 1);
+
+    // Columns added since then restore with the values existing rows got
+    let items = backup.data.items.as_array().unwrap();
+    assert!(items.iter().all(|item| item["visibility"] == "everyone"));
+    assert_eq!(backup.data.inventory_shares[0]["status"], "accepted");
+    assert_eq!(backup.data.user_access_grants[0]["status"], "accepted");
+    assert_eq!(
+        backup.data.user_access_grants[0]["permission_level"],
+        "full"
+    );
+}
+
+#[tokio::test]
+async fn test_backup_round_trips_every_table() {
+    use home_registry::models::{
+        AccessGrantLevel, ApiTokenScope, BackupDatabaseContent, Capability, CreateApiTokenRequest,
+        CreateGroupRequest, CreateInventoryRoleRequest, CreateInvitationRequest,
+        CreateOrganizerOptionRequest, CreateOrganizerTypeRequest, CreateShareLinkRequest,
+        GroupRole, IdentitySource, ItemFieldChange, ItemHistoryAction, PermissionLevel,
+        SetItemOrganizerValueRequest,
+    };
+    use serde_json::json;
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_bak_owner");
+    let other_username = common::test_username("db_bak_other");
+    common::create_test_user(&pool, &username).await;
+    common::create_test_user(&pool, &other_username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let other = db
+        .get_user_by_username(&other_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let secret = |n: u8| format!("{:0>63}{n}", user.id.simple());
+
+    // One row in every backed-up table, found again by (table, column, value)
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Backup Inventory")
+        .await
+        .unwrap();
+    let item_id = common::create_test_item(&pool, inventory_id, "Backup Item")
+        .await
+        .unwrap();
+    let item = db.get_item_by_id(item_id).await.unwrap().unwrap();
+
+    // Categories, tags and custom fields have no database service methods
+    let client = pool.get().await.unwrap();
+    let category_id: i32 = client
+        .query_one(
+            "INSERT INTO categories (name) VALUES ($1) RETURNING id",
+            &[&format!("{username} category")],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    let custom_field_id: i32 = client
+        .query_one(
+            "INSERT INTO custom_fields (category_id, name) VALUES ($1, 'Serial') RETURNING id",
+            &[&category_id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    client
+        .execute(
+            "INSERT INTO item_custom_values (item_id, custom_field_id, value) VALUES ($1, $2, 'X1')",
+            &[&item_id, &custom_field_id],
+        )
+        .await
+        .unwrap();
+    let tag_id: i32 = client
+        .query_one(
+            "INSERT INTO tags (name) VALUES ($1) RETURNING id",
+            &[&format!("{username} tag")],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    client
+        .execute(
+            "INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2)",
+            &[&item_id, &tag_id],
+        )
+        .await
+        .unwrap();
+    drop(client);
+
+    let organizer_type = db
+        .create_organizer_type(
+            inventory_id,
+            CreateOrganizerTypeRequest {
+                name: "Room".to_string(),
+                input_type: None,
+                is_required: None,
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap();
+    let option = db
+        .create_organizer_option(
+            organizer_type.id.unwrap(),
+            CreateOrganizerOptionRequest {
+                name: "Garage".to_string(),
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.set_item_organizer_value(
+        item_id,
+        SetItemOrganizerValueRequest {
+            organizer_type_id: organizer_type.id.unwrap(),
+            organizer_option_id: option.id,
+            text_value: None,
+        },
+    )
+    .await
+    .unwrap();
+    db.record_item_revision(
+        item_id,
+        ItemHistoryAction::Created,
+        None,
+        user.id,
+        &username,
+        &ItemFieldChange::for_new_item(&item),
+    )
+    .await
+    .unwrap();
+
+    db.create_user_settings(user.id).await.unwrap();
+    let role = db
+        .create_inventory_role(
+            user.id,
+            CreateInventoryRoleRequest {
+                name: "Helper".to_string(),
+                description: None,
+                capabilities: vec![Capability::View, Capability::AddItems],
+            },
+        )
+        .await
+        .unwrap();
+    db.create_inventory_share(inventory_id, other.id, user.id, &role, None, None)
+        .await
+        .unwrap();
+    db.create_user_access_grant(user.id, other.id, AccessGrantLevel::View, None, None)
+        .await
+        .unwrap();
+    db.store_recovery_codes(user.id, vec![secret(1)])
+        .await
+        .unwrap();
+    db.create_password_reset_token(
+        user.id,
+        &secret(2),
+        user.id,
+        chrono::Utc::now() + chrono::Duration::hours(1),
+    )
+    .await
+    .unwrap();
+    db.link_user_identity(user.id, IdentitySource::Ldap, Some(&username))
+        .await
+        .unwrap();
+    let token = db
+        .create_api_token(
+            user.id,
+            &secret(3),
+            "hrp_backup",
+            &CreateApiTokenRequest {
+                name: "Backup".to_string(),
+                scopes: vec![ApiTokenScope::ItemsRead],
+                inventory_ids: Some(vec![inventory_id]),
+                expires_in_days: None,
+            },
+        )
+        .await
+        .unwrap();
+    let credential = db
+        .create_webauthn_credential(
+            user.id,
+            "Backup key",
+            user.id.as_bytes(),
+            &[1, 2, 3, 4],
+            7,
+            &["usb".to_string()],
+        )
+        .await
+        .unwrap();
+    let invitation = db
+        .create_invitation(
+            &secret(4),
+            user.id,
+            &CreateInvitationRequest {
+                is_admin: false,
+                inventory_id: Some(inventory_id),
+                permission_level: Some(PermissionLevel::View),
+                grant_all_access: false,
+                expires_in_hours: None,
+            },
+            chrono::Utc::now() + chrono::Duration::hours(1),
+        )
+        .await
+        .unwrap();
+    let link = db
+        .create_share_link(
+            inventory_id,
+            user.id,
+            &secret(5),
+            "hrs_backup",
+            None,
+            &CreateShareLinkRequest {
+                name: "Insurance".to_string(),
+                password: None,
+                expires_in_days: None,
+            },
+        )
+        .await
+        .unwrap();
+    let group = db
+        .create_group(
+            user.id,
+            &CreateGroupRequest {
+                name: format!("{username} household"),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.add_group_member(group.id, other.id, GroupRole::Member)
+        .await
+        .unwrap();
+    db.create_inventory_group_share(
+        inventory_id,
+        group.id,
+        user.id,
+        PermissionLevel::View,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let seeded = [
+        ("users", "id", json!(user.id)),
+        ("inventories", "id", json!(inventory_id)),
+        ("items", "id", json!(item_id)),
+        ("categories", "id", json!(category_id)),
+        ("tags", "id", json!(tag_id)),
+        ("item_tags", "item_id", json!(item_id)),
+        ("custom_fields", "id", json!(custom_field_id)),
+        ("item_custom_values", "item_id", json!(item_id)),
+        ("organizer_types", "id", json!(organizer_type.id)),
+        ("organizer_options", "id", json!(option.id)),
+        ("item_organizer_values", "item_id", json!(item_id)),
+        ("item_history", "item_id", json!(item_id)),
+        ("user_settings", "user_id", json!(user.id)),
+        ("inventory_roles", "id", json!(role.id)),
+        ("inventory_shares", "inventory_id", json!(inventory_id)),
+        ("user_access_grants", "grantor_user_id", json!(user.id)),
+        ("recovery_codes", "user_id", json!(user.id)),
+        ("password_reset_tokens", "user_id", json!(user.id)),
+        ("user_identity_links", "user_id", json!(user.id)),
+        ("api_tokens", "id", json!(token.id)),
+        ("webauthn_credentials", "id", json!(credential.id)),
+        ("user_invitations", "id", json!(invitation.id)),
+        ("inventory_share_links", "id", json!(link.id)),
+        ("user_groups", "id", json!(group.id)),
+        ("user_group_members", "user_id", json!(other.id)),
+        ("inventory_group_shares", "group_id", json!(group.id)),
+    ];
+    assert_eq!(seeded.len(), BackupDatabaseContent::TABLES.len());
+
+    // Every table is exported with its row, and a restore loads back all of them
+    let exported = db.export_all_data().await.unwrap();
+    let counts = db.dry_run_import(&exported).await.unwrap();
+    for (table, rows) in exported.tables() {
+        let (_, column, value) = seeded
+            .iter()
+            .find(|(seeded_table, _, _)| *seeded_table == table)
+            .unwrap_or_else(|| panic!("No {table} row set up"));
+        let rows = rows.as_array().unwrap();
+        assert!(
+            rows.iter().any(|row| &row[column] == value),
+            "{table} row missing from backup"
+        );
+        let restored = counts.iter().find(|count| count.table == table);
+        assert_eq!(
+            restored.map(|count| count.rows),
+            Some(i64::try_from(rows.len()).unwrap()),
+            "{table}"
+        );
+    }
+
+    // Cleanup
+    db.delete_group(group.id).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    let client = pool.get().await.unwrap();
+    client
+        .execute("DELETE FROM categories WHERE id = $1", &[&category_id])
+        .await
+        .ok();
+    client
+        .execute("DELETE FROM tags WHERE id = $1", &[&tag_id])
+        .await
+        .ok();
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &other_username).await.ok();
+}
+
+#[tokio::test]
+async fn test_backup_dry_run_import_leaves_live_data_untouched() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_dry_run");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Dry Run Inventory")
+        .await
+        .unwrap();
+    common::create_test_item(&pool, inventory_id, "Dry Run Item")
+        .await
+        .unwrap();
+
+    let exported = db.export_all_data().await.expect("Failed to export data");
+    let counts = db
+        .dry_run_import(&exported)
+        .await
+        .expect("Dry-run import failed");
+
+    // Every table is reported, with exactly the rows from the export
+    assert_eq!(counts.len(), exported.tables().len());
+    for ((table, rows), count) in exported.tables().iter().zip(&counts) {
+        assert_eq!(*table, count.table);
+        let expected = rows.as_array().map_or(0, Vec::len);
+        assert_eq!(count.rows, i64::try_from(expected).unwrap());
+    }
+
+    // The temporary schema is rolled back and live data is still there
+    let client = pool.get().await.unwrap();
+    let leftover: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM information_schema.schemata WHERE schema_name LIKE 'backup_dry_run_%'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(leftover, 0);
+    assert!(db
+        .get_inventory_by_id(inventory_id)
+        .await
+        .unwrap()
+        .is_some());
+
+    // Cleanup
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[tokio::test]
+async fn test_restore_inventory_from_backup_remaps_ids() {
+    use home_registry::models::{
+        CreateOrganizerOptionRequest, CreateOrganizerTypeRequest, ItemFieldChange,
+        ItemHistoryAction, SetItemOrganizerValueRequest,
+    };
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_sel_inv");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Selective Inventory")
+        .await
+        .unwrap();
+    let item_id = common::create_test_item(&pool, inventory_id, "Selective Item")
+        .await
+        .unwrap();
+    let organizer_type = db
+        .create_organizer_type(
+            inventory_id,
+            CreateOrganizerTypeRequest {
+                name: "Room".to_string(),
+                input_type: None,
+                is_required: None,
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap();
+    let option = db
+        .create_organizer_option(
+            organizer_type.id.unwrap(),
+            CreateOrganizerOptionRequest {
+                name: "Garage".to_string(),
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.set_item_organizer_value(
+        item_id,
+        SetItemOrganizerValueRequest {
+            organizer_type_id: organizer_type.id.unwrap(),
+            organizer_option_id: option.id,
+            text_value: None,
+        },
     )
     .await
     .unwrap();
-    assert!(new_verification);
 
-    // Verify old password no longer works
-    let old_verification_after = home_registry::auth::verify_password(
-        old_password.to_string(),
-        updated_user.password_hash.clone(),
+    let item = db.get_item_by_id(item_id).await.unwrap().unwrap();
+    db.record_item_revision(
+        item_id,
+        ItemHistoryAction::Created,
+        None,
+        user.id,
+        &username,
+        &ItemFieldChange::for_new_item(&item),
     )
     .await
     .unwrap();
-    assert!(!old_verification_after);
+
+    // A tag that will be deleted along with the inventory, and a custom field that survives
+    let client = pool.get().await.unwrap();
+    let tag_name = format!("{username}_tag");
+    let tag_id: i32 = client
+        .query_one(
+            "INSERT INTO tags (name) VALUES ($1) RETURNING id",
+            &[&tag_name],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    client
+        .execute(
+            "INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2)",
+            &[&item_id, &tag_id],
+        )
+        .await
+        .unwrap();
+    let category_id: i32 = client
+        .query_one(
+            "INSERT INTO categories (name) VALUES ($1) RETURNING id",
+            &[&format!("{username}_category")],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    let field_id: i32 = client
+        .query_one(
+            "INSERT INTO custom_fields (category_id, name) VALUES ($1, 'Color') RETURNING id",
+            &[&category_id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    client
+        .execute(
+            "INSERT INTO item_custom_values (item_id, custom_field_id, value) VALUES ($1, $2, 'Blue')",
+            &[&item_id, &field_id],
+        )
+        .await
+        .unwrap();
+
+    let backup = db.export_all_data().await.unwrap();
+    client
+        .execute("DELETE FROM tags WHERE id = $1", &[&tag_id])
+        .await
+        .unwrap();
+
+    // Delete the inventory for good, then merge it back from the backup
+    db.delete_inventory(inventory_id, user.id).await.unwrap();
+    db.purge_from_trash(TrashKind::Inventory, inventory_id)
+        .await
+        .unwrap();
+    let summary = db
+        .restore_inventory_from_backup(&backup, inventory_id, user.id)
+        .await
+        .expect("Selective restore failed");
+
+    assert_eq!(summary.inventories.len(), 1);
+    assert_eq!(summary.items_restored, 1);
+    assert_eq!(summary.organizer_types_restored, 1);
+    assert_eq!(summary.organizer_options_restored, 1);
+    assert_eq!(summary.organizer_values_restored, 1);
+    assert_eq!(summary.tags_restored, 1);
+    assert_eq!(summary.custom_values_restored, 1);
+    assert_eq!(summary.revisions_restored, 1);
+
+    // The deleted inventory's id was free, so it is reused; children get new ids
+    let new_id = summary.inventories[0].new_id;
+    assert_eq!(new_id, inventory_id);
+    let restored = db.get_inventory_by_id(new_id).await.unwrap().unwrap();
+    assert_eq!(restored.name, "Selective Inventory");
+    assert_eq!(restored.user_id, Some(user.id));
+
+    let items = db
+        .get_items_by_inventory(new_id, ItemViewer::Everything)
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let values = db
+        .get_item_organizer_values(items[0].id.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    assert_ne!(items[0].id, Some(item_id));
+    assert_eq!(values[0].value.as_deref(), Some("Garage"));
+
+    // The deleted tag is recreated; the surviving custom field is reused
+    let tags: Vec<String> = client
+        .query(
+            "SELECT t.name FROM item_tags it JOIN tags t ON t.id = it.tag_id WHERE it.item_id = $1",
+            &[&items[0].id.unwrap()],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(tags, vec![tag_name.clone()]);
+    let custom_value = client
+        .query_one(
+            "SELECT custom_field_id, value FROM item_custom_values WHERE item_id = $1",
+            &[&items[0].id.unwrap()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(custom_value.get::<_, i32>(0), field_id);
+    assert_eq!(
+        custom_value.get::<_, Option<String>>(1).as_deref(),
+        Some("Blue")
+    );
+
+    // The item's history follows it to its new id
+    let history = db.get_item_history(items[0].id.unwrap()).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].revision, 1);
+
+    // Cleanup
+    common::delete_test_inventory(&pool, new_id).await.ok();
+    client
+        .execute("DELETE FROM tags WHERE name = $1", &[&tag_name])
+        .await
+        .ok();
+    client
+        .execute("DELETE FROM categories WHERE id = $1", &[&category_id])
+        .await
+        .ok();
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[tokio::test]
+async fn test_restore_inventory_from_backup_keeps_custom_roles() {
+    use home_registry::models::{Capability, CreateInventoryRoleRequest};
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let owner_name = common::test_username("db_sel_role_own");
+    common::create_test_user(&pool, &owner_name).await;
+    let owner = db.get_user_by_username(&owner_name).await.unwrap().unwrap();
+    let recipient_name = common::test_username("db_sel_role_rcp");
+    common::create_test_user(&pool, &recipient_name).await;
+    let recipient = db
+        .get_user_by_username(&recipient_name)
+        .await
+        .unwrap()
+        .unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Role Inventory")
+        .await
+        .unwrap();
+    let role = db
+        .create_inventory_role(
+            owner.id,
+            CreateInventoryRoleRequest {
+                name: "Helper".to_string(),
+                description: None,
+                capabilities: vec![Capability::View, Capability::AddItems],
+            },
+        )
+        .await
+        .unwrap();
+    db.create_inventory_share(inventory_id, recipient.id, owner.id, &role, None, None)
+        .await
+        .unwrap();
+
+    let backup = db.export_all_data().await.unwrap();
+    assert!(backup
+        .inventory_roles
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|row| row["id"] == serde_json::json!(role.id)));
+
+    // Lose both the inventory and the role, then merge the inventory back
+    db.delete_inventory(inventory_id, owner.id).await.unwrap();
+    db.purge_from_trash(TrashKind::Inventory, inventory_id)
+        .await
+        .unwrap();
+    db.delete_inventory_role(role.id).await.unwrap();
+    let summary = db
+        .restore_inventory_from_backup(&backup, inventory_id, owner.id)
+        .await
+        .expect("Selective restore failed");
+    assert_eq!(summary.shares_restored, 1);
+
+    // The role is recreated and the share uses it again
+    let new_id = summary.inventories[0].new_id;
+    let shares = db.get_inventory_shares(new_id).await.unwrap();
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[0].role_id, Some(role.id));
+    let restored = db.get_inventory_role(role.id).await.unwrap().unwrap();
+    assert_eq!(restored.owner_user_id, owner.id);
+    assert_eq!(restored.capabilities, role.capabilities);
+
+    // Cleanup
+    common::delete_test_inventory(&pool, new_id).await.ok();
+    common::delete_test_user(&pool, &recipient_name).await.ok();
+    common::delete_test_user(&pool, &owner_name).await.ok();
+}
+
+#[tokio::test]
+async fn test_restore_deleted_user_from_backup() {
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_sel_user");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Deleted User Inventory")
+        .await
+        .unwrap();
+    common::create_test_item(&pool, inventory_id, "Deleted User Item")
+        .await
+        .unwrap();
+
+    let backup = db.export_all_data().await.unwrap();
+
+    // Deleting the user cascades to their inventories
+    db.delete_user(user.id).await.unwrap();
+    assert!(db
+        .get_inventory_by_id(inventory_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let summary = db
+        .restore_user_from_backup(&backup, user.id)
+        .await
+        .expect("User restore failed");
+
+    assert!(summary.user_restored);
+    assert_eq!(summary.inventories.len(), 1);
+    assert_eq!(summary.items_restored, 1);
+
+    let restored_user = db.get_user_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(restored_user.username, username);
+    assert_eq!(restored_user.password_hash, user.password_hash);
+
+    // Restoring again leaves the recreated account and inventory alone
+    let again = db.restore_user_from_backup(&backup, user.id).await.unwrap();
+    assert!(!again.user_restored);
+    assert!(again.inventories.is_empty());
+
+    // Cleanup
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+// ==================== Trash Tests ====================
+
+#[tokio::test]
+async fn test_trash_restore_and_purge() {
+    use home_registry::models::CreateOrganizerTypeRequest;
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let username = common::test_username("db_trash");
+    common::create_test_user(&pool, &username).await;
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let inventory_id = common::create_test_inventory(&pool, user.id, "Trash Inventory")
+        .await
+        .unwrap();
+    let kept_id = common::create_test_item(&pool, inventory_id, "Kept Item")
+        .await
+        .unwrap();
+    let trashed_id = common::create_test_item(&pool, inventory_id, "Trashed Item")
+        .await
+        .unwrap();
+    let organizer = db
+        .create_organizer_type(
+            inventory_id,
+            CreateOrganizerTypeRequest {
+                name: "Room".to_string(),
+                input_type: None,
+                is_required: None,
+                display_order: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // A trashed item is hidden and listed on its own
+    assert!(db.delete_item(trashed_id, user.id).await.unwrap());
+    assert!(db.get_item_by_id(trashed_id).await.unwrap().is_none());
+    assert!(!db.delete_item(trashed_id, user.id).await.unwrap());
+    let trash = db.get_trash(user.id).await.unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].kind, TrashKind::Item);
+    assert_eq!(trash[0].inventory_id, Some(inventory_id));
+    assert_eq!(
+        trash[0].deleted_by_username.as_deref(),
+        Some(username.as_str())
+    );
+
+    // Trashing the inventory takes the rest along and leaves it out of statistics
+    assert!(db.delete_inventory(inventory_id, user.id).await.unwrap());
+    assert!(db.get_item_by_id(kept_id).await.unwrap().is_none());
+    assert!(db
+        .get_organizer_type_by_id(organizer.id.unwrap())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db
+        .get_accessible_inventories(user.id)
+        .await
+        .unwrap()
+        .is_empty());
+    let stats = db.get_inventory_statistics(None, user.id).await.unwrap();
+    assert_eq!(stats.total_items, 0);
+    let trash = db.get_trash(user.id).await.unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].kind, TrashKind::Inventory);
+    assert_eq!(trash[0].item_count, Some(1));
+
+    // Restoring the inventory brings back what was trashed with it, but not the
+    // item trashed on its own before
+    assert!(db
+        .restore_from_trash(TrashKind::Inventory, inventory_id)
+        .await
+        .unwrap());
+    assert!(db.get_item_by_id(kept_id).await.unwrap().is_some());
+    assert!(db.get_item_by_id(trashed_id).await.unwrap().is_none());
+    assert!(db
+        .get_organizer_type_by_id(organizer.id.unwrap())
+        .await
+        .unwrap()
+        .is_some());
+    let trash = db.get_trash(user.id).await.unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, trashed_id);
+
+    // Only trashed entries can be purged
+    assert!(!db.purge_from_trash(TrashKind::Item, kept_id).await.unwrap());
+    assert!(db
+        .purge_from_trash(TrashKind::Item, trashed_id)
+        .await
+        .unwrap());
+    assert!(!db
+        .restore_from_trash(TrashKind::Item, trashed_id)
+        .await
+        .unwrap());
+    assert!(db.get_trash(user.id).await.unwrap().is_empty());
+
+    // Auto-purge only takes entries older than the retention period
+    assert!(db.delete_item(kept_id, user.id).await.unwrap());
+    db.purge_trash_older_than(30).await.unwrap();
+    assert_eq!(db.get_trash(user.id).await.unwrap().len(), 1);
+    pool.get()
+        .await
+        .unwrap()
+        .execute(
+            "UPDATE items SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1",
+            &[&kept_id],
+        )
+        .await
+        .unwrap();
+    assert!(db.purge_trash_older_than(30).await.unwrap() >= 1);
+    assert!(db.get_trash(user.id).await.unwrap().is_empty());
 
     // Cleanup
     common::delete_test_user(&pool, &username).await.ok();
 }
+
+#[tokio::test]
+async fn test_trashed_inventory_grants_no_access_through_shares() {
+    use home_registry::models::{CreateGroupRequest, GroupRole, PermissionLevel};
+
+    let pool = common::create_test_pool();
+    let db = DatabaseService::new(pool.clone());
+
+    let owner_username = common::test_username("db_trash_owner");
+    let shared_username = common::test_username("db_trash_shared");
+    let member_username = common::test_username("db_trash_member");
+    common::create_test_user(&pool, &owner_username).await;
+    common::create_test_user(&pool, &shared_username).await;
+    common::create_test_user(&pool, &member_username).await;
+    let owner = db
+        .get_user_by_username(&owner_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let shared = db
+        .get_user_by_username(&shared_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let member = db
+        .get_user_by_username(&member_username)
+        .await
+        .unwrap()
+        .unwrap();
+    let inventory_id = common::create_test_inventory(&pool, owner.id, "Trashed Shared")
+        .await
+        .unwrap();
+
+    let share = db
+        .create_inventory_share(
+            inventory_id,
+            shared.id,
+            owner.id,
+            PermissionLevel::EditInventory,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    db.respond_to_inventory_share(share.id, shared.id, ShareStatus::Accepted)
+        .await
+        .unwrap()
+        .unwrap();
+    let group = db
+        .create_group(
+            owner.id,
+            &CreateGroupRequest {
+                name: "Trash Household".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+    db.add_group_member(group.id, member.id, GroupRole::Member)
+        .await
+        .unwrap();
+    db.create_inventory_group_share(
+        inventory_id,
+        group.id,
+        owner.id,
+        PermissionLevel::EditItems,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Once trashed, neither the direct nor the group share gives anything
+    assert!(db.delete_inventory(inventory_id, owner.id).await.unwrap());
+    for user_id in [owner.id, shared.id, member.id] {
+        let perms = db
+            .get_effective_permissions(user_id, inventory_id)
+            .await
+            .unwrap();
+        assert!(!perms.can_view);
+        assert!(!perms.can_edit_items);
+        assert!(!perms.can_edit_inventory);
+    }
+
+    // Restoring it brings the shares back
+    assert!(db
+        .restore_from_trash(TrashKind::Inventory, inventory_id)
+        .await
+        .unwrap());
+    let perms = db
+        .get_effective_permissions(shared.id, inventory_id)
+        .await
+        .unwrap();
+    assert!(perms.can_edit_inventory);
+
+    // Cleanup
+    db.delete_group(group.id).await.ok();
+    common::delete_test_inventory(&pool, inventory_id)
+        .await
+        .ok();
+    common::delete_test_user(&pool, &owner_username).await.ok();
+    common::delete_test_user(&pool, &shared_username).await.ok();
+    common::delete_test_user(&pool, &member_username).await.ok();
+}
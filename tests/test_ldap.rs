@@ -0,0 +1,189 @@
+// LDAP login tests
+//
+// The directory tests need an OpenLDAP server seeded with
+// tests/fixtures/ldap/bootstrap.ldif (e.g. `docker compose --profile ldap up -d`).
+// Configure with TEST_LDAP_URL (e.g. ldap://localhost:389); those tests are
+// skipped when it is not set.
+
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use home_registry::auth::ldap::LdapConfig;
+use serde_json::json;
+
+fn ldap_config(url: String) -> LdapConfig {
+    LdapConfig {
+        url,
+        bind_dn: Some("cn=admin,dc=example,dc=org".to_string()),
+        bind_password: Some(
+            std::env::var("TEST_LDAP_ADMIN_PASSWORD").unwrap_or_else(|_| "admin".to_string()),
+        ),
+        search_base: "ou=people,dc=example,dc=org".to_string(),
+        user_filter: "(&(objectClass=inetOrgPerson)(uid={username}))".to_string(),
+        full_name_attribute: "cn".to_string(),
+        admin_group_dn: Some("cn=registry-admins,ou=groups,dc=example,dc=org".to_string()),
+        start_tls: false,
+        tls_skip_verify: false,
+        auto_provision: true,
+    }
+}
+
+fn test_config() -> Option<LdapConfig> {
+    std::env::var("TEST_LDAP_URL").ok().map(ldap_config)
+}
+
+fn login_request(username: &str, password: &str) -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({"username": username, "password": password}))
+}
+
+#[actix_web::test]
+async fn test_ldap_login_provisions_user_and_maps_admin_group() {
+    let Some(config) = test_config() else {
+        eprintln!("Skipping: TEST_LDAP_URL not set");
+        return;
+    };
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .service(web::scope("/api").service(home_registry::api::auth::login)),
+    )
+    .await;
+
+    // Directory users get a local account on first login
+    let resp = test::call_service(
+        &app,
+        login_request("ldapalice", "alice-password").to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["token"].is_string());
+    assert_eq!(body["data"]["user"]["username"], "ldapalice");
+    assert_eq!(body["data"]["user"]["full_name"], "Alice Directory");
+    assert_eq!(body["data"]["user"]["is_admin"], true);
+
+    let resp =
+        test::call_service(&app, login_request("ldapbob", "bob-password").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["user"]["is_admin"], false);
+
+    // A wrong or empty password is rejected, not retried against the local account
+    for password in ["wrong-password", ""] {
+        let resp = test::call_service(&app, login_request("ldapbob", password).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Filter metacharacters in the username don't match other entries
+    let resp =
+        test::call_service(&app, login_request("ldap*", "alice-password").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    common::delete_test_user(&pool, "ldapalice").await.ok();
+    common::delete_test_user(&pool, "ldapbob").await.ok();
+}
+
+#[actix_web::test]
+async fn test_ldap_login_only_reaches_linked_accounts() {
+    use home_registry::db::DatabaseService;
+    use home_registry::models::IdentitySource;
+
+    let Some(config) = test_config() else {
+        eprintln!("Skipping: TEST_LDAP_URL not set");
+        return;
+    };
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
+            .service(web::scope("/api").service(home_registry::api::auth::login)),
+    )
+    .await;
+
+    // A local account that happens to share a directory username
+    common::delete_test_user(&pool, "ldapcarol").await.ok();
+    let (username, password) = common::create_test_user(&pool, "ldapcarol").await;
+    let db = DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+
+    // It keeps signing in with its local password; the directory password doesn't
+    // reach it and the account is left alone
+    let resp = test::call_service(&app, login_request(&username, &password).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["user"]["id"], user.id.to_string());
+    let resp = test::call_service(
+        &app,
+        login_request(&username, "carol-password").to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(db
+        .get_user_identity_links(user.id)
+        .await
+        .unwrap()
+        .is_empty());
+    // Once an admin links it, the directory login reaches it and binds the entry
+    db.link_user_identity(user.id, IdentitySource::Ldap, None)
+        .await
+        .unwrap();
+    let resp = test::call_service(
+        &app,
+        login_request(&username, "carol-password").to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["user"]["id"], user.id.to_string());
+    let links = db.get_user_identity_links(user.id).await.unwrap();
+    assert_eq!(
+        links[0].subject.as_deref(),
+        Some("uid=ldapcarol,ou=people,dc=example,dc=org")
+    );
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_ldap_falls_back_to_local_accounts() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+
+    let username = common::test_username("ldap_local");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+
+    // A reachable directory without this user, and one that is down
+    let mut configs = vec![ldap_config("ldap://127.0.0.1:1".to_string())];
+    configs.extend(test_config());
+
+    for config in configs {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .service(web::scope("/api").service(home_registry::api::auth::login)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, login_request(&username, &password).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            login_request(&username, "WrongPassword123!").to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
@@ -326,3 +326,93 @@ fn test_item_model_with_optional_fields() {
     };
     assert!(complete.validate().is_ok());
 }
+
+#[test]
+fn test_backup_checksum_survives_round_trip() {
+    use home_registry::models::BackupDatabaseContent;
+    use serde_json::json;
+
+    let mut content: BackupDatabaseContent = serde_json::from_value(json!({
+        "users": [{"id": "6f1c0c1e-0000-4000-8000-000000000001", "username": "alice"}],
+        "inventories": [{"name": "Home", "id": 1, "user_id": "6f1c0c1e-0000-4000-8000-000000000001"}],
+        "items": [{"id": 1, "inventory_id": 1, "purchase_price": 12.5}],
+        "categories": [], "tags": [], "item_tags": [], "custom_fields": [],
+        "item_custom_values": [], "organizer_types": [], "organizer_options": [],
+        "item_organizer_values": [], "user_settings": [], "inventory_shares": [],
+        "user_access_grants": [], "recovery_codes": [], "password_reset_tokens": [],
+        "user_identity_links": [], "api_tokens": [], "webauthn_credentials": [],
+        "user_invitations": [], "inventory_share_links": [], "user_groups": [],
+        "user_group_members": [], "inventory_group_shares": [], "inventory_roles": [], "item_history": []
+    }))
+    .unwrap();
+
+    let checksum = content.checksum().unwrap();
+    assert_eq!(checksum.len(), 64);
+
+    // Pretty-printing and re-parsing (as the backup file does) keeps the checksum stable
+    let pretty = serde_json::to_string_pretty(&content).unwrap();
+    let reparsed: BackupDatabaseContent = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(reparsed.checksum().unwrap(), checksum);
+
+    // The raw data section as read from disk hashes to the same value
+    let raw: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(BackupDatabaseContent::checksum_of_json(&raw), checksum);
+
+    // Any change to the data changes the checksum
+    content.items = json!([{"id": 1, "inventory_id": 1, "purchase_price": 13.5}]);
+    assert_ne!(content.checksum().unwrap(), checksum);
+}
+
+#[test]
+fn test_item_revert_undoes_later_revisions() {
+    use home_registry::models::{
+        Item, ItemFieldChange, ItemHistoryAction, ItemRevision, ItemVisibility,
+    };
+    use serde_json::json;
+
+    let item = |price: Option<f64>, notes: Option<&str>| Item {
+        id: Some(1),
+        inventory_id: 1,
+        name: "TV".to_string(),
+        description: None,
+        category: None,
+        location: None,
+        purchase_date: None,
+        purchase_price: price,
+        warranty_expiry: None,
+        notes: notes.map(str::to_string),
+        quantity: None,
+        visibility: ItemVisibility::Everyone,
+        created_at: None,
+        updated_at: None,
+    };
+    let revision = |number: i32, before: &Item, after: &Item| ItemRevision {
+        revision: number,
+        action: ItemHistoryAction::Updated,
+        reverted_to_revision: None,
+        changed_by_user_id: None,
+        changed_by_username: None,
+        changed_at: chrono::Utc::now(),
+        changes: ItemFieldChange::between_items(before, after),
+    };
+
+    let v1 = item(Some(899.0), None);
+    let v2 = item(Some(749.0), Some("On sale"));
+    let v3 = item(Some(699.0), Some("On sale"));
+    assert!(ItemFieldChange::between_items(&v2, &v2).is_empty());
+
+    // Going back to revision 1 takes each field's value from before revision 2
+    let later = [revision(3, &v2, &v3), revision(2, &v1, &v2)];
+    let changes = ItemFieldChange::reverting(&later, &v3, &[]);
+    assert_eq!(
+        changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.new_value.clone()))
+            .collect::<Vec<_>>(),
+        vec![("notes", json!(null)), ("purchase_price", json!(899.0))]
+    );
+    assert_eq!(changes[1].old_value, json!(699.0));
+
+    // Fields that already match are left out
+    assert!(ItemFieldChange::reverting(&later, &v1, &[]).is_empty());
+}
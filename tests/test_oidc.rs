@@ -0,0 +1,417 @@
+// OIDC single sign-on tests
+//
+// These run the full authorization code + PKCE flow against a mock identity
+// provider started in-process on a random local port.
+
+mod common;
+
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::{http::StatusCode, test, web, App, HttpResponse, HttpServer};
+use home_registry::auth::oidc::{pkce_challenge, OidcConfig};
+use home_registry::db::DatabaseService;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const CLIENT_ID: &str = "home-registry";
+const CLIENT_SECRET: &str = "mock-client-secret";
+
+/// What the mock provider remembers between the authorize redirect and the token request
+#[derive(Default)]
+struct MockProvider {
+    issuer: String,
+    code_challenge: Mutex<Option<String>>,
+    nonce: Mutex<Option<String>>,
+    claims: Mutex<serde_json::Value>,
+}
+
+async fn discovery(provider: web::Data<MockProvider>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "issuer": provider.issuer,
+        "authorization_endpoint": format!("{}/authorize", provider.issuer),
+        "token_endpoint": format!("{}/token", provider.issuer),
+        "jwks_uri": format!("{}/jwks", provider.issuer),
+    }))
+}
+
+async fn token(
+    provider: web::Data<MockProvider>,
+    form: web::Form<HashMap<String, String>>,
+) -> HttpResponse {
+    // Enforce PKCE like a real provider would
+    let expected = provider.code_challenge.lock().unwrap().clone();
+    let verifier = form.get("code_verifier").map_or("", String::as_str);
+    if expected.as_deref() != Some(pkce_challenge(verifier).as_str())
+        || form.get("client_secret").map(String::as_str) != Some(CLIENT_SECRET)
+    {
+        return HttpResponse::BadRequest().json(json!({"error": "invalid_grant"}));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut claims = provider.claims.lock().unwrap().clone();
+    let extra = json!({
+        "iss": provider.issuer,
+        "aud": CLIENT_ID,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": provider.nonce.lock().unwrap().clone(),
+    });
+    for (key, value) in extra.as_object().unwrap() {
+        claims[key] = value.clone();
+    }
+
+    let id_token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(CLIENT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HttpResponse::Ok()
+        .json(json!({"access_token": "mock", "token_type": "Bearer", "id_token": id_token}))
+}
+
+/// Start the mock provider and return its shared state
+fn start_mock_provider() -> web::Data<MockProvider> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let provider = web::Data::new(MockProvider {
+        issuer: format!("http://127.0.0.1:{port}"),
+        ..MockProvider::default()
+    });
+
+    let state = provider.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .route(
+                "/.well-known/openid-configuration",
+                web::get().to(discovery),
+            )
+            .route("/token", web::post().to(token))
+            .route(
+                "/jwks",
+                web::get().to(|| async { HttpResponse::Ok().json(json!({"keys": []})) }),
+            )
+    })
+    .listen(listener)
+    .unwrap()
+    .workers(1)
+    .run();
+    actix_web::rt::spawn(server);
+
+    provider
+}
+
+fn mock_config(provider: &MockProvider, auto_provision: bool) -> OidcConfig {
+    OidcConfig {
+        issuer_url: provider.issuer.clone(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: Some(CLIENT_SECRET.to_string()),
+        redirect_url: "http://localhost:8210/api/auth/oidc/callback".to_string(),
+        scopes: "openid profile groups".to_string(),
+        username_claim: "preferred_username".to_string(),
+        groups_claim: "groups".to_string(),
+        admin_group: Some("registry-admins".to_string()),
+        auto_provision,
+        provider_name: "Mock IdP".to_string(),
+    }
+}
+
+fn location(resp: &actix_web::dev::ServiceResponse) -> reqwest::Url {
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    reqwest::Url::parse("http://localhost")
+        .unwrap()
+        .join(location)
+        .unwrap()
+}
+
+/// The state cookie set by the login redirect
+fn state_cookie(resp: &actix_web::dev::ServiceResponse) -> Cookie<'static> {
+    resp.response()
+        .cookies()
+        .find(|c| c.name() == "oidc_state")
+        .expect("login should set the state cookie")
+        .into_owned()
+}
+
+fn query_param(url: &reqwest::Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+}
+
+#[actix_web::test]
+async fn test_oidc_login_provisions_user_and_maps_admin_group() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let provider = start_mock_provider();
+    let username = common::test_username("sso_user");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(mock_config(&provider, true)))
+            .service(
+                web::scope("/api")
+                    .service(home_registry::api::oidc::oidc_status)
+                    .service(home_registry::api::oidc::oidc_login)
+                    .service(home_registry::api::oidc::oidc_callback)
+                    .service(home_registry::api::oidc::oidc_exchange)
+                    .service(home_registry::api::auth::get_current_user),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/oidc/config")
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["data"]["enabled"], true);
+    assert_eq!(body["data"]["provider_name"], "Mock IdP");
+
+    // Login redirects to the provider with a PKCE challenge
+    let req = test::TestRequest::get()
+        .uri("/api/auth/oidc/login")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let authorize = location(&resp);
+    assert!(authorize.as_str().starts_with(&provider.issuer));
+    let cookie = state_cookie(&resp);
+    assert_eq!(cookie.http_only(), Some(true));
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_eq!(cookie.path(), Some("/api/auth/oidc/callback"));
+    assert_eq!(
+        query_param(&authorize, "code_challenge_method").as_deref(),
+        Some("S256")
+    );
+    let state = query_param(&authorize, "state").unwrap();
+    *provider.code_challenge.lock().unwrap() = query_param(&authorize, "code_challenge");
+    *provider.nonce.lock().unwrap() = query_param(&authorize, "nonce");
+    *provider.claims.lock().unwrap() = json!({
+        "sub": "mock-subject-1",
+        "preferred_username": username,
+        "name": "SSO Test User",
+        "groups": ["family", "registry-admins"],
+    });
+
+    // A callback from a browser that didn't start the login is rejected,
+    // e.g. a victim following a link carrying someone else's state
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/auth/oidc/callback?code=mock-code&state={state}"
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(query_param(&location(&resp), "oidc_error").is_some());
+
+    // The provider calls back with a code; the frontend gets a one-time login code
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/auth/oidc/callback?code=mock-code&state={state}"
+        ))
+        .cookie(cookie.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    let cleared = state_cookie(&resp);
+    assert_eq!(cleared.value(), "");
+    let back = location(&resp);
+    assert_eq!(back.path(), "/login");
+    let login_code =
+        query_param(&back, "oidc_code").unwrap_or_else(|| panic!("SSO login failed: {back}"));
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/oidc/exchange")
+        .set_json(json!({"code": login_code}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["user"]["username"], username.as_str());
+    assert_eq!(body["data"]["user"]["full_name"], "SSO Test User");
+    assert_eq!(body["data"]["user"]["is_admin"], true);
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri("/api/auth/me")
+        .insert_header(("Authorization", format!("Bearer {token}")))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Login codes are single use
+    let req = test::TestRequest::post()
+        .uri("/api/auth/oidc/exchange")
+        .set_json(json!({"code": login_code}))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    // Replaying the callback with a used state is rejected
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/auth/oidc/callback?code=mock-code&state={state}"
+        ))
+        .cookie(cookie)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(query_param(&location(&resp), "oidc_error").is_some());
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_oidc_login_only_reaches_linked_accounts() {
+    use home_registry::models::IdentitySource;
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let provider = start_mock_provider();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(mock_config(&provider, true)))
+            .service(
+                web::scope("/api")
+                    .service(home_registry::api::oidc::oidc_login)
+                    .service(home_registry::api::oidc::oidc_callback)
+                    .service(home_registry::api::oidc::oidc_exchange),
+            ),
+    )
+    .await;
+
+    // Run the login flow for an identity and report whether it got a login code
+    let sso_login = |subject: String, username: String| {
+        let app = &app;
+        let provider = &provider;
+        async move {
+            let req = test::TestRequest::get()
+                .uri("/api/auth/oidc/login")
+                .to_request();
+            let resp = test::call_service(app, req).await;
+            let authorize = location(&resp);
+            let state = query_param(&authorize, "state").unwrap();
+            let cookie = state_cookie(&resp);
+            *provider.code_challenge.lock().unwrap() = query_param(&authorize, "code_challenge");
+            *provider.nonce.lock().unwrap() = query_param(&authorize, "nonce");
+            *provider.claims.lock().unwrap() = json!({
+                "sub": subject,
+                "preferred_username": username,
+                "groups": ["family", "registry-admins"],
+            });
+
+            let req = test::TestRequest::get()
+                .uri(&format!(
+                    "/api/auth/oidc/callback?code=mock-code&state={state}"
+                ))
+                .cookie(cookie)
+                .to_request();
+            let back = location(&test::call_service(app, req).await);
+            assert_ne!(
+                query_param(&back, "oidc_code").is_some(),
+                query_param(&back, "oidc_error").is_some()
+            );
+            query_param(&back, "oidc_code").is_some()
+        }
+    };
+
+    let local = common::test_username("sso_local");
+    let (local, _password) = common::create_test_user(&pool, &local).await;
+    let db = DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&local).await.unwrap().unwrap();
+
+    // A local account isn't taken over by an identity that shares its name,
+    // even with auto-provisioning on
+    assert!(!sso_login(format!("attacker-{local}"), local.clone()).await);
+    let unchanged = db.get_user_by_id(user.id).await.unwrap().unwrap();
+    assert!(!unchanged.is_admin);
+
+    // Once an admin links it, the next SSO login of that name claims it
+    db.link_user_identity(user.id, IdentitySource::Sso, None)
+        .await
+        .unwrap();
+    assert!(sso_login(format!("subject-{local}"), local.clone()).await);
+    let links = db.get_user_identity_links(user.id).await.unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].subject, Some(format!("subject-{local}")));
+
+    // From then on only that identity gets in, whatever name it sends
+    assert!(!sso_login(format!("attacker-{local}"), local.clone()).await);
+    assert!(sso_login(format!("subject-{local}"), "renamed-at-idp".to_string()).await);
+    assert!(db
+        .get_user_by_username("renamed-at-idp")
+        .await
+        .unwrap()
+        .is_none());
+
+    common::delete_test_user(&pool, &local).await.ok();
+}
+
+#[actix_web::test]
+async fn test_oidc_login_requires_matching_account_without_provisioning() {
+    use home_registry::models::IdentitySource;
+
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let provider = start_mock_provider();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(mock_config(&provider, false)))
+            .service(
+                web::scope("/api")
+                    .service(home_registry::api::oidc::oidc_login)
+                    .service(home_registry::api::oidc::oidc_callback)
+                    .service(home_registry::api::oidc::oidc_exchange),
+            ),
+    )
+    .await;
+
+    let existing = common::test_username("sso_local");
+    let (existing, _password) = common::create_test_user(&pool, &existing).await;
+    let unknown = common::test_username("sso_unknown");
+    let db = DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&existing).await.unwrap().unwrap();
+    db.link_user_identity(user.id, IdentitySource::Sso, None)
+        .await
+        .unwrap();
+
+    for (username, should_succeed) in [(&existing, true), (&unknown, false)] {
+        let req = test::TestRequest::get()
+            .uri("/api/auth/oidc/login")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let authorize = location(&resp);
+        let state = query_param(&authorize, "state").unwrap();
+        let cookie = state_cookie(&resp);
+        *provider.code_challenge.lock().unwrap() = query_param(&authorize, "code_challenge");
+        *provider.nonce.lock().unwrap() = query_param(&authorize, "nonce");
+        *provider.claims.lock().unwrap() = json!({
+            "sub": format!("subject-{username}"),
+            "preferred_username": username,
+            "groups": ["family"],
+        });
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/auth/oidc/callback?code=mock-code&state={state}"
+            ))
+            .cookie(cookie)
+            .to_request();
+        let back = location(&test::call_service(&app, req).await);
+        assert_eq!(query_param(&back, "oidc_code").is_some(), should_succeed);
+        assert_eq!(query_param(&back, "oidc_error").is_some(), !should_succeed);
+    }
+
+    // Matching an existing local account keeps it a regular user
+    assert!(db.get_user_by_username(&unknown).await.unwrap().is_none());
+    let user = db.get_user_by_username(&existing).await.unwrap().unwrap();
+    assert!(!user.is_admin);
+
+    common::delete_test_user(&pool, &existing).await.ok();
+}
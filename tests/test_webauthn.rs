@@ -0,0 +1,495 @@
+// WebAuthn passkey tests
+//
+// A software authenticator (an in-memory P-256 key) answers the registration
+// and authentication ceremonies the way a browser and security key would.
+
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ciborium::Value;
+use home_registry::auth::webauthn::WebauthnConfig;
+use home_registry::db::DatabaseService;
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+const ORIGIN: &str = "https://inventory.example.com";
+const RP_ID: &str = "inventory.example.com";
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+fn config() -> WebauthnConfig {
+    WebauthnConfig {
+        rp_id: RP_ID.to_string(),
+        rp_name: "Home Registry".to_string(),
+        origin: ORIGIN.to_string(),
+    }
+}
+
+fn b64(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn cbor(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+/// An ES256 authenticator holding a single credential
+struct SoftAuthenticator {
+    credential_id: Vec<u8>,
+    key_pair: EcdsaKeyPair,
+    sign_count: u32,
+}
+
+impl SoftAuthenticator {
+    fn new() -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        Self {
+            credential_id: uuid::Uuid::new_v4().as_bytes().to_vec(),
+            key_pair: EcdsaKeyPair::from_pkcs8(
+                &ECDSA_P256_SHA256_ASN1_SIGNING,
+                pkcs8.as_ref(),
+                &rng,
+            )
+            .unwrap(),
+            sign_count: 0,
+        }
+    }
+
+    fn cose_public_key(&self) -> Vec<u8> {
+        // Uncompressed point: 0x04 || x || y
+        let point = self.key_pair.public_key().as_ref();
+        cbor(&Value::Map(vec![
+            (Value::from(1), Value::from(2)),
+            (Value::from(3), Value::from(-7)),
+            (Value::from(-1), Value::from(1)),
+            (Value::from(-2), Value::Bytes(point[1..33].to_vec())),
+            (Value::from(-3), Value::Bytes(point[33..].to_vec())),
+        ]))
+    }
+
+    fn client_data(kind: &str, options: &serde_json::Value) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "type": kind,
+            "challenge": options["challenge"],
+            "origin": ORIGIN,
+            "crossOrigin": false,
+        }))
+        .unwrap()
+    }
+
+    fn auth_data(&self, flags: u8, attested: bool) -> Vec<u8> {
+        let flags = if attested {
+            flags | FLAG_ATTESTED_CREDENTIAL_DATA
+        } else {
+            flags
+        };
+        let mut data = Sha256::digest(RP_ID.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend_from_slice(&self.sign_count.to_be_bytes());
+        if attested {
+            data.extend_from_slice(&[0u8; 16]);
+            data.extend_from_slice(
+                &u16::try_from(self.credential_id.len())
+                    .unwrap()
+                    .to_be_bytes(),
+            );
+            data.extend_from_slice(&self.credential_id);
+            data.extend_from_slice(&self.cose_public_key());
+        }
+        data
+    }
+
+    /// Answer `navigator.credentials.create()`
+    fn create(&self, options: &serde_json::Value) -> serde_json::Value {
+        let attestation_object = cbor(&Value::Map(vec![
+            (Value::from("fmt"), Value::from("none")),
+            (Value::from("attStmt"), Value::Map(vec![])),
+            (
+                Value::from("authData"),
+                Value::Bytes(self.auth_data(FLAG_USER_PRESENT | FLAG_USER_VERIFIED, true)),
+            ),
+        ]));
+        json!({
+            "id": b64(&self.credential_id),
+            "response": {
+                "clientDataJSON": b64(&Self::client_data("webauthn.create", options)),
+                "attestationObject": b64(&attestation_object),
+                "transports": ["usb"],
+            },
+        })
+    }
+
+    /// Answer `navigator.credentials.get()`, bumping the signature counter
+    fn get(
+        &mut self,
+        options: &serde_json::Value,
+        flags: u8,
+        user_handle: Option<&[u8]>,
+    ) -> serde_json::Value {
+        self.sign_count += 1;
+        let client_data = Self::client_data("webauthn.get", options);
+        let auth_data = self.auth_data(flags, false);
+
+        let mut signed = auth_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&client_data));
+        let signature = self.key_pair.sign(&SystemRandom::new(), &signed).unwrap();
+
+        json!({
+            "id": b64(&self.credential_id),
+            "response": {
+                "clientDataJSON": b64(&client_data),
+                "authenticatorData": b64(&auth_data),
+                "signature": b64(signature.as_ref()),
+                "userHandle": user_handle.map(b64),
+            },
+        })
+    }
+}
+
+macro_rules! webauthn_app {
+    ($pool:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new($pool.clone()))
+                .app_data(web::Data::new(config()))
+                .service(
+                    web::scope("/api")
+                        .service(home_registry::api::auth::login)
+                        .service(home_registry::api::webauthn::webauthn_status)
+                        .service(home_registry::api::webauthn::webauthn_register_start)
+                        .service(home_registry::api::webauthn::webauthn_register_finish)
+                        .service(home_registry::api::webauthn::get_webauthn_credentials)
+                        .service(home_registry::api::webauthn::rename_webauthn_credential)
+                        .service(home_registry::api::webauthn::delete_webauthn_credential)
+                        .service(home_registry::api::webauthn::webauthn_login_start)
+                        .service(home_registry::api::webauthn::webauthn_login_finish)
+                        .service(home_registry::api::webauthn::webauthn_verify_start)
+                        .service(home_registry::api::webauthn::webauthn_verify_finish),
+                ),
+        )
+        .await
+    };
+}
+
+macro_rules! post_json {
+    ($app:expr, $uri:expr, $token:expr, $body:expr) => {{
+        let mut req = test::TestRequest::post().uri($uri).set_json($body);
+        if let Some(token) = $token {
+            req = req.insert_header(("Authorization", format!("Bearer {token}")));
+        }
+        test::call_service(&$app, req.to_request()).await
+    }};
+}
+
+macro_rules! register_passkey {
+    ($app:expr, $token:expr, $authenticator:expr, $nickname:expr) => {{
+        let resp = post_json!($app, "/api/auth/webauthn/register/start", Some($token), json!({}));
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let options = body["data"].clone();
+
+        let resp = post_json!(
+            $app,
+            "/api/auth/webauthn/register/finish",
+            Some($token),
+            json!({"nickname": $nickname, "credential": $authenticator.create(&options)})
+        );
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        (options, body["data"].clone())
+    }};
+}
+
+#[actix_web::test]
+async fn test_passkey_registration_and_passwordless_login() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let app = webauthn_app!(pool);
+
+    let username = common::test_username("passkey_user");
+    let (username, _password) = common::create_test_user(&pool, &username).await;
+    let token = common::get_test_token(&pool, &username).await;
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/auth/webauthn/config")
+            .to_request(),
+    )
+    .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["enabled"], true);
+
+    let mut authenticator = SoftAuthenticator::new();
+    let (options, credential) = register_passkey!(app, &token, authenticator, "YubiKey");
+    assert_eq!(options["rp"]["id"], RP_ID);
+    assert_eq!(options["attestation"], "none");
+    assert_eq!(credential["nickname"], "YubiKey");
+    assert_eq!(credential["transports"], json!(["usb"]));
+
+    // The same authenticator is excluded from a second registration, and a
+    // replayed response is refused
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/register/start",
+        Some(&token),
+        json!({})
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body["data"]["excludeCredentials"][0]["id"],
+        URL_SAFE_NO_PAD.encode(&authenticator.credential_id)
+    );
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/register/finish",
+        Some(&token),
+        json!({"nickname": "Again", "credential": authenticator.create(&options)})
+    );
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Passwordless login requires user verification
+    let db = DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/start",
+        None::<&str>,
+        json!({})
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let options = body["data"].clone();
+    assert_eq!(options["userVerification"], "required");
+    let assertion = authenticator.get(&options, FLAG_USER_PRESENT, Some(user.id.as_bytes()));
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/finish",
+        None::<&str>,
+        json!({"credential": assertion})
+    );
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/start",
+        None::<&str>,
+        json!({})
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let options = body["data"].clone();
+    let assertion = authenticator.get(
+        &options,
+        FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+        Some(user.id.as_bytes()),
+    );
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/finish",
+        None::<&str>,
+        json!({"credential": assertion.clone()})
+    );
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["token"].is_string());
+    assert!(body["data"]["refresh_token"].is_string());
+    assert_eq!(body["data"]["user"]["username"], username.as_str());
+
+    // A challenge can only be answered once
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/finish",
+        None::<&str>,
+        json!({"credential": assertion})
+    );
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // A cloned authenticator replaying an old counter is rejected
+    authenticator.sign_count -= 2;
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/start",
+        None::<&str>,
+        json!({})
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let assertion = authenticator.get(
+        &body["data"],
+        FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+        Some(user.id.as_bytes()),
+    );
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/login/finish",
+        None::<&str>,
+        json!({"credential": assertion})
+    );
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    common::delete_test_user(&pool, &username).await.ok();
+}
+
+#[actix_web::test]
+async fn test_passkey_satisfies_totp_step() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let app = webauthn_app!(pool);
+
+    let username = common::test_username("passkey_2fa");
+    let (username, password) = common::create_test_user(&pool, &username).await;
+    let token = common::get_test_token(&pool, &username).await;
+    let other = common::test_username("passkey_other");
+    let (other, _) = common::create_test_user(&pool, &other).await;
+    let other_token = common::get_test_token(&pool, &other).await;
+
+    let db = DatabaseService::new(pool.clone());
+    let user = db.get_user_by_username(&username).await.unwrap().unwrap();
+    db.create_totp_settings(user.id, "unused-in-this-test")
+        .await
+        .unwrap();
+    db.enable_totp(user.id, "2fa_only").await.unwrap();
+
+    let mut authenticator = SoftAuthenticator::new();
+    register_passkey!(app, &token, authenticator, "Laptop");
+    let mut other_authenticator = SoftAuthenticator::new();
+    register_passkey!(app, &other_token, other_authenticator, "Phone");
+
+    let resp = post_json!(
+        app,
+        "/api/auth/login",
+        None::<&str>,
+        json!({"username": username, "password": password})
+    );
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"]["requires_totp"], true);
+    assert_eq!(body["data"]["webauthn_available"], true);
+    let partial_token = body["data"]["partial_token"].as_str().unwrap().to_string();
+
+    // Full tokens can't drive the second-factor ceremony
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/verify/start",
+        Some(&token),
+        json!({})
+    );
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Another user's passkey doesn't satisfy this login
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/verify/start",
+        Some(&partial_token),
+        json!({})
+    );
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let options = body["data"].clone();
+    assert_eq!(
+        options["allowCredentials"][0]["id"],
+        URL_SAFE_NO_PAD.encode(&authenticator.credential_id)
+    );
+    let assertion = other_authenticator.get(&options, FLAG_USER_PRESENT, None);
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/verify/finish",
+        Some(&partial_token),
+        json!({"credential": assertion})
+    );
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // The user's own security key only needs presence here; the password was the first factor
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/verify/start",
+        Some(&partial_token),
+        json!({})
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let assertion = authenticator.get(&body["data"], FLAG_USER_PRESENT, None);
+    let resp = post_json!(
+        app,
+        "/api/auth/webauthn/verify/finish",
+        Some(&partial_token),
+        json!({"credential": assertion})
+    );
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["token"].is_string());
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &other).await.ok();
+}
+
+#[actix_web::test]
+async fn test_manage_passkeys() {
+    let pool = common::create_test_pool();
+    home_registry::auth::get_or_init_jwt_secret();
+    let app = webauthn_app!(pool);
+
+    let username = common::test_username("passkey_manage");
+    let (username, _) = common::create_test_user(&pool, &username).await;
+    let token = common::get_test_token(&pool, &username).await;
+    let other = common::test_username("passkey_intruder");
+    let (other, _) = common::create_test_user(&pool, &other).await;
+    let other_token = common::get_test_token(&pool, &other).await;
+
+    let first = SoftAuthenticator::new();
+    let second = SoftAuthenticator::new();
+    let (_, credential) = register_passkey!(app, &token, first, "Laptop");
+    register_passkey!(app, &token, second, "Backup key");
+    let id = credential["id"].as_str().unwrap().to_string();
+
+    let list = |token: String| {
+        test::TestRequest::get()
+            .uri("/api/auth/webauthn/credentials")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, list(token.clone())).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+
+    // Other users can't rename or delete it
+    let rename = |token: &str, nickname: &str| {
+        test::TestRequest::put()
+            .uri(&format!("/api/auth/webauthn/credentials/{id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({"nickname": nickname}))
+            .to_request()
+    };
+    let resp = test::call_service(&app, rename(&other_token, "Mine now")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(&app, rename(&token, "")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, rename(&token, "Work laptop")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let delete = |token: &str| {
+        test::TestRequest::delete()
+            .uri(&format!("/api/auth/webauthn/credentials/{id}"))
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .to_request()
+    };
+    let resp = test::call_service(&app, delete(&other_token)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(&app, delete(&token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, list(token.clone())).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let remaining = body["data"].as_array().unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0]["nickname"], "Backup key");
+
+    common::delete_test_user(&pool, &username).await.ok();
+    common::delete_test_user(&pool, &other).await.ok();
+}